@@ -23,6 +23,10 @@ fn bench_index_full(c: &mut Criterion) {
         db_path,
         data_dir: data_dir.clone(),
         progress: None,
+        commit_policy: Default::default(),
+        remotes: Vec::new(),
+        agents: Vec::new(),
+        paths: Vec::new(),
     };
 
     // create empty index dir so Tantivy opens cleanly
@@ -104,6 +104,29 @@ fn bench_search(c: &mut Criterion) {
     });
 }
 
+/// Cold open + first query, as paid by every one-shot `cass search --robot` process.
+fn bench_cold_open_search(c: &mut Criterion) {
+    let (tmp, _client) = seed_index(40, 12);
+    let index_path = index_dir(tmp.path()).unwrap();
+    let db_path = tmp.path().join("bench.db");
+    c.bench_function("search_cold_open", |b| {
+        b.iter(|| {
+            let client = SearchClient::open(&index_path, Some(&db_path))
+                .unwrap()
+                .expect("client available");
+            let hits = client
+                .search(
+                    black_box("lorem"),
+                    coding_agent_search::search::query::SearchFilters::default(),
+                    10,
+                    0,
+                )
+                .unwrap();
+            black_box(hits.len());
+        })
+    });
+}
+
 // ============================================================
 // Wildcard Performance Benchmarks (bd-d5a)
 // ============================================================
@@ -321,6 +344,7 @@ criterion_group!(
     runtime_perf,
     bench_indexing,
     bench_search,
+    bench_cold_open_search,
     bench_wildcard_exact,
     bench_wildcard_prefix,
     bench_wildcard_suffix,
@@ -0,0 +1,70 @@
+## English (source) strings for cass.
+## CLI subcommand descriptions come from the clap doc comments in src/lib.rs; a
+## translation overrides them with `cmd-<subcommand>` messages.
+
+## Error hints
+
+hint-index-rebuild = Run 'cass index --full' to rebuild the index/database.
+hint-index-create = Run 'cass index --full' to create the database.
+hint-index-refresh = Run 'cass index' to pick up new sessions.
+hint-find-session-paths = Use 'cass search' to find session paths
+hint-find-source-path = Use 'cass search' to find sessions, then use the source_path from results.
+hint-first-line = Use -n 1 for the first line
+hint-last-line = Use -n { $line } for the last line
+hint-message-count = File has { $count } messages
+
+## TUI pane titles
+
+tui-results = Results
+tui-detail = Detail
+
+## TUI help overlay
+
+help-welcome = Welcome to CASS - Coding Agent Session Search
+help-data-locations = Data Locations
+help-updates = Updates
+help-search = Search
+help-filters = Filters
+help-modes = Modes
+help-context = Context
+help-density = Density
+help-navigation = Navigation
+help-mouse = Mouse
+help-actions = Actions
+help-states = States
+help-empty-state = Empty state
+
+## TUI contextual shortcut strip
+
+strip-close = Close
+strip-select = Select
+strip-run = Run
+strip-close-detail = Close detail
+strip-scroll = Scroll
+strip-top-bottom = Top/Bottom
+strip-copy = Copy
+strip-agent-filter = Agent filter
+strip-toggle = Toggle
+strip-workspace-filter = Workspace filter
+strip-pane-filter = Pane filter
+strip-date = Date (YYYY-MM-DD)
+strip-date-presets = Today/Week/Month
+strip-find-term = Find term
+strip-apply = Apply
+strip-cancel = Cancel
+strip-clear = Clear
+strip-palette = Palette
+strip-nav = Nav
+strip-open-detail = Open detail
+strip-bulk-menu = Bulk menu
+strip-filters = Filters
+strip-quit-back = Quit/back
+strip-focus-results = Focus results
+strip-fold = Fold conversation
+strip-tabs = Tabs
+strip-find-in-detail = Find in detail
+strip-next-prev-match = Next/prev match
+strip-open-file = Open file
+strip-pin = Pin
+strip-resume = Resume
+strip-tail = Tail
@@ -0,0 +1,109 @@
+## Spanish strings for cass.
+
+## CLI help
+
+cli-about = Búsqueda unificada en TUI sobre historiales de agentes de programación
+cmd-tui = Abrir la TUI interactiva
+cmd-index = Ejecutar el indexador
+cmd-completions = Generar autocompletado de shell en stdout
+cmd-man = Generar la página de manual en stdout
+cmd-robot-docs = Documentación orientada a agentes de automatización
+cmd-search = Ejecutar una búsqueda puntual e imprimir los resultados en stdout
+cmd-stats = Mostrar estadísticas de los datos indexados
+cmd-diag = Mostrar información de diagnóstico para resolver problemas
+cmd-doctor = Comprobar si los datos de cass están dañados; sale con 1 si se encuentran daños y no se reparan
+cmd-status = Comprobación rápida para agentes: frescura del índice, estadísticas de la base de datos y acción recomendada
+cmd-capabilities = Descubrir funciones, versiones y límites disponibles para la introspección de agentes
+cmd-state = Comprobación rápida de estado (alias de status)
+cmd-api-version = Mostrar la versión de la API y del contrato
+cmd-introspect = Introspección completa del esquema de la API: comandos, argumentos y esquemas de respuesta
+cmd-schema = Imprimir el JSON Schema de cada forma versionada de la salida robot, o solo el de SHAPE
+cmd-view = Ver un archivo fuente en una línea concreta (seguimiento de resultados de búsqueda)
+cmd-health = Comprobación mínima de salud (<50ms). Sale con 0=sano, 1=no sano
+cmd-watch = Mantener el índice al día: ponerse al corriente y reindexar los archivos de sesión a medida que los agentes los escriben
+cmd-list = Listar conversaciones por actividad más reciente, sin consulta
+cmd-context = Buscar sesiones relacionadas con una ruta de origen
+cmd-export = Exportar una conversación a markdown u otros formatos
+cmd-expand = Mostrar los mensajes alrededor de una línea de un archivo de sesión
+cmd-show = Mostrar una conversación con formato, o una ventana alrededor de un mensaje
+cmd-diff = Alinear dos sesiones (p. ej. dos intentos de una tarea) y mostrar los prompts que cambiaron y las respuestas que divergieron
+cmd-grep = Buscar directamente en los archivos de sesión con una expresión regular, sin el índice
+cmd-timeline = Mostrar la línea de tiempo de actividad de un intervalo
+cmd-daemon = Ejecutar un daemon de consultas que mantiene el índice en caliente para `cass search`
+cmd-repl = Consultar el índice línea a línea con un lector en caliente, sin la TUI
+cmd-serve = Servir búsqueda, listado, visualización, estadísticas e indexación por HTTP como JSON en modo robot
+cmd-permalink = Imprimir el enlace permanente cass:// de un mensaje de una sesión indexada
+cmd-resume = Imprimir el comando del propio agente para continuar una sesión indexada, o ejecutarlo
+cmd-open-url = Abrir un enlace permanente cass://<agente>/<id-conversación>#<msg_idx> en la TUI
+cmd-tag = Etiquetar sesiones (`cass tag add <ruta> bugfix auth`) para encontrarlas con `search --tag`
+cmd-bookmark = Marcar con una estrella una sesión o uno de sus mensajes, listar las estrellas o quitar una
+cmd-note = Anotar un mensaje o una conversación (`cass note add <ruta> <idx> "texto"`); las notas se pueden buscar y se muestran en la vista de detalle de la TUI
+cmd-telemetry = Gestionar métricas de uso opcionales y solo locales (desactivadas por defecto)
+cmd-import = Importar conversaciones grabadas fuera de cualquier directorio de agente vigilado
+
+## Error hints
+
+hint-index-rebuild = Ejecuta 'cass index --full' para reconstruir el índice y la base de datos.
+hint-index-create = Ejecuta 'cass index --full' para crear la base de datos.
+hint-index-refresh = Ejecuta 'cass index' para incorporar las sesiones nuevas.
+hint-find-session-paths = Usa 'cass search' para encontrar rutas de sesión
+hint-find-source-path = Usa 'cass search' para encontrar sesiones y luego usa el source_path de los resultados.
+hint-first-line = Usa -n 1 para la primera línea
+hint-last-line = Usa -n { $line } para la última línea
+hint-message-count = El archivo tiene { $count } mensajes
+
+## TUI pane titles
+
+tui-results = Resultados
+tui-detail = Detalle
+
+## TUI help overlay
+
+help-welcome = Bienvenido a CASS - búsqueda de sesiones de agentes de programación
+help-data-locations = Ubicación de los datos
+help-updates = Actualizaciones
+help-search = Búsqueda
+help-filters = Filtros
+help-modes = Modos
+help-context = Contexto
+help-density = Densidad
+help-navigation = Navegación
+help-mouse = Ratón
+help-actions = Acciones
+help-states = Estado
+help-empty-state = Estado vacío
+
+## TUI contextual shortcut strip
+
+strip-close = Cerrar
+strip-select = Seleccionar
+strip-run = Ejecutar
+strip-close-detail = Cerrar detalle
+strip-scroll = Desplazar
+strip-top-bottom = Inicio/Final
+strip-copy = Copiar
+strip-agent-filter = Filtro de agente
+strip-toggle = Alternar
+strip-workspace-filter = Filtro de espacio de trabajo
+strip-pane-filter = Filtro del panel
+strip-date = Fecha (AAAA-MM-DD)
+strip-date-presets = Hoy/Semana/Mes
+strip-find-term = Término a buscar
+strip-apply = Aplicar
+strip-cancel = Cancelar
+strip-clear = Limpiar
+strip-palette = Paleta
+strip-nav = Navegar
+strip-open-detail = Abrir detalle
+strip-bulk-menu = Acciones en lote
+strip-filters = Filtros
+strip-quit-back = Salir/volver
+strip-focus-results = Enfocar resultados
+strip-fold = Plegar conversación
+strip-tabs = Pestañas
+strip-find-in-detail = Buscar en detalle
+strip-next-prev-match = Coincidencia sig./ant.
+strip-open-file = Abrir archivo
+strip-pin = Fijar
+strip-resume = Reanudar
+strip-tail = Seguir
@@ -6,6 +6,7 @@
 use anyhow::{Context, Result};
 use rusqlite::{Connection, OptionalExtension, params};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -189,6 +190,15 @@ impl BookmarkStore {
         Ok(rows > 0)
     }
 
+    /// Remove the bookmark on a `source_path` + line (the whole session when `None`)
+    pub fn remove_at(&self, source_path: &str, line_number: Option<usize>) -> Result<bool> {
+        let rows = self.conn.execute(
+            "DELETE FROM bookmarks WHERE source_path = ?1 AND line_number IS ?2",
+            params![source_path, line_number.map(|n| n as i64)],
+        )?;
+        Ok(rows > 0)
+    }
+
     /// Get a bookmark by ID
     pub fn get(&self, id: i64) -> Result<Option<Bookmark>> {
         self.conn
@@ -274,6 +284,16 @@ impl BookmarkStore {
         Ok(exists)
     }
 
+    /// Source paths of every bookmarked session or message
+    pub fn source_paths(&self) -> Result<HashSet<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT source_path FROM bookmarks")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect::<Result<HashSet<_>, _>>()
+            .context("listing bookmarked paths")
+    }
+
     /// Export all bookmarks to JSON
     pub fn export_json(&self) -> Result<String> {
         let bookmarks = self.list(None)?;
@@ -320,6 +340,11 @@ fn row_to_bookmark(row: &rusqlite::Row) -> Bookmark {
     }
 }
 
+/// The bookmarks database kept in `data_dir`, as `cass bookmark` and the TUI use it
+pub fn bookmarks_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("bookmarks.db")
+}
+
 /// Get the default bookmarks database path
 pub fn default_bookmarks_path() -> PathBuf {
     directories::ProjectDirs::from("com", "coding-agent-search", "coding-agent-search").map_or_else(
@@ -471,6 +496,25 @@ mod tests {
         assert!(!store.is_bookmarked("/other.rs", Some(10)).unwrap());
     }
 
+    #[test]
+    fn test_remove_at() {
+        let (store, _dir) = test_store();
+
+        store
+            .add(&Bookmark::new("Session", "/file.rs", "a", "/w"))
+            .unwrap();
+        store
+            .add(&Bookmark::new("Message", "/file.rs", "a", "/w").with_line(3))
+            .unwrap();
+        assert_eq!(store.source_paths().unwrap().len(), 1);
+
+        assert!(store.remove_at("/file.rs", Some(3)).unwrap());
+        assert!(!store.remove_at("/file.rs", Some(3)).unwrap());
+        assert!(store.is_bookmarked("/file.rs", None).unwrap());
+        assert!(store.remove_at("/file.rs", None).unwrap());
+        assert!(store.source_paths().unwrap().is_empty());
+    }
+
     #[test]
     fn test_export_import() {
         let (store1, _dir1) = test_store();
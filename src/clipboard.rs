@@ -0,0 +1,174 @@
+//! Putting text on the system clipboard, for `--copy` on `cass search` and `cass show`
+//! and for the TUI's copy keys.
+//!
+//! A [`ClipboardMethod`] picks how:
+//!
+//! - `osc52` writes an OSC 52 escape sequence to the terminal, whose emulator sets the
+//!   clipboard of the machine it runs on. It works over SSH (inside tmux only with
+//!   `set -g set-clipboard on`), but terminals that do not support it drop it silently.
+//! - `command` pipes the text to the first of wl-copy, pbcopy, xclip, xsel and clip.exe
+//!   found on `PATH`.
+//! - `native` calls the OS clipboard API, in builds with the `native-clipboard` feature.
+//!   On X11 and Wayland the text is served by cass itself, so it outlives a CLI run only
+//!   if a clipboard manager picks it up.
+//! - `auto` tries a command, then the native API, then OSC 52.
+//!
+//! A tool on a remote host fills that host's clipboard, not the user's, so in an SSH
+//! session (`SSH_TTY`, `SSH_CONNECTION` or `SSH_CLIENT` set) `[clipboard] ssh_method`
+//! applies, OSC 52 unless configured otherwise, instead of `[clipboard] method`.
+
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+pub use crate::config::ClipboardMethod;
+
+/// Longest OSC 52 sequence sent; xterm and others ignore larger ones without a word.
+const OSC52_MAX_LEN: usize = 100_000;
+
+/// Clipboard tools tried by the `command` method, in order, with their arguments.
+const TOOLS: &[(&str, &[&str])] = &[
+    ("wl-copy", &[]),
+    ("pbcopy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("clip.exe", &[]),
+];
+
+/// Whether cass runs inside an SSH session.
+pub fn over_ssh() -> bool {
+    ["SSH_TTY", "SSH_CONNECTION", "SSH_CLIENT"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some_and(|v| !v.is_empty()))
+}
+
+/// The configured method for this session: `ssh_method` over SSH, else `method`.
+pub fn configured_method() -> ClipboardMethod {
+    let config = crate::config::clipboard();
+    if over_ssh() {
+        config.ssh_method
+    } else {
+        config.method
+    }
+}
+
+/// Copy `text` with the configured method, returning the method that took it.
+pub fn copy(text: &str) -> Result<ClipboardMethod, String> {
+    copy_with(configured_method(), text)
+}
+
+/// Copy `text` with `method`; `Auto` returns the method that worked, or every failure.
+pub fn copy_with(method: ClipboardMethod, text: &str) -> Result<ClipboardMethod, String> {
+    let attempt = |method| match method {
+        ClipboardMethod::Command => copy_command(text),
+        ClipboardMethod::Native => copy_native(text),
+        ClipboardMethod::Osc52 | ClipboardMethod::Auto => copy_osc52(text),
+    };
+    if method != ClipboardMethod::Auto {
+        return attempt(method).map(|()| method);
+    }
+    let mut failures = Vec::new();
+    for method in [
+        ClipboardMethod::Command,
+        ClipboardMethod::Native,
+        ClipboardMethod::Osc52,
+    ] {
+        match attempt(method) {
+            Ok(()) => return Ok(method),
+            Err(e) => failures.push(e),
+        }
+    }
+    Err(failures.join("; "))
+}
+
+fn copy_command(text: &str) -> Result<(), String> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let (program, args) = TOOLS
+        .iter()
+        .filter(|(program, _)| *program != "wl-copy" || wayland)
+        .find(|(program, _)| which::which(program).is_ok())
+        .ok_or("no clipboard tool found (wl-copy, pbcopy, xclip, xsel, clip.exe)")?;
+    let mut child = Command::new(program)
+        .args(*args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("running {program}: {e}"))?;
+    // Dropping stdin closes it, so the tool sees the end of the text.
+    let written = child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(text.as_bytes());
+    let status = child
+        .wait()
+        .map_err(|e| format!("running {program}: {e}"))?;
+    match written {
+        Ok(()) if status.success() => Ok(()),
+        Ok(()) => Err(format!("{program} failed ({status})")),
+        Err(e) => Err(format!("writing to {program}: {e}")),
+    }
+}
+
+#[cfg(feature = "native-clipboard")]
+fn copy_native(text: &str) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| format!("native clipboard: {e}"))
+}
+
+#[cfg(not(feature = "native-clipboard"))]
+fn copy_native(_text: &str) -> Result<(), String> {
+    Err("this build has no native clipboard (feature native-clipboard)".to_string())
+}
+
+/// The OSC 52 sequence that sets the clipboard to `text`.
+fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", BASE64.encode(text))
+}
+
+fn copy_osc52(text: &str) -> Result<(), String> {
+    let sequence = osc52_sequence(text);
+    if sequence.len() > OSC52_MAX_LEN {
+        return Err(format!(
+            "{} bytes is too long to copy through the terminal (OSC 52)",
+            text.len()
+        ));
+    }
+    let mut terminal = terminal().ok_or("no terminal to send OSC 52 to")?;
+    terminal
+        .write_all(sequence.as_bytes())
+        .and_then(|()| terminal.flush())
+        .map_err(|e| format!("writing OSC 52: {e}"))
+}
+
+/// The controlling terminal, so the sequence reaches it even when stdout and stderr
+/// are redirected; stderr when that is a terminal and there is no `/dev/tty`.
+fn terminal() -> Option<Box<dyn Write>> {
+    #[cfg(unix)]
+    if let Ok(tty) = std::fs::OpenOptions::new().write(true).open("/dev/tty") {
+        return Some(Box::new(tty));
+    }
+    std::io::stderr()
+        .is_terminal()
+        .then(|| Box::new(std::io::stderr()) as Box<dyn Write>)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn osc52_carries_the_text_base64_encoded() {
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+        let long = "x".repeat(OSC52_MAX_LEN);
+        assert!(
+            copy_with(ClipboardMethod::Osc52, &long)
+                .unwrap_err()
+                .contains("too long")
+        );
+    }
+}
@@ -0,0 +1,669 @@
+//! User configuration read from `~/.config/cass/config.toml`.
+//!
+//! ```toml
+//! remotes = ["me@devbox", "buildhost"]   # used by `cass index --remote`
+//! extra_roots = [{ agent = "claude_code", path = "/mnt/disk2/claude-projects" }]
+//! synonyms = [["k8s", "kubernetes"], ["repro", "reproduce", "reproduction"]]
+//!
+//! [index]
+//! store_raw_json = true
+//! max_index_size_mb = 500
+//! analyzer = "english"
+//!
+//! [ranking]
+//! title_boost = 3.0
+//! recency_alpha = 0.2
+//!
+//! [embeddings]
+//! endpoint = "http://localhost:11434/v1/embeddings"
+//! model = "nomic-embed-text"
+//!
+//! [clipboard]
+//! method = "auto"
+//! ssh_method = "osc52"
+//!
+//! [tui]
+//! density = "compact"
+//! accessible = false
+//!
+//! [logging]
+//! file = true
+//! file_level = "warn,coding_agent_search=debug"
+//! max_files = 7
+//!
+//! [connectors]
+//! cline.enabled = false
+//! claude_code.root = "/mnt/backup/.claude"
+//! ```
+//!
+//! Each `[connectors]` key is a connector slug. `root` replaces the directory a
+//! connector would otherwise discover on its own (the same directory its environment
+//! override, e.g. `CODEX_HOME`, points at); an explicit environment variable still
+//! wins. `enabled = false` skips the connector when indexing and watching. A missing
+//! file means defaults; `CASS_CONFIG` points at another file.
+//!
+//! `extra_roots` adds directories scanned in addition to an agent's usual one, e.g.
+//! history moved onto another disk. The indexer scans each as if it were the
+//! connector's `root`, ahead of any environment override (see [`with_root`]).
+//!
+//! `synonyms` lists groups of interchangeable search terms, such as a team's
+//! shorthand. They are expanded when a query is built, so a plain term in a group also
+//! matches every other member (a member of several words matches them all), without
+//! reindexing. Matching ignores case.
+//!
+//! `[index] store_raw_json` keeps each message's original JSON in the search index
+//! (compressed, not searchable) so `cass export` can reproduce a session whose file is
+//! gone. It is off by default because it roughly doubles the index size.
+//!
+//! `[index] max_index_size_mb` caps the search index on disk. When a commit leaves it
+//! larger, the oldest conversations (by start time) are evicted from the index and the
+//! database until it fits again; `cass index` reports what was evicted.
+//!
+//! `[index] analyzer` picks how message text is split into terms. `simple` (the
+//! default) matches words as written; `english` also stems them, so "retry" finds
+//! "retries" and "retrying". The analyzer is part of the index's schema hash, so the
+//! next `cass index` after changing it rebuilds the index from the database.
+//!
+//! `[ranking]` weighs where a term matched: `title_boost`, `content_boost`,
+//! `prefix_boost` (type-ahead matches on the start of a word) and `snippets_boost`
+//! (tool commands, file paths, code) multiply the score of matches in those fields,
+//! all 1.0 by default. `recency_alpha` replaces the weight of recency in the TUI's
+//! Balanced ranking (0.4 by default), and `recency_half_life_days` sets how fast that
+//! weight fades with a message's age (30 by default).
+//!
+//! `[embeddings]` turns on semantic search: `cass index` embeds new messages into a
+//! vector store beside the search index and `cass search --semantic` ranks by meaning.
+//! `endpoint` is any OpenAI-compatible (or Ollama `/api/embed`) embeddings URL, with an
+//! optional key read from the `api_key_env` variable; `local = true` runs the model
+//! in-process instead, in builds with the `local-embeddings` feature.
+//!
+//! `[clipboard]` picks how `--copy` and the TUI's copy keys reach the clipboard:
+//! `auto`, `osc52`, `command` or `native` (see [`crate::clipboard`]). `method` applies
+//! locally (`auto` by default) and `ssh_method` in an SSH session (`osc52` by default).
+//!
+//! `[tui] density` sets how much room each result gets in the TUI: `compact` (header and
+//! one line of the message), `cozy` (the default; adds the file line and a second
+//! message line) or `spacious` (four message lines and a gap between results). A density
+//! picked in the TUI with Ctrl+D is remembered per profile and wins until it is toggled
+//! back to this one.
+//!
+//! `[tui] accessible = true` is for screen readers and fonts with few symbols: the TUI
+//! draws with ASCII (`+-|` borders, `>` markers, `#`/`.` score bars), uses its high
+//! contrast colors whatever the theme toggle says, and names states that would
+//! otherwise only show as a color, such as `[error]` on toasts and `(off)` on pills.
+//!
+//! `[logging] file = true` makes every run also log to `cass.log` in the data dir, as
+//! `--log-file` does for one run, rotated daily and pruned to the newest `max_files`
+//! (7). `file_level` filters what the file gets, independently of `-v`/`-q`, in
+//! `RUST_LOG` syntax; the default records connector scan decisions and index operations.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// `$XDG_CONFIG_HOME/cass`, or `~/.config/cass`.
+pub fn config_dir() -> PathBuf {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".config"))
+        .join("cass")
+}
+
+/// The config file in use: `CASS_CONFIG` if set, else `config_dir()/config.toml`.
+pub fn config_path() -> PathBuf {
+    std::env::var_os("CASS_CONFIG").map_or_else(|| config_dir().join("config.toml"), PathBuf::from)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Config {
+    /// Hosts `cass index --remote` mirrors when none is named on the command line.
+    #[serde(default, deserialize_with = "remote_hosts")]
+    pub remotes: Vec<String>,
+    #[serde(default)]
+    pub connectors: BTreeMap<String, ConnectorConfig>,
+    /// Additional session directories, scanned alongside each agent's usual root.
+    #[serde(default)]
+    pub extra_roots: Vec<ExtraRoot>,
+    /// Groups of search terms that match each other.
+    #[serde(default)]
+    pub synonyms: Vec<Vec<String>>,
+    #[serde(default)]
+    pub index: IndexConfig,
+    #[serde(default)]
+    pub ranking: RankingConfig,
+    pub embeddings: Option<EmbeddingsConfig>,
+    #[serde(default)]
+    pub clipboard: ClipboardConfig,
+    #[serde(default)]
+    pub tui: TuiConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TuiConfig {
+    /// Result density the TUI starts with.
+    pub density: Density,
+    /// ASCII glyphs and borders, high contrast colors, and states spelled out in text.
+    pub accessible: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    Compact,
+    #[default]
+    Cozy,
+    Spacious,
+}
+
+/// What the log file records unless `[logging] file_level` says otherwise.
+pub const DEFAULT_FILE_LOG_LEVEL: &str = "warn,coding_agent_search=debug";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    /// Log every run to `cass.log` in the data dir.
+    pub file: bool,
+    /// `EnvFilter` directives for the log file.
+    pub file_level: String,
+    /// Daily log files kept.
+    pub max_files: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            file: false,
+            file_level: DEFAULT_FILE_LOG_LEVEL.to_string(),
+            max_files: 7,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IndexConfig {
+    /// Store each message's original JSON alongside its document.
+    #[serde(default)]
+    pub store_raw_json: bool,
+    /// Evict the oldest conversations once the index grows past this many MiB.
+    pub max_index_size_mb: Option<u64>,
+    /// How message text is tokenized.
+    #[serde(default)]
+    pub analyzer: Analyzer,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RankingConfig {
+    /// Score multiplier for matches in conversation titles.
+    pub title_boost: f32,
+    /// Score multiplier for matches in message text.
+    pub content_boost: f32,
+    /// Score multiplier for matches on the start of a word (edge n-grams).
+    pub prefix_boost: f32,
+    /// Score multiplier for matches in tool commands, file paths and code.
+    pub snippets_boost: f32,
+    /// Weight of recency in the TUI's Balanced ranking.
+    pub recency_alpha: Option<f32>,
+    /// Age at which recency counts half as much, in days.
+    pub recency_half_life_days: f32,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            title_boost: 1.0,
+            content_boost: 1.0,
+            prefix_boost: 1.0,
+            snippets_boost: 1.0,
+            recency_alpha: None,
+            recency_half_life_days: 30.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Analyzer {
+    /// Lowercased words, matched as written.
+    #[default]
+    Simple,
+    /// Lowercased words reduced to their English stem.
+    English,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClipboardConfig {
+    /// How to copy in a local session.
+    pub method: ClipboardMethod,
+    /// How to copy in an SSH session.
+    pub ssh_method: ClipboardMethod,
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            method: ClipboardMethod::Auto,
+            ssh_method: ClipboardMethod::Osc52,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardMethod {
+    /// A clipboard tool, then the native API, then OSC 52.
+    #[default]
+    Auto,
+    /// The OSC 52 terminal escape sequence.
+    Osc52,
+    /// wl-copy, pbcopy, xclip, xsel or clip.exe.
+    Command,
+    /// The OS clipboard API (`native-clipboard` feature).
+    Native,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmbeddingsConfig {
+    /// Embeddings URL; requests are `{"model", "input": [...]}`.
+    pub endpoint: Option<String>,
+    /// Embed with the built-in ONNX model instead of an endpoint.
+    #[serde(default)]
+    pub local: bool,
+    /// Model name sent to the endpoint, or the local model (default `AllMiniLML6V2`).
+    pub model: Option<String>,
+    /// Environment variable holding a bearer token for the endpoint.
+    pub api_key_env: Option<String>,
+    /// Messages sent per request.
+    #[serde(default = "default_embedding_batch")]
+    pub batch_size: usize,
+}
+
+fn default_embedding_batch() -> usize {
+    32
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExtraRoot {
+    /// Connector slug, as in `[connectors]`.
+    pub agent: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectorConfig {
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    pub root: Option<PathBuf>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+fn remote_hosts<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<String>, D::Error> {
+    let remotes = Vec::<String>::deserialize(d)?;
+    for remote in &remotes {
+        check_remote(remote).map_err(serde::de::Error::custom)?;
+    }
+    Ok(remotes)
+}
+
+/// Reject a `user@host` remote that ssh or rsync would read as an option (`-e ...`).
+pub fn check_remote(remote: &str) -> std::result::Result<(), String> {
+    if remote.starts_with('-') {
+        return Err(format!(
+            "remote `{remote}` starts with `-`, which ssh and rsync would read as an option"
+        ));
+    }
+    Ok(())
+}
+
+impl Config {
+    /// Read `config_path()`; a missing file yields the defaults.
+    pub fn load() -> Result<Self> {
+        let path = config_path();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::from_path(&path)
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("reading config {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("parsing config {}", path.display()))
+    }
+
+    /// The process-wide config, loaded on first use. An unreadable file is
+    /// reported once and treated as empty so indexing still runs.
+    pub fn global() -> &'static Config {
+        static CONFIG: OnceLock<Config> = OnceLock::new();
+        CONFIG.get_or_init(|| {
+            Self::load().unwrap_or_else(|e| {
+                tracing::warn!(error = %format!("{e:#}"), "ignoring invalid config file");
+                Self::default()
+            })
+        })
+    }
+
+    pub fn connector_enabled(&self, slug: &str) -> bool {
+        self.connectors.get(slug).is_none_or(|c| c.enabled)
+    }
+
+    /// Configured root for `slug`, with a leading `~/` expanded.
+    pub fn connector_root(&self, slug: &str) -> Option<PathBuf> {
+        let root = self.connectors.get(slug)?.root.as_ref()?;
+        Some(expand_home(root))
+    }
+
+    /// `extra_roots` entries for `slug`, with a leading `~/` expanded.
+    pub fn extra_roots(&self, slug: &str) -> Vec<PathBuf> {
+        self.extra_roots
+            .iter()
+            .filter(|r| r.agent == slug)
+            .map(|r| expand_home(&r.path))
+            .collect()
+    }
+
+    /// The other members of every `synonyms` group containing `term`, lowercased.
+    pub fn synonyms(&self, term: &str) -> Vec<String> {
+        let term = term.to_lowercase();
+        let mut out: Vec<String> = Vec::new();
+        for group in &self.synonyms {
+            let members: Vec<String> = group.iter().map(|m| m.trim().to_lowercase()).collect();
+            if members.contains(&term) {
+                for member in members {
+                    if member != term && !member.is_empty() && !out.contains(&member) {
+                        out.push(member);
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+thread_local! {
+    static ROOT_OVERRIDE: RefCell<Option<(String, PathBuf)>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with [`connector_root`] answering `root` for `slug` on this thread, so a
+/// connector scanned inside `f` reads an extra root as if it were configured.
+pub fn with_root<T>(slug: &str, root: &Path, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<(String, PathBuf)>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            ROOT_OVERRIDE.with(|o| *o.borrow_mut() = self.0.take());
+        }
+    }
+    let _restore = Restore(ROOT_OVERRIDE.with(|o| {
+        o.borrow_mut()
+            .replace((slug.to_string(), root.to_path_buf()))
+    }));
+    f()
+}
+
+fn root_override(slug: &str) -> Option<PathBuf> {
+    ROOT_OVERRIDE.with(|o| {
+        o.borrow()
+            .as_ref()
+            .filter(|(s, _)| s == slug)
+            .map(|(_, root)| root.clone())
+    })
+}
+
+/// Shorthand for `Config::global().connector_root(slug)`, used by connectors'
+/// root lookups; a [`with_root`] override for `slug` wins.
+pub fn connector_root(slug: &str) -> Option<PathBuf> {
+    root_override(slug).or_else(|| Config::global().connector_root(slug))
+}
+
+/// Root for a connector whose agent also reads `env`: a [`with_root`] override,
+/// then the environment variable, then the configured root.
+pub fn env_root(slug: &str, env: &str) -> Option<PathBuf> {
+    root_override(slug)
+        .or_else(|| std::env::var_os(env).map(PathBuf::from))
+        .or_else(|| Config::global().connector_root(slug))
+}
+
+/// Shorthand for `Config::global().extra_roots(slug)`.
+pub fn extra_roots(slug: &str) -> Vec<PathBuf> {
+    Config::global().extra_roots(slug)
+}
+
+/// Shorthand for `Config::global().synonyms(term)`.
+pub fn synonyms(term: &str) -> Vec<String> {
+    Config::global().synonyms(term)
+}
+
+/// Shorthand for `Config::global().index.store_raw_json`.
+pub fn store_raw_json() -> bool {
+    Config::global().index.store_raw_json
+}
+
+/// Shorthand for `Config::global().index.max_index_size_mb`.
+pub fn max_index_size_mb() -> Option<u64> {
+    Config::global().index.max_index_size_mb
+}
+
+/// Shorthand for `Config::global().index.analyzer`.
+pub fn analyzer() -> Analyzer {
+    Config::global().index.analyzer
+}
+
+/// Shorthand for `Config::global().ranking`.
+pub fn ranking() -> &'static RankingConfig {
+    &Config::global().ranking
+}
+
+/// Shorthand for `Config::global().embeddings`.
+pub fn embeddings() -> Option<&'static EmbeddingsConfig> {
+    Config::global().embeddings.as_ref()
+}
+
+/// Shorthand for `Config::global().clipboard`.
+pub fn clipboard() -> ClipboardConfig {
+    Config::global().clipboard
+}
+
+/// Shorthand for `Config::global().tui`.
+pub fn tui() -> TuiConfig {
+    Config::global().tui
+}
+
+/// Shorthand for `Config::global().logging`.
+pub fn logging() -> &'static LoggingConfig {
+    &Config::global().logging
+}
+
+/// Shorthand for `Config::global().connector_enabled(slug)`.
+pub fn connector_enabled(slug: &str) -> bool {
+    Config::global().connector_enabled(slug)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connectors_section_parses_roots_and_toggles() {
+        let cfg: Config = toml::from_str(
+            r#"
+[connectors]
+cline.enabled = false
+claude_code.root = "/mnt/backup/.claude"
+codex = { root = "~/old/.codex" }
+"#,
+        )
+        .unwrap();
+        assert!(!cfg.connector_enabled("cline"));
+        assert!(cfg.connector_enabled("claude_code"));
+        assert!(cfg.connector_enabled("gemini"));
+        assert_eq!(
+            cfg.connector_root("claude_code"),
+            Some(PathBuf::from("/mnt/backup/.claude"))
+        );
+        assert_eq!(
+            cfg.connector_root("codex"),
+            Some(dirs::home_dir().unwrap_or_default().join("old/.codex"))
+        );
+        assert_eq!(cfg.connector_root("cline"), None);
+    }
+
+    #[test]
+    fn index_section_sets_raw_json_size_cap_and_analyzer() {
+        let cfg = Config::default();
+        assert!(!cfg.index.store_raw_json);
+        assert_eq!(cfg.index.max_index_size_mb, None);
+        assert_eq!(cfg.index.analyzer, Analyzer::Simple);
+
+        let cfg: Config = toml::from_str(
+            "[index]\nstore_raw_json = true\nmax_index_size_mb = 500\nanalyzer = \"english\"\n",
+        )
+        .unwrap();
+        assert!(cfg.index.store_raw_json);
+        assert_eq!(cfg.index.max_index_size_mb, Some(500));
+        assert_eq!(cfg.index.analyzer, Analyzer::English);
+    }
+
+    #[test]
+    fn ranking_section_overrides_weights_and_rejects_unknown_keys() {
+        assert_eq!(Config::default().ranking, RankingConfig::default());
+        let cfg: Config =
+            toml::from_str("[ranking]\ntitle_boost = 3.0\nrecency_alpha = 0.2\n").unwrap();
+        assert_eq!(cfg.ranking.title_boost, 3.0);
+        assert_eq!(cfg.ranking.content_boost, 1.0);
+        assert_eq!(cfg.ranking.recency_alpha, Some(0.2));
+        assert_eq!(cfg.ranking.recency_half_life_days, 30.0);
+        assert!(toml::from_str::<Config>("[ranking]\ntitle = 3.0\n").is_err());
+    }
+
+    #[test]
+    fn embeddings_section_is_optional_and_fills_defaults() {
+        assert_eq!(Config::default().embeddings, None);
+        let cfg: Config = toml::from_str(
+            "[embeddings]\nendpoint = \"http://localhost:11434/api/embed\"\nmodel = \"nomic-embed-text\"\n",
+        )
+        .unwrap();
+        let embeddings = cfg.embeddings.unwrap();
+        assert_eq!(embeddings.model.as_deref(), Some("nomic-embed-text"));
+        assert!(!embeddings.local);
+        assert_eq!(embeddings.batch_size, 32);
+    }
+
+    #[test]
+    fn clipboard_section_picks_methods_and_keeps_osc52_over_ssh() {
+        assert_eq!(
+            Config::default().clipboard.ssh_method,
+            ClipboardMethod::Osc52
+        );
+        let cfg: Config = toml::from_str("[clipboard]\nmethod = \"command\"\n").unwrap();
+        assert_eq!(cfg.clipboard.method, ClipboardMethod::Command);
+        assert_eq!(cfg.clipboard.ssh_method, ClipboardMethod::Osc52);
+        assert!(toml::from_str::<Config>("[clipboard]\nmethod = \"xclip\"\n").is_err());
+    }
+
+    #[test]
+    fn tui_section_sets_density_and_accessible_mode() {
+        let defaults = Config::default();
+        assert_eq!(defaults.tui.density, Density::Cozy);
+        assert!(!defaults.tui.accessible);
+
+        let cfg: Config = toml::from_str("[tui]\ndensity = \"spacious\"\n").unwrap();
+        assert_eq!(cfg.tui.density, Density::Spacious);
+        assert!(!cfg.tui.accessible);
+        assert!(toml::from_str::<Config>("[tui]\ndensity = \"tiny\"\n").is_err());
+
+        let cfg: Config = toml::from_str("[tui]\naccessible = true\n").unwrap();
+        assert!(cfg.tui.accessible);
+        assert_eq!(cfg.tui.density, Density::Cozy);
+    }
+
+    #[test]
+    fn logging_section_enables_the_log_file_with_defaults() {
+        let cfg: Config = toml::from_str("[logging]\nfile = true\n").unwrap();
+        assert!(cfg.logging.file);
+        assert_eq!(cfg.logging.file_level, DEFAULT_FILE_LOG_LEVEL);
+        assert_eq!(cfg.logging.max_files, 7);
+    }
+
+    #[test]
+    fn synonym_groups_expand_both_ways_ignoring_case() {
+        let cfg: Config = toml::from_str(
+            r#"synonyms = [["k8s", "Kubernetes"], ["repro", "reproduce", "k8s"], ["pr", "pull request"]]"#,
+        )
+        .unwrap();
+        assert_eq!(cfg.synonyms("K8S"), ["kubernetes", "repro", "reproduce"]);
+        assert_eq!(cfg.synonyms("kubernetes"), ["k8s"]);
+        assert_eq!(cfg.synonyms("pr"), ["pull request"]);
+        assert!(cfg.synonyms("deploy").is_empty());
+        assert!(Config::default().synonyms("k8s").is_empty());
+    }
+
+    #[test]
+    fn extra_roots_are_listed_per_agent_and_override_the_root_while_scanning() {
+        let cfg: Config = toml::from_str(
+            r#"
+extra_roots = [
+  { agent = "claude_code", path = "/mnt/disk2/claude" },
+  { agent = "codex", path = "~/archive/codex" },
+  { agent = "claude_code", path = "/mnt/disk3/claude" },
+]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            cfg.extra_roots("claude_code"),
+            vec![
+                PathBuf::from("/mnt/disk2/claude"),
+                PathBuf::from("/mnt/disk3/claude")
+            ]
+        );
+        assert_eq!(
+            cfg.extra_roots("codex"),
+            vec![dirs::home_dir().unwrap_or_default().join("archive/codex")]
+        );
+        assert!(cfg.extra_roots("gemini").is_empty());
+
+        let root = Path::new("/mnt/disk2/claude");
+        with_root("claude_code", root, || {
+            assert_eq!(connector_root("claude_code").as_deref(), Some(root));
+            assert_ne!(connector_root("codex").as_deref(), Some(root));
+        });
+        assert_ne!(connector_root("claude_code").as_deref(), Some(root));
+    }
+
+    #[test]
+    fn unknown_connector_keys_are_rejected() {
+        let err = toml::from_str::<Config>("[connectors]\ncodex.path = \"/x\"\n").unwrap_err();
+        assert!(err.to_string().contains("unknown field"), "{err}");
+    }
+
+    #[test]
+    fn remotes_that_look_like_options_are_rejected() {
+        let config = toml::from_str::<Config>("remotes = [\"me@box1\"]\n").unwrap();
+        assert_eq!(config.remotes, ["me@box1"]);
+        let err =
+            toml::from_str::<Config>("remotes = [\"-oProxyCommand=touch /tmp/x\"]\n").unwrap_err();
+        assert!(err.to_string().contains("starts with `-`"), "{err}");
+    }
+}
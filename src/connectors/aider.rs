@@ -2,9 +2,14 @@ use super::{Connector, DetectionResult, NormalizedConversation, NormalizedMessag
 use anyhow::Result;
 use serde_json::json;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// Markdown transcript aider writes by default.
+const CHAT_HISTORY_FILE: &str = ".aider.chat.history.md";
+/// Raw prompt/response log written with `--llm-history-file .aider.llm.history`.
+const LLM_HISTORY_FILE: &str = ".aider.llm.history";
+
 pub struct AiderConnector;
 
 impl AiderConnector {
@@ -12,23 +17,20 @@ impl AiderConnector {
         Self
     }
 
-    /// Find aider chat history files under the provided roots (limited depth to avoid wide scans).
+    /// Find aider chat and LLM history files under the provided roots (limited depth to avoid wide scans).
     fn find_chat_files(roots: &[&Path]) -> Vec<std::path::PathBuf> {
         let mut files = Vec::new();
         for root in roots {
             if !root.exists() {
                 continue;
             }
-            for entry in WalkDir::new(root)
-                .max_depth(5)
-                .into_iter()
-                .flatten()
+            for entry in crate::connectors::walk(WalkDir::new(root).max_depth(5))
                 .filter(|e| e.file_type().is_file())
             {
                 if entry
                     .file_name()
                     .to_str()
-                    .is_some_and(|n| n == ".aider.chat.history.md")
+                    .is_some_and(|n| n == CHAT_HISTORY_FILE || n == LLM_HISTORY_FILE)
                 {
                     files.push(entry.path().to_path_buf());
                 }
@@ -107,7 +109,7 @@ impl AiderConnector {
             agent_slug: "aider".to_string(),
             external_id: Some(path.file_name().unwrap().to_string_lossy().to_string()),
             title: Some(format!("Aider Chat: {}", path.display())),
-            workspace: path.parent().map(std::path::Path::to_path_buf),
+            workspace: workspace_for(path),
             source_path: path.to_path_buf(),
             started_at: Some(ts),
             ended_at: Some(ts),
@@ -115,6 +117,171 @@ impl AiderConnector {
             messages,
         })
     }
+
+    /// Parse the raw LLM log into its own conversation.
+    ///
+    /// The log alternates `TO LLM <ts>` blocks (the full prompt, one `ROLE text` line per
+    /// content line, messages separated by `-------`) and `LLM RESPONSE <ts>` blocks. Each
+    /// prompt re-sends the whole chat, so only the messages after its last ASSISTANT
+    /// turn are new: the user request plus any command, lint or test output aider fed
+    /// back, which the markdown history drops.
+    fn parse_llm_history(&self, path: &Path) -> Result<NormalizedConversation> {
+        let content = fs::read_to_string(path)?;
+        let mut messages = Vec::new();
+
+        for block in split_llm_blocks(&content) {
+            let new_turns: Vec<(&str, String)> = match block.kind {
+                LlmBlockKind::ToLlm => {
+                    let turns = parse_prompt_turns(&block.body);
+                    let start = turns
+                        .iter()
+                        .rposition(|(role, _)| role == "ASSISTANT")
+                        .map_or(0, |i| i + 1);
+                    turns
+                        .into_iter()
+                        .skip(start)
+                        .filter(|(role, _)| role != "SYSTEM")
+                        .map(|(_, text)| ("user", text))
+                        .collect()
+                }
+                LlmBlockKind::Response => vec![("assistant", strip_role_prefix(&block.body))],
+            };
+            for (role, text) in new_turns {
+                if !text.trim().is_empty() {
+                    messages.push(llm_message(messages.len(), role, &text, block.ts));
+                }
+            }
+        }
+
+        let mtime = fs::metadata(path)?
+            .modified()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        let started_at = messages.iter().filter_map(|m| m.created_at).min();
+        let ended_at = messages.iter().filter_map(|m| m.created_at).max();
+
+        Ok(NormalizedConversation {
+            agent_slug: "aider".to_string(),
+            external_id: Some(path.file_name().unwrap().to_string_lossy().to_string()),
+            title: Some(format!("Aider LLM History: {}", path.display())),
+            workspace: workspace_for(path),
+            source_path: path.to_path_buf(),
+            started_at: started_at.or(Some(mtime)),
+            ended_at: ended_at.or(Some(mtime)),
+            metadata: json!({ "source": "llm_history" }),
+            messages,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LlmBlockKind {
+    ToLlm,
+    Response,
+}
+
+struct LlmBlock {
+    kind: LlmBlockKind,
+    ts: Option<i64>,
+    body: String,
+}
+
+/// Aider writes its history files at the repo root, but `--no-git` or a custom
+/// history path can put them elsewhere; prefer the enclosing git root.
+fn workspace_for(path: &Path) -> Option<PathBuf> {
+    let dir = path.parent()?;
+    super::git_root(dir).or_else(|| Some(dir.to_path_buf()))
+}
+
+/// Recognize `TO LLM 2024-05-01T12:34:56` / `LLM RESPONSE 2024-05-01T12:34:56` headers.
+fn parse_llm_header(line: &str) -> Option<(LlmBlockKind, Option<i64>)> {
+    let (kind, rest) = if let Some(rest) = line.strip_prefix("TO LLM ") {
+        (LlmBlockKind::ToLlm, rest)
+    } else {
+        (LlmBlockKind::Response, line.strip_prefix("LLM RESPONSE ")?)
+    };
+    let naive = chrono::NaiveDateTime::parse_from_str(rest.trim(), "%Y-%m-%dT%H:%M:%S").ok()?;
+    // aider logs local wall-clock time without an offset.
+    let ts = naive
+        .and_local_timezone(chrono::Local)
+        .earliest()
+        .map(|dt| dt.timestamp_millis());
+    Some((kind, ts))
+}
+
+fn split_llm_blocks(content: &str) -> Vec<LlmBlock> {
+    let mut blocks: Vec<LlmBlock> = Vec::new();
+    for line in content.lines() {
+        if let Some((kind, ts)) = parse_llm_header(line) {
+            blocks.push(LlmBlock {
+                kind,
+                ts,
+                body: String::new(),
+            });
+        } else if let Some(block) = blocks.last_mut() {
+            block.body.push_str(line);
+            block.body.push('\n');
+        }
+    }
+    blocks
+}
+
+/// Split a `ROLE text` line; empty content lines are logged as a bare `ROLE`.
+fn split_role_line(line: &str) -> Option<(&str, &str)> {
+    let (role, text) = line.split_once(' ').unwrap_or((line, ""));
+    matches!(role, "SYSTEM" | "USER" | "ASSISTANT").then_some((role, text))
+}
+
+/// Group a prompt body into `(ROLE, text)` turns.
+fn parse_prompt_turns(body: &str) -> Vec<(String, String)> {
+    let mut turns: Vec<(String, String)> = Vec::new();
+    let mut new_turn = true;
+    for line in body.lines() {
+        if line == "-------" {
+            new_turn = true;
+            continue;
+        }
+        match split_role_line(line) {
+            Some((role, text)) if new_turn || turns.last().is_some_and(|(r, _)| r != role) => {
+                turns.push((role.to_string(), format!("{text}\n")));
+                new_turn = false;
+            }
+            Some((_, text)) => {
+                if let Some((_, buf)) = turns.last_mut() {
+                    buf.push_str(text);
+                    buf.push('\n');
+                }
+            }
+            // Lines without a role prefix (e.g. function-call dumps) continue the turn.
+            None => {
+                if let Some((_, buf)) = turns.last_mut() {
+                    buf.push_str(line);
+                    buf.push('\n');
+                }
+            }
+        }
+    }
+    turns
+}
+
+fn strip_role_prefix(body: &str) -> String {
+    body.lines()
+        .map(|line| split_role_line(line).map_or(line, |(_, text)| text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn llm_message(idx: usize, role: &str, text: &str, ts: Option<i64>) -> NormalizedMessage {
+    NormalizedMessage {
+        idx: idx as i64,
+        role: role.to_string(),
+        author: Some(role.to_string()),
+        created_at: ts,
+        content: text.trim().to_string(),
+        extra: json!({}),
+        snippets: Vec::new(),
+    }
 }
 
 impl Connector for AiderConnector {
@@ -123,18 +290,26 @@ impl Connector for AiderConnector {
         // The expensive WalkDir scan is deferred to scan() where it's actually needed.
         // Also check for CASS_AIDER_DATA_ROOT env var as a signal.
         let cwd = std::env::current_dir().unwrap_or_default();
-        let cwd_history = cwd.join(".aider.chat.history.md");
+        for name in [CHAT_HISTORY_FILE, LLM_HISTORY_FILE] {
+            let cwd_history = cwd.join(name);
+            if cwd_history.exists() {
+                return DetectionResult {
+                    detected: true,
+                    evidence: vec![format!("found {}", cwd_history.display())],
+                };
+            }
+        }
 
-        if cwd_history.exists() {
+        if let Some(root) = crate::config::connector_root("aider") {
             return DetectionResult {
                 detected: true,
-                evidence: vec![format!("found {}", cwd_history.display())],
+                evidence: vec![format!("configured aider.root {}", root.display())],
             };
         }
 
         if let Some(override_root) = std::env::var_os("CASS_AIDER_DATA_ROOT") {
             let override_path = std::path::PathBuf::from(&override_root);
-            let override_history = override_path.join(".aider.chat.history.md");
+            let override_history = override_path.join(CHAT_HISTORY_FILE);
             if override_history.exists() {
                 return DetectionResult {
                     detected: true,
@@ -158,17 +333,25 @@ impl Connector for AiderConnector {
     }
 
     fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
-        let files = Self::find_chat_files(std::slice::from_ref(&ctx.data_root.as_path()));
+        let mut roots = vec![ctx.data_root.clone()];
+        roots.extend(crate::config::connector_root("aider"));
+        let roots: Vec<&Path> = roots.iter().map(PathBuf::as_path).collect();
+        let mut files = Self::find_chat_files(&roots);
+        files.retain(|path| ctx.file_changed(path));
 
+        let parse = |path: &Path| {
+            let parsed = if path.file_name().is_some_and(|n| n == LLM_HISTORY_FILE) {
+                self.parse_llm_history(path)
+            } else {
+                self.parse_chat_history(path)
+            };
+            Ok(parsed.ok())
+        };
         let mut conversations = Vec::new();
-        for path in files {
-            if !super::file_modified_since(&path, ctx.since_ts) {
-                continue;
-            }
-            if let Ok(conv) = self.parse_chat_history(&path) {
-                conversations.push(conv);
-            }
-        }
+        crate::connectors::parse_files(files, parse, &mut |conv| {
+            conversations.push(conv);
+            Ok(())
+        })?;
         Ok(conversations)
     }
 }
@@ -39,6 +39,9 @@ impl AmpConnector {
     }
 
     pub fn candidate_roots() -> Vec<PathBuf> {
+        if let Some(root) = crate::config::connector_root("amp") {
+            return vec![root];
+        }
         let mut roots = vec![Self::cache_root()];
         roots.extend(Self::vscode_global_storage());
         roots
@@ -86,7 +89,7 @@ impl Connector for AmpConnector {
                 continue;
             }
 
-            for entry in WalkDir::new(&root).into_iter().flatten() {
+            for entry in crate::connectors::walk(WalkDir::new(&root)) {
                 if !entry.file_type().is_file() {
                     continue;
                 }
@@ -95,7 +98,7 @@ impl Connector for AmpConnector {
                     continue;
                 }
                 // Skip files not modified since last scan (incremental indexing)
-                if !crate::connectors::file_modified_since(path, ctx.since_ts) {
+                if !ctx.file_changed(path) {
                     continue;
                 }
                 let text = match std::fs::read_to_string(path) {
@@ -104,7 +107,10 @@ impl Connector for AmpConnector {
                 };
                 let val: Value = match serde_json::from_str(&text) {
                     Ok(v) => v,
-                    Err(_) => continue,
+                    Err(e) => {
+                        ctx.quarantine("amp", path, e);
+                        continue;
+                    }
                 };
 
                 if let Some(messages) = extract_messages(&val, ctx.since_ts) {
@@ -218,7 +224,7 @@ fn extract_messages(val: &Value, _since_ts: Option<i64>) -> Option<Vec<Normalize
             .map(std::string::ToString::to_string);
 
         // NOTE: Do NOT filter individual messages by timestamp here!
-        // The file-level check in file_modified_since() is sufficient.
+        // The file-level check in ctx.file_changed() is sufficient.
         // Filtering messages would cause older messages to be lost when
         // the file is re-indexed after new messages are added.
 
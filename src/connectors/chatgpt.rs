@@ -140,6 +140,9 @@ impl ChatGptConnector {
 
     /// Get the ChatGPT app support directory
     pub fn app_support_dir() -> Option<PathBuf> {
+        if let Some(root) = crate::config::connector_root("chatgpt") {
+            return Some(root);
+        }
         #[cfg(target_os = "macos")]
         {
             dirs::home_dir().map(|h| h.join("Library/Application Support/com.openai.chat"))
@@ -320,7 +323,7 @@ impl ChatGptConnector {
                     .map(|ts| (ts * 1000.0) as i64);
 
                 // NOTE: Do NOT filter individual messages by timestamp here!
-                // The file-level check in file_modified_since() is sufficient.
+                // The file-level check in ctx.file_changed() is sufficient.
                 // Filtering messages would cause older messages to be lost when
                 // the file is re-indexed after new messages are added.
 
@@ -489,7 +492,7 @@ impl Connector for ChatGptConnector {
             }
 
             // Walk through conversation files
-            for entry in WalkDir::new(&dir_path).max_depth(1).into_iter().flatten() {
+            for entry in crate::connectors::walk(WalkDir::new(&dir_path).max_depth(1)) {
                 if !entry.file_type().is_file() {
                     continue;
                 }
@@ -503,7 +506,7 @@ impl Connector for ChatGptConnector {
                 }
 
                 // Skip files not modified since last scan
-                if !crate::connectors::file_modified_since(path, ctx.since_ts) {
+                if !ctx.file_changed(path) {
                     continue;
                 }
 
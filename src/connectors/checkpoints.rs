@@ -0,0 +1,431 @@
+//! Per-file scan checkpoints.
+//!
+//! Incremental indexing used to rescan any file whose mtime was newer than the last
+//! run. That misses files edited while keeping an old mtime (rsync, `cp -p`) and
+//! rescans whole trees after a backup restore bumps every mtime. Instead the indexer
+//! remembers mtime, size and a SHA-256 of every source file it has read, in
+//! `file_checkpoints.json` in the data dir, and a file is rescanned only when its
+//! content changed.
+//!
+//! The indexer activates the store for its data dir while it runs; connectors ask
+//! through [`ScanContext::file_changed`](super::ScanContext::file_changed). A scan
+//! whose data root has no active store (tests, remote mirrors) keeps the plain
+//! `since_ts` mtime check.
+//!
+//! The store also carries the [quarantine](super::quarantine) list, which shares its
+//! lifecycle: cleared by a full rebuild, saved with each index commit.
+//!
+//! `cass index --path` narrows a run to some files or directories: the store then
+//! reports everything outside them as unchanged ([`FileCheckpoints::within`]).
+//!
+//! Because it lists every file indexed so far, the store is also how `cass index`
+//! notices session files the agent has since deleted ([`FileCheckpoints::vanished`]).
+//!
+//! A file's new state is only pending until the commit. The indexer runs each connector
+//! [`scanning_as`] its name, and when a scan fails it [discards](FileCheckpoints::discard_pending)
+//! what that connector observed, so the files are read again next run.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::quarantine::Quarantine;
+
+const FILE_NAME: &str = "file_checkpoints.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileState {
+    pub mtime_ms: i64,
+    pub size: u64,
+    /// Hex SHA-256 of the content; `None` for directories.
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct FileCheckpoints {
+    path: PathBuf,
+    committed: Mutex<HashMap<PathBuf, FileState>>,
+    /// States observed by this run, saved once the index commit succeeds, with the
+    /// connector that observed them (see [`scanning_as`]).
+    pending: Mutex<HashMap<PathBuf, (FileState, Option<&'static str>)>>,
+    quarantine: Quarantine,
+    /// Files found unchanged, kept only for `cass index --dry-run` reports.
+    unchanged: Option<Mutex<Vec<PathBuf>>>,
+    /// Roots this run is limited to; `None` covers every file.
+    scope: Option<Vec<PathBuf>>,
+    /// Report every file in scope as changed, whatever its content.
+    reread: bool,
+}
+
+impl FileCheckpoints {
+    /// Load the store for `data_dir`; a missing or unreadable file starts empty.
+    pub fn load(data_dir: &Path) -> Self {
+        let path = data_dir.join(FILE_NAME);
+        let committed = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Vec<(PathBuf, FileState)>>(&bytes).ok())
+            .map(|entries| entries.into_iter().collect())
+            .unwrap_or_default();
+        Self {
+            path,
+            committed: Mutex::new(committed),
+            pending: Mutex::new(HashMap::new()),
+            quarantine: Quarantine::load(data_dir),
+            unchanged: None,
+            scope: None,
+            reread: false,
+        }
+    }
+
+    /// Limit the run to files under `roots`; see [`in_scope`](Self::in_scope).
+    pub fn within(mut self, roots: Vec<PathBuf>) -> Self {
+        self.scope = Some(roots);
+        self
+    }
+
+    /// Rescan every file in scope even if unchanged, as `--full` does for a selection,
+    /// while still recording what was read.
+    pub fn rereading(mut self) -> Self {
+        self.reread = true;
+        self
+    }
+
+    /// Whether `path` is under one of the [`within`](Self::within) roots, or is a
+    /// directory holding one (a connector that checks a session directory before its
+    /// files must be let in).
+    pub fn in_scope(&self, path: &Path) -> bool {
+        self.scope.as_ref().is_none_or(|roots| {
+            roots
+                .iter()
+                .any(|root| path.starts_with(root) || root.starts_with(path))
+        })
+    }
+
+    /// Remember the files [`changed`](Self::changed) skips, for [`take_unchanged`](Self::take_unchanged).
+    pub fn tracking_unchanged(mut self) -> Self {
+        self.unchanged = Some(Mutex::new(Vec::new()));
+        self
+    }
+
+    /// Files skipped as unchanged since the last call, when tracking.
+    pub fn take_unchanged(&self) -> Vec<PathBuf> {
+        self.unchanged
+            .as_ref()
+            .map(|u| std::mem::take(&mut *lock(u)))
+            .unwrap_or_default()
+    }
+
+    /// Forget everything, e.g. before a full rebuild.
+    pub fn clear(&self) {
+        lock(&self.committed).clear();
+        lock(&self.pending).clear();
+        self.quarantine.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        lock(&self.committed).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn quarantine(&self) -> &Quarantine {
+        &self.quarantine
+    }
+
+    /// True if `path` is new or its content differs from the last committed scan.
+    /// Unreadable paths count as changed so the connector reports the error. A changed
+    /// file leaves quarantine until the connector reports it again.
+    /// Paths out of [scope](Self::in_scope) are always unchanged.
+    pub fn changed(&self, path: &Path) -> bool {
+        if !self.in_scope(path) {
+            return false;
+        }
+        let changed = self.content_changed(path) || self.reread;
+        if changed {
+            self.quarantine.release(path);
+        } else if let Some(unchanged) = &self.unchanged {
+            lock(unchanged).push(path.to_path_buf());
+        }
+        changed
+    }
+
+    fn content_changed(&self, path: &Path) -> bool {
+        let Ok(meta) = std::fs::metadata(path) else {
+            return true;
+        };
+        let mtime_ms = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_millis() as i64);
+        let size = meta.len();
+        let previous = lock(&self.committed).get(path).cloned();
+        if let Some(prev) = &previous
+            && prev.mtime_ms == mtime_ms
+            && prev.size == size
+        {
+            return false;
+        }
+
+        let sha256 = if meta.is_dir() {
+            None
+        } else {
+            match sha256_file(path) {
+                Ok(digest) => Some(digest),
+                Err(_) => return true,
+            }
+        };
+        // Same bytes under a new mtime (restored from backup): just remember the new mtime.
+        let unchanged = previous.is_some_and(|prev| prev.sha256.is_some() && prev.sha256 == sha256);
+        lock(&self.pending).insert(
+            path.to_path_buf(),
+            (
+                FileState {
+                    mtime_ms,
+                    size,
+                    sha256,
+                },
+                SCANNER.get(),
+            ),
+        );
+        !unchanged
+    }
+
+    /// Indexed files that no longer exist while their directory, or that directory's
+    /// parent, still does: history the agent pruned. A tree that is missing as a whole,
+    /// such as an unmounted disk, is left alone until it comes back.
+    pub fn vanished(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<_> = lock(&self.committed)
+            .keys()
+            .filter(|path| {
+                !path.exists()
+                    && path
+                        .ancestors()
+                        .skip(1)
+                        .take(2)
+                        .any(|dir| !dir.as_os_str().is_empty() && dir.exists())
+            })
+            .cloned()
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Drop `path` and its archived siblings (`x.jsonl.gz` for `x.jsonl`) from the
+    /// store and the quarantine, so a sibling that still exists is rescanned as new.
+    pub fn forget(&self, path: &Path) {
+        let plain = super::uncompressed_path(path);
+        let sibling = |p: &PathBuf| super::uncompressed_path(p) == plain;
+        lock(&self.committed).retain(|p, _| !sibling(p));
+        lock(&self.pending).retain(|p, _| !sibling(p));
+        self.quarantine.release(path);
+    }
+
+    /// Drop what `scanner` observed this run, after its scan failed: files it had not
+    /// parsed yet would otherwise be recorded as read and skipped from then on.
+    pub fn discard_pending(&self, scanner: &str) {
+        lock(&self.pending).retain(|_, (_, by)| *by != Some(scanner));
+    }
+
+    /// Fold this run's observations into the store and write it to disk.
+    pub fn commit(&self) -> Result<()> {
+        let pending: Vec<_> = lock(&self.pending).drain().collect();
+        let mut committed = lock(&self.committed);
+        committed.extend(pending.into_iter().map(|(path, (state, _))| (path, state)));
+        let mut entries: Vec<_> = committed.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&entries)?)
+            .with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("writing {}", self.path.display()))?;
+        self.quarantine.commit()
+    }
+}
+
+thread_local! {
+    static SCANNER: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+/// Run `scan` with the file states it observes attributed to connector `name`, for
+/// [`FileCheckpoints::discard_pending`]. Work a connector hands to other threads carries
+/// the attribution over with `scanning_as(current_scanner(), ..)`.
+pub fn scanning_as<R>(name: Option<&'static str>, scan: impl FnOnce() -> R) -> R {
+    struct Restore(Option<&'static str>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCANNER.set(self.0);
+        }
+    }
+    let _restore = Restore(SCANNER.replace(name));
+    scan()
+}
+
+/// The connector the current thread scans for, if any.
+pub fn current_scanner() -> Option<&'static str> {
+    SCANNER.get()
+}
+
+fn lock<T>(m: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut ctx = ring::digest::Context::new(&ring::digest::SHA256);
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        ctx.update(&buf[..n]);
+    }
+    Ok(ctx
+        .finish()
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+fn registry() -> &'static Mutex<HashMap<PathBuf, Arc<FileCheckpoints>>> {
+    static ACTIVE: OnceLock<Mutex<HashMap<PathBuf, Arc<FileCheckpoints>>>> = OnceLock::new();
+    ACTIVE.get_or_init(Default::default)
+}
+
+/// Keeps a store active for a data dir; dropping it deactivates the store.
+pub struct ActiveCheckpoints {
+    data_dir: PathBuf,
+    store: Arc<FileCheckpoints>,
+}
+
+impl ActiveCheckpoints {
+    pub fn activate(data_dir: &Path, store: FileCheckpoints) -> Self {
+        let store = Arc::new(store);
+        lock(registry()).insert(data_dir.to_path_buf(), store.clone());
+        Self {
+            data_dir: data_dir.to_path_buf(),
+            store,
+        }
+    }
+
+    pub fn store(&self) -> &FileCheckpoints {
+        &self.store
+    }
+}
+
+impl Drop for ActiveCheckpoints {
+    fn drop(&mut self) {
+        lock(registry()).remove(&self.data_dir);
+    }
+}
+
+/// The store the indexer activated for `data_root`, if any.
+pub fn active(data_root: &Path) -> Option<Arc<FileCheckpoints>> {
+    lock(registry()).get(data_root).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restored_copies_are_skipped_and_edits_are_caught() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("session.jsonl");
+        std::fs::write(&file, "one\n").unwrap();
+
+        let store = FileCheckpoints::load(dir.path());
+        assert!(store.changed(&file), "new files are scanned");
+        store.commit().unwrap();
+        assert!(!store.changed(&file), "untouched files are skipped");
+
+        // Same bytes, new mtime: a restore from backup.
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert!(!store.changed(&file));
+        store.commit().unwrap();
+
+        // New content under an old mtime: rsync -t or cp -p.
+        std::fs::write(&file, "two\n").unwrap();
+        let earlier = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(earlier)
+            .unwrap();
+        assert!(store.changed(&file));
+        store.commit().unwrap();
+
+        let reloaded = FileCheckpoints::load(dir.path());
+        assert_eq!(reloaded.len(), 1);
+        assert!(!reloaded.changed(&file));
+    }
+
+    #[test]
+    fn deleted_files_vanish_unless_their_tree_is_gone() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path().join("projects/app");
+        let other = dir.path().join("disk2/projects/app");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::create_dir_all(&other).unwrap();
+        let pruned = project.join("a.jsonl");
+        let archived = project.join("b.jsonl");
+        let unmounted = other.join("c.jsonl");
+        for file in [&pruned, &archived, &unmounted] {
+            std::fs::write(file, "{}\n").unwrap();
+        }
+        let rotated = project.join("b.jsonl.gz");
+        std::fs::write(&rotated, "gz").unwrap();
+
+        let store = FileCheckpoints::load(dir.path());
+        for file in [&pruned, &archived, &rotated, &unmounted] {
+            assert!(store.changed(file));
+        }
+        store.commit().unwrap();
+
+        std::fs::remove_file(&pruned).unwrap();
+        std::fs::remove_file(&archived).unwrap();
+        std::fs::remove_dir_all(dir.path().join("disk2")).unwrap();
+        assert_eq!(store.vanished(), vec![pruned.clone(), archived.clone()]);
+
+        store.forget(&archived);
+        assert!(store.changed(&rotated), "the archived copy is rescanned");
+    }
+
+    #[test]
+    fn a_scoped_run_only_sees_its_roots() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = dir.path().join("projects/app");
+        std::fs::create_dir_all(&project).unwrap();
+        let inside = project.join("a.jsonl");
+        let outside = dir.path().join("b.jsonl");
+        std::fs::write(&inside, "{}\n").unwrap();
+        std::fs::write(&outside, "{}\n").unwrap();
+
+        let store = FileCheckpoints::load(dir.path()).within(vec![project.clone()]);
+        assert!(store.in_scope(&dir.path().join("projects")));
+        assert!(store.changed(&inside));
+        assert!(!store.changed(&outside));
+        store.commit().unwrap();
+        assert_eq!(FileCheckpoints::load(dir.path()).len(), 1);
+
+        let store = FileCheckpoints::load(dir.path()).within(vec![project]);
+        assert!(!store.changed(&inside));
+        assert!(store.rereading().changed(&inside));
+    }
+}
@@ -1,12 +1,12 @@
-use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use serde_json::Value;
 use walkdir::WalkDir;
 
 use crate::connectors::{
-    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    Connector, DetectionResult, EmitConversation, NormalizedConversation, NormalizedMessage,
+    ScanContext,
 };
 
 pub struct ClaudeCodeConnector;
@@ -21,11 +21,240 @@ impl ClaudeCodeConnector {
         Self
     }
 
-    fn projects_root() -> PathBuf {
+    /// `projects/` under the configured `claude_code.root` (or the root itself if it
+    /// is the projects dir), else ~/.claude/projects.
+    pub fn projects_root() -> PathBuf {
+        if let Some(root) = crate::config::connector_root("claude_code") {
+            let projects = root.join("projects");
+            return if projects.exists() { projects } else { root };
+        }
         dirs::home_dir()
             .unwrap_or_default()
             .join(".claude/projects")
     }
+
+    /// Parse one transcript; `None` when it holds no messages or is not valid JSON (then
+    /// it is quarantined).
+    fn parse_file(&self, ctx: &ScanContext, path: &Path) -> Result<Option<NormalizedConversation>> {
+        let plain = crate::connectors::uncompressed_path(path);
+        let ext = plain.extension().and_then(|s| s.to_str());
+        let content = crate::connectors::read_session_file(path)
+            .with_context(|| format!("read {}", path.display()))?;
+        let mut messages = Vec::new();
+        let mut started_at = None;
+        let mut ended_at = None;
+        // Track workspace from first entry's cwd field
+        let mut workspace: Option<PathBuf> = None;
+        let mut session_id: Option<String> = None;
+        let mut git_branch: Option<String> = None;
+        let format = TranscriptFormat::detect(ext, &content);
+
+        if ext == Some("jsonl") {
+            for val in ctx.jsonl_values("claude_code", path, &content) {
+                // Extract session metadata from first available entry
+                if workspace.is_none() {
+                    workspace = val.get("cwd").and_then(|v| v.as_str()).map(PathBuf::from);
+                }
+                if session_id.is_none() {
+                    session_id = val
+                        .get("sessionId")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+                }
+                if git_branch.is_none() {
+                    git_branch = val
+                        .get("gitBranch")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+                }
+
+                // Entries wrap the API message; flat lines are the message itself.
+                let message = match format {
+                    TranscriptFormat::Flat => Some(&val),
+                    _ => val.get("message"),
+                };
+                let entry_type = match format {
+                    TranscriptFormat::Flat => val.get("role"),
+                    _ => val.get("type"),
+                }
+                .and_then(|v| v.as_str());
+                // Filter to user/assistant entries only (skip summary, file-history-snapshot, etc.)
+                if !matches!(entry_type, Some("user" | "assistant")) {
+                    continue;
+                }
+
+                // Parse ISO-8601 timestamp using shared utility
+                let created = val
+                    .get("timestamp")
+                    .and_then(crate::connectors::parse_timestamp);
+
+                // NOTE: Do NOT filter individual messages by timestamp here!
+                // The file-level check in ctx.file_changed() is sufficient.
+                // Filtering messages would cause older messages to be lost when
+                // the file is re-indexed after new messages are added.
+
+                started_at = started_at.or(created);
+                ended_at = created.or(ended_at);
+
+                // Role from message.role or entry type
+                let role = message
+                    .and_then(|m| m.get("role"))
+                    .and_then(|v| v.as_str())
+                    .or(entry_type)
+                    .unwrap_or("agent");
+
+                // Content from message.content (may be string or array)
+                let content_val = message.and_then(|m| m.get("content"));
+                let content_str = content_val
+                    .map(crate::connectors::flatten_content)
+                    .unwrap_or_default();
+
+                // Skip entries with empty content
+                if content_str.trim().is_empty() {
+                    continue;
+                }
+
+                // Extract model name for author field
+                let author = message
+                    .and_then(|m| m.get("model"))
+                    .and_then(|v| v.as_str())
+                    .map(String::from);
+                let snippets = crate::connectors::extract_snippets(content_val, &content_str);
+
+                messages.push(NormalizedMessage {
+                    idx: 0, // will be re-assigned after filtering
+                    role: role.to_string(),
+                    author,
+                    created_at: created,
+                    content: content_str,
+                    extra: val,
+                    snippets,
+                });
+            }
+            // Re-assign sequential indices after filtering
+            for (i, msg) in messages.iter_mut().enumerate() {
+                msg.idx = i as i64;
+            }
+        } else {
+            // JSON or Claude format files
+            let val: Value = match serde_json::from_str(&content) {
+                Ok(v) => v,
+                Err(e) => {
+                    ctx.quarantine("claude_code", path, e);
+                    return Ok(None);
+                }
+            };
+            if let Some(arr) = val.get("messages").and_then(|m| m.as_array()) {
+                for item in arr {
+                    let role = item
+                        .get("role")
+                        .or_else(|| item.get("type"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("agent");
+
+                    // Use parse_timestamp for consistent handling of both i64 and ISO-8601
+                    let created = item
+                        .get("timestamp")
+                        .or_else(|| item.get("time"))
+                        .and_then(crate::connectors::parse_timestamp);
+
+                    // NOTE: Do NOT filter individual messages by timestamp.
+                    // File-level check is sufficient for incremental indexing.
+
+                    started_at = started_at.or(created);
+                    ended_at = created.or(ended_at);
+
+                    // Use flatten_content for consistent handling of both string and array content
+                    let content_val = item.get("content").or_else(|| item.get("text"));
+                    let content_str = content_val
+                        .map(crate::connectors::flatten_content)
+                        .unwrap_or_default();
+
+                    // Skip entries with empty content
+                    if content_str.trim().is_empty() {
+                        continue;
+                    }
+
+                    messages.push(NormalizedMessage {
+                        idx: 0, // will be re-assigned after filtering
+                        role: role.to_string(),
+                        author: None,
+                        created_at: created,
+                        snippets: crate::connectors::extract_snippets(content_val, &content_str),
+                        content: content_str,
+                        extra: item.clone(),
+                    });
+                }
+            }
+            // Re-assign sequential indices after filtering
+            for (i, msg) in messages.iter_mut().enumerate() {
+                msg.idx = i as i64;
+            }
+        }
+        if messages.is_empty() {
+            tracing::debug!(path = %path.display(), "claude_code no messages extracted");
+            return Ok(None);
+        }
+        tracing::debug!(path = %path.display(), messages = messages.len(), "claude_code extracted messages");
+
+        // Extract title from first user message, truncated to reasonable length
+        let title = if ext == Some("jsonl") {
+            messages
+                .iter()
+                .find(|m| m.role == "user")
+                .map(|m| {
+                    m.content
+                        .lines()
+                        .next()
+                        .unwrap_or(&m.content)
+                        .chars()
+                        .take(100)
+                        .collect::<String>()
+                })
+                .or_else(|| {
+                    // Fallback to workspace directory name
+                    workspace
+                        .as_ref()
+                        .and_then(|p| p.file_name())
+                        .and_then(|n| n.to_str())
+                        .map(String::from)
+                })
+        } else {
+            serde_json::from_str::<Value>(&content)
+                .ok()
+                .and_then(|v| {
+                    v.get("title")
+                        .and_then(|t| t.as_str())
+                        .map(std::string::ToString::to_string)
+                })
+                .or_else(|| {
+                    messages
+                        .first()
+                        .and_then(|m| m.content.lines().next())
+                        .map(|s| s.chars().take(100).collect())
+                })
+        };
+
+        Ok(Some(NormalizedConversation {
+            agent_slug: "claude_code".into(),
+            external_id: plain
+                .file_name()
+                .and_then(|s| s.to_str())
+                .map(std::string::ToString::to_string),
+            title,
+            workspace, // Now populated from cwd field!
+            source_path: path.to_path_buf(),
+            started_at,
+            ended_at,
+            metadata: serde_json::json!({
+                "source": "claude_code",
+                "sessionId": session_id,
+                "gitBranch": git_branch,
+                "format": format.name(),
+            }),
+            messages,
+        }))
+    }
 }
 
 impl Connector for ClaudeCodeConnector {
@@ -42,6 +271,10 @@ impl Connector for ClaudeCodeConnector {
     }
 
     fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        crate::connectors::collect_streaming(self, ctx)
+    }
+
+    fn scan_streaming(&self, ctx: &ScanContext, emit: &mut EmitConversation) -> Result<()> {
         // Use data_root only if it looks like a Claude projects directory (for testing)
         // Otherwise use the default projects_root
         let root = if ctx.data_root.join("projects").exists()
@@ -55,247 +288,71 @@ impl Connector for ClaudeCodeConnector {
             Self::projects_root()
         };
         if !root.exists() {
-            return Ok(Vec::new());
+            return Ok(());
         }
 
-        let mut convs = Vec::new();
-        let mut file_count = 0;
-        for entry in WalkDir::new(&root).into_iter().flatten() {
+        let mut files = Vec::new();
+        for entry in crate::connectors::walk(WalkDir::new(&root)) {
             if !entry.file_type().is_file() {
                 continue;
             }
-            let ext = entry.path().extension().and_then(|s| s.to_str());
+            let plain = crate::connectors::uncompressed_path(entry.path());
+            let ext = plain.extension().and_then(|s| s.to_str());
             if ext != Some("jsonl") && ext != Some("json") && ext != Some("claude") {
                 continue;
             }
             // Skip files not modified since last scan (incremental indexing)
-            if !crate::connectors::file_modified_since(entry.path(), ctx.since_ts) {
+            if !ctx.file_changed(entry.path()) {
                 continue;
             }
-            file_count += 1;
-            if file_count <= 3 {
+            if files.len() < 3 {
                 tracing::debug!(path = %entry.path().display(), "claude_code found file");
             }
-            let content = fs::read_to_string(entry.path())
-                .with_context(|| format!("read {}", entry.path().display()))?;
-            let mut messages = Vec::new();
-            let mut started_at = None;
-            let mut ended_at = None;
-            // Track workspace from first entry's cwd field
-            let mut workspace: Option<PathBuf> = None;
-            let mut session_id: Option<String> = None;
-            let mut git_branch: Option<String> = None;
-
-            if ext == Some("jsonl") {
-                for line in content.lines() {
-                    if line.trim().is_empty() {
-                        continue;
-                    }
-                    let val: Value = match serde_json::from_str(line) {
-                        Ok(v) => v,
-                        Err(_) => continue, // Skip malformed lines
-                    };
-
-                    // Extract session metadata from first available entry
-                    if workspace.is_none() {
-                        workspace = val.get("cwd").and_then(|v| v.as_str()).map(PathBuf::from);
-                    }
-                    if session_id.is_none() {
-                        session_id = val
-                            .get("sessionId")
-                            .and_then(|v| v.as_str())
-                            .map(String::from);
-                    }
-                    if git_branch.is_none() {
-                        git_branch = val
-                            .get("gitBranch")
-                            .and_then(|v| v.as_str())
-                            .map(String::from);
-                    }
-
-                    // Filter to user/assistant entries only (skip summary, file-history-snapshot, etc.)
-                    let entry_type = val.get("type").and_then(|v| v.as_str());
-                    if !matches!(entry_type, Some("user" | "assistant")) {
-                        continue;
-                    }
-
-                    // Parse ISO-8601 timestamp using shared utility
-                    let created = val
-                        .get("timestamp")
-                        .and_then(crate::connectors::parse_timestamp);
-
-                    // NOTE: Do NOT filter individual messages by timestamp here!
-                    // The file-level check in file_modified_since() is sufficient.
-                    // Filtering messages would cause older messages to be lost when
-                    // the file is re-indexed after new messages are added.
-
-                    started_at = started_at.or(created);
-                    ended_at = created.or(ended_at);
-
-                    // Role from message.role or entry type
-                    let role = val
-                        .get("message")
-                        .and_then(|m| m.get("role"))
-                        .and_then(|v| v.as_str())
-                        .or(entry_type)
-                        .unwrap_or("agent");
-
-                    // Content from message.content (may be string or array)
-                    let content_val = val.get("message").and_then(|m| m.get("content"));
-                    let content_str = content_val
-                        .map(crate::connectors::flatten_content)
-                        .unwrap_or_default();
-
-                    // Skip entries with empty content
-                    if content_str.trim().is_empty() {
-                        continue;
-                    }
-
-                    // Extract model name for author field
-                    let author = val
-                        .get("message")
-                        .and_then(|m| m.get("model"))
-                        .and_then(|v| v.as_str())
-                        .map(String::from);
-
-                    messages.push(NormalizedMessage {
-                        idx: 0, // will be re-assigned after filtering
-                        role: role.to_string(),
-                        author,
-                        created_at: created,
-                        content: content_str,
-                        extra: val,
-                        snippets: Vec::new(),
-                    });
-                }
-                // Re-assign sequential indices after filtering
-                for (i, msg) in messages.iter_mut().enumerate() {
-                    msg.idx = i as i64;
-                }
-            } else {
-                // JSON or Claude format files
-                let val: Value = match serde_json::from_str(&content) {
-                    Ok(v) => v,
-                    Err(e) => {
-                        tracing::debug!(path = %entry.path().display(), error = %e, "claude_code skipping malformed JSON");
-                        continue;
-                    }
-                };
-                if let Some(arr) = val.get("messages").and_then(|m| m.as_array()) {
-                    for item in arr {
-                        let role = item
-                            .get("role")
-                            .or_else(|| item.get("type"))
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("agent");
-
-                        // Use parse_timestamp for consistent handling of both i64 and ISO-8601
-                        let created = item
-                            .get("timestamp")
-                            .or_else(|| item.get("time"))
-                            .and_then(crate::connectors::parse_timestamp);
-
-                        // NOTE: Do NOT filter individual messages by timestamp.
-                        // File-level check is sufficient for incremental indexing.
-
-                        started_at = started_at.or(created);
-                        ended_at = created.or(ended_at);
-
-                        // Use flatten_content for consistent handling of both string and array content
-                        let content_str = item
-                            .get("content")
-                            .or_else(|| item.get("text"))
-                            .map(crate::connectors::flatten_content)
-                            .unwrap_or_default();
+            files.push(entry.into_path());
+        }
+        crate::connectors::parse_files(files, |path| self.parse_file(ctx, path), emit)
+    }
+}
 
-                        // Skip entries with empty content
-                        if content_str.trim().is_empty() {
-                            continue;
-                        }
+/// Generations of the transcript format, newest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TranscriptFormat {
+    /// `.jsonl` entries (`type`, `cwd`, `sessionId`, ...) wrapping an API `message`.
+    Entries,
+    /// Early `.jsonl`: the API messages themselves, one per line (`role`, `content`,
+    /// optional `model` and `timestamp`), with no entry envelope.
+    Flat,
+    /// `.json`/`.claude` exports: one document with a `messages` array.
+    Document,
+}
 
-                        messages.push(NormalizedMessage {
-                            idx: 0, // will be re-assigned after filtering
-                            role: role.to_string(),
-                            author: None,
-                            created_at: created,
-                            content: content_str,
-                            extra: item.clone(),
-                            snippets: Vec::new(),
-                        });
-                    }
-                }
-                // Re-assign sequential indices after filtering
-                for (i, msg) in messages.iter_mut().enumerate() {
-                    msg.idx = i as i64;
-                }
-            }
-            if messages.is_empty() {
-                if file_count <= 3 {
-                    tracing::debug!(path = %entry.path().display(), "claude_code no messages extracted");
+impl TranscriptFormat {
+    fn detect(ext: Option<&str>, content: &str) -> Self {
+        if ext != Some("jsonl") {
+            return Self::Document;
+        }
+        // Decide on the first line that is a message in either shape; summaries and
+        // snapshots at the top of a file say nothing about the generation.
+        content
+            .lines()
+            .filter_map(|l| serde_json::from_str::<Value>(l).ok())
+            .find_map(|v| {
+                if v.get("message").is_some() {
+                    Some(Self::Entries)
+                } else if v.get("role").is_some() && v.get("content").is_some() {
+                    Some(Self::Flat)
+                } else {
+                    None
                 }
-                continue;
-            }
-            tracing::debug!(path = %entry.path().display(), messages = messages.len(), "claude_code extracted messages");
-
-            // Extract title from first user message, truncated to reasonable length
-            let title = if ext == Some("jsonl") {
-                messages
-                    .iter()
-                    .find(|m| m.role == "user")
-                    .map(|m| {
-                        m.content
-                            .lines()
-                            .next()
-                            .unwrap_or(&m.content)
-                            .chars()
-                            .take(100)
-                            .collect::<String>()
-                    })
-                    .or_else(|| {
-                        // Fallback to workspace directory name
-                        workspace
-                            .as_ref()
-                            .and_then(|p| p.file_name())
-                            .and_then(|n| n.to_str())
-                            .map(String::from)
-                    })
-            } else {
-                serde_json::from_str::<Value>(&content)
-                    .ok()
-                    .and_then(|v| {
-                        v.get("title")
-                            .and_then(|t| t.as_str())
-                            .map(std::string::ToString::to_string)
-                    })
-                    .or_else(|| {
-                        messages
-                            .first()
-                            .and_then(|m| m.content.lines().next())
-                            .map(|s| s.chars().take(100).collect())
-                    })
-            };
+            })
+            .unwrap_or(Self::Entries)
+    }
 
-            convs.push(NormalizedConversation {
-                agent_slug: "claude_code".into(),
-                external_id: entry
-                    .path()
-                    .file_name()
-                    .and_then(|s| s.to_str())
-                    .map(std::string::ToString::to_string),
-                title,
-                workspace, // Now populated from cwd field!
-                source_path: entry.path().to_path_buf(),
-                started_at,
-                ended_at,
-                metadata: serde_json::json!({
-                    "source": "claude_code",
-                    "sessionId": session_id,
-                    "gitBranch": git_branch
-                }),
-                messages,
-            });
+    fn name(self) -> &'static str {
+        match self {
+            Self::Entries => "entries",
+            Self::Flat => "flat",
+            Self::Document => "document",
         }
-
-        Ok(convs)
     }
 }
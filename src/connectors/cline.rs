@@ -20,7 +20,11 @@ impl ClineConnector {
         Self
     }
 
-    fn storage_root() -> PathBuf {
+    /// The configured `cline.root`, else the VS Code extension storage dir.
+    pub fn storage_root() -> PathBuf {
+        if let Some(root) = crate::config::connector_root("cline") {
+            return root;
+        }
         let base = dirs::home_dir().unwrap_or_default();
         let linux = base.join(".config/Code/User/globalStorage/saoudrizwan.claude-dev");
         if linux.exists() {
@@ -102,7 +106,7 @@ impl Connector for ClineConnector {
             };
 
             // Skip files not modified since last scan (incremental indexing)
-            if !crate::connectors::file_modified_since(&file, ctx.since_ts) {
+            if !ctx.file_changed(&file) {
                 continue;
             }
 
@@ -111,7 +115,7 @@ impl Connector for ClineConnector {
             let val: Value = match serde_json::from_str(&data) {
                 Ok(v) => v,
                 Err(e) => {
-                    tracing::debug!(path = %file.display(), error = %e, "cline skipping malformed JSON");
+                    ctx.quarantine("cline", &file, e);
                     continue;
                 }
             };
@@ -127,7 +131,7 @@ impl Connector for ClineConnector {
                         .and_then(crate::connectors::parse_timestamp);
 
                     // NOTE: Do NOT filter individual messages by timestamp here!
-                    // The file-level check in file_modified_since() is sufficient.
+                    // The file-level check in ctx.file_changed() is sufficient.
                     // Filtering messages would cause older messages to be lost when
                     // the file is re-indexed after new messages are added.
 
@@ -1,4 +1,3 @@
-use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
@@ -6,7 +5,8 @@ use serde_json::Value;
 use walkdir::WalkDir;
 
 use crate::connectors::{
-    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    Connector, DetectionResult, EmitConversation, NormalizedConversation, NormalizedMessage,
+    ScanContext,
 };
 
 pub struct CodexConnector;
@@ -21,11 +21,10 @@ impl CodexConnector {
         Self
     }
 
-    fn home() -> PathBuf {
-        std::env::var("CODEX_HOME").map_or_else(
-            |_| dirs::home_dir().unwrap_or_default().join(".codex"),
-            PathBuf::from,
-        )
+    /// Checks CODEX_HOME env var, then the configured `codex.root`, falls back to ~/.codex/
+    pub fn home() -> PathBuf {
+        crate::config::env_root("codex", "CODEX_HOME")
+            .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".codex"))
     }
 
     fn rollout_files(root: &Path) -> Vec<PathBuf> {
@@ -34,10 +33,11 @@ impl CodexConnector {
         if !sessions.exists() {
             return out;
         }
-        for entry in WalkDir::new(sessions).into_iter().flatten() {
+        for entry in crate::connectors::walk(WalkDir::new(sessions)) {
             if entry.file_type().is_file() {
-                let name = entry.file_name().to_str().unwrap_or("");
-                // Match both modern .jsonl and legacy .json formats
+                let plain = crate::connectors::uncompressed_path(entry.path());
+                let name = plain.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                // Match both modern .jsonl and legacy .json formats, plain or archived
                 if name.starts_with("rollout-")
                     && (name.ends_with(".jsonl") || name.ends_with(".json"))
                 {
@@ -47,6 +47,266 @@ impl CodexConnector {
         }
         out
     }
+
+    /// Parse one rollout under `home`; `None` when it holds no messages or is not valid
+    /// JSON (then it is quarantined).
+    fn parse_file(
+        &self,
+        ctx: &ScanContext,
+        home: &Path,
+        file: &Path,
+    ) -> Result<Option<NormalizedConversation>> {
+        let source_path = file.to_path_buf();
+        // An archived rollout keeps the id and format of the file it was rotated from.
+        let plain = crate::connectors::uncompressed_path(file);
+        // Use relative path from sessions dir as external_id for uniqueness
+        // e.g., "2025/11/20/rollout-1" instead of just "rollout-1"
+        let sessions_dir = home.join("sessions");
+        let external_id = plain
+            .strip_prefix(&sessions_dir)
+            .ok()
+            .and_then(|rel| {
+                rel.with_extension("")
+                    .to_str()
+                    .map(std::string::ToString::to_string)
+            })
+            .or_else(|| {
+                plain
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .map(std::string::ToString::to_string)
+            });
+        let content = crate::connectors::read_session_file(file)
+            .with_context(|| format!("read rollout {}", file.display()))?;
+
+        let ext = plain.extension().and_then(|e| e.to_str());
+        let format = RolloutFormat::detect(ext, &content);
+        let mut messages: Vec<NormalizedMessage> = Vec::new();
+        let mut started_at = None;
+        let mut ended_at = None;
+        let mut session_cwd: Option<PathBuf> = None;
+
+        if ext == Some("jsonl") {
+            // Modern envelope format: each line has {type, timestamp, payload}
+            for val in ctx.jsonl_values("codex", file, &content) {
+                let val = match format {
+                    RolloutFormat::Flat => match lift_flat_line(val) {
+                        Some(v) => v,
+                        None => continue,
+                    },
+                    _ => val,
+                };
+
+                let entry_type = val.get("type").and_then(|v| v.as_str()).unwrap_or("");
+                let created = val
+                    .get("timestamp")
+                    .and_then(crate::connectors::parse_timestamp);
+
+                // NOTE: Do NOT filter individual messages by timestamp here!
+                // The file-level check in ctx.file_changed() is sufficient.
+                // Filtering messages would cause older messages to be lost when
+                // the file is re-indexed after new messages are added.
+
+                match entry_type {
+                    "session_meta" => {
+                        // Extract workspace from session metadata
+                        if let Some(payload) = val.get("payload") {
+                            session_cwd = payload
+                                .get("cwd")
+                                .and_then(|v| v.as_str())
+                                .map(PathBuf::from);
+                        }
+                        started_at = started_at.or(created);
+                    }
+                    "response_item" => {
+                        // Main message entries with nested payload
+                        if let Some(payload) = val.get("payload") {
+                            // Tool calls carry no text; attach them to the turn that made them.
+                            let tool_snippets = tool_call_snippets(payload);
+                            if !tool_snippets.is_empty() {
+                                if let Some(last) = messages.last_mut() {
+                                    last.snippets.extend(tool_snippets);
+                                }
+                                continue;
+                            }
+
+                            let role = payload
+                                .get("role")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("agent");
+
+                            let content_str = payload
+                                .get("content")
+                                .map(crate::connectors::flatten_content)
+                                .unwrap_or_default();
+
+                            if content_str.trim().is_empty() {
+                                continue;
+                            }
+
+                            started_at = started_at.or(created);
+                            ended_at = created.or(ended_at);
+
+                            messages.push(NormalizedMessage {
+                                idx: 0, // will be re-assigned after filtering
+                                role: role.to_string(),
+                                author: None,
+                                created_at: created,
+                                snippets: crate::connectors::code_block_snippets(&content_str),
+                                content: content_str,
+                                extra: val,
+                            });
+                        }
+                    }
+                    "event_msg" => {
+                        // Event messages - filter by payload type
+                        if let Some(payload) = val.get("payload") {
+                            let event_type = payload.get("type").and_then(|v| v.as_str());
+
+                            match event_type {
+                                Some("user_message") => {
+                                    let text = payload
+                                        .get("message")
+                                        .and_then(|v| v.as_str())
+                                        .unwrap_or("");
+                                    if !text.is_empty() {
+                                        ended_at = created.or(ended_at);
+                                        messages.push(NormalizedMessage {
+                                            idx: 0, // will be re-assigned after filtering
+                                            role: "user".to_string(),
+                                            author: None,
+                                            created_at: created,
+                                            snippets: crate::connectors::code_block_snippets(text),
+                                            content: text.to_string(),
+                                            extra: val,
+                                        });
+                                    }
+                                }
+                                Some("agent_reasoning") => {
+                                    // Include reasoning - valuable for search
+                                    let text =
+                                        payload.get("text").and_then(|v| v.as_str()).unwrap_or("");
+                                    if !text.is_empty() {
+                                        ended_at = created.or(ended_at);
+                                        messages.push(NormalizedMessage {
+                                            idx: 0, // will be re-assigned after filtering
+                                            role: "assistant".to_string(),
+                                            author: Some("reasoning".to_string()),
+                                            created_at: created,
+                                            content: text.to_string(),
+                                            extra: val,
+                                            snippets: Vec::new(),
+                                        });
+                                    }
+                                }
+                                _ => {} // Skip token_count, turn_aborted, etc.
+                            }
+                        }
+                    }
+                    _ => {} // Skip turn_context and unknown types
+                }
+            }
+            // Re-assign sequential indices after filtering
+            for (i, msg) in messages.iter_mut().enumerate() {
+                msg.idx = i as i64;
+            }
+        } else if ext == Some("json") {
+            // Legacy format: single JSON object with {session, items}
+            let val: Value = match serde_json::from_str(&content) {
+                Ok(v) => v,
+                Err(e) => {
+                    ctx.quarantine("codex", file, e);
+                    return Ok(None);
+                }
+            };
+
+            // Extract workspace from session.cwd
+            session_cwd = val
+                .get("session")
+                .and_then(|s| s.get("cwd"))
+                .and_then(|v| v.as_str())
+                .map(PathBuf::from);
+
+            // Parse items array
+            if let Some(items) = val.get("items").and_then(|v| v.as_array()) {
+                for item in items {
+                    let role = item.get("role").and_then(|v| v.as_str()).unwrap_or("agent");
+
+                    let content_str = item
+                        .get("content")
+                        .map(crate::connectors::flatten_content)
+                        .unwrap_or_default();
+
+                    if content_str.trim().is_empty() {
+                        continue;
+                    }
+
+                    let created = item
+                        .get("timestamp")
+                        .and_then(crate::connectors::parse_timestamp);
+
+                    // NOTE: Do NOT filter individual messages by timestamp.
+                    // File-level check is sufficient for incremental indexing.
+
+                    started_at = started_at.or(created);
+                    ended_at = created.or(ended_at);
+
+                    messages.push(NormalizedMessage {
+                        idx: 0, // will be re-assigned after filtering
+                        role: role.to_string(),
+                        author: None,
+                        created_at: created,
+                        snippets: crate::connectors::code_block_snippets(&content_str),
+                        content: content_str,
+                        extra: item.clone(),
+                    });
+                }
+            }
+            // Re-assign sequential indices after filtering
+            for (i, msg) in messages.iter_mut().enumerate() {
+                msg.idx = i as i64;
+            }
+        }
+
+        if messages.is_empty() {
+            return Ok(None);
+        }
+
+        // Extract title from first user message
+        let title = messages
+            .iter()
+            .find(|m| m.role == "user")
+            .map(|m| {
+                m.content
+                    .lines()
+                    .next()
+                    .unwrap_or(&m.content)
+                    .chars()
+                    .take(100)
+                    .collect::<String>()
+            })
+            .or_else(|| {
+                messages
+                    .first()
+                    .and_then(|m| m.content.lines().next())
+                    .map(|s| s.chars().take(100).collect())
+            });
+
+        Ok(Some(NormalizedConversation {
+            agent_slug: "codex".to_string(),
+            external_id,
+            title,
+            workspace: session_cwd, // Now populated from session_meta/session.cwd!
+            source_path,
+            started_at,
+            ended_at,
+            metadata: serde_json::json!({
+                "source": if ext == Some("json") { "rollout_json" } else { "rollout" },
+                "format": format.name(),
+            }),
+            messages,
+        }))
+    }
 }
 
 impl Connector for CodexConnector {
@@ -63,6 +323,10 @@ impl Connector for CodexConnector {
     }
 
     fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        crate::connectors::collect_streaming(self, ctx)
+    }
+
+    fn scan_streaming(&self, ctx: &ScanContext, emit: &mut EmitConversation) -> Result<()> {
         // Use data_root only if it IS a Codex home directory (for testing).
         // Check for `.codex` in path OR explicit directory name ending in "codex".
         // This avoids false positives from unrelated "sessions" directories.
@@ -76,246 +340,109 @@ impl Connector for CodexConnector {
         } else {
             Self::home()
         };
-        let files = Self::rollout_files(&home);
-        let mut convs = Vec::new();
-
-        for file in files {
-            let source_path = file.clone();
-            // Use relative path from sessions dir as external_id for uniqueness
-            // e.g., "2025/11/20/rollout-1" instead of just "rollout-1"
-            let sessions_dir = home.join("sessions");
-            let external_id = source_path
-                .strip_prefix(&sessions_dir)
-                .ok()
-                .and_then(|rel| {
-                    rel.with_extension("")
-                        .to_str()
-                        .map(std::string::ToString::to_string)
-                })
-                .or_else(|| {
-                    source_path
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .map(std::string::ToString::to_string)
-                });
-            let content = fs::read_to_string(&file)
-                .with_context(|| format!("read rollout {}", file.display()))?;
-
-            let ext = file.extension().and_then(|e| e.to_str());
-            let mut messages = Vec::new();
-            let mut started_at = None;
-            let mut ended_at = None;
-            let mut session_cwd: Option<PathBuf> = None;
-
-            if ext == Some("jsonl") {
-                // Modern envelope format: each line has {type, timestamp, payload}
-                for line in content.lines() {
-                    if line.trim().is_empty() {
-                        continue;
-                    }
-                    let val: Value = match serde_json::from_str(line) {
-                        Ok(v) => v,
-                        Err(_) => continue,
-                    };
-
-                    let entry_type = val.get("type").and_then(|v| v.as_str()).unwrap_or("");
-                    let created = val
-                        .get("timestamp")
-                        .and_then(crate::connectors::parse_timestamp);
-
-                    // NOTE: Do NOT filter individual messages by timestamp here!
-                    // The file-level check in file_modified_since() is sufficient.
-                    // Filtering messages would cause older messages to be lost when
-                    // the file is re-indexed after new messages are added.
-
-                    match entry_type {
-                        "session_meta" => {
-                            // Extract workspace from session metadata
-                            if let Some(payload) = val.get("payload") {
-                                session_cwd = payload
-                                    .get("cwd")
-                                    .and_then(|v| v.as_str())
-                                    .map(PathBuf::from);
-                            }
-                            started_at = started_at.or(created);
-                        }
-                        "response_item" => {
-                            // Main message entries with nested payload
-                            if let Some(payload) = val.get("payload") {
-                                let role = payload
-                                    .get("role")
-                                    .and_then(|v| v.as_str())
-                                    .unwrap_or("agent");
-
-                                let content_str = payload
-                                    .get("content")
-                                    .map(crate::connectors::flatten_content)
-                                    .unwrap_or_default();
-
-                                if content_str.trim().is_empty() {
-                                    continue;
-                                }
-
-                                started_at = started_at.or(created);
-                                ended_at = created.or(ended_at);
-
-                                messages.push(NormalizedMessage {
-                                    idx: 0, // will be re-assigned after filtering
-                                    role: role.to_string(),
-                                    author: None,
-                                    created_at: created,
-                                    content: content_str,
-                                    extra: val,
-                                    snippets: Vec::new(),
-                                });
-                            }
-                        }
-                        "event_msg" => {
-                            // Event messages - filter by payload type
-                            if let Some(payload) = val.get("payload") {
-                                let event_type = payload.get("type").and_then(|v| v.as_str());
-
-                                match event_type {
-                                    Some("user_message") => {
-                                        let text = payload
-                                            .get("message")
-                                            .and_then(|v| v.as_str())
-                                            .unwrap_or("");
-                                        if !text.is_empty() {
-                                            ended_at = created.or(ended_at);
-                                            messages.push(NormalizedMessage {
-                                                idx: 0, // will be re-assigned after filtering
-                                                role: "user".to_string(),
-                                                author: None,
-                                                created_at: created,
-                                                content: text.to_string(),
-                                                extra: val,
-                                                snippets: Vec::new(),
-                                            });
-                                        }
-                                    }
-                                    Some("agent_reasoning") => {
-                                        // Include reasoning - valuable for search
-                                        let text = payload
-                                            .get("text")
-                                            .and_then(|v| v.as_str())
-                                            .unwrap_or("");
-                                        if !text.is_empty() {
-                                            ended_at = created.or(ended_at);
-                                            messages.push(NormalizedMessage {
-                                                idx: 0, // will be re-assigned after filtering
-                                                role: "assistant".to_string(),
-                                                author: Some("reasoning".to_string()),
-                                                created_at: created,
-                                                content: text.to_string(),
-                                                extra: val,
-                                                snippets: Vec::new(),
-                                            });
-                                        }
-                                    }
-                                    _ => {} // Skip token_count, turn_aborted, etc.
-                                }
-                            }
-                        }
-                        _ => {} // Skip turn_context and unknown types
-                    }
-                }
-                // Re-assign sequential indices after filtering
-                for (i, msg) in messages.iter_mut().enumerate() {
-                    msg.idx = i as i64;
-                }
-            } else if ext == Some("json") {
-                // Legacy format: single JSON object with {session, items}
-                let val: Value = match serde_json::from_str(&content) {
-                    Ok(v) => v,
-                    Err(_) => continue,
-                };
-
-                // Extract workspace from session.cwd
-                session_cwd = val
-                    .get("session")
-                    .and_then(|s| s.get("cwd"))
-                    .and_then(|v| v.as_str())
-                    .map(PathBuf::from);
-
-                // Parse items array
-                if let Some(items) = val.get("items").and_then(|v| v.as_array()) {
-                    for item in items {
-                        let role = item.get("role").and_then(|v| v.as_str()).unwrap_or("agent");
-
-                        let content_str = item
-                            .get("content")
-                            .map(crate::connectors::flatten_content)
-                            .unwrap_or_default();
-
-                        if content_str.trim().is_empty() {
-                            continue;
-                        }
+        let files: Vec<_> = Self::rollout_files(&home)
+            .into_iter()
+            .filter(|file| ctx.file_changed(file))
+            .collect();
+        crate::connectors::parse_files(files, |file| self.parse_file(ctx, &home, file), emit)
+    }
+}
 
-                        let created = item
-                            .get("timestamp")
-                            .and_then(crate::connectors::parse_timestamp);
+/// Generations of the rollout file format, newest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RolloutFormat {
+    /// `.jsonl` of `{timestamp, type, payload}` envelopes.
+    Envelope,
+    /// Early `.jsonl`: a bare session header (`{id, timestamp, instructions}`), then
+    /// response items one per line without envelope or timestamp, interleaved with
+    /// `{"record_type": "state"}` markers.
+    Flat,
+    /// `.json`: a single `{session, items}` document.
+    Document,
+}
 
-                        // NOTE: Do NOT filter individual messages by timestamp.
-                        // File-level check is sufficient for incremental indexing.
+impl RolloutFormat {
+    fn detect(ext: Option<&str>, content: &str) -> Self {
+        if ext == Some("json") {
+            return Self::Document;
+        }
+        // Every envelope line carries a payload; the flat header never does.
+        let first = content
+            .lines()
+            .find_map(|l| serde_json::from_str::<Value>(l).ok());
+        match first {
+            Some(v) if v.get("payload").is_none() => Self::Flat,
+            _ => Self::Envelope,
+        }
+    }
 
-                        started_at = started_at.or(created);
-                        ended_at = created.or(ended_at);
-
-                        messages.push(NormalizedMessage {
-                            idx: 0, // will be re-assigned after filtering
-                            role: role.to_string(),
-                            author: None,
-                            created_at: created,
-                            content: content_str,
-                            extra: item.clone(),
-                            snippets: Vec::new(),
-                        });
-                    }
-                }
-                // Re-assign sequential indices after filtering
-                for (i, msg) in messages.iter_mut().enumerate() {
-                    msg.idx = i as i64;
-                }
-            }
+    fn name(self) -> &'static str {
+        match self {
+            Self::Envelope => "envelope",
+            Self::Flat => "flat",
+            Self::Document => "document",
+        }
+    }
+}
 
-            if messages.is_empty() {
-                continue;
-            }
+/// Wrap a line of a flat rollout in the envelope the parser reads; state markers
+/// yield `None`.
+fn lift_flat_line(val: Value) -> Option<Value> {
+    if val.get("record_type").is_some() {
+        return None;
+    }
+    if val.get("type").is_none() {
+        let timestamp = val.get("timestamp").cloned();
+        return Some(serde_json::json!({
+            "type": "session_meta",
+            "timestamp": timestamp,
+            "payload": val,
+        }));
+    }
+    Some(serde_json::json!({ "type": "response_item", "payload": val }))
+}
 
-            // Extract title from first user message
-            let title = messages
-                .iter()
-                .find(|m| m.role == "user")
-                .map(|m| {
-                    m.content
-                        .lines()
-                        .next()
-                        .unwrap_or(&m.content)
-                        .chars()
-                        .take(100)
-                        .collect::<String>()
+/// Snippets for a `function_call` (JSON `arguments`) or `custom_tool_call` (raw
+/// `input`, e.g. `apply_patch`) payload; one per file a patch touches.
+fn tool_call_snippets(payload: &Value) -> Vec<crate::connectors::NormalizedSnippet> {
+    let name = payload
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    match payload.get("type").and_then(|v| v.as_str()) {
+        Some("function_call") => {
+            let args = payload
+                .get("arguments")
+                .and_then(|v| v.as_str())
+                .and_then(|s| serde_json::from_str::<Value>(s).ok())
+                .unwrap_or(Value::Null);
+            vec![crate::connectors::tool_snippet(name, &args)]
+        }
+        Some("custom_tool_call") => {
+            let input = payload
+                .get("input")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let touched: Vec<_> = input
+                .lines()
+                .filter_map(|l| {
+                    ["*** Add File: ", "*** Update File: ", "*** Delete File: "]
+                        .iter()
+                        .find_map(|prefix| l.strip_prefix(prefix))
                 })
-                .or_else(|| {
-                    messages
-                        .first()
-                        .and_then(|m| m.content.lines().next())
-                        .map(|s| s.chars().take(100).collect())
-                });
-
-            convs.push(NormalizedConversation {
-                agent_slug: "codex".to_string(),
-                external_id,
-                title,
-                workspace: session_cwd, // Now populated from session_meta/session.cwd!
-                source_path: source_path.clone(),
-                started_at,
-                ended_at,
-                metadata: serde_json::json!({"source": if ext == Some("json") { "rollout_json" } else { "rollout" }}),
-                messages,
-            });
+                .map(|path| {
+                    crate::connectors::tool_snippet(
+                        name,
+                        &serde_json::json!({ "file_path": path.trim() }),
+                    )
+                })
+                .collect();
+            if touched.is_empty() {
+                vec![crate::connectors::tool_snippet(name, &Value::Null)]
+            } else {
+                touched
+            }
         }
-
-        Ok(convs)
+        _ => Vec::new(),
     }
 }
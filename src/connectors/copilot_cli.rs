@@ -0,0 +1,312 @@
+//! Connector for GitHub Copilot CLI (https://github.com/github/copilot-cli)
+//!
+//! Copilot CLI keeps session state under `~/.copilot/`:
+//! - `session-state/<session-id>.jsonl`: event log, one `{type, data, timestamp}`
+//!   object per line (`session.start`, `user.message`, `assistant.message`,
+//!   `tool.execution_complete`, ...)
+//! - `history-session-state/session_<id>_<ts>.json`: older releases wrote one JSON
+//!   document per session with `sessionId`, `startTime` and `chatMessages` in the
+//!   OpenAI chat format
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use walkdir::WalkDir;
+
+use crate::connectors::{
+    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    flatten_content, parse_timestamp,
+};
+
+pub struct CopilotCliConnector;
+
+impl Default for CopilotCliConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CopilotCliConnector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Get the Copilot CLI home directory.
+    /// Checks COPILOT_HOME env var, then the configured `copilot_cli.root`,
+    /// falls back to ~/.copilot/
+    pub fn home() -> PathBuf {
+        crate::config::env_root("copilot_cli", "COPILOT_HOME")
+            .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".copilot"))
+    }
+
+    /// Session files from both the event-log and legacy layouts.
+    fn session_files(root: &Path) -> Vec<PathBuf> {
+        let mut out = Vec::new();
+        for (dir, ext) in [
+            ("session-state", "jsonl"),
+            ("history-session-state", "json"),
+        ] {
+            let dir = root.join(dir);
+            if !dir.exists() {
+                continue;
+            }
+            for entry in crate::connectors::walk(WalkDir::new(dir)) {
+                if entry.file_type().is_file()
+                    && entry.path().extension().and_then(|e| e.to_str()) == Some(ext)
+                {
+                    out.push(entry.path().to_path_buf());
+                }
+            }
+        }
+        out.sort();
+        out
+    }
+
+    /// Render tool calls attached to an assistant turn so they are searchable.
+    fn tool_calls_text(calls: &Value) -> Vec<String> {
+        calls
+            .as_array()
+            .map(|arr| {
+                arr.iter()
+                    .map(|call| {
+                        let func = call.get("function").unwrap_or(call);
+                        let name = func
+                            .get("name")
+                            .or_else(|| call.get("toolName"))
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("unknown");
+                        let args = func
+                            .get("arguments")
+                            .map(|a| match a {
+                                Value::String(s) => s.clone(),
+                                other => other.to_string(),
+                            })
+                            .unwrap_or_default();
+                        if args.is_empty() {
+                            format!("[Tool: {name}]")
+                        } else {
+                            format!("[Tool: {name}] {args}")
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn push_message(
+        messages: &mut Vec<NormalizedMessage>,
+        role: &str,
+        created_at: Option<i64>,
+        content: String,
+        extra: Value,
+    ) {
+        if content.trim().is_empty() {
+            return;
+        }
+        messages.push(NormalizedMessage {
+            idx: messages.len() as i64,
+            role: role.to_string(),
+            author: None,
+            created_at,
+            content,
+            extra,
+            snippets: Vec::new(),
+        });
+    }
+
+    /// Parse a `session-state/*.jsonl` event log.
+    fn parse_event_log(
+        values: impl Iterator<Item = Value>,
+        session: &mut SessionInfo,
+    ) -> Vec<NormalizedMessage> {
+        let mut messages = Vec::new();
+        for val in values {
+            let created = val.get("timestamp").and_then(parse_timestamp);
+            let data = val.get("data").cloned().unwrap_or(Value::Null);
+            match val.get("type").and_then(|v| v.as_str()).unwrap_or("") {
+                "session.start" => {
+                    session.id = data
+                        .get("sessionId")
+                        .and_then(|v| v.as_str())
+                        .map(String::from)
+                        .or(session.id.take());
+                    session.cwd = data
+                        .get("context")
+                        .and_then(|c| c.get("cwd"))
+                        .or_else(|| data.get("cwd"))
+                        .and_then(|v| v.as_str())
+                        .map(PathBuf::from);
+                    session.model = data
+                        .get("selectedModel")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+                }
+                "session.model_change" => {
+                    session.model = data
+                        .get("newModel")
+                        .and_then(|v| v.as_str())
+                        .map(String::from)
+                        .or(session.model.take());
+                }
+                "user.message" => {
+                    let text = data.get("content").map(flatten_content).unwrap_or_default();
+                    Self::push_message(&mut messages, "user", created, text, val.clone());
+                }
+                "assistant.message" => {
+                    let mut parts =
+                        vec![data.get("content").map(flatten_content).unwrap_or_default()];
+                    if let Some(calls) = data.get("toolRequests") {
+                        parts.extend(Self::tool_calls_text(calls));
+                    }
+                    let text = parts
+                        .into_iter()
+                        .filter(|p| !p.trim().is_empty())
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    Self::push_message(&mut messages, "assistant", created, text, val.clone());
+                }
+                "tool.execution_complete" => {
+                    let text = data
+                        .get("result")
+                        .and_then(|r| r.get("content").or(Some(r)))
+                        .map(flatten_content)
+                        .unwrap_or_default();
+                    Self::push_message(&mut messages, "tool", created, text, val.clone());
+                }
+                _ => {}
+            }
+        }
+        messages
+    }
+
+    /// Parse a legacy `history-session-state/*.json` document.
+    fn parse_history_document(val: &Value, session: &mut SessionInfo) -> Vec<NormalizedMessage> {
+        session.id = val
+            .get("sessionId")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        let started = val.get("startTime").and_then(parse_timestamp);
+
+        let mut messages = Vec::new();
+        let Some(chat) = val.get("chatMessages").and_then(|v| v.as_array()) else {
+            return messages;
+        };
+        for msg in chat {
+            let role = msg
+                .get("role")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown");
+            let mut parts = vec![msg.get("content").map(flatten_content).unwrap_or_default()];
+            if let Some(calls) = msg.get("tool_calls") {
+                parts.extend(Self::tool_calls_text(calls));
+            }
+            let text = parts
+                .into_iter()
+                .filter(|p| !p.trim().is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            // The legacy format only records when the session started.
+            Self::push_message(&mut messages, role, started, text, msg.clone());
+        }
+        messages
+    }
+}
+
+#[derive(Default)]
+struct SessionInfo {
+    id: Option<String>,
+    cwd: Option<PathBuf>,
+    model: Option<String>,
+}
+
+impl Connector for CopilotCliConnector {
+    fn detect(&self) -> DetectionResult {
+        let home = Self::home();
+        let evidence: Vec<String> = ["session-state", "history-session-state"]
+            .iter()
+            .map(|d| home.join(d))
+            .filter(|p| p.exists())
+            .map(|p| format!("found {}", p.display()))
+            .collect();
+        if evidence.is_empty() {
+            DetectionResult::not_found()
+        } else {
+            DetectionResult {
+                detected: true,
+                evidence,
+            }
+        }
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        // Use data_root if it looks like a Copilot CLI home (for testing)
+        let looks_like_home = ctx.data_root.join("session-state").exists()
+            || ctx.data_root.join("history-session-state").exists();
+        let home = if looks_like_home {
+            ctx.data_root.clone()
+        } else {
+            Self::home()
+        };
+
+        let mut convs = Vec::new();
+        for file in Self::session_files(&home) {
+            if !ctx.file_changed(&file) {
+                continue;
+            }
+            let content = fs::read_to_string(&file)
+                .with_context(|| format!("read copilot session {}", file.display()))?;
+
+            let mut session = SessionInfo::default();
+            let messages = if file.extension().and_then(|e| e.to_str()) == Some("jsonl") {
+                Self::parse_event_log(
+                    ctx.jsonl_values("copilot_cli", &file, &content),
+                    &mut session,
+                )
+            } else {
+                match serde_json::from_str::<Value>(&content) {
+                    Ok(val) => Self::parse_history_document(&val, &mut session),
+                    Err(e) => {
+                        ctx.quarantine("copilot_cli", &file, e);
+                        continue;
+                    }
+                }
+            };
+            if messages.is_empty() {
+                continue;
+            }
+
+            let started_at = messages.iter().filter_map(|m| m.created_at).min();
+            let ended_at = messages.iter().filter_map(|m| m.created_at).max();
+            let title = messages
+                .iter()
+                .find(|m| m.role == "user")
+                .or_else(|| messages.first())
+                .and_then(|m| m.content.lines().next())
+                .map(|s| s.chars().take(100).collect());
+            let external_id = session
+                .id
+                .clone()
+                .or_else(|| file.file_stem().and_then(|s| s.to_str()).map(String::from));
+
+            convs.push(NormalizedConversation {
+                agent_slug: "copilot_cli".to_string(),
+                external_id,
+                title,
+                workspace: session.cwd,
+                source_path: file.clone(),
+                started_at,
+                ended_at,
+                metadata: serde_json::json!({
+                    "source": "copilot_cli",
+                    "session_id": session.id,
+                    "model": session.model,
+                }),
+                messages,
+            });
+        }
+
+        Ok(convs)
+    }
+}
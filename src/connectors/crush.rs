@@ -0,0 +1,270 @@
+//! Connector for Crush (https://github.com/charmbracelet/crush)
+//!
+//! Crush keeps a SQLite database per project at `<project>/.crush/crush.db`:
+//! - `sessions`: id, parent_session_id, title, created_at, updated_at (epoch seconds)
+//! - `messages`: id, session_id, role, parts (JSON array), model, created_at
+//!
+//! Message `parts` are `{type, data}` objects: `text`, `reasoning`, `tool_call`,
+//! `tool_result`, `finish`, ... The project a session ran in is the directory holding
+//! `.crush`. Projects are found from Crush's global `projects.json`
+//! (`~/.local/share/crush/`, or `CRUSH_GLOBAL_DATA`) plus a shallow walk of the
+//! current directory, like the aider connector.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde_json::Value;
+use walkdir::WalkDir;
+
+use crate::connectors::{
+    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+};
+
+const DATA_DIR: &str = ".crush";
+const DB_FILE: &str = "crush.db";
+
+pub struct CrushConnector;
+
+impl Default for CrushConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrushConnector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Crush's global data directory (holds `projects.json`).
+    /// Checks CRUSH_GLOBAL_DATA env var, then the configured `crush.root`,
+    /// falls back to ~/.local/share/crush/
+    pub fn global_dir() -> PathBuf {
+        crate::config::env_root("crush", "CRUSH_GLOBAL_DATA").unwrap_or_else(|| {
+            dirs::home_dir()
+                .unwrap_or_default()
+                .join(".local/share/crush")
+        })
+    }
+
+    /// Project databases listed in `projects.json`.
+    fn registered_dbs(global: &Path) -> Vec<PathBuf> {
+        let Some(val) = fs::read_to_string(global.join("projects.json"))
+            .ok()
+            .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+        else {
+            return Vec::new();
+        };
+        let projects = val
+            .get("projects")
+            .and_then(|p| p.as_array())
+            .cloned()
+            .unwrap_or_default();
+        projects
+            .iter()
+            .filter_map(|p| {
+                let root = PathBuf::from(p.get("path")?.as_str()?);
+                let data_dir = p
+                    .get("data_dir")
+                    .and_then(|d| d.as_str())
+                    .map_or_else(|| root.join(DATA_DIR), |d| root.join(d));
+                Some(data_dir.join(DB_FILE))
+            })
+            .filter(|db| db.exists())
+            .collect()
+    }
+
+    /// `.crush/crush.db` files under `roots` (limited depth to avoid wide scans).
+    fn find_dbs(roots: &[&Path]) -> Vec<PathBuf> {
+        let mut out = Vec::new();
+        for root in roots {
+            if !root.exists() {
+                continue;
+            }
+            for entry in crate::connectors::walk(WalkDir::new(root).max_depth(5))
+                .filter(|e| e.file_type().is_file() && e.file_name() == DB_FILE)
+            {
+                let in_data_dir = entry
+                    .path()
+                    .parent()
+                    .and_then(|p| p.file_name())
+                    .is_some_and(|n| n == DATA_DIR);
+                if in_data_dir {
+                    out.push(entry.path().to_path_buf());
+                }
+            }
+        }
+        out
+    }
+
+    /// Flatten Crush message parts to searchable text.
+    fn flatten_parts(parts: &Value) -> String {
+        let Some(arr) = parts.as_array() else {
+            return String::new();
+        };
+        arr.iter()
+            .filter_map(|part| {
+                let data = part.get("data")?;
+                let text = |key: &str| data.get(key).and_then(|v| v.as_str()).map(String::from);
+                match part.get("type").and_then(|t| t.as_str())? {
+                    "text" => text("text"),
+                    "reasoning" => text("thinking").map(|t| format!("[Thinking] {t}")),
+                    "tool_call" => {
+                        let name = text("name").unwrap_or_else(|| "unknown".into());
+                        Some(match text("input").filter(|i| !i.is_empty()) {
+                            Some(input) => format!("[Tool: {name}] {input}"),
+                            None => format!("[Tool: {name}]"),
+                        })
+                    }
+                    "tool_result" => text("content"),
+                    _ => None,
+                }
+            })
+            .filter(|s| !s.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn extract_from_db(db_path: &Path) -> Result<Vec<NormalizedConversation>> {
+        let conn = crate::connectors::sqlite::open_readonly(db_path)?;
+        let workspace = db_path
+            .parent()
+            .and_then(Path::parent)
+            .map(Path::to_path_buf);
+
+        let mut sessions = conn.prepare(
+            "SELECT id, parent_session_id, title, created_at, updated_at FROM sessions ORDER BY created_at",
+        )?;
+        let session_rows = sessions
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<i64>>(3)?,
+                    row.get::<_, Option<i64>>(4)?,
+                ))
+            })?
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let mut messages_stmt = conn.prepare(
+            "SELECT role, parts, model, created_at FROM messages WHERE session_id = ?1 ORDER BY created_at, rowid",
+        )?;
+
+        let mut convs = Vec::new();
+        for (id, parent_id, title, created_at, updated_at) in session_rows {
+            let rows = messages_stmt
+                .query_map([&id], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        row.get::<_, Option<i64>>(3)?,
+                    ))
+                })?
+                .flatten();
+
+            let mut messages = Vec::new();
+            for (role, parts, model, created) in rows {
+                let parts: Value = parts
+                    .and_then(|p| serde_json::from_str(&p).ok())
+                    .unwrap_or(Value::Null);
+                let content = Self::flatten_parts(&parts);
+                if content.trim().is_empty() {
+                    continue;
+                }
+                let author = if role == "assistant" {
+                    model.clone()
+                } else {
+                    None
+                };
+                messages.push(NormalizedMessage {
+                    idx: messages.len() as i64,
+                    role,
+                    author,
+                    created_at: created.map(|s| s * 1000),
+                    content,
+                    extra: serde_json::json!({ "parts": parts, "model": model }),
+                    snippets: Vec::new(),
+                });
+            }
+            if messages.is_empty() {
+                continue;
+            }
+
+            let title = title.filter(|t| !t.trim().is_empty()).or_else(|| {
+                messages
+                    .iter()
+                    .find(|m| m.role == "user")
+                    .and_then(|m| m.content.lines().next())
+                    .map(|s| s.chars().take(100).collect())
+            });
+
+            convs.push(NormalizedConversation {
+                agent_slug: "crush".to_string(),
+                external_id: Some(id.clone()),
+                title,
+                workspace: workspace.clone(),
+                source_path: db_path.to_path_buf(),
+                started_at: created_at.map(|s| s * 1000),
+                ended_at: updated_at.map(|s| s * 1000),
+                metadata: serde_json::json!({
+                    "source": "crush",
+                    "session_id": id,
+                    "parent_session_id": parent_id,
+                }),
+                messages,
+            });
+        }
+        Ok(convs)
+    }
+}
+
+impl Connector for CrushConnector {
+    fn detect(&self) -> DetectionResult {
+        let mut evidence: Vec<String> = Self::registered_dbs(&Self::global_dir())
+            .iter()
+            .map(|p| format!("found {}", p.display()))
+            .collect();
+        let cwd_db = std::env::current_dir()
+            .unwrap_or_default()
+            .join(DATA_DIR)
+            .join(DB_FILE);
+        if cwd_db.exists() {
+            evidence.push(format!("found {}", cwd_db.display()));
+        }
+        if evidence.is_empty() {
+            DetectionResult::not_found()
+        } else {
+            DetectionResult {
+                detected: true,
+                evidence,
+            }
+        }
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let mut dbs = Self::registered_dbs(&Self::global_dir());
+        dbs.extend(Self::find_dbs(&[ctx.data_root.as_path(), cwd.as_path()]));
+        dbs.sort();
+        dbs.dedup();
+
+        let mut convs = Vec::new();
+        for db in dbs {
+            // Crush runs SQLite in WAL mode; recent writes may only touch the -wal file.
+            let wal = db.with_file_name(format!("{DB_FILE}-wal"));
+            // Non-short-circuit `|` so both files get checkpointed.
+            if !(ctx.file_changed(&db) | (wal.exists() && ctx.file_changed(&wal))) {
+                continue;
+            }
+            match Self::extract_from_db(&db) {
+                Ok(found) => convs.extend(found),
+                Err(e) => tracing::warn!(path = %db.display(), error = %e, "skipping crush db"),
+            }
+        }
+        Ok(convs)
+    }
+}
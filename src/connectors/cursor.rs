@@ -15,8 +15,7 @@
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
-use rusqlite::Connection;
+use anyhow::Result;
 use serde_json::Value;
 use walkdir::WalkDir;
 
@@ -39,6 +38,9 @@ impl CursorConnector {
 
     /// Get the base Cursor application support directory
     pub fn app_support_dir() -> Option<PathBuf> {
+        if let Some(root) = crate::config::connector_root("cursor") {
+            return Some(root);
+        }
         #[cfg(target_os = "macos")]
         {
             dirs::home_dir().map(|h| h.join("Library/Application Support/Cursor/User"))
@@ -70,11 +72,7 @@ impl CursorConnector {
         // Check workspaceStorage subdirectories
         let workspace_storage = base.join("workspaceStorage");
         if workspace_storage.exists() {
-            for entry in WalkDir::new(&workspace_storage)
-                .max_depth(2)
-                .into_iter()
-                .flatten()
-            {
+            for entry in crate::connectors::walk(WalkDir::new(&workspace_storage).max_depth(2)) {
                 if entry.file_type().is_file() && entry.file_name().to_str() == Some("state.vscdb")
                 {
                     dbs.push(entry.path().to_path_buf());
@@ -90,11 +88,7 @@ impl CursorConnector {
         db_path: &Path,
         since_ts: Option<i64>,
     ) -> Result<Vec<NormalizedConversation>> {
-        let conn = Connection::open_with_flags(
-            db_path,
-            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
-        )
-        .with_context(|| format!("failed to open Cursor db: {}", db_path.display()))?;
+        let conn = crate::connectors::sqlite::open_readonly(db_path)?;
 
         let mut convs = Vec::new();
         let mut seen_ids = HashSet::new();
@@ -169,7 +163,7 @@ impl CursorConnector {
         let created_at = val.get("createdAt").and_then(|v| v.as_i64());
 
         // NOTE: Do NOT filter conversations/messages by timestamp here!
-        // The file-level check in file_modified_since() is sufficient.
+        // The file-level check in ctx.file_changed() is sufficient.
         // Filtering would cause data loss when the file is re-indexed.
 
         let mut messages = Vec::new();
@@ -431,7 +425,7 @@ impl Connector for CursorConnector {
 
         for db_path in db_files {
             // Skip files not modified since last scan
-            if !crate::connectors::file_modified_since(&db_path, ctx.since_ts) {
+            if !ctx.file_changed(&db_path) {
                 continue;
             }
 
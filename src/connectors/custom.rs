@@ -0,0 +1,502 @@
+//! User-defined connectors driven by mapping files.
+//!
+//! Each `*.toml` or `*.json` file in `~/.config/cass/connectors/` (or the file/dir in
+//! `CASS_CUSTOM_CONNECTORS`) describes one agent cass has no built-in connector for:
+//!
+//! ```toml
+//! slug = "myagent"                       # agent name shown in results
+//! files = "~/.myagent/sessions/**/*.json" # glob, relative to the mapping file
+//! format = "json"                        # "json": one conversation per file
+//!                                        # "jsonl": one message record per line
+//! messages = "$.history[*]"              # json only: where the message records are
+//! role = "$.author.role"                 # the rest are relative to a message record
+//! content = "$.text | $.content"         # `|` tries alternatives in order
+//! timestamp = "$.ts"
+//! timestamp_unit = "s"                   # "ms" (default) or "s" for numeric timestamps
+//! title = "$.title"                      # relative to the whole document
+//! id = "$.id"
+//! workspace = "$.cwd"
+//! filter = { path = "$.type", equals = "message" }  # keep only matching records
+//! role_map = { human = "user", ai = "assistant" }
+//! ```
+//!
+//! Selectors are a JSONPath subset: `$`, `.key`, `["key"]`, `[n]` (negative counts
+//! from the end) and `[*]`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::connectors::{
+    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    flatten_content, parse_timestamp,
+};
+
+/// Where mapping files live unless `CASS_CUSTOM_CONNECTORS` says otherwise:
+/// the configured `custom.root`, else `connectors/` next to the config file.
+pub fn mappings_dir() -> PathBuf {
+    crate::config::connector_root("custom")
+        .unwrap_or_else(|| crate::config::config_dir().join("connectors"))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MappingFormat {
+    #[default]
+    Json,
+    Jsonl,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum TimestampUnit {
+    #[default]
+    #[serde(rename = "ms")]
+    Millis,
+    #[serde(rename = "s")]
+    Seconds,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecordFilter {
+    pub path: String,
+    pub equals: Value,
+}
+
+/// A mapping file as written by the user.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MappingSpec {
+    pub slug: String,
+    pub files: String,
+    #[serde(default)]
+    pub format: MappingFormat,
+    pub messages: Option<String>,
+    pub role: String,
+    pub content: String,
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub timestamp_unit: TimestampUnit,
+    pub title: Option<String>,
+    pub id: Option<String>,
+    pub workspace: Option<String>,
+    pub filter: Option<RecordFilter>,
+    #[serde(default)]
+    pub role_map: HashMap<String, String>,
+}
+
+/// One step of a compiled selector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    Key(String),
+    Index(i64),
+    Wildcard,
+}
+
+/// A compiled selector: alternatives separated by `|`, each a path of steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    alternatives: Vec<Vec<Step>>,
+}
+
+impl Selector {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let alternatives = expr
+            .split('|')
+            .map(|alt| parse_path(alt.trim()))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("invalid selector `{expr}`"))?;
+        Ok(Self { alternatives })
+    }
+
+    /// All values the first matching alternative selects.
+    pub fn select<'a>(&self, root: &'a Value) -> Vec<&'a Value> {
+        for steps in &self.alternatives {
+            let mut current = vec![root];
+            for step in steps {
+                current = current
+                    .into_iter()
+                    .flat_map(|v| apply_step(v, step))
+                    .collect();
+            }
+            current.retain(|v| !v.is_null());
+            if !current.is_empty() {
+                return current;
+            }
+        }
+        Vec::new()
+    }
+
+    /// The first selected value.
+    pub fn first<'a>(&self, root: &'a Value) -> Option<&'a Value> {
+        self.select(root).into_iter().next()
+    }
+}
+
+fn parse_path(expr: &str) -> Result<Vec<Step>> {
+    let mut rest = expr
+        .strip_prefix('$')
+        .ok_or_else(|| anyhow!("selector must start with `$`"))?;
+    let mut steps = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            let key = &after[..end];
+            if key.is_empty() {
+                bail!("empty key after `.`");
+            }
+            steps.push(if key == "*" {
+                Step::Wildcard
+            } else {
+                Step::Key(key.to_string())
+            });
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(|| anyhow!("unclosed `[`"))?;
+            let inner = after[..end].trim();
+            let step = if inner == "*" {
+                Step::Wildcard
+            } else if let Some(quoted) = inner
+                .strip_prefix('"')
+                .and_then(|s| s.strip_suffix('"'))
+                .or_else(|| inner.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
+            {
+                Step::Key(quoted.to_string())
+            } else {
+                Step::Index(inner.parse().map_err(|_| anyhow!("bad index `{inner}`"))?)
+            };
+            steps.push(step);
+            rest = &after[end + 1..];
+        } else {
+            bail!("unexpected `{rest}`");
+        }
+    }
+    Ok(steps)
+}
+
+fn apply_step<'a>(value: &'a Value, step: &Step) -> Vec<&'a Value> {
+    match (step, value) {
+        (Step::Key(key), Value::Object(map)) => map.get(key).into_iter().collect(),
+        (Step::Index(idx), Value::Array(items)) => {
+            let idx = if *idx < 0 {
+                items.len() as i64 + idx
+            } else {
+                *idx
+            };
+            usize::try_from(idx)
+                .ok()
+                .and_then(|i| items.get(i))
+                .into_iter()
+                .collect()
+        }
+        (Step::Wildcard, Value::Array(items)) => items.iter().collect(),
+        (Step::Wildcard, Value::Object(map)) => map.values().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A validated mapping, ready to scan.
+#[derive(Debug, Clone)]
+pub struct Mapping {
+    pub spec: MappingSpec,
+    /// Glob pattern with `~` and relative paths resolved.
+    pattern: String,
+    messages: Option<Selector>,
+    role: Selector,
+    content: Selector,
+    timestamp: Option<Selector>,
+    title: Option<Selector>,
+    id: Option<Selector>,
+    workspace: Option<Selector>,
+    filter: Option<(Selector, Value)>,
+}
+
+impl Mapping {
+    /// Load a mapping from a `.toml` or `.json` file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            fs::read_to_string(path).with_context(|| format!("read mapping {}", path.display()))?;
+        let spec: MappingSpec = if path.extension().is_some_and(|e| e == "json") {
+            serde_json::from_str(&content)?
+        } else {
+            toml::from_str(&content)?
+        };
+        Self::compile(spec, path.parent().unwrap_or(Path::new(".")))
+            .with_context(|| format!("invalid mapping {}", path.display()))
+    }
+
+    /// Validate a spec, resolving relative globs against `base`.
+    pub fn compile(spec: MappingSpec, base: &Path) -> Result<Self> {
+        if spec.slug.trim().is_empty() {
+            bail!("`slug` must not be empty");
+        }
+        if spec.format == MappingFormat::Json && spec.messages.is_none() {
+            bail!("`messages` is required for format = \"json\"");
+        }
+        let parse_opt = |s: &Option<String>| s.as_deref().map(Selector::parse).transpose();
+        let pattern = if let Some(rest) = spec.files.strip_prefix("~/") {
+            dirs::home_dir().unwrap_or_default().join(rest)
+        } else {
+            base.join(&spec.files)
+        };
+        Ok(Self {
+            pattern: pattern.to_string_lossy().into_owned(),
+            messages: parse_opt(&spec.messages)?,
+            role: Selector::parse(&spec.role)?,
+            content: Selector::parse(&spec.content)?,
+            timestamp: parse_opt(&spec.timestamp)?,
+            title: parse_opt(&spec.title)?,
+            id: parse_opt(&spec.id)?,
+            workspace: parse_opt(&spec.workspace)?,
+            filter: spec
+                .filter
+                .as_ref()
+                .map(|f| Ok::<_, anyhow::Error>((Selector::parse(&f.path)?, f.equals.clone())))
+                .transpose()?,
+            spec,
+        })
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        match glob::glob(&self.pattern) {
+            Ok(paths) => {
+                let mut out: Vec<PathBuf> = paths.flatten().filter(|p| p.is_file()).collect();
+                out.sort();
+                out
+            }
+            Err(e) => {
+                tracing::warn!(slug = %self.spec.slug, error = %e, "bad glob in custom mapping");
+                Vec::new()
+            }
+        }
+    }
+
+    fn string_at(selector: Option<&Selector>, root: &Value) -> Option<String> {
+        selector?.first(root).map(|v| match v {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    }
+
+    fn timestamp_at(&self, record: &Value) -> Option<i64> {
+        let raw = self.timestamp.as_ref()?.first(record)?;
+        let ts = parse_timestamp(raw).or_else(|| raw.as_f64().map(|f| f as i64))?;
+        let numeric = raw.is_number();
+        Some(match self.spec.timestamp_unit {
+            TimestampUnit::Seconds if numeric => raw.as_f64().map_or(ts, |f| (f * 1000.0) as i64),
+            _ => ts,
+        })
+    }
+
+    fn message_from(&self, record: &Value, idx: usize) -> Option<NormalizedMessage> {
+        if let Some((path, expected)) = &self.filter
+            && path.first(record) != Some(expected)
+        {
+            return None;
+        }
+        let content = self.content.first(record).map(flatten_content)?;
+        if content.trim().is_empty() {
+            return None;
+        }
+        let raw_role =
+            Self::string_at(Some(&self.role), record).unwrap_or_else(|| "unknown".into());
+        let role = self
+            .spec
+            .role_map
+            .get(&raw_role)
+            .cloned()
+            .unwrap_or(raw_role);
+        Some(NormalizedMessage {
+            idx: idx as i64,
+            role,
+            author: None,
+            created_at: self.timestamp_at(record),
+            content,
+            extra: record.clone(),
+            snippets: Vec::new(),
+        })
+    }
+
+    /// Turn one matched file into a conversation.
+    pub fn conversation(&self, path: &Path) -> Result<Option<NormalizedConversation>> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("read {} session {}", self.spec.slug, path.display()))?;
+
+        let (doc, records): (Value, Vec<Value>) = match self.spec.format {
+            MappingFormat::Json => {
+                let Ok(doc) = serde_json::from_str::<Value>(&content) else {
+                    return Ok(None);
+                };
+                let records = self
+                    .messages
+                    .as_ref()
+                    .map(|s| s.select(&doc).into_iter().cloned().collect())
+                    .unwrap_or_default();
+                (doc, records)
+            }
+            MappingFormat::Jsonl => {
+                let records: Vec<Value> = content
+                    .lines()
+                    .filter(|l| !l.trim().is_empty())
+                    .filter_map(|l| serde_json::from_str(l).ok())
+                    .collect();
+                // Document-level fields come from the first record (often a header).
+                (records.first().cloned().unwrap_or(Value::Null), records)
+            }
+        };
+
+        let mut messages = Vec::new();
+        for record in &records {
+            if let Some(msg) = self.message_from(record, messages.len()) {
+                messages.push(msg);
+            }
+        }
+        if messages.is_empty() {
+            return Ok(None);
+        }
+
+        let title = Self::string_at(self.title.as_ref(), &doc).or_else(|| {
+            messages
+                .iter()
+                .find(|m| m.role == "user")
+                .and_then(|m| m.content.lines().next())
+                .map(|s| s.chars().take(100).collect())
+        });
+        let external_id = Self::string_at(self.id.as_ref(), &doc)
+            .or_else(|| path.file_stem().and_then(|s| s.to_str()).map(String::from));
+
+        Ok(Some(NormalizedConversation {
+            agent_slug: self.spec.slug.clone(),
+            external_id,
+            title,
+            workspace: Self::string_at(self.workspace.as_ref(), &doc).map(PathBuf::from),
+            source_path: path.to_path_buf(),
+            started_at: messages.iter().filter_map(|m| m.created_at).min(),
+            ended_at: messages.iter().filter_map(|m| m.created_at).max(),
+            metadata: serde_json::json!({ "source": "custom", "mapping": self.spec.slug }),
+            messages,
+        }))
+    }
+}
+
+pub struct CustomConnector {
+    mappings: Vec<Mapping>,
+}
+
+impl Default for CustomConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CustomConnector {
+    /// Load every mapping from `CASS_CUSTOM_CONNECTORS` or the default directory.
+    /// Broken mappings are logged and skipped.
+    pub fn new() -> Self {
+        let location =
+            std::env::var_os("CASS_CUSTOM_CONNECTORS").map_or_else(mappings_dir, PathBuf::from);
+        let files = if location.is_file() {
+            vec![location]
+        } else {
+            fs::read_dir(&location)
+                .map(|entries| {
+                    let mut files: Vec<PathBuf> = entries
+                        .flatten()
+                        .map(|e| e.path())
+                        .filter(|p| p.extension().is_some_and(|e| e == "toml" || e == "json"))
+                        .collect();
+                    files.sort();
+                    files
+                })
+                .unwrap_or_default()
+        };
+        let mappings = files
+            .iter()
+            .filter_map(|path| match Mapping::load(path) {
+                Ok(m) => Some(m),
+                Err(e) => {
+                    tracing::warn!(path = %path.display(), error = %format!("{e:#}"), "skipping custom connector mapping");
+                    None
+                }
+            })
+            .collect();
+        Self { mappings }
+    }
+
+    pub fn with_mappings(mappings: Vec<Mapping>) -> Self {
+        Self { mappings }
+    }
+}
+
+impl Connector for CustomConnector {
+    fn detect(&self) -> DetectionResult {
+        if self.mappings.is_empty() {
+            return DetectionResult::not_found();
+        }
+        DetectionResult {
+            detected: true,
+            evidence: self
+                .mappings
+                .iter()
+                .map(|m| format!("custom mapping `{}` ({})", m.spec.slug, m.pattern))
+                .collect(),
+        }
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        let mut convs = Vec::new();
+        for mapping in &self.mappings {
+            for file in mapping.files() {
+                if !ctx.file_changed(&file) {
+                    continue;
+                }
+                if let Some(conv) = mapping.conversation(&file)? {
+                    convs.push(conv);
+                }
+            }
+        }
+        Ok(convs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn selectors_walk_keys_indexes_and_wildcards() {
+        let doc = json!({
+            "a": {"b c": [1, 2, 3]},
+            "items": [{"x": "one"}, {"y": "two"}, {"x": "three"}]
+        });
+        let sel = |s: &str| Selector::parse(s).unwrap();
+        assert_eq!(sel("$.a[\"b c\"][-1]").first(&doc), Some(&json!(3)));
+        assert_eq!(sel("$.items[*].x").select(&doc).len(), 2);
+        assert_eq!(
+            sel("$.items[1].x | $.items[1].y").first(&doc),
+            Some(&json!("two"))
+        );
+        assert_eq!(sel("$").first(&doc), Some(&doc));
+        assert!(sel("$.missing").first(&doc).is_none());
+    }
+
+    #[test]
+    fn selector_errors_are_reported() {
+        assert!(Selector::parse("items").is_err());
+        assert!(Selector::parse("$.a[").is_err());
+        assert!(Selector::parse("$.a[x]").is_err());
+        assert!(Selector::parse("$..a").is_err());
+    }
+
+    #[test]
+    fn json_format_requires_messages_selector() {
+        let spec: MappingSpec =
+            toml::from_str("slug = \"x\"\nfiles = \"*.json\"\nrole = \"$.r\"\ncontent = \"$.c\"")
+                .unwrap();
+        assert!(Mapping::compile(spec, Path::new(".")).is_err());
+    }
+}
@@ -122,7 +122,16 @@ fn extract_path_from_position(content: &str, start: usize) -> Option<PathBuf> {
     Some(path)
 }
 
-pub struct GeminiConnector;
+/// Reads Gemini CLI chats, and those of forks that kept its on-disk layout.
+///
+/// Each flavor differs only in where it keeps `tmp/<hash>/chats` and which slug the
+/// sessions are indexed under.
+pub struct GeminiConnector {
+    slug: &'static str,
+    home_env: &'static str,
+    default_root: &'static str,
+}
+
 impl Default for GeminiConnector {
     fn default() -> Self {
         Self::new()
@@ -130,22 +139,34 @@ impl Default for GeminiConnector {
 }
 
 impl GeminiConnector {
+    /// Gemini CLI: `~/.gemini/tmp`, overridable with GEMINI_HOME.
     pub fn new() -> Self {
-        Self
+        Self {
+            slug: "gemini",
+            home_env: "GEMINI_HOME",
+            default_root: ".gemini/tmp",
+        }
+    }
+
+    /// Qwen Code (a Gemini CLI fork): `~/.qwen/tmp`, overridable with QWEN_HOME.
+    pub fn qwen() -> Self {
+        Self {
+            slug: "qwen",
+            home_env: "QWEN_HOME",
+            default_root: ".qwen/tmp",
+        }
     }
 
-    fn root() -> PathBuf {
-        std::env::var("GEMINI_HOME").map_or_else(
-            |_| dirs::home_dir().unwrap_or_default().join(".gemini/tmp"),
-            PathBuf::from,
-        )
+    pub fn root(&self) -> PathBuf {
+        crate::config::env_root(self.slug, self.home_env)
+            .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(self.default_root))
     }
 
     /// Find all session JSON files in the Gemini structure.
     /// Structure: ~/.gemini/tmp/<hash>/chats/session-*.json
     fn session_files(root: &Path) -> Vec<PathBuf> {
         let mut files = Vec::new();
-        for entry in WalkDir::new(root).into_iter().flatten() {
+        for entry in crate::connectors::walk(WalkDir::new(root)) {
             if !entry.file_type().is_file() {
                 continue;
             }
@@ -166,11 +187,241 @@ impl GeminiConnector {
         }
         files
     }
+
+    /// `checkpoint-<tag>.json` chats saved with `/chat save` by releases that predate
+    /// session recording. Project dirs that have a `chats/` dir are skipped: their
+    /// checkpoints repeat what the session files already hold.
+    fn legacy_checkpoints(root: &Path) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for entry in crate::connectors::walk(WalkDir::new(root).max_depth(2)) {
+            let path = entry.path();
+            let name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+            if entry.file_type().is_file()
+                && name.starts_with("checkpoint")
+                && name.ends_with(".json")
+                && path.parent().is_some_and(|p| !p.join("chats").exists())
+            {
+                files.push(path.to_path_buf());
+            }
+        }
+        files
+    }
+
+    /// Parse one chat file; `None` when it holds no messages or is not a chat (invalid
+    /// JSON is quarantined).
+    fn parse_file(&self, ctx: &ScanContext, file: &Path) -> Result<Option<NormalizedConversation>> {
+        let content =
+            fs::read_to_string(file).with_context(|| format!("read session {}", file.display()))?;
+
+        let val: Value = match serde_json::from_str(&content) {
+            Ok(v) => v,
+            Err(e) => {
+                ctx.quarantine(self.slug, file, e);
+                return Ok(None);
+            }
+        };
+        let Some(format) = ChatFormat::detect(&val) else {
+            return Ok(None);
+        };
+
+        // Extract session metadata
+        let session_id = val
+            .get("sessionId")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        // Checkpoints sit directly in the project's hash dir.
+        let project_hash = val
+            .get("projectHash")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .or_else(|| {
+                (format == ChatFormat::Checkpoint)
+                    .then(|| file.parent()?.file_name()?.to_str().map(String::from))
+                    .flatten()
+            });
+
+        // Parse session-level timestamps
+        let start_time = val
+            .get("startTime")
+            .and_then(crate::connectors::parse_timestamp);
+        // Checkpoints carry no timestamps; the save time is the best we have.
+        let last_updated = val
+            .get("lastUpdated")
+            .and_then(crate::connectors::parse_timestamp)
+            .or_else(|| {
+                (format == ChatFormat::Checkpoint)
+                    .then(|| mtime_millis(file))
+                    .flatten()
+            });
+
+        let Some(messages_arr) = val
+            .get("messages")
+            .and_then(|m| m.as_array())
+            .or_else(|| val.as_array())
+        else {
+            return Ok(None);
+        };
+
+        let mut messages = Vec::new();
+        let mut started_at = match format {
+            ChatFormat::Session => start_time,
+            ChatFormat::Checkpoint => last_updated,
+        };
+        let mut ended_at = last_updated;
+
+        for item in messages_arr {
+            // Role from "type" field (sessions) or "role" (checkpoints) - Gemini uses
+            // "user" and "model"
+            let msg_type = item
+                .get("type")
+                .or_else(|| item.get("role"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("model");
+            let role = if msg_type == "model" {
+                "assistant"
+            } else {
+                msg_type
+            };
+
+            // Parse timestamp using shared utility
+            let created = item
+                .get("timestamp")
+                .and_then(crate::connectors::parse_timestamp);
+
+            // NOTE: Do NOT filter individual messages by timestamp here!
+            // The file-level check in ctx.file_changed() is sufficient.
+            // Filtering messages would cause older messages to be lost when
+            // the file is re-indexed after new messages are added.
+
+            started_at = started_at.or(created);
+            ended_at = created.or(ended_at);
+
+            // Extract content using flatten_content for consistency
+            let content_str = item
+                .get("content")
+                .or_else(|| item.get("parts"))
+                .map(crate::connectors::flatten_content)
+                .unwrap_or_default();
+
+            // Skip entries with empty content
+            if content_str.trim().is_empty() {
+                continue;
+            }
+
+            messages.push(NormalizedMessage {
+                idx: 0, // will be re-assigned after filtering
+                role: role.to_string(),
+                author: None,
+                created_at: created,
+                content: content_str,
+                extra: item.clone(),
+                snippets: Vec::new(),
+            });
+        }
+
+        // Re-assign sequential indices after filtering
+        for (i, msg) in messages.iter_mut().enumerate() {
+            msg.idx = i as i64;
+        }
+
+        if messages.is_empty() {
+            return Ok(None);
+        }
+
+        // Extract title from first user message
+        let title = messages
+            .iter()
+            .find(|m| m.role == "user")
+            .map(|m| {
+                m.content
+                    .lines()
+                    .next()
+                    .unwrap_or(&m.content)
+                    .chars()
+                    .take(100)
+                    .collect::<String>()
+            })
+            .or_else(|| {
+                messages
+                    .first()
+                    .and_then(|m| m.content.lines().next())
+                    .map(|s| s.chars().take(100).collect())
+            });
+
+        // Try to extract actual workspace from message content first
+        // Gemini stores by hash, but messages often contain the real project path
+        let workspace = extract_workspace_from_content(&messages).or_else(|| {
+            // Fallback to parent directory structure
+            // Structure: ~/.gemini/tmp/<hash>/chats/session-*.json
+            // or ~/.gemini/tmp/<hash>/checkpoint-*.json
+            match format {
+                ChatFormat::Session => file.parent().and_then(|p| p.parent()), // <hash>/
+                ChatFormat::Checkpoint => file.parent(),
+            }
+            .map(std::path::Path::to_path_buf)
+        });
+
+        Ok(Some(NormalizedConversation {
+            agent_slug: self.slug.into(),
+            external_id: session_id
+                .or_else(|| file.file_stem().and_then(|s| s.to_str()).map(String::from)),
+            title,
+            workspace,
+            source_path: file.to_path_buf(),
+            started_at,
+            ended_at,
+            metadata: serde_json::json!({
+                "source": self.slug,
+                "project_hash": project_hash,
+                "format": format.name(),
+            }),
+            messages,
+        }))
+    }
+}
+
+fn mtime_millis(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let dur = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+    Some(dur.as_millis() as i64)
+}
+
+/// Generations of the on-disk chat format, newest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChatFormat {
+    /// `chats/session-*.json`: `{sessionId, projectHash, startTime, messages}` with
+    /// messages typed `user`/`gemini`/`model` and a `content` string.
+    Session,
+    /// `checkpoint-<tag>.json`: the raw API history, an array of `{role, parts}` with
+    /// no ids or timestamps.
+    Checkpoint,
+}
+
+impl ChatFormat {
+    fn detect(val: &Value) -> Option<Self> {
+        if val.get("messages").is_some_and(Value::is_array) {
+            Some(Self::Session)
+        } else if val
+            .as_array()
+            .is_some_and(|a| a.iter().any(|c| c.get("parts").is_some()))
+        {
+            Some(Self::Checkpoint)
+        } else {
+            None
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Session => "session",
+            Self::Checkpoint => "checkpoint",
+        }
+    }
 }
 
 impl Connector for GeminiConnector {
     fn detect(&self) -> DetectionResult {
-        let root = Self::root();
+        let root = self.root();
         if root.exists() {
             DetectionResult {
                 detected: true,
@@ -187,7 +438,7 @@ impl Connector for GeminiConnector {
         let root = if ctx
             .data_root
             .file_name()
-            .is_some_and(|n| n.to_str().unwrap_or("").contains("gemini"))
+            .is_some_and(|n| n.to_str().unwrap_or("").contains(self.slug))
             || ctx.data_root.join("chats").exists()
             || fs::read_dir(&ctx.data_root)
                 .map(|mut d| d.any(|e| e.ok().is_some_and(|e| e.path().join("chats").exists())))
@@ -195,156 +446,22 @@ impl Connector for GeminiConnector {
         {
             ctx.data_root.clone()
         } else {
-            Self::root()
+            self.root()
         };
 
         if !root.exists() {
             return Ok(Vec::new());
         }
 
-        let files = Self::session_files(&root);
+        let mut files = Self::session_files(&root);
+        files.extend(Self::legacy_checkpoints(&root));
+        // Skip files not modified since last scan (incremental indexing)
+        files.retain(|file| ctx.file_changed(file));
         let mut convs = Vec::new();
-
-        for file in files {
-            // Skip files not modified since last scan (incremental indexing)
-            if !crate::connectors::file_modified_since(&file, ctx.since_ts) {
-                continue;
-            }
-            let content = fs::read_to_string(&file)
-                .with_context(|| format!("read session {}", file.display()))?;
-
-            let val: Value = match serde_json::from_str(&content) {
-                Ok(v) => v,
-                Err(_) => continue,
-            };
-
-            // Extract session metadata
-            let session_id = val
-                .get("sessionId")
-                .and_then(|v| v.as_str())
-                .map(String::from);
-            let project_hash = val
-                .get("projectHash")
-                .and_then(|v| v.as_str())
-                .map(String::from);
-
-            // Parse session-level timestamps
-            let start_time = val
-                .get("startTime")
-                .and_then(crate::connectors::parse_timestamp);
-            let last_updated = val
-                .get("lastUpdated")
-                .and_then(crate::connectors::parse_timestamp);
-
-            // Parse messages array
-            let Some(messages_arr) = val.get("messages").and_then(|m| m.as_array()) else {
-                continue;
-            };
-
-            let mut messages = Vec::new();
-            let mut started_at = start_time;
-            let mut ended_at = last_updated;
-
-            for item in messages_arr {
-                // Role from "type" field - Gemini uses "user" and "model"
-                let msg_type = item.get("type").and_then(|v| v.as_str()).unwrap_or("model");
-                let role = if msg_type == "model" {
-                    "assistant"
-                } else {
-                    msg_type
-                };
-
-                // Parse timestamp using shared utility
-                let created = item
-                    .get("timestamp")
-                    .and_then(crate::connectors::parse_timestamp);
-
-                // NOTE: Do NOT filter individual messages by timestamp here!
-                // The file-level check in file_modified_since() is sufficient.
-                // Filtering messages would cause older messages to be lost when
-                // the file is re-indexed after new messages are added.
-
-                started_at = started_at.or(created);
-                ended_at = created.or(ended_at);
-
-                // Extract content using flatten_content for consistency
-                let content_str = item
-                    .get("content")
-                    .map(crate::connectors::flatten_content)
-                    .unwrap_or_default();
-
-                // Skip entries with empty content
-                if content_str.trim().is_empty() {
-                    continue;
-                }
-
-                messages.push(NormalizedMessage {
-                    idx: 0, // will be re-assigned after filtering
-                    role: role.to_string(),
-                    author: None,
-                    created_at: created,
-                    content: content_str,
-                    extra: item.clone(),
-                    snippets: Vec::new(),
-                });
-            }
-
-            // Re-assign sequential indices after filtering
-            for (i, msg) in messages.iter_mut().enumerate() {
-                msg.idx = i as i64;
-            }
-
-            if messages.is_empty() {
-                continue;
-            }
-
-            // Extract title from first user message
-            let title = messages
-                .iter()
-                .find(|m| m.role == "user")
-                .map(|m| {
-                    m.content
-                        .lines()
-                        .next()
-                        .unwrap_or(&m.content)
-                        .chars()
-                        .take(100)
-                        .collect::<String>()
-                })
-                .or_else(|| {
-                    messages
-                        .first()
-                        .and_then(|m| m.content.lines().next())
-                        .map(|s| s.chars().take(100).collect())
-                });
-
-            // Try to extract actual workspace from message content first
-            // Gemini stores by hash, but messages often contain the real project path
-            let workspace = extract_workspace_from_content(&messages).or_else(|| {
-                // Fallback to parent directory structure
-                // Structure: ~/.gemini/tmp/<hash>/chats/session-*.json
-                file.parent() // chats/
-                    .and_then(|p| p.parent()) // <hash>/
-                    .map(std::path::Path::to_path_buf)
-            });
-
-            convs.push(NormalizedConversation {
-                agent_slug: "gemini".into(),
-                external_id: session_id
-                    .or_else(|| file.file_stem().and_then(|s| s.to_str()).map(String::from)),
-                title,
-                workspace,
-                source_path: file.clone(),
-                started_at,
-                ended_at,
-                metadata: serde_json::json!({
-                    "source": "gemini",
-                    "project_hash": project_hash
-                }),
-                messages,
-            });
-        }
-
+        crate::connectors::parse_files(files, |file| self.parse_file(ctx, file), &mut |conv| {
+            convs.push(conv);
+            Ok(())
+        })?;
         Ok(convs)
     }
 }
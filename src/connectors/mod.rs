@@ -6,13 +6,24 @@ use std::path::PathBuf;
 pub mod aider;
 pub mod amp;
 pub mod chatgpt;
+pub mod checkpoints;
 pub mod claude_code;
 pub mod cline;
 pub mod codex;
+pub mod copilot_cli;
+pub mod crush;
 pub mod cursor;
+pub mod custom;
 pub mod gemini;
+pub mod open_webui;
 pub mod opencode;
 pub mod pi_agent;
+pub mod plandex;
+pub mod quarantine;
+pub mod sgpt;
+pub mod sqlite;
+pub mod trajectory;
+pub mod vscode_chat;
 
 /// High-level detection status for a connector.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +48,64 @@ pub struct ScanContext {
     pub since_ts: Option<i64>,
 }
 
+impl ScanContext {
+    /// Whether `path` needs (re)scanning. While the indexer has per-file checkpoints
+    /// active for `data_root` this compares content against the last committed scan;
+    /// otherwise it falls back to [`file_modified_since`] with `since_ts`.
+    pub fn file_changed(&self, path: &std::path::Path) -> bool {
+        let (changed, basis) = match checkpoints::active(&self.data_root) {
+            Some(store) => (store.changed(path), "checkpoint"),
+            None => (file_modified_since(path, self.since_ts), "mtime"),
+        };
+        if !changed {
+            tracing::debug!(path = %path.display(), basis, "skipping unchanged file");
+        }
+        changed
+    }
+
+    /// Report a file `agent` could not parse (fully or in part). It is listed in the
+    /// data dir's `quarantine.jsonl` while the indexer has checkpoints active, and
+    /// logged either way.
+    pub fn quarantine(&self, agent: &str, path: &std::path::Path, error: impl std::fmt::Display) {
+        let error = error.to_string();
+        tracing::warn!(agent, path = %path.display(), %error, "quarantining unparseable session file");
+        if let Some(store) = checkpoints::active(&self.data_root) {
+            store.quarantine().record(agent, path, error);
+        }
+    }
+
+    /// Parse the lines of a JSONL log, skipping blank and malformed ones. The first
+    /// malformed line quarantines the file, except an unterminated last line, which is
+    /// usually a record the agent is still writing.
+    pub fn jsonl_values<'a>(
+        &'a self,
+        agent: &'a str,
+        path: &'a std::path::Path,
+        content: &'a str,
+    ) -> impl Iterator<Item = serde_json::Value> + 'a {
+        let mut reported = false;
+        content
+            .split_inclusive('\n')
+            .enumerate()
+            .filter_map(move |(i, raw)| {
+                let line = raw.trim();
+                if line.is_empty() {
+                    return None;
+                }
+                match serde_json::from_str(line) {
+                    Ok(v) => Some(v),
+                    Err(e) => {
+                        if !reported && raw.ends_with('\n') {
+                            reported = true;
+                            self.quarantine(agent, path, format!("line {}: {e}", i + 1));
+                        }
+                        None
+                    }
+                }
+            })
+    }
+}
+
 /// Normalized conversation emitted by connectors.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NormalizedConversation {
@@ -71,9 +140,66 @@ pub struct NormalizedSnippet {
     pub snippet_text: Option<String>,
 }
 
+/// Receives conversations from [`Connector::scan_streaming`]; an error stops the scan.
+pub type EmitConversation<'a> = dyn FnMut(NormalizedConversation) -> anyhow::Result<()> + 'a;
+
 pub trait Connector {
     fn detect(&self) -> DetectionResult;
     fn scan(&self, ctx: &ScanContext) -> anyhow::Result<Vec<NormalizedConversation>>;
+
+    /// Hand each conversation to `emit` as soon as it is parsed, so the indexer can
+    /// write while the scan continues and a large history never sits in memory as a
+    /// whole. The default collects [`scan`](Self::scan) first; connectors for
+    /// agents with large histories override it.
+    fn scan_streaming(&self, ctx: &ScanContext, emit: &mut EmitConversation) -> anyhow::Result<()> {
+        for conv in self.scan(ctx)? {
+            emit(conv)?;
+        }
+        Ok(())
+    }
+}
+
+/// [`Connector::scan`] for connectors that implement [`Connector::scan_streaming`].
+pub fn collect_streaming(
+    connector: &dyn Connector,
+    ctx: &ScanContext,
+) -> anyhow::Result<Vec<NormalizedConversation>> {
+    let mut convs = Vec::new();
+    connector.scan_streaming(ctx, &mut |conv| {
+        convs.push(conv);
+        Ok(())
+    })?;
+    Ok(convs)
+}
+
+/// Parse `files` on the rayon pool and hand their conversations to `emit` in file order.
+/// Files go in rounds of a few per worker, so only one round of parsed sessions is held
+/// at a time. An error from `parse` ends the scan once the files before it are emitted,
+/// as a sequential loop would.
+pub fn parse_files<P>(
+    files: Vec<PathBuf>,
+    parse: P,
+    emit: &mut EmitConversation,
+) -> anyhow::Result<()>
+where
+    P: Fn(&std::path::Path) -> anyhow::Result<Option<NormalizedConversation>> + Sync,
+{
+    use rayon::prelude::*;
+
+    let scanner = checkpoints::current_scanner();
+    let round = rayon::current_num_threads() * 4;
+    for chunk in files.chunks(round) {
+        let parsed: Vec<_> = chunk
+            .par_iter()
+            .map(|path| checkpoints::scanning_as(scanner, || parse(path)))
+            .collect();
+        for conv in parsed {
+            if let Some(conv) = conv? {
+                emit(conv)?;
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Check if a file was modified since the given timestamp.
@@ -100,6 +226,69 @@ pub fn file_modified_since(path: &std::path::Path, since_ts: Option<i64>) -> boo
     }
 }
 
+/// Entries of `walker`'s tree, following symlinks so session dirs linked onto another
+/// disk are indexed. walkdir reports symlink cycles as errors, which are skipped, and
+/// a file reachable through several links is yielded once, under the first path seen.
+pub fn walk(walker: walkdir::WalkDir) -> impl Iterator<Item = walkdir::DirEntry> {
+    let mut seen = std::collections::HashSet::new();
+    walker
+        .follow_links(true)
+        .into_iter()
+        .filter_map(move |entry| match entry {
+            Ok(entry) => {
+                if entry.file_type().is_file()
+                    && let Ok(real) = std::fs::canonicalize(entry.path())
+                    && !seen.insert(real)
+                {
+                    return None;
+                }
+                Some(entry)
+            }
+            Err(e) => {
+                if e.loop_ancestor().is_some() {
+                    tracing::debug!(error = %e, "skipping symlink cycle");
+                }
+                None
+            }
+        })
+}
+
+/// `path` without a trailing `.gz`/`.zst`, so `rollout-1.jsonl.gz` keeps the
+/// identity and format of the `rollout-1.jsonl` it was rotated from.
+pub fn uncompressed_path(path: &std::path::Path) -> PathBuf {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("gz" | "zst") => path.with_extension(""),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Read a session file as text, decompressing `.gz` and `.zst` archives.
+/// A truncated archive yields the complete lines decoded before the damage.
+pub fn read_session_file(path: &std::path::Path) -> std::io::Result<String> {
+    use std::io::Read;
+    let open = || std::fs::File::open(path).map(std::io::BufReader::new);
+    let mut reader: Box<dyn Read> = match path.extension().and_then(|e| e.to_str()) {
+        Some("gz") => Box::new(flate2::read::MultiGzDecoder::new(open()?)),
+        Some("zst") => Box::new(zstd::stream::read::Decoder::with_buffer(open()?)?),
+        _ => return std::fs::read_to_string(path),
+    };
+    let mut buf = Vec::new();
+    if let Err(e) = reader.read_to_end(&mut buf) {
+        // read_to_end keeps everything decoded before the error.
+        let Some(end) = buf.iter().rposition(|&b| b == b'\n') else {
+            return Err(e);
+        };
+        tracing::warn!(
+            path = %path.display(),
+            error = %e,
+            recovered_bytes = end + 1,
+            "truncated archive; indexing the readable part"
+        );
+        buf.truncate(end + 1);
+    }
+    String::from_utf8(buf).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
 /// Parse a timestamp from either i64 milliseconds or ISO-8601 string.
 /// Returns milliseconds since Unix epoch, or None if unparseable.
 ///
@@ -135,7 +324,7 @@ pub fn parse_timestamp(val: &serde_json::Value) -> Option<i64> {
 /// - Direct string content (e.g., user messages)
 /// - Array of content blocks with `{"type": "text", "text": "..."}`
 /// - Tool use blocks: `{"type": "tool_use", "name": "Read", "input": {...}}`
-/// - Codex `input_text` blocks: `{"type": "input_text", "text": "..."}`
+/// - Codex `input_text`/`output_text` blocks: `{"type": "input_text", "text": "..."}`
 pub fn flatten_content(val: &serde_json::Value) -> String {
     // Direct string content (user messages in Claude Code)
     if let Some(s) = val.as_str() {
@@ -155,6 +344,7 @@ pub fn flatten_content(val: &serde_json::Value) -> String {
                     if item_type.is_none()
                         || item_type == Some("text")
                         || item_type == Some("input_text")
+                        || item_type == Some("output_text")
                     {
                         return Some(text.to_string());
                     }
@@ -190,3 +380,232 @@ pub fn flatten_content(val: &serde_json::Value) -> String {
 
     String::new()
 }
+
+/// Structured snippets for one message: a tool snippet per `tool_use` block in
+/// `content` and a code snippet per fenced block in the flattened `text`.
+pub fn extract_snippets(content: Option<&serde_json::Value>, text: &str) -> Vec<NormalizedSnippet> {
+    let mut snippets: Vec<NormalizedSnippet> = content
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|item| item.get("type").and_then(|v| v.as_str()) == Some("tool_use"))
+        .map(|item| {
+            let name = item
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown");
+            tool_snippet(name, item.get("input").unwrap_or(&serde_json::Value::Null))
+        })
+        .collect();
+    snippets.extend(code_block_snippets(text));
+    snippets
+}
+
+/// A tool invocation: `snippet_text` is `[Tool: name] <command/pattern/url/...>` and
+/// `file_path` the file it reads or writes, if the input names one.
+pub fn tool_snippet(name: &str, input: &serde_json::Value) -> NormalizedSnippet {
+    let str_field = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|k| input.get(*k).and_then(|v| v.as_str()))
+            .map(String::from)
+    };
+    let file_path = str_field(&["file_path", "notebook_path", "path", "filePath"]);
+    let detail = input
+        .get("command")
+        .and_then(|v| match v {
+            serde_json::Value::Array(parts) => Some(shell_command(parts)),
+            other => other.as_str().map(String::from),
+        })
+        .or_else(|| str_field(&["pattern", "url", "query", "description"]))
+        .or_else(|| file_path.clone())
+        .unwrap_or_default();
+    NormalizedSnippet {
+        file_path: file_path.map(PathBuf::from),
+        start_line: None,
+        end_line: None,
+        language: None,
+        snippet_text: Some(format!("[Tool: {name}] {detail}").trim_end().to_string()),
+    }
+}
+
+/// `["bash", "-lc", "cargo test"]` → `cargo test`; other argv arrays are joined.
+fn shell_command(parts: &[serde_json::Value]) -> String {
+    let argv: Vec<&str> = parts.iter().filter_map(|p| p.as_str()).collect();
+    match argv.as_slice() {
+        [shell, flag, script]
+            if flag.starts_with('-') && flag.ends_with('c') && shell.ends_with("sh") =>
+        {
+            (*script).to_string()
+        }
+        _ => argv.join(" "),
+    }
+}
+
+/// Fenced (```` ``` ```` or `~~~`) code blocks in `text`. `language` is the info
+/// string, and the line range is 1-based within the message, fences excluded.
+/// An unterminated fence runs to the end of the message.
+pub fn code_block_snippets(text: &str) -> Vec<NormalizedSnippet> {
+    let mut snippets = Vec::new();
+    let mut open: Option<(&str, Option<String>, usize)> = None;
+    let mut body: Vec<&str> = Vec::new();
+    let mut close = |language: Option<String>, start: usize, body: &mut Vec<&str>| {
+        if !body.is_empty() {
+            snippets.push(NormalizedSnippet {
+                file_path: None,
+                start_line: Some(start as i64),
+                end_line: Some((start + body.len() - 1) as i64),
+                language,
+                snippet_text: Some(body.join("\n")),
+            });
+        }
+        body.clear();
+    };
+    for (i, line) in text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        match &open {
+            None => {
+                if let Some(fence) = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f)) {
+                    let info = trimmed.trim_start_matches(fence.chars().next().unwrap_or('`'));
+                    let language = info
+                        .split_whitespace()
+                        .next()
+                        .map(|l| l.to_ascii_lowercase());
+                    open = Some((fence, language, i + 2));
+                }
+            }
+            Some((fence, _, _))
+                if trimmed.starts_with(fence)
+                    && trimmed.trim_start_matches(*fence).trim().is_empty() =>
+            {
+                if let Some((_, language, start)) = open.take() {
+                    close(language, start, &mut body);
+                }
+            }
+            Some(_) => body.push(line),
+        }
+    }
+    if let Some((_, language, start)) = open {
+        close(language, start, &mut body);
+    }
+    snippets
+}
+
+/// Nearest ancestor of absolute `path` (or `path` itself) holding a `.git`
+/// directory or worktree file. The home directory and `/` are never returned, so a dotfiles
+/// repo does not claim every session stored under `~`.
+pub fn git_root(path: &std::path::Path) -> Option<PathBuf> {
+    if !path.is_absolute() {
+        return None;
+    }
+    let home = dirs::home_dir();
+    path.ancestors()
+        .filter(|dir| dir.parent().is_some() && Some(*dir) != home.as_deref())
+        .find(|dir| dir.join(".git").exists())
+        .map(std::path::Path::to_path_buf)
+}
+
+/// Best guess at the workspace of a conversation whose connector recorded none:
+/// the git root that most of the absolute paths it mentions (snippet file paths,
+/// then paths in message text) belong to, else the git root of the source file.
+pub fn infer_workspace(conv: &NormalizedConversation) -> Option<PathBuf> {
+    const MAX_CANDIDATES: usize = 64;
+    let trim = |s: &str| {
+        s.trim_matches(|c: char| {
+            matches!(
+                c,
+                '`' | '\'' | '"' | '(' | ')' | '[' | ']' | '<' | '>' | ',' | ';' | ':'
+            )
+        })
+        .to_string()
+    };
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    for msg in &conv.messages {
+        candidates.extend(msg.snippets.iter().filter_map(|s| s.file_path.clone()));
+        candidates.extend(
+            msg.content
+                .split_whitespace()
+                .map(trim)
+                .filter(|w| w.len() > 1 && w.starts_with('/'))
+                .map(PathBuf::from),
+        );
+    }
+    candidates.retain(|p| p.is_absolute());
+    candidates.dedup();
+    candidates.truncate(MAX_CANDIDATES);
+
+    let mut roots: Vec<(PathBuf, usize)> = Vec::new();
+    for path in &candidates {
+        if let Some(root) = git_root(path) {
+            match roots.iter_mut().find(|(r, _)| *r == root) {
+                Some((_, n)) => *n += 1,
+                None => roots.push((root, 1)),
+            }
+        }
+    }
+    // max_by_key keeps the last maximum; reverse so ties go to the first root seen.
+    roots
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, n)| *n)
+        .map(|(root, _)| root)
+        .or_else(|| git_root(conv.source_path.parent()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conv_for(path: &std::path::Path) -> NormalizedConversation {
+        NormalizedConversation {
+            agent_slug: "test".into(),
+            external_id: None,
+            title: None,
+            workspace: None,
+            source_path: path.to_path_buf(),
+            started_at: None,
+            ended_at: None,
+            metadata: serde_json::Value::Null,
+            messages: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parse_files_emits_in_file_order_and_stops_at_the_first_error() {
+        let files: Vec<PathBuf> = (0..100).map(|i| PathBuf::from(format!("/s/{i}"))).collect();
+
+        let mut emitted = Vec::new();
+        parse_files(
+            files.clone(),
+            |path| Ok((!path.ends_with("7")).then(|| conv_for(path))),
+            &mut |conv| {
+                emitted.push(conv.source_path);
+                Ok(())
+            },
+        )
+        .unwrap();
+        let expected: Vec<_> = files
+            .iter()
+            .filter(|p| !p.ends_with("7"))
+            .cloned()
+            .collect();
+        assert_eq!(emitted, expected);
+
+        let mut emitted = Vec::new();
+        let err = parse_files(
+            files.clone(),
+            |path| {
+                if path.ends_with("42") {
+                    anyhow::bail!("broken {}", path.display());
+                }
+                Ok(Some(conv_for(path)))
+            },
+            &mut |conv| {
+                emitted.push(conv.source_path);
+                Ok(())
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "broken /s/42");
+        assert_eq!(emitted, files[..42]);
+    }
+}
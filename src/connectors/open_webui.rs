@@ -0,0 +1,306 @@
+//! Connector for Open WebUI (https://github.com/open-webui/open-webui), the usual
+//! front end for locally hosted Ollama models.
+//!
+//! Open WebUI keeps chats server-side, so cass reads them from either:
+//! - a chat export (`chat-export-<ts>.json` from Settings → Chats → Export), a JSON
+//!   array of `{id, title, chat, created_at, updated_at}` records
+//! - the server database (`webui.db`), whose `chat` table has the same columns with
+//!   `chat` stored as JSON text
+//!
+//! Both are looked for under `~/.open-webui/`; `OPEN_WEBUI_PATH` may point at that
+//! directory, a single export, or the database itself. The `chat` payload holds a
+//! message tree (`history.messages` keyed by id, `history.currentId` at the tip);
+//! only the branch the user last viewed is indexed. Timestamps are epoch seconds.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::connectors::{
+    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    flatten_content,
+};
+
+pub struct OpenWebUiConnector;
+
+impl Default for OpenWebUiConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One chat record, as found in an export or a `chat` table row.
+struct ChatRecord {
+    id: String,
+    title: Option<String>,
+    chat: Value,
+    created_at: Option<i64>,
+    updated_at: Option<i64>,
+}
+
+impl OpenWebUiConnector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Get the Open WebUI data location.
+    /// Checks OPEN_WEBUI_PATH env var, then the configured `open_webui.root`,
+    /// falls back to ~/.open-webui/
+    pub fn root() -> PathBuf {
+        crate::config::env_root("open_webui", "OPEN_WEBUI_PATH")
+            .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".open-webui"))
+    }
+
+    fn is_db(path: &Path) -> bool {
+        path.extension().and_then(|e| e.to_str()) == Some("db")
+    }
+
+    /// Exports and databases at `root`, which may itself be a single file.
+    fn source_files(root: &Path) -> Vec<PathBuf> {
+        if root.is_file() {
+            return vec![root.to_path_buf()];
+        }
+        let Ok(entries) = fs::read_dir(root) else {
+            return Vec::new();
+        };
+        let mut out: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file())
+            .filter(|p| {
+                let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                name == "webui.db" || (name.starts_with("chat-export") && name.ends_with(".json"))
+            })
+            .collect();
+        out.sort();
+        out
+    }
+
+    fn records_from_export(path: &Path, ctx: &ScanContext) -> Result<Vec<ChatRecord>> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("read open-webui export {}", path.display()))?;
+        let val = match serde_json::from_str::<Value>(&content) {
+            Ok(val) => val,
+            Err(e) => {
+                ctx.quarantine("open_webui", path, e);
+                return Ok(Vec::new());
+            }
+        };
+        // A full export is an array; a single-chat export is one record.
+        let items = match val {
+            Value::Array(items) => items,
+            other => vec![other],
+        };
+        Ok(items
+            .into_iter()
+            .filter_map(|item| {
+                Some(ChatRecord {
+                    id: item.get("id")?.as_str()?.to_string(),
+                    title: item.get("title").and_then(|v| v.as_str()).map(String::from),
+                    created_at: item.get("created_at").and_then(epoch_millis),
+                    updated_at: item.get("updated_at").and_then(epoch_millis),
+                    chat: item.get("chat").cloned()?,
+                })
+            })
+            .collect())
+    }
+
+    fn records_from_db(path: &Path) -> Result<Vec<ChatRecord>> {
+        let conn = crate::connectors::sqlite::open_readonly(path)?;
+        let mut stmt =
+            match conn.prepare("SELECT id, title, chat, created_at, updated_at FROM chat") {
+                Ok(stmt) => stmt,
+                // Not an Open WebUI database (or a schema we don't know).
+                Err(_) => return Ok(Vec::new()),
+            };
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+                row.get::<_, Option<i64>>(4)?,
+            ))
+        })?;
+        Ok(rows
+            .flatten()
+            .filter_map(|(id, title, chat, created, updated)| {
+                Some(ChatRecord {
+                    id,
+                    title,
+                    chat: serde_json::from_str(&chat?).ok()?,
+                    created_at: created.and_then(|v| epoch_millis(&Value::from(v))),
+                    updated_at: updated.and_then(|v| epoch_millis(&Value::from(v))),
+                })
+            })
+            .collect())
+    }
+
+    /// Messages on the active branch, oldest first.
+    fn branch_messages(chat: &Value) -> Vec<Value> {
+        let history = chat.get("history");
+        let tree = history
+            .and_then(|h| h.get("messages"))
+            .and_then(|m| m.as_object());
+        let current = history
+            .and_then(|h| h.get("currentId"))
+            .and_then(|v| v.as_str());
+        if let (Some(tree), Some(mut id)) = (tree, current) {
+            let nodes: HashMap<&str, &Value> = tree.iter().map(|(k, v)| (k.as_str(), v)).collect();
+            let mut branch = Vec::new();
+            while let Some(node) = nodes.get(id) {
+                branch.push((*node).clone());
+                // Guard against malformed trees that loop back on themselves.
+                if branch.len() > nodes.len() {
+                    break;
+                }
+                match node.get("parentId").and_then(|v| v.as_str()) {
+                    Some(parent) => id = parent,
+                    None => break,
+                }
+            }
+            branch.reverse();
+            return branch;
+        }
+        // Older exports carry only the flat list.
+        chat.get("messages")
+            .and_then(|m| m.as_array())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn normalize(record: ChatRecord, source_path: &Path) -> Option<NormalizedConversation> {
+        let mut messages = Vec::new();
+        for msg in Self::branch_messages(&record.chat) {
+            let content = msg.get("content").map(flatten_content).unwrap_or_default();
+            if content.trim().is_empty() {
+                continue;
+            }
+            let role = msg
+                .get("role")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string();
+            let author = if role == "assistant" {
+                msg.get("model").and_then(|v| v.as_str()).map(String::from)
+            } else {
+                None
+            };
+            messages.push(NormalizedMessage {
+                idx: messages.len() as i64,
+                role,
+                author,
+                created_at: msg.get("timestamp").and_then(epoch_millis),
+                content,
+                extra: msg,
+                snippets: Vec::new(),
+            });
+        }
+        if messages.is_empty() {
+            return None;
+        }
+
+        let title = record
+            .title
+            .or_else(|| {
+                record
+                    .chat
+                    .get("title")
+                    .and_then(|v| v.as_str())
+                    .map(String::from)
+            })
+            .filter(|t| !t.trim().is_empty())
+            .or_else(|| {
+                messages
+                    .iter()
+                    .find(|m| m.role == "user")
+                    .and_then(|m| m.content.lines().next())
+                    .map(|s| s.chars().take(100).collect())
+            });
+        let started_at = record
+            .created_at
+            .or_else(|| messages.first().and_then(|m| m.created_at));
+        let ended_at = record
+            .updated_at
+            .or_else(|| messages.last().and_then(|m| m.created_at));
+
+        Some(NormalizedConversation {
+            agent_slug: "open_webui".to_string(),
+            external_id: Some(record.id.clone()),
+            title,
+            workspace: None,
+            source_path: source_path.to_path_buf(),
+            started_at,
+            ended_at,
+            metadata: serde_json::json!({
+                "source": "open_webui",
+                "chat_id": record.id,
+                "models": record.chat.get("models"),
+            }),
+            messages,
+        })
+    }
+}
+
+/// Open WebUI stores epoch seconds; tolerate millisecond and nanosecond values too.
+fn epoch_millis(val: &Value) -> Option<i64> {
+    let raw = val.as_i64().or_else(|| val.as_f64().map(|f| f as i64))?;
+    Some(if raw < 100_000_000_000 {
+        raw * 1_000
+    } else if raw < 100_000_000_000_000 {
+        raw
+    } else if raw < 100_000_000_000_000_000 {
+        raw / 1_000
+    } else {
+        raw / 1_000_000
+    })
+}
+
+impl Connector for OpenWebUiConnector {
+    fn detect(&self) -> DetectionResult {
+        let files = Self::source_files(&Self::root());
+        if files.is_empty() {
+            DetectionResult::not_found()
+        } else {
+            DetectionResult {
+                detected: true,
+                evidence: files
+                    .iter()
+                    .map(|p| format!("found {}", p.display()))
+                    .collect(),
+            }
+        }
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        // Use data_root if it holds Open WebUI data (for testing)
+        let from_data_root = Self::source_files(&ctx.data_root);
+        let files = if from_data_root.is_empty() {
+            Self::source_files(&Self::root())
+        } else {
+            from_data_root
+        };
+
+        let mut convs = Vec::new();
+        for file in files {
+            if !ctx.file_changed(&file) {
+                continue;
+            }
+            let records = if Self::is_db(&file) {
+                Self::records_from_db(&file)?
+            } else {
+                Self::records_from_export(&file, ctx)?
+            };
+            convs.extend(
+                records
+                    .into_iter()
+                    .filter_map(|record| Self::normalize(record, &file)),
+            );
+        }
+
+        Ok(convs)
+    }
+}
@@ -6,10 +6,20 @@ use anyhow::Result;
 use rusqlite::{Connection, Row};
 use walkdir::WalkDir;
 
+use crate::connectors::sqlite::{self, Columns, MessageMapping};
 use crate::connectors::{
     Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
 };
 
+/// OpenCode's `messages` columns differ across releases.
+const MESSAGE_MAPPING: MessageMapping = MessageMapping {
+    role: &["role", "sender"],
+    author: &["author", "sender"],
+    created_at: &["created_at", "timestamp", "ts"],
+    content: &["content", "text", "message"],
+    default_role: "agent",
+};
+
 pub struct OpenCodeConnector;
 impl Default for OpenCodeConnector {
     fn default() -> Self {
@@ -23,6 +33,9 @@ impl OpenCodeConnector {
     }
 
     fn dir_candidates() -> Vec<PathBuf> {
+        if let Some(root) = crate::config::connector_root("opencode") {
+            return vec![root];
+        }
         let cwd = std::env::current_dir().unwrap_or_default();
         let mut dirs = vec![cwd.join(".opencode")];
 
@@ -44,7 +57,7 @@ impl OpenCodeConnector {
             if !root.exists() {
                 continue;
             }
-            for entry in WalkDir::new(root).into_iter().flatten() {
+            for entry in crate::connectors::walk(WalkDir::new(root)) {
                 if entry.file_type().is_file() {
                     let path = entry.path();
                     let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
@@ -80,10 +93,11 @@ impl Connector for OpenCodeConnector {
         let mut seen_ids = std::collections::HashSet::new();
 
         // Use ctx.data_root for tests/custom paths, but filter out CASS internal databases
-        let dbs = if ctx.data_root.exists() {
-            WalkDir::new(&ctx.data_root)
-                .into_iter()
-                .flatten()
+        // A configured root takes precedence over both.
+        let dbs = if crate::config::connector_root("opencode").is_some() {
+            Self::find_dbs()
+        } else if ctx.data_root.exists() {
+            crate::connectors::walk(WalkDir::new(&ctx.data_root))
                 .filter(|e| e.file_type().is_file())
                 .map(|e| e.path().to_path_buf())
                 .filter(|p| {
@@ -101,13 +115,13 @@ impl Connector for OpenCodeConnector {
 
         for db_path in dbs {
             // Skip files not modified since last scan (incremental indexing)
-            if !crate::connectors::file_modified_since(&db_path, ctx.since_ts) {
+            if !ctx.file_changed(&db_path) {
                 continue;
             }
-            let conn = match Connection::open(&db_path) {
+            let conn = match sqlite::open_readonly(&db_path) {
                 Ok(c) => c,
                 Err(err) => {
-                    tracing::warn!("opencode: failed to open {}: {err}", db_path.display());
+                    tracing::warn!("opencode: {err:#}");
                     continue;
                 }
             };
@@ -128,8 +142,8 @@ fn load_db(
     since_ts: Option<i64>,
     seen_ids: &mut std::collections::HashSet<String>,
 ) -> Result<Vec<NormalizedConversation>> {
-    let sessions_present = has_table(conn, "sessions")?;
-    let messages_present = has_table(conn, "messages")?;
+    let sessions_present = sqlite::has_table(conn, "sessions")?;
+    let messages_present = sqlite::has_table(conn, "messages")?;
 
     if !messages_present {
         return Ok(Vec::new());
@@ -145,22 +159,18 @@ fn load_db(
     let mut by_session: HashMap<i64, Vec<NormalizedMessage>> = HashMap::new();
     let mut fallback_messages: Vec<NormalizedMessage> = Vec::new();
 
-    let msg_cols = table_columns(conn, "messages")?;
-    let order_col = msg_cols
-        .iter()
-        .find(|c| c.as_str() == "created_at" || c.as_str() == "timestamp" || c.as_str() == "ts")
-        .cloned();
-    let sql = match order_col {
+    let msg_cols = Columns::of(conn, "messages")?;
+    let sql = match msg_cols.first_of(MESSAGE_MAPPING.created_at) {
         Some(col) => format!("SELECT * FROM messages ORDER BY {col}"),
         None => "SELECT * FROM messages".to_string(),
     };
 
     let mut stmt = conn.prepare(&sql)?;
-    let rows = stmt.query_map([], |row| message_from_row(row, &msg_cols))?;
+    let rows = stmt.query_map([], |row| MESSAGE_MAPPING.message(row, &msg_cols))?;
     for msg in rows {
         let msg = msg?;
         // NOTE: Do NOT filter individual messages by timestamp here!
-        // The file-level check in file_modified_since() is sufficient.
+        // The file-level check in ctx.file_changed() is sufficient.
         // Filtering messages would cause older messages to be lost when
         // the file is re-indexed after new messages are added.
 
@@ -289,22 +299,6 @@ fn load_db(
     Ok(unique)
 }
 
-fn has_table(conn: &Connection, name: &str) -> Result<bool> {
-    let mut stmt =
-        conn.prepare("SELECT name FROM sqlite_master WHERE type='table' AND name = ?1 LIMIT 1")?;
-    Ok(stmt.exists([name])?)
-}
-
-fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
-    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
-    let rows = stmt.query_map([], |row| row.get::<_, String>(1))?; // 1 = name
-    let mut cols = Vec::new();
-    for c in rows {
-        cols.push(c?);
-    }
-    Ok(cols)
-}
-
 #[derive(Debug, Clone)]
 struct SessionRow {
     id: i64,
@@ -314,7 +308,7 @@ struct SessionRow {
 }
 
 fn read_sessions(conn: &Connection) -> Result<HashMap<i64, SessionRow>> {
-    let cols = table_columns(conn, "sessions")?;
+    let cols = Columns::of(conn, "sessions")?;
     let mut stmt = conn.prepare("SELECT * FROM sessions")?;
     let rows = stmt.query_map([], |row| session_from_row(row, &cols))?;
     let mut map = HashMap::new();
@@ -325,106 +319,16 @@ fn read_sessions(conn: &Connection) -> Result<HashMap<i64, SessionRow>> {
     Ok(map)
 }
 
-fn session_from_row(row: &Row<'_>, cols: &[String]) -> rusqlite::Result<SessionRow> {
-    let id = get_opt_i64(row, cols, "id")?.unwrap_or_else(|| row.get::<_, i64>(0).unwrap_or(0));
-
-    let mut title = get_opt_string(row, cols, "title")?;
-    if title.is_none() {
-        title = get_opt_string(row, cols, "name")?;
-    }
-
-    let mut workspace = get_opt_string(row, cols, "workspace")?;
-    if workspace.is_none() {
-        workspace = get_opt_string(row, cols, "root_path")?;
-    }
-    let workspace = workspace.map(PathBuf::from);
-
-    let mut started_at = get_opt_i64(row, cols, "created_at")?;
-    if started_at.is_none() {
-        started_at = get_opt_i64(row, cols, "started_at")?;
-    }
-    if started_at.is_none() {
-        started_at = get_opt_i64(row, cols, "timestamp")?;
-    }
-
+fn session_from_row(row: &Row<'_>, cols: &Columns) -> rusqlite::Result<SessionRow> {
+    let id = cols
+        .i64(row, &["id"])?
+        .unwrap_or_else(|| row.get::<_, i64>(0).unwrap_or(0));
     Ok(SessionRow {
         id,
-        title,
-        workspace,
-        started_at,
+        title: cols.string(row, &["title", "name"])?,
+        workspace: cols
+            .string(row, &["workspace", "root_path"])?
+            .map(PathBuf::from),
+        started_at: cols.i64(row, &["created_at", "started_at", "timestamp"])?,
     })
 }
-
-fn message_from_row(row: &Row<'_>, cols: &[String]) -> rusqlite::Result<NormalizedMessage> {
-    let mut role = get_opt_string(row, cols, "role")?;
-    if role.is_none() {
-        role = get_opt_string(row, cols, "sender")?;
-    }
-    let role = role.unwrap_or_else(|| "agent".to_string());
-
-    let mut author = get_opt_string(row, cols, "author")?;
-    if author.is_none() {
-        author = get_opt_string(row, cols, "sender")?;
-    }
-
-    let mut created_at = get_opt_i64(row, cols, "created_at")?;
-    if created_at.is_none() {
-        created_at = get_opt_i64(row, cols, "timestamp")?;
-    }
-    if created_at.is_none() {
-        created_at = get_opt_i64(row, cols, "ts")?;
-    }
-
-    let mut content = get_opt_string(row, cols, "content")?;
-    if content.is_none() {
-        content = get_opt_string(row, cols, "text")?;
-    }
-    if content.is_none() {
-        content = get_opt_string(row, cols, "message")?;
-    }
-    let content = content.unwrap_or_default();
-
-    // Capture the entire row as best-effort metadata for debugging.
-    let mut extra = serde_json::Map::new();
-    for (idx, c) in cols.iter().enumerate() {
-        if let Ok(val) = row.get::<_, rusqlite::types::Value>(idx) {
-            extra.insert(c.clone(), sqlite_value_to_json(val));
-        }
-    }
-
-    Ok(NormalizedMessage {
-        idx: 0,
-        role,
-        author,
-        created_at,
-        content,
-        extra: serde_json::Value::Object(extra),
-        snippets: Vec::new(),
-    })
-}
-
-fn sqlite_value_to_json(v: rusqlite::types::Value) -> serde_json::Value {
-    use base64::Engine;
-    use rusqlite::types::Value as V;
-    match v {
-        V::Null => serde_json::Value::Null,
-        V::Integer(i) => serde_json::Value::from(i),
-        V::Real(f) => serde_json::Value::from(f),
-        V::Text(t) => serde_json::Value::from(t),
-        V::Blob(b) => serde_json::Value::from(base64::engine::general_purpose::STANDARD.encode(b)),
-    }
-}
-
-fn get_opt_string(row: &Row<'_>, cols: &[String], name: &str) -> rusqlite::Result<Option<String>> {
-    if let Some(idx) = cols.iter().position(|c| c == name) {
-        return row.get::<_, Option<String>>(idx);
-    }
-    Ok(None)
-}
-
-fn get_opt_i64(row: &Row<'_>, cols: &[String], name: &str) -> rusqlite::Result<Option<i64>> {
-    if let Some(idx) = cols.iter().position(|c| c == name) {
-        return row.get::<_, Option<i64>>(idx);
-    }
-    Ok(None)
-}
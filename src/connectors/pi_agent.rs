@@ -10,7 +10,6 @@
 //! - `thinking_level_change`: Records thinking level changes
 //! - `model_change`: Records model/provider changes
 
-use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
@@ -19,7 +18,7 @@ use walkdir::WalkDir;
 
 use crate::connectors::{
     Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
-    file_modified_since, parse_timestamp,
+    parse_timestamp,
 };
 
 pub struct PiAgentConnector;
@@ -36,12 +35,11 @@ impl PiAgentConnector {
     }
 
     /// Get the pi-agent home directory.
-    /// Checks PI_CODING_AGENT_DIR env var, falls back to ~/.pi/agent/
+    /// Checks PI_CODING_AGENT_DIR env var, then the configured `pi_agent.root`,
+    /// falls back to ~/.pi/agent/
     fn home() -> PathBuf {
-        std::env::var("PI_CODING_AGENT_DIR").map_or_else(
-            |_| dirs::home_dir().unwrap_or_default().join(".pi/agent"),
-            PathBuf::from,
-        )
+        crate::config::env_root("pi_agent", "PI_CODING_AGENT_DIR")
+            .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".pi/agent"))
     }
 
     /// Find all session JSONL files under the sessions directory.
@@ -51,10 +49,11 @@ impl PiAgentConnector {
         if !sessions.exists() {
             return out;
         }
-        for entry in WalkDir::new(sessions).into_iter().flatten() {
+        for entry in crate::connectors::walk(WalkDir::new(sessions)) {
             if entry.file_type().is_file() {
-                let name = entry.file_name().to_str().unwrap_or("");
-                // Pi-agent session files are named <timestamp>_<uuid>.jsonl
+                let plain = crate::connectors::uncompressed_path(entry.path());
+                let name = plain.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                // Pi-agent session files are named <timestamp>_<uuid>.jsonl (maybe .gz/.zst)
                 if name.ends_with(".jsonl") && name.contains('_') {
                     out.push(entry.path().to_path_buf());
                 }
@@ -129,6 +128,176 @@ impl PiAgentConnector {
 
         String::new()
     }
+
+    /// Parse one session file under `home`; `None` when it holds no messages.
+    fn parse_file(
+        &self,
+        ctx: &ScanContext,
+        home: &Path,
+        file: &Path,
+    ) -> Result<Option<NormalizedConversation>> {
+        let source_path = file.to_path_buf();
+
+        // Use the parent directory name + filename as external_id
+        // e.g., "--Users-foo-project--/2024-01-15T10-30-00_uuid.jsonl"
+        let sessions_dir = home.join("sessions");
+        let plain = crate::connectors::uncompressed_path(file);
+        let external_id = plain
+            .strip_prefix(&sessions_dir)
+            .ok()
+            .and_then(|rel| rel.to_str().map(String::from))
+            .or_else(|| plain.file_stem().and_then(|s| s.to_str()).map(String::from));
+
+        let content = crate::connectors::read_session_file(file)
+            .with_context(|| format!("read pi-agent session {}", file.display()))?;
+
+        let mut messages = Vec::new();
+        let mut started_at: Option<i64> = None;
+        let mut ended_at: Option<i64> = None;
+        let mut session_cwd: Option<PathBuf> = None;
+        let mut session_id: Option<String> = None;
+        let mut provider: Option<String> = None;
+        let mut model_id: Option<String> = None;
+
+        for val in ctx.jsonl_values("pi_agent", file, &content) {
+            let entry_type = val.get("type").and_then(|v| v.as_str()).unwrap_or("");
+
+            match entry_type {
+                "session" => {
+                    // Session header - extract metadata
+                    session_id = val.get("id").and_then(|v| v.as_str()).map(String::from);
+                    session_cwd = val.get("cwd").and_then(|v| v.as_str()).map(PathBuf::from);
+                    provider = val
+                        .get("provider")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+                    model_id = val
+                        .get("modelId")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+
+                    // Parse timestamp
+                    if let Some(ts_val) = val.get("timestamp") {
+                        started_at = parse_timestamp(ts_val);
+                    }
+                }
+                "message" => {
+                    // Message entry - extract the nested message object
+                    let created = val.get("timestamp").and_then(parse_timestamp);
+
+                    if let Some(msg) = val.get("message") {
+                        let role = msg
+                            .get("role")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("unknown");
+
+                        // Normalize role names
+                        let normalized_role = match role {
+                            "user" => "user",
+                            "assistant" => "assistant",
+                            "toolResult" => "tool",
+                            _ => role,
+                        };
+
+                        // Extract content
+                        let content_str = msg
+                            .get("content")
+                            .map(Self::flatten_message_content)
+                            .unwrap_or_default();
+
+                        if content_str.trim().is_empty() {
+                            continue;
+                        }
+
+                        // Update timestamps
+                        if started_at.is_none() {
+                            started_at = created;
+                        }
+                        ended_at = created.or(ended_at);
+
+                        // Extract author (model) for assistant messages
+                        // Check message.model first, fall back to tracked model_id
+                        let author = if normalized_role == "assistant" {
+                            msg.get("model")
+                                .and_then(|v| v.as_str())
+                                .map(String::from)
+                                .or_else(|| model_id.clone())
+                        } else {
+                            None
+                        };
+
+                        messages.push(NormalizedMessage {
+                            idx: messages.len() as i64,
+                            role: normalized_role.to_string(),
+                            author,
+                            created_at: created,
+                            content: content_str,
+                            extra: val.clone(),
+                            snippets: Vec::new(),
+                        });
+                    }
+                }
+                "model_change" => {
+                    // Track model changes (useful metadata)
+                    provider = val
+                        .get("provider")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+                    model_id = val
+                        .get("modelId")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+                }
+                _ => {
+                    // Skip thinking_level_change and unknown types
+                }
+            }
+        }
+
+        if messages.is_empty() {
+            return Ok(None);
+        }
+
+        // Extract title from first user message
+        let title = messages
+            .iter()
+            .find(|m| m.role == "user")
+            .map(|m| {
+                m.content
+                    .lines()
+                    .next()
+                    .unwrap_or(&m.content)
+                    .chars()
+                    .take(100)
+                    .collect::<String>()
+            })
+            .or_else(|| {
+                messages
+                    .first()
+                    .and_then(|m| m.content.lines().next())
+                    .map(|s| s.chars().take(100).collect())
+            });
+
+        // Build metadata
+        let metadata = serde_json::json!({
+            "source": "pi_agent",
+            "session_id": session_id,
+            "provider": provider,
+            "model_id": model_id,
+        });
+
+        Ok(Some(NormalizedConversation {
+            agent_slug: "pi_agent".to_string(),
+            external_id,
+            title,
+            workspace: session_cwd,
+            source_path: source_path.clone(),
+            started_at,
+            ended_at,
+            metadata,
+            messages,
+        }))
+    }
 }
 
 impl Connector for PiAgentConnector {
@@ -159,190 +328,20 @@ impl Connector for PiAgentConnector {
             Self::home()
         };
 
-        let files = Self::session_files(&home);
+        // Skip files not modified since last scan
+        let files: Vec<_> = Self::session_files(&home)
+            .into_iter()
+            .filter(|file| ctx.file_changed(file))
+            .collect();
         let mut convs = Vec::new();
-
-        for file in files {
-            // Skip files not modified since last scan
-            if !file_modified_since(&file, ctx.since_ts) {
-                continue;
-            }
-
-            let source_path = file.clone();
-
-            // Use the parent directory name + filename as external_id
-            // e.g., "--Users-foo-project--/2024-01-15T10-30-00_uuid.jsonl"
-            let sessions_dir = home.join("sessions");
-            let external_id = source_path
-                .strip_prefix(&sessions_dir)
-                .ok()
-                .and_then(|rel| rel.to_str().map(String::from))
-                .or_else(|| {
-                    source_path
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .map(String::from)
-                });
-
-            let content = fs::read_to_string(&file)
-                .with_context(|| format!("read pi-agent session {}", file.display()))?;
-
-            let mut messages = Vec::new();
-            let mut started_at: Option<i64> = None;
-            let mut ended_at: Option<i64> = None;
-            let mut session_cwd: Option<PathBuf> = None;
-            let mut session_id: Option<String> = None;
-            let mut provider: Option<String> = None;
-            let mut model_id: Option<String> = None;
-
-            for line in content.lines() {
-                if line.trim().is_empty() {
-                    continue;
-                }
-                let val: Value = match serde_json::from_str(line) {
-                    Ok(v) => v,
-                    Err(_) => continue,
-                };
-
-                let entry_type = val.get("type").and_then(|v| v.as_str()).unwrap_or("");
-
-                match entry_type {
-                    "session" => {
-                        // Session header - extract metadata
-                        session_id = val.get("id").and_then(|v| v.as_str()).map(String::from);
-                        session_cwd = val.get("cwd").and_then(|v| v.as_str()).map(PathBuf::from);
-                        provider = val
-                            .get("provider")
-                            .and_then(|v| v.as_str())
-                            .map(String::from);
-                        model_id = val
-                            .get("modelId")
-                            .and_then(|v| v.as_str())
-                            .map(String::from);
-
-                        // Parse timestamp
-                        if let Some(ts_val) = val.get("timestamp") {
-                            started_at = parse_timestamp(ts_val);
-                        }
-                    }
-                    "message" => {
-                        // Message entry - extract the nested message object
-                        let created = val.get("timestamp").and_then(parse_timestamp);
-
-                        if let Some(msg) = val.get("message") {
-                            let role = msg
-                                .get("role")
-                                .and_then(|v| v.as_str())
-                                .unwrap_or("unknown");
-
-                            // Normalize role names
-                            let normalized_role = match role {
-                                "user" => "user",
-                                "assistant" => "assistant",
-                                "toolResult" => "tool",
-                                _ => role,
-                            };
-
-                            // Extract content
-                            let content_str = msg
-                                .get("content")
-                                .map(Self::flatten_message_content)
-                                .unwrap_or_default();
-
-                            if content_str.trim().is_empty() {
-                                continue;
-                            }
-
-                            // Update timestamps
-                            if started_at.is_none() {
-                                started_at = created;
-                            }
-                            ended_at = created.or(ended_at);
-
-                            // Extract author (model) for assistant messages
-                            // Check message.model first, fall back to tracked model_id
-                            let author = if normalized_role == "assistant" {
-                                msg.get("model")
-                                    .and_then(|v| v.as_str())
-                                    .map(String::from)
-                                    .or_else(|| model_id.clone())
-                            } else {
-                                None
-                            };
-
-                            messages.push(NormalizedMessage {
-                                idx: messages.len() as i64,
-                                role: normalized_role.to_string(),
-                                author,
-                                created_at: created,
-                                content: content_str,
-                                extra: val.clone(),
-                                snippets: Vec::new(),
-                            });
-                        }
-                    }
-                    "model_change" => {
-                        // Track model changes (useful metadata)
-                        provider = val
-                            .get("provider")
-                            .and_then(|v| v.as_str())
-                            .map(String::from);
-                        model_id = val
-                            .get("modelId")
-                            .and_then(|v| v.as_str())
-                            .map(String::from);
-                    }
-                    _ => {
-                        // Skip thinking_level_change and unknown types
-                    }
-                }
-            }
-
-            if messages.is_empty() {
-                continue;
-            }
-
-            // Extract title from first user message
-            let title = messages
-                .iter()
-                .find(|m| m.role == "user")
-                .map(|m| {
-                    m.content
-                        .lines()
-                        .next()
-                        .unwrap_or(&m.content)
-                        .chars()
-                        .take(100)
-                        .collect::<String>()
-                })
-                .or_else(|| {
-                    messages
-                        .first()
-                        .and_then(|m| m.content.lines().next())
-                        .map(|s| s.chars().take(100).collect())
-                });
-
-            // Build metadata
-            let metadata = serde_json::json!({
-                "source": "pi_agent",
-                "session_id": session_id,
-                "provider": provider,
-                "model_id": model_id,
-            });
-
-            convs.push(NormalizedConversation {
-                agent_slug: "pi_agent".to_string(),
-                external_id,
-                title,
-                workspace: session_cwd,
-                source_path: source_path.clone(),
-                started_at,
-                ended_at,
-                metadata,
-                messages,
-            });
-        }
-
+        crate::connectors::parse_files(
+            files,
+            |file| self.parse_file(ctx, &home, file),
+            &mut |conv| {
+                convs.push(conv);
+                Ok(())
+            },
+        )?;
         Ok(convs)
     }
 }
@@ -0,0 +1,241 @@
+//! Connector for Plandex (https://github.com/plandex-ai/plandex)
+//!
+//! The Plandex server (local mode) keeps plan state under its home directory
+//! (`~/.plandex-home-v2`, or `PLANDEX_BASE_DIR`):
+//! - `orgs/<org-id>/plans/<plan-id>/conversation/<message-id>.json`, one file per
+//!   message with `role`, `num`, `message` and `createdAt`
+//!
+//! Each repo using Plandex has a `.plandex-v2` (older releases: `.plandex`) directory
+//! whose JSON settings reference the plan ids created there. Like the aider connector,
+//! cass walks known roots for those directories and uses them to attach each plan
+//! to its repo as the workspace.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde_json::Value;
+use walkdir::WalkDir;
+
+use crate::connectors::{
+    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    parse_timestamp,
+};
+
+/// Per-repo directory names, newest first.
+const PROJECT_DIRS: &[&str] = &[".plandex-v2", ".plandex"];
+
+pub struct PlandexConnector;
+
+impl Default for PlandexConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlandexConnector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Get the Plandex server home directory.
+    /// Checks PLANDEX_BASE_DIR env var, then the configured `plandex.root`,
+    /// falls back to ~/.plandex-home-v2/
+    pub fn home() -> PathBuf {
+        crate::config::env_root("plandex", "PLANDEX_BASE_DIR").unwrap_or_else(|| {
+            dirs::home_dir()
+                .unwrap_or_default()
+                .join(".plandex-home-v2")
+        })
+    }
+
+    /// Plan conversation directories under a Plandex home.
+    fn conversation_dirs(home: &Path) -> Vec<PathBuf> {
+        let orgs = home.join("orgs");
+        if !orgs.exists() {
+            return Vec::new();
+        }
+        let mut out: Vec<PathBuf> =
+            crate::connectors::walk(WalkDir::new(orgs).min_depth(4).max_depth(4))
+                .filter(|e| e.file_type().is_dir() && e.file_name() == "conversation")
+                .map(|e| e.path().to_path_buf())
+                .collect();
+        out.sort();
+        out
+    }
+
+    /// Map plan ids to the repo whose `.plandex*` directory references them
+    /// (limited depth to avoid wide scans).
+    fn plan_workspaces(roots: &[&Path]) -> HashMap<String, PathBuf> {
+        let mut map = HashMap::new();
+        for root in roots {
+            if !root.exists() {
+                continue;
+            }
+            for entry in crate::connectors::walk(WalkDir::new(root).max_depth(5))
+                .filter(|e| e.file_type().is_file())
+            {
+                let path = entry.path();
+                let in_project_dir = path
+                    .parent()
+                    .and_then(|p| p.file_name())
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| PROJECT_DIRS.contains(&n));
+                if !in_project_dir || path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    continue;
+                }
+                let Some(repo) = path.parent().and_then(Path::parent) else {
+                    continue;
+                };
+                let Some(val) = fs::read_to_string(path)
+                    .ok()
+                    .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+                else {
+                    continue;
+                };
+                let mut ids = Vec::new();
+                collect_strings(&val, &mut ids);
+                for id in ids {
+                    map.entry(id).or_insert_with(|| repo.to_path_buf());
+                }
+            }
+        }
+        map
+    }
+
+    fn read_messages(dir: &Path) -> Vec<(i64, Value)> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut out: Vec<(i64, Value)> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("json"))
+            .filter_map(|p| serde_json::from_str::<Value>(&fs::read_to_string(p).ok()?).ok())
+            .map(|v| (v.get("num").and_then(|n| n.as_i64()).unwrap_or(i64::MAX), v))
+            .collect();
+        out.sort_by_key(|(num, _)| *num);
+        out
+    }
+}
+
+fn collect_strings(val: &Value, out: &mut Vec<String>) {
+    match val {
+        Value::String(s) => out.push(s.clone()),
+        Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
+
+impl Connector for PlandexConnector {
+    fn detect(&self) -> DetectionResult {
+        let home = Self::home();
+        if home.join("orgs").exists() {
+            return DetectionResult {
+                detected: true,
+                evidence: vec![format!("found {}", home.display())],
+            };
+        }
+        let cwd = std::env::current_dir().unwrap_or_default();
+        for name in PROJECT_DIRS {
+            if cwd.join(name).is_dir() {
+                return DetectionResult {
+                    detected: true,
+                    evidence: vec![format!("found {}", cwd.join(name).display())],
+                };
+            }
+        }
+        DetectionResult::not_found()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        // Use data_root if it looks like a Plandex home (for testing)
+        let home = if ctx.data_root.join("orgs").exists() {
+            ctx.data_root.clone()
+        } else {
+            Self::home()
+        };
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let workspaces = Self::plan_workspaces(&[ctx.data_root.as_path(), cwd.as_path()]);
+
+        let mut convs = Vec::new();
+        for dir in Self::conversation_dirs(&home) {
+            let Some(plan_dir) = dir.parent() else {
+                continue;
+            };
+            let plan_id = plan_dir
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default()
+                .to_string();
+            // A plan changes by gaining message files, which bumps the directory mtime.
+            if !ctx.file_changed(&dir) {
+                continue;
+            }
+
+            let mut messages = Vec::new();
+            for (_, val) in Self::read_messages(&dir) {
+                let content = val
+                    .get("message")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                if content.trim().is_empty() {
+                    continue;
+                }
+                let role = val
+                    .get("role")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+                messages.push(NormalizedMessage {
+                    idx: messages.len() as i64,
+                    role,
+                    author: None,
+                    created_at: val.get("createdAt").and_then(parse_timestamp),
+                    content,
+                    extra: val,
+                    snippets: Vec::new(),
+                });
+            }
+            if messages.is_empty() {
+                continue;
+            }
+
+            let title = messages
+                .iter()
+                .find(|m| m.role == "user")
+                .or_else(|| messages.first())
+                .and_then(|m| m.content.lines().next())
+                .map(|s| s.chars().take(100).collect());
+            let started_at = messages.iter().filter_map(|m| m.created_at).min();
+            let ended_at = messages.iter().filter_map(|m| m.created_at).max();
+            let org_id = plan_dir
+                .parent()
+                .and_then(Path::parent)
+                .and_then(|p| p.file_name())
+                .and_then(|n| n.to_str())
+                .map(String::from);
+
+            convs.push(NormalizedConversation {
+                agent_slug: "plandex".to_string(),
+                external_id: Some(plan_id.clone()),
+                title,
+                workspace: workspaces.get(&plan_id).cloned(),
+                source_path: dir.clone(),
+                started_at,
+                ended_at,
+                metadata: serde_json::json!({
+                    "source": "plandex",
+                    "plan_id": plan_id,
+                    "org_id": org_id,
+                }),
+                messages,
+            });
+        }
+
+        Ok(convs)
+    }
+}
@@ -0,0 +1,130 @@
+//! Session files that failed to parse.
+//!
+//! A connector that cannot parse a file (or some lines of a JSONL log) reports it
+//! through [`ScanContext::quarantine`](super::ScanContext::quarantine) instead of
+//! skipping it silently. The indexer keeps the list next to its per-file
+//! checkpoints and writes it to `quarantine.jsonl` in the data dir, one
+//! [`QuarantineEntry`] per line; `cass index` reports how many there are.
+//!
+//! A file leaves quarantine when its content changes and it parses on the next scan.
+//! Unchanged files are not rescanned, so their entries persist across runs.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+const FILE_NAME: &str = "quarantine.jsonl";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    pub path: PathBuf,
+    pub agent: String,
+    pub error: String,
+    /// Unix ms when the failure was recorded.
+    pub quarantined_at: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct Quarantine {
+    path: PathBuf,
+    entries: Mutex<BTreeMap<PathBuf, QuarantineEntry>>,
+}
+
+impl Quarantine {
+    /// Load the list for `data_dir`; unreadable lines are dropped.
+    pub fn load(data_dir: &Path) -> Self {
+        let path = data_dir.join(FILE_NAME);
+        let entries = read_entries(&path)
+            .into_iter()
+            .map(|e| (e.path.clone(), e))
+            .collect();
+        Self {
+            path,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    pub fn clear(&self) {
+        lock(&self.entries).clear();
+    }
+
+    pub fn len(&self) -> usize {
+        lock(&self.entries).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn entries(&self) -> Vec<QuarantineEntry> {
+        lock(&self.entries).values().cloned().collect()
+    }
+
+    /// Forget `path` before it is rescanned; a failure re-records it.
+    pub fn release(&self, path: &Path) {
+        lock(&self.entries).remove(path);
+    }
+
+    /// Record the first failure seen for `path` in this scan.
+    pub fn record(&self, agent: &str, path: &Path, error: String) {
+        lock(&self.entries)
+            .entry(path.to_path_buf())
+            .or_insert_with(|| QuarantineEntry {
+                path: path.to_path_buf(),
+                agent: agent.to_string(),
+                error,
+                quarantined_at: chrono::Utc::now().timestamp_millis(),
+            });
+    }
+
+    /// Write the list to disk; an empty list removes the file.
+    pub fn commit(&self) -> Result<()> {
+        let entries = lock(&self.entries);
+        if entries.is_empty() {
+            match std::fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(e).with_context(|| format!("removing {}", self.path.display()));
+                }
+                _ => return Ok(()),
+            }
+        }
+        let mut out = String::new();
+        for entry in entries.values() {
+            out.push_str(&serde_json::to_string(entry)?);
+            out.push('\n');
+        }
+        let tmp = self.path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, out).with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("writing {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+fn lock<T>(m: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn read_entries(path: &Path) -> Vec<QuarantineEntry> {
+    std::fs::read_to_string(path)
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|l| serde_json::from_str(l).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Entries currently quarantined under `data_dir`.
+pub fn entries(data_dir: &Path) -> Vec<QuarantineEntry> {
+    read_entries(&data_dir.join(FILE_NAME))
+}
+
+/// Where the list lives for `data_dir`.
+pub fn file_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(FILE_NAME)
+}
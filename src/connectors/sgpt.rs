@@ -0,0 +1,178 @@
+//! Connector for shell-gpt / sgpt (https://github.com/TheR1D/shell_gpt)
+//!
+//! sgpt caches `--chat <id>` sessions under `~/.config/shell_gpt/chat_cache/`:
+//! - One file per chat, named after the chat id (no extension)
+//! - Each file is a JSON array of `{"role": "...", "content": "..."}` messages
+//!
+//! The cache carries no timestamps, so the file mtime stands in for every message.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::connectors::{
+    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    flatten_content,
+};
+
+pub struct SgptConnector;
+
+impl Default for SgptConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SgptConnector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Get the sgpt chat cache directory.
+    /// Checks the CHAT_CACHE_PATH env var sgpt itself honors, falls back to
+    /// the configured `sgpt.root`, then ~/.config/shell_gpt/chat_cache/
+    pub fn cache_dir() -> PathBuf {
+        crate::config::env_root("sgpt", "CHAT_CACHE_PATH").unwrap_or_else(|| {
+            dirs::home_dir()
+                .unwrap_or_default()
+                .join(".config/shell_gpt/chat_cache")
+        })
+    }
+
+    /// Chat files directly inside the cache directory.
+    fn chat_files(root: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(root) else {
+            return Vec::new();
+        };
+        let mut out: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file())
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| !n.starts_with('.'))
+            })
+            .collect();
+        out.sort();
+        out
+    }
+
+    fn mtime_millis(path: &Path) -> Option<i64> {
+        let modified = fs::metadata(path).ok()?.modified().ok()?;
+        let dur = modified.duration_since(UNIX_EPOCH).ok()?;
+        Some(dur.as_millis() as i64)
+    }
+}
+
+impl Connector for SgptConnector {
+    fn detect(&self) -> DetectionResult {
+        let dir = Self::cache_dir();
+        if dir.is_dir() {
+            DetectionResult {
+                detected: true,
+                evidence: vec![format!("found {}", dir.display())],
+            }
+        } else {
+            DetectionResult::not_found()
+        }
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        // Use data_root if it looks like an sgpt cache directory (for testing)
+        let is_cache_dir = ctx
+            .data_root
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n == "chat_cache");
+        let root = if is_cache_dir {
+            ctx.data_root.clone()
+        } else {
+            Self::cache_dir()
+        };
+
+        let mut convs = Vec::new();
+        for file in Self::chat_files(&root) {
+            if !ctx.file_changed(&file) {
+                continue;
+            }
+
+            // One unreadable chat must not stop the others from being indexed.
+            let content = match fs::read_to_string(&file) {
+                Ok(content) => content,
+                Err(e) => {
+                    ctx.quarantine("sgpt", &file, e);
+                    continue;
+                }
+            };
+            // Other tools may drop stray files in the cache; skip anything that isn't a chat.
+            let Ok(Value::Array(entries)) = serde_json::from_str::<Value>(&content) else {
+                continue;
+            };
+
+            let chat_id = file
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default()
+                .to_string();
+            let modified = Self::mtime_millis(&file);
+
+            let mut messages = Vec::new();
+            for entry in entries {
+                let role = entry
+                    .get("role")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+                let text = entry
+                    .get("content")
+                    .map(flatten_content)
+                    .unwrap_or_default();
+                if text.trim().is_empty() {
+                    continue;
+                }
+                messages.push(NormalizedMessage {
+                    idx: messages.len() as i64,
+                    role,
+                    author: None,
+                    created_at: modified,
+                    content: text,
+                    extra: entry,
+                    snippets: Vec::new(),
+                });
+            }
+
+            if messages.is_empty() {
+                continue;
+            }
+
+            let title = messages
+                .iter()
+                .find(|m| m.role == "user")
+                .or_else(|| messages.first())
+                .and_then(|m| m.content.lines().next())
+                .map(|s| s.chars().take(100).collect());
+
+            convs.push(NormalizedConversation {
+                agent_slug: "sgpt".to_string(),
+                external_id: Some(chat_id.clone()),
+                title,
+                workspace: None,
+                source_path: file.clone(),
+                started_at: modified,
+                ended_at: modified,
+                metadata: serde_json::json!({
+                    "source": "sgpt",
+                    "chat_id": chat_id,
+                    "timestamps": "file_mtime",
+                }),
+                messages,
+            });
+        }
+
+        Ok(convs)
+    }
+}
@@ -0,0 +1,215 @@
+//! Shared helpers for connectors whose agent keeps history in SQLite (Cursor,
+//! OpenCode, Crush, Open WebUI).
+//!
+//! The agent is usually running while we scan, so stores are opened read-only with
+//! a busy timeout instead of failing on the first `SQLITE_BUSY`. Schemas drift
+//! between agent versions, so rows are read by column name through [`Columns`],
+//! and [`MessageMapping`] turns a row into a [`NormalizedMessage`] from a list of
+//! candidate column names per field.
+//!
+//! Built with the default `sqlite-connectors` feature. Without it, [`open_readonly`]
+//! fails and the SQLite-backed connectors skip their stores with a warning.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+use rusqlite::{Connection, Row};
+use serde_json::Value;
+
+use crate::connectors::NormalizedMessage;
+
+/// How long a read waits for the agent's write lock before giving up.
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Open `path` read-only, waiting up to [`BUSY_TIMEOUT`] on a locked database.
+#[cfg(feature = "sqlite-connectors")]
+pub fn open_readonly(path: &Path) -> Result<Connection> {
+    use anyhow::Context;
+    let conn = Connection::open_with_flags(
+        path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .with_context(|| format!("opening SQLite store {}", path.display()))?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    Ok(conn)
+}
+
+#[cfg(not(feature = "sqlite-connectors"))]
+pub fn open_readonly(path: &Path) -> Result<Connection> {
+    anyhow::bail!(
+        "cannot read SQLite store {}: cass was built without the `sqlite-connectors` feature",
+        path.display()
+    )
+}
+
+pub fn has_table(conn: &Connection, name: &str) -> Result<bool> {
+    let mut stmt =
+        conn.prepare("SELECT name FROM sqlite_master WHERE type='table' AND name = ?1 LIMIT 1")?;
+    Ok(stmt.exists([name])?)
+}
+
+/// Column names of `table`, in declaration order (the order of `SELECT *`).
+pub fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(1))?; // 1 = name
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+/// Name-based access to rows of a `SELECT *`.
+#[derive(Debug, Clone)]
+pub struct Columns(Vec<String>);
+
+impl Columns {
+    pub fn of(conn: &Connection, table: &str) -> Result<Self> {
+        table_columns(conn, table).map(Self)
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.0
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.position(name).is_some()
+    }
+
+    /// First of `names` present in the table, e.g. to pick an `ORDER BY` column.
+    pub fn first_of<'a>(&self, names: &[&'a str]) -> Option<&'a str> {
+        names.iter().copied().find(|n| self.has(n))
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.0.iter().position(|c| c == name)
+    }
+
+    /// First non-null text value among `names`.
+    pub fn string(&self, row: &Row<'_>, names: &[&str]) -> rusqlite::Result<Option<String>> {
+        for name in names {
+            if let Some(idx) = self.position(name)
+                && let Some(v) = row.get::<_, Option<String>>(idx)?
+            {
+                return Ok(Some(v));
+            }
+        }
+        Ok(None)
+    }
+
+    /// First non-null integer value among `names`.
+    pub fn i64(&self, row: &Row<'_>, names: &[&str]) -> rusqlite::Result<Option<i64>> {
+        for name in names {
+            if let Some(idx) = self.position(name)
+                && let Some(v) = row.get::<_, Option<i64>>(idx)?
+            {
+                return Ok(Some(v));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The whole row as a JSON object; blobs are base64-encoded.
+    pub fn to_json(&self, row: &Row<'_>) -> Value {
+        let mut obj = serde_json::Map::new();
+        for (idx, name) in self.0.iter().enumerate() {
+            if let Ok(val) = row.get::<_, rusqlite::types::Value>(idx) {
+                obj.insert(name.clone(), value_to_json(val));
+            }
+        }
+        Value::Object(obj)
+    }
+}
+
+pub fn value_to_json(v: rusqlite::types::Value) -> Value {
+    use base64::Engine;
+    use rusqlite::types::Value as V;
+    match v {
+        V::Null => Value::Null,
+        V::Integer(i) => Value::from(i),
+        V::Real(f) => Value::from(f),
+        V::Text(t) => Value::from(t),
+        V::Blob(b) => Value::from(base64::engine::general_purpose::STANDARD.encode(b)),
+    }
+}
+
+/// Candidate column names for each message field; the first non-null one wins.
+#[derive(Debug, Clone, Copy)]
+pub struct MessageMapping {
+    pub role: &'static [&'static str],
+    pub author: &'static [&'static str],
+    pub created_at: &'static [&'static str],
+    pub content: &'static [&'static str],
+    /// Role used when no role column has a value.
+    pub default_role: &'static str,
+}
+
+impl MessageMapping {
+    /// Map one row. `idx` is left at 0 for the caller to assign once rows are grouped
+    /// and ordered; the full row is kept in `extra`.
+    pub fn message(&self, row: &Row<'_>, cols: &Columns) -> rusqlite::Result<NormalizedMessage> {
+        Ok(NormalizedMessage {
+            idx: 0,
+            role: cols
+                .string(row, self.role)?
+                .unwrap_or_else(|| self.default_role.to_string()),
+            author: cols.string(row, self.author)?,
+            created_at: cols.i64(row, self.created_at)?,
+            content: cols.string(row, self.content)?.unwrap_or_default(),
+            extra: cols.to_json(row),
+            snippets: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_rows_by_column_name_while_the_store_is_locked() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("chat.db");
+        let writer = Connection::open(&path).unwrap();
+        writer
+            .execute_batch(
+                "CREATE TABLE messages (id INTEGER, sender TEXT, text TEXT, ts INTEGER, raw BLOB);
+                 INSERT INTO messages VALUES (1, 'user', 'hello', 1700000000000, x'00ff');
+                 INSERT INTO messages VALUES (2, NULL, NULL, NULL, NULL);",
+            )
+            .unwrap();
+
+        let conn = open_readonly(&path).unwrap();
+        assert!(has_table(&conn, "messages").unwrap());
+        assert!(!has_table(&conn, "sessions").unwrap());
+        let cols = Columns::of(&conn, "messages").unwrap();
+        assert_eq!(cols.first_of(&["created_at", "ts"]), Some("ts"));
+
+        let mapping = MessageMapping {
+            role: &["role", "sender"],
+            author: &["author", "sender"],
+            created_at: &["created_at", "ts"],
+            content: &["content", "text"],
+            default_role: "agent",
+        };
+        let mut stmt = conn.prepare("SELECT * FROM messages ORDER BY id").unwrap();
+        let msgs: Vec<_> = stmt
+            .query_map([], |row| mapping.message(row, &cols))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(msgs[0].role, "user");
+        assert_eq!(msgs[0].author.as_deref(), Some("user"));
+        assert_eq!(msgs[0].content, "hello");
+        assert_eq!(msgs[0].created_at, Some(1_700_000_000_000));
+        assert_eq!(msgs[0].extra["raw"], "AP8=");
+        assert_eq!(msgs[1].role, "agent");
+        assert_eq!(msgs[1].content, "");
+
+        // Reads keep working while the agent holds a write transaction.
+        writer.execute_batch("BEGIN IMMEDIATE").unwrap();
+        assert_eq!(
+            conn.query_row("SELECT count(*) FROM messages", [], |r| r.get::<_, i64>(0))
+                .unwrap(),
+            2
+        );
+        writer.execute_batch("ROLLBACK").unwrap();
+    }
+}
@@ -0,0 +1,272 @@
+//! Connector for agent trajectories imported with `cass import --format trajectory`.
+//!
+//! Research harnesses record runs out-of-band, so there is no live session dir to watch.
+//! `cass import` copies each trajectory to `<data_dir>/imports/trajectory/<name>.json`
+//! and this connector re-reads that directory, so imports survive `cass index --full`.
+//!
+//! Two shapes are understood:
+//! - SWE-agent `.traj`: an object with `history` (chat messages tagged with
+//!   `message_type`) and/or `trajectory` steps (`thought`, `action`, `observation`).
+//! - Event logs (OpenHands/Devin-style): an array, or an object with `events`, of
+//!   records carrying `source` (`user`/`agent`/`environment`), `message`/`content`,
+//!   an optional `action` with `args`, and an ISO `timestamp`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+
+use crate::connectors::{
+    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    flatten_content, parse_timestamp,
+};
+
+pub struct TrajectoryConnector;
+
+impl Default for TrajectoryConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrajectoryConnector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Where `cass import` stores trajectories under a data dir.
+    pub fn import_dir(data_dir: &Path) -> PathBuf {
+        data_dir.join("imports").join("trajectory")
+    }
+
+    /// Parse a trajectory file, failing if it matches neither supported shape.
+    pub fn parse_file(path: &Path) -> Result<NormalizedConversation> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("read trajectory {}", path.display()))?;
+        let val: Value = serde_json::from_str(&content)
+            .with_context(|| format!("parse trajectory {}", path.display()))?;
+
+        let (format, messages) = if let Some(events) = val
+            .as_array()
+            .or_else(|| val.get("events").and_then(|e| e.as_array()))
+        {
+            ("events", event_messages(events))
+        } else if let Some(history) = val.get("history").and_then(|h| h.as_array())
+            && !history.is_empty()
+        {
+            ("swe-agent", history_messages(history))
+        } else if let Some(steps) = val.get("trajectory").and_then(|t| t.as_array()) {
+            ("swe-agent", step_messages(steps))
+        } else {
+            bail!(
+                "{} is not a trajectory: expected `history`/`trajectory` (SWE-agent) or an event list",
+                path.display()
+            );
+        };
+        if messages.is_empty() {
+            bail!("{} contains no messages", path.display());
+        }
+
+        let info = val.get("info").cloned().unwrap_or(Value::Null);
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("trajectory")
+            .to_string();
+        let title = info
+            .get("instance_id")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .or_else(|| {
+                messages
+                    .iter()
+                    .find(|m| m.role == "user")
+                    .and_then(|m| m.content.lines().find(|l| !l.trim().is_empty()))
+                    .map(|s| s.chars().take(100).collect())
+            });
+        let started_at = messages.iter().filter_map(|m| m.created_at).min();
+        let ended_at = messages.iter().filter_map(|m| m.created_at).max();
+
+        Ok(NormalizedConversation {
+            agent_slug: "trajectory".to_string(),
+            external_id: Some(stem),
+            title,
+            workspace: None,
+            source_path: path.to_path_buf(),
+            started_at,
+            ended_at,
+            metadata: serde_json::json!({
+                "source": "trajectory",
+                "format": format,
+                "exit_status": info.get("exit_status"),
+                "model_stats": info.get("model_stats"),
+            }),
+            messages,
+        })
+    }
+}
+
+fn push(
+    messages: &mut Vec<NormalizedMessage>,
+    role: &str,
+    content: String,
+    created_at: Option<i64>,
+    extra: Value,
+) {
+    if content.trim().is_empty() {
+        return;
+    }
+    messages.push(NormalizedMessage {
+        idx: messages.len() as i64,
+        role: role.to_string(),
+        author: None,
+        created_at,
+        content,
+        extra,
+        snippets: Vec::new(),
+    });
+}
+
+/// SWE-agent `history`: observations arrive as `user` turns but are tool output.
+fn history_messages(history: &[Value]) -> Vec<NormalizedMessage> {
+    let mut messages = Vec::new();
+    for item in history {
+        let kind = item.get("message_type").and_then(|v| v.as_str());
+        let role = match (item.get("role").and_then(|v| v.as_str()), kind) {
+            (_, Some("observation")) => "tool",
+            (Some(role), _) => role,
+            (None, _) => continue,
+        };
+        let content = item.get("content").map(flatten_content).unwrap_or_default();
+        let extra = serde_json::json!({
+            "agent": item.get("agent"),
+            "message_type": kind,
+        });
+        push(&mut messages, role, content, None, extra);
+    }
+    messages
+}
+
+/// SWE-agent `trajectory` steps, used when the file carries no `history`.
+fn step_messages(steps: &[Value]) -> Vec<NormalizedMessage> {
+    let text = |step: &Value, key: &str| {
+        step.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    let mut messages = Vec::new();
+    for step in steps {
+        let thought = text(step, "thought");
+        let action = text(step, "action");
+        let mut content = if thought.is_empty() {
+            text(step, "response")
+        } else {
+            thought
+        };
+        if !action.is_empty() {
+            content = format!("{content}\n[Action] {action}");
+        }
+        push(
+            &mut messages,
+            "assistant",
+            content.trim().to_string(),
+            None,
+            serde_json::json!({ "execution_time": step.get("execution_time") }),
+        );
+        push(
+            &mut messages,
+            "tool",
+            text(step, "observation"),
+            None,
+            Value::Null,
+        );
+    }
+    messages
+}
+
+/// Event logs: `agent` actions become tool calls, `environment` output becomes tool turns.
+fn event_messages(events: &[Value]) -> Vec<NormalizedMessage> {
+    let mut messages = Vec::new();
+    for event in events {
+        let Some(source) = event.get("source").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let created_at = event.get("timestamp").and_then(parse_timestamp);
+        let mut content = event
+            .get("message")
+            .or_else(|| event.get("content"))
+            .map(flatten_content)
+            .unwrap_or_default();
+        if let Some(action) = event.get("action").and_then(|v| v.as_str())
+            && !matches!(action, "message" | "system")
+        {
+            let args = event.get("args");
+            let detail = ["command", "code", "path", "url"]
+                .iter()
+                .find_map(|k| args.and_then(|a| a.get(*k)).and_then(|v| v.as_str()))
+                .unwrap_or_default();
+            if !content.contains(detail) || content.trim().is_empty() {
+                content = format!("{content}\n[Tool: {action}] {detail}");
+            }
+        }
+        let role = match source {
+            "user" => "user",
+            "environment" => "tool",
+            _ if event.get("observation").is_some() => "tool",
+            _ => "assistant",
+        };
+        push(
+            &mut messages,
+            role,
+            content.trim().to_string(),
+            created_at,
+            serde_json::json!({
+                "id": event.get("id"),
+                "action": event.get("action"),
+                "observation": event.get("observation"),
+            }),
+        );
+    }
+    messages
+}
+
+impl Connector for TrajectoryConnector {
+    fn detect(&self) -> DetectionResult {
+        // Imports live under the data dir, which is only known at scan time.
+        DetectionResult {
+            detected: true,
+            evidence: vec!["imported trajectories are read from <data_dir>/imports".to_string()],
+        }
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        let dir = Self::import_dir(&ctx.data_root);
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return Ok(Vec::new());
+        };
+        let mut files: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "json"))
+            .collect();
+        files.sort();
+        files.retain(|file| ctx.file_changed(file));
+
+        let mut convs = Vec::new();
+        let parse = |file: &Path| match Self::parse_file(file) {
+            Ok(conv) => Ok(Some(conv)),
+            Err(e) => {
+                ctx.quarantine("trajectory", file, format!("{e:#}"));
+                Ok(None)
+            }
+        };
+        crate::connectors::parse_files(files, parse, &mut |conv| {
+            convs.push(conv);
+            Ok(())
+        })?;
+        Ok(convs)
+    }
+}
@@ -0,0 +1,345 @@
+//! Connector for VS Code's built-in chat sessions.
+//!
+//! Recent VS Code builds persist every chat panel session, whichever provider
+//! (Copilot, a third-party extension, ...) answered it, as JSON under the user dir:
+//! - `workspaceStorage/<hash>/chatSessions/<session-id>.json`
+//! - `globalStorage/emptyWindowChatSessions/<session-id>.json` (no folder open)
+//!
+//! Each `workspaceStorage/<hash>/workspace.json` names the folder (`folder`) or
+//! `.code-workspace` file (`workspace`) as a `file://` URI; that becomes the workspace.
+//! A session holds `requests`, each a user `message` plus the `response` parts.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::connectors::{
+    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+};
+
+/// VS Code flavors sharing the chat session format.
+const PRODUCTS: &[&str] = &["Code", "Code - Insiders", "VSCodium"];
+
+pub struct VsCodeChatConnector;
+
+impl Default for VsCodeChatConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VsCodeChatConnector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// VS Code `User` directories that exist on this machine.
+    /// CASS_VSCODE_USER_DIR, then the configured `vscode_chat.root`, replaces the
+    /// platform defaults.
+    pub fn user_dirs() -> Vec<PathBuf> {
+        if let Some(dir) = crate::config::env_root("vscode_chat", "CASS_VSCODE_USER_DIR") {
+            return vec![dir];
+        }
+        let Some(home) = dirs::home_dir() else {
+            return Vec::new();
+        };
+        PRODUCTS
+            .iter()
+            .flat_map(|product| {
+                [
+                    home.join(".config").join(product).join("User"),
+                    home.join("Library/Application Support")
+                        .join(product)
+                        .join("User"),
+                    home.join("AppData/Roaming").join(product).join("User"),
+                ]
+            })
+            .filter(|p| p.exists())
+            .collect()
+    }
+
+    /// `(session file, workspace)` pairs under a user dir.
+    fn session_files(user_dir: &Path) -> Vec<(PathBuf, Option<PathBuf>)> {
+        let mut out = Vec::new();
+        if let Ok(entries) = fs::read_dir(user_dir.join("workspaceStorage")) {
+            for entry in entries.flatten() {
+                let storage = entry.path();
+                let sessions = storage.join("chatSessions");
+                if !sessions.is_dir() {
+                    continue;
+                }
+                let workspace = Self::resolve_workspace(&storage.join("workspace.json"));
+                for file in json_files(&sessions) {
+                    out.push((file, workspace.clone()));
+                }
+            }
+        }
+        let empty_window = user_dir.join("globalStorage/emptyWindowChatSessions");
+        for file in json_files(&empty_window) {
+            out.push((file, None));
+        }
+        out.sort();
+        out
+    }
+
+    /// Folder named by a `workspace.json`.
+    fn resolve_workspace(path: &Path) -> Option<PathBuf> {
+        let val: Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+        let uri = val
+            .get("folder")
+            .or_else(|| val.get("workspace"))
+            .and_then(|v| v.as_str())?;
+        let path = file_uri_to_path(uri)?;
+        // A multi-root `.code-workspace` file lives in the folder it describes.
+        if path.extension().is_some_and(|e| e == "code-workspace") {
+            return path.parent().map(Path::to_path_buf);
+        }
+        Some(path)
+    }
+
+    /// Flatten a request's `response` parts to searchable text.
+    fn response_text(parts: &Value) -> String {
+        let Some(arr) = parts.as_array() else {
+            return String::new();
+        };
+        arr.iter()
+            .filter_map(|part| {
+                let kind = part.get("kind").and_then(|k| k.as_str());
+                match kind {
+                    None => part.get("value").and_then(|v| v.as_str()).map(String::from),
+                    Some("markdownContent") => part
+                        .get("content")
+                        .and_then(|c| c.get("value"))
+                        .and_then(|v| v.as_str())
+                        .map(String::from),
+                    Some("toolInvocationSerialized") => {
+                        let tool = part
+                            .get("toolId")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("unknown");
+                        let msg = part
+                            .get("invocationMessage")
+                            .and_then(|m| m.as_str().or_else(|| m.get("value")?.as_str()))
+                            .unwrap_or_default();
+                        // Tool calls interrupt the markdown stream; keep them on their own line.
+                        Some(format!("\n[Tool: {tool}] {msg}\n"))
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+
+    fn parse_session(
+        path: &Path,
+        workspace: Option<PathBuf>,
+    ) -> Result<Option<NormalizedConversation>> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("read vscode chat session {}", path.display()))?;
+        let Ok(val) = serde_json::from_str::<Value>(&content) else {
+            return Ok(None);
+        };
+        let Some(requests) = val.get("requests").and_then(|r| r.as_array()) else {
+            return Ok(None);
+        };
+        let responder = val
+            .get("responderUsername")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+
+        let mut messages = Vec::new();
+        for req in requests {
+            let ts = req.get("timestamp").and_then(|v| v.as_i64());
+            let prompt = req
+                .get("message")
+                .and_then(|m| m.get("text"))
+                .and_then(|t| t.as_str())
+                .unwrap_or_default();
+            if !prompt.trim().is_empty() {
+                messages.push(NormalizedMessage {
+                    idx: messages.len() as i64,
+                    role: "user".into(),
+                    author: None,
+                    created_at: ts,
+                    content: prompt.to_string(),
+                    extra: req.get("message").cloned().unwrap_or(Value::Null),
+                    snippets: Vec::new(),
+                });
+            }
+
+            let answer = req
+                .get("response")
+                .map(Self::response_text)
+                .unwrap_or_default();
+            if !answer.trim().is_empty() {
+                let elapsed = req
+                    .pointer("/result/timings/totalElapsed")
+                    .and_then(|v| v.as_i64());
+                let model = req
+                    .get("modelId")
+                    .and_then(|v| v.as_str())
+                    .map(String::from);
+                messages.push(NormalizedMessage {
+                    idx: messages.len() as i64,
+                    role: "assistant".into(),
+                    author: model.or_else(|| responder.clone()),
+                    created_at: ts.map(|t| t + elapsed.unwrap_or(0)),
+                    content: answer,
+                    extra: serde_json::json!({
+                        "requestId": req.get("requestId"),
+                        "result": req.get("result"),
+                    }),
+                    snippets: Vec::new(),
+                });
+            }
+        }
+        if messages.is_empty() {
+            return Ok(None);
+        }
+
+        let session_id = val
+            .get("sessionId")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .or_else(|| path.file_stem().and_then(|s| s.to_str()).map(String::from));
+        let title = val
+            .get("customTitle")
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .or_else(|| {
+                messages
+                    .iter()
+                    .find(|m| m.role == "user")
+                    .and_then(|m| m.content.lines().next())
+                    .map(|s| s.chars().take(100).collect())
+            });
+        let started_at = val
+            .get("creationDate")
+            .and_then(|v| v.as_i64())
+            .or_else(|| messages.first().and_then(|m| m.created_at));
+        let ended_at = val
+            .get("lastMessageDate")
+            .and_then(|v| v.as_i64())
+            .or_else(|| messages.last().and_then(|m| m.created_at));
+
+        Ok(Some(NormalizedConversation {
+            agent_slug: "vscode_chat".to_string(),
+            external_id: session_id.clone(),
+            title,
+            workspace,
+            source_path: path.to_path_buf(),
+            started_at,
+            ended_at,
+            metadata: serde_json::json!({
+                "source": "vscode_chat",
+                "session_id": session_id,
+                "responder": responder,
+                "location": val.get("initialLocation"),
+            }),
+            messages,
+        }))
+    }
+}
+
+fn json_files(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == "json"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Convert a `file://` URI (percent-encoded) to a local path.
+fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let bytes = rest.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = rest
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+            continue;
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    let path = String::from_utf8(decoded).ok()?;
+    // Windows URIs look like `file:///c:/Users/...`.
+    let is_windows_drive = path.len() > 3 && path.as_bytes()[2] == b':';
+    Some(PathBuf::from(if is_windows_drive {
+        &path[1..]
+    } else {
+        &path
+    }))
+}
+
+impl Connector for VsCodeChatConnector {
+    fn detect(&self) -> DetectionResult {
+        let evidence: Vec<String> = Self::user_dirs()
+            .into_iter()
+            .filter(|dir| !Self::session_files(dir).is_empty())
+            .map(|dir| format!("found chat sessions in {}", dir.display()))
+            .collect();
+        if evidence.is_empty() {
+            DetectionResult::not_found()
+        } else {
+            DetectionResult {
+                detected: true,
+                evidence,
+            }
+        }
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        // Use data_root if it looks like a VS Code user dir (for testing)
+        let roots = if ctx.data_root.join("workspaceStorage").exists() {
+            vec![ctx.data_root.clone()]
+        } else {
+            Self::user_dirs()
+        };
+
+        let mut convs = Vec::new();
+        for root in roots {
+            for (file, workspace) in Self::session_files(&root) {
+                if !ctx.file_changed(&file) {
+                    continue;
+                }
+                if let Some(conv) = Self::parse_session(&file, workspace)? {
+                    convs.push(conv);
+                }
+            }
+        }
+        Ok(convs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_uris_decode_to_paths() {
+        assert_eq!(
+            file_uri_to_path("file:///home/me/my%20project"),
+            Some(PathBuf::from("/home/me/my project"))
+        );
+        assert_eq!(
+            file_uri_to_path("file:///c%3A/Users/me/app"),
+            Some(PathBuf::from("c:/Users/me/app"))
+        );
+        assert_eq!(file_uri_to_path("vscode-remote://ssh/x"), None);
+    }
+}
@@ -0,0 +1,328 @@
+//! Persistent query daemon (`cass daemon`).
+//!
+//! Keeps a warm `SearchClient` (Tantivy reader, prefix cache, SQLite handle) behind a
+//! unix socket at `<data_dir>/cass.sock`. `cass search` tries the socket first and only
+//! opens the index in-process when no daemon answers, so agents that call cass in a
+//! loop skip the per-process index open and cache warmup.
+//!
+//! Protocol: one JSON request per line, one JSON response per line, one request per
+//! connection. Set `CASS_NO_DAEMON=1` to bypass delegation.
+//!
+//! Before each search the client pings the daemon and compares its version and the
+//! schema hash of the index it opened with its own version and the index on disk. A
+//! daemon left running across an upgrade or a schema rebuild is skipped, and the search
+//! runs in-process, until it is restarted.
+//!
+//! Only `cass search` delegates. The TUI is itself a long-running process that keeps
+//! its own warm `SearchClient`, so it has nothing to gain from a round trip to the
+//! daemon.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::search::query::{SearchFilters, SearchResult};
+
+/// How long a client waits on the daemon before falling back to in-process search.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum DaemonRequest {
+    Ping,
+    Search {
+        query: String,
+        filters: Box<SearchFilters>,
+        limit: usize,
+        offset: usize,
+        sparse_threshold: usize,
+    },
+}
+
+/// Externally tagged: internally tagged enums buffer their payload, which cannot carry
+/// the `u128` timing counters in `CacheStats`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DaemonResponse {
+    Pong {
+        pid: u32,
+        version: String,
+        /// Schema hash of the index the daemon searches; absent from older daemons.
+        #[serde(default)]
+        schema: String,
+    },
+    Ok {
+        result: SearchResult,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// A running daemon, as it answers a ping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaemonInfo {
+    pub pid: u32,
+    pub version: String,
+    pub schema: String,
+}
+
+impl DaemonInfo {
+    /// Whether the daemon runs this build of cass on the index in-process search would
+    /// open for `data_dir`.
+    fn matches(&self, data_dir: &Path) -> bool {
+        let on_disk = crate::search::tantivy::stored_schema_hash(
+            &crate::search::tantivy::index_path(data_dir),
+        );
+        self.version == env!("CARGO_PKG_VERSION") && on_disk.unwrap_or_default() == self.schema
+    }
+}
+
+pub fn socket_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("cass.sock")
+}
+
+fn delegation_disabled() -> bool {
+    std::env::var("CASS_NO_DAEMON")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Run a search through the daemon if one is listening for `data_dir`.
+///
+/// Returns `None` when no daemon is running, delegation is disabled, the daemon is
+/// stale (another version, or another index schema) or fails to answer; callers then
+/// search in-process.
+pub fn try_search(
+    data_dir: &Path,
+    query: &str,
+    filters: &SearchFilters,
+    limit: usize,
+    offset: usize,
+    sparse_threshold: usize,
+) -> Option<SearchResult> {
+    if delegation_disabled() {
+        return None;
+    }
+    let daemon = ping(data_dir)?;
+    if !daemon.matches(data_dir) {
+        tracing::debug!(
+            pid = daemon.pid,
+            version = %daemon.version,
+            schema = %daemon.schema,
+            "query daemon is stale; searching in-process (restart `cass daemon`)"
+        );
+        return None;
+    }
+    let request = DaemonRequest::Search {
+        query: query.to_string(),
+        filters: Box::new(filters.clone()),
+        limit,
+        offset,
+        sparse_threshold,
+    };
+    match send(&socket_path(data_dir), &request) {
+        Ok(DaemonResponse::Ok { result }) => Some(result),
+        Ok(DaemonResponse::Error { message }) => {
+            tracing::debug!(%message, "daemon search failed; searching in-process");
+            None
+        }
+        Ok(_) => None,
+        Err(e) => {
+            tracing::debug!(error = %e, "no query daemon; searching in-process");
+            None
+        }
+    }
+}
+
+/// Check whether a daemon is answering on `data_dir`'s socket.
+pub fn ping(data_dir: &Path) -> Option<DaemonInfo> {
+    match send(&socket_path(data_dir), &DaemonRequest::Ping) {
+        Ok(DaemonResponse::Pong {
+            pid,
+            version,
+            schema,
+        }) => Some(DaemonInfo {
+            pid,
+            version,
+            schema,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(unix)]
+fn send(socket: &Path, request: &DaemonRequest) -> Result<DaemonResponse> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut line = serde_json::to_string(request)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    stream.flush()?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+    Ok(serde_json::from_str(&response)?)
+}
+
+#[cfg(not(unix))]
+fn send(_socket: &Path, _request: &DaemonRequest) -> Result<DaemonResponse> {
+    anyhow::bail!("query daemon is only supported on unix platforms")
+}
+
+/// Serve queries on `data_dir`'s socket until the process is killed.
+///
+/// Requests are handled one at a time on the warm client; individual queries take
+/// milliseconds, so a serial loop keeps the reader and caches single-owner.
+#[cfg(unix)]
+pub fn serve(data_dir: &Path, db_path: &Path) -> Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+
+    use crate::search::query::SearchClient;
+    use crate::search::tantivy::index_dir;
+
+    let socket = socket_path(data_dir);
+    if socket.exists() {
+        if ping(data_dir).is_some() {
+            anyhow::bail!(
+                "a query daemon is already listening on {}",
+                socket.display()
+            );
+        }
+        // Left behind by a daemon that was killed; safe to replace.
+        std::fs::remove_file(&socket)?;
+    }
+
+    let index_path = index_dir(data_dir)?;
+    let client = SearchClient::open(&index_path, Some(db_path))?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "index not found at {}; run 'cass index --full' first",
+            index_path.display()
+        )
+    })?;
+
+    let schema = crate::search::tantivy::stored_schema_hash(&index_path).unwrap_or_default();
+    let listener = UnixListener::bind(&socket)?;
+    tracing::info!(socket = %socket.display(), "query daemon listening");
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::warn!(error = %e, "daemon accept failed");
+                continue;
+            }
+        };
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT)).ok();
+
+        let mut line = String::new();
+        if let Err(e) = BufReader::new(&stream).read_line(&mut line) {
+            tracing::warn!(error = %e, "daemon read failed");
+            continue;
+        }
+
+        let response = match serde_json::from_str::<DaemonRequest>(&line) {
+            Ok(DaemonRequest::Ping) => DaemonResponse::Pong {
+                pid: std::process::id(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                schema: schema.clone(),
+            },
+            Ok(DaemonRequest::Search {
+                query,
+                filters,
+                limit,
+                offset,
+                sparse_threshold,
+            }) => {
+                match client.search_with_fallback(&query, *filters, limit, offset, sparse_threshold)
+                {
+                    Ok(result) => DaemonResponse::Ok { result },
+                    Err(e) => DaemonResponse::Error {
+                        message: e.to_string(),
+                    },
+                }
+            }
+            Err(e) => DaemonResponse::Error {
+                message: format!("invalid request: {e}"),
+            },
+        };
+
+        let mut out = match serde_json::to_string(&response) {
+            Ok(out) => out,
+            Err(e) => {
+                tracing::warn!(error = %e, "daemon could not encode its response");
+                continue;
+            }
+        };
+        out.push('\n');
+        if let Err(e) = stream.write_all(out.as_bytes()) {
+            tracing::warn!(error = %e, "daemon write failed");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn serve(_data_dir: &Path, _db_path: &Path) -> Result<()> {
+    anyhow::bail!("query daemon is only supported on unix platforms")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_round_trips_as_tagged_json() {
+        let req = DaemonRequest::Search {
+            query: "auth error".into(),
+            filters: Box::default(),
+            limit: 10,
+            offset: 5,
+            sparse_threshold: 3,
+        };
+        let json = serde_json::to_string(&req).unwrap();
+        assert!(json.contains("\"op\":\"search\""));
+        match serde_json::from_str::<DaemonRequest>(&json).unwrap() {
+            DaemonRequest::Search { query, limit, .. } => {
+                assert_eq!(query, "auth error");
+                assert_eq!(limit, 10);
+            }
+            DaemonRequest::Ping => panic!("expected search request"),
+        }
+    }
+
+    #[test]
+    fn daemons_of_another_version_or_schema_are_stale() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let index = crate::search::tantivy::index_path(tmp.path());
+        std::fs::create_dir_all(&index).unwrap();
+        std::fs::write(index.join("schema_hash.json"), r#"{"schema_hash":"h1"}"#).unwrap();
+        let daemon = |version: &str, schema: &str| DaemonInfo {
+            pid: 1,
+            version: version.into(),
+            schema: schema.into(),
+        };
+        assert!(daemon(env!("CARGO_PKG_VERSION"), "h1").matches(tmp.path()));
+        assert!(!daemon(env!("CARGO_PKG_VERSION"), "h0").matches(tmp.path()));
+        assert!(!daemon("0.0.1", "h1").matches(tmp.path()));
+
+        // A daemon from before the handshake answers without a schema.
+        let old: DaemonResponse =
+            serde_json::from_str(r#"{"pong":{"pid":7,"version":"0.1.0"}}"#).unwrap();
+        assert!(matches!(old, DaemonResponse::Pong { schema, .. } if schema.is_empty()));
+    }
+
+    #[test]
+    fn missing_socket_falls_back_to_in_process() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        assert!(try_search(tmp.path(), "hello", &SearchFilters::default(), 10, 0, 3).is_none());
+        assert!(ping(tmp.path()).is_none());
+    }
+}
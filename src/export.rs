@@ -319,6 +319,9 @@ mod tests {
             created_at: Some(1700000000000),
             line_number: Some(42),
             match_type: crate::search::query::MatchType::Exact,
+            snippets: Vec::new(),
+            conversation_id: String::new(),
+            role: String::new(),
         }
     }
 
@@ -0,0 +1,153 @@
+//! OSC 8 hyperlinks on session file paths, so a Cmd+click (Ctrl+click on Linux) in the
+//! terminal opens the file.
+//!
+//! Terminals without OSC 8 support are meant to print the link text and drop the escape,
+//! but some older ones and multiplexers show it as garbage, so links are only emitted
+//! where the terminal is known to handle them: kitty, iTerm2, WezTerm, Ghostty, foot,
+//! Alacritty, VS Code, Windows Terminal, Konsole and VTE-based terminals. Inside tmux or
+//! screen they are off. `CASS_HYPERLINKS=1` (or `0`) overrides the detection.
+
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Whether the terminal running cass renders OSC 8 hyperlinks.
+pub fn supported() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| detect(|name| std::env::var(name).ok()))
+}
+
+fn detect(env: impl Fn(&str) -> Option<String>) -> bool {
+    if let Some(value) = env("CASS_HYPERLINKS") {
+        match value.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "always" => return true,
+            "0" | "false" | "no" | "never" => return false,
+            _ => {}
+        }
+    }
+    let term = env("TERM").unwrap_or_default();
+    if env("TMUX").is_some() || term.starts_with("screen") || term.starts_with("tmux") {
+        return false;
+    }
+    if let Some(program) = env("TERM_PROGRAM")
+        && matches!(
+            program.as_str(),
+            "iTerm.app" | "WezTerm" | "ghostty" | "vscode" | "Hyper" | "Tabby" | "rio"
+        )
+    {
+        return true;
+    }
+    if ["kitty", "wezterm", "ghostty", "foot", "alacritty"]
+        .iter()
+        .any(|name| term.contains(name))
+    {
+        return true;
+    }
+    if [
+        "KITTY_WINDOW_ID",
+        "WEZTERM_EXECUTABLE",
+        "WT_SESSION",
+        "KONSOLE_VERSION",
+    ]
+    .iter()
+    .any(|name| env(name).is_some())
+    {
+        return true;
+    }
+    // VTE (GNOME Terminal, Tilix, ...) since 0.50.
+    env("VTE_VERSION")
+        .and_then(|v| v.parse::<u32>().ok())
+        .is_some_and(|v| v >= 5000)
+}
+
+/// `file://` URL for an absolute path, percent-encoding everything but unreserved
+/// characters and `/`. Relative paths and remote `host:path` sources have none.
+pub fn file_url(path: &str) -> Option<String> {
+    if !Path::new(path).is_absolute() {
+        return None;
+    }
+    let mut url = String::from("file://");
+    // Windows paths (`C:\...`) become `file:///C:/...`.
+    if !path.starts_with('/') {
+        url.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'\\' => url.push('/'),
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                url.push(byte as char)
+            }
+            b':' if cfg!(windows) => url.push(':'),
+            _ => url.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    Some(url)
+}
+
+/// Escape that starts a hyperlink to `url`; text printed until [`CLOSE`] is the link.
+pub fn open(url: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\")
+}
+
+/// Escape that ends the hyperlink started by [`open`].
+pub const CLOSE: &str = "\x1b]8;;\x1b\\";
+
+/// `text` wrapped in an OSC 8 hyperlink to `url`.
+pub fn osc8(url: &str, text: &str) -> String {
+    format!("{}{text}{CLOSE}", open(url))
+}
+
+/// `path` for plain CLI output: linked to its file when stdout is a terminal that
+/// supports it, unchanged otherwise.
+pub fn path_for_stdout(path: &str) -> String {
+    match file_url(path) {
+        Some(url) if supported() && std::io::stdout().is_terminal() => osc8(&url, path),
+        _ => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect_with(vars: &[(&str, &str)]) -> bool {
+        detect(|name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn detects_terminals_that_render_links() {
+        assert!(detect_with(&[("TERM_PROGRAM", "iTerm.app")]));
+        assert!(detect_with(&[("TERM", "xterm-kitty")]));
+        assert!(detect_with(&[("WEZTERM_EXECUTABLE", "/usr/bin/wezterm")]));
+        assert!(detect_with(&[("VTE_VERSION", "7600")]));
+        assert!(!detect_with(&[("VTE_VERSION", "4800")]));
+        assert!(!detect_with(&[("TERM", "xterm-256color")]));
+        assert!(!detect_with(&[
+            ("TERM", "xterm-kitty"),
+            ("TMUX", "/tmp/tmux")
+        ]));
+        assert!(detect_with(&[("TERM", "linux"), ("CASS_HYPERLINKS", "1")]));
+        assert!(!detect_with(&[
+            ("TERM_PROGRAM", "WezTerm"),
+            ("CASS_HYPERLINKS", "never")
+        ]));
+    }
+
+    #[test]
+    fn file_urls_are_percent_encoded() {
+        assert_eq!(
+            file_url("/home/me/My Sessions/a#1.jsonl").as_deref(),
+            Some("file:///home/me/My%20Sessions/a%231.jsonl")
+        );
+        assert_eq!(file_url("relative/path.jsonl"), None);
+        assert_eq!(file_url("laptop:/home/me/a.jsonl"), None);
+        assert_eq!(
+            osc8("file:///a", "a"),
+            "\x1b]8;;file:///a\x1b\\a\x1b]8;;\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Localized UI and CLI strings.
+//!
+//! Messages live in Fluent files under `locales/<lang>/cass.ftl` and are embedded at
+//! build time. The active locale comes from `CASS_LANG` (e.g. `es`), falling back to
+//! the system locale; unsupported locales and messages missing from a translation fall
+//! back to English.
+
+use std::sync::OnceLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Shipped locales and their Fluent sources. English must stay first.
+const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en/cass.ftl")),
+    ("es", include_str!("../locales/es/cass.ftl")),
+];
+
+struct Catalog {
+    locale: &'static str,
+    active: FluentBundle<FluentResource>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+fn catalog() -> &'static Catalog {
+    CATALOG.get_or_init(|| {
+        let locale = detect_locale();
+        Catalog {
+            locale,
+            active: build_bundle(locale),
+            fallback: build_bundle(DEFAULT_LOCALE),
+        }
+    })
+}
+
+/// Codes of the locales cass ships translations for.
+pub fn available_locales() -> impl Iterator<Item = &'static str> {
+    LOCALES.iter().map(|(code, _)| *code)
+}
+
+/// The locale in use for this process.
+pub fn locale() -> &'static str {
+    catalog().locale
+}
+
+/// Map a requested locale such as `es_ES.UTF-8` or `pt-BR` to a shipped locale.
+pub fn negotiate(requested: &str) -> Option<&'static str> {
+    let tag = requested.split(['.', '@']).next()?.replace('_', "-");
+    let lang: LanguageIdentifier = tag.parse().ok()?;
+    available_locales().find(|code| *code == lang.language.as_str())
+}
+
+fn detect_locale() -> &'static str {
+    std::env::var("CASS_LANG")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .or_else(sys_locale::get_locale)
+        .and_then(|tag| negotiate(&tag))
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+fn build_bundle(code: &str) -> FluentBundle<FluentResource> {
+    let source = LOCALES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map_or(LOCALES[0].1, |(_, s)| *s);
+    // Keep whatever parsed; a broken entry only loses that one message.
+    let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(res, _)| res);
+    let langid: LanguageIdentifier = code.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Output goes to terminals, not bidi-aware renderers; skip the isolation marks.
+    bundle.set_use_isolating(false);
+    let _ = bundle.add_resource(resource);
+    bundle
+}
+
+fn format(
+    bundle: &FluentBundle<FluentResource>,
+    id: &str,
+    args: Option<&FluentArgs>,
+) -> Option<String> {
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    Some(
+        bundle
+            .format_pattern(pattern, args, &mut errors)
+            .into_owned(),
+    )
+}
+
+fn lookup(id: &str, args: Option<&FluentArgs>) -> String {
+    let cat = catalog();
+    format(&cat.active, id, args)
+        .or_else(|| format(&cat.fallback, id, args))
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// Localized message `id`.
+pub fn tr(id: &str) -> String {
+    lookup(id, None)
+}
+
+/// Localized message `id` with named arguments, e.g. `[("count", 3.into())]`.
+pub fn tr_args(id: &str, args: &[(&str, FluentValue<'_>)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    lookup(id, Some(&fluent_args))
+}
+
+/// Message `id` from the active translation only, without the English fallback.
+fn translated(id: &str) -> Option<String> {
+    let cat = catalog();
+    if cat.locale == DEFAULT_LOCALE {
+        return None;
+    }
+    format(&cat.active, id, None)
+}
+
+/// Apply translated `about` text to the CLI and its subcommands.
+///
+/// English help comes straight from the clap doc comments; translations provide
+/// `cli-about` and `cmd-<subcommand>` messages.
+pub fn localize_command(mut cmd: clap::Command) -> clap::Command {
+    if let Some(about) = translated("cli-about") {
+        cmd = cmd.about(about);
+    }
+    let names: Vec<String> = cmd
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect();
+    for name in names {
+        if let Some(about) = translated(&format!("cmd-{name}")) {
+            cmd = cmd.mut_subcommand(name, |sub| sub.about(about));
+        }
+    }
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+    use std::collections::BTreeSet;
+
+    fn message_ids(code: &str) -> Vec<String> {
+        let source = LOCALES.iter().find(|(c, _)| *c == code).unwrap().1;
+        assert!(
+            FluentResource::try_new(source.to_string()).is_ok(),
+            "{code}: ftl parses"
+        );
+        source
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn negotiate_matches_language_subtag() {
+        assert_eq!(negotiate("es_ES.UTF-8"), Some("es"));
+        assert_eq!(negotiate("es-MX"), Some("es"));
+        assert_eq!(negotiate("en_US"), Some("en"));
+        assert_eq!(negotiate("pt-BR"), None);
+        assert_eq!(negotiate(""), None);
+    }
+
+    #[test]
+    fn translations_have_the_same_message_ids_as_english() {
+        // English CLI help comes from clap, so translations add `cli-about` and one
+        // `cmd-<subcommand>` per subcommand on top of the English ids.
+        let mut expected: BTreeSet<String> = message_ids("en").into_iter().collect();
+        expected.insert("cli-about".to_string());
+        expected.extend(
+            crate::Cli::command()
+                .get_subcommands()
+                .map(|s| format!("cmd-{}", s.get_name())),
+        );
+        for (code, _) in LOCALES.iter().skip(1) {
+            let ids: BTreeSet<String> = message_ids(code).into_iter().collect();
+            let missing: Vec<_> = expected.difference(&ids).collect();
+            let unknown: Vec<_> = ids.difference(&expected).collect();
+            assert!(
+                missing.is_empty() && unknown.is_empty(),
+                "{code}: missing {missing:?}, unknown {unknown:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn bundles_format_arguments_without_isolation_marks() {
+        for (code, _) in LOCALES {
+            let bundle = build_bundle(code);
+            let mut args = FluentArgs::new();
+            args.set("count", 3);
+            let text = format(&bundle, "hint-message-count", Some(&args)).unwrap();
+            assert!(text.contains('3'), "{code}: {text}");
+            assert!(!text.contains('\u{2068}'), "{code}: {text}");
+        }
+    }
+}
@@ -1,24 +1,29 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use crossbeam_channel::{Receiver, Sender};
 use notify::{RecursiveMode, Watcher, recommended_watcher};
 
 use crate::connectors::NormalizedConversation;
+use crate::connectors::checkpoints::{self, ActiveCheckpoints, FileCheckpoints};
 use crate::connectors::{
     Connector, aider::AiderConnector, amp::AmpConnector, chatgpt::ChatGptConnector,
     claude_code::ClaudeCodeConnector, cline::ClineConnector, codex::CodexConnector,
-    cursor::CursorConnector, gemini::GeminiConnector, opencode::OpenCodeConnector,
-    pi_agent::PiAgentConnector,
+    copilot_cli::CopilotCliConnector, crush::CrushConnector, cursor::CursorConnector,
+    custom::CustomConnector, gemini::GeminiConnector, open_webui::OpenWebUiConnector,
+    opencode::OpenCodeConnector, pi_agent::PiAgentConnector, plandex::PlandexConnector,
+    sgpt::SgptConnector, trajectory::TrajectoryConnector, vscode_chat::VsCodeChatConnector,
 };
 use crate::search::tantivy::{TantivyIndex, index_dir};
 use crate::storage::sqlite::SqliteStorage;
 
+pub mod remote;
+
 #[derive(Debug, Clone)]
 pub enum ReindexCommand {
     Full,
@@ -39,8 +44,79 @@ pub struct IndexingProgress {
     pub is_rebuilding: AtomicBool,
     /// Number of coding agents discovered so far during scanning
     pub discovered_agents: AtomicUsize,
+    /// Connector whose conversations are being written, while indexing
+    pub current_agent: Mutex<Option<String>>,
+    /// Passes finished so far: the initial scan, then one per watch cycle
+    pub completed_passes: AtomicUsize,
+    /// Why the last pass failed, until a UI takes it to report
+    pub last_error: Mutex<Option<String>>,
     /// Names of discovered agents (protected by mutex for concurrent access)
     pub discovered_agent_names: Mutex<Vec<String>>,
+    /// Conversations removed because their session file was deleted
+    pub purged: AtomicUsize,
+    /// Conversations copied from the database into a search index rebuilt for a new schema
+    pub restored: AtomicUsize,
+    /// Conversations evicted to keep the index under `[index] max_index_size_mb`
+    pub evicted: Mutex<Vec<EvictedConversation>>,
+    /// Messages that gained an embedding for `cass search --semantic`
+    pub embedded: AtomicUsize,
+    /// Print NDJSON progress events on stdout (`--progress json`)
+    pub emit_events: AtomicBool,
+}
+
+impl IndexingProgress {
+    /// Note that `agent`'s conversations are being written, or that none are.
+    pub fn set_current_agent(&self, agent: Option<&str>) {
+        if let Ok(mut current) = self.current_agent.lock() {
+            *current = agent.map(str::to_string);
+        }
+    }
+
+    /// Keep `error` for a UI to report; a later failure replaces an unreported one.
+    pub fn set_error(&self, error: impl std::fmt::Display) {
+        if let Ok(mut last) = self.last_error.lock() {
+            *last = Some(error.to_string());
+        }
+    }
+
+    /// Back to idle after a pass over the sources.
+    fn finish_pass(&self) {
+        self.phase.store(0, Ordering::Relaxed);
+        self.set_current_agent(None);
+        self.completed_passes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Print one `{"event": ..}` line when `--progress json` asked for events.
+    pub fn emit(&self, event: &str, fields: serde_json::Value) {
+        use std::io::Write;
+
+        if !self.emit_events.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut record = serde_json::json!({ "event": event });
+        if let (Some(record), serde_json::Value::Object(fields)) = (record.as_object_mut(), fields)
+        {
+            record.extend(fields);
+        }
+        let mut out = std::io::stdout().lock();
+        let _ = writeln!(out, "{record}");
+        let _ = out.flush();
+    }
+}
+
+fn emit(progress: &Option<Arc<IndexingProgress>>, event: &str, fields: serde_json::Value) {
+    if let Some(p) = progress {
+        p.emit(event, fields);
+    }
+}
+
+/// A conversation dropped by [`enforce_size_budget`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EvictedConversation {
+    pub agent: String,
+    pub source_path: PathBuf,
+    pub title: Option<String>,
+    pub started_at: Option<i64>,
 }
 
 #[derive(Clone)]
@@ -53,6 +129,72 @@ pub struct IndexOptions {
     pub db_path: PathBuf,
     pub data_dir: PathBuf,
     pub progress: Option<Arc<IndexingProgress>>,
+    pub commit_policy: CommitPolicy,
+    /// `user@host` remotes to mirror over rsync before indexing their mirrors.
+    pub remotes: Vec<String>,
+    /// Connectors to scan (`cass index --agent`); empty scans every enabled one.
+    pub agents: Vec<String>,
+    /// Files or directories to rescan (`cass index --path`); empty rescans whatever
+    /// changed anywhere.
+    pub paths: Vec<PathBuf>,
+}
+
+impl IndexOptions {
+    /// Whether the run is limited to some connectors or paths. Such a run leaves the
+    /// rest of the index alone: `--full` re-reads only the selection, remote mirrors
+    /// are skipped and the last scan time is kept for the next complete run.
+    pub fn is_selective(&self) -> bool {
+        !self.agents.is_empty() || !self.paths.is_empty()
+    }
+}
+
+/// When the initial scan commits the Tantivy index: after `max_docs` new messages or
+/// `max_interval`, whichever comes first. Large first-time indexes become searchable
+/// progressively, and a crash loses at most one batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitPolicy {
+    pub max_docs: usize,
+    pub max_interval: Duration,
+}
+
+impl Default for CommitPolicy {
+    fn default() -> Self {
+        Self {
+            max_docs: 20_000,
+            max_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Tracks uncommitted work against a `CommitPolicy`.
+#[derive(Debug)]
+struct CommitTracker {
+    policy: CommitPolicy,
+    pending_docs: usize,
+    last_commit: Instant,
+}
+
+impl CommitTracker {
+    fn new(policy: CommitPolicy, now: Instant) -> Self {
+        Self {
+            policy,
+            pending_docs: 0,
+            last_commit: now,
+        }
+    }
+
+    /// Record `docs` newly written documents; returns true when a commit is due.
+    fn record(&mut self, docs: usize, now: Instant) -> bool {
+        self.pending_docs += docs;
+        self.pending_docs > 0
+            && (self.pending_docs >= self.policy.max_docs
+                || now.duration_since(self.last_commit) >= self.policy.max_interval)
+    }
+
+    fn committed(&mut self, now: Instant) {
+        self.pending_docs = 0;
+        self.last_commit = now;
+    }
 }
 
 pub fn run_index(
@@ -61,22 +203,13 @@ pub fn run_index(
 ) -> Result<()> {
     let mut storage = SqliteStorage::open(&opts.db_path)?;
     let index_path = index_dir(&opts.data_dir)?;
+    // Fail before touching the database if another run is writing the index.
+    ensure_unlocked(&index_path)?;
 
     // Detect if we are rebuilding due to missing meta/schema mismatch
-    let schema_matches = index_path.join("schema_hash.json").exists()
-        && std::fs::read_to_string(index_path.join("schema_hash.json"))
-            .ok()
-            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
-            .and_then(|json| {
-                json.get("schema_hash")
-                    .and_then(|v| v.as_str())
-                    .map(String::from)
-            })
-            .as_deref()
-            == Some(crate::search::tantivy::SCHEMA_HASH);
-    let needs_rebuild = opts.force_rebuild
-        || !index_path.join("meta.json").exists()
-        || (index_path.join("schema_hash.json").exists() && !schema_matches);
+    let needs_rebuild = opts.force_rebuild || !index_is_current(&index_path);
+    let selective = opts.is_selective();
+    let reset = opts.full && !selective || opts.force_rebuild;
 
     if needs_rebuild && let Some(p) = &opts.progress {
         p.is_rebuilding.store(true, Ordering::Relaxed);
@@ -89,15 +222,22 @@ pub fn run_index(
         TantivyIndex::open_or_create(&index_path)?
     };
 
-    if opts.full {
+    // Tantivy only receives messages SQLite has not stored yet, so an explicit full
+    // rebuild empties the database too. A missing or outdated index is instead refilled
+    // from the database, which still holds sessions whose files have since been deleted.
+    let mut restored = 0;
+    if reset {
         reset_storage(&mut storage)?;
         t_index.delete_all()?;
+    } else if needs_rebuild {
+        restored = rebuild_index_from_storage(&storage, &mut t_index, &opts.progress)?;
     }
+    let rescan_all = reset || (needs_rebuild && restored == 0);
 
     // Get last scan timestamp for incremental indexing.
     // If full rebuild or force_rebuild, scan everything (since_ts = None).
     // Otherwise, only scan files modified since last successful scan.
-    let since_ts = if opts.full || needs_rebuild {
+    let since_ts = if rescan_all || opts.full {
         None
     } else {
         storage
@@ -106,17 +246,48 @@ pub fn run_index(
             .map(|ts| ts.saturating_sub(1))
     };
 
+    emit(
+        &opts.progress,
+        "index_start",
+        serde_json::json!({
+            "full": reset,
+            "rebuild": needs_rebuild,
+            "restored": restored,
+            "incremental": since_ts.is_some(),
+        }),
+    );
     if since_ts.is_some() {
         tracing::info!(since_ts = ?since_ts, "incremental_scan: using last_scan_ts");
     } else {
         tracing::info!("full_scan: no last_scan_ts or rebuild requested");
     }
 
+    // Connectors decide per file through the checkpoints (see `ScanContext::file_changed`);
+    // `since_ts` remains for connectors that filter rows inside a database.
+    let mut checkpoints = FileCheckpoints::load(&opts.data_dir);
+    if rescan_all {
+        checkpoints.clear();
+    }
+    if !opts.paths.is_empty() {
+        checkpoints = checkpoints.within(opts.paths.clone());
+    }
+    if selective && opts.full {
+        checkpoints = checkpoints.rereading();
+    }
+    tracing::info!(files = checkpoints.len(), "loaded file checkpoints");
+    let checkpoints = ActiveCheckpoints::activate(&opts.data_dir, checkpoints);
+
+    // Before scanning, so an archived copy of a rotated file is rescanned in this run.
+    let purged = purge_vanished(checkpoints.store(), &mut storage, &mut t_index)?;
+    if let Some(p) = &opts.progress {
+        p.purged.store(purged, Ordering::Relaxed);
+    }
+
     // Record scan start time before scanning
     let scan_start_ts = SqliteStorage::now_millis();
 
-    // First pass: Scan all to get counts if we have progress tracker
-    // Use parallel iteration for faster agent discovery
+    // Scanning and indexing overlap in the pipeline below; totals grow as each
+    // connector finishes its scan.
     if let Some(p) = &opts.progress {
         p.phase.store(1, Ordering::Relaxed); // Scanning
         // Reset; totals will be populated during scanning.
@@ -128,100 +299,81 @@ pub fn run_index(
         }
     }
 
-    // Define connector factories for parallel execution
-    // Each tuple: (name, factory_fn) where factory_fn creates a fresh Connector
-    #[allow(clippy::type_complexity)]
-    let connector_factories: Vec<(&'static str, fn() -> Box<dyn Connector + Send>)> = vec![
-        ("codex", || Box::new(CodexConnector::new())),
-        ("cline", || Box::new(ClineConnector::new())),
-        ("gemini", || Box::new(GeminiConnector::new())),
-        ("claude", || Box::new(ClaudeCodeConnector::new())),
-        ("opencode", || Box::new(OpenCodeConnector::new())),
-        ("amp", || Box::new(AmpConnector::new())),
-        ("aider", || Box::new(AiderConnector::new())),
-        ("cursor", || Box::new(CursorConnector::new())),
-        ("chatgpt", || Box::new(ChatGptConnector::new())),
-        ("pi_agent", || Box::new(PiAgentConnector::new())),
-    ];
-
-    // Run connector detection and scanning in parallel using rayon
-    use rayon::prelude::*;
-
-    let progress_ref = opts.progress.as_ref();
-    let data_dir = opts.data_dir.clone();
-
-    let pending_batches: Vec<(&'static str, Vec<NormalizedConversation>)> = connector_factories
-        .into_par_iter()
-        .filter_map(|(name, factory)| {
-            let conn = factory();
-            let detect = conn.detect();
-            if !detect.detected {
-                return None;
-            }
-
-            // Update discovered agents count immediately when detected
-            // This gives fast UI feedback during the discovery phase
-            if let Some(p) = progress_ref {
-                p.discovered_agents.fetch_add(1, Ordering::Relaxed);
-                if let Ok(mut names) = p.discovered_agent_names.lock() {
-                    names.push(name.to_string());
-                }
-            }
-
-            let ctx = crate::connectors::ScanContext {
-                data_root: data_dir.clone(),
-                since_ts,
-            };
-
-            match conn.scan(&ctx) {
-                Ok(convs) => {
-                    if let Some(p) = progress_ref {
-                        p.total.fetch_add(convs.len(), Ordering::Relaxed);
-                    }
-                    tracing::info!(
-                        connector = name,
-                        conversations = convs.len(),
-                        "parallel_scan_complete"
-                    );
-                    Some((name, convs))
-                }
-                Err(e) => {
-                    // Note: agent was counted as discovered but scan failed
-                    // This is acceptable as detection succeeded (agent exists)
-                    tracing::warn!("scan failed for {}: {}", name, e);
-                    None
-                }
+    let connector_factories = connector_factories()
+        .into_iter()
+        .filter(|(name, _)| opts.agents.is_empty() || opts.agents.iter().any(|a| a == name))
+        .filter(|(name, _)| {
+            let enabled = crate::config::connector_enabled(name);
+            if !enabled {
+                tracing::info!(connector = name, "connector disabled in config");
             }
+            enabled
         })
         .collect();
 
-    if let Some(p) = &opts.progress {
-        p.phase.store(2, Ordering::Relaxed); // Indexing
-    }
+    run_scan_pipeline(
+        connector_factories,
+        &opts.data_dir,
+        since_ts,
+        &mut storage,
+        &mut t_index,
+        &opts.progress,
+        opts.commit_policy,
+    )?;
 
-    for (name, convs) in pending_batches {
+    // Remote mirrors are re-read in full when refreshed or on a full rebuild; rsync
+    // keeps remote mtimes, so `since_ts` says nothing about what just arrived.
+    let mut remote_errors = Vec::new();
+    let mut mirrors = Vec::new();
+    for remote_spec in opts.remotes.iter().filter(|_| !selective) {
+        match remote::sync(remote_spec, &opts.data_dir) {
+            Ok(mirror) => mirrors.push((remote::host_name(remote_spec), mirror)),
+            Err(e) => {
+                tracing::warn!(remote = %remote_spec, "remote sync failed: {e:#}");
+                remote_errors.push(format!("{e:#}"));
+            }
+        }
+    }
+    if opts.remotes.is_empty() && since_ts.is_none() && !selective {
+        mirrors = remote::existing_mirrors(&opts.data_dir);
+    }
+    for (host, mirror) in &mirrors {
+        let convs = remote::scan_mirror(host, mirror);
+        tracing::info!(host, conversations = convs.len(), "remote_ingest");
         ingest_batch(&mut storage, &mut t_index, &convs, &opts.progress)?;
-        tracing::info!(
-            connector = name,
-            conversations = convs.len(),
-            "connector_ingest"
-        );
     }
 
+    // Re-reading a session drops its documents, notes included; put them back.
+    t_index.sync_notes(&crate::notes::load(&opts.data_dir))?;
     t_index.commit()?;
-
-    // Update last_scan_ts after successful scan and commit
-    storage.set_last_scan_ts(scan_start_ts)?;
-    tracing::info!(
-        scan_start_ts,
-        "updated last_scan_ts for incremental indexing"
+    emit(
+        &opts.progress,
+        "commit",
+        serde_json::json!({ "final": true }),
     );
+    apply_size_budget(&mut storage, &mut t_index, &index_path, &opts.progress)?;
+    checkpoints.store().commit()?;
+    apply_embeddings(&opts);
+
+    // Update last_scan_ts after successful scan and commit. A selective run did not
+    // look everywhere, so the next complete run must still cover the same window.
+    if !selective {
+        storage.set_last_scan_ts(scan_start_ts)?;
+        tracing::info!(
+            scan_start_ts,
+            "updated last_scan_ts for incremental indexing"
+        );
+    }
 
     if let Some(p) = &opts.progress {
-        p.phase.store(0, Ordering::Relaxed); // Idle
+        p.finish_pass(); // Idle
         p.is_rebuilding.store(false, Ordering::Relaxed);
     }
 
+    if !remote_errors.is_empty() {
+        anyhow::bail!("remote sync failed: {}", remote_errors.join("; "));
+    }
+
     if opts.watch || opts.watch_once_paths.is_some() {
         let opts_clone = opts.clone();
         let state = Arc::new(Mutex::new(load_watch_state(&opts.data_dir)));
@@ -246,7 +398,7 @@ pub fn run_index(
                     }
                     // Pass all watch roots
                     let roots = watch_roots();
-                    let _ = reindex_paths(
+                    let result = reindex_paths(
                         &opts_clone,
                         roots,
                         state.clone(),
@@ -254,8 +406,9 @@ pub fn run_index(
                         t_index.clone(),
                         true,
                     );
+                    report_watch_error(&opts_clone, result);
                 } else {
-                    let _ = reindex_paths(
+                    let result = reindex_paths(
                         &opts_clone,
                         paths,
                         state.clone(),
@@ -263,12 +416,741 @@ pub fn run_index(
                         t_index.clone(),
                         false,
                     );
+                    report_watch_error(&opts_clone, result);
                 }
             },
         )?;
     }
 
-    Ok(())
+    Ok(())
+}
+
+/// Log a failed watch pass and hand it to whoever follows the progress; watching goes on.
+fn report_watch_error(opts: &IndexOptions, result: Result<()>) {
+    if let Err(e) = result {
+        tracing::warn!(error = %e, "watch reindex failed");
+        if let Some(p) = &opts.progress {
+            p.set_error(format!("{e:#}"));
+        }
+    }
+}
+
+/// What one connector would ingest; see [`dry_run`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConnectorReport {
+    pub connector: &'static str,
+    pub enabled: bool,
+    pub detected: bool,
+    pub evidence: Vec<String>,
+    /// Distinct source files the scan produced conversations from.
+    pub files: usize,
+    pub conversations: usize,
+    pub messages: usize,
+    pub skipped: Vec<SkippedPath>,
+    /// Set when `scan()` failed outright.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SkippedPath {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Detect and scan every connector as `cass index` would, without writing to the
+/// database, the index or the checkpoints. Connectors run one at a time so each
+/// file skipped as unchanged is attributed to the connector that skipped it. With
+/// `agents`, only those connectors are reported.
+pub fn dry_run(
+    data_dir: &Path,
+    db_path: &Path,
+    full: bool,
+    agents: &[String],
+) -> Vec<ConnectorReport> {
+    let since_ts = if full {
+        None
+    } else {
+        SqliteStorage::open_readonly(db_path)
+            .ok()
+            .and_then(|s| s.get_last_scan_ts().ok().flatten())
+            .map(|ts| ts.saturating_sub(1))
+    };
+    let checkpoints = FileCheckpoints::load(data_dir).tracking_unchanged();
+    if full {
+        checkpoints.clear();
+    }
+    let checkpoints = ActiveCheckpoints::activate(data_dir, checkpoints);
+
+    let mut reports = Vec::new();
+    for (name, factory) in connector_factories()
+        .into_iter()
+        .filter(|(name, _)| agents.is_empty() || agents.iter().any(|a| a == name))
+    {
+        let mut report = ConnectorReport {
+            connector: name,
+            enabled: crate::config::connector_enabled(name),
+            detected: false,
+            evidence: Vec::new(),
+            files: 0,
+            conversations: 0,
+            messages: 0,
+            skipped: Vec::new(),
+            error: None,
+        };
+        if !report.enabled {
+            reports.push(report);
+            continue;
+        }
+        let conn = factory();
+        let detect = conn.detect();
+        let extra_roots = existing_extra_roots(name);
+        report.detected = detect.detected || !extra_roots.is_empty();
+        report.evidence = detect.evidence;
+        report.evidence.extend(
+            extra_roots
+                .iter()
+                .map(|root| format!("extra root {}", root.display())),
+        );
+        if !report.detected {
+            reports.push(report);
+            continue;
+        }
+
+        let ctx = crate::connectors::ScanContext {
+            data_root: data_dir.to_path_buf(),
+            since_ts,
+        };
+        let mut files = std::collections::HashSet::new();
+        let scan = scan_roots(
+            name,
+            &*conn,
+            detect.detected,
+            &extra_roots,
+            &ctx,
+            &mut |conv| {
+                report.conversations += 1;
+                report.messages += conv.messages.len();
+                files.insert(conv.source_path);
+                Ok(())
+            },
+        );
+        report.files = files.len();
+        if let Err(e) = scan {
+            report.error = Some(format!("{e:#}"));
+        }
+        report.skipped.extend(
+            checkpoints
+                .store()
+                .take_unchanged()
+                .into_iter()
+                .map(|path| SkippedPath {
+                    path,
+                    reason: "unchanged since last index".to_string(),
+                }),
+        );
+        report.skipped.extend(
+            checkpoints
+                .store()
+                .quarantine()
+                .entries()
+                .into_iter()
+                .filter(|e| e.agent == name)
+                .map(|e| SkippedPath {
+                    path: e.path,
+                    reason: format!("unparseable: {}", e.error),
+                }),
+        );
+        reports.push(report);
+    }
+    reports
+}
+
+/// Read every session file of the enabled connectors (only `agents`, when given)
+/// straight from disk, bypassing the database, the index and the checkpoints, and hand
+/// each conversation to `emit` as it is parsed. Connectors scan concurrently on the
+/// rayon pool, so `emit` runs on their threads; an error from it stops that connector.
+/// Returns the connectors whose scan failed, with the error.
+pub fn scan_sessions(
+    data_dir: &Path,
+    agents: &[String],
+    emit: &(dyn Fn(NormalizedConversation) -> Result<()> + Sync),
+) -> Vec<(&'static str, anyhow::Error)> {
+    use rayon::prelude::*;
+
+    connector_factories()
+        .into_par_iter()
+        .filter(|(name, _)| {
+            crate::config::connector_enabled(name)
+                && (agents.is_empty() || agents.iter().any(|a| a == name))
+        })
+        .filter_map(|(name, factory)| {
+            let conn = factory();
+            let detected = conn.detect().detected;
+            let extra_roots = existing_extra_roots(name);
+            if !detected && extra_roots.is_empty() {
+                return None;
+            }
+            let ctx = crate::connectors::ScanContext {
+                data_root: data_dir.to_path_buf(),
+                since_ts: None,
+            };
+            scan_roots(name, &*conn, detected, &extra_roots, &ctx, &mut |conv| {
+                emit(conv)
+            })
+            .err()
+            .map(|e| (name, e))
+        })
+        .collect()
+}
+
+/// `extra_roots` configured for `name` that exist on disk.
+fn existing_extra_roots(name: &str) -> Vec<PathBuf> {
+    crate::config::extra_roots(name)
+        .into_iter()
+        .filter(|root| {
+            let exists = root.exists();
+            if !exists {
+                tracing::warn!(connector = name, root = %root.display(), "extra root not found");
+            }
+            exists
+        })
+        .collect()
+}
+
+/// Stream `conn`'s conversations from its usual root (when `detected`) and then from
+/// each of `extra_roots`, scanned as the connector's configured root. A connector that
+/// does not honour the override falls back to its usual dirs; only what came from the
+/// extra root itself is kept. Every root is tried; the first error is returned.
+fn scan_roots(
+    name: &str,
+    conn: &dyn Connector,
+    detected: bool,
+    extra_roots: &[PathBuf],
+    ctx: &crate::connectors::ScanContext,
+    emit: &mut crate::connectors::EmitConversation,
+) -> Result<()> {
+    let mut result = if detected {
+        conn.scan_streaming(ctx, emit)
+    } else {
+        Ok(())
+    };
+    for root in extra_roots {
+        let scan = crate::config::with_root(name, root, || {
+            conn.scan_streaming(ctx, &mut |conv| {
+                if conv.source_path.starts_with(root) {
+                    emit(conv)
+                } else {
+                    Ok(())
+                }
+            })
+        });
+        if let Err(e) = scan
+            && result.is_ok()
+        {
+            result = Err(e.context(format!("scanning extra root {}", root.display())));
+        }
+    }
+    result
+}
+
+/// Every local connector as `(name, factory)`; the factory creates a fresh connector.
+fn connector_factories() -> Vec<(&'static str, ConnectorFactory)> {
+    vec![
+        ("codex", || Box::new(CodexConnector::new())),
+        ("cline", || Box::new(ClineConnector::new())),
+        ("gemini", || Box::new(GeminiConnector::new())),
+        ("qwen", || Box::new(GeminiConnector::qwen())),
+        ("claude_code", || Box::new(ClaudeCodeConnector::new())),
+        ("opencode", || Box::new(OpenCodeConnector::new())),
+        ("amp", || Box::new(AmpConnector::new())),
+        ("aider", || Box::new(AiderConnector::new())),
+        ("cursor", || Box::new(CursorConnector::new())),
+        ("chatgpt", || Box::new(ChatGptConnector::new())),
+        ("pi_agent", || Box::new(PiAgentConnector::new())),
+        ("sgpt", || Box::new(SgptConnector::new())),
+        ("copilot_cli", || Box::new(CopilotCliConnector::new())),
+        ("open_webui", || Box::new(OpenWebUiConnector::new())),
+        ("plandex", || Box::new(PlandexConnector::new())),
+        ("crush", || Box::new(CrushConnector::new())),
+        ("vscode_chat", || Box::new(VsCodeChatConnector::new())),
+        ("custom", || Box::new(CustomConnector::new())),
+        ("trajectory", || Box::new(TrajectoryConnector::new())),
+    ]
+}
+
+/// Names of every local connector, as accepted by `cass index --agent`.
+pub fn connector_names() -> Vec<&'static str> {
+    connector_factories()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Creates a fresh connector instance for one scan.
+type ConnectorFactory = fn() -> Box<dyn Connector + Send>;
+
+/// Scan batches allowed in flight between the scanners and the writer.
+const PIPELINE_DEPTH: usize = 8;
+/// Conversations handed to the writer per batch.
+const WRITER_BATCH_SIZE: usize = 128;
+
+/// Scan → write pipeline.
+///
+/// Connectors detect and scan concurrently on the rayon pool, and the connectors that
+/// read one session per file (Claude Code, Codex, Gemini, pi-agent, Aider, trajectory
+/// imports) parse those files on the pool too, through [`crate::connectors::parse_files`].
+/// Conversations go to a bounded channel in fixed-size batches; the calling thread is
+/// the single writer for SQLite and Tantivy, and scanners block once `PIPELINE_DEPTH`
+/// batches are queued.
+///
+/// Only connectors that override [`Connector::scan_streaming`] (Claude Code and Codex)
+/// emit conversations as they parse them, so only their histories are never held in
+/// memory whole. The others build their full scan before the first batch is sent, and
+/// the channel bounds just what waits for the writer. The writer commits Tantivy
+/// whenever `commit_policy` says a batch is due; the caller makes the final commit.
+fn run_scan_pipeline(
+    connector_factories: Vec<(&'static str, ConnectorFactory)>,
+    data_dir: &Path,
+    since_ts: Option<i64>,
+    storage: &mut SqliteStorage,
+    t_index: &mut TantivyIndex,
+    progress: &Option<Arc<IndexingProgress>>,
+    commit_policy: CommitPolicy,
+) -> Result<()> {
+    use rayon::prelude::*;
+
+    let (tx, rx) =
+        crossbeam_channel::bounded::<(&'static str, Vec<NormalizedConversation>)>(PIPELINE_DEPTH);
+    let progress_ref = progress.as_ref();
+    // Connectors reading a database rather than files ignore `--path`; drop what they
+    // emit from outside it here.
+    let checkpoints = crate::connectors::checkpoints::active(data_dir);
+    let checkpoints = &checkpoints;
+
+    std::thread::scope(|s| {
+        s.spawn(move || {
+            connector_factories
+                .into_par_iter()
+                .for_each_with(tx, |tx, (name, factory)| {
+                    let conn = factory();
+                    let detected = conn.detect().detected;
+                    let extra_roots = existing_extra_roots(name);
+                    if !detected && extra_roots.is_empty() {
+                        tracing::debug!(connector = name, "connector not detected; skipping");
+                        return;
+                    }
+
+                    // Update discovered agents count immediately when detected
+                    // This gives fast UI feedback during the discovery phase
+                    if let Some(p) = progress_ref {
+                        p.discovered_agents.fetch_add(1, Ordering::Relaxed);
+                        if let Ok(mut names) = p.discovered_agent_names.lock() {
+                            names.push(name.to_string());
+                        }
+                        p.emit("connector_start", serde_json::json!({ "agent": name }));
+                    }
+
+                    let ctx = crate::connectors::ScanContext {
+                        data_root: data_dir.to_path_buf(),
+                        since_ts,
+                    };
+
+                    let mut batch = Vec::with_capacity(WRITER_BATCH_SIZE);
+                    let mut scanned = 0usize;
+                    let scan = crate::connectors::checkpoints::scanning_as(Some(name), || {
+                        scan_roots(name, &*conn, detected, &extra_roots, &ctx, &mut |conv| {
+                            if checkpoints
+                                .as_ref()
+                                .is_some_and(|store| !store.in_scope(&conv.source_path))
+                            {
+                                return Ok(());
+                            }
+                            scanned += 1;
+                            if let Some(p) = progress_ref {
+                                p.total.fetch_add(1, Ordering::Relaxed);
+                            }
+                            batch.push(conv);
+                            if batch.len() == WRITER_BATCH_SIZE {
+                                let full = std::mem::replace(
+                                    &mut batch,
+                                    Vec::with_capacity(WRITER_BATCH_SIZE),
+                                );
+                                // A closed channel means the writer bailed out; stop producing.
+                                tx.send((name, full))
+                                    .map_err(|_| anyhow::anyhow!("index writer stopped"))?;
+                            }
+                            Ok(())
+                        })
+                    });
+                    if let Err(e) = scan {
+                        // Note: agent was counted as discovered but scan failed
+                        // This is acceptable as detection succeeded (agent exists)
+                        // Conversations emitted before the failure are still indexed,
+                        // but every file it checked is read again next run.
+                        tracing::warn!("scan failed for {}: {}", name, e);
+                        if let Some(store) = checkpoints {
+                            store.discard_pending(name);
+                        }
+                    }
+                    tracing::info!(
+                        connector = name,
+                        conversations = scanned,
+                        "parallel_scan_complete"
+                    );
+                    if let Some(p) = progress_ref {
+                        p.emit(
+                            "connector_done",
+                            serde_json::json!({ "agent": name, "conversations": scanned }),
+                        );
+                    }
+                    if !batch.is_empty() {
+                        let _ = tx.send((name, batch));
+                    }
+                });
+        });
+
+        let mut result = Ok(());
+        let mut commits = CommitTracker::new(commit_policy, Instant::now());
+        for (name, batch) in rx.iter() {
+            if let Some(p) = progress_ref {
+                p.phase.store(2, Ordering::Relaxed); // Indexing
+                p.set_current_agent(Some(name));
+            }
+            if let Err(e) = ingest_batch(storage, t_index, &batch, progress) {
+                result = Err(e);
+                break;
+            }
+            tracing::info!(
+                connector = name,
+                conversations = batch.len(),
+                "connector_ingest"
+            );
+            let docs = batch.iter().map(|c| c.messages.len()).sum();
+            if let Some(p) = progress_ref {
+                p.emit(
+                    "ingest",
+                    serde_json::json!({
+                        "agent": name,
+                        "conversations": batch.len(),
+                        "messages": docs,
+                        "indexed": p.current.load(Ordering::Relaxed),
+                        "total": p.total.load(Ordering::Relaxed),
+                    }),
+                );
+            }
+            if commits.record(docs, Instant::now()) {
+                if let Err(e) = t_index.commit() {
+                    result = Err(e);
+                    break;
+                }
+                tracing::info!(docs = commits.pending_docs, "batch_commit");
+                emit(
+                    progress,
+                    "commit",
+                    serde_json::json!({ "docs": commits.pending_docs }),
+                );
+                commits.committed(Instant::now());
+            }
+        }
+        // Dropping the receiver unblocks any scanner still waiting to send.
+        drop(rx);
+        result
+    })
+}
+
+/// Fail with [`IndexLocked`](crate::search::tantivy::IndexLocked) while another
+/// process holds the writer lock on `index_path`.
+fn ensure_unlocked(index_path: &Path) -> Result<()> {
+    if crate::search::tantivy::writer_locked(index_path) {
+        return Err(crate::search::tantivy::IndexLocked {
+            path: index_path.to_path_buf(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Index the `cass note` annotations under `data_dir` now, returning whether it could:
+/// without a current index, or while another writer holds it, the next `cass index`
+/// (or watch pass) picks them up instead.
+pub fn sync_notes(data_dir: &Path) -> Result<bool> {
+    let index_path = crate::search::tantivy::index_path(data_dir);
+    if !index_is_current(&index_path) || crate::search::tantivy::writer_locked(&index_path) {
+        return Ok(false);
+    }
+    let mut t_index = match TantivyIndex::open_or_create(&index_path) {
+        Err(e) if e.is::<crate::search::tantivy::IndexLocked>() => return Ok(false),
+        other => other?,
+    };
+    t_index.sync_notes(&crate::notes::load(data_dir))?;
+    t_index.commit()?;
+    Ok(true)
+}
+
+/// True when `index_path` holds an index built under the current schema.
+fn index_is_current(index_path: &Path) -> bool {
+    index_path.join("meta.json").exists()
+        && fs::read_to_string(index_path.join("schema_hash.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .is_some_and(|json| json["schema_hash"] == crate::search::tantivy::schema_hash())
+}
+
+/// Refill a freshly created search index from the database after a schema change or
+/// a lost index directory, so an upgrade keeps every conversation searchable, including
+/// those whose session files are gone. Returns how many conversations were restored.
+fn rebuild_index_from_storage(
+    storage: &SqliteStorage,
+    t_index: &mut TantivyIndex,
+    progress: &Option<Arc<IndexingProgress>>,
+) -> Result<usize> {
+    const PAGE: i64 = 500;
+    let total: i64 = storage
+        .raw()
+        .query_row("SELECT COUNT(*) FROM conversations", [], |r| r.get(0))?;
+    if total == 0 {
+        return Ok(0);
+    }
+    tracing::warn!(
+        conversations = total,
+        "search index is missing or from an older schema; rebuilding it from the database"
+    );
+    if let Some(p) = progress {
+        p.phase.store(2, Ordering::Relaxed); // Indexing
+        p.total.store(total as usize, Ordering::Relaxed);
+        p.current.store(0, Ordering::Relaxed);
+    }
+
+    let mut restored = 0;
+    loop {
+        let page = storage.list_conversations(PAGE, restored as i64)?;
+        if page.is_empty() {
+            break;
+        }
+        for conv in page {
+            restored += 1;
+            let Some(id) = conv.id else { continue };
+            let messages = storage.fetch_messages(id)?;
+            let snippets = storage.fetch_snippets(id)?;
+            t_index.add_conversation(&persist::from_internal(conv, messages, snippets))?;
+            if let Some(p) = progress {
+                p.current.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+    t_index.commit()?;
+    if let Some(p) = progress {
+        p.restored.store(restored, Ordering::Relaxed);
+    }
+    tracing::info!(
+        conversations = restored,
+        "search index rebuilt from the database"
+    );
+    Ok(restored)
+}
+
+/// What `cass doctor --index --repair` changed.
+#[derive(Debug, Default, serde::Serialize)]
+pub struct IndexRepair {
+    /// The index was recreated and refilled from the database.
+    pub rebuilt: bool,
+    /// Damaged segments removed from the index.
+    pub dropped_segments: usize,
+    /// Source files whose documents were re-added after dropping segments.
+    pub reindexed_sources: usize,
+    /// Conversations written back into the index.
+    pub conversations: usize,
+}
+
+/// Fix the `problems` found by [`check_index`](crate::search::tantivy::check_index)
+/// in the index under `data_dir`. An unreadable, missing or outdated index is rebuilt
+/// from the database; damaged segments are dropped and only the source files whose
+/// documents they held are re-added, so the healthy segments are kept.
+pub fn repair_index(
+    data_dir: &Path,
+    db_path: &Path,
+    problems: &[crate::search::tantivy::IndexProblem],
+) -> Result<IndexRepair> {
+    use crate::search::tantivy::{IndexProblem, drop_segments, source_path_counts};
+
+    let mut repair = IndexRepair::default();
+    if problems.is_empty() {
+        return Ok(repair);
+    }
+    let storage = SqliteStorage::open(db_path)?;
+    let index_path = index_dir(data_dir)?;
+    ensure_unlocked(&index_path)?;
+    if problems.iter().any(IndexProblem::needs_full_rebuild) {
+        fs::remove_dir_all(&index_path).ok();
+        let mut t_index = TantivyIndex::open_or_create(&index_path)?;
+        repair.rebuilt = true;
+        repair.conversations = rebuild_index_from_storage(&storage, &mut t_index, &None)?;
+        return Ok(repair);
+    }
+
+    let damaged: Vec<String> = problems
+        .iter()
+        .filter_map(|p| match p {
+            IndexProblem::DamagedSegment { segment, .. } => Some(segment.clone()),
+            _ => None,
+        })
+        .collect();
+    drop_segments(&index_path, &damaged)?;
+    repair.dropped_segments = damaged.len();
+
+    // Whatever the index now lacks compared with the database lived in those segments.
+    let indexed = source_path_counts(&index_path)?;
+    let mut t_index = TantivyIndex::open_or_create(&index_path)?;
+    for (source_path, stored) in storage.message_counts_by_source_path()? {
+        if indexed.get(&source_path) == Some(&stored) {
+            continue;
+        }
+        let source_path = PathBuf::from(source_path);
+        t_index.delete_source_path(&source_path);
+        for conv in storage.conversations_for_source_path(&source_path)? {
+            let Some(id) = conv.id else { continue };
+            let messages = storage.fetch_messages(id)?;
+            let snippets = storage.fetch_snippets(id)?;
+            t_index.add_conversation(&persist::from_internal(conv, messages, snippets))?;
+            repair.conversations += 1;
+        }
+        repair.reindexed_sources += 1;
+    }
+    t_index.commit()?;
+    tracing::info!(
+        segments = repair.dropped_segments,
+        sources = repair.reindexed_sources,
+        "repaired damaged search index segments"
+    );
+    Ok(repair)
+}
+
+/// Embed new messages when `[embeddings]` is configured. A failing model or endpoint
+/// only delays semantic search, so it is logged rather than failing the run; the next
+/// run picks up where this one stopped.
+fn apply_embeddings(opts: &IndexOptions) {
+    match crate::search::embeddings::sync_configured(&opts.data_dir, &opts.db_path) {
+        Ok(Some(embedded)) => {
+            tracing::info!(embedded, "embedded messages for semantic search");
+            if let Some(p) = &opts.progress {
+                p.embedded.fetch_add(embedded, Ordering::Relaxed);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("embedding messages failed: {e:#}"),
+    }
+}
+
+/// Run [`enforce_size_budget`] with the configured `[index] max_index_size_mb`, if any,
+/// recording what was evicted in `progress`.
+fn apply_size_budget(
+    storage: &mut SqliteStorage,
+    t_index: &mut TantivyIndex,
+    index_path: &Path,
+    progress: &Option<Arc<IndexingProgress>>,
+) -> Result<()> {
+    let Some(max_mb) = crate::config::max_index_size_mb() else {
+        return Ok(());
+    };
+    let evicted = enforce_size_budget(storage, t_index, index_path, max_mb * 1024 * 1024)?;
+    if let Some(p) = progress
+        && let Ok(mut all) = p.evicted.lock()
+    {
+        all.extend(evicted);
+    }
+    Ok(())
+}
+
+/// Evict the oldest conversations, by start time, from the index and the database until
+/// the index at `index_path` takes at most `budget` bytes. Deleted documents only free
+/// space once their segments are merged, so each round estimates how many messages to
+/// drop from the average message size, then merges and measures again. Evicted
+/// conversations stay out until their session file changes, as its checkpoint is kept.
+pub fn enforce_size_budget(
+    storage: &mut SqliteStorage,
+    t_index: &mut TantivyIndex,
+    index_path: &Path,
+    budget: u64,
+) -> Result<Vec<EvictedConversation>> {
+    let mut evicted = Vec::new();
+    loop {
+        let size = crate::search::tantivy::index_size(index_path);
+        if size <= budget {
+            break;
+        }
+        let counts = storage.message_counts_by_conversation()?;
+        let messages: u64 = counts.values().sum();
+        if messages == 0 {
+            break;
+        }
+        let per_message = (size / messages).max(1);
+        let mut excess = size - budget;
+        let mut oldest = Vec::new();
+        for conv in storage.oldest_conversations(500)? {
+            if excess == 0 {
+                break;
+            }
+            let stored = conv.id.and_then(|id| counts.get(&id)).copied().unwrap_or(0);
+            excess = excess.saturating_sub((stored * per_message).max(1));
+            oldest.push(conv);
+        }
+        if oldest.is_empty() {
+            break;
+        }
+        for conv in oldest {
+            tracing::warn!(
+                path = %conv.source_path.display(),
+                started_at = ?conv.started_at,
+                "evicted conversation to stay under max_index_size_mb"
+            );
+            // Agents that keep every conversation in one database file (Cursor, Crush,
+            // OpenCode) share a source path, so only a file's sole conversation takes
+            // the whole file with it.
+            if storage
+                .conversations_for_source_path(&conv.source_path)?
+                .len()
+                <= 1
+            {
+                storage.delete_source_path(&conv.source_path)?;
+                t_index.delete_source_path(&conv.source_path);
+            } else if let Some(id) = conv.id {
+                storage.delete_conversation(id)?;
+                t_index.delete_conversation(&crate::search::tantivy::conversation_key(
+                    &conv.agent_slug,
+                    conv.external_id.as_deref(),
+                    &conv.source_path.to_string_lossy(),
+                ))?;
+            }
+            evicted.push(EvictedConversation {
+                agent: conv.agent_slug,
+                source_path: conv.source_path,
+                title: conv.title,
+                started_at: conv.started_at,
+            });
+        }
+        t_index.commit()?;
+        t_index.force_merge()?;
+        t_index.garbage_collect()?;
+    }
+    Ok(evicted)
+}
+
+/// Remove what was indexed from session files deleted since the last run (see
+/// [`FileCheckpoints::vanished`]). Returns how many conversations were purged.
+fn purge_vanished(
+    store: &FileCheckpoints,
+    storage: &mut SqliteStorage,
+    t_index: &mut TantivyIndex,
+) -> Result<usize> {
+    let mut purged = 0;
+    for path in store.vanished() {
+        let conversations = storage.delete_source_path(&path)?;
+        t_index.delete_source_path(&path);
+        store.forget(&path);
+        tracing::info!(path = %path.display(), conversations, "purged deleted source");
+        purged += conversations;
+    }
+    Ok(purged)
 }
 
 fn ingest_batch(
@@ -286,6 +1168,93 @@ fn ingest_batch(
     Ok(())
 }
 
+/// Store conversations produced outside the scan pipeline (e.g. `cass import`)
+/// in the database and index under `data_dir`.
+pub fn ingest_conversations(
+    db_path: &Path,
+    data_dir: &Path,
+    convs: &[NormalizedConversation],
+) -> Result<()> {
+    let mut storage = SqliteStorage::open(db_path)?;
+    let index_path = index_dir(data_dir)?;
+    let current = index_is_current(&index_path);
+    let mut t_index = TantivyIndex::open_or_create(&index_path)?;
+    if !current {
+        rebuild_index_from_storage(&storage, &mut t_index, &None)?;
+    }
+    ingest_batch(&mut storage, &mut t_index, convs, &None)?;
+    t_index.commit()
+}
+
+/// Distinct paths held for the next watch flush before collapsing to a root rescan.
+const MAX_PENDING_PATHS: usize = 4096;
+/// Minimum time between two reindexes of the same file, so a session that is being
+/// streamed to disk is picked up periodically instead of on every write burst.
+const MIN_REINDEX_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Coalesces notify bursts into bounded, rate-limited reindex batches.
+#[derive(Debug, Default)]
+struct PendingPaths {
+    paths: HashSet<PathBuf>,
+    /// Set when more than `MAX_PENDING_PATHS` distinct paths arrived; the next flush
+    /// rescans the watch roots instead of tracking every file.
+    overflowed: bool,
+    last_reindexed: HashMap<PathBuf, Instant>,
+}
+
+/// Work released by `PendingPaths::take_ready`.
+#[derive(Debug, PartialEq, Eq)]
+enum WatchBatch {
+    Paths(Vec<PathBuf>),
+    Roots,
+}
+
+impl PendingPaths {
+    fn extend(&mut self, paths: impl IntoIterator<Item = PathBuf>) {
+        if self.overflowed {
+            return;
+        }
+        for path in paths {
+            if self.paths.len() >= MAX_PENDING_PATHS && !self.paths.contains(&path) {
+                self.paths.clear();
+                self.overflowed = true;
+                return;
+            }
+            self.paths.insert(path);
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.paths.is_empty() && !self.overflowed
+    }
+
+    /// Release the pending work that is due at `now`. Files reindexed less than
+    /// `MIN_REINDEX_INTERVAL` ago stay queued for a later flush.
+    fn take_ready(&mut self, now: Instant) -> Option<WatchBatch> {
+        self.last_reindexed
+            .retain(|_, at| now.duration_since(*at) < MIN_REINDEX_INTERVAL);
+
+        if self.overflowed {
+            self.overflowed = false;
+            return Some(WatchBatch::Roots);
+        }
+
+        let last_reindexed = &self.last_reindexed;
+        let (ready, deferred): (Vec<PathBuf>, Vec<PathBuf>) = self
+            .paths
+            .drain()
+            .partition(|p| !last_reindexed.contains_key(p));
+        self.paths.extend(deferred);
+        if ready.is_empty() {
+            return None;
+        }
+        for path in &ready {
+            self.last_reindexed.insert(path.clone(), now);
+        }
+        Some(WatchBatch::Paths(ready))
+    }
+}
+
 fn watch_sources<F: Fn(Vec<PathBuf>, bool) + Send + 'static>(
     watch_once_paths: Option<Vec<PathBuf>>,
     event_channel: Option<(Sender<IndexerEvent>, Receiver<IndexerEvent>)>,
@@ -313,8 +1282,20 @@ fn watch_sources<F: Fn(Vec<PathBuf>, bool) + Send + 'static>(
 
     let debounce = Duration::from_secs(2);
     let max_wait = Duration::from_secs(5);
-    let mut pending: Vec<PathBuf> = Vec::new();
-    let mut first_event: Option<std::time::Instant> = None;
+    let mut pending = PendingPaths::default();
+    let mut first_event: Option<Instant> = None;
+
+    let flush = |pending: &mut PendingPaths| match pending.take_ready(Instant::now()) {
+        Some(WatchBatch::Paths(paths)) => callback(paths, false),
+        Some(WatchBatch::Roots) => {
+            tracing::warn!(
+                limit = MAX_PENDING_PATHS,
+                "watch backlog overflowed; rescanning watch roots"
+            );
+            callback(watch_roots(), false);
+        }
+        None => {}
+    };
 
     loop {
         if pending.is_empty() {
@@ -322,7 +1303,7 @@ fn watch_sources<F: Fn(Vec<PathBuf>, bool) + Send + 'static>(
                 Ok(event) => match event {
                     IndexerEvent::Notify(paths) => {
                         pending.extend(paths);
-                        first_event = Some(std::time::Instant::now());
+                        first_event = Some(Instant::now());
                     }
                     IndexerEvent::Command(cmd) => match cmd {
                         ReindexCommand::Full => {
@@ -333,11 +1314,12 @@ fn watch_sources<F: Fn(Vec<PathBuf>, bool) + Send + 'static>(
                 Err(_) => break, // Channel closed
             }
         } else {
-            let now = std::time::Instant::now();
+            let now = Instant::now();
             let elapsed = now.duration_since(first_event.unwrap_or(now));
             if elapsed >= max_wait {
-                callback(std::mem::take(&mut pending), false);
-                first_event = None;
+                flush(&mut pending);
+                // Anything still pending was rate limited; wait another window.
+                first_event = (!pending.is_empty()).then(Instant::now);
                 continue;
             }
 
@@ -349,19 +1331,16 @@ fn watch_sources<F: Fn(Vec<PathBuf>, bool) + Send + 'static>(
                     IndexerEvent::Notify(paths) => pending.extend(paths),
                     IndexerEvent::Command(cmd) => match cmd {
                         ReindexCommand::Full => {
-                            // Flush pending first? Or discard?
-                            // Let's flush pending then do full.
-                            if !pending.is_empty() {
-                                callback(std::mem::take(&mut pending), false);
-                            }
+                            // A full rebuild covers everything pending.
+                            pending = PendingPaths::default();
                             callback(vec![], true);
                             first_event = None; // Reset debounce
                         }
                     },
                 },
                 Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
-                    callback(std::mem::take(&mut pending), false);
-                    first_event = None;
+                    flush(&mut pending);
+                    first_event = (!pending.is_empty()).then(Instant::now);
                 }
                 Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
             }
@@ -372,17 +1351,11 @@ fn watch_sources<F: Fn(Vec<PathBuf>, bool) + Send + 'static>(
 
 fn watch_roots() -> Vec<PathBuf> {
     let mut roots = vec![
-        std::env::var("CODEX_HOME").map_or_else(
-            |_| dirs::home_dir().unwrap_or_default().join(".codex/sessions"),
-            PathBuf::from,
-        ),
-        dirs::home_dir()
-            .unwrap_or_default()
-            .join(".config/Code/User/globalStorage/saoudrizwan.claude-dev"),
-        dirs::home_dir().unwrap_or_default().join(".gemini/tmp"),
-        dirs::home_dir()
-            .unwrap_or_default()
-            .join(".claude/projects"),
+        CodexConnector::home(),
+        ClineConnector::storage_root(),
+        GeminiConnector::new().root(),
+        GeminiConnector::qwen().root(),
+        ClaudeCodeConnector::projects_root(),
         dirs::home_dir()
             .unwrap_or_default()
             .join(".config/Code/User/globalStorage/sourcegraph.amp"),
@@ -405,9 +1378,34 @@ fn watch_roots() -> Vec<PathBuf> {
         roots.push(chat_base);
     }
 
+    // VS Code built-in chat sessions
+    roots.extend(crate::connectors::vscode_chat::VsCodeChatConnector::user_dirs());
+
+    // Crush project registry
+    roots.push(crate::connectors::crush::CrushConnector::global_dir());
+
+    // Plandex server home
+    roots.push(crate::connectors::plandex::PlandexConnector::home());
+
+    // Open WebUI exports / database
+    roots.push(crate::connectors::open_webui::OpenWebUiConnector::root());
+
+    // GitHub Copilot CLI session state
+    roots.push(crate::connectors::copilot_cli::CopilotCliConnector::home());
+
+    // shell-gpt chat cache
+    roots.push(crate::connectors::sgpt::SgptConnector::cache_dir());
+
     // Aider keeps history alongside the current workspace
     roots.push(std::env::current_dir().unwrap_or_default());
 
+    // Directories added with `extra_roots`
+    roots.extend(
+        ConnectorKind::ALL
+            .into_iter()
+            .flat_map(|kind| crate::config::extra_roots(kind.slug())),
+    );
+
     roots
 }
 
@@ -448,21 +1446,38 @@ fn reindex_paths(
     if triggers.is_empty() {
         return Ok(());
     }
+    let checkpoints = checkpoints::active(&opts.data_dir);
+    if force_full && let Some(store) = &checkpoints {
+        store.clear();
+    }
 
+    // Watch state only advances once the whole batch is committed.
+    let mut advanced: Vec<(ConnectorKind, i64)> = Vec::new();
     for (kind, ts) in triggers {
         let conn: Box<dyn Connector> = match kind {
             ConnectorKind::Codex => Box::new(CodexConnector::new()),
             ConnectorKind::Cline => Box::new(ClineConnector::new()),
             ConnectorKind::Gemini => Box::new(GeminiConnector::new()),
+            ConnectorKind::Qwen => Box::new(GeminiConnector::qwen()),
             ConnectorKind::Claude => Box::new(ClaudeCodeConnector::new()),
             ConnectorKind::Amp => Box::new(AmpConnector::new()),
             ConnectorKind::OpenCode => Box::new(OpenCodeConnector::new()),
             ConnectorKind::Aider => Box::new(AiderConnector::new()),
             ConnectorKind::Cursor => Box::new(CursorConnector::new()),
             ConnectorKind::ChatGpt => Box::new(ChatGptConnector::new()),
+            ConnectorKind::Sgpt => Box::new(SgptConnector::new()),
+            ConnectorKind::CopilotCli => Box::new(CopilotCliConnector::new()),
+            ConnectorKind::OpenWebUi => Box::new(OpenWebUiConnector::new()),
+            ConnectorKind::Plandex => Box::new(PlandexConnector::new()),
+            ConnectorKind::Crush => Box::new(CrushConnector::new()),
+            ConnectorKind::VsCodeChat => Box::new(VsCodeChatConnector::new()),
         };
-        let detect = conn.detect();
-        if !detect.detected {
+        if !crate::config::connector_enabled(kind.slug()) {
+            continue;
+        }
+        let detected = conn.detect().detected;
+        let extra_roots = existing_extra_roots(kind.slug());
+        if !detected && extra_roots.is_empty() {
             continue;
         }
 
@@ -487,34 +1502,66 @@ fn reindex_paths(
             data_root: opts.data_dir.clone(),
             since_ts,
         };
-        let convs = conn.scan(&ctx)?;
+        let mut convs = Vec::new();
+        scan_roots(
+            kind.slug(),
+            &*conn,
+            detected,
+            &extra_roots,
+            &ctx,
+            &mut |conv| {
+                convs.push(conv);
+                Ok(())
+            },
+        )?;
 
         // Update total and phase to indexing
         if let Some(p) = &opts.progress {
             p.total.fetch_add(convs.len(), Ordering::Relaxed);
             p.phase.store(2, Ordering::Relaxed);
+            p.set_current_agent(Some(kind.slug()));
         }
 
         tracing::info!(?kind, conversations = convs.len(), since_ts, "watch_scan");
         ingest_batch(&mut storage, &mut t_index, &convs, &opts.progress)?;
+        if let Some(ts_val) = ts {
+            advanced.push((kind, ts_val));
+        }
+    }
 
-        // Commit to Tantivy immediately to ensure index consistency before advancing watch state.
-        // This prevents a state where we think we've indexed up to T, but the index is stale.
-        t_index.commit()?;
+    t_index.sync_notes(&crate::notes::load(&opts.data_dir))?;
+    // One commit per batch, before advancing watch state: this prevents a state where
+    // we think we've indexed up to T, but the index is stale.
+    t_index.commit()?;
+    apply_size_budget(
+        &mut storage,
+        &mut t_index,
+        &index_dir(&opts.data_dir)?,
+        &opts.progress,
+    )?;
+    if let Some(store) = &checkpoints {
+        store.commit()?;
+    }
+    apply_embeddings(opts);
+    // Small watch commits pile up segments; merge them once the cooldown has passed.
+    if let Err(e) = t_index.optimize_if_idle() {
+        tracing::warn!(error = %e, "watch merge failed");
+    }
 
-        if let Some(ts_val) = ts {
-            let mut guard = state
-                .lock()
-                .map_err(|_| anyhow::anyhow!("state lock poisoned"))?;
+    if !advanced.is_empty() {
+        let mut guard = state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("state lock poisoned"))?;
+        for (kind, ts_val) in advanced {
             let entry = guard.entry(kind).or_insert(ts_val);
             *entry = (*entry).max(ts_val);
-            save_watch_state(&opts.data_dir, &guard)?;
         }
+        save_watch_state(&opts.data_dir, &guard)?;
     }
 
     // Reset phase to idle if progress exists
     if let Some(p) = &opts.progress {
-        p.phase.store(0, Ordering::Relaxed);
+        p.finish_pass();
     }
 
     Ok(())
@@ -531,6 +1578,56 @@ enum ConnectorKind {
     Aider,
     Cursor,
     ChatGpt,
+    Sgpt,
+    CopilotCli,
+    Qwen,
+    OpenWebUi,
+    Plandex,
+    Crush,
+    VsCodeChat,
+}
+
+impl ConnectorKind {
+    const ALL: [ConnectorKind; 16] = [
+        ConnectorKind::Codex,
+        ConnectorKind::Cline,
+        ConnectorKind::Gemini,
+        ConnectorKind::Claude,
+        ConnectorKind::Amp,
+        ConnectorKind::OpenCode,
+        ConnectorKind::Aider,
+        ConnectorKind::Cursor,
+        ConnectorKind::ChatGpt,
+        ConnectorKind::Sgpt,
+        ConnectorKind::CopilotCli,
+        ConnectorKind::Qwen,
+        ConnectorKind::OpenWebUi,
+        ConnectorKind::Plandex,
+        ConnectorKind::Crush,
+        ConnectorKind::VsCodeChat,
+    ];
+
+    /// Agent slug, which is also the connector's key in the config file.
+    fn slug(self) -> &'static str {
+        match self {
+            ConnectorKind::Codex => "codex",
+            ConnectorKind::Cline => "cline",
+            ConnectorKind::Gemini => "gemini",
+            ConnectorKind::Claude => "claude_code",
+            ConnectorKind::Amp => "amp",
+            ConnectorKind::OpenCode => "opencode",
+            ConnectorKind::Aider => "aider",
+            ConnectorKind::Cursor => "cursor",
+            ConnectorKind::ChatGpt => "chatgpt",
+            ConnectorKind::Sgpt => "sgpt",
+            ConnectorKind::CopilotCli => "copilot_cli",
+            ConnectorKind::Qwen => "qwen",
+            ConnectorKind::OpenWebUi => "open_webui",
+            ConnectorKind::Plandex => "plandex",
+            ConnectorKind::Crush => "crush",
+            ConnectorKind::VsCodeChat => "vscode_chat",
+        }
+    }
 }
 
 fn state_path(data_dir: &Path) -> PathBuf {
@@ -565,34 +1662,7 @@ fn classify_paths(paths: Vec<PathBuf>) -> Vec<(ConnectorKind, Option<i64>)> {
             && let Ok(dur) = time.duration_since(std::time::UNIX_EPOCH)
         {
             let ts = Some(dur.as_millis() as i64);
-            let s = p.to_string_lossy().replace('\\', "/");
-            let tag =
-                if s.contains(".codex") || s.contains("codex/sessions") || s.contains("rollout-") {
-                    Some(ConnectorKind::Codex)
-                } else if s.contains("saoudrizwan.claude-dev") || s.contains("cline") {
-                    Some(ConnectorKind::Cline)
-                } else if s.contains(".gemini/tmp") {
-                    Some(ConnectorKind::Gemini)
-                } else if s.contains(".claude/projects")
-                    || s.ends_with(".claude")
-                    || s.ends_with(".claude.json")
-                {
-                    Some(ConnectorKind::Claude)
-                } else if s.contains("sourcegraph.amp") || s.contains("/amp/") {
-                    Some(ConnectorKind::Amp)
-                } else if s.contains(".opencode") || s.contains("/opencode/") {
-                    Some(ConnectorKind::OpenCode)
-                } else if s.contains(".aider.chat.history.md") {
-                    Some(ConnectorKind::Aider)
-                } else if s.contains("Cursor/User") || s.contains("cursor/User") {
-                    Some(ConnectorKind::Cursor)
-                } else if s.contains("com.openai.chat") || s.contains("conversations-") {
-                    Some(ConnectorKind::ChatGpt)
-                } else {
-                    None
-                };
-
-            if let Some(kind) = tag {
+            if let Some(kind) = classify_path(&p) {
                 let entry = map.entry(kind).or_insert(None);
                 *entry = match (*entry, ts) {
                     (Some(prev), Some(cur)) => Some(prev.max(cur)),
@@ -605,10 +1675,71 @@ fn classify_paths(paths: Vec<PathBuf>) -> Vec<(ConnectorKind, Option<i64>)> {
     map.into_iter().collect()
 }
 
+/// The connector whose sessions live at `p`, from its configured roots or the
+/// telltale directory names of its default location.
+fn classify_path(p: &Path) -> Option<ConnectorKind> {
+    let s = p.to_string_lossy().replace('\\', "/");
+    // Roots moved in the config file rarely keep the telltale directory names.
+    let configured = ConnectorKind::ALL.into_iter().find(|kind| {
+        crate::config::connector_root(kind.slug()).is_some_and(|root| p.starts_with(root))
+            || crate::config::extra_roots(kind.slug())
+                .iter()
+                .any(|root| p.starts_with(root))
+    });
+    if configured.is_some() {
+        configured
+    } else if s.contains(".codex") || s.contains("codex/sessions") || s.contains("rollout-") {
+        Some(ConnectorKind::Codex)
+    } else if s.contains("/chatSessions/") || s.contains("emptyWindowChatSessions") {
+        Some(ConnectorKind::VsCodeChat)
+    } else if s.contains("saoudrizwan.claude-dev") || s.contains("cline") {
+        Some(ConnectorKind::Cline)
+    } else if s.contains(".gemini/tmp") {
+        Some(ConnectorKind::Gemini)
+    } else if s.contains(".qwen/tmp") {
+        Some(ConnectorKind::Qwen)
+    } else if s.contains(".claude/projects")
+        || s.ends_with(".claude")
+        || s.ends_with(".claude.json")
+    {
+        Some(ConnectorKind::Claude)
+    } else if s.contains("sourcegraph.amp") || s.contains("/amp/") {
+        Some(ConnectorKind::Amp)
+    } else if s.contains(".opencode") || s.contains("/opencode/") {
+        Some(ConnectorKind::OpenCode)
+    } else if s.contains(".aider.chat.history.md") || s.contains(".aider.llm.history") {
+        Some(ConnectorKind::Aider)
+    } else if s.contains("Cursor/User") || s.contains("cursor/User") {
+        Some(ConnectorKind::Cursor)
+    } else if s.contains("com.openai.chat") || s.contains("conversations-") {
+        Some(ConnectorKind::ChatGpt)
+    } else if s.contains("shell_gpt/chat_cache") {
+        Some(ConnectorKind::Sgpt)
+    } else if s.contains(".copilot/session-state") || s.contains(".copilot/history-session-state") {
+        Some(ConnectorKind::CopilotCli)
+    } else if s.contains(".open-webui") || s.ends_with("webui.db") {
+        Some(ConnectorKind::OpenWebUi)
+    } else if s.contains(".plandex") {
+        Some(ConnectorKind::Plandex)
+    } else if s.contains(".crush/crush.db") || s.contains("share/crush") {
+        Some(ConnectorKind::Crush)
+    } else {
+        None
+    }
+}
+
+/// The connector name (as in `cass index --agent`) for sessions at `path`, if it can
+/// be told from the path alone.
+pub fn connector_for_path(path: &Path) -> Option<&'static str> {
+    classify_path(path).map(ConnectorKind::slug)
+}
+
 pub mod persist {
+    use std::collections::HashMap;
+
     use anyhow::Result;
 
-    use crate::connectors::NormalizedConversation;
+    use crate::connectors::{NormalizedConversation, NormalizedMessage, NormalizedSnippet};
     use crate::model::types::{Agent, AgentKind, Conversation, Message, MessageRole, Snippet};
     use crate::search::tantivy::TantivyIndex;
     use crate::storage::sqlite::{InsertOutcome, SqliteStorage};
@@ -669,6 +1800,21 @@ pub mod persist {
         };
         let agent_id = storage.ensure_agent(&agent)?;
 
+        // Connectors without workspace metadata (cline, imports) get the git root
+        // of the paths the conversation touches.
+        let inferred;
+        let conv = if conv.workspace.is_none()
+            && let Some(ws) = crate::connectors::infer_workspace(conv)
+        {
+            inferred = NormalizedConversation {
+                workspace: Some(ws),
+                ..conv.clone()
+            };
+            &inferred
+        } else {
+            conv
+        };
+
         let workspace_id = if let Some(ws) = &conv.workspace {
             Some(storage.ensure_workspace(ws, None)?)
         } else {
@@ -695,6 +1841,59 @@ pub mod persist {
         Ok(())
     }
 
+    /// Rebuild the connector-level form of a stored conversation, e.g. to re-index it.
+    /// `snippets` maps message ids to their snippets, as from `fetch_snippets`.
+    pub fn from_internal(
+        conv: Conversation,
+        messages: Vec<Message>,
+        mut snippets: HashMap<i64, Vec<Snippet>>,
+    ) -> NormalizedConversation {
+        NormalizedConversation {
+            agent_slug: conv.agent_slug,
+            external_id: conv.external_id,
+            title: conv.title,
+            workspace: conv.workspace,
+            source_path: conv.source_path,
+            started_at: conv.started_at,
+            ended_at: conv.ended_at,
+            metadata: conv.metadata_json,
+            messages: messages
+                .into_iter()
+                .map(|m| NormalizedMessage {
+                    idx: m.idx,
+                    role: role_name(&m.role),
+                    author: m.author,
+                    created_at: m.created_at,
+                    content: m.content,
+                    extra: m.extra_json,
+                    snippets: m
+                        .id
+                        .and_then(|id| snippets.remove(&id))
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|s| NormalizedSnippet {
+                            file_path: s.file_path,
+                            start_line: s.start_line,
+                            end_line: s.end_line,
+                            language: s.language,
+                            snippet_text: s.snippet_text,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn role_name(role: &MessageRole) -> String {
+        match role {
+            MessageRole::User => "user".into(),
+            MessageRole::Agent => "agent".into(),
+            MessageRole::Tool => "tool".into(),
+            MessageRole::System => "system".into(),
+            MessageRole::Other(other) => other.clone(),
+        }
+    }
+
     fn map_role(role: &str) -> MessageRole {
         match role {
             "user" => MessageRole::User,
@@ -839,6 +2038,68 @@ mod tests {
         assert_eq!(reader.searcher().num_docs(), 3);
     }
 
+    #[test]
+    fn persist_infers_missing_workspace_from_git_root() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let mut storage = SqliteStorage::open(&data_dir.join("db.sqlite")).unwrap();
+        ensure_fts_schema(storage.raw());
+        let mut index = TantivyIndex::open_or_create(&index_dir(&data_dir).unwrap()).unwrap();
+
+        let mut msg = norm_msg(0, 100);
+        msg.content = format!("see `{}` for the bug", repo.join("src/lib.rs").display());
+        let mut conv = norm_conv(Some("ext"), vec![msg]);
+        conv.workspace = None;
+        persist::persist_conversation(&mut storage, &mut index, &conv).unwrap();
+
+        let workspaces = storage.list_workspaces().unwrap();
+        assert_eq!(workspaces.len(), 1);
+        assert_eq!(workspaces[0].path, repo);
+    }
+
+    #[test]
+    fn pending_paths_coalesce_and_rate_limit_hot_files() {
+        let mut pending = PendingPaths::default();
+        let hot = PathBuf::from("/tmp/.codex/sessions/rollout-hot.jsonl");
+        let cold = PathBuf::from("/tmp/.codex/sessions/rollout-cold.jsonl");
+
+        pending.extend(vec![hot.clone(); 50]);
+        let t0 = Instant::now();
+        assert_eq!(
+            pending.take_ready(t0),
+            Some(WatchBatch::Paths(vec![hot.clone()]))
+        );
+
+        // The hot file keeps streaming; it is held back until the interval passes.
+        pending.extend([hot.clone(), cold.clone()]);
+        assert_eq!(
+            pending.take_ready(t0 + Duration::from_secs(1)),
+            Some(WatchBatch::Paths(vec![cold]))
+        );
+        pending.extend([hot.clone()]);
+        assert_eq!(pending.take_ready(t0 + Duration::from_secs(2)), None);
+        assert!(!pending.is_empty());
+        assert_eq!(
+            pending.take_ready(t0 + MIN_REINDEX_INTERVAL),
+            Some(WatchBatch::Paths(vec![hot]))
+        );
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn pending_paths_overflow_collapses_to_root_rescan() {
+        let mut pending = PendingPaths::default();
+        pending.extend((0..=MAX_PENDING_PATHS).map(|i| PathBuf::from(format!("/tmp/s{i}.jsonl"))));
+        assert!(pending.paths.is_empty(), "overflow should release memory");
+        assert!(!pending.is_empty());
+        assert_eq!(pending.take_ready(Instant::now()), Some(WatchBatch::Roots));
+        assert!(pending.is_empty());
+    }
+
     #[test]
     fn classify_paths_uses_latest_mtime_per_connector() {
         let tmp = TempDir::new().unwrap();
@@ -935,6 +2196,10 @@ mod tests {
             data_dir: data_dir.clone(),
             progress: None,
             watch_once_paths: None,
+            commit_policy: super::CommitPolicy::default(),
+            remotes: Vec::new(),
+            agents: Vec::new(),
+            paths: Vec::new(),
         };
 
         // Manually set up dependencies for reindex_paths
@@ -1041,6 +2306,10 @@ CREATE VIRTUAL TABLE fts_messages USING fts5(
             db_path: data_dir.join("db.sqlite"),
             data_dir: data_dir.clone(),
             progress: Some(progress.clone()),
+            commit_policy: super::CommitPolicy::default(),
+            remotes: Vec::new(),
+            agents: Vec::new(),
+            paths: Vec::new(),
         };
 
         let storage = SqliteStorage::open(&opts.db_path).unwrap();
@@ -1064,6 +2333,8 @@ CREATE VIRTUAL TABLE fts_messages USING fts5(
         assert_eq!(progress.current.load(Ordering::Relaxed), 1);
         // Phase resets to 0 (idle) at the end
         assert_eq!(progress.phase.load(Ordering::Relaxed), 0);
+        assert_eq!(progress.completed_passes.load(Ordering::Relaxed), 1);
+        assert!(progress.current_agent.lock().unwrap().is_none());
 
         // Explicitly drop resources to release locks before cleanup
         drop(t_index);
@@ -1076,4 +2347,370 @@ CREATE VIRTUAL TABLE fts_messages USING fts5(
             unsafe { std::env::remove_var("XDG_DATA_HOME") };
         }
     }
+
+    struct BulkConnector;
+
+    impl Connector for BulkConnector {
+        fn detect(&self) -> crate::connectors::DetectionResult {
+            crate::connectors::DetectionResult {
+                detected: true,
+                evidence: vec!["test".into()],
+            }
+        }
+
+        fn scan(
+            &self,
+            _ctx: &crate::connectors::ScanContext,
+        ) -> anyhow::Result<Vec<NormalizedConversation>> {
+            Ok((0..(WRITER_BATCH_SIZE * 3 + 5))
+                .map(|i| {
+                    let mut conv = norm_conv(Some(&format!("bulk-{i}")), vec![norm_msg(0, 10)]);
+                    conv.source_path = PathBuf::from(format!("/logs/bulk-{i}.jsonl"));
+                    conv
+                })
+                .collect())
+        }
+    }
+
+    /// Streams two full batches and a partial one, then fails mid-scan.
+    struct FailingStreamConnector;
+
+    impl Connector for FailingStreamConnector {
+        fn detect(&self) -> crate::connectors::DetectionResult {
+            crate::connectors::DetectionResult {
+                detected: true,
+                evidence: vec!["test".into()],
+            }
+        }
+
+        fn scan(
+            &self,
+            ctx: &crate::connectors::ScanContext,
+        ) -> anyhow::Result<Vec<NormalizedConversation>> {
+            crate::connectors::collect_streaming(self, ctx)
+        }
+
+        fn scan_streaming(
+            &self,
+            _ctx: &crate::connectors::ScanContext,
+            emit: &mut crate::connectors::EmitConversation,
+        ) -> anyhow::Result<()> {
+            for i in 0..(WRITER_BATCH_SIZE * 2 + 3) {
+                let mut conv = norm_conv(Some(&format!("stream-{i}")), vec![norm_msg(0, 10)]);
+                conv.source_path = PathBuf::from(format!("/logs/stream-{i}.jsonl"));
+                emit(conv)?;
+            }
+            anyhow::bail!("history truncated")
+        }
+    }
+
+    /// Checks `sessions/a.jsonl` under the data root, then fails to parse it while a
+    /// `broken` marker sits next to it.
+    struct FlakyFileConnector;
+
+    impl Connector for FlakyFileConnector {
+        fn detect(&self) -> crate::connectors::DetectionResult {
+            crate::connectors::DetectionResult {
+                detected: true,
+                evidence: vec!["test".into()],
+            }
+        }
+
+        fn scan(
+            &self,
+            ctx: &crate::connectors::ScanContext,
+        ) -> anyhow::Result<Vec<NormalizedConversation>> {
+            let file = ctx.data_root.join("sessions/a.jsonl");
+            if !ctx.file_changed(&file) {
+                return Ok(Vec::new());
+            }
+            if ctx.data_root.join("sessions/broken").exists() {
+                anyhow::bail!("cannot parse {}", file.display());
+            }
+            let mut conv = norm_conv(Some("flaky"), vec![norm_msg(0, 10)]);
+            conv.source_path = file;
+            Ok(vec![conv])
+        }
+    }
+
+    struct AbsentConnector;
+
+    impl Connector for AbsentConnector {
+        fn detect(&self) -> crate::connectors::DetectionResult {
+            crate::connectors::DetectionResult::not_found()
+        }
+
+        fn scan(
+            &self,
+            _ctx: &crate::connectors::ScanContext,
+        ) -> anyhow::Result<Vec<NormalizedConversation>> {
+            panic!("undetected connectors must not be scanned");
+        }
+    }
+
+    #[test]
+    fn scan_pipeline_ingests_every_batch_from_concurrent_connectors() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        let mut storage = SqliteStorage::open(&data_dir.join("db.sqlite")).unwrap();
+        ensure_fts_schema(storage.raw());
+        let mut index = TantivyIndex::open_or_create(&index_dir(&data_dir).unwrap()).unwrap();
+        let progress = Arc::new(IndexingProgress::default());
+
+        let factories: Vec<(&'static str, ConnectorFactory)> = vec![
+            ("bulk", || Box::new(BulkConnector)),
+            ("absent", || Box::new(AbsentConnector)),
+        ];
+        run_scan_pipeline(
+            factories,
+            &data_dir,
+            None,
+            &mut storage,
+            &mut index,
+            &Some(progress.clone()),
+            CommitPolicy::default(),
+        )
+        .unwrap();
+        index.commit().unwrap();
+
+        let expected = WRITER_BATCH_SIZE * 3 + 5;
+        let conv_count: i64 = storage
+            .raw()
+            .query_row("SELECT COUNT(*) FROM conversations", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(conv_count as usize, expected);
+
+        assert_eq!(progress.total.load(Ordering::Relaxed), expected);
+        assert_eq!(progress.current.load(Ordering::Relaxed), expected);
+        assert_eq!(progress.discovered_agents.load(Ordering::Relaxed), 1);
+
+        let reader = index.reader().unwrap();
+        reader.reload().unwrap();
+        assert_eq!(reader.searcher().num_docs() as usize, expected);
+    }
+
+    #[test]
+    fn scan_pipeline_keeps_conversations_streamed_before_a_scan_error() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        let mut storage = SqliteStorage::open(&data_dir.join("db.sqlite")).unwrap();
+        ensure_fts_schema(storage.raw());
+        let mut index = TantivyIndex::open_or_create(&index_dir(&data_dir).unwrap()).unwrap();
+        let progress = Arc::new(IndexingProgress::default());
+
+        let factories: Vec<(&'static str, ConnectorFactory)> =
+            vec![("stream", || Box::new(FailingStreamConnector))];
+        run_scan_pipeline(
+            factories,
+            &data_dir,
+            None,
+            &mut storage,
+            &mut index,
+            &Some(progress.clone()),
+            CommitPolicy::default(),
+        )
+        .unwrap();
+
+        let expected = WRITER_BATCH_SIZE * 2 + 3;
+        let conv_count: i64 = storage
+            .raw()
+            .query_row("SELECT COUNT(*) FROM conversations", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(conv_count as usize, expected);
+        assert_eq!(progress.total.load(Ordering::Relaxed), expected);
+    }
+
+    #[test]
+    fn files_of_a_failed_scan_are_read_again_next_run() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(data_dir.join("sessions")).unwrap();
+        std::fs::write(data_dir.join("sessions/a.jsonl"), "{}\n").unwrap();
+        std::fs::write(data_dir.join("sessions/broken"), "").unwrap();
+
+        let mut storage = SqliteStorage::open(&data_dir.join("db.sqlite")).unwrap();
+        ensure_fts_schema(storage.raw());
+        let mut index = TantivyIndex::open_or_create(&index_dir(&data_dir).unwrap()).unwrap();
+        let active = ActiveCheckpoints::activate(&data_dir, FileCheckpoints::load(&data_dir));
+
+        let mut scan = |storage: &mut SqliteStorage| {
+            let factories: Vec<(&'static str, ConnectorFactory)> =
+                vec![("flaky", || Box::new(FlakyFileConnector))];
+            run_scan_pipeline(
+                factories,
+                &data_dir,
+                None,
+                storage,
+                &mut index,
+                &None,
+                CommitPolicy::default(),
+            )
+            .unwrap();
+            active.store().commit().unwrap();
+            storage
+                .conversations_for_source_path(&data_dir.join("sessions/a.jsonl"))
+                .unwrap()
+                .len()
+        };
+
+        assert_eq!(scan(&mut storage), 0);
+        std::fs::remove_file(data_dir.join("sessions/broken")).unwrap();
+        assert_eq!(scan(&mut storage), 1, "the failed file is retried");
+    }
+
+    #[test]
+    fn scan_pipeline_commits_each_batch_when_policy_is_due() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        let mut storage = SqliteStorage::open(&data_dir.join("db.sqlite")).unwrap();
+        ensure_fts_schema(storage.raw());
+        let mut index = TantivyIndex::open_or_create(&index_dir(&data_dir).unwrap()).unwrap();
+
+        let factories: Vec<(&'static str, ConnectorFactory)> =
+            vec![("bulk", || Box::new(BulkConnector))];
+        let policy = CommitPolicy {
+            max_docs: WRITER_BATCH_SIZE,
+            max_interval: Duration::from_secs(3600),
+        };
+        run_scan_pipeline(
+            factories,
+            &data_dir,
+            None,
+            &mut storage,
+            &mut index,
+            &None,
+            policy,
+        )
+        .unwrap();
+
+        // No final commit: the three full batches are already searchable, the
+        // trailing partial batch is still pending.
+        let reader = index.reader().unwrap();
+        reader.reload().unwrap();
+        assert_eq!(reader.searcher().num_docs() as usize, WRITER_BATCH_SIZE * 3);
+    }
+
+    #[test]
+    fn size_budget_evicts_the_oldest_conversations_first() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let mut storage = SqliteStorage::open(&data_dir.join("db.sqlite")).unwrap();
+        ensure_fts_schema(storage.raw());
+        let index_path = index_dir(&data_dir).unwrap();
+        let mut index = TantivyIndex::open_or_create(&index_path).unwrap();
+
+        let convs: Vec<_> = (0..10)
+            .map(|day| {
+                let started = 1_700_000_000_000 + day * 86_400_000;
+                let msgs = (0..20)
+                    .map(|i| NormalizedMessage {
+                        content: format!("day{day} message{i} {}", "payload ".repeat(60)),
+                        ..norm_msg(i, started + i)
+                    })
+                    .collect();
+                NormalizedConversation {
+                    source_path: PathBuf::from(format!("/logs/day{day}.jsonl")),
+                    ..norm_conv(Some(&format!("day{day}")), msgs)
+                }
+            })
+            .collect();
+        ingest_batch(&mut storage, &mut index, &convs, &None).unwrap();
+        index.commit().unwrap();
+
+        let size = crate::search::tantivy::index_size(&index_path);
+        let budget = size / 2;
+        let evicted = enforce_size_budget(&mut storage, &mut index, &index_path, budget).unwrap();
+        assert!(crate::search::tantivy::index_size(&index_path) <= budget);
+        assert!(!evicted.is_empty() && evicted.len() < 10, "{evicted:?}");
+        for (day, conv) in evicted.iter().enumerate() {
+            assert_eq!(
+                conv.source_path,
+                PathBuf::from(format!("/logs/day{day}.jsonl"))
+            );
+        }
+        let remaining = storage.message_counts_by_source_path().unwrap();
+        assert_eq!(remaining.len(), 10 - evicted.len());
+        assert!(remaining.contains_key("/logs/day9.jsonl"));
+
+        assert!(
+            enforce_size_budget(&mut storage, &mut index, &index_path, budget)
+                .unwrap()
+                .is_empty(),
+            "already under budget"
+        );
+    }
+
+    #[test]
+    fn size_budget_keeps_newer_conversations_of_a_shared_source_file() {
+        let tmp = TempDir::new().unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let mut storage = SqliteStorage::open(&data_dir.join("db.sqlite")).unwrap();
+        ensure_fts_schema(storage.raw());
+        let index_path = index_dir(&data_dir).unwrap();
+        let mut index = TantivyIndex::open_or_create(&index_path).unwrap();
+
+        // Day 0 and day 9 live in one database file, like Cursor or Crush keep them.
+        let shared = PathBuf::from("/logs/store.db");
+        let convs: Vec<_> = (0..10)
+            .map(|day| {
+                let started = 1_700_000_000_000 + day * 86_400_000;
+                let msgs = (0..20)
+                    .map(|i| NormalizedMessage {
+                        content: format!("day{day} message{i} {}", "payload ".repeat(60)),
+                        ..norm_msg(i, started + i)
+                    })
+                    .collect();
+                let source_path = if day == 0 || day == 9 {
+                    shared.clone()
+                } else {
+                    PathBuf::from(format!("/logs/day{day}.jsonl"))
+                };
+                NormalizedConversation {
+                    source_path,
+                    ..norm_conv(Some(&format!("day{day}")), msgs)
+                }
+            })
+            .collect();
+        ingest_batch(&mut storage, &mut index, &convs, &None).unwrap();
+        index.commit().unwrap();
+
+        let budget = crate::search::tantivy::index_size(&index_path) / 2;
+        let evicted = enforce_size_budget(&mut storage, &mut index, &index_path, budget).unwrap();
+        assert_eq!(evicted[0].source_path, shared);
+        assert!(evicted.len() < 9, "{evicted:?}");
+
+        let left = storage.conversations_for_source_path(&shared).unwrap();
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].external_id.as_deref(), Some("day9"));
+        let docs = crate::search::tantivy::source_path_counts(&index_path).unwrap();
+        assert_eq!(docs.get("/logs/store.db").copied(), Some(20));
+    }
+
+    #[test]
+    fn commit_tracker_fires_on_docs_or_elapsed_time() {
+        let policy = CommitPolicy {
+            max_docs: 100,
+            max_interval: Duration::from_secs(10),
+        };
+        let t0 = Instant::now();
+        let mut tracker = CommitTracker::new(policy, t0);
+        assert!(!tracker.record(60, t0));
+        assert!(tracker.record(40, t0));
+        tracker.committed(t0);
+
+        assert!(
+            !tracker.record(0, t0 + Duration::from_secs(60)),
+            "nothing to commit"
+        );
+        assert!(!tracker.record(1, t0 + Duration::from_secs(5)));
+        assert!(tracker.record(1, t0 + Duration::from_secs(10)));
+    }
 }
@@ -0,0 +1,245 @@
+//! Index agent history from other machines.
+//!
+//! `cass index --remote user@host` copies the well-known agent directories from the
+//! remote home into `<data_dir>/remotes/<host>/` with rsync over ssh, then runs the
+//! regular connectors against that mirror. Mirrored conversations carry
+//! `metadata.host` and a `<host>:` prefix on their external id so they never merge
+//! with a local session of the same id. Mirrors stay on disk, so a full local rebuild
+//! re-reads them without contacting the remote again.
+//!
+//! `CASS_RSYNC` replaces the `rsync` binary (e.g. to add `-e "ssh -p 2222"` via a wrapper).
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+use crate::connectors::{
+    Connector, NormalizedConversation, ScanContext, amp::AmpConnector,
+    claude_code::ClaudeCodeConnector, cline::ClineConnector, codex::CodexConnector,
+    copilot_cli::CopilotCliConnector, gemini::GeminiConnector, opencode::OpenCodeConnector,
+    pi_agent::PiAgentConnector, plandex::PlandexConnector, sgpt::SgptConnector,
+};
+
+struct RemoteSource {
+    slug: &'static str,
+    /// Paths copied from the remote home.
+    paths: &'static [&'static str],
+    /// Directory inside the mirror handed to the connector as its data root.
+    scan_root: &'static str,
+    connector: fn() -> Box<dyn Connector + Send>,
+}
+
+/// Connectors whose history lives in fixed per-user directories.
+const SOURCES: &[RemoteSource] = &[
+    RemoteSource {
+        slug: "codex",
+        paths: &[".codex/sessions"],
+        scan_root: ".codex",
+        connector: || Box::new(CodexConnector::new()),
+    },
+    RemoteSource {
+        slug: "claude_code",
+        paths: &[".claude/projects"],
+        scan_root: ".claude",
+        connector: || Box::new(ClaudeCodeConnector::new()),
+    },
+    RemoteSource {
+        slug: "gemini",
+        paths: &[".gemini/tmp"],
+        scan_root: ".gemini/tmp",
+        connector: || Box::new(GeminiConnector::new()),
+    },
+    RemoteSource {
+        slug: "qwen",
+        paths: &[".qwen/tmp"],
+        scan_root: ".qwen/tmp",
+        connector: || Box::new(GeminiConnector::qwen()),
+    },
+    RemoteSource {
+        slug: "cline",
+        paths: &[".config/Code/User/globalStorage/saoudrizwan.claude-dev"],
+        scan_root: ".config/Code/User/globalStorage/saoudrizwan.claude-dev",
+        connector: || Box::new(ClineConnector::new()),
+    },
+    RemoteSource {
+        slug: "amp",
+        paths: &[".local/share/amp"],
+        scan_root: ".local/share/amp",
+        connector: || Box::new(AmpConnector::new()),
+    },
+    RemoteSource {
+        slug: "opencode",
+        paths: &[".local/share/opencode"],
+        scan_root: ".local/share/opencode",
+        connector: || Box::new(OpenCodeConnector::new()),
+    },
+    RemoteSource {
+        slug: "pi_agent",
+        paths: &[".pi/agent/sessions"],
+        scan_root: ".pi/agent",
+        connector: || Box::new(PiAgentConnector::new()),
+    },
+    RemoteSource {
+        slug: "sgpt",
+        paths: &[".config/shell_gpt/chat_cache"],
+        scan_root: ".config/shell_gpt/chat_cache",
+        connector: || Box::new(SgptConnector::new()),
+    },
+    RemoteSource {
+        slug: "copilot_cli",
+        paths: &[".copilot/session-state", ".copilot/history-session-state"],
+        scan_root: ".copilot",
+        connector: || Box::new(CopilotCliConnector::new()),
+    },
+    RemoteSource {
+        slug: "plandex",
+        paths: &[".plandex-home-v2"],
+        scan_root: ".plandex-home-v2",
+        connector: || Box::new(PlandexConnector::new()),
+    },
+];
+
+/// Host name recorded on conversations and used for the mirror dir: the part of
+/// `user@host` after the `@`, with anything unsafe in a file name replaced.
+pub fn host_name(remote: &str) -> String {
+    remote
+        .rsplit_once('@')
+        .map_or(remote, |(_, host)| host)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Where the mirror of `remote` lives under the data dir.
+pub fn mirror_dir(data_dir: &Path, remote: &str) -> PathBuf {
+    data_dir.join("remotes").join(host_name(remote))
+}
+
+/// Mirrors already on disk, as `(host, mirror dir)`.
+pub fn existing_mirrors(data_dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut mirrors: Vec<(String, PathBuf)> = std::fs::read_dir(data_dir.join("remotes"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| (e.file_name().to_string_lossy().into_owned(), e.path()))
+                .collect()
+        })
+        .unwrap_or_default();
+    mirrors.sort();
+    mirrors
+}
+
+/// Copy the agent directories of `remote` into its mirror.
+pub fn sync(remote: &str, data_dir: &Path) -> Result<PathBuf> {
+    crate::config::check_remote(remote).map_err(anyhow::Error::msg)?;
+    let mirror = mirror_dir(data_dir, remote);
+    std::fs::create_dir_all(&mirror)
+        .with_context(|| format!("creating mirror dir {}", mirror.display()))?;
+
+    let rsync = std::env::var("CASS_RSYNC").unwrap_or_else(|_| "rsync".to_string());
+    let mut cmd = Command::new(&rsync);
+    // --relative keeps `.codex/sessions` etc. at the same place under the mirror;
+    // agents that are not installed remotely are simply absent.
+    cmd.args(["-az", "--relative", "--ignore-missing-args"]);
+    // Everything after `--` is a source or the destination, never an option.
+    cmd.arg("--");
+    for source in SOURCES
+        .iter()
+        .filter(|s| crate::config::connector_enabled(s.slug))
+    {
+        for path in source.paths {
+            cmd.arg(format!("{remote}:{path}"));
+        }
+    }
+    cmd.arg(format!("{}/", mirror.display()));
+
+    tracing::info!(remote, mirror = %mirror.display(), "remote_sync");
+    let output = cmd
+        .output()
+        .with_context(|| format!("running {rsync} for {remote}"))?;
+    if !output.status.success() {
+        bail!(
+            "{rsync} from {remote} failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(mirror)
+}
+
+/// Run the connectors over a mirror and tag what they find with `host`.
+pub fn scan_mirror(host: &str, mirror: &Path) -> Vec<NormalizedConversation> {
+    let mut convs = Vec::new();
+    for source in SOURCES
+        .iter()
+        .filter(|s| crate::config::connector_enabled(s.slug))
+    {
+        let root = mirror.join(source.scan_root);
+        if !root.exists() {
+            continue;
+        }
+        let ctx = ScanContext {
+            data_root: root,
+            since_ts: None,
+        };
+        let found = match (source.connector)().scan(&ctx) {
+            Ok(found) => found,
+            Err(e) => {
+                tracing::warn!(host, connector = source.slug, "remote scan failed: {e:#}");
+                continue;
+            }
+        };
+        // A connector that did not recognise the mirror falls back to local dirs;
+        // only keep what actually came from the mirror.
+        convs.extend(
+            found
+                .into_iter()
+                .filter(|c| c.source_path.starts_with(mirror)),
+        );
+    }
+    for conv in &mut convs {
+        tag_host(conv, host);
+    }
+    convs
+}
+
+fn tag_host(conv: &mut NormalizedConversation, host: &str) {
+    conv.external_id = Some(format!(
+        "{host}:{}",
+        conv.external_id
+            .as_deref()
+            .unwrap_or(&conv.source_path.to_string_lossy())
+    ));
+    match conv.metadata.as_object_mut() {
+        Some(obj) => {
+            obj.insert("host".into(), host.into());
+        }
+        None => {
+            conv.metadata = serde_json::json!({ "host": host, "original": conv.metadata });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirror_dirs_use_the_host_part() {
+        let data = Path::new("/data");
+        assert_eq!(host_name("me@box1"), "box1");
+        assert_eq!(host_name("box2"), "box2");
+        assert_eq!(
+            mirror_dir(data, "me@dev box:22"),
+            PathBuf::from("/data/remotes/dev_box_22")
+        );
+    }
+}
@@ -1,10 +1,24 @@
 pub mod bookmarks;
+pub mod clipboard;
+pub mod config;
 pub mod connectors;
+pub mod daemon;
 pub mod export;
+pub mod hyperlink;
+pub mod i18n;
 pub mod indexer;
 pub mod model;
+pub mod notes;
+pub mod permalink;
+pub mod pins;
+pub mod repl;
+pub mod resume;
+pub mod saved_searches;
 pub mod search;
+pub mod serve;
+pub mod session_tags;
 pub mod storage;
+pub mod telemetry;
 pub mod ui;
 pub mod update_check;
 
@@ -12,7 +26,10 @@ use anyhow::Result;
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD as BASE64;
 use chrono::Utc;
-use clap::{Arg, ArgAction, Command, CommandFactory, Parser, Subcommand, ValueEnum, ValueHint};
+use clap::{
+    Arg, ArgAction, Command, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum,
+    ValueHint,
+};
 use indexer::IndexOptions;
 use reqwest::Client;
 use semver::Version;
@@ -25,6 +42,35 @@ use tracing::{info, warn};
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 
 const CONTRACT_VERSION: &str = "1";
+
+/// Major version of each robot output shape. Every such output carries it as
+/// `"schema": "cass.<shape>.v<N>"`, and `cass schema` prints the shape's JSON Schema.
+/// Within a major version a shape only gains fields; removing, renaming or retyping
+/// one bumps it. Untagged output from earlier releases counts as v1.
+const ROBOT_SCHEMAS: &[(&str, u32)] = &[
+    ("search", 2),
+    ("status", 2),
+    ("state", 2),
+    ("health", 2),
+    ("stats", 2),
+    ("diag", 2),
+    ("doctor", 2),
+    ("view", 2),
+    ("index", 2),
+    ("capabilities", 2),
+    ("api-version", 2),
+    ("introspect", 2),
+    ("error", 2),
+];
+
+/// The `schema` tag of robot output shape `shape`, e.g. `cass.search.v2`.
+pub(crate) fn schema_tag(shape: &str) -> String {
+    let major = ROBOT_SCHEMAS
+        .iter()
+        .find(|(name, _)| *name == shape)
+        .map_or(1, |(_, major)| *major);
+    format!("cass.{shape}.v{major}")
+}
 const DEFAULT_STALE_THRESHOLD_SECS: u64 = 1800;
 
 fn read_watch_once_paths_env() -> Option<Vec<std::path::PathBuf>> {
@@ -59,13 +105,18 @@ pub struct Cli {
     #[arg(long)]
     pub trace_file: Option<PathBuf>,
 
-    /// Reduce log noise (warnings and errors only)
-    #[arg(long, short = 'q', default_value_t = false)]
-    pub quiet: bool,
+    /// Reduce log noise: -q warnings and errors only, -qq errors only
+    #[arg(long, short = 'q', action = ArgAction::Count)]
+    pub quiet: u8,
+
+    /// Increase verbosity: -v debug information, -vv trace
+    #[arg(long, short = 'v', action = ArgAction::Count)]
+    pub verbose: u8,
 
-    /// Increase verbosity (show debug information)
-    #[arg(long, short = 'v', default_value_t = false)]
-    pub verbose: bool,
+    /// Also log to the daily-rotated `cass.log` in the data dir (`[logging] file` in
+    /// the config turns this on for every run)
+    #[arg(long, default_value_t = false)]
+    pub log_file: bool,
 
     /// Color behavior for CLI output
     #[arg(long, value_enum, default_value_t = ColorPref::Auto)]
@@ -100,6 +151,16 @@ pub enum Commands {
         #[arg(long, default_value_t = false)]
         reset_state: bool,
 
+        /// Keep UI preferences (sort, ranking, layout, ...) under this profile name
+        /// instead of the default; also read from `CASS_PROFILE`
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Draw in this many rows under the prompt instead of taking over the whole
+        /// terminal, like `fzf --height` (at least 12)
+        #[arg(long, value_name = "ROWS")]
+        height: Option<u16>,
+
         /// Override data dir (matches index --data-dir)
         #[arg(long)]
         data_dir: Option<PathBuf>,
@@ -122,18 +183,63 @@ pub enum Commands {
         #[arg(long, value_delimiter = ',', num_args = 1..)]
         watch_once: Option<Vec<PathBuf>>,
 
+        /// Commit the index after this many new messages so a large first index becomes
+        /// searchable progressively (default 20000)
+        #[arg(long, value_name = "N")]
+        commit_every: Option<usize>,
+
+        /// Commit the index at least this often while indexing, in seconds (default 30)
+        #[arg(long, value_name = "SECS")]
+        commit_interval: Option<u64>,
+
         /// Override data dir (index + db). Defaults to platform data dir.
         #[arg(long)]
         data_dir: Option<PathBuf>,
 
+        /// Also mirror and index user@host over rsync/ssh (repeatable); with no value,
+        /// every host in the config file's `remotes` list
+        #[arg(long, value_name = "USER@HOST", num_args = 0..=1)]
+        remote: Option<Vec<String>>,
+
+        /// Only scan these connectors (comma-separated or repeated, e.g. codex,claude_code)
+        #[arg(long, value_delimiter = ',', value_name = "AGENT", conflicts_with_all = ["force_rebuild", "watch", "watch_once", "remote"])]
+        agent: Vec<String>,
+
+        /// Only rescan sessions under this file or directory (repeatable); the connector
+        /// is told from the path unless --agent names it
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["force_rebuild", "watch", "watch_once", "remote", "dry_run"])]
+        path: Vec<PathBuf>,
+
+        /// Detect and scan every connector and report what would be ingested and which
+        /// paths were skipped, without writing the database or index
+        #[arg(long, conflicts_with_all = ["watch", "watch_once", "remote", "idempotency_key"])]
+        dry_run: bool,
+
         /// Output as JSON (for automation)
-        #[arg(long)]
+        #[arg(long, visible_alias = "robot")]
         json: bool,
 
         /// Idempotency key for safe retries. If the same key is used with identical parameters,
         /// the cached result is returned. Keys expire after 24 hours.
         #[arg(long)]
         idempotency_key: Option<String>,
+
+        /// `optimize`: merge the search index's segments instead of indexing
+        #[arg(value_enum)]
+        action: Option<IndexAction>,
+
+        /// With `optimize`, merge even when the segment count is below the threshold
+        #[arg(long, requires = "action")]
+        force: bool,
+
+        /// Print the search index's segment count and merge state, then exit
+        #[arg(long, conflicts_with_all = ["action", "full", "force_rebuild", "watch", "watch_once", "remote", "dry_run"])]
+        merge_status: bool,
+
+        /// If another `cass index` (or the watcher) is writing the index, wait up to SECS
+        /// for it to finish instead of failing (default 600)
+        #[arg(long, value_name = "SECS", num_args = 0..=1, default_missing_value = "600")]
+        wait: Option<u64>,
     },
     /// Generate shell completions to stdout
     Completions {
@@ -151,7 +257,8 @@ pub enum Commands {
     /// Run a one-off search and print results to stdout
     Search {
         /// The query string
-        query: String,
+        #[arg(required_unless_present = "saved")]
+        query: Option<String>,
         /// Filter by agent slug (can be specified multiple times)
         #[arg(long)]
         agent: Vec<String>,
@@ -207,10 +314,12 @@ pub enum Commands {
         /// Filter to last 7 days
         #[arg(long)]
         week: bool,
-        /// Filter to entries since ISO date (YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS)
+        /// Filter to entries since a time: a date (2024-06-15, 2024-06), a span back from now
+        /// (7d, 24h, 3mo) or a name (yesterday, last monday, this month)
         #[arg(long)]
         since: Option<String>,
-        /// Filter to entries until ISO date
+        /// Filter to entries until a time, in the same forms as --since; a date or month
+        /// names its start
         #[arg(long)]
         until: Option<String>,
         /// Server-side aggregation by field(s). Comma-separated: `agent,workspace,date,match_type`
@@ -226,17 +335,87 @@ pub enum Commands {
         /// Timeout in milliseconds. Returns partial results and error if exceeded.
         #[arg(long)]
         timeout: Option<u64>,
-        /// Highlight matching terms in output (uses **bold** markers in text, <mark> in HTML)
+        /// Highlight matching terms in output (uses **bold** markers in text, <mark> in HTML;
+        /// robot output adds per-hit `highlights` match offsets instead)
         #[arg(long)]
         highlight: bool,
+        /// Snippet length in characters; snippets are centered on the best-matching part
+        /// of the message [default: 160]
+        #[arg(long, value_name = "N")]
+        snippet_chars: Option<usize>,
+        /// Collapse hits from the same conversation into one result; --limit/--offset
+        /// then count conversations
+        #[arg(long, value_enum)]
+        group_by: Option<GroupBy>,
+        /// With --group-by: best-matching messages to keep per group
+        #[arg(long, default_value_t = 3, requires = "group_by")]
+        group_size: usize,
+        /// Match every plain term fuzzily, as if written `term~`: typos within an edit
+        /// distance of 1-2 still match, ranked below exact matches
+        #[arg(long)]
+        fuzzy: bool,
+        /// Rank messages by meaning rather than by terms, using the embeddings `cass index`
+        /// builds when `[embeddings]` is configured
+        #[arg(long, conflicts_with_all = ["fuzzy", "aggregate", "explain", "dry_run"])]
+        semantic: bool,
+        /// Favor recent messages: a new message scores up to 1+WEIGHT times an equally
+        /// relevant old one, fading with age (`[ranking] recency_half_life_days`). The
+        /// TUI's Balanced ranking uses 0.4
+        #[arg(long, value_name = "WEIGHT", conflicts_with = "semantic")]
+        recency: Option<f32>,
+        /// Collapse copies of the same message (e.g. one session captured by two
+        /// connectors) into the best-scoring one; `--dedupe=false` lists every copy
+        #[arg(
+            long,
+            value_name = "BOOL",
+            num_args = 0..=1,
+            default_value_t = true,
+            default_missing_value = "true",
+            action = ArgAction::Set
+        )]
+        dedupe: bool,
+        /// Only messages of at least N tokens (approximate), e.g. to skip one-word replies
+        #[arg(long, value_name = "N", visible_alias = "min-tokens")]
+        min_message_tokens: Option<u64>,
+        /// Only messages of at most N tokens (approximate); --max-tokens is the output budget
+        #[arg(long, value_name = "N")]
+        max_message_tokens: Option<u64>,
+        /// With --robot: print each hit as one JSON line as soon as it is found, then a
+        /// `_summary` line, instead of a single object at the end
+        #[arg(long, conflicts_with_all = ["aggregate", "group_by", "semantic", "explain", "cursor", "max_tokens"])]
+        stream: bool,
+        /// Save the query and its filters under NAME (in the data dir), then run it
+        #[arg(long, value_name = "NAME")]
+        save: Option<String>,
+        /// Run the search saved under NAME; a query or filter given here replaces the
+        /// saved one
+        #[arg(long, value_name = "NAME")]
+        saved: Option<String>,
+        /// Only sessions tagged TAG with `cass tag add` (can be repeated; any tag matches)
+        #[arg(long, value_name = "TAG")]
+        tag: Vec<String>,
+        /// Only sessions bookmarked with `cass bookmark add` (or holding a bookmarked message)
+        #[arg(long)]
+        bookmarked: bool,
+        /// Also put the first hit's content, snippet or path on the clipboard
+        /// (`[clipboard]` in the config picks how)
+        #[arg(
+            long,
+            value_enum,
+            value_name = "WHAT",
+            num_args = 0..=1,
+            default_missing_value = "content",
+            conflicts_with = "stream"
+        )]
+        copy: Option<CopyTarget>,
     },
     /// Show statistics about indexed data
     Stats {
         /// Override data dir
         #[arg(long)]
         data_dir: Option<PathBuf>,
-        /// Output as JSON
-        #[arg(long)]
+        /// Output as JSON (--robot also works)
+        #[arg(long, visible_alias = "robot")]
         json: bool,
     },
     /// Output diagnostic information for troubleshooting
@@ -251,6 +430,23 @@ pub enum Commands {
         #[arg(long, short)]
         verbose: bool,
     },
+    /// Check cass's data for damage; exit 1 if any is found and left unrepaired
+    Doctor {
+        /// Validate the search index: meta.json, schema hash, segment files and checksums
+        /// (the only check so far, run when none is named)
+        #[arg(long)]
+        index: bool,
+        /// Fix what was found: damaged segments are dropped and their documents re-added
+        /// from the database; an unreadable or outdated index is rebuilt from it
+        #[arg(long)]
+        repair: bool,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (--robot also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
     /// Quick health check for agents: index freshness, db stats, recommended action
     Status {
         /// Override data dir
@@ -299,6 +495,11 @@ pub enum Commands {
         #[arg(long)]
         json: bool,
     },
+    /// Print the JSON Schema of every versioned robot output shape, or of SHAPE alone
+    Schema {
+        /// Output shape, e.g. search, status or error (all when omitted)
+        shape: Option<String>,
+    },
     /// View a source file at a specific line (follow up on search results)
     View {
         /// Path to the source file
@@ -328,6 +529,39 @@ pub enum Commands {
         #[arg(long, default_value = "300")]
         stale_threshold: u64,
     },
+    /// Keep the index current: catch up, then reindex session files as agents write them
+    Watch {
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List conversations by most recent activity, without a query
+    List {
+        /// Filter by agent slug (can be specified multiple times)
+        #[arg(long)]
+        agent: Vec<String>,
+        /// Filter by workspace path or its last component (can be specified multiple times)
+        #[arg(long)]
+        workspace: Vec<String>,
+        /// Only sessions active since (7d, yesterday, 2024-06-15, ...)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only sessions active until
+        #[arg(long)]
+        until: Option<String>,
+        /// Max sessions (default: 20)
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (--robot also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
     /// Find related sessions for a given source path
     Context {
         /// Path to the source session file
@@ -345,7 +579,14 @@ pub enum Commands {
     /// Export a conversation to markdown or other formats
     Export {
         /// Path to session file
-        path: PathBuf,
+        #[arg(required_unless_present = "query", conflicts_with = "query")]
+        path: Option<PathBuf>,
+        /// Export the conversations that best match this search query instead of a path
+        #[arg(long)]
+        query: Option<String>,
+        /// With --query: how many conversations to export, best match first
+        #[arg(long, default_value_t = 1, requires = "query")]
+        limit: usize,
         /// Output format
         #[arg(long, value_enum, default_value_t = ConvExportFormat::Markdown)]
         format: ConvExportFormat,
@@ -355,6 +596,9 @@ pub enum Commands {
         /// Include tool use details in export
         #[arg(long)]
         include_tools: bool,
+        /// Data dir whose index is read when the session file no longer exists
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
     },
     /// Show messages around a specific line in a session file
     Expand {
@@ -370,9 +614,79 @@ pub enum Commands {
         #[arg(long)]
         json: bool,
     },
+    /// Pretty-print a conversation, or a window around one message
+    Show {
+        /// Path to session file
+        path: PathBuf,
+        /// Zero-based message index to center on (`msg_idx` from permalinks and search hits)
+        #[arg(long)]
+        around_msg: Option<usize>,
+        /// Messages before/after --around-msg (default: 5)
+        #[arg(long, short = 'C', default_value_t = 5, requires = "around_msg")]
+        context: usize,
+        /// Output as JSON (--robot also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+        /// Data dir whose index is read when the session file no longer exists
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Also put the --around-msg message (else every message shown) or the session
+        /// path on the clipboard
+        #[arg(
+            long,
+            value_enum,
+            value_name = "WHAT",
+            num_args = 0..=1,
+            default_missing_value = "content"
+        )]
+        copy: Option<CopyTarget>,
+    },
+    /// Align two sessions (e.g. two attempts at one task) and show the prompts that
+    /// changed and the responses that diverged
+    Diff {
+        /// First session file
+        a: PathBuf,
+        /// Second session file
+        b: PathBuf,
+        /// Layout of the human-readable output
+        #[arg(long, value_enum, default_value_t = DiffMode::Unified)]
+        mode: DiffMode,
+        /// Total width for --mode side-by-side [default: --wrap, else the terminal width]
+        #[arg(long)]
+        width: Option<usize>,
+        /// Output as JSON (--robot also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+        /// Data dir whose index is read when a session file no longer exists
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
+    /// Search session files directly with a regex, without the index
+    Grep {
+        /// Regular expression matched against each line of message text
+        pattern: String,
+        /// Only scan these agents' sessions (can be repeated)
+        #[arg(long)]
+        agent: Vec<String>,
+        /// Match case-insensitively
+        #[arg(long, short = 'i')]
+        ignore_case: bool,
+        /// Treat the pattern as a literal string
+        #[arg(long, short = 'F')]
+        fixed_strings: bool,
+        /// Stop after this many matching lines
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Data dir handed to connectors that keep state there
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Print NDJSON matches followed by a `_summary` line (--robot also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
     /// Show activity timeline for a time range
     Timeline {
-        /// Start time (ISO date, 'today', 'yesterday', 'Nd' for N days ago)
+        /// Start time (ISO date, 'today', 'yesterday', 'Nd' for N days ago); default 7d
         #[arg(long)]
         since: Option<String>,
         /// End time (ISO date or relative)
@@ -384,16 +698,277 @@ pub enum Commands {
         /// Filter by agent (can be repeated)
         #[arg(long)]
         agent: Vec<String>,
+        /// Filter by workspace path (can be repeated)
+        #[arg(long)]
+        workspace: Vec<String>,
         /// Override data dir
         #[arg(long)]
         data_dir: Option<PathBuf>,
-        /// Output as JSON
-        #[arg(long)]
+        /// Output as JSON (--robot also works)
+        #[arg(long, visible_alias = "robot")]
         json: bool,
         /// Group by: hour, day, or none
-        #[arg(long, value_enum, default_value_t = TimelineGrouping::Hour)]
+        #[arg(long, value_enum, default_value_t = TimelineGrouping::Day)]
         group_by: TimelineGrouping,
     },
+    /// Run a persistent query daemon that keeps the index warm for `cass search`
+    Daemon {
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
+    /// Query the index line by line with a warm reader, without the TUI
+    Repl {
+        /// Hits per page
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
+    /// Serve search, list, show, stats and indexing over HTTP as robot-mode JSON
+    Serve {
+        /// Address to bind; use 0.0.0.0 to share with a team (needs --token)
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// Port to listen on (0 picks a free one)
+        #[arg(long, default_value_t = 7777)]
+        port: u16,
+        /// Require `Authorization: Bearer <TOKEN>` on every endpoint but /health; POST /index
+        /// is refused without one
+        #[arg(long, env = "CASS_SERVE_TOKEN", hide_env_values = true)]
+        token: Option<String>,
+        /// Origin allowed to call the API from a browser (can be repeated; `*` for any)
+        #[arg(long = "cors-origin", value_name = "ORIGIN")]
+        cors_origins: Vec<String>,
+        /// Refuse POST /index
+        #[arg(long)]
+        read_only: bool,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
+    /// Print the cass:// permalink for a message in an indexed session
+    Permalink {
+        /// Path to session file
+        path: PathBuf,
+        /// Message line number from search results (1-indexed); omit to link the whole session
+        #[arg(long, short = 'n')]
+        line: Option<usize>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the agent's own command for continuing an indexed session, or run it
+    Resume {
+        /// Path to session file (source_path from search results)
+        path: PathBuf,
+        /// Run the command (from the session's workspace) instead of printing it
+        #[arg(long, conflicts_with = "json")]
+        exec: bool,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Open a cass://<agent>/<conversation-id>#<msg_idx> permalink in the TUI
+    OpenUrl {
+        /// Permalink to open
+        url: String,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Resolve the link and print the target as JSON instead of launching the TUI
+        #[arg(long)]
+        json: bool,
+    },
+    /// Tag sessions (`cass tag add <path> bugfix auth`) to find them with `search --tag`
+    Tag {
+        /// What to do
+        #[arg(value_enum)]
+        action: TagAction,
+        /// Path to session file; `ls` without one lists every tagged session
+        path: Option<PathBuf>,
+        /// Tags to add or remove; `rm` without any removes them all
+        tags: Vec<String>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// Star a session or one of its messages, list stars, or remove one
+    Bookmark {
+        /// What to do
+        #[arg(value_enum)]
+        action: BookmarkAction,
+        /// Path to session file (not needed for `ls`)
+        path: Option<PathBuf>,
+        /// Message line number from search results (1-indexed); omit to star the whole session
+        #[arg(long, short = 'n')]
+        line: Option<usize>,
+        /// Note to keep with the bookmark
+        #[arg(long)]
+        note: Option<String>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// Annotate a message or conversation (`cass note add <path> <idx> "text"`); notes
+    /// are searchable and shown in the TUI's detail view
+    Note {
+        /// What to do
+        #[arg(value_enum)]
+        action: NoteAction,
+        /// Path to session file (`add`, and `ls` of one session)
+        path: Option<PathBuf>,
+        /// Message index the note is on (a search hit's line number minus 1, as in
+        /// permalinks), or `session` for the whole conversation
+        target: Option<String>,
+        /// Note text
+        text: Option<String>,
+        /// Note to remove, as listed by `ls`
+        #[arg(long)]
+        id: Option<u64>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// Manage opt-in, local-only usage metrics (disabled by default)
+    Telemetry {
+        /// What to do
+        #[arg(value_enum, default_value_t = TelemetryAction::Status)]
+        action: TelemetryAction,
+        /// Write `export` output to this file instead of stdout
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Import conversations recorded outside any watched agent dir
+    Import {
+        /// Files to import
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Input format
+        #[arg(long, value_enum)]
+        format: ImportFormat,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+impl Commands {
+    /// The command's `--data-dir`, if it takes one and it was given.
+    fn data_dir_override(&self) -> Option<&PathBuf> {
+        match self {
+            Commands::Tui { data_dir, .. }
+            | Commands::Index { data_dir, .. }
+            | Commands::Search { data_dir, .. }
+            | Commands::Stats { data_dir, .. }
+            | Commands::Diag { data_dir, .. }
+            | Commands::Doctor { data_dir, .. }
+            | Commands::Status { data_dir, .. }
+            | Commands::State { data_dir, .. }
+            | Commands::Health { data_dir, .. }
+            | Commands::Watch { data_dir, .. }
+            | Commands::List { data_dir, .. }
+            | Commands::Context { data_dir, .. }
+            | Commands::Export { data_dir, .. }
+            | Commands::Show { data_dir, .. }
+            | Commands::Diff { data_dir, .. }
+            | Commands::Grep { data_dir, .. }
+            | Commands::Timeline { data_dir, .. }
+            | Commands::Daemon { data_dir, .. }
+            | Commands::Repl { data_dir, .. }
+            | Commands::Serve { data_dir, .. }
+            | Commands::Permalink { data_dir, .. }
+            | Commands::Resume { data_dir, .. }
+            | Commands::OpenUrl { data_dir, .. }
+            | Commands::Tag { data_dir, .. }
+            | Commands::Bookmark { data_dir, .. }
+            | Commands::Note { data_dir, .. }
+            | Commands::Import { data_dir, .. } => data_dir.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// SWE-agent `.traj` files or OpenHands/Devin-style event logs
+    Trajectory,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum IndexAction {
+    /// Merge fragmented segments so searches touch fewer files
+    Optimize,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum TagAction {
+    /// Add tags to a session
+    Add,
+    /// Remove tags from a session
+    #[value(alias = "remove")]
+    Rm,
+    /// List a session's tags, or every tagged session
+    #[value(alias = "list")]
+    Ls,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum BookmarkAction {
+    /// Bookmark a session, or a message with --line
+    Add,
+    /// Remove the bookmark on a session, or on a message with --line
+    #[value(alias = "remove")]
+    Rm,
+    /// List bookmarks, newest first
+    #[value(alias = "list")]
+    Ls,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum NoteAction {
+    /// Add a note to a message, or to a whole conversation
+    Add,
+    /// Remove the note given by --id
+    #[value(alias = "remove")]
+    Rm,
+    /// List notes, on one session or on all
+    #[value(alias = "list")]
+    Ls,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum TelemetryAction {
+    /// Show whether collection is on and where data is kept
+    Status,
+    /// Start recording aggregate metrics locally
+    Enable,
+    /// Stop recording and discard collected metrics
+    Disable,
+    /// Print collected metrics as JSON for sharing
+    Export,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
@@ -409,6 +984,8 @@ pub enum ProgressMode {
     Bars,
     Plain,
     None,
+    /// NDJSON progress events on stdout (`cass index`), ending with a `done` record
+    Json,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
@@ -448,11 +1025,19 @@ pub enum DisplayFormat {
     Markdown,
 }
 
+/// Result grouping for `cass search --group-by`
+#[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum GroupBy {
+    /// One result per conversation, with its best-matching messages
+    Conversation,
+}
+
 /// Conversation export format (for export command)
 #[derive(Copy, Clone, Debug, Default, ValueEnum, PartialEq, Eq)]
 pub enum ConvExportFormat {
     /// Markdown with headers and formatting
     #[default]
+    #[value(alias = "md")]
     Markdown,
     /// Plain text
     Text,
@@ -462,13 +1047,36 @@ pub enum ConvExportFormat {
     Html,
 }
 
+/// Layouts for `cass diff`
+#[derive(Copy, Clone, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum DiffMode {
+    /// Aligned messages in one column; changed ones as -/+ line diffs
+    #[default]
+    Unified,
+    /// The two sessions in columns, one row per aligned message
+    #[value(alias = "split")]
+    SideBySide,
+}
+
+/// What `--copy` puts on the clipboard
+#[derive(Copy, Clone, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum CopyTarget {
+    /// Full message text
+    #[default]
+    Content,
+    /// The matching excerpt (search only)
+    Snippet,
+    /// Session file path
+    Path,
+}
+
 /// Timeline grouping options
 #[derive(Copy, Clone, Debug, Default, ValueEnum, PartialEq, Eq)]
 pub enum TimelineGrouping {
     /// Group by hour
-    #[default]
     Hour,
     /// Group by day
+    #[default]
     Day,
     /// No grouping (flat list)
     None,
@@ -567,6 +1175,20 @@ impl std::fmt::Display for CliError {
 impl std::error::Error for CliError {}
 
 impl CliError {
+    /// The error as robot output: `{"schema": "cass.error.v2", "error": {..}}`.
+    pub fn robot_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "schema": schema_tag("error"),
+            "error": {
+                "code": self.code,
+                "kind": self.kind,
+                "message": self.message,
+                "hint": self.hint,
+                "retryable": self.retryable,
+            }
+        })
+    }
+
     fn usage(message: impl Into<String>, hint: Option<String>) -> Self {
         CliError {
             code: 2,
@@ -593,6 +1215,7 @@ pub enum ProgressResolved {
     Bars,
     Plain,
     None,
+    Json,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -655,6 +1278,7 @@ fn normalize_args(raw: Vec<String>) -> (Vec<String>, Option<String>) {
         "data-dir",
         "verbose",
         "quiet",
+        "log-file",
         "color",
         "progress",
         "wrap",
@@ -672,15 +1296,13 @@ fn normalize_args(raw: Vec<String>) -> (Vec<String>, Option<String>) {
 
     // Subcommand aliases for common mistakes
     const SUBCOMMAND_ALIASES: &[(&str, &str)] = &[
-        // Search aliases
+        // Search aliases (`grep` is its own, index-free command)
         ("find", "search"),
         ("query", "search"),
         ("q", "search"),
         ("lookup", "search"),
-        ("grep", "search"),
         // Stats aliases
-        ("ls", "stats"),
-        ("list", "stats"),
+        ("ls", "list"),
         ("info", "stats"),
         ("summary", "stats"),
         // Status aliases
@@ -690,8 +1312,7 @@ fn normalize_args(raw: Vec<String>) -> (Vec<String>, Option<String>) {
         ("reindex", "index"),
         ("idx", "index"),
         ("rebuild", "index"),
-        // View aliases
-        ("show", "view"),
+        // View aliases (`show` is its own command)
         ("get", "view"),
         ("read", "view"),
         // Diag aliases
@@ -711,7 +1332,7 @@ fn normalize_args(raw: Vec<String>) -> (Vec<String>, Option<String>) {
     ];
 
     // Short flags that should remain as single-dash
-    const VALID_SHORT_FLAGS: &[&str] = &["-q", "-v", "-h", "-V"];
+    const VALID_SHORT_FLAGS: &[&str] = &["-q", "-qq", "-v", "-vv", "-h", "-V"];
 
     // Global flags that take a value via separate argument (--flag VALUE)
     // Note: --data-dir is NOT a global flag - it's per-subcommand
@@ -736,8 +1357,11 @@ fn normalize_args(raw: Vec<String>) -> (Vec<String>, Option<String>) {
             || s.starts_with("--db=")
             || s == "--quiet"
             || s == "-q"
+            || s == "-qq"
             || s == "--verbose"
             || s == "-v"
+            || s == "-vv"
+            || s == "--log-file"
             || s == "--trace-file"
             || s.starts_with("--trace-file=")
             || s == "--robot-help"
@@ -1168,6 +1792,7 @@ fn heuristic_parse_recovery(
         "stats",
         "status",
         "diag",
+        "doctor",
         "view",
         "capabilities",
         "introspect",
@@ -1292,12 +1917,18 @@ fn heuristic_parse_recovery(
     }
 }
 
+/// Parse arguments with help text in the user's locale.
+fn parse_cli(args: &[String]) -> Result<Cli, clap::Error> {
+    let matches = i18n::localize_command(Cli::command()).try_get_matches_from(args)?;
+    Cli::from_arg_matches(&matches)
+}
+
 pub async fn run() -> CliResult<()> {
     let raw_args: Vec<String> = std::env::args().collect();
     // First normalization pass (global flags lift)
     let (normalized_args, parse_note) = normalize_args(raw_args.clone());
 
-    let (cli, heuristic_note) = match Cli::try_parse_from(&normalized_args) {
+    let (cli, heuristic_note) = match parse_cli(&normalized_args) {
         Ok(cli) => (cli, None),
         Err(err) => {
             // Let clap handle help/version natively (exit 0, print to stdout)
@@ -1311,7 +1942,7 @@ pub async fn run() -> CliResult<()> {
             // Attempt heuristic recovery
             if let Some((recovered_args, note)) = heuristic_parse_recovery(&err, &normalized_args) {
                 // Try parsing again with recovered args
-                match Cli::try_parse_from(&recovered_args) {
+                match parse_cli(&recovered_args) {
                     Ok(cli) => (cli, Some(note)),
                     Err(retry_err) => {
                         // Check again for help/version in case recovered args triggered it
@@ -1353,7 +1984,10 @@ pub async fn run() -> CliResult<()> {
         .iter()
         .any(|s| s == "--json" || s == "--robot" || s == "-json" || s == "-robot")
         || matches!(&cli.command, Some(Commands::Capabilities { .. }))
-        || matches!(&cli.command, Some(Commands::Introspect { .. }));
+        || matches!(
+            &cli.command,
+            Some(Commands::Introspect { .. } | Commands::Schema { .. })
+        );
     let is_doc_mode = cli.robot_help || matches!(&cli.command, Some(Commands::RobotDocs { .. }));
 
     // Combine all correction notes
@@ -1381,6 +2015,10 @@ pub async fn run() -> CliResult<()> {
     )
     .await;
 
+    if !matches!(cli.command, Some(Commands::Telemetry { .. })) {
+        telemetry::record(telemetry::Event::Command(&command_label));
+    }
+
     if let Some(path) = &cli.trace_file {
         let duration_ms = start_instant.elapsed().as_millis();
         let exit_code = result.as_ref().map_or_else(|e| e.code, |()| 0);
@@ -1410,6 +2048,8 @@ async fn execute_cli(
     let command = cli.command.clone().unwrap_or(Commands::Tui {
         once: false,
         reset_state: false,
+        profile: None,
+        height: None,
         data_dir: None,
     });
 
@@ -1424,8 +2064,10 @@ async fn execute_cli(
     }
 
     // Block TUI in non-TTY contexts unless TUI_HEADLESS is set (for testing)
-    if matches!(command, Commands::Tui { .. })
-        && !stdout_is_tty
+    if matches!(
+        command,
+        Commands::Tui { .. } | Commands::OpenUrl { json: false, .. }
+    ) && !stdout_is_tty
         && std::env::var("TUI_HEADLESS").is_err()
     {
         return Err(CliError::usage(
@@ -1437,25 +2079,35 @@ async fn execute_cli(
     // Auto-quiet in robot mode: suppress INFO logs for clean JSON output
     // This ensures AI agents get parseable stdout without log noise on stderr
     let robot_mode = is_robot_mode(&command);
-    let filter = if cli.quiet || robot_mode {
-        // Robot mode implies quiet unless verbose is explicitly requested
-        if cli.verbose {
-            EnvFilter::new("debug")
-        } else {
-            EnvFilter::new("warn")
-        }
-    } else if cli.verbose {
-        EnvFilter::new("debug")
-    } else {
-        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
-    };
+    // The REPL prints results between prompts; per-query INFO lines would bury them.
+    let interactive_quiet = matches!(command, Commands::Repl { .. });
+    let filter = log_filter(cli.verbose, cli.quiet, robot_mode || interactive_quiet);
+    let log_file_dir = (cli.log_file || config::logging().file).then(|| {
+        command
+            .data_dir_override()
+            .cloned()
+            .unwrap_or_else(default_data_dir)
+    });
+    let ansi = matches!(cli.color, ColorPref::Always)
+        || (matches!(cli.color, ColorPref::Auto) && stderr_is_tty);
 
     match &command {
-        Commands::Tui { data_dir, .. } => {
+        Commands::Tui { data_dir, .. }
+        | Commands::OpenUrl {
+            data_dir,
+            json: false,
+            ..
+        } => {
             let log_dir = data_dir.clone().unwrap_or_else(default_data_dir);
             std::fs::create_dir_all(&log_dir).ok();
 
-            let file_appender = tracing_appender::rolling::daily(&log_dir, "cass.log");
+            let file_appender = log_file_appender(&log_dir).map_err(|e| CliError {
+                code: 9,
+                kind: "log-file",
+                message: format!("cannot open the log file in {}: {e}", log_dir.display()),
+                hint: None,
+                retryable: false,
+            })?;
             let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
 
             tracing_subscriber::registry()
@@ -1482,59 +2134,81 @@ async fn execute_cli(
             if let Commands::Tui {
                 once: false,
                 reset_state,
+                profile,
+                height,
                 data_dir,
-                ..
             } = command.clone()
             {
                 let bg_data_dir = log_dir.clone();
                 let bg_db = cli.db.clone();
                 // Create shared progress tracker
                 let progress = std::sync::Arc::new(indexer::IndexingProgress::default());
-                spawn_background_indexer(bg_data_dir, bg_db, Some(progress.clone()));
+                let reindex_tx =
+                    spawn_background_indexer(bg_data_dir, bg_db, Some(progress.clone()));
 
-                ui::tui::run_tui(data_dir, false, reset_state, Some(progress), None).map_err(
-                    |e| CliError {
-                        code: 9,
-                        kind: "tui",
-                        message: format!("tui failed: {e}"),
-                        hint: None,
-                        retryable: false,
-                    },
-                )?;
+                ui::tui::run_tui(
+                    data_dir,
+                    false,
+                    reset_state,
+                    Some(progress),
+                    reindex_tx,
+                    None,
+                    profile,
+                    height,
+                )
+                .map_err(|e| CliError {
+                    code: 9,
+                    kind: "tui",
+                    message: format!("tui failed: {e}"),
+                    hint: None,
+                    retryable: false,
+                })?;
             } else if let Commands::Tui {
                 once,
                 reset_state,
+                profile,
+                height,
                 data_dir,
-                ..
             } = command.clone()
             {
-                ui::tui::run_tui(data_dir, once, reset_state, None, None).map_err(|e| {
-                    CliError {
-                        code: 9,
-                        kind: "tui",
-                        message: format!("tui failed: {e}"),
+                ui::tui::run_tui(
+                    data_dir,
+                    once,
+                    reset_state,
+                    None,
+                    None,
+                    None,
+                    profile,
+                    height,
+                )
+                .map_err(|e| CliError {
+                    code: 9,
+                    kind: "tui",
+                    message: format!("tui failed: {e}"),
+                    hint: None,
+                    retryable: false,
+                })?;
+            } else if let Commands::OpenUrl { url, data_dir, .. } = command.clone() {
+                let target = resolve_permalink(&url, &data_dir, cli.db.clone())?;
+                ui::tui::run_tui(data_dir, false, false, None, None, Some(target), None, None)
+                    .map_err(|e| CliError {
+                        code: 9,
+                        kind: "tui",
+                        message: format!("tui failed: {e}"),
                         hint: None,
                         retryable: false,
-                    }
-                })?;
+                    })?;
             }
         }
         Commands::Index { .. }
         | Commands::Search { .. }
         | Commands::Stats { .. }
         | Commands::Diag { .. }
+        | Commands::Doctor { .. }
         | Commands::Status { .. }
+        | Commands::Watch { .. }
         | Commands::View { .. } => {
-            tracing_subscriber::fmt()
-                .with_env_filter(filter)
-                .with_writer(std::io::stderr)
-                .compact()
-                .with_target(false)
-                .with_ansi(
-                    matches!(cli.color, ColorPref::Always)
-                        || (matches!(cli.color, ColorPref::Auto) && stderr_is_tty),
-                )
-                .init();
+            let _log_guard = init_stderr_logging(filter, ansi, log_file_dir.as_deref());
 
             match command {
                 Commands::Index {
@@ -1545,17 +2219,77 @@ async fn execute_cli(
                     data_dir,
                     json,
                     idempotency_key,
+                    commit_every,
+                    commit_interval,
+                    remote,
+                    agent,
+                    path,
+                    dry_run,
+                    action,
+                    force,
+                    merge_status,
+                    wait,
                 } => {
+                    if let Some(secs) = wait
+                        && !merge_status
+                        && !dry_run
+                    {
+                        wait_for_index_writer(&data_dir, Duration::from_secs(secs))?;
+                    }
+                    if merge_status {
+                        run_index_merge_status(data_dir, json)?;
+                    } else if action == Some(IndexAction::Optimize) {
+                        run_index_optimize(data_dir, force, json)?;
+                    } else if dry_run {
+                        let (agents, _) = resolve_index_selection(agent, Vec::new())?;
+                        run_index_dry_run(
+                            cli.db.clone(),
+                            data_dir,
+                            full || force_rebuild,
+                            &agents,
+                            json,
+                        )?;
+                    } else {
+                        let (agents, paths) = resolve_index_selection(agent, path)?;
+                        let defaults = indexer::CommitPolicy::default();
+                        let commit_policy = indexer::CommitPolicy {
+                            max_docs: commit_every.unwrap_or(defaults.max_docs).max(1),
+                            max_interval: commit_interval
+                                .map(Duration::from_secs)
+                                .unwrap_or(defaults.max_interval),
+                        };
+                        run_index_with_data(
+                            cli.db.clone(),
+                            full,
+                            force_rebuild,
+                            watch,
+                            watch_once,
+                            data_dir,
+                            progress,
+                            json,
+                            idempotency_key,
+                            commit_policy,
+                            remote,
+                            agents,
+                            paths,
+                        )?;
+                    }
+                }
+                Commands::Watch { data_dir, json } => {
                     run_index_with_data(
                         cli.db.clone(),
-                        full,
-                        force_rebuild,
-                        watch,
-                        watch_once,
+                        false,
+                        false,
+                        true,
+                        None,
                         data_dir,
                         progress,
                         json,
-                        idempotency_key,
+                        None,
+                        indexer::CommitPolicy::default(),
+                        None,
+                        Vec::new(),
+                        Vec::new(),
                     )?;
                 }
                 Commands::Search {
@@ -1585,11 +2319,54 @@ async fn execute_cli(
                     dry_run,
                     timeout,
                     highlight,
+                    snippet_chars,
+                    group_by,
+                    group_size,
+                    fuzzy,
+                    semantic,
+                    recency,
+                    dedupe,
+                    min_message_tokens,
+                    max_message_tokens,
+                    stream,
+                    save,
+                    saved,
+                    tag,
+                    bookmarked,
+                    copy,
                 } => {
+                    let search = resolve_saved_search(
+                        &data_dir,
+                        saved.as_deref(),
+                        save.as_deref(),
+                        saved_searches::SavedSearch {
+                            name: String::new(),
+                            query: query.unwrap_or_default(),
+                            agents: agent,
+                            workspaces: workspace,
+                            days,
+                            today,
+                            yesterday,
+                            week,
+                            since,
+                            until,
+                            fuzzy,
+                            tags: tag,
+                            bookmarked,
+                            saved_at: 0,
+                        },
+                    )?;
+                    let query = if search.fuzzy {
+                        crate::search::query::fuzzy_query(&search.query)
+                    } else {
+                        search.query.clone()
+                    };
                     run_cli_search(
                         &query,
-                        &agent,
-                        &workspace,
+                        &search.agents,
+                        &search.workspaces,
+                        &search.tags,
+                        search.bookmarked,
                         &limit,
                         &offset,
                         &json,
@@ -1606,19 +2383,20 @@ async fn execute_cli(
                         wrap,
                         progress,
                         robot_mode,
-                        TimeFilter::new(
-                            days,
-                            today,
-                            yesterday,
-                            week,
-                            since.as_deref(),
-                            until.as_deref(),
-                        ),
+                        search.time_filter()?,
                         aggregate,
                         explain,
                         dry_run,
                         timeout,
                         highlight,
+                        snippet_chars,
+                        group_by.map(|_| group_size.max(1)),
+                        semantic,
+                        recency,
+                        dedupe,
+                        (min_message_tokens, max_message_tokens),
+                        stream,
+                        copy,
                     )?;
                 }
                 Commands::Stats { data_dir, json } => {
@@ -1631,13 +2409,28 @@ async fn execute_cli(
                 } => {
                     run_diag(&data_dir, cli.db.clone(), json, verbose)?;
                 }
+                Commands::Doctor {
+                    index: _,
+                    repair,
+                    data_dir,
+                    json,
+                } => {
+                    run_doctor(data_dir, cli.db.clone(), repair, json)?;
+                }
                 Commands::Status {
                     data_dir,
                     json,
                     robot_meta,
                     stale_threshold,
                 } => {
-                    run_status(&data_dir, cli.db.clone(), json, stale_threshold, robot_meta)?;
+                    run_status(
+                        &data_dir,
+                        cli.db.clone(),
+                        json,
+                        stale_threshold,
+                        robot_meta,
+                        "status",
+                    )?;
                 }
                 Commands::View {
                     path,
@@ -1651,16 +2444,7 @@ async fn execute_cli(
             }
         }
         _ => {
-            tracing_subscriber::fmt()
-                .with_env_filter(filter)
-                .with_writer(std::io::stderr)
-                .compact()
-                .with_target(false)
-                .with_ansi(
-                    matches!(cli.color, ColorPref::Always)
-                        || (matches!(cli.color, ColorPref::Auto) && stderr_is_tty),
-                )
-                .init();
+            let _log_guard = init_stderr_logging(filter, ansi, log_file_dir.as_deref());
 
             match command {
                 Commands::Completions { shell } => {
@@ -1685,11 +2469,14 @@ async fn execute_cli(
                     robot_meta,
                     stale_threshold,
                 } => {
-                    run_status(&data_dir, None, json, stale_threshold, robot_meta)?;
+                    run_status(&data_dir, None, json, stale_threshold, robot_meta, "state")?;
                 }
                 Commands::Introspect { json } => {
                     run_introspect(json)?;
                 }
+                Commands::Schema { shape } => {
+                    run_schema(shape.as_deref())?;
+                }
                 Commands::Health {
                     data_dir,
                     json,
@@ -1698,6 +2485,33 @@ async fn execute_cli(
                 } => {
                     run_health(&data_dir, cli.db.clone(), json, stale_threshold, robot_meta)?;
                 }
+                Commands::List {
+                    agent,
+                    workspace,
+                    since,
+                    until,
+                    limit,
+                    data_dir,
+                    json,
+                } => {
+                    let time_filter = TimeFilter::new(
+                        None,
+                        false,
+                        false,
+                        false,
+                        since.as_deref(),
+                        until.as_deref(),
+                    )?;
+                    run_list(
+                        &agent,
+                        &workspace,
+                        &time_filter,
+                        limit,
+                        &data_dir,
+                        cli.db.clone(),
+                        json,
+                    )?;
+                }
                 Commands::Context {
                     path,
                     data_dir,
@@ -1708,11 +2522,23 @@ async fn execute_cli(
                 }
                 Commands::Export {
                     path,
+                    query,
+                    limit,
                     format,
                     output,
                     include_tools,
+                    data_dir,
                 } => {
-                    run_export(&path, format, output.as_deref(), include_tools)?;
+                    run_export(
+                        path.as_deref(),
+                        query.as_deref(),
+                        limit,
+                        format,
+                        output.as_deref(),
+                        include_tools,
+                        &data_dir,
+                        cli.db.clone(),
+                    )?;
                 }
                 Commands::Expand {
                     path,
@@ -1722,26 +2548,230 @@ async fn execute_cli(
                 } => {
                     run_expand(&path, line, context, json)?;
                 }
+                Commands::Show {
+                    path,
+                    around_msg,
+                    context,
+                    json,
+                    data_dir,
+                    copy,
+                } => {
+                    run_show(&path, around_msg, context, json, &data_dir, copy)?;
+                }
+                Commands::Diff {
+                    a,
+                    b,
+                    mode,
+                    width,
+                    json,
+                    data_dir,
+                } => {
+                    run_diff(
+                        &a,
+                        &b,
+                        mode,
+                        width.or(wrap.effective_width()),
+                        json,
+                        &data_dir,
+                    )?;
+                }
+                Commands::Grep {
+                    pattern,
+                    agent,
+                    ignore_case,
+                    fixed_strings,
+                    limit,
+                    data_dir,
+                    json,
+                } => {
+                    run_grep(
+                        &pattern,
+                        &agent,
+                        ignore_case,
+                        fixed_strings,
+                        limit,
+                        &data_dir,
+                        json,
+                    )?;
+                }
                 Commands::Timeline {
                     since,
                     until,
                     today,
                     agent,
+                    workspace,
                     data_dir,
                     json,
                     group_by,
                 } => {
-                    run_timeline(
+                    let mut time_filter = TimeFilter::new(
+                        None,
+                        today,
+                        false,
+                        false,
                         since.as_deref(),
                         until.as_deref(),
-                        today,
+                    )?;
+                    if time_filter.since.is_none() && time_filter.until.is_none() {
+                        time_filter = TimeFilter::new(Some(7), false, false, false, None, None)?;
+                    }
+                    run_timeline(
                         &agent,
+                        &workspace,
+                        &time_filter,
                         &data_dir,
                         cli.db.clone(),
                         json,
                         group_by,
                     )?;
                 }
+                Commands::Daemon { data_dir } => {
+                    let data_dir = data_dir.unwrap_or_else(default_data_dir);
+                    let db_path = cli
+                        .db
+                        .clone()
+                        .unwrap_or_else(|| data_dir.join("agent_search.db"));
+                    daemon::serve(&data_dir, &db_path).map_err(|e| CliError {
+                        code: 9,
+                        kind: "daemon",
+                        message: format!("query daemon failed: {e}"),
+                        hint: None,
+                        retryable: false,
+                    })?;
+                }
+                Commands::Repl { limit, data_dir } => {
+                    run_repl(limit, &data_dir, cli.db.clone())?;
+                }
+                Commands::Serve {
+                    host,
+                    port,
+                    token,
+                    cors_origins,
+                    read_only,
+                    data_dir,
+                } => {
+                    let loopback = host
+                        .parse::<std::net::IpAddr>()
+                        .map(|ip| ip.is_loopback())
+                        .unwrap_or(host == "localhost");
+                    if token.is_none() && !loopback {
+                        return Err(CliError::usage(
+                            format!(
+                                "refusing to serve {host} without --token; anyone who can reach it could read your sessions"
+                            ),
+                            Some(
+                                "pass --token (or set CASS_SERVE_TOKEN), or bind 127.0.0.1".into(),
+                            ),
+                        ));
+                    }
+                    let opts = serve::ServeOptions {
+                        host,
+                        port,
+                        token,
+                        cors_origins,
+                        read_only,
+                        data_dir: data_dir.unwrap_or_else(default_data_dir),
+                        db: cli.db.clone(),
+                    };
+                    serve::serve(&opts, |addr| {
+                        eprintln!("cass serve: listening on http://{addr}");
+                    })
+                    .map_err(|e| CliError {
+                        code: 9,
+                        kind: "serve",
+                        message: format!("http server failed: {e:#}"),
+                        hint: None,
+                        retryable: false,
+                    })?;
+                }
+                Commands::Permalink {
+                    path,
+                    line,
+                    data_dir,
+                    json,
+                } => {
+                    run_permalink(&path, line, &data_dir, cli.db.clone(), json)?;
+                }
+                Commands::Resume {
+                    path,
+                    exec,
+                    data_dir,
+                    json,
+                } => {
+                    run_resume(&path, exec, &data_dir, cli.db.clone(), json)?;
+                }
+                Commands::OpenUrl { url, data_dir, .. } => {
+                    run_open_url_json(&url, &data_dir, cli.db.clone())?;
+                }
+                Commands::Tag {
+                    action,
+                    path,
+                    tags,
+                    data_dir,
+                    json,
+                } => {
+                    run_tag(
+                        action,
+                        path.as_deref(),
+                        &tags,
+                        &data_dir,
+                        cli.db.clone(),
+                        json,
+                    )?;
+                }
+                Commands::Bookmark {
+                    action,
+                    path,
+                    line,
+                    note,
+                    data_dir,
+                    json,
+                } => {
+                    run_bookmark(
+                        action,
+                        path.as_deref(),
+                        line,
+                        note,
+                        &data_dir,
+                        cli.db.clone(),
+                        json,
+                    )?;
+                }
+                Commands::Note {
+                    action,
+                    path,
+                    target,
+                    text,
+                    id,
+                    data_dir,
+                    json,
+                } => {
+                    run_note(
+                        action,
+                        path.as_deref(),
+                        target.as_deref(),
+                        text,
+                        id,
+                        &data_dir,
+                        cli.db.clone(),
+                        json,
+                    )?;
+                }
+                Commands::Telemetry {
+                    action,
+                    output,
+                    json,
+                } => {
+                    run_telemetry(action, output.as_deref(), json)?;
+                }
+                Commands::Import {
+                    paths,
+                    format,
+                    data_dir,
+                    json,
+                } => {
+                    run_import(&paths, format, &data_dir, cli.db.clone(), json)?;
+                }
                 _ => {}
             }
         }
@@ -1870,6 +2900,7 @@ fn resolve_progress(mode: ProgressMode, stdout_is_tty: bool) -> ProgressResolved
         ProgressMode::Bars => ProgressResolved::Bars,
         ProgressMode::Plain => ProgressResolved::Plain,
         ProgressMode::None => ProgressResolved::None,
+        ProgressMode::Json => ProgressResolved::Json,
         ProgressMode::Auto => {
             if stdout_is_tty {
                 ProgressResolved::Bars
@@ -1884,9 +2915,11 @@ fn describe_command(cli: &Cli) -> String {
     match &cli.command {
         Some(Commands::Tui { .. }) => "tui".to_string(),
         Some(Commands::Index { .. }) => "index".to_string(),
+        Some(Commands::Watch { .. }) => "watch".to_string(),
         Some(Commands::Search { .. }) => "search".to_string(),
         Some(Commands::Stats { .. }) => "stats".to_string(),
         Some(Commands::Diag { .. }) => "diag".to_string(),
+        Some(Commands::Doctor { .. }) => "doctor".to_string(),
         Some(Commands::Status { .. }) => "status".to_string(),
         Some(Commands::View { .. }) => "view".to_string(),
         Some(Commands::Completions { .. }) => "completions".to_string(),
@@ -1895,18 +2928,121 @@ fn describe_command(cli: &Cli) -> String {
         Some(Commands::ApiVersion { .. }) => "api-version".to_string(),
         Some(Commands::State { .. }) => "state".to_string(),
         Some(Commands::Introspect { .. }) => "introspect".to_string(),
+        Some(Commands::Schema { .. }) => "schema".to_string(),
         Some(Commands::RobotDocs { topic }) => format!("robot-docs:{topic:?}"),
         Some(Commands::Health { .. }) => "health".to_string(),
+        Some(Commands::List { .. }) => "list".to_string(),
         Some(Commands::Context { .. }) => "context".to_string(),
         Some(Commands::Export { .. }) => "export".to_string(),
         Some(Commands::Expand { .. }) => "expand".to_string(),
+        Some(Commands::Show { .. }) => "show".to_string(),
+        Some(Commands::Diff { .. }) => "diff".to_string(),
+        Some(Commands::Grep { .. }) => "grep".to_string(),
         Some(Commands::Timeline { .. }) => "timeline".to_string(),
+        Some(Commands::Daemon { .. }) => "daemon".to_string(),
+        Some(Commands::Repl { .. }) => "repl".to_string(),
+        Some(Commands::Serve { .. }) => "serve".to_string(),
+        Some(Commands::Permalink { .. }) => "permalink".to_string(),
+        Some(Commands::Resume { .. }) => "resume".to_string(),
+        Some(Commands::OpenUrl { .. }) => "open-url".to_string(),
+        Some(Commands::Tag { .. }) => "tag".to_string(),
+        Some(Commands::Bookmark { .. }) => "bookmark".to_string(),
+        Some(Commands::Note { .. }) => "note".to_string(),
+        Some(Commands::Telemetry { .. }) => "telemetry".to_string(),
+        Some(Commands::Import { .. }) => "import".to_string(),
         None => "(default)".to_string(),
     }
 }
 
 /// Returns true if the command is using robot/JSON output mode.
 /// Used to auto-suppress INFO logs for clean machine-parseable output.
+/// The stderr log filter. `-v`/`-q` win, then `CASS_LOG` (`EnvFilter` directives such
+/// as `debug` or `coding_agent_search::connectors=trace`); without either, robot output
+/// and the REPL stay at warnings and everything else reads `RUST_LOG`, else info.
+fn log_filter(verbose: u8, quiet: u8, auto_quiet: bool) -> EnvFilter {
+    match (verbose, quiet) {
+        (0, 0) => {}
+        (1, _) => return EnvFilter::new("debug"),
+        (0, 1) => return EnvFilter::new("warn"),
+        (0, _) => return EnvFilter::new("error"),
+        _ => return EnvFilter::new("trace"),
+    }
+    if let Some(directives) = std::env::var("CASS_LOG")
+        .ok()
+        .filter(|d| !d.trim().is_empty())
+    {
+        match EnvFilter::try_new(&directives) {
+            Ok(filter) => return filter,
+            Err(e) => eprintln!("ignoring CASS_LOG={directives}: {e}"),
+        }
+    }
+    if auto_quiet {
+        EnvFilter::new("warn")
+    } else {
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
+    }
+}
+
+/// The daily-rotated `cass.log` in `data_dir`, keeping `[logging] max_files` days.
+fn log_file_appender(
+    data_dir: &Path,
+) -> Result<tracing_appender::rolling::RollingFileAppender, tracing_appender::rolling::InitError> {
+    tracing_appender::rolling::Builder::new()
+        .rotation(tracing_appender::rolling::Rotation::DAILY)
+        .filename_prefix("cass.log")
+        .max_log_files(config::logging().max_files.max(1))
+        .build(data_dir)
+}
+
+/// Log to stderr through `filter` and, with `log_file_dir`, to its `cass.log` at
+/// `[logging] file_level`. Keep the returned guard alive so the file is flushed.
+fn init_stderr_logging(
+    filter: EnvFilter,
+    ansi: bool,
+    log_file_dir: Option<&Path>,
+) -> Option<tracing_appender::non_blocking::WorkerGuard> {
+    use tracing_subscriber::Layer;
+
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .compact()
+        .with_target(false)
+        .with_ansi(ansi)
+        .with_filter(filter);
+    // A log file that cannot be opened must not stop the command; say so once it can be logged.
+    let mut file_error = None;
+    let file = log_file_dir.and_then(|dir| match log_file_appender(dir) {
+        Ok(appender) => Some(tracing_appender::non_blocking(appender)),
+        Err(e) => {
+            file_error = Some(format!("{}: {e}", dir.display()));
+            None
+        }
+    });
+    let (file_layer, guard) = match file {
+        Some((writer, guard)) => {
+            let level = &config::logging().file_level;
+            let file_filter = EnvFilter::try_new(level).unwrap_or_else(|e| {
+                eprintln!("ignoring [logging] file_level = {level:?}: {e}");
+                EnvFilter::new(config::DEFAULT_FILE_LOG_LEVEL)
+            });
+            let layer = tracing_subscriber::fmt::layer()
+                .with_writer(writer)
+                .with_ansi(false)
+                .with_filter(file_filter);
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+    tracing_subscriber::registry()
+        .with(stderr)
+        .with(file_layer)
+        .init();
+    if let Some(error) = file_error {
+        tracing::warn!("not logging to a file: cannot open {error}");
+    }
+    guard
+}
+
 fn is_robot_mode(command: &Commands) -> bool {
     match command {
         Commands::Search {
@@ -1918,6 +3054,7 @@ fn is_robot_mode(command: &Commands) -> bool {
         Commands::Index { json, .. } => *json,
         Commands::Stats { json, .. } => *json,
         Commands::Diag { json, .. } => *json,
+        Commands::Doctor { json, .. } => *json,
         Commands::Status { json, .. } => *json,
         Commands::Health { json, .. } => *json,
         Commands::ApiVersion { json, .. } => *json,
@@ -1925,7 +3062,21 @@ fn is_robot_mode(command: &Commands) -> bool {
         Commands::View { json, .. } => *json,
         Commands::Capabilities { json, .. } => *json,
         Commands::Introspect { json, .. } => *json,
+        Commands::Schema { .. } => true,
         Commands::Context { json, .. } => *json,
+        Commands::Permalink { json, .. } => *json,
+        Commands::Resume { json, .. } => *json,
+        Commands::OpenUrl { json, .. } => *json,
+        Commands::Tag { json, .. } => *json,
+        Commands::Bookmark { json, .. } => *json,
+        Commands::Note { json, .. } => *json,
+        Commands::Diff { json, .. } => *json,
+        Commands::Grep { json, .. } => *json,
+        Commands::Timeline { json, .. } => *json,
+        Commands::Telemetry { json, action, .. } => {
+            *json || matches!(action, TelemetryAction::Export)
+        }
+        Commands::Import { json, .. } => *json,
         _ => false,
     }
 }
@@ -1968,50 +3119,115 @@ fn apply_wrap(line: &str, wrap: WrapConfig) -> String {
 /// * `start_mark` - Opening marker (e.g., "**" for markdown bold, "<mark>" for HTML)
 /// * `end_mark` - Closing marker (e.g., "**" for markdown bold, "</mark>" for HTML)
 fn highlight_matches(text: &str, query: &str, start_mark: &str, end_mark: &str) -> String {
-    // Extract search terms from query (handles quoted phrases and individual words)
-    let terms = extract_search_terms(query);
-    if terms.is_empty() {
-        return text.to_string();
-    }
+    let mut result = String::with_capacity(text.len());
+    let mut last_end = 0;
+    for range in match_ranges(text, query) {
+        result.push_str(&text[last_end..range.start]);
+        result.push_str(start_mark);
+        result.push_str(&text[range.clone()]);
+        result.push_str(end_mark);
+        last_end = range.end;
+    }
+    result.push_str(&text[last_end..]);
+    result
+}
 
-    // Sort terms by length (longest first) to avoid partial matches
-    let mut terms: Vec<_> = terms.into_iter().collect();
-    terms.sort_by_key(|s| std::cmp::Reverse(s.len()));
+/// Byte ranges of query-term matches in `text`, sorted and non-overlapping.
+fn match_ranges(text: &str, query: &str) -> Vec<std::ops::Range<usize>> {
+    crate::search::query::match_ranges(text, &extract_search_terms(query))
+}
 
-    let mut result = text.to_string();
-    for term in &terms {
-        if term.is_empty() {
-            continue;
-        }
-        // Case-insensitive replacement
-        // Note: We lowercase both and find matches in the lowercased version,
-        // but the matched substring length in the original might differ from term.len()
-        // for certain Unicode characters. We use the actual matched length from lower_result.
-        let lower_result = result.to_lowercase();
-        let lower_term = term.to_lowercase();
-        let mut new_result = String::new();
-        let mut last_end = 0;
-
-        for (idx, matched_str) in lower_result.match_indices(&lower_term) {
-            // Skip if this overlaps with a previous highlight (from a longer term)
-            if idx < last_end {
-                continue;
-            }
-            // Append text before this match
-            new_result.push_str(&result[last_end..idx]);
-            // Append highlighted match (preserve original case)
-            // Use matched_str.len() which is the actual byte length in the lowercased string
-            new_result.push_str(start_mark);
-            new_result.push_str(&result[idx..idx + matched_str.len()]);
-            new_result.push_str(end_mark);
-            last_end = idx + matched_str.len();
+/// Hits from one conversation, best first, for `cass search --group-by conversation`.
+struct HitGroup {
+    hits: Vec<crate::search::query::SearchHit>,
+    /// Matching messages in the conversation among the fetched hits.
+    count: usize,
+}
+
+/// Group ranked `hits` by conversation in order of each one's best hit, keeping
+/// the `size` best messages per conversation.
+fn group_by_conversation(hits: &[crate::search::query::SearchHit], size: usize) -> Vec<HitGroup> {
+    let mut groups: Vec<HitGroup> = Vec::new();
+    let mut index: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    for hit in hits {
+        // Older indexes carry no conversation id; the session file stands in.
+        let key = if hit.conversation_id.is_empty() {
+            hit.source_path.as_str()
+        } else {
+            hit.conversation_id.as_str()
+        };
+        let slot = *index.entry(key).or_insert_with(|| {
+            groups.push(HitGroup {
+                hits: Vec::new(),
+                count: 0,
+            });
+            groups.len() - 1
+        });
+        let group = &mut groups[slot];
+        group.count += 1;
+        if group.hits.len() < size {
+            group.hits.push(hit.clone());
         }
-        // Append remaining text
-        new_result.push_str(&result[last_end..]);
-        result = new_result;
     }
+    groups
+}
 
-    result
+/// Re-cut `hit`'s snippet to `max_chars` around its matches. Words the engine
+/// marked in the original snippet count as matches too, so fuzzy and stemmed hits
+/// stay centered.
+fn resize_snippet(hit: &crate::search::query::SearchHit, query: &str, max_chars: usize) -> String {
+    let mut terms = extract_search_terms(query);
+    terms.extend(
+        hit.snippet
+            .split("**")
+            .skip(1)
+            .step_by(2)
+            .map(str::to_string),
+    );
+    let matches = crate::search::query::match_ranges(&hit.content, &terms);
+    crate::search::query::centered_snippet(&hit.content, &matches, max_chars)
+}
+
+/// Robot-mode `highlights` entry: match ranges in `text` as character and byte offsets.
+fn match_offsets_json(text: &str, query: &str) -> serde_json::Value {
+    let mut chars = 0;
+    let mut last = 0;
+    let offsets: Vec<_> = match_ranges(text, query)
+        .into_iter()
+        .map(|range| {
+            chars += text[last..range.start].chars().count();
+            let char_start = chars;
+            chars += text[range.clone()].chars().count();
+            last = range.end;
+            serde_json::json!({
+                "start": char_start,
+                "end": chars,
+                "byte_start": range.start,
+                "byte_end": range.end,
+            })
+        })
+        .collect();
+    serde_json::Value::Array(offsets)
+}
+
+/// Attach `highlights` offsets for the `content` and `snippet` fields a robot hit carries.
+fn attach_match_offsets(hit: serde_json::Value, query: &str) -> serde_json::Value {
+    let serde_json::Value::Object(mut obj) = hit else {
+        return hit;
+    };
+    let mut highlights = serde_json::Map::new();
+    for field in ["content", "snippet"] {
+        if let Some(serde_json::Value::String(text)) = obj.get(field) {
+            highlights.insert(field.to_string(), match_offsets_json(text, query));
+        }
+    }
+    if !highlights.is_empty() {
+        obj.insert(
+            "highlights".to_string(),
+            serde_json::Value::Object(highlights),
+        );
+    }
+    serde_json::Value::Object(obj)
 }
 
 /// Extract meaningful search terms from a query string
@@ -2069,6 +3285,12 @@ fn extract_search_terms(query: &str) -> Vec<String> {
         // Skip whitespace and other characters
     }
 
+    // Configured synonyms match too, so they are highlighted like the query's own terms.
+    let synonyms: Vec<String> = terms
+        .iter()
+        .flat_map(|t| crate::config::synonyms(t))
+        .collect();
+    terms.extend(synonyms);
     terms
 }
 
@@ -2121,8 +3343,9 @@ fn print_robot_docs(topic: RobotTopic, wrap: WrapConfig) -> CliResult<()> {
     let lines: Vec<String> = match topic {
         RobotTopic::Commands => vec![
             "commands:".to_string(),
-            "  (global) --quiet / -q  Suppress info logs (auto-enabled in robot mode)".to_string(),
-            "  (global) --verbose/-v  Enable debug logs (overrides auto-quiet)".to_string(),
+            "  (global) --quiet / -q  Suppress info logs (auto-enabled in robot mode); -qq errors only".to_string(),
+            "  (global) --verbose/-v  Enable debug logs (overrides auto-quiet); -vv trace".to_string(),
+            "  (global) --log-file    Also log to the daily-rotated cass.log in the data dir".to_string(),
             "  Tip: `--robot-docs=<topic>` is normalized to `robot-docs <topic>`; globals can appear before/after subcommands.".to_string(),
             "  cass search <query> [OPTIONS]".to_string(),
             "    --agent A         Filter by agent (codex, claude_code, gemini, opencode, amp, cline)".to_string(),
@@ -2147,9 +3370,16 @@ fn print_robot_docs(topic: RobotTopic, wrap: WrapConfig) -> CliResult<()> {
             "  cass status [--json] [--stale-threshold N] [--data-dir DIR]".to_string(),
             "  cass diag [--json] [--verbose] [--data-dir DIR]".to_string(),
             "  cass view <path> [-n LINE] [-C CONTEXT] [--json]".to_string(),
-            "  cass index [--full] [--watch] [--json] [--data-dir DIR]".to_string(),
+            "  cass resume <path> [--exec] [--json]   the agent's command to continue the session".to_string(),
+            "  cass index [--full] [--watch] [--remote USER@HOST] [--json] [--data-dir DIR]".to_string(),
+            "  cass index [--agent A,B] [--path DIR]   rescan only these connectors or sessions; the rest of the index is kept".to_string(),
+            "  cass index --dry-run [--full] [--json]   per-connector files/conversations/messages and skipped paths, writes nothing".to_string(),
             "  cass tui [--once] [--data-dir DIR] [--reset-state]".to_string(),
+            "  cass daemon [--data-dir DIR]   keep index warm; cass search delegates to it".to_string(),
+            "  cass telemetry [status|enable|disable|export] [--json] [-o FILE]   opt-in local usage metrics".to_string(),
+            "  cass import --format trajectory <FILE>... [--json] [--data-dir DIR]   index SWE-agent/event-log trajectories".to_string(),
             "  cass capabilities [--json]".to_string(),
+            "  cass schema [SHAPE]   JSON Schema of each versioned robot output (cass.search.v2, ...)".to_string(),
             "  cass robot-docs <topic>".to_string(),
             "  cass --robot-help".to_string(),
         ],
@@ -2158,22 +3388,28 @@ fn print_robot_docs(topic: RobotTopic, wrap: WrapConfig) -> CliResult<()> {
             "  CODING_AGENT_SEARCH_NO_UPDATE_PROMPT=1   skip update prompt".to_string(),
             "  TUI_HEADLESS=1                           skip update prompt".to_string(),
             "  CASS_DATA_DIR                            override data dir".to_string(),
+            "  CASS_RSYNC                               rsync binary used by index --remote".to_string(),
+            "  CASS_CONFIG                              config file (default ~/.config/cass/config.toml)".to_string(),
             "  CASS_DB_PATH                             override db path".to_string(),
             "  NO_COLOR / CASS_NO_COLOR                 disable color".to_string(),
             "  CASS_TRACE_FILE                          default trace path".to_string(),
+            "  CASS_LOG=debug                           stderr log filter (RUST_LOG syntax; -v/-q win)".to_string(),
+            "  CASS_NO_DAEMON=1                         search in-process even if daemon runs".to_string(),
+            "  CASS_LANG=es                             UI/help language (default: system locale, then en)".to_string(),
         ],
         RobotTopic::Paths => {
             let mut lines: Vec<String> = vec!["paths:".to_string()];
             lines.push(format!("  data dir default: {}", default_data_dir().display()));
             lines.push(format!("  db path default: {}", default_db_path().display()));
             lines.push("  log path: <data-dir>/cass.log (daily rolling)".to_string());
+            lines.push("  daemon socket: <data-dir>/cass.sock".to_string());
             lines.push("  trace: user-provided path (JSONL).".to_string());
             lines
         }
         RobotTopic::Guide => vec![
             "guide:".to_string(),
             "  Robot-mode handbook: docs/ROBOT_MODE.md (automation quickstart)".to_string(),
-            "  Output: --robot/--json; JSONL via --robot-format jsonl; compact via --robot-format compact".to_string(),
+            "  Output: --robot/--json; JSONL via --robot-format jsonl; compact via --robot-format compact; hits as found via --robot --stream".to_string(),
             "  Logging: INFO auto-suppressed in robot mode; add -v to re-enable".to_string(),
             "  Args: accepts --robot-docs=topic and misplaced globals; detailed errors with examples on parse failure".to_string(),
             "  Safety: prefer --color=never in non-TTY; use --trace-file for spans; reset TUI via `cass tui --reset-state`".to_string(),
@@ -2241,6 +3477,8 @@ fn print_robot_docs(topic: RobotTopic, wrap: WrapConfig) -> CliResult<()> {
             "  Color auto off when non-TTY unless forced.".to_string(),
             "  Use --quiet to silence info logs in robot runs.".to_string(),
             "  JSON errors only to stderr.".to_string(),
+            "  Robot outputs carry \"schema\": \"cass.<shape>.v<N>\"; within a major version".to_string(),
+            "  fields are only added. `cass schema [SHAPE]` prints the JSON Schemas.".to_string(),
         ],
         RobotTopic::Wrap => vec![
             "wrap:".to_string(),
@@ -2346,6 +3584,81 @@ fn write_trace_line(
     Ok(())
 }
 
+/// The search to run for `cass search`: the one saved as `saved`, if named, with
+/// whatever the command line sets (`given`) replacing its query or filters. With `save`
+/// the result is stored under that name first.
+fn resolve_saved_search(
+    data_dir: &Option<PathBuf>,
+    saved: Option<&str>,
+    save: Option<&str>,
+    given: saved_searches::SavedSearch,
+) -> CliResult<saved_searches::SavedSearch> {
+    let data_dir = data_dir.clone().unwrap_or_else(default_data_dir);
+    let mut search = match saved {
+        Some(name) => saved_searches::get(&data_dir, name).ok_or_else(|| {
+            let names: Vec<_> = saved_searches::load(&data_dir)
+                .into_iter()
+                .map(|s| s.name)
+                .collect();
+            CliError {
+                code: 4,
+                kind: "not_found",
+                message: format!("No saved search named '{name}'"),
+                hint: Some(if names.is_empty() {
+                    "save one with cass search <query> --save <name>".to_string()
+                } else {
+                    format!("saved searches: {}", names.join(", "))
+                }),
+                retryable: false,
+            }
+        })?,
+        None => saved_searches::SavedSearch::default(),
+    };
+    if !given.query.is_empty() {
+        search.query = given.query;
+    }
+    if !given.agents.is_empty() {
+        search.agents = given.agents;
+    }
+    if !given.workspaces.is_empty() {
+        search.workspaces = given.workspaces;
+    }
+    if !given.tags.is_empty() {
+        search.tags = given.tags;
+    }
+    let times_given = given.days.is_some()
+        || given.today
+        || given.yesterday
+        || given.week
+        || given.since.is_some()
+        || given.until.is_some();
+    if times_given {
+        search.days = given.days;
+        search.today = given.today;
+        search.yesterday = given.yesterday;
+        search.week = given.week;
+        search.since = given.since;
+        search.until = given.until;
+    }
+    search.fuzzy |= given.fuzzy;
+    search.bookmarked |= given.bookmarked;
+
+    if let Some(name) = save {
+        // Check the times now rather than saving a search that can never run.
+        search.time_filter()?;
+        search.name = name.to_string();
+        saved_searches::save(&data_dir, search.clone()).map_err(|e| CliError {
+            code: 9,
+            kind: "file-write",
+            message: format!("failed to save search '{name}': {e:#}"),
+            hint: None,
+            retryable: false,
+        })?;
+        eprintln!("Saved search '{name}'");
+    }
+    Ok(search)
+}
+
 /// Time filter helper for search commands
 #[derive(Debug, Clone, Default)]
 pub struct TimeFilter {
@@ -2361,7 +3674,7 @@ impl TimeFilter {
         week: bool,
         since_str: Option<&str>,
         until_str: Option<&str>,
-    ) -> Self {
+    ) -> CliResult<Self> {
         use chrono::{Datelike, Duration, Local, TimeZone};
 
         let now = Local::now();
@@ -2388,34 +3701,35 @@ impl TimeFilter {
             (None, None)
         };
 
-        // Explicit --since/--until override convenience flags when they parse successfully
-        let since = since_str.and_then(parse_datetime_str).or(since);
-        let until = until_str.and_then(parse_datetime_str).or(until);
+        // Explicit --since/--until override convenience flags
+        let since = since_str
+            .map(|s| parse_time_flag("--since", s))
+            .transpose()?
+            .or(since);
+        let until = until_str
+            .map(|s| parse_time_flag("--until", s))
+            .transpose()?
+            .or(until);
 
-        TimeFilter { since, until }
+        Ok(TimeFilter { since, until })
     }
 }
 
 fn parse_datetime_str(s: &str) -> Option<i64> {
-    use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
-
-    // Try full datetime first: YYYY-MM-DDTHH:MM:SS
-    if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
-        return Local
-            .from_local_datetime(&dt)
-            .single()
-            .map(|d| d.timestamp_millis());
-    }
-
-    // Try date only: YYYY-MM-DD
-    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
-        return Local
-            .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
-            .single()
-            .map(|d| d.timestamp_millis());
-    }
+    crate::ui::time_parser::parse_time_input(s)
+}
 
-    None
+fn parse_time_flag(flag: &str, value: &str) -> CliResult<i64> {
+    parse_datetime_str(value).ok_or_else(|| CliError {
+        code: 2,
+        kind: "invalid-time",
+        message: format!("{flag}: cannot read '{value}' as a time"),
+        hint: Some(
+            "Try 7d, 24h, yesterday, last monday, last month, 2024-06, 2024-06-15 or 2024-06-15T14:30:00Z"
+                .to_string(),
+        ),
+        retryable: false,
+    })
 }
 
 /// Compute aggregations from search hits
@@ -2501,6 +3815,8 @@ fn run_cli_search(
     query: &str,
     agents: &[String],
     workspaces: &[String],
+    tags: &[String],
+    bookmarked: bool,
     limit: &usize,
     offset: &usize,
     json: &bool,
@@ -2523,42 +3839,48 @@ fn run_cli_search(
     dry_run: bool,
     timeout_ms: Option<u64>,
     highlight: bool,
+    snippet_chars: Option<usize>,
+    group_size: Option<usize>,
+    semantic: bool,
+    recency: Option<f32>,
+    dedupe: bool,
+    (min_tokens, max_message_tokens): (Option<u64>, Option<u64>),
+    stream: bool,
+    copy: Option<CopyTarget>,
 ) -> CliResult<()> {
-    use crate::search::query::{QueryExplanation, SearchClient, SearchFilters};
-    use crate::search::tantivy::index_dir;
+    use crate::search::query::{QueryExplanation, RecencyDecay, SearchClient, SearchFilters};
     use std::collections::HashSet;
 
     // Start timing for robot_meta elapsed_ms
     let start_time = Instant::now();
 
+    // Searching never writes: resolve the index path without creating it.
     let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
-    let index_path = index_dir(&data_dir).map_err(|e| CliError {
-        code: 9,
-        kind: "path",
-        message: format!("failed to open index dir: {e}"),
-        hint: None,
-        retryable: false,
-    })?;
+    let index_path = crate::search::tantivy::index_path(&data_dir);
     let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
 
-    let client = SearchClient::open(&index_path, Some(&db_path))
-        .map_err(|e| CliError {
-            code: 9,
-            kind: "open-index",
-            message: format!("failed to open index: {e}"),
-            hint: Some("try cass index --full".to_string()),
-            retryable: true,
-        })?
-        .ok_or_else(|| CliError {
-            code: 3,
-            kind: "missing-index",
-            message: format!(
-                "Index not found at {}. Run 'cass index --full' first.",
-                index_path.display()
-            ),
-            hint: None,
-            retryable: true,
-        })?;
+    // A running `cass daemon` already holds a warm reader; only open the index
+    // in-process when no daemon answers.
+    let open_client = || {
+        SearchClient::open(&index_path, Some(&db_path))
+            .map_err(|e| CliError {
+                code: 9,
+                kind: "open-index",
+                message: format!("failed to open index: {e}"),
+                hint: Some("try cass index --full".to_string()),
+                retryable: true,
+            })?
+            .ok_or_else(|| CliError {
+                code: 3,
+                kind: "missing-index",
+                message: format!(
+                    "Index not found at {}. Run 'cass index --full' first.",
+                    index_path.display()
+                ),
+                hint: None,
+                retryable: true,
+            })
+    };
 
     let mut filters = SearchFilters::default();
     if !agents.is_empty() {
@@ -2567,8 +3889,51 @@ fn run_cli_search(
     if !workspaces.is_empty() {
         filters.workspaces = HashSet::from_iter(workspaces.iter().cloned());
     }
+    if !tags.is_empty() {
+        filters.source_paths = Some(session_tags::paths_with_any(&data_dir, tags));
+    }
+    if bookmarked {
+        let store_path = bookmarks::bookmarks_path(&data_dir);
+        let paths = if store_path.exists() {
+            bookmarks::BookmarkStore::open(&store_path)
+                .and_then(|store| store.source_paths())
+                .map_err(|e| CliError {
+                    code: 9,
+                    kind: "bookmarks",
+                    message: format!("failed to read bookmarks: {e:#}"),
+                    hint: None,
+                    retryable: false,
+                })?
+        } else {
+            HashSet::new()
+        };
+        filters.source_paths = Some(match filters.source_paths.take() {
+            Some(tagged) => tagged.intersection(&paths).cloned().collect(),
+            None => paths,
+        });
+    }
     filters.created_from = time_filter.since;
     filters.created_to = time_filter.until;
+    if let Some(weight) = recency {
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(CliError::usage(
+                "--recency must be a non-negative number",
+                Some("e.g. --recency 0.4".to_string()),
+            ));
+        }
+        filters.recency = Some(RecencyDecay::with_weight(weight));
+    }
+    filters.keep_duplicates = !dedupe;
+    if let (Some(min), Some(max)) = (min_tokens, max_message_tokens)
+        && min > max
+    {
+        return Err(CliError::usage(
+            format!("--min-message-tokens {min} is above --max-message-tokens {max}"),
+            None,
+        ));
+    }
+    filters.min_tokens = min_tokens;
+    filters.max_tokens = max_message_tokens;
 
     // Apply cursor overrides (base64-encoded JSON { "offset": usize, "limit": usize })
     let mut limit_val = *limit;
@@ -2648,12 +4013,55 @@ fn run_cli_search(
     // Use search_with_fallback to get full metadata (wildcard_fallback, cache_stats)
     let sparse_threshold = 3; // Threshold for triggering wildcard fallback
 
-    // When aggregating, we need more results for accurate counts
-    // Fetch up to 1000 for aggregation starting at offset 0, then apply offset/limit
-    let (search_limit, search_offset) = if has_aggregation {
+    if stream {
+        if effective_robot.is_none() {
+            return Err(CliError::usage(
+                "--stream writes JSON lines and needs robot output",
+                Some("add --robot (or --json)".to_string()),
+            ));
+        }
+        let mut client = None;
+        let fetch = |n: usize| -> CliResult<crate::search::query::SearchResult> {
+            if let Some(result) =
+                crate::daemon::try_search(&data_dir, query, &filters, n, 0, sparse_threshold)
+            {
+                return Ok(result);
+            }
+            if client.is_none() {
+                client = Some(open_client()?);
+            }
+            client
+                .as_ref()
+                .expect("client opened above")
+                .search_with_fallback(query, filters.clone(), n, 0, sparse_threshold)
+                .map_err(|e| CliError {
+                    code: 9,
+                    kind: "search",
+                    message: format!("search failed: {e}"),
+                    hint: None,
+                    retryable: true,
+                })
+        };
+        let budgets = FieldBudgets {
+            snippet: max_content_length,
+            content: max_content_length,
+            title: max_content_length,
+            fallback: max_content_length,
+        };
+        return stream_robot_results(
+            query, limit_val, offset_val, &fields, budgets, request_id, start_time, fetch,
+        );
+    }
+
+    // Always fetch from offset 0 and slice the page out afterwards: hits are
+    // de-duplicated after retrieval, so pages must be cut from one ordered list
+    // or consecutive pages could overlap. The extra hit tells us whether another
+    // page follows. Aggregation needs up to 1000 hits for accurate counts, and
+    // grouping as many to fill a page of conversations.
+    let (search_limit, search_offset) = if has_aggregation || group_size.is_some() {
         (1000.max(limit_val + offset_val), 0)
     } else {
-        (limit_val, offset_val)
+        (offset_val + limit_val + 1, 0)
     };
 
     // Check if we're already past timeout before starting search
@@ -2673,21 +4081,49 @@ fn run_cli_search(
         });
     }
 
-    let result = client
-        .search_with_fallback(
+    let result = if semantic {
+        crate::search::embeddings::semantic_search(
+            &data_dir,
+            &db_path,
             query,
-            filters.clone(),
+            &filters,
             search_limit,
             search_offset,
-            sparse_threshold,
         )
         .map_err(|e| CliError {
             code: 9,
-            kind: "search",
-            message: format!("search failed: {e}"),
-            hint: None,
+            kind: "semantic-search",
+            message: format!("semantic search failed: {e:#}"),
+            hint: Some("configure [embeddings] in config.toml, then run cass index".to_string()),
             retryable: true,
-        })?;
+        })?
+    } else {
+        match crate::daemon::try_search(
+            &data_dir,
+            query,
+            &filters,
+            search_limit,
+            search_offset,
+            sparse_threshold,
+        ) {
+            Some(result) => result,
+            None => open_client()?
+                .search_with_fallback(
+                    query,
+                    filters.clone(),
+                    search_limit,
+                    search_offset,
+                    sparse_threshold,
+                )
+                .map_err(|e| CliError {
+                    code: 9,
+                    kind: "search",
+                    message: format!("search failed: {e}"),
+                    hint: None,
+                    retryable: true,
+                })?,
+        }
+    };
 
     // Check if search exceeded timeout - return partial results with timeout indicator
     let timed_out = timeout_duration.is_some_and(|t| start_time.elapsed() > t);
@@ -2702,35 +4138,63 @@ fn run_cli_search(
         None
     };
 
-    // Compute aggregations and create display result based on mode
-    let (aggregations, display_result, total_matches) = if has_aggregation {
-        // Compute aggregations from all fetched results
-        let aggs = compute_aggregations(&result.hits, &agg_fields);
-        let total = result.hits.len();
-
-        // Apply offset and limit to get display hits
-        let display_hits: Vec<_> = result
+    // Compute aggregations from all fetched results, then cut out the requested page
+    let aggregations = if has_aggregation {
+        compute_aggregations(&result.hits, &agg_fields)
+    } else {
+        Aggregations::default()
+    };
+    let fetched = result.hits.len();
+    let resize = |mut hit: crate::search::query::SearchHit| {
+        if let Some(chars) = snippet_chars {
+            hit.snippet = resize_snippet(&hit, query, chars);
+        }
+        hit
+    };
+    // With --group-by the page is cut from conversations; each row is the
+    // conversation's best hit and its group keeps the runners-up.
+    let groups = group_size.map(|size| group_by_conversation(&result.hits, size));
+    let rows = groups.as_ref().map_or(fetched, Vec::len);
+    let mut page_groups: Option<Vec<HitGroup>> = groups.map(|groups| {
+        groups
+            .into_iter()
+            .skip(offset_val)
+            .take(limit_val)
+            .map(|g| HitGroup {
+                hits: g.hits.into_iter().map(resize).collect(),
+                count: g.count,
+            })
+            .collect()
+    });
+    let display_hits: Vec<_> = match &mut page_groups {
+        Some(page) => page.iter_mut().map(|g| g.hits.remove(0)).collect(),
+        None => result
             .hits
             .iter()
             .skip(offset_val)
             .take(limit_val)
             .cloned()
-            .collect();
-
-        let display = crate::search::query::SearchResult {
-            hits: display_hits,
-            wildcard_fallback: result.wildcard_fallback,
-            cache_stats: result.cache_stats,
-            suggestions: result.suggestions.clone(),
-        };
-        (aggs, display, total)
+            .map(resize)
+            .collect(),
+    };
+    let has_more = rows > offset_val + display_hits.len();
+    // The index count can lag behind hits found by the wildcard fallback.
+    let total_hits = result.total_hits.map(|n| n.max(fetched));
+    let total_matches = if has_aggregation || page_groups.is_some() {
+        rows
     } else {
-        // No aggregation - use result as-is
-        let total = result.hits.len();
-        (Aggregations::default(), result, total)
+        total_hits.unwrap_or(fetched)
+    };
+    let display_result = crate::search::query::SearchResult {
+        hits: display_hits,
+        wildcard_fallback: result.wildcard_fallback,
+        cache_stats: result.cache_stats,
+        suggestions: result.suggestions,
+        total_hits,
     };
 
     let elapsed_ms = start_time.elapsed().as_millis() as u64;
+    telemetry::record(telemetry::Event::QueryLatency { elapsed_ms });
 
     // Derive per-field budgets, preferring snippet > content > title
     let (snippet_budget, content_budget, title_budget, fallback_budget) = {
@@ -2760,7 +4224,7 @@ fn run_cli_search(
     };
 
     // Build next cursor if more results remain
-    let next_cursor = if total_matches > offset_val + display_result.hits.len() {
+    let next_cursor = if has_more {
         let payload = serde_json::json!({
             "offset": offset_val + display_result.hits.len(),
             "limit": limit_val,
@@ -2844,6 +4308,8 @@ fn run_cli_search(
             explanation.as_ref(),
             timed_out,
             timeout_ms,
+            highlight.then_some(query),
+            page_groups.as_deref(),
         )?;
     } else if display_result.hits.is_empty() {
         eprintln!("No results found.");
@@ -2852,24 +4318,81 @@ fn run_cli_search(
         output_display_results(&display_result.hits, display, wrap, query, highlight)?;
     } else {
         // Default plain text output
-        for hit in &display_result.hits {
+        let plain_snippet = |hit: &crate::search::query::SearchHit| {
+            let snippet = hit.snippet.replace('\n', " ");
+            if highlight {
+                highlight_matches(&snippet, query, "**", "**")
+            } else {
+                snippet
+            }
+        };
+        for (i, hit) in display_result.hits.iter().enumerate() {
             println!("----------------------------------------------------------------");
             println!(
                 "Score: {:.2} | Agent: {} | WS: {}",
                 hit.score, hit.agent, hit.workspace
             );
-            println!("Path: {}", hit.source_path);
-            let snippet = hit.snippet.replace('\n', " ");
-            let snippet = if highlight {
-                highlight_matches(&snippet, query, "**", "**")
-            } else {
-                snippet
-            };
-            println!("Snippet: {}", apply_wrap(&snippet, wrap));
+            println!("Path: {}", hyperlink::path_for_stdout(&hit.source_path));
+            println!("Snippet: {}", apply_wrap(&plain_snippet(hit), wrap));
+            if let Some(group) = page_groups.as_ref().and_then(|g| g.get(i)) {
+                for other in &group.hits {
+                    let line = other.line_number.map(|n| n.to_string()).unwrap_or_default();
+                    println!("  line {line}: {}", apply_wrap(&plain_snippet(other), wrap));
+                }
+                println!("Matches in conversation: {}", group.count);
+            }
         }
         println!("----------------------------------------------------------------");
     }
+    if effective_robot.is_none()
+        && let Some(fix) = display_result
+            .suggestions
+            .iter()
+            .find(|s| matches!(s.kind, crate::search::query::SuggestionKind::SpellingFix))
+    {
+        eprintln!("{}", fix.message);
+    }
+
+    if let Some(target) = copy {
+        match display_result.hits.first() {
+            Some(hit) => {
+                let text = match target {
+                    CopyTarget::Content => hit.content.clone(),
+                    // Snippets mark matched terms with `**`.
+                    CopyTarget::Snippet => hit.snippet.replace("**", ""),
+                    CopyTarget::Path => hit.source_path.clone(),
+                };
+                copy_to_clipboard(&text, target)?;
+            }
+            None => eprintln!("Nothing to copy: no hits."),
+        }
+    }
+
+    Ok(())
+}
 
+/// Put `text` (the `--copy` target) on the clipboard and say so on stderr.
+fn copy_to_clipboard(text: &str, target: CopyTarget) -> CliResult<()> {
+    let method = crate::clipboard::copy(text).map_err(|e| CliError {
+        code: 9,
+        kind: "clipboard",
+        message: format!("copy to clipboard failed: {e}"),
+        hint: Some("set [clipboard] method in the config file (osc52, command, native)".into()),
+        retryable: false,
+    })?;
+    let what = match target {
+        CopyTarget::Content => "content",
+        CopyTarget::Snippet => "snippet",
+        CopyTarget::Path => "path",
+    };
+    let how = match method {
+        crate::clipboard::ClipboardMethod::Osc52 => " via OSC 52",
+        _ => "",
+    };
+    eprintln!(
+        "Copied {what} ({} chars) to the clipboard{how}.",
+        text.chars().count()
+    );
     Ok(())
 }
 
@@ -2914,7 +4437,10 @@ fn output_display_results(
                 let snippet_short = truncate_end(&snippet, 60);
                 println!(
                     "[{:.1}] {} | {} | {}",
-                    hit.score, hit.agent, hit.source_path, snippet_short
+                    hit.score,
+                    hit.agent,
+                    hyperlink::path_for_stdout(&hit.source_path),
+                    snippet_short
                 );
             }
         }
@@ -2995,6 +4521,9 @@ fn filter_hit_fields(
                 "created_at",
                 "line_number",
                 "match_type",
+                "snippets",
+                "conversation_id",
+                "role",
             ];
 
             for field in field_list {
@@ -3023,6 +4552,81 @@ fn truncate_content(s: &str, max_len: usize) -> (String, bool) {
     }
 }
 
+/// Hits fetched by the first `--stream` round; each later round fetches 4x as many.
+const STREAM_FIRST_ROUND: usize = 10;
+
+/// `--stream`: print hits as NDJSON while widening the search, then a `_summary` line.
+///
+/// Every round searches from offset 0, because hits are de-duplicated after retrieval and
+/// pages must be cut from one ordered list; hits already printed are skipped.
+#[allow(clippy::too_many_arguments)]
+fn stream_robot_results(
+    query: &str,
+    limit: usize,
+    offset: usize,
+    fields: &Option<Vec<String>>,
+    budgets: FieldBudgets,
+    request_id: Option<String>,
+    start_time: Instant,
+    mut fetch: impl FnMut(usize) -> CliResult<crate::search::query::SearchResult>,
+) -> CliResult<()> {
+    use std::io::Write;
+
+    let resolved_fields = expand_field_presets(fields);
+    let wanted = offset + limit;
+    let mut fetch_size = (offset + STREAM_FIRST_ROUND).min(wanted);
+    let mut printed = std::collections::HashSet::new();
+    let mut total_hits = None;
+    let mut has_more = false;
+    let mut rounds = 0;
+    let mut out = std::io::stdout().lock();
+
+    while printed.len() < limit {
+        rounds += 1;
+        // One extra hit tells us whether anything follows the requested page.
+        let result = fetch(fetch_size + 1)?;
+        total_hits = result.total_hits;
+        let exhausted = result.hits.len() <= fetch_size;
+        has_more = result.hits.len() > wanted;
+        for hit in result.hits.iter().skip(offset).take(wanted - offset) {
+            if printed.len() >= limit || !printed.insert((hit.source_path.clone(), hit.line_number))
+            {
+                continue;
+            }
+            let value = apply_content_truncation(filter_hit_fields(hit, &resolved_fields), budgets);
+            let line = serde_json::to_string(&value).unwrap_or_default();
+            // The consumer (`head`, fzf) may close the pipe early; that ends the stream.
+            if writeln!(out, "{line}").and_then(|()| out.flush()).is_err() {
+                return Ok(());
+            }
+        }
+        if exhausted || fetch_size >= wanted {
+            break;
+        }
+        fetch_size = (fetch_size * 4).min(wanted);
+    }
+
+    let summary = serde_json::json!({
+        "_summary": {
+            "query": query,
+            "limit": limit,
+            "offset": offset,
+            "count": printed.len(),
+            "total_hits": total_hits,
+            "has_more": has_more,
+            "rounds": rounds,
+            "elapsed_ms": start_time.elapsed().as_millis() as u64,
+            "request_id": request_id,
+        }
+    });
+    let _ = writeln!(
+        out,
+        "{}",
+        serde_json::to_string(&summary).unwrap_or_default()
+    );
+    Ok(())
+}
+
 /// Apply content truncation to a filtered hit JSON object
 #[derive(Clone, Copy)]
 struct FieldBudgets {
@@ -3116,16 +4720,39 @@ fn output_robot_results(
     explanation: Option<&crate::search::query::QueryExplanation>,
     timed_out: bool,
     timeout_ms: Option<u64>,
+    highlight_query: Option<&str>,
+    groups: Option<&[HitGroup]>,
 ) -> CliResult<()> {
     // Expand presets (minimal, summary, all, *)
     let resolved_fields = expand_field_presets(fields);
 
-    // Filter hits to requested fields, then apply content truncation
+    // Filter hits to requested fields, then apply content truncation; match
+    // offsets are taken last so they point into the text actually emitted
+    let render = |hit: &crate::search::query::SearchHit| {
+        let hit =
+            apply_content_truncation(filter_hit_fields(hit, &resolved_fields), truncation_budgets);
+        match highlight_query {
+            Some(q) => attach_match_offsets(hit, q),
+            None => hit,
+        }
+    };
     let filtered_hits: Vec<serde_json::Value> = result
         .hits
         .iter()
-        .map(|hit| filter_hit_fields(hit, &resolved_fields))
-        .map(|hit| apply_content_truncation(hit, truncation_budgets))
+        .enumerate()
+        .map(|(i, hit)| {
+            let mut value = render(hit);
+            if let (Some(group), serde_json::Value::Object(obj)) =
+                (groups.and_then(|g| g.get(i)), &mut value)
+            {
+                obj.insert("group_count".to_string(), serde_json::json!(group.count));
+                obj.insert(
+                    "group_hits".to_string(),
+                    serde_json::Value::Array(group.hits.iter().map(render).collect()),
+                );
+            }
+            value
+        })
         .collect();
 
     // Clamp hits to token budget if provided (approx 4 chars per token)
@@ -3142,15 +4769,18 @@ fn output_robot_results(
     match format {
         RobotFormat::Json => {
             let mut payload = serde_json::json!({
+                "schema": schema_tag("search"),
                 "query": query,
                 "limit": limit,
                 "offset": offset,
                 "count": filtered_hits.len(),
                 "total_matches": total_matches,
+                "total_hits": result.total_hits,
                 "hits": filtered_hits,
                 "max_tokens": max_tokens,
                 "request_id": request_id,
                 "cursor": input_cursor,
+                "next_cursor": next_cursor,
                 "hits_clamped": hits_clamped,
             });
 
@@ -3253,12 +4883,14 @@ fn output_robot_results(
                 || explanation.is_some()
             {
                 let mut meta = serde_json::json!({
+                    "schema": schema_tag("search"),
                     "_meta": {
                         "query": query,
                         "limit": limit,
                         "offset": offset,
                         "count": filtered_hits.len(),
                         "total_matches": total_matches,
+                        "total_hits": result.total_hits,
                         "elapsed_ms": elapsed_ms,
                         "wildcard_fallback": result.wildcard_fallback,
                         "cache_stats": {
@@ -3346,15 +4978,18 @@ fn output_robot_results(
         RobotFormat::Compact => {
             // Single-line compact JSON
             let mut payload = serde_json::json!({
+                "schema": schema_tag("search"),
                 "query": query,
                 "limit": limit,
                 "offset": offset,
                 "count": filtered_hits.len(),
                 "total_matches": total_matches,
+                "total_hits": result.total_hits,
                 "hits": filtered_hits,
                 "max_tokens": max_tokens,
                 "request_id": request_id,
                 "cursor": input_cursor,
+                "next_cursor": next_cursor,
                 "hits_clamped": hits_clamped,
             });
 
@@ -3526,10 +5161,41 @@ fn run_stats(
         )
         .ok();
 
+    // The indexer records when its last successful scan started.
+    let last_indexed_at: Option<i64> = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'last_scan_ts'",
+            [],
+            |r| r.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|s| s.parse().ok());
+
+    // Document-level numbers come from the Tantivy index itself; a missing or
+    // unreadable index leaves them out rather than failing the command.
+    let index_path = crate::search::tantivy::index_path(&data_dir);
+    let index = crate::search::tantivy::index_stats(&index_path)
+        .map_err(|e| tracing::debug!(error = %e, "index stats unavailable"))
+        .ok();
+    let rfc3339 = |ts: i64| chrono::DateTime::from_timestamp_millis(ts).map(|d| d.to_rfc3339());
+
     if json {
         let payload = serde_json::json!({
+            "schema": schema_tag("stats"),
             "conversations": conversation_count,
             "messages": message_count,
+            "last_indexed_at": last_indexed_at.and_then(rfc3339),
+            "index": index.as_ref().map(|ix| serde_json::json!({
+                "path": index_path.display().to_string(),
+                "docs": ix.docs,
+                "segments": ix.segments,
+                "size_bytes": ix.size_bytes,
+                "tokens": ix.tokens,
+                "by_agent": ix.by_agent.iter().map(|(a, c)| serde_json::json!({"agent": a, "docs": c})).collect::<Vec<_>>(),
+                "by_workspace": ix.by_workspace.iter().map(|(w, c)| serde_json::json!({"workspace": w, "docs": c})).collect::<Vec<_>>(),
+                "oldest_message": ix.oldest.and_then(rfc3339),
+                "newest_message": ix.newest.and_then(rfc3339),
+            })),
             "by_agent": agent_rows.iter().map(|(a, c)| serde_json::json!({"agent": a, "count": c})).collect::<Vec<_>>(),
             "top_workspaces": ws_rows.iter().map(|(w, c)| serde_json::json!({"workspace": w, "count": c})).collect::<Vec<_>>(),
             "date_range": {
@@ -3575,6 +5241,42 @@ fn run_stats(
                 new_dt.format("%Y-%m-%d")
             );
         }
+        if let Some(dt) = last_indexed_at.and_then(chrono::DateTime::from_timestamp_millis) {
+            println!("Last Indexed: {}", dt.format("%Y-%m-%d %H:%M:%S UTC"));
+        }
+        println!();
+        match &index {
+            Some(ix) => {
+                println!("Search Index: {}", index_path.display());
+                println!("  Documents: {}", ix.docs);
+                println!("  Segments: {}", ix.segments);
+                println!("  Size: {}", format_bytes(ix.size_bytes));
+                println!("  Tokens (approx.): {}", ix.tokens);
+                if let (Some(old), Some(new)) = (ix.oldest, ix.newest)
+                    && let (Some(old_dt), Some(new_dt)) = (
+                        chrono::DateTime::from_timestamp_millis(old),
+                        chrono::DateTime::from_timestamp_millis(new),
+                    )
+                {
+                    println!(
+                        "  Messages: {} to {}",
+                        old_dt.format("%Y-%m-%d %H:%M"),
+                        new_dt.format("%Y-%m-%d %H:%M")
+                    );
+                }
+                println!("  Documents by Agent:");
+                for (agent, docs) in &ix.by_agent {
+                    println!("    {agent}: {docs}");
+                }
+                if !ix.by_workspace.is_empty() {
+                    println!("  Documents by Workspace:");
+                    for (ws, docs) in &ix.by_workspace {
+                        println!("    {ws}: {docs}");
+                    }
+                }
+            }
+            None => println!("Search Index: not found at {}", index_path.display()),
+        }
     }
 
     Ok(())
@@ -3654,6 +5356,7 @@ fn run_diag(
 
     if json {
         let payload = serde_json::json!({
+            "schema": schema_tag("diag"),
             "version": version,
             "platform": { "os": platform, "arch": arch },
             "paths": {
@@ -3804,6 +5507,7 @@ fn run_status(
     json: bool,
     stale_threshold: u64,
     _robot_meta: bool,
+    shape: &str,
 ) -> CliResult<()> {
     use rusqlite::Connection;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -3897,6 +5601,7 @@ fn run_status(
             .unwrap_or_else(chrono::Utc::now)
             .to_rfc3339();
         let payload = serde_json::json!({
+            "schema": schema_tag(shape),
             "healthy": healthy,
             "index": {
                 "exists": index_exists,
@@ -4034,6 +5739,7 @@ fn run_health(
 
     if json {
         let payload = serde_json::json!({
+            "schema": schema_tag("health"),
             "healthy": healthy,
             "latency_ms": latency_ms,
             "state": state
@@ -4069,12 +5775,134 @@ fn run_health(
             code: 1,
             kind: "health",
             message: "Health check failed".to_string(),
-            hint: Some("Run 'cass index --full' to rebuild the index/database.".to_string()),
+            hint: Some(i18n::tr("hint-index-rebuild")),
             retryable: true,
         })
     }
 }
 
+/// List conversations by most recent activity
+fn run_list(
+    agents: &[String],
+    workspaces: &[String],
+    time_filter: &TimeFilter,
+    limit: usize,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    use colored::Colorize;
+
+    let conn = open_permalink_db(data_dir_override, db_override)?;
+
+    let mut sql = String::from(
+        "SELECT a.slug, w.path, c.title, c.source_path, c.started_at, COUNT(m.id),
+                COALESCE(MAX(m.created_at), c.ended_at, c.started_at) AS last_active
+         FROM conversations c
+         JOIN agents a ON a.id = c.agent_id
+         LEFT JOIN workspaces w ON w.id = c.workspace_id
+         LEFT JOIN messages m ON m.conversation_id = c.id
+         WHERE 1 = 1",
+    );
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    if !agents.is_empty() {
+        let placeholders: Vec<String> = agents
+            .iter()
+            .map(|agent| {
+                params.push(Box::new(agent.clone()));
+                format!("?{}", params.len())
+            })
+            .collect();
+        sql.push_str(&format!(" AND a.slug IN ({})", placeholders.join(", ")));
+    }
+    if !workspaces.is_empty() {
+        let clauses: Vec<String> = workspaces
+            .iter()
+            .map(|ws| {
+                params.push(Box::new(ws.trim_end_matches('/').to_string()));
+                let n = params.len();
+                format!("w.path = ?{n} OR w.path LIKE '%/' || ?{n}")
+            })
+            .collect();
+        sql.push_str(&format!(" AND ({})", clauses.join(" OR ")));
+    }
+    sql.push_str(" GROUP BY c.id HAVING 1 = 1");
+    if let Some(since) = time_filter.since {
+        params.push(Box::new(since));
+        sql.push_str(&format!(" AND last_active >= ?{}", params.len()));
+    }
+    if let Some(until) = time_filter.until {
+        params.push(Box::new(until));
+        sql.push_str(&format!(" AND last_active <= ?{}", params.len()));
+    }
+    params.push(Box::new(limit as i64));
+    sql.push_str(&format!(
+        " ORDER BY last_active DESC NULLS LAST LIMIT ?{}",
+        params.len()
+    ));
+
+    let query_err = |e: rusqlite::Error| CliError {
+        code: 9,
+        kind: "db-query",
+        message: format!("Query failed: {e}"),
+        hint: None,
+        retryable: false,
+    };
+    let mut stmt = conn.prepare(&sql).map_err(query_err)?;
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    let sessions: Vec<serde_json::Value> = stmt
+        .query_map(param_refs.as_slice(), |row| {
+            Ok(serde_json::json!({
+                "agent": row.get::<_, String>(0)?,
+                "workspace": row.get::<_, Option<String>>(1)?,
+                "title": row.get::<_, Option<String>>(2)?,
+                "source_path": row.get::<_, String>(3)?,
+                "started_at": row.get::<_, Option<i64>>(4)?,
+                "message_count": row.get::<_, i64>(5)?,
+                "last_activity": row.get::<_, Option<i64>>(6)?,
+            }))
+        })
+        .map_err(query_err)?
+        .collect::<Result<_, _>>()
+        .map_err(query_err)?;
+
+    if json {
+        let payload = serde_json::json!({
+            "count": sessions.len(),
+            "sessions": sessions,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    if sessions.is_empty() {
+        println!("No sessions found.");
+        return Ok(());
+    }
+    for session in &sessions {
+        let when = session["last_activity"]
+            .as_i64()
+            .and_then(format_export_time)
+            .unwrap_or_else(|| "unknown time".to_string());
+        let title = session["title"].as_str().unwrap_or("Untitled Session");
+        println!(
+            "{}  {:<12} {:>5} msgs  {}",
+            when.dimmed(),
+            session["agent"].as_str().unwrap_or_default().cyan(),
+            session["message_count"],
+            title.chars().take(80).collect::<String>().bold()
+        );
+        let location = session["workspace"]
+            .as_str()
+            .unwrap_or_else(|| session["source_path"].as_str().unwrap_or_default());
+        println!("    {}", location.dimmed());
+    }
+    Ok(())
+}
+
 /// Find related sessions for a given source path.
 /// Returns sessions that share the same workspace, same day, or same agent.
 fn run_context(
@@ -4094,7 +5922,7 @@ fn run_context(
             code: 3,
             kind: "missing_index",
             message: "Database not found".to_string(),
-            hint: Some("Run 'cass index --full' to create the database.".to_string()),
+            hint: Some(i18n::tr("hint-index-create")),
             retryable: true,
         });
     }
@@ -4135,10 +5963,7 @@ fn run_context(
             code: 4,
             kind: "not_found",
             message: format!("No session found at path: {path_str}"),
-            hint: Some(
-                "Use 'cass search' to find sessions, then use the source_path from results."
-                    .to_string(),
-            ),
+            hint: Some(i18n::tr("hint-find-source-path")),
             retryable: false,
         });
     };
@@ -4368,6 +6193,8 @@ fn run_context(
 /// Provides static information about CLI features, versions, and limits.
 #[derive(Debug, Clone, Serialize)]
 pub struct CapabilitiesResponse {
+    /// Versioned shape of this response (`cass.capabilities.v2`)
+    pub schema: String,
     /// Semantic version of the crate
     pub crate_version: String,
     /// API contract version (bumped on breaking changes)
@@ -4401,6 +6228,8 @@ pub struct CapabilitiesLimits {
 /// Full API introspection response
 #[derive(Debug, Clone, Serialize)]
 pub struct IntrospectResponse {
+    /// Versioned shape of this response (`cass.introspect.v2`)
+    pub schema: String,
     /// API version (matches capabilities)
     pub api_version: u32,
     /// Contract version (human-visible)
@@ -4499,7 +6328,7 @@ fn build_global_flag_schemas() -> Vec<ArgumentSchema> {
             required: false,
             default: None,
             enum_values: None,
-            repeatable: None,
+            repeatable: Some(true),
         },
         ArgumentSchema {
             name: "verbose".to_string(),
@@ -4510,6 +6339,17 @@ fn build_global_flag_schemas() -> Vec<ArgumentSchema> {
             required: false,
             default: None,
             enum_values: None,
+            repeatable: Some(true),
+        },
+        ArgumentSchema {
+            name: "log-file".to_string(),
+            short: None,
+            description: "Also log to the daily-rotated cass.log in the data dir".to_string(),
+            arg_type: "flag".to_string(),
+            value_type: None,
+            required: false,
+            default: None,
+            enum_values: None,
             repeatable: None,
         },
         ArgumentSchema {
@@ -4571,6 +6411,7 @@ fn build_global_flag_schemas() -> Vec<ArgumentSchema> {
 /// Discover available features, versions, and limits for agent introspection.
 fn run_capabilities(json: bool) -> CliResult<()> {
     let response = CapabilitiesResponse {
+        schema: schema_tag("capabilities"),
         crate_version: env!("CARGO_PKG_VERSION").to_string(),
         api_version: 1,
         contract_version: CONTRACT_VERSION.to_string(),
@@ -4595,8 +6436,33 @@ fn run_capabilities(json: bool) -> CliResult<()> {
             "introspect_command".to_string(),
             "export_command".to_string(),
             "expand_command".to_string(),
+            "show_command".to_string(),
+            "list_command".to_string(),
             "timeline_command".to_string(),
             "highlight_matches".to_string(),
+            "permalinks".to_string(),
+            "query_daemon".to_string(),
+            "localization".to_string(),
+            "telemetry_command".to_string(),
+            "import_command".to_string(),
+            "index_dry_run".to_string(),
+            "index_optimize".to_string(),
+            "doctor_command".to_string(),
+            "index_lock_wait".to_string(),
+            "fuzzy_search".to_string(),
+            "semantic_search".to_string(),
+            "group_by_conversation".to_string(),
+            "saved_searches".to_string(),
+            "session_tags".to_string(),
+            "bookmarks".to_string(),
+            "notes".to_string(),
+            "diff_command".to_string(),
+            "grep_command".to_string(),
+            "http_api".to_string(),
+            "repl".to_string(),
+            "clipboard_copy".to_string(),
+            "schema_versioning".to_string(),
+            "resume_command".to_string(),
         ],
         connectors: vec![
             "codex".to_string(),
@@ -4609,6 +6475,15 @@ fn run_capabilities(json: bool) -> CliResult<()> {
             "cursor".to_string(),
             "chatgpt".to_string(),
             "pi_agent".to_string(),
+            "sgpt".to_string(),
+            "copilot_cli".to_string(),
+            "qwen".to_string(),
+            "open_webui".to_string(),
+            "plandex".to_string(),
+            "crush".to_string(),
+            "vscode_chat".to_string(),
+            "custom".to_string(),
+            "trajectory".to_string(),
         ],
         limits: CapabilitiesLimits {
             max_limit: 10000,
@@ -4663,6 +6538,7 @@ fn run_introspect(json: bool) -> CliResult<()> {
     let response_schemas = build_response_schemas();
 
     let response = IntrospectResponse {
+        schema: schema_tag("introspect"),
         api_version: 1,
         contract_version: CONTRACT_VERSION.to_string(),
         global_flags,
@@ -4755,6 +6631,7 @@ fn run_introspect(json: bool) -> CliResult<()> {
 /// Show API and contract versions (robot-friendly)
 fn run_api_version(json: bool) -> CliResult<()> {
     let payload = serde_json::json!({
+        "schema": schema_tag("api-version"),
         "crate_version": env!("CARGO_PKG_VERSION"),
         "api_version": 1,
         "contract_version": CONTRACT_VERSION,
@@ -4920,51 +6797,115 @@ fn build_response_schemas() -> std::collections::HashMap<String, serde_json::Val
     use serde_json::json;
     let mut schemas = std::collections::HashMap::new();
 
-    schemas.insert(
-        "search".to_string(),
-        json!({
-            "type": "object",
-            "properties": {
-                "query": { "type": "string" },
-                "limit": { "type": "integer" },
-                "offset": { "type": "integer" },
-                "count": { "type": "integer" },
-                "total_matches": { "type": "integer" },
-                "max_tokens": { "type": ["integer", "null"] },
-                "request_id": { "type": ["string", "null"] },
-                "cursor": { "type": ["string", "null"] },
-                "hits_clamped": { "type": "boolean" },
-                "hits": {
+    // Split out of the `json!` below to stay under the macro recursion limit.
+    let hit_schema = json!({
+        "type": "object",
+        "properties": {
+            "source_path": { "type": "string" },
+            "line_number": { "type": ["integer", "null"] },
+            "agent": { "type": "string" },
+            "workspace": { "type": ["string", "null"] },
+            "title": { "type": ["string", "null"] },
+            "content": { "type": ["string", "null"] },
+            "snippet": { "type": ["string", "null"] },
+            "score": { "type": ["number", "null"] },
+            "created_at": { "type": ["integer", "string", "null"] },
+            "match_type": { "type": ["string", "null"] },
+            "conversation_id": { "type": "string" },
+            "role": { "type": "string" },
+            "group_count": { "type": "integer" },
+            "group_hits": { "type": "array", "items": { "type": "object" } },
+            "highlights": {
+                "type": "object",
+                "additionalProperties": {
                     "type": "array",
                     "items": {
                         "type": "object",
                         "properties": {
-                            "source_path": { "type": "string" },
-                            "line_number": { "type": ["integer", "null"] },
-                            "agent": { "type": "string" },
-                            "workspace": { "type": ["string", "null"] },
-                            "title": { "type": ["string", "null"] },
-                            "content": { "type": ["string", "null"] },
-                            "snippet": { "type": ["string", "null"] },
-                            "score": { "type": ["number", "null"] },
-                            "created_at": { "type": ["integer", "string", "null"] },
-                            "match_type": { "type": ["string", "null"] }
+                            "start": { "type": "integer" },
+                            "end": { "type": "integer" },
+                            "byte_start": { "type": "integer" },
+                            "byte_end": { "type": "integer" }
                         }
                     }
-                },
-                "aggregations": {
-                    "type": ["object", "null"],
-                    "additionalProperties": {
-                        "type": "array",
-                        "items": {
-                            "type": "object",
-                            "properties": {
-                                "key": { "type": "string" },
-                                "count": { "type": "integer" }
-                            }
-                        }
+                }
+            },
+            "snippets": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "file_path": { "type": ["string", "null"] },
+                        "start_line": { "type": ["integer", "null"] },
+                        "end_line": { "type": ["integer", "null"] },
+                        "language": { "type": ["string", "null"] },
+                        "snippet_text": { "type": ["string", "null"] }
                     }
+                }
+            }
+        }
+    });
+
+    let state_schema = json!({
+        "type": "object",
+        "properties": {
+            "index": {
+                "type": "object",
+                "properties": {
+                    "exists": { "type": "boolean" },
+                    "fresh": { "type": "boolean" },
+                    "last_indexed_at": { "type": ["string", "null"] },
+                    "age_seconds": { "type": ["integer", "null"] },
+                    "stale": { "type": "boolean" },
+                    "stale_threshold_seconds": { "type": "integer" }
+                }
+            },
+            "database": {
+                "type": "object",
+                "properties": {
+                    "exists": { "type": "boolean" },
+                    "conversations": { "type": "integer" },
+                    "messages": { "type": "integer" }
+                }
+            }
+        }
+    });
+
+    let aggregations_schema = json!({
+        "type": ["object", "null"],
+        "additionalProperties": {
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "key": { "type": "string" },
+                    "count": { "type": "integer" }
+                }
+            }
+        }
+    });
+
+    schemas.insert(
+        "search".to_string(),
+        json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string" },
+                "limit": { "type": "integer" },
+                "offset": { "type": "integer" },
+                "count": { "type": "integer" },
+                "total_matches": { "type": "integer" },
+                "total_hits": { "type": ["integer", "null"] },
+                "max_tokens": { "type": ["integer", "null"] },
+                "request_id": { "type": ["string", "null"] },
+                "cursor": { "type": ["string", "null"] },
+                "next_cursor": { "type": ["string", "null"] },
+                "hits_clamped": { "type": "boolean" },
+                "hits": {
+                    "type": "array",
+                    "items": hit_schema
                 },
+                "aggregations": aggregations_schema,
                 "_warning": { "type": ["string", "null"] },
                 "_meta": {
                     "type": "object",
@@ -4984,30 +6925,7 @@ fn build_response_schemas() -> std::collections::HashMap<String, serde_json::Val
                         "request_id": { "type": ["string", "null"] },
                         "next_cursor": { "type": ["string", "null"] },
                         "hits_clamped": { "type": "boolean" },
-                        "state": {
-                            "type": "object",
-                            "properties": {
-                                "index": {
-                                    "type": "object",
-                                    "properties": {
-                                        "exists": { "type": "boolean" },
-                                        "fresh": { "type": "boolean" },
-                                        "last_indexed_at": { "type": ["string", "null"] },
-                                        "age_seconds": { "type": ["integer", "null"] },
-                                        "stale": { "type": "boolean" },
-                                        "stale_threshold_seconds": { "type": "integer" }
-                                    }
-                                },
-                                "database": {
-                                    "type": "object",
-                                    "properties": {
-                                        "exists": { "type": "boolean" },
-                                        "conversations": { "type": "integer" },
-                                        "messages": { "type": "integer" }
-                                    }
-                                }
-                            }
-                        },
+                        "state": state_schema,
                         "index_freshness": {
                             "type": "object",
                             "properties": {
@@ -5223,6 +7141,8 @@ fn build_response_schemas() -> std::collections::HashMap<String, serde_json::Val
                 "db_path": { "type": ["string", "null"] },
                 "conversations": { "type": ["integer", "null"] },
                 "messages": { "type": ["integer", "null"] },
+                "agents": { "type": "array", "items": { "type": "string" } },
+                "paths": { "type": "array", "items": { "type": "string" } },
                 "error": { "type": ["string", "null"] }
             }
         }),
@@ -5280,6 +7200,33 @@ fn build_response_schemas() -> std::collections::HashMap<String, serde_json::Val
         }),
     );
 
+    schemas.insert(
+        "doctor".to_string(),
+        json!({
+            "type": "object",
+            "properties": {
+                "index": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "healthy": { "type": "boolean" },
+                        "problems": { "type": "array", "items": { "type": "object" } },
+                        "repair": {
+                            "type": ["object", "null"],
+                            "properties": {
+                                "rebuilt": { "type": "boolean" },
+                                "dropped_segments": { "type": "integer" },
+                                "reindexed_sources": { "type": "integer" },
+                                "conversations": { "type": "integer" }
+                            }
+                        },
+                        "remaining": { "type": "array", "items": { "type": "object" } }
+                    }
+                }
+            }
+        }),
+    );
+
     schemas.insert(
         "view".to_string(),
         json!({
@@ -5338,6 +7285,39 @@ fn build_response_schemas() -> std::collections::HashMap<String, serde_json::Val
                         "newest": { "type": ["string", "null"] }
                     }
                 },
+                "last_indexed_at": { "type": ["string", "null"] },
+                "index": {
+                    "type": ["object", "null"],
+                    "properties": {
+                        "path": { "type": "string" },
+                        "docs": { "type": "integer" },
+                        "segments": { "type": "integer" },
+                        "size_bytes": { "type": "integer" },
+                        "tokens": { "type": "integer" },
+                        "by_agent": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "agent": { "type": "string" },
+                                    "docs": { "type": "integer" }
+                                }
+                            }
+                        },
+                        "by_workspace": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "workspace": { "type": "string" },
+                                    "docs": { "type": "integer" }
+                                }
+                            }
+                        },
+                        "oldest_message": { "type": ["string", "null"] },
+                        "newest_message": { "type": ["string", "null"] }
+                    }
+                },
                 "db_path": { "type": "string" }
             }
         }),
@@ -5393,9 +7373,97 @@ fn build_response_schemas() -> std::collections::HashMap<String, serde_json::Val
         }),
     );
 
+    schemas.insert(
+        "error".to_string(),
+        json!({
+            "type": "object",
+            "properties": {
+                "error": {
+                    "type": "object",
+                    "properties": {
+                        "code": { "type": "integer" },
+                        "kind": { "type": "string" },
+                        "message": { "type": "string" },
+                        "hint": { "type": ["string", "null"] },
+                        "retryable": { "type": "boolean" }
+                    },
+                    "required": ["code", "kind", "message"]
+                }
+            },
+            "required": ["error"]
+        }),
+    );
+
+    // Every shape names its version; see `ROBOT_SCHEMAS`.
+    for (shape, schema) in schemas.iter_mut() {
+        let Some(schema) = schema.as_object_mut() else {
+            continue;
+        };
+        if let Some(props) = schema
+            .get_mut("properties")
+            .and_then(serde_json::Value::as_object_mut)
+        {
+            props.insert(
+                "schema".to_string(),
+                json!({ "type": "string", "const": schema_tag(shape) }),
+            );
+        }
+        match schema.get_mut("required") {
+            Some(serde_json::Value::Array(required)) => required.insert(0, json!("schema")),
+            _ => {
+                schema.insert("required".to_string(), json!(["schema"]));
+            }
+        }
+    }
+
     schemas
 }
 
+/// `cass schema`: the JSON Schema of robot output shape `shape`, or of every shape
+/// keyed by its `schema` tag.
+fn run_schema(shape: Option<&str>) -> CliResult<()> {
+    let schemas = build_response_schemas();
+    let document = |shape: &str, schema: &serde_json::Value| {
+        let mut doc = serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$id": schema_tag(shape),
+            "title": format!("cass {shape} output"),
+        });
+        if let (Some(doc), Some(schema)) = (doc.as_object_mut(), schema.as_object()) {
+            doc.extend(schema.clone());
+        }
+        doc
+    };
+    let payload = match shape {
+        Some(shape) => {
+            let schema = schemas.get(shape).ok_or_else(|| {
+                let mut shapes: Vec<&str> = schemas.keys().map(String::as_str).collect();
+                shapes.sort_unstable();
+                CliError::usage(
+                    format!("no robot output shape '{shape}'"),
+                    Some(format!("Shapes: {}", shapes.join(", "))),
+                )
+            })?;
+            document(shape, schema)
+        }
+        None => {
+            let all: serde_json::Map<String, serde_json::Value> = schemas
+                .iter()
+                .map(|(shape, schema)| (schema_tag(shape), document(shape, schema)))
+                .collect();
+            serde_json::json!({
+                "contract_version": CONTRACT_VERSION,
+                "schemas": all,
+            })
+        }
+    };
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&payload).unwrap_or_default()
+    );
+    Ok(())
+}
+
 fn run_view(path: &PathBuf, line: Option<usize>, context: usize, json: bool) -> CliResult<()> {
     use std::fs::File;
     use std::io::{BufRead, BufReader};
@@ -5439,7 +7507,7 @@ fn run_view(path: &PathBuf, line: Option<usize>, context: usize, json: bool) ->
             code: 2,
             kind: "invalid-line",
             message: "Line numbers start at 1, not 0".to_string(),
-            hint: Some("Use -n 1 for the first line".to_string()),
+            hint: Some(i18n::tr("hint-first-line")),
             retryable: false,
         });
     }
@@ -5453,7 +7521,10 @@ fn run_view(path: &PathBuf, line: Option<usize>, context: usize, json: bool) ->
                 target_line,
                 lines.len()
             ),
-            hint: Some(format!("Use -n {} for the last line", lines.len())),
+            hint: Some(i18n::tr_args(
+                "hint-last-line",
+                &[("line", lines.len().into())],
+            )),
             retryable: false,
         });
     }
@@ -5480,6 +7551,7 @@ fn run_view(path: &PathBuf, line: Option<usize>, context: usize, json: bool) ->
             .collect();
 
         let payload = serde_json::json!({
+            "schema": schema_tag("view"),
             "path": path.display().to_string(),
             "target_line": if highlight_line { Some(target_line) } else { None::<usize> },
             "context": context,
@@ -5526,6 +7598,7 @@ fn spawn_background_indexer(
     let tx_clone = tx.clone();
     std::thread::spawn(move || {
         let db_path = db.unwrap_or_else(|| data_dir.join("agent_search.db"));
+        let reporter = progress.clone();
         let opts = IndexOptions {
             full: false,
             force_rebuild: false,
@@ -5534,132 +7607,557 @@ fn spawn_background_indexer(
             db_path,
             data_dir,
             progress,
+            commit_policy: indexer::CommitPolicy::default(),
+            remotes: Vec::new(),
+            agents: Vec::new(),
+            paths: Vec::new(),
         };
         // Pass the receiver to run_index so it can listen for commands
         if let Err(e) = indexer::run_index(opts, Some((tx_clone, rx))) {
-            warn!("Background indexer failed: {}", e);
+            if e.is::<crate::search::tantivy::IndexLocked>() {
+                // Another process keeps the index current; search it read-only.
+                info!("Another cass index is running; searching its index read-only");
+            } else {
+                warn!("Background indexer failed: {}", e);
+                if let Some(p) = reporter {
+                    p.set_error(format!("{e:#}"));
+                }
+            }
         }
     });
     Some(tx)
 }
 
-#[allow(clippy::too_many_arguments)]
-fn run_index_with_data(
+/// Check `cass index --agent` names and `--path` arguments, returning the connectors to
+/// scan and the absolute paths. Without `--agent`, each path's connector is told from
+/// the path itself.
+fn resolve_index_selection(
+    mut agents: Vec<String>,
+    paths: Vec<PathBuf>,
+) -> CliResult<(Vec<String>, Vec<PathBuf>)> {
+    let known = indexer::connector_names();
+    if let Some(unknown) = agents.iter().find(|a| !known.contains(&a.as_str())) {
+        return Err(CliError::usage(
+            format!("unknown agent '{unknown}'"),
+            Some(format!("Known agents: {}", known.join(", "))),
+        ));
+    }
+    let infer = agents.is_empty();
+    let mut resolved = Vec::with_capacity(paths.len());
+    for path in paths {
+        let path = std::path::absolute(&path).unwrap_or(path);
+        if !path.exists() {
+            return Err(CliError {
+                code: 3,
+                kind: "file-not-found",
+                message: format!("Path not found: {}", path.display()),
+                hint: None,
+                retryable: false,
+            });
+        }
+        if infer {
+            let Some(agent) = indexer::connector_for_path(&path) else {
+                return Err(CliError::usage(
+                    format!(
+                        "cannot tell which agent's sessions are at {}",
+                        path.display()
+                    ),
+                    Some("Name the connector with --agent, e.g. --agent claude_code".to_string()),
+                ));
+            };
+            if !agents.iter().any(|a| a == agent) {
+                agents.push(agent.to_string());
+            }
+        }
+        resolved.push(path);
+    }
+    Ok((agents, resolved))
+}
+
+/// `cass index --dry-run`: the per-connector report from [`indexer::dry_run`].
+fn run_index_dry_run(
     db_override: Option<PathBuf>,
-    full: bool,
-    force_rebuild: bool,
-    watch: bool,
-    watch_once: Option<Vec<PathBuf>>,
     data_dir_override: Option<PathBuf>,
-    progress: ProgressResolved,
+    full: bool,
+    agents: &[String],
     json: bool,
-    idempotency_key: Option<String>,
 ) -> CliResult<()> {
-    use rusqlite::Connection;
-    use std::time::Instant;
-
     let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
     let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+    let reports = indexer::dry_run(&data_dir, &db_path, full, agents);
 
-    // Generate params hash for idempotency validation
-    let params_hash = {
-        use std::hash::{Hash, Hasher};
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        full.hash(&mut hasher);
-        force_rebuild.hash(&mut hasher);
-        watch.hash(&mut hasher);
-        format!("{}", data_dir.display()).hash(&mut hasher);
-        hasher.finish()
-    };
-
-    // Check for cached idempotency result
-    if let Some(key) = &idempotency_key
-        && let Ok(conn) = Connection::open(&db_path)
-    {
-        // Ensure idempotency_keys table exists
-        let _ = conn.execute(
-            "CREATE TABLE IF NOT EXISTS idempotency_keys (
-                key TEXT PRIMARY KEY,
-                params_hash TEXT NOT NULL,
-                result_json TEXT NOT NULL,
-                created_at INTEGER NOT NULL,
-                expires_at INTEGER NOT NULL
-            )",
-            [],
-        );
-
-        // Clean expired keys
-        let now_ms = chrono::Utc::now().timestamp_millis();
-        let _ = conn.execute(
-            "DELETE FROM idempotency_keys WHERE expires_at < ?1",
-            [now_ms],
+    if json {
+        let payload = serde_json::json!({
+            "dry_run": true,
+            "full": full,
+            "data_dir": data_dir.display().to_string(),
+            "connectors": reports,
+            "totals": {
+                "files": reports.iter().map(|r| r.files).sum::<usize>(),
+                "conversations": reports.iter().map(|r| r.conversations).sum::<usize>(),
+                "messages": reports.iter().map(|r| r.messages).sum::<usize>(),
+            },
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
         );
+        return Ok(());
+    }
 
-        // Look up existing key
-        let cached: Option<(String, String)> = conn
-            .query_row(
-                "SELECT params_hash, result_json FROM idempotency_keys WHERE key = ?1 AND expires_at > ?2",
-                rusqlite::params![key, now_ms],
-                |r| Ok((r.get(0)?, r.get(1)?)),
+    const SHOWN: usize = 5;
+    for r in &reports {
+        let status = if !r.enabled {
+            "disabled in config".to_string()
+        } else if !r.detected {
+            "not detected".to_string()
+        } else if let Some(err) = &r.error {
+            format!("scan failed: {err}")
+        } else {
+            format!(
+                "{} file(s), {} conversation(s), {} message(s)",
+                r.files, r.conversations, r.messages
             )
-            .ok();
-
-        if let Some((stored_hash, result_json)) = cached {
-            // Verify params match
-            if stored_hash == params_hash.to_string() {
-                // Return cached result
-                if json {
-                    // Parse and augment with cached flag
-                    if let Ok(mut val) = serde_json::from_str::<serde_json::Value>(&result_json) {
-                        val["cached"] = serde_json::json!(true);
-                        val["idempotency_key"] = serde_json::json!(key);
-                        println!("{}", serde_json::to_string_pretty(&val).unwrap_or_default());
-                        return Ok(());
-                    }
-                } else {
-                    eprintln!(
-                        "Using cached result for idempotency key '{}' (use different key to force re-index)",
-                        key
-                    );
-                    return Ok(());
-                }
-            } else {
-                // Parameter mismatch - return error
-                return Err(CliError {
-                    code: 5,
-                    kind: "idempotency_mismatch",
-                    message: format!(
-                        "Idempotency key '{}' was used with different parameters",
-                        key
-                    ),
-                    hint: Some(
-                        "Use a different idempotency key or wait for the existing one to expire (24h)".to_string(),
-                    ),
-                    retryable: false,
-                });
+        };
+        println!("{:<12} {status}", r.connector);
+        for evidence in r.evidence.iter().filter(|_| r.detected) {
+            println!("    {evidence}");
+        }
+        let mut reasons: Vec<&str> = r.skipped.iter().map(|s| s.reason.as_str()).collect();
+        reasons.dedup();
+        for reason in reasons {
+            let paths: Vec<_> = r.skipped.iter().filter(|s| s.reason == reason).collect();
+            println!("    skipped {} ({reason}):", paths.len());
+            for skipped in paths.iter().take(SHOWN) {
+                println!("      {}", skipped.path.display());
+            }
+            if paths.len() > SHOWN {
+                println!("      ... and {} more", paths.len() - SHOWN);
             }
         }
     }
+    println!(
+        "would ingest {} conversation(s), {} message(s); nothing was written",
+        reports.iter().map(|r| r.conversations).sum::<usize>(),
+        reports.iter().map(|r| r.messages).sum::<usize>()
+    );
+    Ok(())
+}
 
-    let watch_once_paths = watch_once
-        .filter(|paths| !paths.is_empty())
-        .or_else(read_watch_once_paths_env);
-    let opts = IndexOptions {
-        full,
-        force_rebuild,
-        watch,
-        watch_once_paths: watch_once_paths.clone(),
-        db_path: db_path.clone(),
+/// Open the existing search index for maintenance. Unlike `TantivyIndex::open_or_create`
+/// this never wipes an index built under another schema; that takes `cass index --full`.
+fn open_existing_index(data_dir: &Path) -> CliResult<crate::search::tantivy::TantivyIndex> {
+    let index_path = crate::search::tantivy::index_path(data_dir);
+    let schema_hash = std::fs::read_to_string(index_path.join("schema_hash.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|json| json["schema_hash"].as_str().map(String::from));
+    if !index_path.join("meta.json").exists()
+        || schema_hash.as_deref() != Some(crate::search::tantivy::schema_hash().as_str())
+    {
+        return Err(CliError {
+            code: 3,
+            kind: "missing-index",
+            message: format!(
+                "No current index at {}. Run 'cass index --full' first.",
+                index_path.display()
+            ),
+            hint: None,
+            retryable: true,
+        });
+    }
+    crate::search::tantivy::TantivyIndex::open_or_create(&index_path)
+        .map_err(|e| index_write_error("Failed to open index", e))
+}
+
+/// Map a failure to open or write the index to a CLI error, reporting a writer lock held
+/// by another process as `index-locked` (exit 7, lock/busy) rather than a generic failure.
+fn index_write_error(context: &str, e: anyhow::Error) -> CliError {
+    if let Some(locked) = e.downcast_ref::<crate::search::tantivy::IndexLocked>() {
+        return CliError {
+            code: 7,
+            kind: "index-locked",
+            message: locked.to_string(),
+            hint: Some(
+                "Wait for it to finish or pass --wait; searches keep working meanwhile.".into(),
+            ),
+            retryable: true,
+        };
+    }
+    CliError {
+        code: 9,
+        kind: "index",
+        message: format!("{context}: {e:#}"),
+        hint: None,
+        retryable: true,
+    }
+}
+
+/// `cass index --wait`: block until no other process holds the index writer lock, or
+/// fail with `index-locked` once `timeout` has passed.
+fn wait_for_index_writer(data_dir_override: &Option<PathBuf>, timeout: Duration) -> CliResult<()> {
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let index_path = crate::search::tantivy::index_path(&data_dir);
+    let deadline = std::time::Instant::now() + timeout;
+    let mut announced = false;
+    while crate::search::tantivy::writer_locked(&index_path) {
+        if std::time::Instant::now() >= deadline {
+            return Err(index_write_error(
+                "Failed to open index",
+                crate::search::tantivy::IndexLocked { path: index_path }.into(),
+            ));
+        }
+        if !announced {
+            eprintln!(
+                "Another cass index is writing the search index; waiting for it to finish..."
+            );
+            announced = true;
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+    Ok(())
+}
+
+fn merge_status_json(status: &crate::search::tantivy::MergeStatus) -> serde_json::Value {
+    serde_json::json!({
+        "segment_count": status.segment_count,
+        "last_merge_ts": (status.last_merge_ts > 0).then_some(status.last_merge_ts),
+        "ms_since_last_merge": (status.ms_since_last_merge >= 0).then_some(status.ms_since_last_merge),
+        "merge_threshold": status.merge_threshold,
+        "cooldown_ms": status.cooldown_ms,
+        "should_merge": status.should_merge(),
+    })
+}
+
+fn run_index_merge_status(data_dir_override: Option<PathBuf>, json: bool) -> CliResult<()> {
+    let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
+    let index_path = crate::search::tantivy::index_path(&data_dir);
+    // Read-only, so it works while a writer holds the lock.
+    let status = crate::search::tantivy::read_merge_status(&index_path).map_err(|e| CliError {
+        code: 3,
+        kind: "missing-index",
+        message: format!("No readable index at {}: {e:#}", index_path.display()),
+        hint: Some("Run 'cass index --full' first.".into()),
+        retryable: true,
+    })?;
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&merge_status_json(&status)).unwrap_or_default()
+        );
+    } else {
+        println!("Segments: {}", status.segment_count);
+        println!("Merge threshold: {} segments", status.merge_threshold);
+        println!(
+            "Merge recommended: {}",
+            if status.should_merge() {
+                "yes (run 'cass index optimize')"
+            } else {
+                "no"
+            }
+        );
+    }
+    Ok(())
+}
+
+/// `cass index optimize`: merge every segment into one, waiting for the merge so the
+/// process does not exit mid-way. Without `force`, an index below the merge threshold
+/// is left alone.
+fn run_index_optimize(
+    data_dir_override: Option<PathBuf>,
+    force: bool,
+    json: bool,
+) -> CliResult<()> {
+    let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
+    let mut index = open_existing_index(&data_dir)?;
+    let before = index.merge_status();
+    let merged = force || before.should_merge();
+    if merged {
+        index
+            .force_merge()
+            .map_err(|e| CliError::unknown(format!("Index merge failed: {e:#}")))?;
+    }
+    let after = index.merge_status();
+
+    if json {
+        let payload = serde_json::json!({
+            "merged": merged,
+            "segments_before": before.segment_count,
+            "segments_after": after.segment_count,
+            "merge_status": merge_status_json(&after),
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+    } else if merged {
+        println!(
+            "Merged {} segment(s) into {}",
+            before.segment_count, after.segment_count
+        );
+    } else {
+        println!(
+            "Index has {} segment(s), below the merge threshold of {}; use --force to merge anyway",
+            before.segment_count, before.merge_threshold
+        );
+    }
+    Ok(())
+}
+
+/// `cass doctor --index`: report what [`check_index`](crate::search::tantivy::check_index)
+/// finds and, with `repair`, fix it through [`indexer::repair_index`].
+fn run_doctor(
+    data_dir_override: Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    repair: bool,
+    json: bool,
+) -> CliResult<()> {
+    let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+    let index_path = crate::search::tantivy::index_path(&data_dir);
+    let problems = crate::search::tantivy::check_index(&index_path);
+
+    let repaired = if repair && !problems.is_empty() {
+        Some(
+            indexer::repair_index(&data_dir, &db_path, &problems)
+                .map_err(|e| index_write_error("Index repair failed", e))?,
+        )
+    } else {
+        None
+    };
+    let remaining = if repaired.is_some() {
+        crate::search::tantivy::check_index(&index_path)
+    } else {
+        problems.clone()
+    };
+
+    if json {
+        let payload = serde_json::json!({
+            "schema": schema_tag("doctor"),
+            "index": {
+                "path": index_path.display().to_string(),
+                "healthy": problems.is_empty(),
+                "problems": problems,
+                "repair": repaired,
+                "remaining": remaining,
+            },
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+    } else {
+        println!("Search index: {}", index_path.display());
+        if problems.is_empty() {
+            println!("  ✓ healthy");
+        }
+        for problem in &problems {
+            let line = match problem {
+                crate::search::tantivy::IndexProblem::Missing => "missing".to_string(),
+                crate::search::tantivy::IndexProblem::SchemaMismatch { found } => format!(
+                    "schema hash is {}, expected {}",
+                    found.as_deref().unwrap_or("absent"),
+                    crate::search::tantivy::schema_hash()
+                ),
+                crate::search::tantivy::IndexProblem::MetaUnreadable { error } => {
+                    format!("meta.json unreadable: {error}")
+                }
+                crate::search::tantivy::IndexProblem::DamagedSegment { segment, error } => {
+                    format!("segment {segment} damaged: {error}")
+                }
+            };
+            println!("  ✗ {line}");
+        }
+        if let Some(r) = &repaired {
+            if r.rebuilt {
+                println!(
+                    "Rebuilt the index from the database ({} conversations)",
+                    r.conversations
+                );
+            } else {
+                println!(
+                    "Dropped {} damaged segment(s) and re-indexed {} source file(s) ({} conversations)",
+                    r.dropped_segments, r.reindexed_sources, r.conversations
+                );
+            }
+        } else if !problems.is_empty() {
+            println!("Run 'cass doctor --index --repair' to fix.");
+        }
+    }
+
+    if remaining.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError {
+            code: 1,
+            kind: "doctor",
+            message: format!("{} index problem(s) found", remaining.len()),
+            hint: Some("Run 'cass doctor --index --repair'.".into()),
+            retryable: true,
+        })
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_index_with_data(
+    db_override: Option<PathBuf>,
+    full: bool,
+    force_rebuild: bool,
+    watch: bool,
+    watch_once: Option<Vec<PathBuf>>,
+    data_dir_override: Option<PathBuf>,
+    progress: ProgressResolved,
+    json: bool,
+    idempotency_key: Option<String>,
+    commit_policy: indexer::CommitPolicy,
+    remote: Option<Vec<String>>,
+    agents: Vec<String>,
+    paths: Vec<PathBuf>,
+) -> CliResult<()> {
+    use rusqlite::Connection;
+    use std::time::Instant;
+
+    let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+
+    // Generate params hash for idempotency validation
+    let params_hash = {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        full.hash(&mut hasher);
+        force_rebuild.hash(&mut hasher);
+        watch.hash(&mut hasher);
+        remote.hash(&mut hasher);
+        agents.hash(&mut hasher);
+        paths.hash(&mut hasher);
+        format!("{}", data_dir.display()).hash(&mut hasher);
+        hasher.finish()
+    };
+
+    // Check for cached idempotency result
+    if let Some(key) = &idempotency_key
+        && let Ok(conn) = Connection::open(&db_path)
+    {
+        // Ensure idempotency_keys table exists
+        let _ = conn.execute(
+            "CREATE TABLE IF NOT EXISTS idempotency_keys (
+                key TEXT PRIMARY KEY,
+                params_hash TEXT NOT NULL,
+                result_json TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL
+            )",
+            [],
+        );
+
+        // Clean expired keys
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let _ = conn.execute(
+            "DELETE FROM idempotency_keys WHERE expires_at < ?1",
+            [now_ms],
+        );
+
+        // Look up existing key
+        let cached: Option<(String, String)> = conn
+            .query_row(
+                "SELECT params_hash, result_json FROM idempotency_keys WHERE key = ?1 AND expires_at > ?2",
+                rusqlite::params![key, now_ms],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .ok();
+
+        if let Some((stored_hash, result_json)) = cached {
+            // Verify params match
+            if stored_hash == params_hash.to_string() {
+                // Return cached result
+                if json {
+                    // Parse and augment with cached flag
+                    if let Ok(mut val) = serde_json::from_str::<serde_json::Value>(&result_json) {
+                        val["cached"] = serde_json::json!(true);
+                        val["idempotency_key"] = serde_json::json!(key);
+                        println!("{}", serde_json::to_string_pretty(&val).unwrap_or_default());
+                        return Ok(());
+                    }
+                } else {
+                    eprintln!(
+                        "Using cached result for idempotency key '{}' (use different key to force re-index)",
+                        key
+                    );
+                    return Ok(());
+                }
+            } else {
+                // Parameter mismatch - return error
+                return Err(CliError {
+                    code: 5,
+                    kind: "idempotency_mismatch",
+                    message: format!(
+                        "Idempotency key '{}' was used with different parameters",
+                        key
+                    ),
+                    hint: Some(
+                        "Use a different idempotency key or wait for the existing one to expire (24h)".to_string(),
+                    ),
+                    retryable: false,
+                });
+            }
+        }
+    }
+
+    let remotes = match remote {
+        None => Vec::new(),
+        Some(hosts) if hosts.is_empty() => {
+            let configured = config::Config::global().remotes.clone();
+            if configured.is_empty() {
+                return Err(CliError::usage(
+                    "--remote without a host needs `remotes` in the config file",
+                    Some(format!(
+                        "Add remotes = [\"user@host\"] to {} or pass --remote user@host",
+                        config::config_path().display()
+                    )),
+                ));
+            }
+            configured
+        }
+        Some(hosts) => {
+            if let Some(e) = hosts.iter().find_map(|h| config::check_remote(h).err()) {
+                return Err(CliError::usage(
+                    e,
+                    Some("Pass the remote as user@host".to_string()),
+                ));
+            }
+            hosts
+        }
+    };
+
+    let watch_once_paths = watch_once
+        .filter(|paths| !paths.is_empty())
+        .or_else(read_watch_once_paths_env);
+    // `--progress json` streams events on stdout, so the final report must be one line too.
+    let events = matches!(progress, ProgressResolved::Json);
+    let json = json || events;
+    let index_progress = std::sync::Arc::new(indexer::IndexingProgress {
+        emit_events: events.into(),
+        ..Default::default()
+    });
+    let opts = IndexOptions {
+        full,
+        force_rebuild,
+        watch,
+        watch_once_paths: watch_once_paths.clone(),
+        db_path: db_path.clone(),
         data_dir: data_dir.clone(),
-        progress: None,
+        progress: Some(index_progress.clone()),
+        commit_policy,
+        remotes: remotes.clone(),
+        agents: agents.clone(),
+        paths: paths.clone(),
     };
     let spinner = if json {
         None
     } else {
         match progress {
             ProgressResolved::Bars => Some(indicatif::ProgressBar::new_spinner()),
-            ProgressResolved::Plain => None,
-            ProgressResolved::None => None,
+            ProgressResolved::Plain | ProgressResolved::None | ProgressResolved::Json => None,
         }
     };
     if let Some(pb) = &spinner {
@@ -5680,6 +8178,9 @@ fn run_index_with_data(
     let start = Instant::now();
     // CLI index command doesn't support manual reindex triggering from TUI, so pass None
     let res = indexer::run_index(opts, None).map_err(|e| {
+        if e.is::<crate::search::tantivy::IndexLocked>() {
+            return index_write_error("index failed", e);
+        }
         let chain = e
             .chain()
             .map(std::string::ToString::to_string)
@@ -5694,18 +8195,41 @@ fn run_index_with_data(
         }
     });
     let elapsed_ms = start.elapsed().as_millis();
-
+    let quarantined = crate::connectors::quarantine::entries(&data_dir).len();
+    let purged = index_progress
+        .purged
+        .load(std::sync::atomic::Ordering::Relaxed);
+    let restored = index_progress
+        .restored
+        .load(std::sync::atomic::Ordering::Relaxed);
+    let evicted = index_progress
+        .evicted
+        .lock()
+        .map(|e| e.clone())
+        .unwrap_or_default();
+    let embedded = index_progress
+        .embedded
+        .load(std::sync::atomic::Ordering::Relaxed);
+
+    let print_report = |mut payload: serde_json::Value| {
+        if events {
+            payload["event"] = serde_json::json!("done");
+            println!("{}", serde_json::to_string(&payload).unwrap_or_default());
+        } else {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&payload).unwrap_or_default()
+            );
+        }
+    };
     if let Err(err) = &res {
         if json {
-            let payload = serde_json::json!({
+            print_report(serde_json::json!({
+                "schema": schema_tag("index"),
                 "success": false,
                 "error": err.message,
                 "elapsed_ms": elapsed_ms,
-            });
-            println!(
-                "{}",
-                serde_json::to_string_pretty(&payload).unwrap_or_default()
-            );
+            }));
         } else {
             eprintln!("index debug error: {err:?}");
         }
@@ -5723,6 +8247,7 @@ fn run_index_with_data(
             (0, 0)
         };
         let mut payload = serde_json::json!({
+            "schema": schema_tag("index"),
             "success": true,
             "elapsed_ms": elapsed_ms,
             "full": full,
@@ -5731,8 +8256,22 @@ fn run_index_with_data(
             "db_path": db_path.display().to_string(),
             "conversations": conversations,
             "messages": messages,
+            "quarantined": quarantined,
+            "purged": purged,
+            "restored": restored,
+            "evicted": evicted,
+            "embedded": embedded,
         });
-
+        if !remotes.is_empty() {
+            payload["remotes"] = serde_json::json!(remotes);
+        }
+        if !agents.is_empty() {
+            payload["agents"] = serde_json::json!(agents);
+        }
+        if !paths.is_empty() {
+            payload["paths"] = serde_json::json!(paths);
+        }
+
         // Store idempotency key if provided
         if let Some(key) = &idempotency_key {
             payload["idempotency_key"] = serde_json::json!(key);
@@ -5749,10 +8288,7 @@ fn run_index_with_data(
             }
         }
 
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&payload).unwrap_or_default()
-        );
+        print_report(payload);
     }
 
     if let Some(pb) = spinner {
@@ -5760,6 +8296,48 @@ fn run_index_with_data(
     } else if !json && matches!(progress, ProgressResolved::Plain) {
         eprintln!("index completed");
     }
+    if res.is_ok() && !json && quarantined > 0 {
+        eprintln!(
+            "{quarantined} session file(s) could not be parsed and were skipped; see {}",
+            crate::connectors::quarantine::file_path(&data_dir).display()
+        );
+    }
+    if res.is_ok() && !json && restored > 0 {
+        eprintln!(
+            "The search index format changed; rebuilt it from {restored} conversation(s) in the database"
+        );
+    }
+    if res.is_ok() && !json && purged > 0 {
+        eprintln!(
+            "{purged} conversation(s) whose session files were deleted were removed from the index"
+        );
+    }
+    if res.is_ok() && !json && !evicted.is_empty() {
+        eprintln!(
+            "{} oldest conversation(s) were evicted to keep the index under max_index_size_mb:",
+            evicted.len()
+        );
+        for conv in &evicted {
+            eprintln!(
+                "  {} {}",
+                conv.agent,
+                conv.title
+                    .as_deref()
+                    .unwrap_or(&conv.source_path.display().to_string())
+            );
+        }
+    }
+
+    if res.is_ok()
+        && telemetry::enabled()
+        && let Ok(conn) = Connection::open(&db_path)
+        && let Ok(messages) =
+            conn.query_row("SELECT COUNT(*) FROM messages", [], |r| r.get::<_, i64>(0))
+    {
+        telemetry::record(telemetry::Event::IndexSize {
+            messages: messages.max(0) as u64,
+        });
+    }
 
     res
 }
@@ -5892,53 +8470,202 @@ fn run_self_update(tag: &str) -> Result<bool> {
 // ============================================================================
 
 /// Export a conversation to markdown or other formats
+#[allow(clippy::too_many_arguments)]
 fn run_export(
-    path: &Path,
+    path: Option<&Path>,
+    query: Option<&str>,
+    limit: usize,
     format: ConvExportFormat,
     output: Option<&Path>,
     include_tools: bool,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
 ) -> CliResult<()> {
-    use std::fs::File;
-    use std::io::{BufRead, BufReader, Write};
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let paths = match path {
+        Some(path) => vec![path.to_path_buf()],
+        None => export_query_paths(query.unwrap_or_default(), limit, &data_dir, db_override)?,
+    };
 
-    if !path.exists() {
-        return Err(CliError {
+    let mut sessions = Vec::with_capacity(paths.len());
+    for path in paths {
+        let messages = load_session_messages(&path, &data_dir)?;
+        sessions.push((path, messages));
+    }
+
+    // `--query --limit N` with N > 1 always writes into a directory, however many matched.
+    let several = query.is_some() && limit > 1;
+    if let [(_, messages)] = sessions.as_slice()
+        && !several
+    {
+        let formatted = format_session(messages, format, include_tools);
+        match output {
+            Some(out_path) => write_export(out_path, &formatted)?,
+            None => println!("{formatted}"),
+        }
+        return Ok(());
+    }
+
+    // Several conversations: `-o` names a directory with one file per session.
+    if let Some(out_dir) = output {
+        std::fs::create_dir_all(out_dir).map_err(|e| CliError {
+            code: 9,
+            kind: "file-create",
+            message: format!("Failed to create output directory: {e}"),
+            hint: None,
+            retryable: false,
+        })?;
+        let extension = match format {
+            ConvExportFormat::Markdown => "md",
+            ConvExportFormat::Text => "txt",
+            ConvExportFormat::Json => "json",
+            ConvExportFormat::Html => "html",
+        };
+        let mut used = std::collections::HashSet::new();
+        for (path, messages) in &sessions {
+            let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "conversation".to_string());
+            let mut name = format!("{stem}.{extension}");
+            let mut n = 2;
+            while !used.insert(name.clone()) {
+                name = format!("{stem}-{n}.{extension}");
+                n += 1;
+            }
+            write_export(
+                &out_dir.join(name),
+                &format_session(messages, format, include_tools),
+            )?;
+        }
+    } else if matches!(format, ConvExportFormat::Json) {
+        let all: Vec<&Vec<serde_json::Value>> = sessions.iter().map(|(_, m)| m).collect();
+        println!("{}", serde_json::to_string_pretty(&all).unwrap_or_default());
+    } else {
+        let rendered: Vec<String> = sessions
+            .iter()
+            .map(|(_, messages)| format_session(messages, format, include_tools))
+            .collect();
+        println!("{}", rendered.join("\n"));
+    }
+
+    Ok(())
+}
+
+/// Source paths of the conversations whose messages best match `query`, best first.
+fn export_query_paths(
+    query: &str,
+    limit: usize,
+    data_dir: &Path,
+    db_override: Option<PathBuf>,
+) -> CliResult<Vec<PathBuf>> {
+    use crate::search::query::{SearchClient, SearchFilters};
+
+    let index_path = crate::search::tantivy::index_path(data_dir);
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+    let client = SearchClient::open(&index_path, Some(&db_path))
+        .map_err(|e| CliError {
+            code: 9,
+            kind: "open-index",
+            message: format!("failed to open index: {e}"),
+            hint: Some("try cass index --full".to_string()),
+            retryable: true,
+        })?
+        .ok_or_else(|| CliError {
             code: 3,
-            kind: "file-not-found",
-            message: format!("Session file not found: {}", path.display()),
-            hint: Some("Use 'cass search' to find session paths".to_string()),
+            kind: "missing-index",
+            message: format!(
+                "Index not found at {}. Run 'cass index --full' first.",
+                index_path.display()
+            ),
+            hint: None,
+            retryable: true,
+        })?;
+
+    let limit = limit.max(1);
+    // Several hits usually come from one conversation; over-fetch before collapsing.
+    let hits = client
+        .search(query, SearchFilters::default(), limit * 20, 0)
+        .map_err(|e| CliError {
+            code: 9,
+            kind: "search",
+            message: format!("search failed: {e}"),
+            hint: None,
+            retryable: true,
+        })?;
+    let mut seen = std::collections::HashSet::new();
+    let paths: Vec<PathBuf> = hits
+        .into_iter()
+        .filter(|hit| seen.insert(hit.source_path.clone()))
+        .take(limit)
+        .map(|hit| PathBuf::from(hit.source_path))
+        .collect();
+    if paths.is_empty() {
+        return Err(CliError {
+            code: 4,
+            kind: "not_found",
+            message: format!("No conversation matches \"{query}\""),
+            hint: Some("try a broader query or run 'cass index' first".to_string()),
             retryable: false,
         });
     }
+    Ok(paths)
+}
 
-    let file = File::open(path).map_err(|e| CliError {
-        code: 9,
-        kind: "file-open",
-        message: format!("Failed to open file: {e}"),
-        hint: None,
-        retryable: false,
-    })?;
+/// Read a session's JSON lines, or the raw JSON kept in the index when the file is gone.
+fn load_session_messages(path: &Path, data_dir: &Path) -> CliResult<Vec<serde_json::Value>> {
+    Ok(load_numbered_messages(path, data_dir)?
+        .into_iter()
+        .map(|(_, msg)| msg)
+        .collect())
+}
 
-    let reader = BufReader::new(file);
-    let mut messages: Vec<serde_json::Value> = Vec::new();
-    let mut session_title: Option<String> = None;
-    let mut session_start: Option<i64> = None;
-    let mut session_end: Option<i64> = None;
+/// Like [`load_session_messages`], keeping each message's 1-based line number.
+fn load_numbered_messages(
+    path: &Path,
+    data_dir: &Path,
+) -> CliResult<Vec<(usize, serde_json::Value)>> {
+    use std::fs::File;
+    use std::io::{BufRead, BufReader};
 
-    for line in reader.lines().map_while(Result::ok) {
-        if line.trim().is_empty() {
-            continue;
-        }
-        if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&line) {
-            if let Some(ts) = msg.get("timestamp").and_then(|t| t.as_i64()) {
-                if session_start.is_none() || ts < session_start.unwrap() {
-                    session_start = Some(ts);
-                }
-                if session_end.is_none() || ts > session_end.unwrap() {
-                    session_end = Some(ts);
-                }
+    let mut messages: Vec<(usize, serde_json::Value)> = Vec::new();
+    if path.exists() {
+        let file = File::open(path).map_err(|e| CliError {
+            code: 9,
+            kind: "file-open",
+            message: format!("Failed to open file: {e}"),
+            hint: None,
+            retryable: false,
+        })?;
+        let reader = BufReader::new(file);
+        for (idx, line) in reader.lines().map_while(Result::ok).enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&line) {
+                messages.push((idx + 1, msg));
             }
-            messages.push(msg);
+        }
+    } else {
+        // The agent pruned the file: fall back to the original JSON kept in the index
+        // when it was built with `[index] store_raw_json`.
+        messages = crate::search::tantivy::raw_messages(
+            &crate::search::tantivy::index_path(data_dir),
+            path,
+        )
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .map(|(idx, msg)| (idx + 1, msg))
+        .collect();
+        if messages.is_empty() {
+            return Err(CliError {
+                code: 3,
+                kind: "file-not-found",
+                message: format!("Session file not found: {}", path.display()),
+                hint: Some(i18n::tr("hint-find-session-paths")),
+                retryable: false,
+            });
         }
     }
 
@@ -5951,61 +8678,78 @@ fn run_export(
             retryable: false,
         });
     }
+    Ok(messages)
+}
+
+fn write_export(out_path: &Path, formatted: &str) -> CliResult<()> {
+    use std::io::Write;
+
+    let mut out_file = std::fs::File::create(out_path).map_err(|e| CliError {
+        code: 9,
+        kind: "file-create",
+        message: format!("Failed to create output file: {e}"),
+        hint: None,
+        retryable: false,
+    })?;
+    out_file
+        .write_all(formatted.as_bytes())
+        .map_err(|e| CliError {
+            code: 9,
+            kind: "file-write",
+            message: format!("Failed to write output: {e}"),
+            hint: None,
+            retryable: false,
+        })?;
+    println!("Exported to: {}", out_path.display());
+    Ok(())
+}
+
+fn format_session(
+    messages: &[serde_json::Value],
+    format: ConvExportFormat,
+    include_tools: bool,
+) -> String {
+    let session_start = messages.iter().filter_map(message_time_ms).min();
 
     // Find title from first user message
-    for msg in &messages {
-        let role = extract_role(msg);
-        if role == "user" {
-            let content = extract_text_content(msg);
-            if !content.is_empty() {
-                session_title = Some(
-                    content
-                        .lines()
-                        .next()
-                        .unwrap_or("Untitled Session")
-                        .chars()
-                        .take(80)
-                        .collect(),
-                );
-                break;
-            }
-        }
-    }
+    let session_title: Option<String> = messages
+        .iter()
+        .filter(|msg| extract_role(msg) == "user")
+        .map(extract_text_content)
+        .find(|content| !content.is_empty())
+        .map(|content| {
+            content
+                .lines()
+                .next()
+                .unwrap_or("Untitled Session")
+                .chars()
+                .take(80)
+                .collect()
+        });
 
-    let formatted = match format {
+    match format {
         ConvExportFormat::Markdown => {
-            format_as_markdown(&messages, &session_title, session_start, include_tools)
+            format_as_markdown(messages, &session_title, session_start, include_tools)
         }
-        ConvExportFormat::Text => format_as_text(&messages, include_tools),
-        ConvExportFormat::Json => serde_json::to_string_pretty(&messages).unwrap_or_default(),
+        ConvExportFormat::Text => format_as_text(messages, include_tools),
+        ConvExportFormat::Json => serde_json::to_string_pretty(messages).unwrap_or_default(),
         ConvExportFormat::Html => {
-            format_as_html(&messages, &session_title, session_start, include_tools)
+            format_as_html(messages, &session_title, session_start, include_tools)
         }
-    };
-
-    if let Some(out_path) = output {
-        let mut out_file = File::create(out_path).map_err(|e| CliError {
-            code: 9,
-            kind: "file-create",
-            message: format!("Failed to create output file: {e}"),
-            hint: None,
-            retryable: false,
-        })?;
-        out_file
-            .write_all(formatted.as_bytes())
-            .map_err(|e| CliError {
-                code: 9,
-                kind: "file-write",
-                message: format!("Failed to write output: {e}"),
-                hint: None,
-                retryable: false,
-            })?;
-        println!("Exported to: {}", out_path.display());
-    } else {
-        println!("{formatted}");
     }
+}
 
-    Ok(())
+/// A message's timestamp in milliseconds; agents write epoch seconds, millis or ISO-8601.
+fn message_time_ms(msg: &serde_json::Value) -> Option<i64> {
+    let ts = crate::connectors::parse_timestamp(msg.get("timestamp")?)?;
+    Some(if ts < 100_000_000_000 { ts * 1000 } else { ts })
+}
+
+fn format_export_time(ts_ms: i64) -> Option<String> {
+    use chrono::{TimeZone, Utc};
+    Utc.timestamp_millis_opt(ts_ms)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
 }
 
 fn format_as_markdown(
@@ -6014,19 +8758,13 @@ fn format_as_markdown(
     start_ts: Option<i64>,
     include_tools: bool,
 ) -> String {
-    use chrono::{TimeZone, Utc};
     let mut md = String::new();
     md.push_str("# ");
     md.push_str(title.as_deref().unwrap_or("Conversation Export"));
     md.push('\n');
 
-    if let Some(ts) = start_ts
-        && let Some(dt) = Utc.timestamp_opt(ts, 0).single()
-    {
-        md.push_str(&format!(
-            "\n*Started: {}*\n",
-            dt.format("%Y-%m-%d %H:%M UTC")
-        ));
+    if let Some(started) = start_ts.and_then(format_export_time) {
+        md.push_str(&format!("\n*Started: {started}*\n"));
     }
     md.push_str("\n---\n\n");
 
@@ -6037,6 +8775,9 @@ fn format_as_markdown(
             "assistant" => md.push_str("## 🤖 Assistant\n\n"),
             _ => md.push_str(&format!("## {}\n\n", role)),
         }
+        if let Some(time) = message_time_ms(msg).and_then(format_export_time) {
+            md.push_str(&format!("*{time}*\n\n"));
+        }
 
         let content = extract_text_content(msg);
         if !content.is_empty() {
@@ -6093,7 +8834,10 @@ fn format_as_text(messages: &[serde_json::Value], include_tools: bool) -> String
     let mut text = String::new();
     for msg in messages {
         let role = extract_role(msg);
-        text.push_str(&format!("=== {} ===\n\n", role.to_uppercase()));
+        match message_time_ms(msg).and_then(format_export_time) {
+            Some(time) => text.push_str(&format!("=== {} · {time} ===\n\n", role.to_uppercase())),
+            None => text.push_str(&format!("=== {} ===\n\n", role.to_uppercase())),
+        }
 
         let content = extract_text_content(msg);
         if !content.is_empty() {
@@ -6129,12 +8873,8 @@ fn format_as_html(
     start_ts: Option<i64>,
     include_tools: bool,
 ) -> String {
-    use chrono::{TimeZone, Utc};
     let title_str = title.as_deref().unwrap_or("Conversation Export");
-    let date_str = start_ts
-        .and_then(|ts| Utc.timestamp_opt(ts, 0).single())
-        .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
-        .unwrap_or_default();
+    let date_str = start_ts.and_then(format_export_time).unwrap_or_default();
 
     let mut html = format!(
         r#"<!DOCTYPE html>
@@ -6170,9 +8910,13 @@ fn format_as_html(
             _ => "💬 Message",
         };
 
+        let time = message_time_ms(msg)
+            .and_then(format_export_time)
+            .map(|t| format!(r#" <span class="meta">{t}</span>"#))
+            .unwrap_or_default();
         html.push_str(&format!(
             r#"    <div class="message {role_class}">
-        <div class="role">{role_display}</div>
+        <div class="role">{role_display}{time}</div>
         <div class="content">"#
         ));
 
@@ -6213,46 +8957,841 @@ fn html_escape(s: &str) -> String {
         .replace('"', "&quot;")
 }
 
-/// Show messages around a specific line in a session file
-fn run_expand(path: &Path, line: usize, context: usize, json: bool) -> CliResult<()> {
-    use std::fs::File;
-    use std::io::{BufRead, BufReader};
+fn open_permalink_db(
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+) -> CliResult<rusqlite::Connection> {
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
 
-    if !path.exists() {
+    if !db_path.exists() {
         return Err(CliError {
             code: 3,
-            kind: "file-not-found",
-            message: format!("Session file not found: {}", path.display()),
-            hint: Some("Use 'cass search' to find session paths".to_string()),
-            retryable: false,
+            kind: "missing_index",
+            message: "Database not found".to_string(),
+            hint: Some(i18n::tr("hint-index-create")),
+            retryable: true,
         });
     }
 
-    let file = File::open(path).map_err(|e| CliError {
+    rusqlite::Connection::open(&db_path).map_err(|e| CliError {
         code: 9,
-        kind: "file-open",
-        message: format!("Failed to open file: {e}"),
+        kind: "db-open",
+        message: format!("Failed to open database: {e}"),
         hint: None,
         retryable: false,
+    })
+}
+
+/// Parse a cass:// permalink and resolve it to an indexed session
+fn resolve_permalink(
+    url: &str,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+) -> CliResult<permalink::ResolvedPermalink> {
+    let link = permalink::Permalink::parse(url).map_err(|e| {
+        CliError::usage(
+            format!("Invalid permalink: {e}"),
+            Some("Expected cass://<agent>/<conversation-id>#<msg_idx>".to_string()),
+        )
     })?;
+    let conn = open_permalink_db(data_dir_override, db_override)?;
+    link.resolve(&conn)
+        .map_err(|e| CliError::unknown(format!("Failed to resolve permalink: {e}")))?
+        .ok_or_else(|| CliError {
+            code: 4,
+            kind: "not_found",
+            message: format!("No indexed session matches {link}"),
+            hint: Some(i18n::tr("hint-index-refresh")),
+            retryable: false,
+        })
+}
 
-    let reader = BufReader::new(file);
-    let mut messages: Vec<(usize, serde_json::Value)> = Vec::new();
-    let mut target_msg_idx: Option<usize> = None;
-    let mut current_line: usize = 0;
+/// Print the cass:// permalink for a message in an indexed session
+fn run_permalink(
+    path: &Path,
+    line: Option<usize>,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    let conn = open_permalink_db(data_dir_override, db_override)?;
+    let path_str = path.to_string_lossy().to_string();
+    let row: Option<(String, Option<String>)> = conn
+        .query_row(
+            "SELECT a.slug, c.external_id
+             FROM conversations c
+             JOIN agents a ON c.agent_id = a.id
+             WHERE c.source_path = ?1
+             ORDER BY c.started_at DESC LIMIT 1",
+            [&path_str],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .ok();
+    let Some((agent, external_id)) = row else {
+        return Err(CliError {
+            code: 4,
+            kind: "not_found",
+            message: format!("No session found at path: {path_str}"),
+            hint: Some(i18n::tr("hint-find-source-path")),
+            retryable: false,
+        });
+    };
 
-    for raw_line in reader.lines().map_while(Result::ok) {
-        current_line += 1;
-        if raw_line.trim().is_empty() {
-            continue;
-        }
-        if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&raw_line) {
-            if current_line == line {
-                target_msg_idx = Some(messages.len());
-            }
-            messages.push((current_line, msg));
-        }
+    let msg_idx = line.map(|n| n.saturating_sub(1) as i64);
+    let link =
+        permalink::Permalink::for_conversation(&agent, external_id.as_deref(), path, msg_idx);
+
+    if json {
+        let payload = serde_json::json!({
+            "url": link.to_string(),
+            "agent": link.agent,
+            "conversation_id": link.conversation_id,
+            "msg_idx": link.msg_idx,
+            "source_path": path_str,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+    } else {
+        println!("{link}");
     }
+    Ok(())
+}
+
+/// `cass resume`: the agent-native command that continues the session at `path`,
+/// printed as a shell line, reported as JSON, or run in place of cass with `exec`.
+fn run_resume(
+    path: &Path,
+    exec: bool,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    let conn = open_permalink_db(data_dir_override, db_override)?;
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let path_str = path.to_string_lossy().to_string();
+    let row: Option<(String, Option<String>, Option<String>)> = conn
+        .query_row(
+            "SELECT a.slug, c.external_id, w.path
+             FROM conversations c
+             JOIN agents a ON c.agent_id = a.id
+             LEFT JOIN workspaces w ON c.workspace_id = w.id
+             WHERE c.source_path = ?1
+             ORDER BY c.started_at DESC LIMIT 1",
+            [&path_str],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )
+        .ok();
+    let Some((agent, external_id, workspace)) = row else {
+        return Err(CliError {
+            code: 4,
+            kind: "not_found",
+            message: format!("No session found at path: {path_str}"),
+            hint: Some(i18n::tr("hint-find-source-path")),
+            retryable: false,
+        });
+    };
+    let Some(command) = resume::command_for(
+        &agent,
+        external_id.as_deref(),
+        &path,
+        workspace.as_deref().map(Path::new),
+    ) else {
+        let message = if resume::SUPPORTED.contains(&agent.as_str()) {
+            format!("cannot tell the {agent} session id of {path_str}")
+        } else {
+            format!("no resume command is known for {agent} sessions")
+        };
+        return Err(CliError {
+            code: 4,
+            kind: "unsupported",
+            message,
+            hint: Some(format!(
+                "cass resume supports: {}",
+                resume::SUPPORTED.join(", ")
+            )),
+            retryable: false,
+        });
+    };
+
+    if exec {
+        let mut cmd = std::process::Command::new(&command.program);
+        cmd.args(&command.args);
+        if let Some(dir) = &command.cwd {
+            cmd.current_dir(dir);
+        }
+        let exec_error = |e: std::io::Error| CliError {
+            code: 9,
+            kind: "exec",
+            message: format!("running {}: {e}", command.program),
+            hint: Some(format!("Is {} installed and on PATH?", command.program)),
+            retryable: false,
+        };
+        // Hand the terminal over to the agent for good; nothing is left for cass to do.
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            return Err(exec_error(cmd.exec()));
+        }
+        #[cfg(not(unix))]
+        {
+            let status = cmd.status().map_err(exec_error)?;
+            std::process::exit(status.code().unwrap_or(1));
+        }
+    }
+
+    if json {
+        let payload = serde_json::json!({
+            "agent": agent,
+            "source_path": path_str,
+            "program": command.program,
+            "args": command.args,
+            "cwd": command.cwd,
+            "command": command.shell_line(),
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+    } else {
+        println!("{}", command.shell_line());
+    }
+    Ok(())
+}
+
+/// Resolve a permalink for automation without launching the TUI
+fn run_open_url_json(
+    url: &str,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+) -> CliResult<()> {
+    let target = resolve_permalink(url, data_dir_override, db_override)?;
+    let payload = serde_json::json!({
+        "url": url.trim(),
+        "source_path": target.source_path,
+        "title": target.title,
+        "msg_idx": target.msg_idx,
+        "line_number": target.msg_idx.map(|idx| idx + 1),
+    });
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&payload).unwrap_or_default()
+    );
+    Ok(())
+}
+
+/// Add, remove or list the tags of indexed sessions
+fn run_tag(
+    action: TagAction,
+    path: Option<&Path>,
+    tags: &[String],
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    use colored::Colorize;
+
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let write_err = |e: anyhow::Error| CliError {
+        code: 9,
+        kind: "file-write",
+        message: format!("failed to save tags: {e:#}"),
+        hint: None,
+        retryable: false,
+    };
+    // Sessions are keyed by the absolute path the index stores.
+    let source_path = path.map(|p| {
+        std::path::absolute(p)
+            .unwrap_or_else(|_| p.to_path_buf())
+            .to_string_lossy()
+            .into_owned()
+    });
+
+    let tagged: Vec<(String, Vec<String>)> = match (action, source_path) {
+        (TagAction::Ls, None) => session_tags::load(&data_dir)
+            .into_iter()
+            .map(|(path, tags)| (path, tags.into_iter().collect()))
+            .collect(),
+        (TagAction::Ls, Some(path)) => {
+            let tags = session_tags::tags_for(&data_dir, &path);
+            vec![(path, tags.into_iter().collect())]
+        }
+        (_, None) => {
+            return Err(CliError::usage(
+                "cass tag add/rm needs a session path",
+                Some("e.g. cass tag add <source_path> bugfix auth".to_string()),
+            ));
+        }
+        (TagAction::Add, Some(path)) => {
+            if tags.iter().all(|t| session_tags::normalize(t).is_empty()) {
+                return Err(CliError::usage(
+                    "no tags to add",
+                    Some("e.g. cass tag add <source_path> bugfix auth".to_string()),
+                ));
+            }
+            let conn = open_permalink_db(data_dir_override, db_override)?;
+            let indexed = conn
+                .query_row(
+                    "SELECT 1 FROM conversations WHERE source_path = ?1 LIMIT 1",
+                    [&path],
+                    |_| Ok(()),
+                )
+                .is_ok();
+            if !indexed {
+                return Err(CliError {
+                    code: 4,
+                    kind: "not_found",
+                    message: format!("No session found at path: {path}"),
+                    hint: Some(i18n::tr("hint-find-source-path")),
+                    retryable: false,
+                });
+            }
+            let tags = session_tags::add(&data_dir, &path, tags).map_err(write_err)?;
+            vec![(path, tags.into_iter().collect())]
+        }
+        (TagAction::Rm, Some(path)) => {
+            let tags = session_tags::remove(&data_dir, &path, tags).map_err(write_err)?;
+            vec![(path, tags.into_iter().collect())]
+        }
+    };
+
+    if json {
+        let sessions: Vec<_> = tagged
+            .iter()
+            .map(|(path, tags)| serde_json::json!({ "source_path": path, "tags": tags }))
+            .collect();
+        let payload = serde_json::json!({ "count": sessions.len(), "sessions": sessions });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+    } else if tagged.is_empty() {
+        println!("No tagged sessions. Tag one with: cass tag add <source_path> <tag>...");
+    } else {
+        for (path, tags) in &tagged {
+            let chips: Vec<String> = tags.iter().map(|t| format!("#{t}")).collect();
+            if chips.is_empty() {
+                println!("{path}  (no tags)");
+            } else {
+                println!("{path}  {}", chips.join(" ").cyan());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Add, remove or list bookmarks on sessions and messages
+fn run_bookmark(
+    action: BookmarkAction,
+    path: Option<&Path>,
+    line: Option<usize>,
+    note: Option<String>,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    use colored::Colorize;
+
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let store_err = |e: anyhow::Error| CliError {
+        code: 9,
+        kind: "bookmarks",
+        message: format!("bookmarks: {e:#}"),
+        hint: None,
+        retryable: false,
+    };
+    let store =
+        bookmarks::BookmarkStore::open(&bookmarks::bookmarks_path(&data_dir)).map_err(store_err)?;
+    // Sessions are keyed by the absolute path the index stores.
+    let source_path = path.map(|p| {
+        std::path::absolute(p)
+            .unwrap_or_else(|_| p.to_path_buf())
+            .to_string_lossy()
+            .into_owned()
+    });
+    let source_path = match (action, source_path) {
+        (BookmarkAction::Ls, _) => None,
+        (_, Some(path)) => Some(path),
+        (_, None) => {
+            return Err(CliError::usage(
+                "cass bookmark add/rm needs a session path",
+                Some("e.g. cass bookmark add <source_path> --line 12".to_string()),
+            ));
+        }
+    };
+
+    let marks = match (action, source_path) {
+        (BookmarkAction::Add, Some(path)) => {
+            if store.is_bookmarked(&path, line).map_err(store_err)? {
+                return Err(CliError {
+                    code: 2,
+                    kind: "already_bookmarked",
+                    message: format!("{path} is already bookmarked"),
+                    hint: Some("remove it first with cass bookmark rm".to_string()),
+                    retryable: false,
+                });
+            }
+            let conn = open_permalink_db(data_dir_override, db_override)?;
+            let session: Option<(String, String, String)> = conn
+                .query_row(
+                    "SELECT COALESCE(c.title, ''), a.slug, COALESCE(w.path, '')
+                     FROM conversations c
+                     JOIN agents a ON c.agent_id = a.id
+                     LEFT JOIN workspaces w ON c.workspace_id = w.id
+                     WHERE c.source_path = ?1
+                     ORDER BY c.started_at DESC LIMIT 1",
+                    [&path],
+                    |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+                )
+                .ok();
+            let Some((title, agent, workspace)) = session else {
+                return Err(CliError {
+                    code: 4,
+                    kind: "not_found",
+                    message: format!("No session found at path: {path}"),
+                    hint: Some(i18n::tr("hint-find-source-path")),
+                    retryable: false,
+                });
+            };
+            let mut mark = bookmarks::Bookmark::new(title, path.clone(), agent, workspace)
+                .with_note(note.unwrap_or_default());
+            if let Some(line) = line {
+                let content: Option<String> = conn
+                    .query_row(
+                        "SELECT m.content FROM messages m
+                         JOIN conversations c ON m.conversation_id = c.id
+                         WHERE c.source_path = ?1 AND m.idx = ?2
+                         LIMIT 1",
+                        rusqlite::params![path, line.saturating_sub(1) as i64],
+                        |r| r.get(0),
+                    )
+                    .ok();
+                let Some(content) = content else {
+                    return Err(CliError {
+                        code: 4,
+                        kind: "not_found",
+                        message: format!("No message at line {line} of {path}"),
+                        hint: Some("use a line_number from cass search results".to_string()),
+                        retryable: false,
+                    });
+                };
+                let snippet: String = content.split_whitespace().collect::<Vec<_>>().join(" ");
+                mark = mark
+                    .with_line(line)
+                    .with_snippet(snippet.chars().take(160).collect::<String>());
+            }
+            mark.id = store.add(&mark).map_err(store_err)?;
+            vec![mark]
+        }
+        (BookmarkAction::Rm, Some(path)) => {
+            if !store.remove_at(&path, line).map_err(store_err)? {
+                return Err(CliError {
+                    code: 4,
+                    kind: "not_found",
+                    message: match line {
+                        Some(line) => format!("No bookmark on line {line} of {path}"),
+                        None => format!("No bookmark on session {path}"),
+                    },
+                    hint: Some("list bookmarks with cass bookmark ls".to_string()),
+                    retryable: false,
+                });
+            }
+            if json {
+                let payload = serde_json::json!({
+                    "removed": true,
+                    "source_path": path,
+                    "line_number": line,
+                });
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&payload).unwrap_or_default()
+                );
+            } else {
+                println!("Bookmark removed");
+            }
+            return Ok(());
+        }
+        _ => store.list(None).map_err(store_err)?,
+    };
+
+    if json {
+        let payload = serde_json::json!({ "count": marks.len(), "bookmarks": marks });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+    } else if marks.is_empty() {
+        println!("No bookmarks. Star one with: cass bookmark add <source_path> [--line N]");
+    } else {
+        for mark in &marks {
+            let location = match mark.line_number {
+                Some(line) => format!("{}:{line}", mark.source_path),
+                None => mark.source_path.clone(),
+            };
+            let title = if mark.title.is_empty() {
+                "(untitled)"
+            } else {
+                mark.title.as_str()
+            };
+            println!("{} {}  {}", "★".yellow(), title.bold(), location.dimmed());
+            if !mark.snippet.is_empty() {
+                println!("    {}", mark.snippet);
+            }
+            if !mark.note.is_empty() {
+                println!("    {} {}", "note:".cyan(), mark.note);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Add, remove or list notes on messages and conversations
+#[allow(clippy::too_many_arguments)]
+fn run_note(
+    action: NoteAction,
+    path: Option<&Path>,
+    target: Option<&str>,
+    text: Option<String>,
+    id: Option<u64>,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    use colored::Colorize;
+
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let write_err = |e: anyhow::Error| CliError {
+        code: 9,
+        kind: "file-write",
+        message: format!("failed to save notes: {e:#}"),
+        hint: None,
+        retryable: false,
+    };
+    // Sessions are keyed by the absolute path the index stores.
+    let source_path = path.map(|p| {
+        std::path::absolute(p)
+            .unwrap_or_else(|_| p.to_path_buf())
+            .to_string_lossy()
+            .into_owned()
+    });
+    let usage_hint =
+        || Some("e.g. cass note add <source_path> 12 \"the fix that worked\"".to_string());
+
+    let notes = match action {
+        NoteAction::Ls => match &source_path {
+            Some(path) => notes::for_session(&data_dir, path),
+            None => notes::load(&data_dir),
+        },
+        NoteAction::Rm => {
+            let Some(id) = id else {
+                return Err(CliError::usage(
+                    "cass note rm needs --id",
+                    Some("list note ids with cass note ls".to_string()),
+                ));
+            };
+            let Some(removed) = notes::remove(&data_dir, id).map_err(write_err)? else {
+                return Err(CliError {
+                    code: 4,
+                    kind: "not_found",
+                    message: format!("No note with id {id}"),
+                    hint: Some("list note ids with cass note ls".to_string()),
+                    retryable: false,
+                });
+            };
+            vec![removed]
+        }
+        NoteAction::Add => {
+            let (Some(path), Some(target), Some(text)) = (source_path, target, text) else {
+                return Err(CliError::usage(
+                    "cass note add needs a session path, a message index and the note",
+                    usage_hint(),
+                ));
+            };
+            let msg_idx = match target {
+                "session" => None,
+                idx => Some(idx.parse::<i64>().map_err(|_| {
+                    CliError::usage(
+                        format!("'{idx}' is not a message index or 'session'"),
+                        usage_hint(),
+                    )
+                })?),
+            };
+            let conn = open_permalink_db(data_dir_override, db_override)?;
+            let session: Option<(i64, String, String, String, Option<String>)> = conn
+                .query_row(
+                    "SELECT c.id, a.slug, COALESCE(w.path, ''), COALESCE(c.title, ''), c.external_id
+                     FROM conversations c
+                     JOIN agents a ON c.agent_id = a.id
+                     LEFT JOIN workspaces w ON c.workspace_id = w.id
+                     WHERE c.source_path = ?1
+                     ORDER BY c.started_at DESC LIMIT 1",
+                    [&path],
+                    |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?, r.get(4)?)),
+                )
+                .ok();
+            let Some((conversation_id, agent, workspace, title, external_id)) = session else {
+                return Err(CliError {
+                    code: 4,
+                    kind: "not_found",
+                    message: format!("No session found at path: {path}"),
+                    hint: Some(i18n::tr("hint-find-source-path")),
+                    retryable: false,
+                });
+            };
+            if let Some(idx) = msg_idx {
+                let exists = conn
+                    .query_row(
+                        "SELECT 1 FROM messages WHERE conversation_id = ?1 AND idx = ?2",
+                        rusqlite::params![conversation_id, idx],
+                        |_| Ok(()),
+                    )
+                    .is_ok();
+                if !exists {
+                    return Err(CliError {
+                        code: 4,
+                        kind: "not_found",
+                        message: format!("No message {idx} in {path}"),
+                        hint: Some(
+                            "message indexes are a search hit's line_number minus 1".to_string(),
+                        ),
+                        retryable: false,
+                    });
+                }
+            }
+            let note = notes::Note {
+                id: 0,
+                source_path: path,
+                msg_idx,
+                text,
+                agent,
+                workspace,
+                title,
+                external_id,
+                created_at: chrono::Utc::now().timestamp_millis(),
+            };
+            vec![notes::add(&data_dir, note).map_err(write_err)?]
+        }
+    };
+
+    // Search picks the change up now if it can, else at the next index run.
+    let indexed = if action == NoteAction::Ls {
+        true
+    } else {
+        indexer::sync_notes(&data_dir).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "indexing notes failed");
+            false
+        })
+    };
+
+    if json {
+        let mut payload = serde_json::json!({ "count": notes.len(), "notes": notes });
+        if action != NoteAction::Ls {
+            payload["indexed"] = serde_json::Value::Bool(indexed);
+        }
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+        return Ok(());
+    }
+    match action {
+        NoteAction::Add => println!("Added note #{}", notes[0].id),
+        NoteAction::Rm => println!("Removed note #{}", notes[0].id),
+        NoteAction::Ls if notes.is_empty() => {
+            println!("No notes. Add one with: cass note add <source_path> <idx> \"text\"");
+        }
+        NoteAction::Ls => {
+            for note in &notes {
+                let at = match note.msg_idx {
+                    Some(idx) => format!("{}#{idx}", note.source_path),
+                    None => note.source_path.clone(),
+                };
+                println!("{} {}", format!("#{}", note.id).yellow(), at.dimmed());
+                println!("    {}", note.text);
+            }
+        }
+    }
+    if !indexed {
+        eprintln!("Search will include this change after the next cass index.");
+    }
+    Ok(())
+}
+
+/// Show, toggle or export the opt-in usage metrics
+fn run_telemetry(action: TelemetryAction, output: Option<&Path>, json: bool) -> CliResult<()> {
+    let mut state = telemetry::TelemetryState::load();
+    let save_err = |e: anyhow::Error| CliError::unknown(format!("Failed to save telemetry: {e}"));
+    match action {
+        TelemetryAction::Enable => state = telemetry::set_enabled(true).map_err(save_err)?,
+        TelemetryAction::Disable => state = telemetry::set_enabled(false).map_err(save_err)?,
+        TelemetryAction::Export => {
+            let body = serde_json::to_string_pretty(&state.export()).unwrap_or_default();
+            match output {
+                Some(path) => std::fs::write(path, body + "\n").map_err(|e| {
+                    CliError::unknown(format!("Failed to write {}: {e}", path.display()))
+                })?,
+                None => println!("{body}"),
+            }
+            return Ok(());
+        }
+        TelemetryAction::Status => {}
+    }
+
+    let path = telemetry::state_path();
+    if json {
+        let payload = serde_json::json!({
+            "enabled": state.enabled,
+            "enabled_at": state.enabled_at,
+            "path": path.display().to_string(),
+            "recorded_commands": state.commands.values().sum::<u64>(),
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+    } else if state.enabled {
+        println!(
+            "Telemetry is enabled. Metrics are stored locally in {}",
+            path.display()
+        );
+        println!("Run 'cass telemetry export' to view them, 'cass telemetry disable' to stop.");
+    } else {
+        println!("Telemetry is disabled (default). Nothing is recorded.");
+        println!("Run 'cass telemetry enable' to record aggregate, non-content metrics locally.");
+    }
+    Ok(())
+}
+
+/// Copy trajectory files into the data dir and index them under the `trajectory` agent
+fn run_import(
+    paths: &[PathBuf],
+    format: ImportFormat,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    use crate::connectors::trajectory::TrajectoryConnector;
+
+    let ImportFormat::Trajectory = format;
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+    let import_dir = TrajectoryConnector::import_dir(&data_dir);
+
+    // Parse everything up front so one bad file leaves nothing half-imported.
+    let mut convs = Vec::with_capacity(paths.len());
+    for path in paths {
+        if !path.exists() {
+            return Err(CliError {
+                code: 3,
+                kind: "file-not-found",
+                message: format!("Trajectory file not found: {}", path.display()),
+                hint: None,
+                retryable: false,
+            });
+        }
+        let conv = TrajectoryConnector::parse_file(path).map_err(|e| CliError {
+            code: 2,
+            kind: "invalid-trajectory",
+            message: format!("{e:#}"),
+            hint: Some(
+                "Expected a SWE-agent .traj file or a JSON list of agent events".to_string(),
+            ),
+            retryable: false,
+        })?;
+        convs.push(conv);
+    }
+
+    std::fs::create_dir_all(&import_dir).map_err(|e| {
+        CliError::unknown(format!("Failed to create {}: {e}", import_dir.display()))
+    })?;
+    let mut imported = Vec::with_capacity(convs.len());
+    for (path, conv) in paths.iter().zip(convs.iter_mut()) {
+        // The external id is the file stem, so re-importing a file updates it in place.
+        let stored = import_dir.join(format!(
+            "{}.json",
+            conv.external_id.as_deref().unwrap_or("trajectory")
+        ));
+        std::fs::copy(path, &stored)
+            .map_err(|e| CliError::unknown(format!("Failed to copy {}: {e}", path.display())))?;
+        conv.source_path = stored.clone();
+        imported.push(serde_json::json!({
+            "source": path.display().to_string(),
+            "stored": stored.display().to_string(),
+            "conversation_id": conv.external_id,
+            "title": conv.title,
+            "messages": conv.messages.len(),
+        }));
+    }
+
+    indexer::ingest_conversations(&db_path, &data_dir, &convs).map_err(|e| CliError {
+        code: 9,
+        kind: "index",
+        message: format!("Failed to index imported trajectories: {e:#}"),
+        hint: Some("Stop any running `cass index --watch` and retry".to_string()),
+        retryable: true,
+    })?;
+
+    if json {
+        let payload = serde_json::json!({
+            "agent": "trajectory",
+            "imported": imported,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+    } else {
+        for conv in &convs {
+            println!(
+                "Imported {} ({} messages)",
+                conv.source_path.display(),
+                conv.messages.len()
+            );
+        }
+        println!("Search them with --agent trajectory.");
+    }
+    Ok(())
+}
+
+/// Every JSON line of a session file with its 1-based line number; blank and
+/// unparseable lines are skipped.
+fn jsonl_messages(reader: impl std::io::BufRead) -> Vec<(usize, serde_json::Value)> {
+    reader
+        .lines()
+        .map_while(Result::ok)
+        .enumerate()
+        .filter(|(_, raw)| !raw.trim().is_empty())
+        .filter_map(|(i, raw)| Some((i + 1, serde_json::from_str(&raw).ok()?)))
+        .collect()
+}
+
+/// Show messages around a specific line in a session file
+fn run_expand(path: &Path, line: usize, context: usize, json: bool) -> CliResult<()> {
+    use std::fs::File;
+    use std::io::BufReader;
+
+    if !path.exists() {
+        return Err(CliError {
+            code: 3,
+            kind: "file-not-found",
+            message: format!("Session file not found: {}", path.display()),
+            hint: Some(i18n::tr("hint-find-session-paths")),
+            retryable: false,
+        });
+    }
+
+    let file = File::open(path).map_err(|e| CliError {
+        code: 9,
+        kind: "file-open",
+        message: format!("Failed to open file: {e}"),
+        hint: None,
+        retryable: false,
+    })?;
+
+    let messages = jsonl_messages(BufReader::new(file));
+    let mut target_msg_idx = messages.iter().position(|(l, _)| *l == line);
 
     if target_msg_idx.is_none() && line > 0 {
         for (idx, (msg_line, _)) in messages.iter().enumerate() {
@@ -6270,7 +9809,10 @@ fn run_expand(path: &Path, line: usize, context: usize, json: bool) -> CliResult
         code: 2,
         kind: "line-not-found",
         message: format!("No message found at or near line {}", line),
-        hint: Some(format!("File has {} messages", messages.len())),
+        hint: Some(i18n::tr_args(
+            "hint-message-count",
+            &[("count", messages.len().into())],
+        )),
         retryable: false,
     })?;
 
@@ -6335,370 +9877,900 @@ fn run_expand(path: &Path, line: usize, context: usize, json: bool) -> CliResult
 
         println!("{}", "─".repeat(60));
         println!(
-            "Showing messages {} to {} of {} total",
-            start + 1,
-            end,
-            messages.len()
+            "Showing messages {} to {} of {} total",
+            start + 1,
+            end,
+            messages.len()
+        );
+    }
+    Ok(())
+}
+
+fn extract_text_content(msg: &serde_json::Value) -> String {
+    // Try direct content first (standard format)
+    if let Some(content) = msg.get("content") {
+        if let Some(text) = content.as_str() {
+            return text.to_string();
+        }
+        if let Some(arr) = content.as_array() {
+            let mut result = String::new();
+            for block in arr {
+                if block.get("type").and_then(|t| t.as_str()) == Some("text")
+                    && let Some(text) = block.get("text").and_then(|t| t.as_str())
+                {
+                    if !result.is_empty() {
+                        result.push('\n');
+                    }
+                    result.push_str(text);
+                }
+            }
+            if !result.is_empty() {
+                return result;
+            }
+        }
+    }
+    // Try nested message.content (Claude Code format)
+    if let Some(inner) = msg.get("message")
+        && let Some(content) = inner.get("content")
+    {
+        if let Some(text) = content.as_str() {
+            return text.to_string();
+        }
+        if let Some(arr) = content.as_array() {
+            let mut result = String::new();
+            for block in arr {
+                if block.get("type").and_then(|t| t.as_str()) == Some("text")
+                    && let Some(text) = block.get("text").and_then(|t| t.as_str())
+                {
+                    if !result.is_empty() {
+                        result.push('\n');
+                    }
+                    result.push_str(text);
+                }
+            }
+            return result;
+        }
+    }
+    // Codex envelope: {"type": "event_msg"|"response_item", "payload": {...}}
+    if let Some(payload) = msg.get("payload") {
+        if let Some(text) = payload.get("message").and_then(|m| m.as_str()) {
+            return text.to_string();
+        }
+        if let Some(content) = payload.get("content") {
+            return crate::connectors::flatten_content(content);
+        }
+    }
+    String::new()
+}
+
+/// Extract role from message (supports various formats)
+fn extract_role(msg: &serde_json::Value) -> String {
+    // Try direct role
+    if let Some(role) = msg.get("role").and_then(|r| r.as_str()) {
+        return role.to_string();
+    }
+    // Try Codex payload.role, or the event type for user/agent messages
+    if let Some(payload) = msg.get("payload") {
+        if let Some(role) = payload.get("role").and_then(|r| r.as_str()) {
+            return role.to_string();
+        }
+        match payload.get("type").and_then(|t| t.as_str()) {
+            Some("user_message") => return "user".to_string(),
+            Some("agent_message") => return "assistant".to_string(),
+            _ => {}
+        }
+    }
+    // Try nested message.role (Claude Code format)
+    if let Some(inner) = msg.get("message")
+        && let Some(role) = inner.get("role").and_then(|r| r.as_str())
+    {
+        return role.to_string();
+    }
+    // Try type field (Claude Code also uses "type": "user" or "type": "assistant")
+    if let Some(type_val) = msg.get("type").and_then(|t| t.as_str()) {
+        match type_val {
+            "user" => return "user".to_string(),
+            "assistant" => return "assistant".to_string(),
+            _ => {}
+        }
+    }
+    "unknown".to_string()
+}
+
+/// Pretty-print a conversation, or the messages around `--around-msg`
+fn run_show(
+    path: &Path,
+    around_msg: Option<usize>,
+    context: usize,
+    json: bool,
+    data_dir_override: &Option<PathBuf>,
+    copy: Option<CopyTarget>,
+) -> CliResult<()> {
+    use colored::Colorize;
+
+    if copy == Some(CopyTarget::Snippet) {
+        return Err(CliError::usage(
+            "cass show has no snippet to copy",
+            Some("use --copy content or --copy path".to_string()),
+        ));
+    }
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let messages = load_numbered_messages(path, &data_dir)?;
+    let total = messages.len();
+
+    // msg_idx is zero-based over the session's lines, as in permalinks and search hits.
+    let (window, target_line) = match around_msg {
+        Some(idx) => {
+            let line = idx + 1;
+            let target = messages
+                .iter()
+                .position(|(msg_line, _)| *msg_line >= line)
+                .ok_or_else(|| CliError {
+                    code: 2,
+                    kind: "message-not-found",
+                    message: format!("No message at index {idx} in {}", path.display()),
+                    hint: Some(i18n::tr_args(
+                        "hint-message-count",
+                        &[("count", total.into())],
+                    )),
+                    retryable: false,
+                })?;
+            let start = target.saturating_sub(context);
+            let end = (target + context + 1).min(total);
+            (&messages[start..end], Some(messages[target].0))
+        }
+        None => (messages.as_slice(), None),
+    };
+    // --copy takes the --around-msg message as is, or every message shown under its role.
+    let copy_shown = || -> CliResult<()> {
+        let Some(target) = copy else {
+            return Ok(());
+        };
+        let text = match (target, target_line) {
+            (CopyTarget::Path, _) => path.display().to_string(),
+            (_, Some(line)) => window
+                .iter()
+                .find(|(msg_line, _)| *msg_line == line)
+                .map(|(_, msg)| extract_text_content(msg))
+                .unwrap_or_default(),
+            (_, None) => window
+                .iter()
+                .filter_map(|(_, msg)| {
+                    let content = extract_text_content(msg);
+                    (!content.is_empty())
+                        .then(|| format!("{}:\n{content}", extract_role(msg).to_uppercase()))
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        };
+        copy_to_clipboard(&text, target)
+    };
+
+    if json {
+        let output: Vec<serde_json::Value> = window
+            .iter()
+            .map(|(line_num, msg)| {
+                serde_json::json!({
+                    "msg_idx": line_num - 1,
+                    "line": line_num,
+                    "role": extract_role(msg),
+                    "timestamp": message_time_ms(msg),
+                    "is_target": Some(*line_num) == target_line,
+                    "content": extract_text_content(msg),
+                })
+            })
+            .collect();
+        let payload = serde_json::json!({
+            "source_path": path.display().to_string(),
+            "total_messages": total,
+            "messages": output,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        );
+        return copy_shown();
+    }
+
+    println!("{}", path.display().to_string().bold());
+    for (line_num, msg) in window {
+        let role = extract_role(msg);
+        let content = extract_text_content(msg);
+        let is_target = Some(*line_num) == target_line;
+        // Session metadata lines carry no text; skip them unless asked for.
+        if content.is_empty() && !is_target {
+            continue;
+        }
+        let label = match role.as_str() {
+            "user" => role.to_uppercase().bold().blue(),
+            "assistant" => role.to_uppercase().bold().green(),
+            "system" | "tool" => role.to_uppercase().bold().yellow(),
+            _ => role.to_uppercase().bold().dimmed(),
+        };
+        let time = message_time_ms(msg)
+            .and_then(format_export_time)
+            .map(|t| format!(" · {t}"))
+            .unwrap_or_default();
+        let marker = if is_target { "▶ " } else { "" };
+        println!();
+        println!(
+            "{}{label} {}",
+            marker.bold().yellow(),
+            format!("#{}{time}", line_num - 1).dimmed()
+        );
+        for line in content.lines() {
+            println!("  {line}");
+        }
+    }
+    copy_shown()
+}
+
+/// Open the index once and hand it to the query loop.
+fn run_repl(
+    limit: usize,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+) -> CliResult<()> {
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let index_path = crate::search::tantivy::index_path(&data_dir);
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+    let client = crate::search::query::SearchClient::open(&index_path, Some(&db_path))
+        .map_err(|e| CliError {
+            code: 9,
+            kind: "open-index",
+            message: format!("failed to open index: {e}"),
+            hint: Some("try cass index --full".to_string()),
+            retryable: true,
+        })?
+        .ok_or_else(|| CliError {
+            code: 3,
+            kind: "missing-index",
+            message: format!(
+                "Index not found at {}. Run 'cass index --full' first.",
+                index_path.display()
+            ),
+            hint: None,
+            retryable: true,
+        })?;
+    repl::run(client, &data_dir, limit.max(1)).map_err(|e| CliError {
+        code: 9,
+        kind: "repl",
+        message: format!("repl failed: {e:#}"),
+        hint: None,
+        retryable: false,
+    })
+}
+
+/// A message of one side of `cass diff`.
+struct DiffMessage {
+    /// Zero-based message index, as in permalinks and `cass show`.
+    idx: usize,
+    role: String,
+    text: String,
+}
+
+/// How `cass diff` lined up the messages of the two sessions.
+enum DiffEntry<'a> {
+    Same(&'a DiffMessage, &'a DiffMessage),
+    Changed(&'a DiffMessage, &'a DiffMessage),
+    OnlyA(&'a DiffMessage),
+    OnlyB(&'a DiffMessage),
+}
+
+fn diff_messages(path: &Path, data_dir: &Path) -> CliResult<Vec<DiffMessage>> {
+    Ok(load_numbered_messages(path, data_dir)?
+        .into_iter()
+        .map(|(line, msg)| DiffMessage {
+            idx: line - 1,
+            role: extract_role(&msg),
+            text: extract_text_content(&msg),
+        })
+        // Session metadata lines carry no text.
+        .filter(|m| !m.text.trim().is_empty())
+        .collect())
+}
+
+/// Align `a` and `b` on identical messages (same role, same words). Between two such
+/// anchors, messages of the same role pair up in order as changed; the rest exist on
+/// one side only.
+fn align_sessions<'a>(a: &'a [DiffMessage], b: &'a [DiffMessage]) -> Vec<DiffEntry<'a>> {
+    let key = |m: &DiffMessage| {
+        let words: Vec<&str> = m.text.split_whitespace().collect();
+        (m.role.clone(), words.join(" "))
+    };
+    let keys_a: Vec<_> = a.iter().map(key).collect();
+    let keys_b: Vec<_> = b.iter().map(key).collect();
+
+    let mut entries = Vec::new();
+    let pair_up = |old: &'a [DiffMessage], new: &'a [DiffMessage], entries: &mut Vec<_>| {
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() && j < new.len() {
+            if old[i].role == new[j].role {
+                entries.push(DiffEntry::Changed(&old[i], &new[j]));
+                i += 1;
+                j += 1;
+            } else if new[j..].iter().any(|m| m.role == old[i].role) {
+                entries.push(DiffEntry::OnlyB(&new[j]));
+                j += 1;
+            } else {
+                entries.push(DiffEntry::OnlyA(&old[i]));
+                i += 1;
+            }
+        }
+        entries.extend(old[i..].iter().map(DiffEntry::OnlyA));
+        entries.extend(new[j..].iter().map(DiffEntry::OnlyB));
+    };
+    let (mut old_from, mut new_from) = (0, 0);
+    for op in similar::capture_diff_slices(similar::Algorithm::Myers, &keys_a, &keys_b) {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        if tag != similar::DiffTag::Equal {
+            continue;
+        }
+        pair_up(
+            &a[old_from..old_range.start],
+            &b[new_from..new_range.start],
+            &mut entries,
+        );
+        for (i, j) in old_range.clone().zip(new_range.clone()) {
+            entries.push(DiffEntry::Same(&a[i], &b[j]));
+        }
+        (old_from, new_from) = (old_range.end, new_range.end);
+    }
+    pair_up(&a[old_from..], &b[new_from..], &mut entries);
+    entries
+}
+
+/// Diff two sessions message by message
+fn run_diff(
+    path_a: &Path,
+    path_b: &Path,
+    mode: DiffMode,
+    width: Option<usize>,
+    json: bool,
+    data_dir_override: &Option<PathBuf>,
+) -> CliResult<()> {
+    use colored::Colorize;
+
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let a = diff_messages(path_a, &data_dir)?;
+    let b = diff_messages(path_b, &data_dir)?;
+    let entries = align_sessions(&a, &b);
+
+    let count = |f: fn(&DiffEntry) -> bool| entries.iter().filter(|e| f(e)).count();
+    let same = count(|e| matches!(e, DiffEntry::Same(..)));
+    let changed_prompts = count(|e| matches!(e, DiffEntry::Changed(m, _) if m.role == "user"));
+    let changed_other = count(|e| matches!(e, DiffEntry::Changed(m, _) if m.role != "user"));
+    let only_a = count(|e| matches!(e, DiffEntry::OnlyA(_)));
+    let only_b = count(|e| matches!(e, DiffEntry::OnlyB(_)));
+
+    if json {
+        let side = |m: &DiffMessage| serde_json::json!({ "msg_idx": m.idx, "content": m.text });
+        let output: Vec<serde_json::Value> = entries
+            .iter()
+            .map(|entry| match entry {
+                DiffEntry::Same(x, y) => serde_json::json!({
+                    "status": "same", "role": x.role, "a": side(x), "b": side(y),
+                }),
+                DiffEntry::Changed(x, y) => serde_json::json!({
+                    "status": "changed",
+                    "role": x.role,
+                    "a": side(x),
+                    "b": side(y),
+                    "diff": similar::TextDiff::from_lines(&x.text, &y.text)
+                        .unified_diff()
+                        .context_radius(2)
+                        .header("a", "b")
+                        .to_string(),
+                }),
+                DiffEntry::OnlyA(x) => serde_json::json!({
+                    "status": "only_a", "role": x.role, "a": side(x),
+                }),
+                DiffEntry::OnlyB(y) => serde_json::json!({
+                    "status": "only_b", "role": y.role, "b": side(y),
+                }),
+            })
+            .collect();
+        let payload = serde_json::json!({
+            "a": { "source_path": path_a.display().to_string(), "messages": a.len() },
+            "b": { "source_path": path_b.display().to_string(), "messages": b.len() },
+            "summary": {
+                "same": same,
+                "changed_prompts": changed_prompts,
+                "divergent_responses": changed_other,
+                "only_a": only_a,
+                "only_b": only_b,
+            },
+            "entries": output,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
         );
+        return Ok(());
     }
-    Ok(())
-}
 
-fn extract_text_content(msg: &serde_json::Value) -> String {
-    // Try direct content first (standard format)
-    if let Some(content) = msg.get("content") {
-        if let Some(text) = content.as_str() {
-            return text.to_string();
-        }
-        if let Some(arr) = content.as_array() {
-            let mut result = String::new();
-            for block in arr {
-                if block.get("type").and_then(|t| t.as_str()) == Some("text")
-                    && let Some(text) = block.get("text").and_then(|t| t.as_str())
-                {
-                    if !result.is_empty() {
-                        result.push('\n');
+    let first_line = |m: &DiffMessage, max: usize| {
+        let line = m.text.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        truncate_end(line.trim(), max)
+    };
+    println!(
+        "{} {}  ({} messages)",
+        "--- a:".red().bold(),
+        path_a.display(),
+        a.len()
+    );
+    println!(
+        "{} {}  ({} messages)",
+        "+++ b:".green().bold(),
+        path_b.display(),
+        b.len()
+    );
+    println!();
+
+    match mode {
+        DiffMode::Unified => {
+            for entry in &entries {
+                match entry {
+                    DiffEntry::Same(x, y) => println!(
+                        "{}",
+                        format!(
+                            "  = {} #{}/#{}  {}",
+                            x.role.to_uppercase(),
+                            x.idx,
+                            y.idx,
+                            first_line(x, 70)
+                        )
+                        .dimmed()
+                    ),
+                    DiffEntry::Changed(x, y) => {
+                        let what = if x.role == "user" {
+                            "prompt changed"
+                        } else {
+                            "response diverged"
+                        };
+                        println!(
+                            "  {} {} #{}/#{}  {}",
+                            "~".yellow().bold(),
+                            x.role.to_uppercase().bold(),
+                            x.idx,
+                            y.idx,
+                            what.yellow()
+                        );
+                        let diff = similar::TextDiff::from_lines(&x.text, &y.text);
+                        for (g, group) in diff.grouped_ops(2).iter().enumerate() {
+                            if g > 0 {
+                                println!("      {}", "…".dimmed());
+                            }
+                            for op in group {
+                                for change in diff.iter_changes(op) {
+                                    let line = change.value().trim_end_matches('\n');
+                                    match change.tag() {
+                                        similar::ChangeTag::Delete => {
+                                            println!("      {}", format!("- {line}").red());
+                                        }
+                                        similar::ChangeTag::Insert => {
+                                            println!("      {}", format!("+ {line}").green());
+                                        }
+                                        similar::ChangeTag::Equal => {
+                                            println!("      {}", format!("  {line}").dimmed());
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    DiffEntry::OnlyA(x) => {
+                        println!(
+                            "  {} {} #{}  {}",
+                            "-".red().bold(),
+                            x.role.to_uppercase().bold(),
+                            x.idx,
+                            "only in a".red()
+                        );
+                        for line in x.text.lines() {
+                            println!("      {}", format!("- {line}").red());
+                        }
+                    }
+                    DiffEntry::OnlyB(y) => {
+                        println!(
+                            "  {} {} #{}  {}",
+                            "+".green().bold(),
+                            y.role.to_uppercase().bold(),
+                            y.idx,
+                            "only in b".green()
+                        );
+                        for line in y.text.lines() {
+                            println!("      {}", format!("+ {line}").green());
+                        }
                     }
-                    result.push_str(text);
                 }
             }
-            if !result.is_empty() {
-                return result;
-            }
-        }
-    }
-    // Try nested message.content (Claude Code format)
-    if let Some(inner) = msg.get("message")
-        && let Some(content) = inner.get("content")
-    {
-        if let Some(text) = content.as_str() {
-            return text.to_string();
+            println!();
         }
-        if let Some(arr) = content.as_array() {
-            let mut result = String::new();
-            for block in arr {
-                if block.get("type").and_then(|t| t.as_str()) == Some("text")
-                    && let Some(text) = block.get("text").and_then(|t| t.as_str())
-                {
-                    if !result.is_empty() {
-                        result.push('\n');
-                    }
-                    result.push_str(text);
+        DiffMode::SideBySide => {
+            let total = width
+                .or_else(|| crossterm::terminal::size().ok().map(|(w, _)| w as usize))
+                .unwrap_or(120)
+                .max(40);
+            let column = (total - 3) / 2;
+            let wrap = |text: &str| -> Vec<String> {
+                text.lines()
+                    .flat_map(|line| {
+                        let chars: Vec<char> = line.chars().collect();
+                        if chars.is_empty() {
+                            return vec![String::new()];
+                        }
+                        chars
+                            .chunks(column)
+                            .map(|c| c.iter().collect())
+                            .collect::<Vec<String>>()
+                    })
+                    .collect()
+            };
+            let header = |m: &DiffMessage| format!("{} #{}", m.role.to_uppercase(), m.idx);
+            for entry in &entries {
+                let (left, right, marker) = match entry {
+                    DiffEntry::Same(x, y) => (
+                        vec![header(x), first_line(x, column)],
+                        vec![header(y), first_line(y, column)],
+                        " ".normal(),
+                    ),
+                    DiffEntry::Changed(x, y) => (
+                        [vec![header(x)], wrap(&x.text)].concat(),
+                        [vec![header(y)], wrap(&y.text)].concat(),
+                        "|".yellow().bold(),
+                    ),
+                    DiffEntry::OnlyA(x) => (
+                        [vec![header(x)], wrap(&x.text)].concat(),
+                        Vec::new(),
+                        "<".red().bold(),
+                    ),
+                    DiffEntry::OnlyB(y) => (
+                        Vec::new(),
+                        [vec![header(y)], wrap(&y.text)].concat(),
+                        ">".green().bold(),
+                    ),
+                };
+                for row in 0..left.len().max(right.len()) {
+                    let l = left.get(row).map_or("", String::as_str);
+                    let r = right.get(row).map_or("", String::as_str);
+                    let (l, r) = if matches!(entry, DiffEntry::Same(..)) {
+                        (l.dimmed(), r.dimmed())
+                    } else if row == 0 {
+                        (l.bold(), r.bold())
+                    } else {
+                        (l.normal(), r.normal())
+                    };
+                    // Pad by hand: colored strings carry escape codes.
+                    let pad = column.saturating_sub(l.chars().count());
+                    println!("{l}{} {marker} {r}", " ".repeat(pad));
                 }
+                println!();
             }
-            return result;
         }
     }
-    String::new()
+
+    println!(
+        "{} same, {} changed prompt(s), {} divergent response(s), {} only in a, {} only in b",
+        same, changed_prompts, changed_other, only_a, only_b
+    );
+    Ok(())
 }
 
-/// Extract role from message (supports various formats)
-fn extract_role(msg: &serde_json::Value) -> String {
-    // Try direct role
-    if let Some(role) = msg.get("role").and_then(|r| r.as_str()) {
-        return role.to_string();
-    }
-    // Try nested message.role (Claude Code format)
-    if let Some(inner) = msg.get("message")
-        && let Some(role) = inner.get("role").and_then(|r| r.as_str())
-    {
-        return role.to_string();
-    }
-    // Try type field (Claude Code also uses "type": "user" or "type": "assistant")
-    if let Some(type_val) = msg.get("type").and_then(|t| t.as_str()) {
-        match type_val {
-            "user" => return "user".to_string(),
-            "assistant" => return "assistant".to_string(),
-            _ => {}
+/// Characters of a matching line printed by `cass grep`.
+const GREP_LINE_MAX_CHARS: usize = 240;
+
+/// Grep session files without the index: every line of message text matching `pattern`,
+/// printed as soon as its connector parses the session.
+fn run_grep(
+    pattern: &str,
+    agents: &[String],
+    ignore_case: bool,
+    fixed_strings: bool,
+    limit: Option<usize>,
+    data_dir_override: &Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    use colored::Colorize;
+    use std::io::Write;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let source = if fixed_strings {
+        regex::escape(pattern)
+    } else {
+        pattern.to_string()
+    };
+    let re = regex::RegexBuilder::new(&source)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| {
+            CliError::usage(
+                format!("invalid pattern: {e}"),
+                Some("Use -F to match the pattern literally".to_string()),
+            )
+        })?;
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let start = Instant::now();
+    let sessions = AtomicUsize::new(0);
+    let matched = AtomicUsize::new(0);
+    let stopped = std::sync::atomic::AtomicBool::new(false);
+    let out = Mutex::new(std::io::stdout());
+
+    let failures = crate::indexer::scan_sessions(&data_dir, agents, &|conv| {
+        sessions.fetch_add(1, Ordering::Relaxed);
+        let source_path = conv.source_path.display().to_string();
+        let workspace = conv.workspace.as_ref().map(|w| w.display().to_string());
+        for msg in &conv.messages {
+            for line in msg.content.lines().filter(|line| re.is_match(line)) {
+                let mut out = out.lock().unwrap_or_else(|e| e.into_inner());
+                if stopped.load(Ordering::Relaxed) {
+                    anyhow::bail!("grep limit reached");
+                }
+                let n = matched.fetch_add(1, Ordering::Relaxed) + 1;
+                let text = truncate_end(line.trim(), GREP_LINE_MAX_CHARS);
+                let written = if json {
+                    let hit = serde_json::json!({
+                        "agent": conv.agent_slug,
+                        "source_path": source_path,
+                        "line_number": msg.idx + 1,
+                        "role": msg.role,
+                        "title": conv.title,
+                        "workspace": workspace,
+                        "created_at": msg.created_at,
+                        "text": text,
+                    });
+                    writeln!(out, "{}", serde_json::to_string(&hit).unwrap_or_default())
+                } else {
+                    let highlighted = re.replace_all(&text, |caps: &regex::Captures| {
+                        caps[0].red().bold().to_string()
+                    });
+                    writeln!(
+                        out,
+                        "{}:{}: {} {}",
+                        source_path.magenta(),
+                        (msg.idx + 1).to_string().green(),
+                        format!("[{}]", msg.role).dimmed(),
+                        highlighted
+                    )
+                };
+                // A closed pipe (`| head`) or the limit ends the scan.
+                if written.is_err() || limit.is_some_and(|max| n >= max) {
+                    stopped.store(true, Ordering::Relaxed);
+                    anyhow::bail!("grep stopped");
+                }
+            }
         }
+        Ok(())
+    });
+    let stopped = stopped.load(Ordering::Relaxed);
+    let failures: Vec<_> = if stopped { Vec::new() } else { failures };
+    let matched = matched.load(Ordering::Relaxed);
+    let sessions = sessions.load(Ordering::Relaxed);
+
+    if json {
+        let summary = serde_json::json!({
+            "_summary": {
+                "pattern": pattern,
+                "count": matched,
+                "sessions_scanned": sessions,
+                "limit_reached": stopped && limit.is_some_and(|max| matched >= max),
+                "errors": failures
+                    .iter()
+                    .map(|(agent, e)| serde_json::json!({ "agent": agent, "error": format!("{e:#}") }))
+                    .collect::<Vec<_>>(),
+                "elapsed_ms": start.elapsed().as_millis() as u64,
+            }
+        });
+        let _ = writeln!(
+            std::io::stdout(),
+            "{}",
+            serde_json::to_string(&summary).unwrap_or_default()
+        );
+        return Ok(());
     }
-    "unknown".to_string()
+    for (agent, e) in &failures {
+        eprintln!("{} {agent}: {e:#}", "warning:".yellow());
+    }
+    eprintln!(
+        "{}",
+        format!(
+            "{matched} matching line(s) in {sessions} session(s) scanned in {:.1}s",
+            start.elapsed().as_secs_f64()
+        )
+        .dimmed()
+    );
+    Ok(())
 }
 
-/// Show activity timeline for a time range
-#[allow(clippy::too_many_arguments)]
+/// Show activity timeline for a time range: sessions active in it, oldest first.
 fn run_timeline(
-    since: Option<&str>,
-    until: Option<&str>,
-    today: bool,
     agents: &[String],
-    data_dir: &Option<PathBuf>,
+    workspaces: &[String],
+    time_filter: &TimeFilter,
+    data_dir_override: &Option<PathBuf>,
     db_override: Option<PathBuf>,
     json: bool,
     group_by: TimelineGrouping,
 ) -> CliResult<()> {
-    use chrono::{Local, TimeZone, Utc};
-    use rusqlite::Connection;
-    use std::collections::HashMap;
-
-    let data_root = data_dir.clone().unwrap_or_else(default_data_dir);
-    let db_path = db_override.unwrap_or_else(|| data_root.join("agent_search.db"));
+    use chrono::{Local, TimeZone};
+    use colored::Colorize;
 
-    if !db_path.exists() {
-        return Err(CliError {
-            code: 3,
-            kind: "db-not-found",
-            message: "No database found. Run 'cass index' first.".to_string(),
-            hint: Some(format!("Expected: {}", db_path.display())),
-            retryable: true,
-        });
-    }
-
-    let conn = Connection::open(&db_path).map_err(|e| CliError {
-        code: 9,
-        kind: "db-open",
-        message: format!("Failed to open database: {e}"),
-        hint: None,
-        retryable: true,
-    })?;
-
-    let now = Local::now();
-    let (start_ts, end_ts) = if today {
-        let start_of_day = now.date_naive().and_hms_opt(0, 0, 0).unwrap();
-        let local_start = Local.from_local_datetime(&start_of_day).single().unwrap();
-        (local_start.timestamp(), now.timestamp())
-    } else {
-        let start = since
-            .and_then(parse_datetime_flexible)
-            .unwrap_or_else(|| (now - chrono::Duration::days(7)).timestamp());
-        let end = until
-            .and_then(parse_datetime_flexible)
-            .unwrap_or_else(|| now.timestamp());
-        (start, end)
-    };
+    let conn = open_permalink_db(data_dir_override, db_override)?;
 
+    // A session belongs to the range when any part of it falls inside, so a long
+    // session that started the day before an incident still shows up.
     let mut sql = String::from(
-        "SELECT c.id, c.agent, c.title, c.started_at, c.ended_at, c.source_path,
-                COUNT(m.id) as message_count
+        "SELECT a.slug, w.path, c.title, c.source_path, COUNT(m.id),
+                COALESCE(c.started_at, MIN(m.created_at)) AS first_active,
+                COALESCE(MAX(m.created_at), c.ended_at, c.started_at) AS last_active
          FROM conversations c
+         JOIN agents a ON a.id = c.agent_id
+         LEFT JOIN workspaces w ON w.id = c.workspace_id
          LEFT JOIN messages m ON m.conversation_id = c.id
-         WHERE c.started_at >= ?1 AND c.started_at <= ?2",
+         WHERE 1 = 1",
     );
-
-    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(start_ts), Box::new(end_ts)];
-
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
     if !agents.is_empty() {
-        sql.push_str(" AND c.agent IN (");
-        for (i, agent) in agents.iter().enumerate() {
-            if i > 0 {
-                sql.push_str(", ");
-            }
-            sql.push_str(&format!("?{}", params.len() + 1));
-            params.push(Box::new(agent.clone()));
-        }
-        sql.push(')');
+        let placeholders: Vec<String> = agents
+            .iter()
+            .map(|agent| {
+                params.push(Box::new(agent.clone()));
+                format!("?{}", params.len())
+            })
+            .collect();
+        sql.push_str(&format!(" AND a.slug IN ({})", placeholders.join(", ")));
     }
+    if !workspaces.is_empty() {
+        let clauses: Vec<String> = workspaces
+            .iter()
+            .map(|ws| {
+                params.push(Box::new(ws.trim_end_matches('/').to_string()));
+                let n = params.len();
+                format!("w.path = ?{n} OR w.path LIKE '%/' || ?{n}")
+            })
+            .collect();
+        sql.push_str(&format!(" AND ({})", clauses.join(" OR ")));
+    }
+    sql.push_str(" GROUP BY c.id HAVING first_active IS NOT NULL");
+    if let Some(since) = time_filter.since {
+        params.push(Box::new(since));
+        sql.push_str(&format!(" AND last_active >= ?{}", params.len()));
+    }
+    if let Some(until) = time_filter.until {
+        params.push(Box::new(until));
+        sql.push_str(&format!(" AND first_active <= ?{}", params.len()));
+    }
+    sql.push_str(" ORDER BY first_active ASC");
 
-    sql.push_str(" GROUP BY c.id ORDER BY c.started_at DESC");
-
-    let mut stmt = conn.prepare(&sql).map_err(|e| CliError {
+    let query_err = |e: rusqlite::Error| CliError {
         code: 9,
         kind: "db-query",
         message: format!("Query failed: {e}"),
         hint: None,
         retryable: false,
-    })?;
-
+    };
+    let mut stmt = conn.prepare(&sql).map_err(query_err)?;
     let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
-
-    let rows = stmt
+    let sessions: Vec<serde_json::Value> = stmt
         .query_map(param_refs.as_slice(), |row| {
-            Ok((
-                row.get::<_, i64>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, Option<String>>(2)?,
-                row.get::<_, i64>(3)?,
-                row.get::<_, Option<i64>>(4)?,
-                row.get::<_, String>(5)?,
-                row.get::<_, i64>(6)?,
-            ))
+            let started = row.get::<_, i64>(5)?;
+            let ended = row.get::<_, i64>(6)?.max(started);
+            Ok(serde_json::json!({
+                "agent": row.get::<_, String>(0)?,
+                "workspace": row.get::<_, Option<String>>(1)?,
+                "title": row.get::<_, Option<String>>(2)?,
+                "source_path": row.get::<_, String>(3)?,
+                "message_count": row.get::<_, i64>(4)?,
+                "started_at": started,
+                "ended_at": ended,
+                "duration_seconds": (ended - started) / 1000,
+            }))
         })
-        .map_err(|e| CliError {
-            code: 9,
-            kind: "db-query",
-            message: format!("Query failed: {e}"),
-            hint: None,
-            retryable: false,
-        })?;
-
-    #[allow(clippy::type_complexity)]
-    let mut sessions: Vec<(i64, String, Option<String>, i64, Option<i64>, String, i64)> =
-        Vec::new();
-    for r in rows.flatten() {
-        sessions.push(r);
+        .map_err(query_err)?
+        .collect::<Result<_, _>>()
+        .map_err(query_err)?;
+
+    let local = |ts_ms: i64| Local.timestamp_millis_opt(ts_ms).single();
+    let group_key = |session: &serde_json::Value| {
+        let started = local(session["started_at"].as_i64().unwrap_or_default());
+        match (group_by, started) {
+            (TimelineGrouping::Hour, Some(dt)) => dt.format("%Y-%m-%d %H:00").to_string(),
+            (TimelineGrouping::Day, Some(dt)) => dt.format("%Y-%m-%d").to_string(),
+            _ => String::new(),
+        }
+    };
+    // Sessions are in start order, so each group is one run of equal keys.
+    let mut groups: Vec<(String, Vec<&serde_json::Value>)> = Vec::new();
+    for session in &sessions {
+        let key = group_key(session);
+        match groups.last_mut() {
+            Some((last, members)) if *last == key => members.push(session),
+            _ => groups.push((key, vec![session])),
+        }
     }
 
     if json {
-        let output = match group_by {
-            TimelineGrouping::None => {
-                let items: Vec<serde_json::Value> = sessions
-                    .iter()
-                    .map(|(id, agent, title, started, ended, path, msg_count)| {
-                        let duration = ended.map(|e| e - started);
-                        serde_json::json!({
-                            "id": id, "agent": agent, "title": title,
-                            "started_at": started, "ended_at": ended,
-                            "duration_seconds": duration, "source_path": path,
-                            "message_count": msg_count,
-                        })
-                    })
-                    .collect();
-                serde_json::json!({
-                    "range": { "start": start_ts, "end": end_ts },
-                    "total_sessions": sessions.len(),
-                    "sessions": items,
-                })
-            }
-            TimelineGrouping::Hour | TimelineGrouping::Day => {
-                let mut groups: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
-                for (id, agent, title, started, ended, path, msg_count) in &sessions {
-                    let dt = Utc
-                        .timestamp_opt(*started, 0)
-                        .single()
-                        .unwrap_or_else(Utc::now);
-                    let key = match group_by {
-                        TimelineGrouping::Hour => dt.format("%Y-%m-%d %H:00").to_string(),
-                        TimelineGrouping::Day => dt.format("%Y-%m-%d").to_string(),
-                        _ => unreachable!(),
-                    };
-                    groups.entry(key).or_default().push(serde_json::json!({
-                        "id": id, "agent": agent, "title": title,
-                        "started_at": started, "ended_at": ended,
-                        "source_path": path, "message_count": msg_count,
-                    }));
-                }
-                serde_json::json!({
-                    "range": { "start": start_ts, "end": end_ts },
-                    "total_sessions": sessions.len(),
-                    "groups": groups,
-                })
-            }
-        };
+        let mut payload = serde_json::json!({
+            "range": { "start": time_filter.since, "end": time_filter.until },
+            "total_sessions": sessions.len(),
+        });
+        if group_by == TimelineGrouping::None {
+            payload["sessions"] = serde_json::json!(sessions);
+        } else {
+            payload["groups"] = groups
+                .iter()
+                .map(|(key, members)| serde_json::json!({ "key": key, "sessions": members }))
+                .collect();
+        }
         println!(
             "{}",
-            serde_json::to_string_pretty(&output).unwrap_or_default()
-        );
-    } else {
-        let start_dt = Utc
-            .timestamp_opt(start_ts, 0)
-            .single()
-            .unwrap_or_else(Utc::now);
-        let end_dt = Utc
-            .timestamp_opt(end_ts, 0)
-            .single()
-            .unwrap_or_else(Utc::now);
-
-        println!("\n📅 Activity Timeline");
-        println!(
-            "   {} to {}",
-            start_dt.format("%Y-%m-%d %H:%M"),
-            end_dt.format("%Y-%m-%d %H:%M")
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
         );
-        println!("{}", "─".repeat(70));
+        return Ok(());
+    }
 
-        if sessions.is_empty() {
-            println!("\n   No sessions found in this time range.\n");
-            return Ok(());
-        }
+    let bound = |ts: Option<i64>| {
+        ts.and_then(local)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+    };
+    println!("\n📅 Activity Timeline");
+    println!(
+        "   {} to {}",
+        bound(time_filter.since).unwrap_or_else(|| "the beginning".to_string()),
+        bound(time_filter.until).unwrap_or_else(|| "now".to_string())
+    );
+    println!("{}", "─".repeat(70));
 
-        let mut current_group = String::new();
-        for (_id, agent, title, started, ended, _path, msg_count) in &sessions {
-            let dt = Utc
-                .timestamp_opt(*started, 0)
-                .single()
-                .unwrap_or_else(Utc::now);
+    if sessions.is_empty() {
+        println!("\n   No sessions found in this time range.\n");
+        return Ok(());
+    }
 
-            let group_key = match group_by {
-                TimelineGrouping::Hour => dt.format("%Y-%m-%d %H:00").to_string(),
-                TimelineGrouping::Day => dt.format("%Y-%m-%d (%A)").to_string(),
-                TimelineGrouping::None => String::new(),
+    for (key, members) in &groups {
+        if group_by != TimelineGrouping::None {
+            let heading = match group_by {
+                TimelineGrouping::Day => members
+                    .first()
+                    .and_then(|s| local(s["started_at"].as_i64()?))
+                    .map(|dt| dt.format("%Y-%m-%d (%A)").to_string())
+                    .unwrap_or_else(|| key.clone()),
+                _ => key.clone(),
             };
-
-            if group_key != current_group && group_by != TimelineGrouping::None {
-                println!("\n  📆 {}", group_key);
-                current_group = group_key;
-            }
-
-            let duration = ended.map(|e| {
-                let mins = (e - started) / 60;
-                if mins < 60 {
-                    format!("{}m", mins)
-                } else {
-                    format!("{}h{}m", mins / 60, mins % 60)
+            println!("\n  📆 {}", heading.bold());
+        }
+        for session in members {
+            let started = local(session["started_at"].as_i64().unwrap_or_default());
+            let ended = local(session["ended_at"].as_i64().unwrap_or_default());
+            let span = match (started, ended) {
+                (Some(s), Some(e)) if s.date_naive() == e.date_naive() => {
+                    format!("{}–{}", s.format("%H:%M"), e.format("%H:%M"))
                 }
-            });
-
-            let title_str = title.as_deref().unwrap_or("(untitled)");
-            let title_preview: String = title_str.chars().take(40).collect();
-
-            let agent_icon = match agent.as_str() {
-                "claude_code" => "🟣",
-                "codex" => "🟢",
-                "gemini" => "🔵",
-                "amp" => "🟡",
-                "cursor" => "⚪",
-                _ => "⚫",
+                (Some(s), Some(e)) => format!("{}–{}", s.format("%H:%M"), e.format("%m-%d %H:%M")),
+                _ => String::new(),
             };
-
+            let mins = session["duration_seconds"].as_i64().unwrap_or_default() / 60;
+            let duration = if mins < 60 {
+                format!("{mins}m")
+            } else {
+                format!("{}h{}m", mins / 60, mins % 60)
+            };
+            let title = session["title"].as_str().unwrap_or("(untitled)");
             println!(
-                "     {} {} {:>5} │ {:>3} msgs │ {}",
-                dt.format("%H:%M"),
-                agent_icon,
-                duration.as_deref().unwrap_or(""),
-                msg_count,
-                title_preview
+                "     {:<17} {:<12} {:>6} │ {:>4} msgs │ {}",
+                span,
+                session["agent"].as_str().unwrap_or_default().cyan(),
+                duration,
+                session["message_count"].as_i64().unwrap_or_default(),
+                title.chars().take(60).collect::<String>()
             );
-        }
-
-        println!("\n{}", "─".repeat(70));
-        println!("   Total: {} sessions\n", sessions.len());
-    }
-    Ok(())
-}
-
-fn parse_datetime_flexible(s: &str) -> Option<i64> {
-    use chrono::{Local, NaiveDate, TimeZone};
-
-    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
-        return Some(dt.timestamp());
-    }
-
-    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d")
-        && let Some(dt) = date.and_hms_opt(0, 0, 0)
-        && let Some(local) = Local.from_local_datetime(&dt).single()
-    {
-        return Some(local.timestamp());
-    }
-
-    let now = Local::now();
-    match s.to_lowercase().as_str() {
-        "today" => {
-            let start = now.date_naive().and_hms_opt(0, 0, 0)?;
-            Local
-                .from_local_datetime(&start)
-                .single()
-                .map(|d| d.timestamp())
-        }
-        "yesterday" => {
-            let yesterday = (now - chrono::Duration::days(1)).date_naive();
-            let start = yesterday.and_hms_opt(0, 0, 0)?;
-            Local
-                .from_local_datetime(&start)
-                .single()
-                .map(|d| d.timestamp())
-        }
-        _ => {
-            if let Some(days_str) = s.strip_suffix('d')
-                && let Ok(days) = days_str.parse::<i64>()
-            {
-                return Some((now - chrono::Duration::days(days)).timestamp());
-            }
-            if let Some(hours_str) = s.strip_suffix('h')
-                && let Ok(hours) = hours_str.parse::<i64>()
-            {
-                return Some((now - chrono::Duration::hours(hours)).timestamp());
+            if let Some(workspace) = session["workspace"].as_str() {
+                println!("     {:<17} {}", "", workspace.dimmed());
             }
-            None
         }
     }
+
+    println!("\n{}", "─".repeat(70));
+    println!("   Total: {} sessions\n", sessions.len());
+    Ok(())
 }
@@ -11,16 +11,7 @@ async fn main() -> anyhow::Result<()> {
                 eprintln!("{}", err.message);
             } else {
                 // Otherwise wrap structured error
-                let payload = serde_json::json!({
-                    "error": {
-                        "code": err.code,
-                        "kind": err.kind,
-                        "message": err.message,
-                        "hint": err.hint,
-                        "retryable": err.retryable,
-                    }
-                });
-                eprintln!("{payload}");
+                eprintln!("{}", err.robot_json());
             }
             std::process::exit(err.code);
         }
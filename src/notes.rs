@@ -0,0 +1,119 @@
+//! Free-text notes on a message or a whole conversation, added with `cass note add`.
+//!
+//! Notes are the user's own writing, so like tags they live in a sidecar, `notes.json` in
+//! the data dir, and survive `cass index --full`. Each index run re-adds them to the search
+//! index as documents of role [`NOTE_ROLE`] pointing at the annotated message, which makes
+//! them searchable alongside the conversations they describe.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+const FILE_NAME: &str = "notes.json";
+
+/// The role note documents carry in the search index and in search hits.
+pub const NOTE_ROLE: &str = "note";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Note {
+    pub id: u64,
+    pub source_path: String,
+    /// Index of the annotated message; `None` annotates the whole conversation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub msg_idx: Option<i64>,
+    pub text: String,
+    /// The conversation's agent, workspace, title and id when the note was added, so
+    /// its search document files under the same session.
+    pub agent: String,
+    #[serde(default)]
+    pub workspace: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    /// Unix ms when the note was added.
+    pub created_at: i64,
+}
+
+/// Where the notes live for `data_dir`.
+pub fn file_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(FILE_NAME)
+}
+
+/// Every note under `data_dir`, oldest first; a missing or unreadable file is empty.
+pub fn load(data_dir: &Path) -> Vec<Note> {
+    std::fs::read(file_path(data_dir))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// The notes on the session at `source_path`.
+pub fn for_session(data_dir: &Path, source_path: &str) -> Vec<Note> {
+    load(data_dir)
+        .into_iter()
+        .filter(|n| n.source_path == source_path)
+        .collect()
+}
+
+/// Store `note` under the next free id, returning it with that id.
+pub fn add(data_dir: &Path, mut note: Note) -> Result<Note> {
+    let mut notes = load(data_dir);
+    note.id = notes.iter().map(|n| n.id).max().unwrap_or(0) + 1;
+    notes.push(note.clone());
+    save(data_dir, &notes)?;
+    Ok(note)
+}
+
+/// Remove the note with `id`, returning it if there was one.
+pub fn remove(data_dir: &Path, id: u64) -> Result<Option<Note>> {
+    let mut notes = load(data_dir);
+    let Some(pos) = notes.iter().position(|n| n.id == id) else {
+        return Ok(None);
+    };
+    let removed = notes.remove(pos);
+    save(data_dir, &notes)?;
+    Ok(Some(removed))
+}
+
+fn save(data_dir: &Path, notes: &[Note]) -> Result<()> {
+    let path = file_path(data_dir);
+    std::fs::create_dir_all(data_dir)
+        .with_context(|| format!("creating {}", data_dir.display()))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(notes)?)
+        .with_context(|| format!("writing {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notes_get_the_next_id_and_list_by_session() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let note = |path: &str, msg_idx| Note {
+            id: 0,
+            source_path: path.into(),
+            msg_idx,
+            text: "this is the fix that worked".into(),
+            agent: "codex".into(),
+            workspace: String::new(),
+            title: String::new(),
+            external_id: None,
+            created_at: 0,
+        };
+
+        assert_eq!(add(dir.path(), note("/a.jsonl", Some(3))).unwrap().id, 1);
+        assert_eq!(add(dir.path(), note("/a.jsonl", None)).unwrap().id, 2);
+        assert_eq!(add(dir.path(), note("/b.jsonl", Some(0))).unwrap().id, 3);
+        assert_eq!(for_session(dir.path(), "/a.jsonl").len(), 2);
+
+        assert_eq!(remove(dir.path(), 1).unwrap().unwrap().msg_idx, Some(3));
+        assert!(remove(dir.path(), 1).unwrap().is_none());
+        assert_eq!(add(dir.path(), note("/b.jsonl", None)).unwrap().id, 4);
+        assert_eq!(load(dir.path()).len(), 3);
+    }
+}
@@ -0,0 +1,237 @@
+//! `cass://` permalinks that address a single message inside an indexed conversation.
+//!
+//! Format: `cass://<agent>/<conversation-id>#<msg_idx>`
+//!
+//! - `agent` is the connector slug (e.g. `claude_code`, `codex`).
+//! - `conversation-id` is the connector's external id, or the session file stem when the
+//!   connector does not expose one. Reserved characters are percent-encoded.
+//! - `msg_idx` is the zero-based message index; the fragment is optional and a link
+//!   without it addresses the conversation as a whole.
+
+use anyhow::{Result, anyhow, bail};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+
+pub const SCHEME: &str = "cass://";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Permalink {
+    pub agent: String,
+    pub conversation_id: String,
+    pub msg_idx: Option<i64>,
+}
+
+/// A permalink resolved against the local database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedPermalink {
+    pub source_path: String,
+    pub title: Option<String>,
+    pub msg_idx: Option<i64>,
+}
+
+impl Permalink {
+    pub fn new(agent: &str, conversation_id: &str, msg_idx: Option<i64>) -> Self {
+        Self {
+            agent: agent.to_string(),
+            conversation_id: conversation_id.to_string(),
+            msg_idx,
+        }
+    }
+
+    /// Build a permalink for a stored conversation, falling back to the file stem
+    /// when the connector did not record an external id.
+    pub fn for_conversation(
+        agent: &str,
+        external_id: Option<&str>,
+        source_path: &Path,
+        msg_idx: Option<i64>,
+    ) -> Self {
+        let conversation_id = external_id
+            .filter(|id| !id.trim().is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                source_path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            });
+        Self {
+            agent: agent.to_string(),
+            conversation_id,
+            msg_idx,
+        }
+    }
+
+    pub fn parse(url: &str) -> Result<Self> {
+        let rest = url
+            .trim()
+            .strip_prefix(SCHEME)
+            .ok_or_else(|| anyhow!("permalink must start with {SCHEME}"))?;
+        let (body, fragment) = match rest.split_once('#') {
+            Some((body, frag)) => (body, Some(frag)),
+            None => (rest, None),
+        };
+        let (agent, conversation_id) = body
+            .split_once('/')
+            .ok_or_else(|| anyhow!("permalink must look like {SCHEME}<agent>/<conversation-id>"))?;
+        if agent.is_empty() || conversation_id.is_empty() {
+            bail!("permalink is missing the agent or conversation id");
+        }
+        let msg_idx = match fragment.filter(|f| !f.is_empty()) {
+            Some(frag) => Some(
+                frag.parse::<i64>()
+                    .ok()
+                    .filter(|idx| *idx >= 0)
+                    .ok_or_else(|| anyhow!("invalid message index '{frag}'"))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            agent: percent_decode(agent)?,
+            conversation_id: percent_decode(conversation_id.trim_end_matches('/'))?,
+            msg_idx,
+        })
+    }
+
+    /// Look up the conversation this link points at.
+    ///
+    /// Matches on agent slug + external id first, then on the source file stem for
+    /// connectors that do not record external ids.
+    pub fn resolve(&self, conn: &Connection) -> Result<Option<ResolvedPermalink>> {
+        let by_external: Option<(String, Option<String>)> = conn
+            .query_row(
+                "SELECT c.source_path, c.title
+                 FROM conversations c
+                 JOIN agents a ON c.agent_id = a.id
+                 WHERE a.slug = ?1 AND c.external_id = ?2
+                 ORDER BY c.started_at DESC LIMIT 1",
+                [&self.agent, &self.conversation_id],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .optional()?;
+        if let Some((source_path, title)) = by_external {
+            return Ok(Some(ResolvedPermalink {
+                source_path,
+                title,
+                msg_idx: self.msg_idx,
+            }));
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT c.source_path, c.title
+             FROM conversations c
+             JOIN agents a ON c.agent_id = a.id
+             WHERE a.slug = ?1
+             ORDER BY c.started_at DESC",
+        )?;
+        let rows = stmt.query_map([&self.agent], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, Option<String>>(1)?))
+        })?;
+        for row in rows {
+            let (source_path, title) = row?;
+            let stem_matches = Path::new(&source_path)
+                .file_stem()
+                .is_some_and(|s| s.to_string_lossy() == self.conversation_id);
+            if stem_matches {
+                return Ok(Some(ResolvedPermalink {
+                    source_path,
+                    title,
+                    msg_idx: self.msg_idx,
+                }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl fmt::Display for Permalink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{SCHEME}{}/{}",
+            percent_encode(&self.agent),
+            percent_encode(&self.conversation_id)
+        )?;
+        if let Some(idx) = self.msg_idx {
+            write!(f, "#{idx}")?;
+        }
+        Ok(())
+    }
+}
+
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~' | b':' | b'@') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+fn percent_decode(s: &str) -> Result<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+                .ok_or_else(|| anyhow!("invalid percent-encoding in '{s}'"))?;
+            out.push(hex);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).map_err(|_| anyhow!("permalink is not valid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_with_message_index() {
+        let link = Permalink::new("claude_code", "abc-123", Some(7));
+        let uri = link.to_string();
+        assert_eq!(uri, "cass://claude_code/abc-123#7");
+        assert_eq!(Permalink::parse(&uri).unwrap(), link);
+    }
+
+    #[test]
+    fn encodes_reserved_characters_in_ids() {
+        let link = Permalink::new("aider", "my chat/#1?", None);
+        let uri = link.to_string();
+        assert_eq!(uri, "cass://aider/my%20chat%2F%231%3F");
+        assert_eq!(Permalink::parse(&uri).unwrap(), link);
+    }
+
+    #[test]
+    fn falls_back_to_file_stem_without_external_id() {
+        let link = Permalink::for_conversation(
+            "codex",
+            None,
+            Path::new("/home/u/.codex/sessions/rollout-1.jsonl"),
+            Some(0),
+        );
+        assert_eq!(link.conversation_id, "rollout-1");
+        assert_eq!(link.to_string(), "cass://codex/rollout-1#0");
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        assert!(Permalink::parse("https://example.com/x").is_err());
+        assert!(Permalink::parse("cass://codex").is_err());
+        assert!(Permalink::parse("cass://codex/#1").is_err());
+        assert!(Permalink::parse("cass://codex/abc#-1").is_err());
+        assert!(Permalink::parse("cass://codex/abc#x").is_err());
+        assert!(Permalink::parse("cass://codex/a%ZZ").is_err());
+    }
+}
@@ -0,0 +1,100 @@
+//! Conversations pinned in the TUI with `p`.
+//!
+//! Like session tags, pins belong to the user, so they live in `pins.json` in the data dir
+//! rather than in the database, which `cass index --full` rebuilds. A pin is keyed by the
+//! conversation's [`conversation_key`](crate::search::tantivy::conversation_key), which
+//! stays the same across rebuilds, and keeps enough of the conversation to be listed
+//! without running a search.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+const FILE_NAME: &str = "pins.json";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pin {
+    /// Conversation key, or the source path for hits indexed without one.
+    pub conversation: String,
+    pub title: String,
+    pub agent: String,
+    #[serde(default)]
+    pub workspace: String,
+    pub source_path: String,
+    /// When it was pinned (unix millis).
+    pub pinned_at: i64,
+}
+
+/// Where the pins live for `data_dir`.
+pub fn file_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(FILE_NAME)
+}
+
+/// Every pin under `data_dir`, most recently pinned first; a missing or unreadable file
+/// is empty.
+pub fn load(data_dir: &Path) -> Vec<Pin> {
+    std::fs::read(file_path(data_dir))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// Pin `pin`'s conversation, or unpin it when it is already pinned. Returns the pins
+/// afterwards and whether the conversation is now pinned.
+pub fn toggle(data_dir: &Path, pin: Pin) -> Result<(Vec<Pin>, bool)> {
+    let mut all = load(data_dir);
+    let before = all.len();
+    all.retain(|p| p.conversation != pin.conversation);
+    let pinned = all.len() == before;
+    if pinned {
+        all.insert(0, pin);
+    }
+    save(data_dir, &all)?;
+    Ok((all, pinned))
+}
+
+fn save(data_dir: &Path, all: &[Pin]) -> Result<()> {
+    let path = file_path(data_dir);
+    std::fs::create_dir_all(data_dir)
+        .with_context(|| format!("creating {}", data_dir.display()))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(all)?)
+        .with_context(|| format!("writing {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pin(conversation: &str, pinned_at: i64) -> Pin {
+        Pin {
+            conversation: conversation.into(),
+            title: format!("title {conversation}"),
+            agent: "codex".into(),
+            workspace: "/ws".into(),
+            source_path: format!("/sessions/{conversation}.jsonl"),
+            pinned_at,
+        }
+    }
+
+    #[test]
+    fn toggle_pins_newest_first_and_unpins() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let (_, pinned) = toggle(dir.path(), pin("codex:a", 1)).unwrap();
+        assert!(pinned);
+        toggle(dir.path(), pin("codex:b", 2)).unwrap();
+        let order: Vec<String> = load(dir.path())
+            .into_iter()
+            .map(|p| p.conversation)
+            .collect();
+        assert_eq!(order, ["codex:b", "codex:a"]);
+
+        let (left, pinned) = toggle(dir.path(), pin("codex:b", 3)).unwrap();
+        assert!(!pinned);
+        assert_eq!(left, vec![pin("codex:a", 1)]);
+        assert_eq!(load(dir.path()), left);
+    }
+}
@@ -0,0 +1,385 @@
+//! Line-oriented query loop (`cass repl`).
+//!
+//! Opens the index once and keeps the reader warm between queries, for quick lookups
+//! over SSH where the TUI is too much and a fresh `cass search` per query pays for the
+//! index open every time. A line is a query unless it starts with `:`, which sets a
+//! filter or acts on the last results (`:help` lists them). Line history of interactive
+//! sessions is kept in `repl_history.txt` in the data dir.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use colored::Colorize;
+use rustyline::error::ReadlineError;
+
+use crate::search::query::{SearchClient, SearchFilters, SearchHit};
+
+const HISTORY_FILE: &str = "repl_history.txt";
+
+/// Hits with fewer matches than this are widened with wildcards, as in `cass search`.
+const SPARSE_THRESHOLD: usize = 3;
+
+const HELP: &str = "\
+  <words>             search with the current filters
+  :agent [SLUG...]    only these agents (no slug clears)
+  :workspace [PATH...] only these workspaces
+  :since [TIME]       only messages after TIME (7d, yesterday, 2025-06-01)
+  :until [TIME]       only messages before TIME
+  :tag [TAG...]       only sessions tagged with any TAG
+  :limit N            hits per page
+  :more               next page of the last query
+  :show N             full text of hit N
+  :filters            show the current filters
+  :clear              drop every filter
+  :quit               leave (also Ctrl+D)";
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Query(String),
+    Agent(Vec<String>),
+    Workspace(Vec<String>),
+    Since(Option<String>),
+    Until(Option<String>),
+    Tag(Vec<String>),
+    Limit(usize),
+    More,
+    Show(usize),
+    Filters,
+    Clear,
+    Help,
+    Quit,
+}
+
+/// Read one input line; `None` for a blank one.
+fn parse(line: &str) -> Result<Option<Command>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let Some(rest) = line.strip_prefix(':') else {
+        return Ok(Some(Command::Query(line.to_string())));
+    };
+    let mut words = rest.split_whitespace();
+    let name = words.next().unwrap_or_default();
+    let args: Vec<String> = words.map(str::to_string).collect();
+    let number = |what: &str| -> Result<usize, String> {
+        match args.as_slice() {
+            [n] => n
+                .parse()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| format!(":{what} takes a positive number")),
+            _ => Err(format!(":{what} takes one number")),
+        }
+    };
+    let command = match name {
+        "agent" | "a" => Command::Agent(args),
+        "workspace" | "ws" => Command::Workspace(args),
+        "since" => Command::Since((!args.is_empty()).then(|| args.join(" "))),
+        "until" => Command::Until((!args.is_empty()).then(|| args.join(" "))),
+        "tag" => Command::Tag(args),
+        "limit" => Command::Limit(number("limit")?),
+        "more" | "m" | "next" => Command::More,
+        "show" | "s" => Command::Show(number("show")?),
+        "filters" | "f" => Command::Filters,
+        "clear" => Command::Clear,
+        "help" | "h" | "?" => Command::Help,
+        "quit" | "q" | "exit" => Command::Quit,
+        other => return Err(format!("unknown command :{other}; try :help")),
+    };
+    Ok(Some(command))
+}
+
+struct Repl {
+    client: SearchClient,
+    data_dir: PathBuf,
+    agents: Vec<String>,
+    workspaces: Vec<String>,
+    since: Option<(String, i64)>,
+    until: Option<(String, i64)>,
+    tags: Vec<String>,
+    limit: usize,
+    last_query: Option<String>,
+    offset: usize,
+    hits: Vec<SearchHit>,
+}
+
+/// Run the loop on `client` until `:quit` or end of input.
+pub fn run(client: SearchClient, data_dir: &Path, limit: usize) -> Result<()> {
+    let mut editor = rustyline::DefaultEditor::new()?;
+    let history = data_dir.join(HISTORY_FILE);
+    let _ = editor.load_history(&history);
+    let mut repl = Repl {
+        client,
+        data_dir: data_dir.to_path_buf(),
+        agents: Vec::new(),
+        workspaces: Vec::new(),
+        since: None,
+        until: None,
+        tags: Vec::new(),
+        limit,
+        last_query: None,
+        offset: 0,
+        hits: Vec::new(),
+    };
+    eprintln!("{}", "cass repl: type a query, :help for commands".dimmed());
+
+    loop {
+        let line = match editor.readline(&repl.prompt()) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let command = match parse(&line) {
+            Ok(Some(command)) => command,
+            Ok(None) => continue,
+            Err(message) => {
+                eprintln!("{}", message.red());
+                continue;
+            }
+        };
+        let _ = editor.add_history_entry(line.trim());
+        if command == Command::Quit {
+            break;
+        }
+        if let Err(message) = repl.execute(command) {
+            eprintln!("{}", message.red());
+        }
+    }
+    // Piped input (scripts, tests) is not worth recalling.
+    if std::io::IsTerminal::is_terminal(&std::io::stdin())
+        && std::fs::create_dir_all(data_dir).is_ok()
+    {
+        let _ = editor.save_history(&history);
+    }
+    Ok(())
+}
+
+impl Repl {
+    fn prompt(&self) -> String {
+        let filters = self.describe_filters();
+        if filters.is_empty() {
+            "cass> ".to_string()
+        } else {
+            format!("cass {}> ", filters.join(" "))
+        }
+    }
+
+    fn describe_filters(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        if !self.agents.is_empty() {
+            out.push(format!("agent={}", self.agents.join(",")));
+        }
+        if !self.workspaces.is_empty() {
+            out.push(format!("workspace={}", self.workspaces.join(",")));
+        }
+        if let Some((text, _)) = &self.since {
+            out.push(format!("since={text}"));
+        }
+        if let Some((text, _)) = &self.until {
+            out.push(format!("until={text}"));
+        }
+        if !self.tags.is_empty() {
+            out.push(format!("tag={}", self.tags.join(",")));
+        }
+        out
+    }
+
+    fn execute(&mut self, command: Command) -> Result<(), String> {
+        let time = |text: Option<String>| -> Result<Option<(String, i64)>, String> {
+            text.map(|t| {
+                crate::ui::time_parser::parse_time_input(&t)
+                    .map(|ms| (t.clone(), ms))
+                    .ok_or_else(|| format!("cannot read '{t}' as a time"))
+            })
+            .transpose()
+        };
+        match command {
+            Command::Query(query) => {
+                self.last_query = Some(query);
+                self.offset = 0;
+                return self.search();
+            }
+            Command::More => {
+                if self.last_query.is_none() {
+                    return Err("no query yet".to_string());
+                }
+                self.offset += self.limit;
+                return self.search();
+            }
+            Command::Show(n) => {
+                let hit = n
+                    .checked_sub(self.offset + 1)
+                    .and_then(|i| self.hits.get(i))
+                    .ok_or_else(|| format!("no hit {n} on this page"))?;
+                println!("{}", header(n, hit));
+                println!("{}", hit.content);
+                println!("{}", location(hit).dimmed());
+                return Ok(());
+            }
+            Command::Agent(agents) => self.agents = agents,
+            Command::Workspace(workspaces) => self.workspaces = workspaces,
+            Command::Since(text) => self.since = time(text)?,
+            Command::Until(text) => self.until = time(text)?,
+            Command::Tag(tags) => self.tags = tags,
+            Command::Limit(limit) => {
+                self.limit = limit;
+                println!("{}", format!("{limit} hits per page").dimmed());
+                return Ok(());
+            }
+            Command::Clear => {
+                self.agents.clear();
+                self.workspaces.clear();
+                self.since = None;
+                self.until = None;
+                self.tags.clear();
+            }
+            Command::Help => {
+                println!("{HELP}");
+                return Ok(());
+            }
+            Command::Filters | Command::Quit => {}
+        }
+        let filters = self.describe_filters();
+        if filters.is_empty() {
+            println!("{}", "no filters".dimmed());
+        } else {
+            println!("{}", filters.join("  ").dimmed());
+        }
+        Ok(())
+    }
+
+    fn search(&mut self) -> Result<(), String> {
+        let query = self.last_query.clone().unwrap_or_default();
+        let filters = SearchFilters {
+            agents: self.agents.iter().cloned().collect(),
+            workspaces: self.workspaces.iter().cloned().collect(),
+            created_from: self.since.as_ref().map(|(_, ms)| *ms),
+            created_to: self.until.as_ref().map(|(_, ms)| *ms),
+            source_paths: (!self.tags.is_empty())
+                .then(|| crate::session_tags::paths_with_any(&self.data_dir, &self.tags)),
+            ..SearchFilters::default()
+        };
+        // Like `cass search`, cut every page from one list fetched from the top: hits are
+        // de-duplicated after retrieval, so the backend's own offset can repeat or skip.
+        let result = self
+            .client
+            .search_with_fallback(
+                &query,
+                filters,
+                self.offset + self.limit,
+                0,
+                SPARSE_THRESHOLD,
+            )
+            .map_err(|e| format!("search failed: {e:#}"))?;
+        let page: Vec<SearchHit> = result.hits.into_iter().skip(self.offset).collect();
+        if page.is_empty() && self.offset > 0 {
+            // Stay on the last page, so `:show` still refers to it.
+            self.offset -= self.limit;
+            println!("{}", "no more hits".dimmed());
+            return Ok(());
+        }
+        self.hits = page;
+        if self.hits.is_empty() {
+            println!("{}", "no hits".dimmed());
+            if let Some(suggestion) = result.suggestions.first() {
+                println!("{}", suggestion.message.dimmed());
+            }
+            return Ok(());
+        }
+        let width = crossterm::terminal::size()
+            .map(|(w, _)| w as usize)
+            .unwrap_or(100);
+        for (i, hit) in self.hits.iter().enumerate() {
+            let snippet: Vec<&str> = hit.snippet.split_whitespace().collect();
+            println!("{}", header(self.offset + i + 1, hit));
+            let snippet = crate::truncate_end(&snippet.join(" "), width.saturating_sub(4).max(20));
+            println!("    {}", emphasize(&snippet));
+            println!("    {}", location(hit).dimmed());
+        }
+        let shown = self.offset + self.hits.len();
+        let total = result
+            .total_hits
+            .map(|n| format!(" of ~{n}"))
+            .unwrap_or_default();
+        println!(
+            "{}",
+            format!("hits {}-{shown}{total} · :more, :show N", self.offset + 1).dimmed()
+        );
+        Ok(())
+    }
+}
+
+fn header(n: usize, hit: &SearchHit) -> String {
+    let when = hit
+        .created_at
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default();
+    let title = if hit.title.is_empty() {
+        "(untitled)"
+    } else {
+        hit.title.as_str()
+    };
+    format!(
+        "{:>3}. {} {}  {}",
+        n,
+        format!("[{}]", hit.agent).cyan(),
+        title.bold(),
+        when.dimmed()
+    )
+}
+
+/// Render the `**match**` marks of a snippet as bold text.
+fn emphasize(snippet: &str) -> String {
+    snippet
+        .split("**")
+        .enumerate()
+        .map(|(i, part)| {
+            if i % 2 == 1 {
+                part.yellow().bold().to_string()
+            } else {
+                part.to_string()
+            }
+        })
+        .collect()
+}
+
+fn location(hit: &SearchHit) -> String {
+    match hit.line_number {
+        Some(line) => format!("{}:{line}", hit.source_path),
+        None => hit.source_path.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_are_queries_unless_they_start_with_a_colon() {
+        assert_eq!(parse("  ").unwrap(), None);
+        assert_eq!(
+            parse("auth error").unwrap(),
+            Some(Command::Query("auth error".into()))
+        );
+        assert_eq!(
+            parse(":agent codex amp").unwrap(),
+            Some(Command::Agent(vec!["codex".into(), "amp".into()]))
+        );
+        assert_eq!(parse(":agent").unwrap(), Some(Command::Agent(Vec::new())));
+        assert_eq!(
+            parse(":since last monday").unwrap(),
+            Some(Command::Since(Some("last monday".into())))
+        );
+        assert_eq!(parse(":show 2").unwrap(), Some(Command::Show(2)));
+        assert!(parse(":show 0").is_err());
+        assert!(parse(":limit").is_err());
+        assert!(parse(":frobnicate").is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! The agent's own command for continuing an indexed session (`cass resume`).
+//!
+//! Each supported agent resumes a session by id through a flag or subcommand of its CLI;
+//! the id comes from what the connector stored, the external id or the session file
+//! name. Agents that keep sessions per project are resumed from the session's workspace,
+//! since they look the id up relative to the directory they start in.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+/// Agents `cass resume` has a command for.
+pub const SUPPORTED: &[&str] = &[
+    "claude_code",
+    "codex",
+    "gemini",
+    "amp",
+    "copilot_cli",
+    "opencode",
+    "aider",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResumeCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Directory to run it from, when the agent resolves sessions per project.
+    pub cwd: Option<PathBuf>,
+}
+
+impl ResumeCommand {
+    fn new(program: &str, args: &[&str], cwd: Option<&Path>) -> Self {
+        Self {
+            program: program.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            cwd: cwd.filter(|dir| dir.is_dir()).map(Path::to_path_buf),
+        }
+    }
+
+    /// The command as one line for a POSIX shell, with a `cd` first when it has a `cwd`.
+    pub fn shell_line(&self) -> String {
+        let command = std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .map(shell_quote)
+            .collect::<Vec<_>>()
+            .join(" ");
+        match &self.cwd {
+            Some(dir) => format!("cd {} && {command}", shell_quote(&dir.to_string_lossy())),
+            None => command,
+        }
+    }
+}
+
+/// The resume command for `agent`'s session at `source_path`, or `None` when the agent
+/// has none or the session id cannot be told.
+pub fn command_for(
+    agent: &str,
+    external_id: Option<&str>,
+    source_path: &Path,
+    workspace: Option<&Path>,
+) -> Option<ResumeCommand> {
+    let plain = crate::connectors::uncompressed_path(source_path);
+    let stem = plain.file_stem().and_then(|s| s.to_str());
+    let external_id = external_id.filter(|id| !id.trim().is_empty());
+    match agent {
+        // `<project dir>/<session uuid>.jsonl`
+        "claude_code" => Some(ResumeCommand::new(
+            "claude",
+            &["--resume", stem?],
+            workspace,
+        )),
+        // `rollout-<timestamp>-<session uuid>.jsonl`
+        "codex" => Some(ResumeCommand::new(
+            "codex",
+            &["resume", trailing_uuid(stem?)?],
+            workspace,
+        )),
+        // `chats/session-*.json` carries a `sessionId`; checkpoints saved with `/chat save`
+        // are only resumable from inside gemini.
+        "gemini" if !stem?.starts_with("checkpoint-") => Some(ResumeCommand::new(
+            "gemini",
+            &["--resume", external_id?],
+            workspace,
+        )),
+        "amp" => Some(ResumeCommand::new(
+            "amp",
+            &["threads", "continue", external_id.or(stem)?],
+            None,
+        )),
+        "copilot_cli" => Some(ResumeCommand::new(
+            "copilot",
+            &["--resume", external_id.or(stem)?],
+            workspace,
+        )),
+        // `session-<id>-<hash of the database path>`
+        "opencode" => {
+            let (id, _) = external_id?.strip_prefix("session-")?.rsplit_once('-')?;
+            Some(ResumeCommand::new(
+                "opencode",
+                &["--session", id],
+                workspace,
+            ))
+        }
+        // The chat history file sits in the repository aider ran in.
+        "aider" => Some(ResumeCommand::new(
+            "aider",
+            &["--restore-chat-history"],
+            plain.parent(),
+        )),
+        _ => None,
+    }
+}
+
+/// The UUID that ends `stem`, if any.
+fn trailing_uuid(stem: &str) -> Option<&str> {
+    let uuid = stem.get(stem.len().checked_sub(36)?..)?;
+    let shaped = uuid.char_indices().all(|(i, c)| match i {
+        8 | 13 | 18 | 23 => c == '-',
+        _ => c.is_ascii_hexdigit(),
+    });
+    shaped.then_some(uuid)
+}
+
+fn shell_quote(s: &str) -> String {
+    let plain = !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+    if plain {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_ids_come_from_the_stored_session() {
+        let claude = command_for(
+            "claude_code",
+            Some("0b6c.jsonl"),
+            Path::new("/home/u/.claude/projects/-src-app/0b6c.jsonl"),
+            Some(Path::new("/nonexistent/app dir")),
+        )
+        .unwrap();
+        assert_eq!(claude.shell_line(), "claude --resume 0b6c");
+
+        let codex = command_for(
+            "codex",
+            Some("2025/01/02/rollout-2025-01-02T10-00-00-5973b6c0-94b8-487b-a530-2aeb6098ae0e"),
+            Path::new("/c/sessions/2025/01/02/rollout-2025-01-02T10-00-00-5973b6c0-94b8-487b-a530-2aeb6098ae0e.jsonl.gz"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            codex.args,
+            ["resume", "5973b6c0-94b8-487b-a530-2aeb6098ae0e"]
+        );
+        assert!(command_for("codex", None, Path::new("/c/rollout-1.jsonl"), None).is_none());
+
+        let opencode = command_for(
+            "opencode",
+            Some("session-ses_abc-1f2e"),
+            Path::new("/o/opencode.db"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(opencode.args, ["--session", "ses_abc"]);
+
+        let gemini = command_for(
+            "gemini",
+            Some("9f1c2d3e"),
+            Path::new("/g/tmp/abc/chats/session-2025-01-02T10-00-9f1c.json"),
+            None,
+        )
+        .unwrap();
+        assert_eq!(gemini.shell_line(), "gemini --resume 9f1c2d3e");
+        assert!(
+            command_for(
+                "gemini",
+                Some("checkpoint-wip"),
+                Path::new("/g/tmp/abc/checkpoint-wip.json"),
+                None
+            )
+            .is_none()
+        );
+        assert!(command_for("cline", Some("x"), Path::new("/c/x.json"), None).is_none());
+    }
+
+    #[test]
+    fn shell_lines_quote_what_the_shell_would_split() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let ws = dir.path().join("it's here");
+        std::fs::create_dir_all(&ws).unwrap();
+        let cmd = ResumeCommand::new("claude", &["--resume", "abc"], Some(&ws));
+        assert_eq!(
+            cmd.shell_line(),
+            format!(
+                "cd '{}/it'\\''s here' && claude --resume abc",
+                dir.path().display()
+            )
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Named searches saved with `cass search --save <name>`.
+//!
+//! An entry keeps the query and the filters as they were typed, so a relative time such
+//! as `--since 7d` is resolved again each time the search runs, whether from
+//! `cass search --saved <name>` or from the TUI's command palette. The list lives in
+//! `saved_searches.json` in the data dir, next to `tui_state.json`.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+const FILE_NAME: &str = "saved_searches.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub today: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub yesterday: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub week: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    /// `--tag` filters.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Saved with `--bookmarked`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bookmarked: bool,
+    /// Saved with `--fuzzy`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fuzzy: bool,
+    /// Unix ms when the search was last saved.
+    #[serde(default)]
+    pub saved_at: i64,
+}
+
+impl SavedSearch {
+    /// The search's time window, resolved against the current time.
+    pub fn time_filter(&self) -> crate::CliResult<crate::TimeFilter> {
+        crate::TimeFilter::new(
+            self.days,
+            self.today,
+            self.yesterday,
+            self.week,
+            self.since.as_deref(),
+            self.until.as_deref(),
+        )
+    }
+}
+
+/// Where the list lives for `data_dir`.
+pub fn file_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(FILE_NAME)
+}
+
+/// Every saved search under `data_dir`, by name; a missing or unreadable file is empty.
+pub fn load(data_dir: &Path) -> Vec<SavedSearch> {
+    std::fs::read(file_path(data_dir))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// The saved search called `name`.
+pub fn get(data_dir: &Path, name: &str) -> Option<SavedSearch> {
+    load(data_dir).into_iter().find(|s| s.name == name)
+}
+
+/// Save `search`, replacing any saved under the same name.
+pub fn save(data_dir: &Path, mut search: SavedSearch) -> Result<()> {
+    search.saved_at = chrono::Utc::now().timestamp_millis();
+    let mut searches = load(data_dir);
+    searches.retain(|s| s.name != search.name);
+    searches.push(search);
+    searches.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let path = file_path(data_dir);
+    std::fs::create_dir_all(data_dir)
+        .with_context(|| format!("creating {}", data_dir.display()))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(&searches)?)
+        .with_context(|| format!("writing {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saving_a_name_again_replaces_it() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(load(dir.path()).is_empty());
+
+        let nightly = SavedSearch {
+            name: "nightly-errors".into(),
+            query: "error".into(),
+            agents: vec!["codex".into()],
+            since: Some("1d".into()),
+            ..SavedSearch::default()
+        };
+        save(dir.path(), nightly.clone()).unwrap();
+        save(
+            dir.path(),
+            SavedSearch {
+                name: "auth".into(),
+                query: "oauth token".into(),
+                ..SavedSearch::default()
+            },
+        )
+        .unwrap();
+        save(
+            dir.path(),
+            SavedSearch {
+                query: "panic".into(),
+                ..nightly
+            },
+        )
+        .unwrap();
+
+        let names: Vec<_> = load(dir.path()).into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["auth", "nightly-errors"]);
+        let saved = get(dir.path(), "nightly-errors").unwrap();
+        assert_eq!(saved.query, "panic");
+        assert_eq!(saved.agents, ["codex"]);
+        assert!(saved.time_filter().unwrap().since.is_some());
+        assert!(get(dir.path(), "missing").is_none());
+    }
+}
@@ -0,0 +1,709 @@
+//! Semantic search over message embeddings.
+//!
+//! With `[embeddings]` configured, `cass index` embeds every message the database
+//! holds that has no vector yet and stores the vectors in `vectors.db` beside the
+//! Tantivy index. `cass search --semantic` embeds the query with the same model and
+//! ranks messages by cosine similarity. The scan is brute force: a personal history
+//! of a few hundred thousand messages is scored in well under a second, and it keeps
+//! every filter exact.
+//!
+//! The database stays the source of truth. Each sync drops vectors of messages that
+//! were purged, evicted or re-ingested, then embeds what is missing. Vectors are
+//! cached by a hash of the embedded text, so a session file that is rewritten only
+//! costs requests for its new messages. Switching models discards every vector.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde_json::Value;
+
+use crate::config::EmbeddingsConfig;
+use crate::search::query::{CacheStats, MatchType, SearchFilters, SearchHit, SearchResult};
+
+const FILE_NAME: &str = "vectors.db";
+
+/// Longest prefix of a message that is embedded; most models truncate far earlier.
+const MAX_EMBED_CHARS: usize = 2000;
+
+const SCHEMA: &str = r"
+CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS vectors (
+    content_hash TEXT PRIMARY KEY,
+    vector BLOB NOT NULL
+);
+CREATE TABLE IF NOT EXISTS message_vectors (
+    message_id INTEGER PRIMARY KEY,
+    content_hash TEXT NOT NULL
+);
+";
+
+/// Turns text into vectors. Vectors from different [`model_id`](Self::model_id)s are
+/// never compared.
+pub trait Embedder {
+    fn model_id(&self) -> String;
+    fn embed(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// An OpenAI-compatible `/v1/embeddings` endpoint, or Ollama's `/api/embed`.
+pub struct EndpointEmbedder {
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl EndpointEmbedder {
+    pub fn new(url: &str, model: &str, api_key: Option<String>) -> Self {
+        Self {
+            url: url.to_string(),
+            model: model.to_string(),
+            api_key,
+        }
+    }
+
+    fn request(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()?;
+        let mut request = client
+            .post(&self.url)
+            .json(&serde_json::json!({ "model": self.model, "input": texts }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request
+            .send()
+            .with_context(|| format!("requesting embeddings from {}", self.url))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            bail!("embeddings endpoint {} returned {status}: {body}", self.url);
+        }
+        let body: Value = response
+            .json()
+            .with_context(|| format!("parsing embeddings from {}", self.url))?;
+        let vectors = parse_embeddings(&body)?;
+        if vectors.len() != texts.len() {
+            bail!(
+                "embeddings endpoint {} returned {} vectors for {} inputs",
+                self.url,
+                vectors.len(),
+                texts.len()
+            );
+        }
+        Ok(vectors)
+    }
+}
+
+impl Embedder for EndpointEmbedder {
+    fn model_id(&self) -> String {
+        format!("{}@{}", self.model, self.url)
+    }
+
+    fn embed(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        // reqwest's blocking client panics on a tokio runtime thread, which is where
+        // the CLI runs, so each request gets a thread of its own.
+        std::thread::scope(|s| s.spawn(|| self.request(texts)).join())
+            .map_err(|_| anyhow!("embeddings request to {} panicked", self.url))?
+    }
+}
+
+/// Vectors from an OpenAI-style `{"data": [{"embedding", "index"}]}` body or an
+/// Ollama-style `{"embeddings": [[...]]}` one, in input order.
+fn parse_embeddings(body: &Value) -> Result<Vec<Vec<f32>>> {
+    let to_vec = |v: &Value| -> Option<Vec<f32>> {
+        v.as_array()?
+            .iter()
+            .map(|x| x.as_f64().map(|f| f as f32))
+            .collect()
+    };
+    if let Some(data) = body.get("data").and_then(Value::as_array) {
+        let mut items: Vec<(u64, Vec<f32>)> = data
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let index = item
+                    .get("index")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(i as u64);
+                item.get("embedding")
+                    .and_then(to_vec)
+                    .map(|v| (index, v))
+                    .ok_or_else(|| anyhow!("embedding {i} is not a list of numbers"))
+            })
+            .collect::<Result<_>>()?;
+        items.sort_by_key(|(index, _)| *index);
+        return Ok(items.into_iter().map(|(_, v)| v).collect());
+    }
+    if let Some(embeddings) = body.get("embeddings").and_then(Value::as_array) {
+        return embeddings
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                to_vec(v).ok_or_else(|| anyhow!("embedding {i} is not a list of numbers"))
+            })
+            .collect();
+    }
+    bail!("response has neither `data` nor `embeddings`")
+}
+
+#[cfg(feature = "local-embeddings")]
+struct LocalEmbedder {
+    model: fastembed::TextEmbedding,
+    name: String,
+}
+
+#[cfg(feature = "local-embeddings")]
+impl Embedder for LocalEmbedder {
+    fn model_id(&self) -> String {
+        format!("local:{}", self.name)
+    }
+
+    fn embed(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.model.embed(texts, None)
+    }
+}
+
+#[cfg(feature = "local-embeddings")]
+fn local_embedder(cfg: &EmbeddingsConfig, data_dir: &Path) -> Result<Box<dyn Embedder>> {
+    let name = cfg.model.as_deref().unwrap_or("AllMiniLML6V2");
+    let model: fastembed::EmbeddingModel = name.parse().map_err(|e: String| anyhow!(e))?;
+    let options = fastembed::TextInitOptions::new(model)
+        .with_cache_dir(data_dir.join("models"))
+        .with_show_download_progress(false);
+    Ok(Box::new(LocalEmbedder {
+        model: fastembed::TextEmbedding::try_new(options)?,
+        name: name.to_string(),
+    }))
+}
+
+#[cfg(not(feature = "local-embeddings"))]
+fn local_embedder(_cfg: &EmbeddingsConfig, _data_dir: &Path) -> Result<Box<dyn Embedder>> {
+    bail!("`[embeddings] local = true` needs cass built with the `local-embeddings` feature")
+}
+
+/// The embedder `[embeddings]` describes; local models are cached under `data_dir`.
+pub fn embedder(cfg: &EmbeddingsConfig, data_dir: &Path) -> Result<Box<dyn Embedder>> {
+    if cfg.local {
+        return local_embedder(cfg, data_dir);
+    }
+    let Some(url) = &cfg.endpoint else {
+        bail!("[embeddings] needs an `endpoint`, or `local = true`");
+    };
+    let model = cfg
+        .model
+        .as_deref()
+        .ok_or_else(|| anyhow!("[embeddings] needs a `model` to send to {url}"))?;
+    let api_key = match &cfg.api_key_env {
+        Some(var) => Some(
+            std::env::var(var)
+                .with_context(|| format!("reading embeddings API key from ${var}"))?,
+        ),
+        None => None,
+    };
+    Ok(Box::new(EndpointEmbedder::new(url, model, api_key)))
+}
+
+/// Where the vector store lives for `data_dir`.
+pub fn store_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(FILE_NAME)
+}
+
+/// Message vectors, keyed by the id of the message in the main database, which is
+/// attached as `cass`.
+pub struct VectorStore {
+    conn: Connection,
+}
+
+impl VectorStore {
+    /// Open (creating if needed) the store for `data_dir`, reading messages from `db_path`.
+    pub fn open(data_dir: &Path, db_path: &Path) -> Result<Self> {
+        let path = store_path(data_dir);
+        let conn = Connection::open(&path)
+            .with_context(|| format!("opening vector store {}", path.display()))?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.execute_batch(SCHEMA)?;
+        conn.execute("ATTACH DATABASE ?1 AS cass", [db_path.to_string_lossy()])
+            .with_context(|| format!("attaching {}", db_path.display()))?;
+        Ok(Self { conn })
+    }
+
+    /// Messages with a vector.
+    pub fn len(&self) -> Result<usize> {
+        let n: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM message_vectors", [], |r| r.get(0))?;
+        Ok(n as usize)
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    fn model(&self) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row("SELECT value FROM meta WHERE key = 'model'", [], |r| {
+                r.get(0)
+            })
+            .optional()?)
+    }
+
+    /// Bring the store in line with the database: forget vectors of messages that are
+    /// gone, then embed the missing ones `batch_size` at a time. Each batch is written
+    /// as it completes, so an interrupted sync resumes where it stopped. Returns how
+    /// many messages gained a vector.
+    pub fn sync(&mut self, embedder: &mut dyn Embedder, batch_size: usize) -> Result<usize> {
+        let model = embedder.model_id();
+        if self.model()?.as_deref() != Some(model.as_str()) {
+            self.conn.execute_batch(
+                "DELETE FROM message_vectors; DELETE FROM vectors; DELETE FROM meta;",
+            )?;
+            self.conn.execute(
+                "INSERT INTO meta (key, value) VALUES ('model', ?1)",
+                [&model],
+            )?;
+        }
+        self.conn.execute(
+            "DELETE FROM message_vectors WHERE message_id NOT IN (SELECT id FROM cass.messages)",
+            [],
+        )?;
+
+        let mut embedded = 0;
+        loop {
+            let pending: Vec<(i64, String)> = {
+                let mut stmt = self.conn.prepare(
+                    "SELECT m.id, m.content FROM cass.messages m
+                     WHERE m.id NOT IN (SELECT message_id FROM message_vectors)
+                       AND trim(m.content) != ''
+                     ORDER BY m.id LIMIT ?1",
+                )?;
+                stmt.query_map([batch_size.max(1) as i64], |r| {
+                    Ok((r.get(0)?, embed_text(&r.get::<_, String>(1)?)))
+                })?
+                .collect::<rusqlite::Result<_>>()?
+            };
+            if pending.is_empty() {
+                break;
+            }
+
+            let hashes: Vec<String> = pending.iter().map(|(_, text)| content_hash(text)).collect();
+            let mut missing: Vec<(String, String)> = Vec::new();
+            let mut seen = HashSet::new();
+            for ((_, text), hash) in pending.iter().zip(&hashes) {
+                let cached = self
+                    .conn
+                    .query_row(
+                        "SELECT 1 FROM vectors WHERE content_hash = ?1",
+                        [hash],
+                        |_| Ok(()),
+                    )
+                    .optional()?
+                    .is_some();
+                if !cached && seen.insert(hash.clone()) {
+                    missing.push((hash.clone(), text.clone()));
+                }
+            }
+            let vectors = if missing.is_empty() {
+                Vec::new()
+            } else {
+                let texts: Vec<String> = missing.iter().map(|(_, t)| t.clone()).collect();
+                embedder.embed(&texts)?
+            };
+
+            let tx = self.conn.transaction()?;
+            for ((hash, _), vector) in missing.iter().zip(vectors) {
+                tx.execute(
+                    "INSERT OR REPLACE INTO vectors (content_hash, vector) VALUES (?1, ?2)",
+                    params![hash, encode(&normalized(vector))],
+                )?;
+            }
+            for ((id, _), hash) in pending.iter().zip(&hashes) {
+                tx.execute(
+                    "INSERT OR REPLACE INTO message_vectors (message_id, content_hash) VALUES (?1, ?2)",
+                    params![id, hash],
+                )?;
+            }
+            tx.commit()?;
+            embedded += pending.len();
+            tracing::debug!(embedded, "embedded messages");
+        }
+
+        self.conn.execute(
+            "DELETE FROM vectors WHERE content_hash NOT IN (SELECT content_hash FROM message_vectors)",
+            [],
+        )?;
+        Ok(embedded)
+    }
+
+    /// The `limit` messages after `offset` closest to `query`, by cosine similarity,
+    /// and how many messages passed the filters.
+    pub fn search(
+        &self,
+        query: &[f32],
+        filters: &SearchFilters,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<SearchHit>, usize)> {
+        let query = normalized(query.to_vec());
+        let mut sql = String::from(
+            "SELECT mv.message_id, v.vector
+             FROM message_vectors mv
+             JOIN vectors v ON v.content_hash = mv.content_hash
+             JOIN cass.messages m ON m.id = mv.message_id
+             JOIN cass.conversations c ON c.id = m.conversation_id
+             JOIN cass.agents a ON a.id = c.agent_id
+             LEFT JOIN cass.workspaces w ON w.id = c.workspace_id
+             WHERE 1 = 1",
+        );
+        let mut args: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        let mut push_in = |sql: &mut String, column: &str, values: Vec<String>| {
+            if values.is_empty() {
+                return;
+            }
+            let placeholders = vec!["?"; values.len()].join(",");
+            sql.push_str(&format!(" AND {column} IN ({placeholders})"));
+            for v in values {
+                args.push(Box::new(v));
+            }
+        };
+        push_in(&mut sql, "a.slug", filters.agents.iter().cloned().collect());
+        push_in(
+            &mut sql,
+            "w.path",
+            filters.workspaces.iter().cloned().collect(),
+        );
+        push_in(
+            &mut sql,
+            "m.role",
+            filters
+                .roles
+                .iter()
+                .map(|r| crate::search::tantivy::role_term(r).to_string())
+                .collect(),
+        );
+        if let Some(paths) = &filters.source_paths {
+            if paths.is_empty() {
+                sql.push_str(" AND 0");
+            }
+            push_in(&mut sql, "c.source_path", paths.iter().cloned().collect());
+        }
+        if let Some(from) = filters.created_from {
+            sql.push_str(" AND m.created_at >= ?");
+            args.push(Box::new(from));
+        }
+        if let Some(to) = filters.created_to {
+            sql.push_str(" AND m.created_at <= ?");
+            args.push(Box::new(to));
+        }
+
+        let mut scored: Vec<(f32, i64)> = Vec::new();
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(rusqlite::params_from_iter(args.iter().map(|b| &**b)))?;
+        while let Some(row) = rows.next()? {
+            let vector = decode(&row.get::<_, Vec<u8>>(1)?);
+            if vector.len() != query.len() {
+                continue;
+            }
+            let score = vector.iter().zip(&query).map(|(a, b)| a * b).sum();
+            scored.push((score, row.get(0)?));
+        }
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let total = scored.len();
+        let mut hits = Vec::new();
+        for (score, id) in scored.into_iter().skip(offset).take(limit) {
+            hits.push(self.hit(id, score)?);
+        }
+        Ok((hits, total))
+    }
+
+    fn hit(&self, message_id: i64, score: f32) -> Result<SearchHit> {
+        Ok(self.conn.query_row(
+            "SELECT m.content, m.idx, m.role, m.created_at, c.title, c.source_path,
+                    c.external_id, a.slug, w.path
+             FROM cass.messages m
+             JOIN cass.conversations c ON c.id = m.conversation_id
+             JOIN cass.agents a ON a.id = c.agent_id
+             LEFT JOIN cass.workspaces w ON w.id = c.workspace_id
+             WHERE m.id = ?1",
+            [message_id],
+            |r| {
+                let content: String = r.get(0)?;
+                let idx: i64 = r.get(1)?;
+                let source_path: String = r.get(5)?;
+                let external_id: Option<String> = r.get(6)?;
+                let agent: String = r.get(7)?;
+                Ok(SearchHit {
+                    title: r.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                    snippet: content.chars().take(200).collect(),
+                    content,
+                    score,
+                    conversation_id: crate::search::tantivy::conversation_key(
+                        &agent,
+                        external_id.as_deref(),
+                        &source_path,
+                    ),
+                    source_path,
+                    agent,
+                    workspace: r.get::<_, Option<String>>(8)?.unwrap_or_default(),
+                    created_at: r.get(3)?,
+                    line_number: Some((idx + 1) as usize),
+                    match_type: MatchType::Semantic,
+                    snippets: Vec::new(),
+                    role: r.get(2)?,
+                })
+            },
+        )?)
+    }
+}
+
+/// Embed what `[embeddings]` has not covered yet. Returns `None` when it is not
+/// configured.
+pub fn sync_configured(data_dir: &Path, db_path: &Path) -> Result<Option<usize>> {
+    let Some(cfg) = crate::config::embeddings() else {
+        return Ok(None);
+    };
+    let mut embedder = embedder(cfg, data_dir)?;
+    let mut store = VectorStore::open(data_dir, db_path)?;
+    store.sync(embedder.as_mut(), cfg.batch_size).map(Some)
+}
+
+/// `cass search --semantic`: rank messages by similarity to `query` using the
+/// configured model.
+pub fn semantic_search(
+    data_dir: &Path,
+    db_path: &Path,
+    query: &str,
+    filters: &SearchFilters,
+    limit: usize,
+    offset: usize,
+) -> Result<SearchResult> {
+    let cfg = crate::config::embeddings().ok_or_else(|| {
+        anyhow!(
+            "semantic search needs an [embeddings] section in {}",
+            crate::config::config_path().display()
+        )
+    })?;
+    if !store_path(data_dir).exists() {
+        bail!("no message embeddings yet; run `cass index` to create them");
+    }
+    let mut embedder = embedder(cfg, data_dir)?;
+    let store = VectorStore::open(data_dir, db_path)?;
+    if store.model()?.as_deref() != Some(embedder.model_id().as_str()) {
+        bail!("the embeddings were made with another model; run `cass index` to redo them");
+    }
+    let query_vector = embedder
+        .embed(&[query.to_string()])?
+        .pop()
+        .ok_or_else(|| anyhow!("the embedder returned no vector for the query"))?;
+    let (hits, total) = store.search(&query_vector, filters, limit, offset)?;
+    Ok(SearchResult {
+        hits,
+        wildcard_fallback: false,
+        cache_stats: CacheStats::default(),
+        suggestions: Vec::new(),
+        total_hits: Some(total),
+    })
+}
+
+fn embed_text(content: &str) -> String {
+    content.trim().chars().take(MAX_EMBED_CHARS).collect()
+}
+
+fn content_hash(text: &str) -> String {
+    ring::digest::digest(&ring::digest::SHA256, text.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Scale to unit length, so cosine similarity is a dot product.
+fn normalized(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
+
+fn encode(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn decode(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::types::{Agent, AgentKind, Conversation, Message, MessageRole};
+    use crate::storage::sqlite::SqliteStorage;
+
+    /// Bag of words over a tiny vocabulary, so "auth" and "login" land close together.
+    struct WordEmbedder {
+        calls: usize,
+        texts: usize,
+    }
+
+    impl Embedder for WordEmbedder {
+        fn model_id(&self) -> String {
+            "words".to_string()
+        }
+
+        fn embed(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            self.calls += 1;
+            self.texts += texts.len();
+            let topics: [&[&str]; 3] = [
+                &["auth", "login", "token", "session"],
+                &["race", "lock", "mutex", "concurrent"],
+                &["css", "layout", "button"],
+            ];
+            Ok(texts
+                .iter()
+                .map(|t| {
+                    let t = t.to_lowercase();
+                    topics
+                        .iter()
+                        .map(|words| words.iter().filter(|w| t.contains(*w)).count() as f32)
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    fn conversation(external_id: &str, contents: &[&str]) -> Conversation {
+        Conversation {
+            id: None,
+            agent_slug: "codex".into(),
+            workspace: Some(PathBuf::from("/ws")),
+            external_id: Some(external_id.into()),
+            title: Some(external_id.into()),
+            source_path: PathBuf::from(format!("/logs/{external_id}.jsonl")),
+            started_at: Some(1_700_000_000_000),
+            ended_at: None,
+            approx_tokens: None,
+            metadata_json: serde_json::Value::Null,
+            messages: contents
+                .iter()
+                .enumerate()
+                .map(|(i, c)| Message {
+                    id: None,
+                    idx: i as i64,
+                    role: MessageRole::User,
+                    author: None,
+                    created_at: Some(1_700_000_000_000 + i as i64),
+                    content: (*c).to_string(),
+                    extra_json: serde_json::Value::Null,
+                    snippets: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn messages_are_embedded_once_and_ranked_by_similarity() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db_path = dir.path().join("agent_search.db");
+        let mut storage = SqliteStorage::open(&db_path).unwrap();
+        let agent_id = storage
+            .ensure_agent(&Agent {
+                id: None,
+                slug: "codex".into(),
+                name: "Codex".into(),
+                version: None,
+                kind: AgentKind::Cli,
+            })
+            .unwrap();
+        let ws = storage.ensure_workspace(Path::new("/ws"), None).unwrap();
+        storage
+            .insert_conversation_tree(
+                agent_id,
+                Some(ws),
+                &conversation(
+                    "a",
+                    &["fixed the login token refresh", "the button layout is off"],
+                ),
+            )
+            .unwrap();
+        storage
+            .insert_conversation_tree(
+                agent_id,
+                Some(ws),
+                &conversation("b", &["a mutex around the race", "  "]),
+            )
+            .unwrap();
+
+        let mut embedder = WordEmbedder { calls: 0, texts: 0 };
+        let mut store = VectorStore::open(dir.path(), &db_path).unwrap();
+        assert_eq!(
+            store.sync(&mut embedder, 2).unwrap(),
+            3,
+            "blank messages are skipped"
+        );
+        assert_eq!(store.sync(&mut embedder, 2).unwrap(), 0);
+        assert_eq!(embedder.texts, 3);
+
+        let query = embedder
+            .embed(&["how did I fix the auth session".into()])
+            .unwrap();
+        let (hits, total) = store
+            .search(&query[0], &SearchFilters::default(), 2, 0)
+            .unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(hits[0].content, "fixed the login token refresh");
+        assert_eq!(hits[0].match_type, MatchType::Semantic);
+        assert_eq!(hits[0].line_number, Some(1));
+        assert_eq!(hits[0].conversation_id, "codex:a");
+        assert!((hits[0].score - 1.0).abs() < 1e-6);
+
+        let mut filters = SearchFilters::default();
+        filters.agents.insert("claude_code".into());
+        assert_eq!(store.search(&query[0], &filters, 5, 0).unwrap().1, 0);
+
+        // Re-ingesting a file gives its messages new ids; unchanged text is not re-embedded.
+        storage
+            .delete_source_path(Path::new("/logs/a.jsonl"))
+            .unwrap();
+        storage
+            .insert_conversation_tree(
+                agent_id,
+                Some(ws),
+                &conversation("a", &["fixed the login token refresh", "concurrent lock"]),
+            )
+            .unwrap();
+        let texts_before = embedder.texts;
+        assert_eq!(store.sync(&mut embedder, 8).unwrap(), 2);
+        assert_eq!(embedder.texts - texts_before, 1);
+        assert_eq!(store.len().unwrap(), 3);
+        let stale: i64 = store
+            .conn
+            .query_row("SELECT COUNT(*) FROM vectors", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(stale, 3, "the layout message's vector was dropped");
+    }
+
+    #[test]
+    fn openai_and_ollama_responses_are_parsed_in_input_order() {
+        let openai = serde_json::json!({
+            "data": [
+                { "index": 1, "embedding": [0.0, 1.0] },
+                { "index": 0, "embedding": [1.0, 0.0] },
+            ]
+        });
+        assert_eq!(
+            parse_embeddings(&openai).unwrap(),
+            vec![vec![1.0, 0.0], vec![0.0, 1.0]]
+        );
+        let ollama = serde_json::json!({ "embeddings": [[0.5, 0.5]] });
+        assert_eq!(parse_embeddings(&ollama).unwrap(), vec![vec![0.5, 0.5]]);
+        assert!(parse_embeddings(&serde_json::json!({ "error": "no model" })).is_err());
+    }
+}
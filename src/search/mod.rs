@@ -1,3 +1,4 @@
 //! Search layer facade.
+pub mod embeddings;
 pub mod query;
 pub mod tantivy;
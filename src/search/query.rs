@@ -4,12 +4,16 @@ use once_cell::sync::Lazy;
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::num::NonZeroUsize;
+use std::ops::Range;
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tantivy::collector::TopDocs;
-use tantivy::query::{AllQuery, BooleanQuery, Occur, Query, RangeQuery, RegexQuery, TermQuery};
+use tantivy::collector::{Count, TopDocs};
+use tantivy::query::{
+    AllQuery, BooleanQuery, BoostQuery, EmptyQuery, FuzzyTermQuery, Occur, Query, RangeQuery,
+    RegexQuery, TermQuery,
+};
 use tantivy::schema::{IndexRecordOption, Term, Value};
 use tantivy::snippet::SnippetGenerator;
 use tantivy::{Index, IndexReader, Searcher, TantivyDocument};
@@ -19,14 +23,101 @@ use tokio::task::JoinHandle;
 
 use rusqlite::Connection;
 
+use crate::connectors::NormalizedSnippet;
 use crate::search::tantivy::fields_from_schema;
 
-#[derive(Debug, Clone, Default, serde::Serialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct SearchFilters {
     pub agents: HashSet<String>,
     pub workspaces: HashSet<String>,
     pub created_from: Option<i64>,
     pub created_to: Option<i64>,
+    /// Message roles to keep (`user`, `agent`/`assistant`, `tool`, `system`).
+    pub roles: HashSet<String>,
+    /// Favor newer messages when ranking; `None` ranks by relevance alone.
+    #[serde(default)]
+    pub recency: Option<RecencyDecay>,
+    /// Return every copy of a message instead of only the best-scoring one.
+    #[serde(default)]
+    pub keep_duplicates: bool,
+    /// Tools the message must have called, lowercased (`tool:` atoms).
+    #[serde(default)]
+    pub tools: HashSet<String>,
+    /// Files the message's tool calls must have touched, as
+    /// [`crate::search::tantivy::file_atom_term`]s (`file:` atoms).
+    #[serde(default)]
+    pub files: HashSet<String>,
+    /// Inclusive bounds on a message's length in approximate tokens
+    /// ([`crate::search::tantivy::approx_token_count`]).
+    #[serde(default)]
+    pub min_tokens: Option<u64>,
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+    /// Sessions to search, by source path (`cass search --tag`); `Some` of an empty set
+    /// matches nothing.
+    #[serde(default)]
+    pub source_paths: Option<HashSet<String>>,
+}
+
+/// Move the `tool:NAME` and `file:PATH` atoms of `query` into `filters`, returning
+/// the rest of the query. A file matches by its trailing components, so `file:main.rs`
+/// and `file:src/main.rs` both find edits of `/home/me/app/src/main.rs`.
+pub fn extract_atoms(query: &str, filters: &mut SearchFilters) -> String {
+    let mut rest = Vec::new();
+    for word in query.split_whitespace() {
+        let atom = word.split_once(':').filter(|(_, value)| !value.is_empty());
+        match atom.map(|(key, value)| (key.to_ascii_lowercase(), value)) {
+            Some((key, value)) if key == "tool" => {
+                filters.tools.insert(value.to_lowercase());
+            }
+            Some((key, value)) if key == "file" => {
+                filters
+                    .files
+                    .insert(crate::search::tantivy::file_atom_term(value));
+            }
+            _ => rest.push(word),
+        }
+    }
+    rest.join(" ")
+}
+
+/// Exponential time decay on `created_at`, applied to every hit's relevance score.
+///
+/// A hit's score is multiplied by `1 + weight * 0.5^(age / half_life)`, so a message
+/// from now counts `1 + weight` times as much as an equally relevant one far in the
+/// past. Hits without a timestamp keep their score.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecencyDecay {
+    pub weight: f32,
+    pub half_life_days: f32,
+}
+
+impl RecencyDecay {
+    /// A decay of `weight`, with the configured half-life (`[ranking] recency_half_life_days`).
+    pub fn with_weight(weight: f32) -> Self {
+        Self {
+            weight,
+            half_life_days: crate::config::ranking().recency_half_life_days,
+        }
+    }
+
+    /// The score multiplier for a message created at `created_at`, both in Unix ms.
+    pub fn factor(&self, now_ms: i64, created_at: Option<i64>) -> f32 {
+        let Some(created_at) = created_at else {
+            return 1.0;
+        };
+        let half_life_ms = f64::from(self.half_life_days.max(f32::EPSILON)) * 86_400_000.0;
+        let age_ms = now_ms.saturating_sub(created_at).max(0) as f64;
+        1.0 + self.weight.max(0.0) * 0.5f64.powf(age_ms / half_life_ms) as f32
+    }
+
+    /// Rescore `hits` and re-sort them by the decayed score.
+    fn apply(&self, now_ms: i64, hits: &mut [SearchHit]) {
+        for hit in hits.iter_mut() {
+            hit.score *= self.factor(now_ms, hit.created_at);
+        }
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    }
 }
 
 // ============================================================================
@@ -160,6 +251,7 @@ impl QueryExplanation {
                         WildcardPattern::Prefix(_) => "prefix (*)",
                         WildcardPattern::Suffix(_) => "suffix (*)",
                         WildcardPattern::Substring(_) => "substring (*)",
+                        WildcardPattern::Fuzzy(..) => "fuzzy (~)",
                     };
                     parsed.terms.push(ParsedTerm {
                         text: t.clone(),
@@ -227,8 +319,12 @@ impl QueryExplanation {
         // Check for filters first (they modify everything)
         let has_filters = !filters.agents.is_empty()
             || !filters.workspaces.is_empty()
+            || !filters.roles.is_empty()
             || filters.created_from.is_some()
-            || filters.created_to.is_some();
+            || filters.created_to.is_some()
+            || filters.min_tokens.is_some()
+            || filters.max_tokens.is_some()
+            || filters.source_paths.is_some();
 
         if has_filters {
             return QueryType::Filtered;
@@ -334,6 +430,17 @@ impl QueryExplanation {
         if has_time_filter {
             parts.push("time range".to_string());
         }
+        if filters.min_tokens.is_some() || filters.max_tokens.is_some() {
+            parts.push("message length".to_string());
+        }
+        if filters.source_paths.is_some() {
+            parts.push("tagged sessions".to_string());
+        }
+        if !filters.roles.is_empty() {
+            let mut roles: Vec<_> = filters.roles.iter().map(String::as_str).collect();
+            roles.sort_unstable();
+            parts.push(format!("role {}", roles.join("/")));
+        }
 
         let description = if parts.is_empty() {
             None
@@ -419,7 +526,7 @@ impl QueryExplanation {
 
 /// Indicates how a search result matched the query.
 /// Used for ranking: exact matches rank higher than wildcard matches.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MatchType {
     /// No wildcards - matched via exact term or edge n-gram prefix
@@ -433,6 +540,10 @@ pub enum MatchType {
     Substring,
     /// Matched via automatic wildcard fallback when exact search was sparse
     ImplicitWildcard,
+    /// Matched a term within the edit distance of a fuzzy term (foo~), not the term itself
+    Fuzzy,
+    /// Ranked by embedding similarity (`--semantic`) rather than by terms
+    Semantic,
 }
 
 impl MatchType {
@@ -444,12 +555,15 @@ impl MatchType {
             MatchType::Suffix => 0.8,
             MatchType::Substring => 0.7,
             MatchType::ImplicitWildcard => 0.6,
+            MatchType::Fuzzy => 0.5,
+            // Ranked on its own path; similarity already orders the hits.
+            MatchType::Semantic => 1.0,
         }
     }
 }
 
 /// Type of suggestion for did-you-mean
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SuggestionKind {
     /// Typo correction (Levenshtein distance)
@@ -465,7 +579,7 @@ pub enum SuggestionKind {
 }
 
 /// A "did-you-mean" suggestion when search returns zero hits.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct QuerySuggestion {
     /// What kind of suggestion this is
     pub kind: SuggestionKind,
@@ -533,7 +647,7 @@ impl QuerySuggestion {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SearchHit {
     pub title: String,
     pub snippet: String,
@@ -548,10 +662,20 @@ pub struct SearchHit {
     /// How this result matched the query (exact, prefix wildcard, etc.)
     #[serde(default)]
     pub match_type: MatchType,
+    /// Tool calls, touched files and code blocks extracted from the message.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub snippets: Vec<NormalizedSnippet>,
+    /// Conversation the message belongs to, for grouping hits; see
+    /// [`conversation_key`](crate::search::tantivy::conversation_key).
+    #[serde(default)]
+    pub conversation_id: String,
+    /// Who wrote the message: `user`, `agent`, `tool`, `system`, or the agent's own label.
+    #[serde(default)]
+    pub role: String,
 }
 
 /// Result of a search operation with metadata about how matches were found
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SearchResult {
     /// The search results
     pub hits: Vec<SearchHit>,
@@ -561,6 +685,10 @@ pub struct SearchResult {
     pub cache_stats: CacheStats,
     /// Did-you-mean suggestions when hits are empty or sparse
     pub suggestions: Vec<QuerySuggestion>,
+    /// Messages matching the query across all pages, counted before duplicate
+    /// messages are folded together; `None` when the backend cannot count
+    #[serde(default)]
+    pub total_hits: Option<usize>,
 }
 
 pub struct SearchClient {
@@ -578,7 +706,7 @@ pub struct SearchClient {
     cache_namespace: String,
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct CacheStats {
     pub cache_hits: u64,
     pub cache_miss: u64,
@@ -656,13 +784,16 @@ impl CachedHit {
     fn approx_bytes(&self) -> usize {
         // Base struct overhead
         let base = std::mem::size_of::<Self>();
-        // SearchHit string fields (title, snippet, content, source_path, agent, workspace)
+        // SearchHit string fields (title, snippet, content, source_path, agent, workspace,
+        // conversation_id, role)
         let hit_strings = self.hit.title.len()
             + self.hit.snippet.len()
             + self.hit.content.len()
             + self.hit.source_path.len()
             + self.hit.agent.len()
-            + self.hit.workspace.len();
+            + self.hit.workspace.len()
+            + self.hit.conversation_id.len()
+            + self.hit.role.len();
         // Lowercase cache copies
         let lc_strings = self.lc_content.len()
             + self.lc_title.as_ref().map_or(0, std::string::String::len)
@@ -793,17 +924,120 @@ thread_local! {
 fn sanitize_query(raw: &str) -> String {
     // Replace any character that is not alphanumeric or asterisk with a space.
     // Asterisks are preserved for wildcard query support (*foo, foo*, *bar*).
+    // A tilde directly after a word is kept as its fuzzy marker (foo~, foo~1).
     // This ensures that the input tokens match how SimpleTokenizer splits content.
     // e.g. "c++" -> "c  ", "foo.bar" -> "foo bar", "*config*" -> "*config*"
+    let mut prev = ' ';
     raw.chars()
         .map(|c| {
-            if c.is_alphanumeric() || c == '*' {
-                c
+            let keep = c.is_alphanumeric() || c == '*' || (c == '~' && prev.is_alphanumeric());
+            prev = c;
+            if keep { c } else { ' ' }
+        })
+        .collect()
+}
+
+/// Append the fuzzy marker to every plain term of `query`, for `cass search --fuzzy`.
+/// Operators, phrases, wildcard, already-fuzzy terms and `tool:`/`file:` atoms are left
+/// as they are, and so are excluded terms, which would otherwise exclude their near
+/// misses too.
+pub fn fuzzy_query(query: &str) -> String {
+    let mut in_phrase = false;
+    let mut negated = false;
+    query
+        .split(' ')
+        .map(|word| {
+            let quotes = word.matches('"').count();
+            let operator = word.to_uppercase();
+            let plain = !in_phrase
+                && !negated
+                && quotes == 0
+                && !word.starts_with('-')
+                && !word.contains(['*', '~', ':'])
+                && !matches!(operator.as_str(), "AND" | "OR" | "NOT")
+                && word.chars().any(char::is_alphanumeric);
+            in_phrase ^= quotes % 2 == 1;
+            if !word.is_empty() {
+                negated = operator == "NOT";
+            }
+            if plain {
+                format!("{word}~")
             } else {
-                ' '
+                word.to_string()
             }
         })
-        .collect()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `query` with fuzzy markers removed, for backends that cannot match fuzzily.
+fn strip_fuzzy(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|term| match WildcardPattern::parse(term) {
+            WildcardPattern::Fuzzy(core, _) => core,
+            _ => term.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Edit distance used for `term~`: none for very short terms, where any typo would
+/// match unrelated words, 1 up to five characters, 2 beyond.
+fn auto_fuzzy_distance(term: &str) -> u8 {
+    match term.chars().count() {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    }
+}
+
+/// The word of `field`'s term dictionary nearest to `term` within its
+/// [`auto_fuzzy_distance`], preferring the more common of equally near words. Only
+/// words sharing the first letter are scanned: typos rarely hit it, and it keeps the
+/// scan to a slice of the dictionary.
+fn closest_term(searcher: &Searcher, field: tantivy::schema::Field, term: &str) -> Option<String> {
+    const MAX_SCANNED: usize = 200_000;
+    let max_distance = usize::from(auto_fuzzy_distance(term));
+    let first = term.chars().next()?;
+    if max_distance == 0 {
+        return None;
+    }
+    let len = term.chars().count();
+    let mut prefix = [0u8; 4];
+    let prefix = first.encode_utf8(&mut prefix).as_bytes();
+    let mut candidates: HashMap<String, (usize, u64)> = HashMap::new();
+    for segment in searcher.segment_readers() {
+        let Ok(inverted) = segment.inverted_index(field) else {
+            continue;
+        };
+        let Ok(mut stream) = inverted.terms().range().ge(prefix).into_stream() else {
+            continue;
+        };
+        let mut scanned = 0;
+        while stream.advance() && scanned < MAX_SCANNED {
+            scanned += 1;
+            if !stream.key().starts_with(prefix) {
+                break;
+            }
+            let Ok(word) = std::str::from_utf8(stream.key()) else {
+                continue;
+            };
+            if word.chars().count().abs_diff(len) > max_distance {
+                continue;
+            }
+            let distance = levenshtein_distance(term, word);
+            if distance == 0 || distance > max_distance {
+                continue;
+            }
+            let entry = candidates.entry(word.to_string()).or_insert((distance, 0));
+            entry.1 += u64::from(stream.value().doc_freq);
+        }
+    }
+    candidates
+        .into_iter()
+        .min_by(|(a, (da, fa)), (b, (db, fb))| da.cmp(db).then(fb.cmp(fa)).then(a.cmp(b)))
+        .map(|(word, _)| word)
 }
 
 /// Calculate Levenshtein edit distance between two strings.
@@ -865,10 +1099,23 @@ enum WildcardPattern {
     Suffix(String),
     /// Both wildcards: *foo* (substring match - requires regex)
     Substring(String),
+    /// Trailing tilde: foo~ or foo~N (terms within edit distance N, at most 2)
+    Fuzzy(String, u8),
 }
 
 impl WildcardPattern {
     fn parse(term: &str) -> Self {
+        if let Some((core, distance)) = term.rsplit_once('~')
+            && !core.is_empty()
+            && !core.contains(['*', '~'])
+            && (distance.is_empty() || distance.chars().all(|c| c.is_ascii_digit()))
+        {
+            let core = core.to_lowercase();
+            let distance = distance
+                .parse::<u8>()
+                .map_or_else(|_| auto_fuzzy_distance(&core), |d| d.min(2));
+            return WildcardPattern::Fuzzy(core, distance);
+        }
         let starts_with_star = term.starts_with('*');
         let ends_with_star = term.ends_with('*');
 
@@ -901,6 +1148,7 @@ impl WildcardPattern {
             WildcardPattern::Prefix(_) => MatchType::Prefix,
             WildcardPattern::Suffix(_) => MatchType::Suffix,
             WildcardPattern::Substring(_) => MatchType::Substring,
+            WildcardPattern::Fuzzy(..) => MatchType::Fuzzy,
         }
     }
 }
@@ -1146,9 +1394,48 @@ fn dominant_match_type(query: &str) -> MatchType {
 
 /// Build query clauses for a single term based on its wildcard pattern.
 /// Returns a Vec of (`Occur::Should`, Query) for use in a `BooleanQuery`.
+/// Clauses matching `pattern`, widened to the configured synonyms of a plain term.
 fn build_term_query_clauses(
     pattern: &WildcardPattern,
     fields: &crate::search::tantivy::Fields,
+) -> Vec<(Occur, Box<dyn Query>)> {
+    let mut shoulds = pattern_clauses(pattern, fields);
+    let WildcardPattern::Exact(term) = pattern else {
+        return shoulds;
+    };
+    for synonym in crate::config::synonyms(term) {
+        let words: Vec<String> = sanitize_query(&synonym)
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        match words.as_slice() {
+            [] => {}
+            [word] => shoulds.extend(pattern_clauses(
+                &WildcardPattern::Exact(word.clone()),
+                fields,
+            )),
+            // A multi-word synonym matches messages containing all of its words.
+            _ => {
+                let all = words
+                    .into_iter()
+                    .map(|word| {
+                        let clauses = pattern_clauses(&WildcardPattern::Exact(word), fields);
+                        (
+                            Occur::Must,
+                            Box::new(BooleanQuery::new(clauses)) as Box<dyn Query>,
+                        )
+                    })
+                    .collect();
+                shoulds.push((Occur::Should, Box::new(BooleanQuery::new(all))));
+            }
+        }
+    }
+    shoulds
+}
+
+fn pattern_clauses(
+    pattern: &WildcardPattern,
+    fields: &crate::search::tantivy::Fields,
 ) -> Vec<(Occur, Box<dyn Query>)> {
     let mut shoulds: Vec<(Occur, Box<dyn Query>)> = Vec::new();
 
@@ -1159,34 +1446,54 @@ fn build_term_query_clauses(
             if term.is_empty() {
                 return shoulds;
             }
-            shoulds.push((
-                Occur::Should,
-                Box::new(TermQuery::new(
-                    Term::from_field_text(fields.title, term),
-                    IndexRecordOption::WithFreqsAndPositions,
-                )),
-            ));
-            shoulds.push((
-                Occur::Should,
-                Box::new(TermQuery::new(
-                    Term::from_field_text(fields.content, term),
-                    IndexRecordOption::WithFreqsAndPositions,
-                )),
-            ));
-            shoulds.push((
-                Occur::Should,
-                Box::new(TermQuery::new(
-                    Term::from_field_text(fields.title_prefix, term),
+            // Stemmed indexes hold "retri" for "retries"; prefix fields keep words as written.
+            let word = stemmed(term, fields);
+            for (field, text) in [
+                (fields.title, &word),
+                (fields.content, &word),
+                (fields.title_prefix, term),
+                (fields.content_prefix, term),
+                (fields.snippets, &word),
+            ] {
+                let query = Box::new(TermQuery::new(
+                    Term::from_field_text(field, text),
                     IndexRecordOption::WithFreqsAndPositions,
-                )),
-            ));
-            shoulds.push((
-                Occur::Should,
-                Box::new(TermQuery::new(
-                    Term::from_field_text(fields.content_prefix, term),
+                ));
+                shoulds.push((Occur::Should, boosted(query, field, fields)));
+            }
+            if term.len() >= crate::search::tantivy::LONG_TOKEN_BYTES {
+                shoulds.push((
+                    Occur::Should,
+                    Box::new(TermQuery::new(
+                        Term::from_field_text(fields.long_tokens, term),
+                        IndexRecordOption::Basic,
+                    )),
+                ));
+            }
+        }
+        WildcardPattern::Fuzzy(term, distance) => {
+            // The term itself scores as usual; its near misses only add a constant,
+            // discounted by the fuzzy quality factor, so exact matches rank first.
+            if term.is_empty() {
+                return shoulds;
+            }
+            let term = &stemmed(term, fields);
+            for field in [fields.title, fields.content, fields.snippets] {
+                let exact = Box::new(TermQuery::new(
+                    Term::from_field_text(field, term),
                     IndexRecordOption::WithFreqsAndPositions,
-                )),
-            ));
+                ));
+                shoulds.push((Occur::Should, boosted(exact, field, fields)));
+                let near = Box::new(BoostQuery::new(
+                    Box::new(FuzzyTermQuery::new(
+                        Term::from_field_text(field, term),
+                        *distance,
+                        true,
+                    )),
+                    MatchType::Fuzzy.quality_factor(),
+                ));
+                shoulds.push((Occur::Should, boosted(near, field, fields)));
+            }
         }
         WildcardPattern::Suffix(term) | WildcardPattern::Substring(term) => {
             // For suffix and substring patterns, use RegexQuery
@@ -1196,11 +1503,11 @@ fn build_term_query_clauses(
             if let Some(regex_pattern) = pattern.to_regex() {
                 // Try to create RegexQuery for content field
                 if let Ok(rq) = RegexQuery::from_pattern(&regex_pattern, fields.content) {
-                    shoulds.push((Occur::Should, Box::new(rq)));
+                    shoulds.push((Occur::Should, boosted(Box::new(rq), fields.content, fields)));
                 }
                 // Also try for title field
                 if let Ok(rq) = RegexQuery::from_pattern(&regex_pattern, fields.title) {
-                    shoulds.push((Occur::Should, Box::new(rq)));
+                    shoulds.push((Occur::Should, boosted(Box::new(rq), fields.title, fields)));
                 }
             }
         }
@@ -1209,6 +1516,40 @@ fn build_term_query_clauses(
     shoulds
 }
 
+/// `query` against `field`, weighted by the field's `[ranking]` boost.
+fn boosted(
+    query: Box<dyn Query>,
+    field: tantivy::schema::Field,
+    fields: &crate::search::tantivy::Fields,
+) -> Box<dyn Query> {
+    let ranking = crate::config::ranking();
+    let boost = if field == fields.title {
+        ranking.title_boost
+    } else if field == fields.content {
+        ranking.content_boost
+    } else if field == fields.title_prefix || field == fields.content_prefix {
+        ranking.prefix_boost
+    } else if field == fields.snippets {
+        ranking.snippets_boost
+    } else {
+        1.0
+    };
+    if (boost - 1.0).abs() < f32::EPSILON {
+        query
+    } else {
+        Box::new(BoostQuery::new(query, boost.max(0.0)))
+    }
+}
+
+/// `term` as the index's word fields hold it.
+fn stemmed(term: &str, fields: &crate::search::tantivy::Fields) -> String {
+    if fields.stemmed {
+        crate::search::tantivy::stem_term(term)
+    } else {
+        term.to_string()
+    }
+}
+
 /// Check if content is primarily a tool invocation (noise that shouldn't appear in search results).
 /// Tool invocations like "[Tool: Bash - Check status]" are not informative search results.
 fn is_tool_invocation_noise(content: &str) -> bool {
@@ -1233,12 +1574,171 @@ fn is_tool_invocation_noise(content: &str) -> bool {
     false
 }
 
+/// The Tantivy query for a sanitized `query` under `filters`, shared by searching and
+/// [`SearchClient::count`].
+fn build_tantivy_query(
+    fields: &crate::search::tantivy::Fields,
+    query: &str,
+    filters: SearchFilters,
+) -> Box<dyn Query> {
+    let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+
+    // Parse query with boolean operator support (AND, OR, NOT, "phrases")
+    // Falls back to simple whitespace split for plain queries (implicit AND)
+    let tokens = parse_boolean_query(query);
+    if tokens.is_empty() {
+        clauses.push((Occur::Must, Box::new(AllQuery)));
+    } else if has_boolean_operators(query) {
+        // Use boolean query builder for complex queries
+        let bool_clauses = build_boolean_query_clauses(&tokens, fields);
+        clauses.extend(bool_clauses);
+    } else {
+        // Simple query: treat each term as MUST (implicit AND)
+        for token in tokens {
+            if let QueryToken::Term(term_str) = token {
+                let pattern = WildcardPattern::parse(&term_str);
+                let term_shoulds = build_term_query_clauses(&pattern, fields);
+                if !term_shoulds.is_empty() {
+                    clauses.push((Occur::Must, Box::new(BooleanQuery::new(term_shoulds))));
+                }
+            }
+        }
+    }
+
+    if !filters.agents.is_empty() {
+        let terms = filters
+            .agents
+            .into_iter()
+            .map(|agent| {
+                (
+                    Occur::Should,
+                    Box::new(TermQuery::new(
+                        Term::from_field_text(fields.agent, &agent),
+                        IndexRecordOption::Basic,
+                    )) as Box<dyn Query>,
+                )
+            })
+            .collect();
+        clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
+    }
+
+    if !filters.workspaces.is_empty() {
+        let terms = filters
+            .workspaces
+            .into_iter()
+            .map(|ws| {
+                (
+                    Occur::Should,
+                    Box::new(TermQuery::new(
+                        Term::from_field_text(fields.workspace, &ws),
+                        IndexRecordOption::Basic,
+                    )) as Box<dyn Query>,
+                )
+            })
+            .collect();
+        clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
+    }
+
+    // Atoms are ANDed like the query's terms.
+    for (field, values) in [
+        (fields.tools, &filters.tools),
+        (fields.files, &filters.files),
+    ] {
+        for value in values {
+            clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(field, value),
+                    IndexRecordOption::Basic,
+                )),
+            ));
+        }
+    }
+
+    if !filters.roles.is_empty() {
+        let terms = filters
+            .roles
+            .iter()
+            .map(|role| {
+                (
+                    Occur::Should,
+                    Box::new(TermQuery::new(
+                        Term::from_field_text(fields.role, crate::search::tantivy::role_term(role)),
+                        IndexRecordOption::Basic,
+                    )) as Box<dyn Query>,
+                )
+            })
+            .collect();
+        clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
+    }
+
+    if filters.created_from.is_some() || filters.created_to.is_some() {
+        use std::ops::Bound::{Included, Unbounded};
+        let lower = filters.created_from.map_or(Unbounded, |v| {
+            Included(Term::from_field_i64(fields.created_at, v))
+        });
+        let upper = filters.created_to.map_or(Unbounded, |v| {
+            Included(Term::from_field_i64(fields.created_at, v))
+        });
+        let range = RangeQuery::new(lower, upper);
+        clauses.push((Occur::Must, Box::new(range)));
+    }
+
+    if filters.min_tokens.is_some() || filters.max_tokens.is_some() {
+        use std::ops::Bound::{Included, Unbounded};
+        let bound = |v: Option<u64>| {
+            v.map_or(Unbounded, |v| {
+                Included(Term::from_field_u64(fields.token_count, v))
+            })
+        };
+        let range = RangeQuery::new(bound(filters.min_tokens), bound(filters.max_tokens));
+        clauses.push((Occur::Must, Box::new(range)));
+    }
+
+    if let Some(paths) = &filters.source_paths {
+        if paths.is_empty() {
+            clauses.push((Occur::Must, Box::new(EmptyQuery)));
+        } else {
+            let terms = paths
+                .iter()
+                .map(|path| {
+                    (
+                        Occur::Should,
+                        Box::new(TermQuery::new(
+                            Term::from_field_text(fields.source_path, path),
+                            IndexRecordOption::Basic,
+                        )) as Box<dyn Query>,
+                    )
+                })
+                .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
+        }
+    }
+
+    if clauses.is_empty() {
+        Box::new(AllQuery)
+    } else if clauses.len() == 1 {
+        let (occur, query_box) = clauses.pop().unwrap();
+        match occur {
+            // For Must, we can safely unwrap and use the inner query directly
+            Occur::Must => query_box,
+            // For MustNot or Should, we must preserve the Occur by wrapping
+            // in a BooleanQuery. A lone MustNot (e.g., "NOT foo") should match
+            // nothing, not match "foo".
+            _ => Box::new(BooleanQuery::new(vec![(occur, query_box)])),
+        }
+    } else {
+        Box::new(BooleanQuery::new(clauses))
+    }
+}
+
 /// Deduplicate search hits by content, keeping only the highest-scored hit for each unique content.
 /// This removes duplicate results when the same message appears multiple times (e.g., user repeated
-/// themselves in a conversation, or the same content was indexed from multiple sources).
-/// Also filters out tool invocation noise that isn't useful for search results.
+/// themselves in a conversation, or the same session was captured by two connectors, such as an
+/// agent's own log and an editor extension's). Also filters out tool invocation noise that isn't
+/// useful for search results.
 fn deduplicate_hits(hits: Vec<SearchHit>) -> Vec<SearchHit> {
-    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut seen: HashMap<u64, usize> = HashMap::new();
     let mut deduped: Vec<SearchHit> = Vec::new();
 
     for hit in hits {
@@ -1247,17 +1747,15 @@ fn deduplicate_hits(hits: Vec<SearchHit>) -> Vec<SearchHit> {
             continue;
         }
 
-        // Normalize content for comparison (trim whitespace, collapse multiple spaces)
-        let normalized = hit.content.split_whitespace().collect::<Vec<_>>().join(" ");
-
-        if let Some(&existing_idx) = seen.get(&normalized) {
+        let key = content_hash(&hit.content);
+        if let Some(&existing_idx) = seen.get(&key) {
             // If existing hit has lower score, replace it
             if deduped[existing_idx].score < hit.score {
                 deduped[existing_idx] = hit;
             }
             // Otherwise keep existing (higher score)
         } else {
-            seen.insert(normalized, deduped.len());
+            seen.insert(key, deduped.len());
             deduped.push(hit);
         }
     }
@@ -1265,17 +1763,57 @@ fn deduplicate_hits(hits: Vec<SearchHit>) -> Vec<SearchHit> {
     deduped
 }
 
+/// Drop tool invocation noise but keep duplicates (`SearchFilters::keep_duplicates`).
+fn drop_tool_noise(mut hits: Vec<SearchHit>) -> Vec<SearchHit> {
+    hits.retain(|hit| !is_tool_invocation_noise(&hit.content));
+    hits
+}
+
+/// Hash of a message's words, lowercased, so copies that differ only in whitespace,
+/// case, punctuation or markdown (as two connectors often render the same message)
+/// hash alike. Text without any words is hashed as written.
+fn content_hash(content: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let lowered = content.to_lowercase();
+    let words: Vec<&str> = token_stream(&lowered).collect();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    if words.is_empty() {
+        content.trim().hash(&mut hasher);
+    } else {
+        words.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
 impl SearchClient {
+    /// Open the index and database for querying.
+    ///
+    /// Both are opened read-only and nothing is created on disk: the Tantivy reader
+    /// uses a manual reload policy (searches reload explicitly via
+    /// `maybe_reload_reader`, so no meta.json watcher thread is spawned) and SQLite is
+    /// only opened if the database already exists.
     pub fn open(index_path: &Path, db_path: Option<&Path>) -> Result<Option<Self>> {
         let tantivy = Index::open_in_dir(index_path).ok().and_then(|mut idx| {
             // Register custom tokenizer so searches work
             crate::search::tantivy::ensure_tokenizer(&mut idx);
             let schema = idx.schema();
             let fields = fields_from_schema(&schema).ok()?;
-            idx.reader().ok().map(|reader| (reader, fields))
+            idx.reader_builder()
+                .reload_policy(tantivy::ReloadPolicy::Manual)
+                .try_into()
+                .ok()
+                .map(|reader| (reader, fields))
         });
 
-        let sqlite = db_path.and_then(|p| Connection::open(p).ok());
+        let sqlite = db_path.and_then(|p| {
+            Connection::open_with_flags(
+                p,
+                rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY
+                    | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            )
+            .ok()
+        });
 
         if tantivy.is_none() && sqlite.is_none() {
             return Ok(None);
@@ -1324,7 +1862,8 @@ impl SearchClient {
         limit: usize,
         offset: usize,
     ) -> Result<Vec<SearchHit>> {
-        let sanitized = sanitize_query(query);
+        let mut filters = filters;
+        let sanitized = sanitize_query(&extract_atoms(query, &mut filters));
 
         // Schedule warmup for likely prefixes when user pauses typing.
         if offset == 0
@@ -1337,7 +1876,13 @@ impl SearchClient {
         }
 
         // Fast path: reuse cached prefix when user is typing forward (offset 0 only).
-        if offset == 0 {
+        // Cached hits are verified literally, which would drop fuzzy, stemmed and
+        // synonym matches.
+        let stemmed = self.reader.as_ref().is_some_and(|(_, f)| f.stemmed);
+        let has_synonyms = sanitized
+            .split_whitespace()
+            .any(|word| !crate::config::synonyms(word).is_empty());
+        if offset == 0 && !sanitized.contains('~') && !stemmed && !has_synonyms {
             if let Some(cached) = self.cached_prefix_hits(&sanitized, &filters) {
                 let mut filtered: Vec<SearchHit> = cached
                     .into_iter()
@@ -1378,7 +1923,11 @@ impl SearchClient {
                 offset,
             )?;
             if !hits.is_empty() {
-                let mut deduped = deduplicate_hits(hits);
+                let mut deduped = if filters.keep_duplicates {
+                    drop_tool_noise(hits)
+                } else {
+                    deduplicate_hits(hits)
+                };
                 deduped.truncate(limit);
                 self.put_cache(&sanitized, &filters, &deduped);
                 return Ok(deduped);
@@ -1393,9 +1942,11 @@ impl SearchClient {
         // Fallback: SQLite FTS (slower, but strictly consistent with DB)
         // Skip SQLite fallback when the query contains leading/trailing wildcards that
         // FTS5 cannot parse (e.g., "*handler" or "*foo*"), to avoid "unknown special query" errors.
+        // FTS5 cannot parse leading wildcards and has no tool or file data.
         let query_has_wildcards = sanitized.contains('*');
+        let has_atoms = !filters.tools.is_empty() || !filters.files.is_empty();
         if let Some(conn) = &self.sqlite {
-            if query_has_wildcards {
+            if query_has_wildcards || has_atoms {
                 return Ok(Vec::new());
             }
             tracing::info!(
@@ -1405,8 +1956,27 @@ impl SearchClient {
                 offset = offset,
                 "search_start"
             );
-            let hits = self.search_sqlite(conn, &sanitized, filters.clone(), limit * 3, offset)?;
-            let mut deduped = deduplicate_hits(hits);
+            // FTS5 has no fuzzy matching; search fuzzy terms exactly.
+            let fts_query = strip_fuzzy(&sanitized);
+            let mut hits =
+                self.search_sqlite(conn, &fts_query, filters.clone(), limit * 3, offset)?;
+            // The database keeps no per-message token counts; measure the fetched window.
+            if filters.min_tokens.is_some() || filters.max_tokens.is_some() {
+                hits.retain(|hit| {
+                    let tokens = crate::search::tantivy::approx_token_count(&hit.content);
+                    filters.min_tokens.is_none_or(|min| tokens >= min)
+                        && filters.max_tokens.is_none_or(|max| tokens <= max)
+                });
+            }
+            // FTS5 cannot rank by time, so the decay reorders the fetched window.
+            if let Some(decay) = filters.recency {
+                decay.apply(chrono::Utc::now().timestamp_millis(), &mut hits);
+            }
+            let mut deduped = if filters.keep_duplicates {
+                drop_tool_noise(hits)
+            } else {
+                deduplicate_hits(hits)
+            };
             deduped.truncate(limit);
             self.put_cache(&sanitized, &filters, &deduped);
             return Ok(deduped);
@@ -1427,27 +1997,27 @@ impl SearchClient {
         offset: usize,
         sparse_threshold: usize,
     ) -> Result<SearchResult> {
+        // Atoms are filters, never widened into wildcards below; suggestions keep them.
+        let raw_query = query;
+        let mut filters = filters;
+        let query = &extract_atoms(query, &mut filters);
         // First, try the normal search
         let hits = self.search(query, filters.clone(), limit, offset)?;
         let baseline_stats = self.cache_stats();
 
-        // Check if we should try wildcard fallback
-        let query_has_wildcards = query.contains('*');
+        // Check if we should try wildcard fallback (fuzzy terms already widen the search)
+        let query_has_wildcards = query.contains(['*', '~']);
         let is_sparse = hits.len() < sparse_threshold && offset == 0;
 
         if !is_sparse || query_has_wildcards || query.trim().is_empty() {
             // Either we have enough results, query already has wildcards, or query is empty
-            // Generate suggestions only if truly zero hits
-            let suggestions = if hits.is_empty() && !query.trim().is_empty() {
-                self.generate_suggestions(query, &filters)
-            } else {
-                Vec::new()
-            };
+            let suggestions = self.suggestions_for(raw_query, &filters, hits.len(), is_sparse);
             return Ok(SearchResult {
                 hits,
                 wildcard_fallback: false,
                 cache_stats: baseline_stats,
                 suggestions,
+                total_hits: self.count(query, &filters)?,
             });
         }
 
@@ -1474,40 +2044,57 @@ impl SearchClient {
             for hit in &mut fallback_hits {
                 hit.match_type = MatchType::ImplicitWildcard;
             }
-            // Generate suggestions if still zero hits after fallback
-            let suggestions = if fallback_hits.is_empty() {
-                self.generate_suggestions(query, &filters)
-            } else {
-                Vec::new()
-            };
+            let suggestions = self.suggestions_for(
+                raw_query,
+                &filters,
+                fallback_hits.len(),
+                fallback_hits.len() < sparse_threshold,
+            );
             Ok(SearchResult {
                 hits: fallback_hits,
                 wildcard_fallback: true,
                 cache_stats: fallback_stats,
                 suggestions,
+                total_hits: self.count(&wildcard_query, &filters)?,
             })
         } else {
             // Keep original results even if sparse
-            // Generate suggestions if zero hits
-            let suggestions = if hits.is_empty() {
-                self.generate_suggestions(query, &filters)
-            } else {
-                Vec::new()
-            };
+            let suggestions = self.suggestions_for(raw_query, &filters, hits.len(), true);
             Ok(SearchResult {
                 hits,
                 wildcard_fallback: false,
                 cache_stats: baseline_stats,
                 suggestions,
+                total_hits: self.count(query, &filters)?,
             })
         }
     }
 
+    /// How many messages in the Tantivy index match `query` under `filters`, without
+    /// fetching any; `None` when only the SQLite fallback is open.
+    pub fn count(&self, query: &str, filters: &SearchFilters) -> Result<Option<usize>> {
+        let Some((reader, fields)) = &self.reader else {
+            return Ok(None);
+        };
+        self.maybe_reload_reader(reader)?;
+        let searcher = self.searcher_for_thread(reader);
+        let mut filters = filters.clone();
+        let query = sanitize_query(&extract_atoms(query, &mut filters));
+        let q = build_tantivy_query(fields, &query, filters);
+        Ok(Some(searcher.search(&q, &Count)?))
+    }
+
     /// Generate "did-you-mean" suggestions for zero-hit queries.
     fn generate_suggestions(&self, query: &str, filters: &SearchFilters) -> Vec<QuerySuggestion> {
         let mut suggestions = Vec::new();
         let query_lower = query.to_lowercase();
 
+        // 0. Suggest the query with misspelled terms replaced by words in the index
+        let corrected = self.corrected_query(query);
+        if let Some(fix) = &corrected {
+            suggestions.push(QuerySuggestion::spelling(query, fix));
+        }
+
         // 1. Suggest wildcard search if query doesn't have wildcards
         if !query.contains('*') && query.len() >= 2 {
             suggestions.push(QuerySuggestion::wildcard(query).with_shortcut(1));
@@ -1535,7 +2122,7 @@ impl SearchClient {
             "amp",
             "opencode",
         ];
-        for agent in &known_agents {
+        for agent in known_agents.iter().filter(|_| corrected.is_none()) {
             if levenshtein_distance(&query_lower, agent) <= 2 && query_lower != *agent {
                 suggestions.push(
                     QuerySuggestion::spelling(query, agent)
@@ -1571,6 +2158,74 @@ impl SearchClient {
         suggestions
     }
 
+    /// Suggestions for a result of `hits` hits: the full set when nothing matched, just
+    /// a spelling fix when the result is only sparse.
+    fn suggestions_for(
+        &self,
+        query: &str,
+        filters: &SearchFilters,
+        hits: usize,
+        sparse: bool,
+    ) -> Vec<QuerySuggestion> {
+        if hits == 0 && !query.trim().is_empty() {
+            self.generate_suggestions(query, filters)
+        } else if sparse {
+            self.corrected_query(query)
+                .map(|fix| QuerySuggestion::spelling(query, &fix).with_shortcut(1))
+                .into_iter()
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// `query` with each plain term the index has never seen replaced by the closest
+    /// word it has (see [`closest_term`]); `None` when every term is known or nothing
+    /// is close enough. Operators, phrases, wildcards and atoms are kept as written.
+    fn corrected_query(&self, query: &str) -> Option<String> {
+        let (reader, fields) = self.reader.as_ref()?;
+        let searcher = self.searcher_for_thread(reader);
+        let mut analyzer = searcher.index().tokenizer_for_field(fields.content).ok()?;
+        // A stemmed `content` holds stems; `preview` keeps the words as written.
+        let vocabulary = if fields.stemmed {
+            fields.preview
+        } else {
+            fields.content
+        };
+        let mut changed = false;
+        let words: Vec<String> = query
+            .split_whitespace()
+            .map(|word| {
+                let WildcardPattern::Exact(term) = WildcardPattern::parse(word) else {
+                    return word.to_string();
+                };
+                if matches!(word, "AND" | "OR" | "NOT")
+                    || term.chars().count() < 3
+                    || !term.chars().all(char::is_alphanumeric)
+                {
+                    return word.to_string();
+                }
+                let mut stream = analyzer.token_stream(&term);
+                let mut known = true;
+                while let Some(token) = stream.next() {
+                    let indexed = Term::from_field_text(fields.content, &token.text);
+                    known &= searcher.doc_freq(&indexed).unwrap_or(0) > 0;
+                }
+                if known {
+                    return word.to_string();
+                }
+                match closest_term(&searcher, vocabulary, &term) {
+                    Some(fix) => {
+                        changed = true;
+                        fix
+                    }
+                    None => word.to_string(),
+                }
+            })
+            .collect();
+        changed.then(|| words.join(" "))
+    }
+
     fn searcher_for_thread(&self, reader: &IndexReader) -> Searcher {
         let epoch = self.reload_epoch.load(Ordering::Relaxed);
         THREAD_SEARCHER.with(|slot| {
@@ -1613,102 +2268,51 @@ impl SearchClient {
         let searcher = self.searcher_for_thread(reader);
         self.track_generation(searcher.generation().generation_id());
 
-        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        let recency = filters.recency;
+        let q = build_tantivy_query(fields, query, filters);
 
-        // Parse query with boolean operator support (AND, OR, NOT, "phrases")
-        // Falls back to simple whitespace split for plain queries (implicit AND)
-        let tokens = parse_boolean_query(query);
-        if tokens.is_empty() {
-            clauses.push((Occur::Must, Box::new(AllQuery)));
-        } else if has_boolean_operators(query) {
-            // Use boolean query builder for complex queries
-            let bool_clauses = build_boolean_query_clauses(&tokens, fields);
-            clauses.extend(bool_clauses);
+        let prefix_only = is_prefix_only(query);
+        // A stemmed index can match "retries" in "retrying", which the literal prefix scan misses.
+        let snippet_generator = if prefix_only && !fields.stemmed {
+            None
         } else {
-            // Simple query: treat each term as MUST (implicit AND)
-            for token in tokens {
-                if let QueryToken::Term(term_str) = token {
-                    let pattern = WildcardPattern::parse(&term_str);
-                    let term_shoulds = build_term_query_clauses(&pattern, fields);
-                    if !term_shoulds.is_empty() {
-                        clauses.push((Occur::Must, Box::new(BooleanQuery::new(term_shoulds))));
-                    }
-                }
-            }
+            let mut generator = SnippetGenerator::create(&searcher, &*q, fields.content)?;
+            // Highlight the whole message; `centered_snippet` picks the window.
+            generator.set_max_num_chars(usize::MAX);
+            Some(generator)
+        };
+        let mut prefix_terms: Vec<String> = query.split_whitespace().map(str::to_string).collect();
+        for word in query.split_whitespace() {
+            prefix_terms.extend(crate::config::synonyms(word));
         }
 
-        if !filters.agents.is_empty() {
-            let terms = filters
-                .agents
-                .into_iter()
-                .map(|agent| {
-                    (
-                        Occur::Should,
-                        Box::new(TermQuery::new(
-                            Term::from_field_text(fields.agent, &agent),
-                            IndexRecordOption::Basic,
-                        )) as Box<dyn Query>,
-                    )
-                })
-                .collect();
-            clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
-        }
-
-        if !filters.workspaces.is_empty() {
-            let terms = filters
-                .workspaces
-                .into_iter()
-                .map(|ws| {
-                    (
-                        Occur::Should,
-                        Box::new(TermQuery::new(
-                            Term::from_field_text(fields.workspace, &ws),
-                            IndexRecordOption::Basic,
-                        )) as Box<dyn Query>,
-                    )
-                })
-                .collect();
-            clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
-        }
-
-        if filters.created_from.is_some() || filters.created_to.is_some() {
-            use std::ops::Bound::{Included, Unbounded};
-            let lower = filters.created_from.map_or(Unbounded, |v| {
-                Included(Term::from_field_i64(fields.created_at, v))
-            });
-            let upper = filters.created_to.map_or(Unbounded, |v| {
-                Included(Term::from_field_i64(fields.created_at, v))
-            });
-            let range = RangeQuery::new(lower, upper);
-            clauses.push((Occur::Must, Box::new(range)));
-        }
-
-        let q: Box<dyn Query> = if clauses.is_empty() {
-            Box::new(AllQuery)
-        } else if clauses.len() == 1 {
-            let (occur, query_box) = clauses.pop().unwrap();
-            match occur {
-                // For Must, we can safely unwrap and use the inner query directly
-                Occur::Must => query_box,
-                // For MustNot or Should, we must preserve the Occur by wrapping
-                // in a BooleanQuery. A lone MustNot (e.g., "NOT foo") should match
-                // nothing, not match "foo".
-                _ => Box::new(BooleanQuery::new(vec![(occur, query_box)])),
+        let top_docs = match recency {
+            Some(decay) => {
+                let now_ms = chrono::Utc::now().timestamp_millis();
+                let collector = TopDocs::with_limit(limit).and_offset(offset).tweak_score(
+                    move |segment_reader: &tantivy::SegmentReader| {
+                        let created_at = segment_reader.fast_fields().i64("created_at").ok();
+                        move |doc, score: tantivy::Score| {
+                            let ts = created_at.as_ref().and_then(|col| col.first(doc));
+                            score * decay.factor(now_ms, ts)
+                        }
+                    },
+                );
+                searcher.search(&q, &collector)?
             }
-        } else {
-            Box::new(BooleanQuery::new(clauses))
-        };
-
-        let prefix_only = is_prefix_only(query);
-        let snippet_generator = if prefix_only {
-            None
-        } else {
-            Some(SnippetGenerator::create(&searcher, &*q, fields.content)?)
+            None => searcher.search(&q, &TopDocs::with_limit(limit).and_offset(offset))?,
         };
-
-        let top_docs = searcher.search(&q, &TopDocs::with_limit(limit).and_offset(offset))?;
-        // Compute match type once for all results (not per-hit)
+        // Compute match type once for all results (not per-hit). With fuzzy terms, a hit
+        // containing every fuzzy term verbatim gets the match type of the query without them.
         let query_match_type = dominant_match_type(query);
+        let fuzzy_terms: Vec<String> = query
+            .split_whitespace()
+            .filter_map(|t| match WildcardPattern::parse(t) {
+                WildcardPattern::Fuzzy(core, _) => Some(core),
+                _ => None,
+            })
+            .collect();
+        let literal_match_type = dominant_match_type(&strip_fuzzy(query));
         let mut hits = Vec::new();
         for (score, addr) in top_docs {
             let doc: TantivyDocument = searcher.doc(addr)?;
@@ -1728,17 +2332,16 @@ impl SearchClient {
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string();
-            let snippet = if let Some(r#gen) = &snippet_generator {
-                r#gen
-                    .snippet_from_doc(&doc)
-                    .to_html()
-                    .replace("<b>", "**")
-                    .replace("</b>", "**")
-            } else if let Some(sn) = cached_prefix_snippet(&content, query, 160) {
-                sn
+            let matches = match &snippet_generator {
+                Some(r#gen) => r#gen.snippet(&content).highlighted().to_vec(),
+                None => Vec::new(),
+            };
+            let matches = if matches.is_empty() {
+                match_ranges(&content, &prefix_terms)
             } else {
-                quick_prefix_snippet(&content, query, 160)
+                matches
             };
+            let snippet = centered_snippet(&content, &matches, SNIPPET_CHARS);
             let source = doc
                 .get_first(fields.source_path)
                 .and_then(|v| v.as_str())
@@ -1754,6 +2357,29 @@ impl SearchClient {
                 .get_first(fields.msg_idx)
                 .and_then(|v| v.as_u64())
                 .map(|i| (i + 1) as usize);
+            let snippets = doc
+                .get_first(fields.snippets_json)
+                .and_then(|v| v.as_str())
+                .and_then(|s| serde_json::from_str(s).ok())
+                .unwrap_or_default();
+            let text = |field| {
+                doc.get_first(field)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string()
+            };
+            let conversation_id = text(fields.conversation_id);
+            let role = text(fields.role);
+            let match_type = if fuzzy_terms.is_empty() {
+                query_match_type
+            } else {
+                let haystack = format!("{} {}", title, content).to_lowercase();
+                if fuzzy_terms.iter().all(|t| haystack.contains(t.as_str())) {
+                    literal_match_type
+                } else {
+                    MatchType::Fuzzy
+                }
+            };
             hits.push(SearchHit {
                 title,
                 snippet,
@@ -1764,7 +2390,19 @@ impl SearchClient {
                 workspace,
                 created_at,
                 line_number,
-                match_type: query_match_type,
+                match_type,
+                snippets,
+                conversation_id,
+                role,
+            });
+        }
+        if !fuzzy_terms.is_empty() {
+            // Exact hits first, then near misses, each by score.
+            hits.sort_by(|a, b| {
+                b.match_type
+                    .quality_factor()
+                    .total_cmp(&a.match_type.quality_factor())
+                    .then(b.score.total_cmp(&a.score))
             });
         }
         Ok(hits)
@@ -1785,9 +2423,10 @@ impl SearchClient {
         // Compute match type once for all results
         let query_match_type = dominant_match_type(query);
         let mut sql = String::from(
-            "SELECT f.title, f.content, f.agent, f.workspace, f.source_path, f.created_at, bm25(fts_messages) AS score, snippet(fts_messages, 0, '**', '**', '...', 64) AS snippet, m.idx
+            "SELECT f.title, f.content, f.agent, f.workspace, f.source_path, f.created_at, bm25(fts_messages) AS score, snippet(fts_messages, 0, '**', '**', '...', 64) AS snippet, m.idx, m.role, c.external_id
              FROM fts_messages f
              LEFT JOIN messages m ON f.message_id = m.id
+             LEFT JOIN conversations c ON m.conversation_id = c.id
              WHERE fts_messages MATCH ?",
         );
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(query.to_string())];
@@ -1814,6 +2453,29 @@ impl SearchClient {
             }
         }
 
+        if let Some(paths) = filters.source_paths {
+            if paths.is_empty() {
+                sql.push_str(" AND 0");
+            } else {
+                let placeholders = vec!["?"; paths.len()].join(",");
+                sql.push_str(&format!(" AND f.source_path IN ({placeholders})"));
+                for p in paths {
+                    params.push(Box::new(p));
+                }
+            }
+        }
+
+        if !filters.roles.is_empty() {
+            let placeholders = (0..filters.roles.len())
+                .map(|_| "?".to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            sql.push_str(&format!(" AND m.role IN ({placeholders})"));
+            for r in &filters.roles {
+                params.push(Box::new(crate::search::tantivy::role_term(r).to_string()));
+            }
+        }
+
         if let Some(created_from) = filters.created_from {
             sql.push_str(" AND f.created_at >= ?");
             params.push(Box::new(created_from));
@@ -1842,6 +2504,13 @@ impl SearchClient {
                 // idx is 0-indexed message index; convert to 1-indexed line number for JSONL files
                 let idx: Option<i64> = row.get(8).ok();
                 let line_number = idx.map(|i| (i + 1) as usize);
+                let role: Option<String> = row.get(9)?;
+                let external_id: Option<String> = row.get(10)?;
+                let conversation_id = crate::search::tantivy::conversation_key(
+                    &agent,
+                    external_id.as_deref(),
+                    &source_path,
+                );
                 Ok(SearchHit {
                     title,
                     snippet,
@@ -1853,6 +2522,9 @@ impl SearchClient {
                     created_at,
                     line_number,
                     match_type: query_match_type,
+                    snippets: Vec::new(),
+                    conversation_id,
+                    role: role.unwrap_or_default(),
                 })
             },
         )?;
@@ -2060,67 +2732,120 @@ fn is_prefix_only(query: &str) -> bool {
         .all(|t| !t.is_empty() && t.chars().all(char::is_alphanumeric))
 }
 
-fn quick_prefix_snippet(content: &str, query: &str, max_chars: usize) -> String {
-    let content_char_count = content.chars().count();
-
-    // Handle empty query case first
-    if query.is_empty() {
-        let snippet: String = content.chars().take(max_chars).collect();
-        return if content_char_count > max_chars {
-            format!("{snippet}…")
-        } else {
-            snippet
-        };
+/// Default length, in characters, of the snippet returned with each hit.
+pub const SNIPPET_CHARS: usize = 160;
+
+/// Byte ranges of `terms` in `text`, sorted and non-overlapping.
+///
+/// Matching is case-insensitive; where matches overlap, longer terms win so a
+/// phrase is one range rather than one per word.
+pub fn match_ranges(text: &str, terms: &[String]) -> Vec<Range<usize>> {
+    let mut terms: Vec<String> = terms.iter().map(|t| t.to_lowercase()).collect();
+    terms.sort_by_key(|t| std::cmp::Reverse(t.len()));
+
+    // Lowercasing can change a character's byte length, so remember which
+    // original character every byte of the lowercased text came from.
+    let mut lower = String::with_capacity(text.len());
+    let mut origin = Vec::with_capacity(text.len());
+    for (idx, ch) in text.char_indices() {
+        for lc in ch.to_lowercase() {
+            lower.push(lc);
+            origin.extend(std::iter::repeat_n(idx..idx + ch.len_utf8(), lc.len_utf8()));
+        }
     }
 
-    let lc_content = content.to_lowercase();
-    let lc_query = query.to_lowercase();
-    if let Some(pos) = lc_content.find(&lc_query) {
-        // Convert byte index in the lowercased string to a character index.
-        // IMPORTANT: Use lc_content[..pos], not content[..pos], because pos is a byte
-        // index valid only for the lowercased string (Unicode case mappings can change
-        // byte lengths, e.g., German ß → SS).
-        let start_char = lc_content[..pos].chars().count().saturating_sub(15);
-        let snippet: String = content.chars().skip(start_char).take(max_chars).collect();
-        // Check if we truncated: snippet covers chars [start_char, start_char + snippet_len)
-        let snippet_char_count = snippet.chars().count();
-        if start_char + snippet_char_count < content_char_count {
-            format!("{snippet}…")
-        } else {
-            snippet
-        }
-    } else {
-        let snippet: String = content.chars().take(max_chars).collect();
-        if content_char_count > max_chars {
-            format!("{snippet}…")
-        } else {
-            snippet
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for term in terms.iter().filter(|t| !t.is_empty()) {
+        for (idx, matched) in lower.match_indices(term.as_str()) {
+            let range = origin[idx].start..origin[idx + matched.len() - 1].end;
+            if ranges
+                .iter()
+                .all(|r| range.end <= r.start || range.start >= r.end)
+            {
+                ranges.push(range);
+            }
         }
     }
+    ranges.sort_unstable_by_key(|r| r.start);
+    ranges
 }
 
-fn cached_prefix_snippet(content: &str, query: &str, max_chars: usize) -> Option<String> {
-    if query.trim().is_empty() {
-        return None;
-    }
-    let lc_content = content.to_lowercase();
-    let lc_query = query.to_lowercase();
-    let content_char_count = content.chars().count();
-    lc_content.find(&lc_query).map(|pos| {
-        // Convert byte index in the lowercased string to a character index.
-        // IMPORTANT: Use lc_content[..pos], not content[..pos], because pos is a byte
-        // index valid only for the lowercased string (Unicode case mappings can change
-        // byte lengths, e.g., German ß → SS).
-        let start_char = lc_content[..pos].chars().count().saturating_sub(15);
-        let snippet: String = content.chars().skip(start_char).take(max_chars).collect();
-        // Check if we truncated: snippet covers chars [start_char, start_char + snippet_len)
-        let snippet_char_count = snippet.chars().count();
-        if start_char + snippet_char_count < content_char_count {
-            format!("{snippet}…")
-        } else {
-            snippet
+/// Byte range of a window of about `max_chars` characters centered on the
+/// densest cluster of `matches` (sorted byte ranges into `text`), or the start
+/// of `text` when nothing matched. Edges are moved to word boundaries when one
+/// is close.
+pub fn snippet_window(text: &str, matches: &[Range<usize>], max_chars: usize) -> Range<usize> {
+    let bounds: Vec<usize> = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(text.len()))
+        .collect();
+    let len = bounds.len() - 1;
+    if len <= max_chars {
+        return 0..text.len();
+    }
+    let char_at = |byte: usize| bounds.partition_point(|&b| b < byte);
+
+    // Center on the match with the most neighbours within half a window; the
+    // earliest wins ties.
+    let centers: Vec<usize> = matches
+        .iter()
+        .map(|m| (char_at(m.start) + char_at(m.end)) / 2)
+        .collect();
+    let center = centers
+        .iter()
+        .copied()
+        .max_by_key(|&c| {
+            let near = centers
+                .iter()
+                .filter(|&&o| o.abs_diff(c) <= max_chars / 2)
+                .count();
+            (near, std::cmp::Reverse(c))
+        })
+        .unwrap_or(0);
+    let mut start = center.saturating_sub(max_chars / 2).min(len - max_chars);
+    let mut end = start + max_chars;
+
+    let slack = max_chars / 8;
+    let is_space = |i: usize| text[bounds[i]..].starts_with(char::is_whitespace);
+    if start > 0
+        && let Some(k) = (start..start + slack).find(|&i| is_space(i))
+    {
+        start = k + 1;
+    }
+    if end < len
+        && let Some(k) = (end - slack..end).rev().find(|&i| is_space(i))
+    {
+        end = k;
+    }
+    bounds[start]..bounds[end]
+}
+
+/// Snippet of about `max_chars` characters centered on the best cluster of
+/// `matches`, with each match wrapped in `**` and `…` marking cut edges.
+pub fn centered_snippet(text: &str, matches: &[Range<usize>], max_chars: usize) -> String {
+    let window = snippet_window(text, matches, max_chars);
+    let mut out = String::new();
+    if window.start > 0 {
+        out.push('…');
+    }
+    let mut last = window.start;
+    for m in matches {
+        let (start, end) = (m.start.max(last), m.end.min(window.end));
+        if start >= end {
+            continue;
         }
-    })
+        out.push_str(&text[last..start]);
+        out.push_str("**");
+        out.push_str(&text[start..end]);
+        out.push_str("**");
+        last = end;
+    }
+    out.push_str(&text[last..window.end]);
+    if window.end < text.len() {
+        out.push('…');
+    }
+    out
 }
 
 fn filters_fingerprint(filters: &SearchFilters) -> String {
@@ -2135,12 +2860,44 @@ fn filters_fingerprint(filters: &SearchFilters) -> String {
         v.sort();
         parts.push(format!("w:{v:?}"));
     }
+    if !filters.roles.is_empty() {
+        let mut v: Vec<_> = filters.roles.iter().cloned().collect();
+        v.sort();
+        parts.push(format!("r:{v:?}"));
+    }
     if let Some(f) = filters.created_from {
         parts.push(format!("from:{f}"));
     }
     if let Some(t) = filters.created_to {
         parts.push(format!("to:{t}"));
     }
+    if let Some(d) = filters.recency {
+        parts.push(format!("decay:{}/{}", d.weight, d.half_life_days));
+    }
+    if filters.keep_duplicates {
+        parts.push("dupes".to_string());
+    }
+    if let Some(min) = filters.min_tokens {
+        parts.push(format!("tmin:{min}"));
+    }
+    if let Some(max) = filters.max_tokens {
+        parts.push(format!("tmax:{max}"));
+    }
+    if !filters.tools.is_empty() {
+        let mut v: Vec<_> = filters.tools.iter().cloned().collect();
+        v.sort();
+        parts.push(format!("tool:{v:?}"));
+    }
+    if !filters.files.is_empty() {
+        let mut v: Vec<_> = filters.files.iter().cloned().collect();
+        v.sort();
+        parts.push(format!("file:{v:?}"));
+    }
+    if let Some(paths) = &filters.source_paths {
+        let mut v: Vec<_> = paths.iter().cloned().collect();
+        v.sort();
+        parts.push(format!("src:{v:?}"));
+    }
     parts.join("|")
 }
 
@@ -2308,6 +3065,9 @@ mod tests {
             created_at: None,
             line_number: None,
             match_type: MatchType::Exact,
+            snippets: Vec::new(),
+            conversation_id: String::new(),
+            role: String::new(),
         }];
 
         client.put_cache("こん", &SearchFilters::default(), &hits);
@@ -2332,6 +3092,9 @@ mod tests {
             created_at: None,
             line_number: None,
             match_type: MatchType::Exact,
+            snippets: Vec::new(),
+            conversation_id: String::new(),
+            role: String::new(),
         };
         let cached = cached_hit_from(&hit);
         assert!(hit_matches_query_cached(&cached, "hello"));
@@ -2388,6 +3151,107 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn long_messages_get_snippets_centered_on_the_match() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut index = TantivyIndex::open_or_create(dir.path())?;
+        let filler = "the build passed and nothing else happened here. ".repeat(20);
+        let conv = NormalizedConversation {
+            agent_slug: "codex".into(),
+            external_id: None,
+            title: None,
+            workspace: None,
+            source_path: dir.path().join("rollout-long.jsonl"),
+            started_at: Some(1_700_000_000_000),
+            ended_at: None,
+            metadata: serde_json::json!({}),
+            messages: vec![NormalizedMessage {
+                idx: 0,
+                role: "assistant".into(),
+                author: None,
+                created_at: Some(1_700_000_000_000),
+                content: format!("{filler}The deadlock comes from the writer lock. {filler}"),
+                extra: serde_json::json!({}),
+                snippets: Vec::new(),
+            }],
+        };
+        index.add_conversation(&conv)?;
+        index.commit()?;
+
+        let client = SearchClient::open(dir.path(), None)?.expect("index present");
+        let hits = client.search("deadlock", SearchFilters::default(), 10, 0)?;
+        let snippet = &hits[0].snippet;
+        assert!(snippet.contains("The **deadlock** comes from"), "{snippet}");
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        // The window plus the two ellipses; `**` markers come on top.
+        assert!(snippet.replace("**", "").chars().count() <= SNIPPET_CHARS + 2);
+
+        let matches = match_ranges(&hits[0].content, &["deadlock".to_string()]);
+        let short = centered_snippet(&hits[0].content, &matches, 40);
+        assert!(short.contains("**deadlock**"), "{short}");
+        assert!(short.replace("**", "").chars().count() <= 42);
+        Ok(())
+    }
+
+    #[test]
+    fn search_filters_by_role_and_reports_conversation() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut index = TantivyIndex::open_or_create(dir.path())?;
+        let message = |idx: i64, role: &str, content: &str| NormalizedMessage {
+            idx,
+            role: role.into(),
+            author: None,
+            created_at: Some(1_700_000_000_000 + idx),
+            content: content.into(),
+            extra: serde_json::json!({}),
+            snippets: Vec::new(),
+        };
+        let conv = NormalizedConversation {
+            agent_slug: "claude_code".into(),
+            external_id: Some("session-1".into()),
+            title: None,
+            workspace: None,
+            source_path: dir.path().join("session-1.jsonl"),
+            started_at: Some(1_700_000_000_000),
+            ended_at: None,
+            metadata: serde_json::json!({}),
+            messages: vec![
+                message(0, "user", "why does the migration fail"),
+                message(1, "assistant", "the migration fails because of a lock"),
+                message(2, "tool", "migration log: lock timeout"),
+            ],
+        };
+        index.add_conversation(&conv)?;
+        index.commit()?;
+
+        let client = SearchClient::open(dir.path(), None)?.expect("index present");
+        let hits = client.search("migration", SearchFilters::default(), 10, 0)?;
+        assert_eq!(hits.len(), 3);
+        assert!(
+            hits.iter()
+                .all(|h| h.conversation_id == "claude_code:session-1")
+        );
+
+        let mut filters = SearchFilters::default();
+        filters.roles.insert("assistant".into());
+        let hits = client.search("migration", filters, 10, 0)?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].role, "agent");
+
+        let mut filters = SearchFilters::default();
+        filters
+            .roles
+            .extend(["user".to_string(), "tool".to_string()]);
+        let mut roles: Vec<_> = client
+            .search("migration", filters, 10, 0)?
+            .into_iter()
+            .map(|h| h.role)
+            .collect();
+        roles.sort();
+        assert_eq!(roles, ["tool", "user"]);
+        Ok(())
+    }
+
     #[test]
     fn search_honors_created_range_and_workspace() -> Result<()> {
         let dir = TempDir::new()?;
@@ -2657,6 +3521,102 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn fuzzy_terms_find_typos_and_rank_below_exact_matches() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut index = TantivyIndex::open_or_create(dir.path())?;
+        let messages = [
+            "the tokenizer splits words",
+            "a tokenzier typo in the notes",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, content)| NormalizedMessage {
+            idx: i as i64,
+            role: "user".into(),
+            author: None,
+            created_at: Some(1),
+            content: (*content).into(),
+            extra: serde_json::json!({}),
+            snippets: vec![],
+        })
+        .collect();
+        let conv = NormalizedConversation {
+            agent_slug: "codex".into(),
+            external_id: None,
+            title: Some("fuzzy".into()),
+            workspace: None,
+            source_path: dir.path().join("f.jsonl"),
+            started_at: Some(1),
+            ended_at: None,
+            metadata: serde_json::json!({}),
+            messages,
+        };
+        index.add_conversation(&conv)?;
+        index.commit()?;
+
+        let client = SearchClient::open(dir.path(), None)?.expect("index present");
+        let plain = client.search("tokenzier", SearchFilters::default(), 10, 0)?;
+        assert_eq!(plain.len(), 1);
+
+        let hits = client.search("tokenzier~", SearchFilters::default(), 10, 0)?;
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].content.contains("tokenzier"));
+        assert_eq!(hits[0].match_type, MatchType::Exact);
+        assert!(hits[1].content.contains("tokenizer"));
+        assert_eq!(hits[1].match_type, MatchType::Fuzzy);
+
+        let fuzzy = fuzzy_query("tokenzier");
+        assert_eq!(fuzzy, "tokenzier~");
+        let hits = client.search(&fuzzy, SearchFilters::default(), 10, 0)?;
+        assert_eq!(hits.len(), 2);
+
+        // `~0` is an exact search; very short terms are never widened.
+        assert_eq!(
+            client
+                .search("tokenzier~0", SearchFilters::default(), 10, 0)?
+                .len(),
+            1
+        );
+        assert_eq!(
+            WildcardPattern::parse("ab~"),
+            WildcardPattern::Fuzzy("ab".into(), 0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fuzzy_markers_are_parsed_and_applied_to_plain_terms_only() {
+        assert_eq!(
+            WildcardPattern::parse("Parser~"),
+            WildcardPattern::Fuzzy("parser".into(), 2)
+        );
+        assert_eq!(
+            WildcardPattern::parse("cache~1"),
+            WildcardPattern::Fuzzy("cache".into(), 1)
+        );
+        assert_eq!(
+            WildcardPattern::parse("cache~9"),
+            WildcardPattern::Fuzzy("cache".into(), 2)
+        );
+        assert_eq!(
+            WildcardPattern::parse("*cache~"),
+            WildcardPattern::Suffix("cache~".into())
+        );
+        assert_eq!(sanitize_query("foo~ ~bar baz~2"), "foo~  bar baz~2");
+        assert_eq!(strip_fuzzy("foo~ bar baz~2"), "foo bar baz");
+        assert_eq!(
+            fuzzy_query(r#"auth OR "race condition" cach* -lock retry~1"#),
+            r#"auth~ OR "race condition" cach* -lock retry~1"#
+        );
+        assert_eq!(
+            fuzzy_query("deploy NOT staging prod"),
+            "deploy~ NOT staging prod~"
+        );
+        assert_eq!(fuzzy_query("tool:bash perf"), "tool:bash perf~");
+        assert_eq!(dominant_match_type("auth~ login"), MatchType::Fuzzy);
+    }
+
     #[test]
     fn search_sets_match_type_for_wildcards() -> Result<()> {
         let dir = TempDir::new()?;
@@ -2739,6 +3699,290 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn commit_shas_and_other_long_tokens_are_searchable() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut index = TantivyIndex::open_or_create(dir.path())?;
+        let sha = "3a95cb7d1e0f4b8c9a2d6e5f708192a3b4c5d6e7";
+        let conv = NormalizedConversation {
+            agent_slug: "codex".into(),
+            external_id: None,
+            title: Some("bisect".into()),
+            workspace: None,
+            source_path: dir.path().join("sha.jsonl"),
+            started_at: Some(1),
+            ended_at: None,
+            metadata: serde_json::json!({}),
+            messages: vec![NormalizedMessage {
+                idx: 0,
+                role: "user".into(),
+                author: None,
+                created_at: Some(1),
+                content: format!("the regression landed in {sha}, revert it"),
+                extra: serde_json::json!({}),
+                snippets: vec![],
+            }],
+        };
+        index.add_conversation(&conv)?;
+        index.commit()?;
+
+        let client = SearchClient::open(dir.path(), None)?.expect("index present");
+        let hits = client.search(&sha.to_uppercase(), SearchFilters::default(), 10, 0)?;
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].snippet.contains(&format!("**{sha}**")));
+        // Short prefixes keep matching through the edge n-grams.
+        assert_eq!(
+            client
+                .search("3a95cb7", SearchFilters::default(), 10, 0)?
+                .len(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn recency_decay_ranks_the_newer_of_equal_hits_first() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut index = TantivyIndex::open_or_create(dir.path())?;
+        let now = chrono::Utc::now().timestamp_millis();
+        // Distinct content of equal length, so dedup keeps both and BM25 ties.
+        for (name, created_at) in [("old", now - 365 * 86_400_000), ("new", now - 86_400_000)] {
+            index.add_conversation(&NormalizedConversation {
+                agent_slug: "codex".into(),
+                external_id: None,
+                title: Some(name.into()),
+                workspace: None,
+                source_path: dir.path().join(format!("{name}.jsonl")),
+                started_at: Some(created_at),
+                ended_at: None,
+                metadata: serde_json::json!({}),
+                messages: vec![NormalizedMessage {
+                    idx: 0,
+                    role: "user".into(),
+                    author: None,
+                    created_at: Some(created_at),
+                    content: format!("flaky migration test in the {name} branch"),
+                    extra: serde_json::json!({}),
+                    snippets: vec![],
+                }],
+            })?;
+        }
+        index.commit()?;
+
+        let client = SearchClient::open(dir.path(), None)?.expect("index present");
+        let plain = client.search("migration", SearchFilters::default(), 10, 0)?;
+        assert_eq!(plain.len(), 2);
+        assert_eq!(plain[0].score, plain[1].score);
+
+        let filters = SearchFilters {
+            recency: Some(RecencyDecay {
+                weight: 1.0,
+                half_life_days: 30.0,
+            }),
+            ..Default::default()
+        };
+        let hits = client.search("migration", filters, 10, 0)?;
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].title, "new");
+        assert!(hits[0].score > hits[1].score * 1.5);
+        assert!(hits[0].score < plain[0].score * 2.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tool_and_file_atoms_match_tool_calls() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut index = TantivyIndex::open_or_create(dir.path())?;
+        let message = |idx: i64, content: &str, snippet: NormalizedSnippet| NormalizedMessage {
+            idx,
+            role: "assistant".into(),
+            author: None,
+            created_at: Some(1),
+            content: content.into(),
+            extra: serde_json::json!({}),
+            snippets: vec![snippet],
+        };
+        index.add_conversation(&NormalizedConversation {
+            agent_slug: "claude_code".into(),
+            external_id: None,
+            title: Some("profiling".into()),
+            workspace: None,
+            source_path: dir.path().join("tools.jsonl"),
+            started_at: Some(1),
+            ended_at: None,
+            metadata: serde_json::json!({}),
+            messages: vec![
+                message(
+                    0,
+                    "profiling the indexer",
+                    crate::connectors::tool_snippet(
+                        "Bash",
+                        &serde_json::json!({ "command": "cargo flamegraph --bin cass" }),
+                    ),
+                ),
+                message(
+                    1,
+                    "fixing the hot loop",
+                    crate::connectors::tool_snippet(
+                        "Edit",
+                        &serde_json::json!({ "file_path": "/home/me/app/src/Main.rs" }),
+                    ),
+                ),
+            ],
+        })?;
+        index.commit()?;
+
+        let client = SearchClient::open(dir.path(), None)?.expect("index present");
+        let contents = |query: &str| -> Result<Vec<String>> {
+            Ok(client
+                .search(query, SearchFilters::default(), 10, 0)?
+                .into_iter()
+                .map(|h| h.content)
+                .collect())
+        };
+        assert_eq!(contents("tool:bash flamegraph")?, ["profiling the indexer"]);
+        assert_eq!(contents("TOOL:Bash")?, ["profiling the indexer"]);
+        assert_eq!(contents("file:src/main.rs")?, ["fixing the hot loop"]);
+        assert_eq!(contents("file:./main.rs hot")?, ["fixing the hot loop"]);
+        assert!(contents("tool:read")?.is_empty());
+        assert!(contents("tool:bash hot")?.is_empty());
+        assert!(contents("file:app/main.rs")?.is_empty());
+        assert_eq!(
+            client
+                .count("tool:edit", &SearchFilters::default())?
+                .unwrap(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn misspelled_terms_get_did_you_mean_suggestions() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut index = TantivyIndex::open_or_create(dir.path())?;
+        let message = |idx: i64, content: &str| NormalizedMessage {
+            idx,
+            role: "user".into(),
+            author: None,
+            created_at: Some(1),
+            content: content.into(),
+            extra: serde_json::json!({}),
+            snippets: vec![],
+        };
+        index.add_conversation(&NormalizedConversation {
+            agent_slug: "codex".into(),
+            external_id: None,
+            title: Some("debugging".into()),
+            workspace: None,
+            source_path: dir.path().join("typos.jsonl"),
+            started_at: Some(1),
+            ended_at: None,
+            metadata: serde_json::json!({}),
+            messages: vec![
+                message(0, "the tokenizer panics on emoji"),
+                message(1, "a deadlock in the watcher"),
+                message(2, "tokens and tokenizers"),
+            ],
+        })?;
+        index.commit()?;
+
+        let client = SearchClient::open(dir.path(), None)?.expect("index present");
+        let fixes = |query: &str| -> Result<Vec<Option<String>>> {
+            Ok(client
+                .search_with_fallback(query, SearchFilters::default(), 10, 0, 3)?
+                .suggestions
+                .into_iter()
+                .filter(|s| matches!(s.kind, SuggestionKind::SpellingFix))
+                .map(|s| s.suggested_query)
+                .collect())
+        };
+        // No hits: the fix leads the usual suggestions.
+        assert_eq!(
+            fixes("tokenzier panics")?,
+            [Some("tokenizer panics".to_string())]
+        );
+        // A few hits: only the fix, for the term the index lacks.
+        assert_eq!(
+            fixes("emoji OR deadlok")?,
+            [Some("emoji OR deadlock".to_string())]
+        );
+        // Known words and far-off ones get no fix.
+        assert!(fixes("deadlock")?.is_empty());
+        assert!(fixes("qwxyzzy")?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn token_bounds_filter_by_message_length() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut index = TantivyIndex::open_or_create(dir.path())?;
+        let wall = "fn main() { println!(\"retry\"); } ".repeat(40);
+        let messages = [
+            "retry ok",
+            "retry the flaky step twice before failing the build",
+            &wall,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(idx, content)| NormalizedMessage {
+            idx: idx as i64,
+            role: "user".into(),
+            author: None,
+            created_at: Some(1),
+            content: content.into(),
+            extra: serde_json::json!({}),
+            snippets: vec![],
+        })
+        .collect();
+        index.add_conversation(&NormalizedConversation {
+            agent_slug: "codex".into(),
+            external_id: None,
+            title: None,
+            workspace: None,
+            source_path: dir.path().join("lengths.jsonl"),
+            started_at: Some(1),
+            ended_at: None,
+            metadata: serde_json::json!({}),
+            messages,
+        })?;
+        index.commit()?;
+
+        let client = SearchClient::open(dir.path(), None)?.expect("index present");
+        let lengths = |min_tokens, max_tokens| -> Result<Vec<usize>> {
+            let filters = SearchFilters {
+                min_tokens,
+                max_tokens,
+                ..Default::default()
+            };
+            let mut lens: Vec<usize> = client
+                .search("retry", filters, 10, 0)?
+                .into_iter()
+                .map(|h| h.content.len())
+                .collect();
+            lens.sort_unstable();
+            Ok(lens)
+        };
+        assert_eq!(lengths(None, None)?.len(), 3);
+        assert_eq!(lengths(Some(5), None)?, [51, wall.len()]);
+        assert_eq!(lengths(None, Some(100))?, [8, 51]);
+        assert_eq!(lengths(Some(5), Some(100))?, [51]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn extract_atoms_moves_tool_and_file_into_filters() {
+        let mut filters = SearchFilters::default();
+        let rest = extract_atoms("tool:Bash perf file:.\\src\\lib.rs tool: a:b", &mut filters);
+        assert_eq!(rest, "perf tool: a:b");
+        assert_eq!(filters.tools, HashSet::from(["bash".to_string()]));
+        assert_eq!(filters.files, HashSet::from(["src/lib.rs".to_string()]));
+    }
+
     #[test]
     fn sqlite_backend_skips_wildcard_queries() -> Result<()> {
         // Build a client with SQLite only; wildcard queries should short-circuit without errors.
@@ -2882,6 +4126,9 @@ mod tests {
             created_at: None,
             line_number: None,
             match_type: MatchType::Exact,
+            snippets: Vec::new(),
+            conversation_id: String::new(),
+            role: String::new(),
         };
         let hits = vec![hit];
 
@@ -2931,6 +4178,9 @@ mod tests {
             created_at: None,
             line_number: None,
             match_type: MatchType::Exact,
+            snippets: Vec::new(),
+            conversation_id: String::new(),
+            role: String::new(),
         };
         let hits = vec![hit.clone()];
 
@@ -3007,6 +4257,9 @@ mod tests {
             created_at: None,
             line_number: None,
             match_type: MatchType::Exact,
+            snippets: Vec::new(),
+            conversation_id: String::new(),
+            role: String::new(),
         };
 
         // Put 3 entries - should trigger 1 eviction (cap is 2)
@@ -3064,6 +4317,9 @@ mod tests {
             created_at: None,
             line_number: None,
             match_type: MatchType::Exact,
+            snippets: Vec::new(),
+            conversation_id: String::new(),
+            role: String::new(),
         };
 
         // Put 3 large entries - should trigger byte-based evictions
@@ -3347,6 +4603,9 @@ mod tests {
                 created_at: Some(100),
                 line_number: None,
                 match_type: MatchType::Exact,
+                snippets: Vec::new(),
+                conversation_id: String::new(),
+                role: String::new(),
             },
             SearchHit {
                 title: "title2".into(),
@@ -3359,6 +4618,9 @@ mod tests {
                 created_at: Some(200),
                 line_number: None,
                 match_type: MatchType::Exact,
+                snippets: Vec::new(),
+                conversation_id: String::new(),
+                role: String::new(),
             },
         ];
 
@@ -3382,6 +4644,9 @@ mod tests {
                 created_at: Some(100),
                 line_number: None,
                 match_type: MatchType::Exact,
+                snippets: Vec::new(),
+                conversation_id: String::new(),
+                role: String::new(),
             },
             SearchHit {
                 title: "title2".into(),
@@ -3394,6 +4659,9 @@ mod tests {
                 created_at: Some(200),
                 line_number: None,
                 match_type: MatchType::Exact,
+                snippets: Vec::new(),
+                conversation_id: String::new(),
+                role: String::new(),
             },
         ];
 
@@ -3417,6 +4685,9 @@ mod tests {
                 created_at: Some(100),
                 line_number: None,
                 match_type: MatchType::Exact,
+                snippets: Vec::new(),
+                conversation_id: String::new(),
+                role: String::new(),
             },
             SearchHit {
                 title: "title2".into(),
@@ -3429,6 +4700,9 @@ mod tests {
                 created_at: Some(200),
                 line_number: None,
                 match_type: MatchType::Exact,
+                snippets: Vec::new(),
+                conversation_id: String::new(),
+                role: String::new(),
             },
         ];
 
@@ -3436,6 +4710,41 @@ mod tests {
         assert_eq!(deduped.len(), 1); // normalized to same content
     }
 
+    #[test]
+    fn deduplicate_hits_merges_copies_from_two_connectors() {
+        let claude = SearchHit {
+            title: "fix login".into(),
+            snippet: String::new(),
+            content: "Run `cargo test` then **retry** the login flow.".into(),
+            score: 0.7,
+            source_path: "a.jsonl".into(),
+            agent: "claude_code".into(),
+            workspace: "ws".into(),
+            created_at: Some(100),
+            line_number: None,
+            match_type: MatchType::Exact,
+            snippets: Vec::new(),
+            conversation_id: String::new(),
+            role: String::new(),
+        };
+        let extension = SearchHit {
+            content: "run cargo test then retry the login flow".into(),
+            score: 0.9,
+            source_path: "b.json".into(),
+            agent: "cline".into(),
+            ..claude.clone()
+        };
+        let other = SearchHit {
+            content: "run cargo test then retry the signup flow".into(),
+            ..claude.clone()
+        };
+
+        let deduped = deduplicate_hits(vec![claude.clone(), extension.clone(), other.clone()]);
+        assert_eq!(deduped.len(), 2);
+        assert_eq!(deduped[0].agent, "cline");
+        assert_eq!(drop_tool_noise(vec![claude, extension, other]).len(), 3);
+    }
+
     #[test]
     fn deduplicate_hits_filters_tool_noise() {
         let hits = vec![
@@ -3450,6 +4759,9 @@ mod tests {
                 created_at: Some(100),
                 line_number: None,
                 match_type: MatchType::Exact,
+                snippets: Vec::new(),
+                conversation_id: String::new(),
+                role: String::new(),
             },
             SearchHit {
                 title: "title2".into(),
@@ -3462,6 +4774,9 @@ mod tests {
                 created_at: Some(200),
                 line_number: None,
                 match_type: MatchType::Exact,
+                snippets: Vec::new(),
+                conversation_id: String::new(),
+                role: String::new(),
             },
         ];
 
@@ -3484,6 +4799,9 @@ mod tests {
                 created_at: Some(100),
                 line_number: None,
                 match_type: MatchType::Exact,
+                snippets: Vec::new(),
+                conversation_id: String::new(),
+                role: String::new(),
             },
             SearchHit {
                 title: "title2".into(),
@@ -3496,6 +4814,9 @@ mod tests {
                 created_at: Some(200),
                 line_number: None,
                 match_type: MatchType::Exact,
+                snippets: Vec::new(),
+                conversation_id: String::new(),
+                role: String::new(),
             },
             SearchHit {
                 title: "title3".into(),
@@ -3508,6 +4829,9 @@ mod tests {
                 created_at: Some(300),
                 line_number: None,
                 match_type: MatchType::Exact,
+                snippets: Vec::new(),
+                conversation_id: String::new(),
+                role: String::new(),
             },
         ];
 
@@ -1,15 +1,18 @@
-use std::path::Path;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicI64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{Result, anyhow};
+use tantivy::query::{BooleanQuery, Occur, Query, TermQuery};
 use tantivy::schema::{
-    FAST, Field, INDEXED, IndexRecordOption, STORED, STRING, Schema, TEXT, TextFieldIndexing,
-    TextOptions,
+    FAST, Field, FieldType, INDEXED, IndexRecordOption, STORED, STRING, Schema, TEXT,
+    TextFieldIndexing, TextOptions,
 };
-use tantivy::{Index, IndexReader, IndexWriter, doc};
+use tantivy::{Index, IndexReader, IndexWriter, Term, doc};
 use tracing::{debug, info, warn};
 
+use crate::config::Analyzer;
 use crate::connectors::NormalizedConversation;
 
 const SCHEMA_VERSION: &str = "v4";
@@ -39,6 +42,27 @@ pub struct MergeStatus {
 }
 
 impl MergeStatus {
+    /// Status of an index with `segment_count` searchable segments.
+    fn for_segments(segment_count: usize) -> Self {
+        let last_merge_ts = LAST_MERGE_TS.load(Ordering::Relaxed);
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+        let ms_since_last = if last_merge_ts > 0 {
+            now_ms - last_merge_ts
+        } else {
+            -1 // never merged
+        };
+        MergeStatus {
+            segment_count,
+            last_merge_ts,
+            ms_since_last_merge: ms_since_last,
+            merge_threshold: MERGE_SEGMENT_THRESHOLD,
+            cooldown_ms: MERGE_COOLDOWN_MS,
+        }
+    }
+
     /// Returns true if merge is recommended based on current status
     pub fn should_merge(&self) -> bool {
         self.segment_count >= self.merge_threshold
@@ -47,7 +71,28 @@ impl MergeStatus {
 }
 
 // Bump this when schema/tokenizer changes. Used to trigger rebuilds.
-pub const SCHEMA_HASH: &str = "tantivy-schema-v4-edge-ngram-agent-string";
+pub const SCHEMA_HASH: &str = "tantivy-schema-v13-conversation-doc-keys";
+
+/// Tokenizers of the message text fields, one per [`Analyzer`]. The schema stores the
+/// name, so an index is always searched with the analyzer it was built with.
+const SIMPLE_TOKENIZER: &str = "hyphen_normalize";
+const ENGLISH_TOKENIZER: &str = "english_stem";
+/// Tokenizer of `long_tokens`: words as written, with no length limit.
+const LONG_TOKENIZER: &str = "long_token";
+
+/// Words of at least this many bytes are dropped by the text analyzers (a Tantivy
+/// term cannot be arbitrarily long) and indexed whole in `long_tokens` instead, so a
+/// pasted commit SHA or token fragment still matches.
+pub const LONG_TOKEN_BYTES: usize = 40;
+
+/// [`SCHEMA_HASH`] qualified by the configured `[index] analyzer`: switching analyzers
+/// outdates the index like a schema change, so the next `cass index` rebuilds it.
+pub fn schema_hash() -> String {
+    match crate::config::analyzer() {
+        Analyzer::Simple => SCHEMA_HASH.to_string(),
+        Analyzer::English => format!("{SCHEMA_HASH}+english"),
+    }
+}
 
 #[derive(Clone, Copy)]
 pub struct Fields {
@@ -61,12 +106,58 @@ pub struct Fields {
     pub title_prefix: Field,
     pub content_prefix: Field,
     pub preview: Field,
+    /// Tool commands, touched file paths and code blocks, searchable.
+    pub snippets: Field,
+    /// The message's snippets as JSON, returned with hits.
+    pub snippets_json: Field,
+    /// [`doc_key`] of the message, so re-indexing replaces its document.
+    pub doc_key: Field,
+    /// [`conversation_key`] of the message's conversation, for grouping hits.
+    pub conversation_id: Field,
+    /// Who wrote the message, spelled as in the database (see [`role_term`]).
+    pub role: Field,
+    /// The message's original JSON, stored only when `[index] store_raw_json` is set.
+    pub raw_json: Field,
+    /// [`approx_token_count`] of the content, for range filters and totals.
+    pub token_count: Field,
+    /// [`long_tokens`] of the title, content and snippets, for exact lookups.
+    pub long_tokens: Field,
+    /// Lowercased names of the tools the message called (`tool:` atoms).
+    pub tools: Field,
+    /// [`path_suffixes`] of the files the message's tool calls touched (`file:` atoms).
+    pub files: Field,
+    /// Title, content and snippets hold English stems; query terms must be stemmed too.
+    pub stemmed: bool,
+}
+
+/// Returned (inside `anyhow::Error`) when another process holds the index's writer lock.
+/// Readers are unaffected: searches open the index without taking the lock.
+#[derive(Debug, thiserror::Error)]
+#[error("search index at {} is locked by another writer (a running `cass index` or watcher)", path.display())]
+pub struct IndexLocked {
+    pub path: PathBuf,
+}
+
+/// True while another writer holds the lock on the index at `path`.
+pub fn writer_locked(path: &Path) -> bool {
+    use tantivy::Directory;
+    if !path.join(".tantivy-writer.lock").exists() {
+        return false;
+    }
+    let Ok(dir) = tantivy::directory::MmapDirectory::open(path) else {
+        return false;
+    };
+    matches!(
+        dir.acquire_lock(&tantivy::directory::INDEX_WRITER_LOCK),
+        Err(tantivy::directory::error::LockError::LockBusy)
+    )
 }
 
 pub struct TantivyIndex {
     pub index: Index,
     writer: IndexWriter,
     pub fields: Fields,
+    store_raw_json: bool,
 }
 
 impl TantivyIndex {
@@ -74,18 +165,25 @@ impl TantivyIndex {
         // Schema we will use if we need to (re)create the index.
         let schema = build_schema();
         std::fs::create_dir_all(path)?;
+        // Never recreate an index under a live writer.
+        if writer_locked(path) {
+            return Err(IndexLocked {
+                path: path.to_path_buf(),
+            }
+            .into());
+        }
 
         let meta_path = path.join("schema_hash.json");
-        let mut needs_rebuild = true;
-        if meta_path.exists()
-            && let Ok(meta) = std::fs::read_to_string(&meta_path)
-            && let Ok(json) = serde_json::from_str::<serde_json::Value>(&meta)
-            && json.get("schema_hash").and_then(|v| v.as_str()) == Some(SCHEMA_HASH)
-        {
-            needs_rebuild = false;
-        }
+        let schema_hash = schema_hash();
+        let needs_rebuild = stored_schema_hash(path).as_deref() != Some(schema_hash.as_str());
 
         if needs_rebuild {
+            if path.join("meta.json").exists() {
+                warn!(
+                    path = %path.display(),
+                    "search index was built with another schema; recreating it empty"
+                );
+            }
             // Recreate index directory completely to avoid stale lock files or
             // stale tantivy internals.
             let _ = std::fs::remove_dir_all(path);
@@ -114,27 +212,118 @@ impl TantivyIndex {
         ensure_tokenizer(&mut index);
 
         // Always write the current schema hash so future runs can detect mismatches.
-        std::fs::write(&meta_path, format!("{{\"schema_hash\":\"{SCHEMA_HASH}\"}}"))?;
+        std::fs::write(&meta_path, format!("{{\"schema_hash\":\"{schema_hash}\"}}"))?;
 
         // Use the schema actually attached to this index to derive field ids.
         // This avoids subtle field-id mismatches if the on-disk index was created
         // by a slightly different binary.
         let actual_schema = index.schema();
-        let writer = index
-            .writer(50_000_000)
-            .map_err(|e| anyhow!("create index writer: {e:?}"))?;
+        let writer = index.writer(50_000_000).map_err(|e| match e {
+            tantivy::TantivyError::LockFailure(
+                tantivy::directory::error::LockError::LockBusy,
+                _,
+            ) => IndexLocked {
+                path: path.to_path_buf(),
+            }
+            .into(),
+            e => anyhow!("create index writer: {e:?}"),
+        })?;
         let fields = fields_from_schema(&actual_schema)?;
         Ok(Self {
             index,
             writer,
             fields,
+            store_raw_json: crate::config::store_raw_json(),
         })
     }
 
+    /// Store (or stop storing) messages' original JSON, overriding the config file.
+    pub fn with_raw_json(mut self, store: bool) -> Self {
+        self.store_raw_json = store;
+        self
+    }
+
+    /// Index `conv`, replacing whatever was indexed for it before, so a conversation that
+    /// was rewritten with fewer messages loses the extra ones.
     pub fn add_conversation(&mut self, conv: &NormalizedConversation) -> Result<()> {
+        self.delete_conversation(&conversation_key(
+            &conv.agent_slug,
+            conv.external_id.as_deref(),
+            &conv.source_path.to_string_lossy(),
+        ))?;
         self.add_messages(conv, &conv.messages)
     }
 
+    /// Remove every document read from `source_path`; takes effect on the next commit.
+    pub fn delete_source_path(&mut self, source_path: &Path) {
+        self.writer.delete_term(Term::from_field_text(
+            self.fields.source_path,
+            &source_path.to_string_lossy(),
+        ));
+    }
+
+    /// Remove the message documents of the conversation with this [`conversation_key`];
+    /// its notes stay, as [`sync_notes`](Self::sync_notes) owns them. Takes effect on the
+    /// next commit.
+    pub fn delete_conversation(&mut self, conversation_id: &str) -> Result<()> {
+        let term_query = |field, text: &str| -> Box<dyn Query> {
+            Box::new(TermQuery::new(
+                Term::from_field_text(field, text),
+                IndexRecordOption::Basic,
+            ))
+        };
+        self.writer.delete_query(Box::new(BooleanQuery::new(vec![
+            (
+                Occur::Must,
+                term_query(self.fields.conversation_id, conversation_id),
+            ),
+            (
+                Occur::MustNot,
+                term_query(self.fields.role, crate::notes::NOTE_ROLE),
+            ),
+        ])))?;
+        Ok(())
+    }
+
+    /// Replace every note document with one per entry of `notes` (see [`crate::notes`]);
+    /// takes effect on the next commit.
+    pub fn sync_notes(&mut self, notes: &[crate::notes::Note]) -> Result<()> {
+        self.writer.delete_term(Term::from_field_text(
+            self.fields.role,
+            crate::notes::NOTE_ROLE,
+        ));
+        for note in notes {
+            let mut d = doc! {
+                self.fields.doc_key => format!("{}#note-{}", note.source_path, note.id),
+                self.fields.conversation_id => conversation_key(
+                    &note.agent,
+                    note.external_id.as_deref(),
+                    &note.source_path,
+                ),
+                self.fields.role => crate::notes::NOTE_ROLE,
+                self.fields.agent => note.agent.clone(),
+                self.fields.source_path => note.source_path.clone(),
+                self.fields.created_at => note.created_at,
+                self.fields.token_count => approx_token_count(&note.text),
+                self.fields.content => note.text.clone(),
+                self.fields.content_prefix => generate_edge_ngrams(&note.text),
+                self.fields.preview => build_preview(&note.text, 400),
+            };
+            if let Some(idx) = note.msg_idx {
+                d.add_u64(self.fields.msg_idx, idx.max(0) as u64);
+            }
+            if !note.workspace.is_empty() {
+                d.add_text(self.fields.workspace, &note.workspace);
+            }
+            if !note.title.is_empty() {
+                d.add_text(self.fields.title, &note.title);
+                d.add_text(self.fields.title_prefix, generate_edge_ngrams(&note.title));
+            }
+            self.writer.add_document(d)?;
+        }
+        Ok(())
+    }
+
     pub fn delete_all(&mut self) -> Result<()> {
         self.writer.delete_all_documents()?;
         Ok(())
@@ -159,23 +348,13 @@ impl TantivyIndex {
 
     /// Returns debug info about merge status
     pub fn merge_status(&self) -> MergeStatus {
-        let last_merge_ts = LAST_MERGE_TS.load(Ordering::Relaxed);
-        let now_ms = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_millis() as i64)
-            .unwrap_or(0);
-        let ms_since_last = if last_merge_ts > 0 {
-            now_ms - last_merge_ts
-        } else {
-            -1 // never merged
-        };
-        MergeStatus {
-            segment_count: self.segment_count(),
-            last_merge_ts,
-            ms_since_last_merge: ms_since_last,
-            merge_threshold: MERGE_SEGMENT_THRESHOLD,
-            cooldown_ms: MERGE_COOLDOWN_MS,
-        }
+        MergeStatus::for_segments(self.segment_count())
+    }
+
+    /// Remove files no longer referenced by any segment, e.g. those a merge replaced.
+    pub fn garbage_collect(&self) -> Result<()> {
+        self.writer.garbage_collect_files().wait()?;
+        Ok(())
     }
 
     /// Attempt to merge segments if idle conditions are met.
@@ -252,16 +431,32 @@ impl TantivyIndex {
         }
     }
 
+    /// Index `messages` of `conv`, replacing any documents already indexed for them,
+    /// so indexing the same session twice leaves one document per message. Use it for
+    /// messages appended to a conversation; [`add_conversation`](Self::add_conversation)
+    /// replaces the whole conversation.
     pub fn add_messages(
         &mut self,
         conv: &NormalizedConversation,
         messages: &[crate::connectors::NormalizedMessage],
     ) -> Result<()> {
+        let conversation_id = conversation_key(
+            &conv.agent_slug,
+            conv.external_id.as_deref(),
+            &conv.source_path.to_string_lossy(),
+        );
         for msg in messages {
+            let key = doc_key(&conversation_id, msg.idx);
+            self.writer
+                .delete_term(Term::from_field_text(self.fields.doc_key, &key));
             let mut d = doc! {
+                self.fields.doc_key => key,
+                self.fields.conversation_id => conversation_id.clone(),
+                self.fields.role => role_term(&msg.role),
                 self.fields.agent => conv.agent_slug.clone(),
                 self.fields.source_path => conv.source_path.to_string_lossy().into_owned(),
                 self.fields.msg_idx => msg.idx as u64,
+                self.fields.token_count => approx_token_count(&msg.content),
                 self.fields.content => msg.content.clone(),
             };
             if let Some(ws) = &conv.workspace {
@@ -279,12 +474,85 @@ impl TantivyIndex {
                 generate_edge_ngrams(&msg.content),
             );
             d.add_text(self.fields.preview, build_preview(&msg.content, 400));
+            let snippet_text = snippet_search_text(&msg.snippets);
+            let long = [
+                conv.title.as_deref().unwrap_or(""),
+                &msg.content,
+                &snippet_text,
+            ]
+            .map(long_tokens)
+            .join(" ");
+            if !long.trim().is_empty() {
+                d.add_text(self.fields.long_tokens, long);
+            }
+            if self.store_raw_json && !msg.extra.is_null() {
+                d.add_text(self.fields.raw_json, serde_json::to_string(&msg.extra)?);
+            }
+            let mut tools = BTreeSet::new();
+            let mut files = BTreeSet::new();
+            for snippet in &msg.snippets {
+                tools.extend(tool_name(snippet));
+                if let Some(path) = &snippet.file_path {
+                    files.extend(path_suffixes(&path.to_string_lossy()));
+                }
+            }
+            for tool in tools {
+                d.add_text(self.fields.tools, tool);
+            }
+            for file in files {
+                d.add_text(self.fields.files, file);
+            }
+            if !msg.snippets.is_empty() {
+                d.add_text(self.fields.snippets, snippet_text);
+                d.add_text(
+                    self.fields.snippets_json,
+                    serde_json::to_string(&msg.snippets)?,
+                );
+            }
             self.writer.add_document(d)?;
         }
         Ok(())
     }
 }
 
+/// Identity of a message's document: its conversation's [`conversation_key`] and its
+/// position in the conversation. Not the source file, which several conversations share
+/// for agents that keep them all in one database (Cursor, OpenCode, Crush, ...).
+pub fn doc_key(conversation_id: &str, msg_idx: i64) -> String {
+    format!("{conversation_id}#{msg_idx}")
+}
+
+/// Identity of a conversation across the index and the database: the agent plus the
+/// conversation's external id, or its source file when the agent has none.
+pub fn conversation_key(agent: &str, external_id: Option<&str>, source_path: &str) -> String {
+    format!("{agent}:{}", external_id.unwrap_or(source_path))
+}
+
+/// Approximate BPE token count of `text`, close to what cl100k-style tokenizers
+/// produce: a run of letters or digits costs one token per four characters, and
+/// every other non-space character costs one.
+pub fn approx_token_count(text: &str) -> u64 {
+    let mut tokens = 0;
+    let mut run: u64 = 0;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            run += 1;
+            continue;
+        }
+        tokens += run.div_ceil(4);
+        run = 0;
+        if !c.is_whitespace() {
+            tokens += 1;
+        }
+    }
+    tokens + run.div_ceil(4)
+}
+
+/// A message role as the database stores it: connectors' `assistant` is `agent`.
+pub fn role_term(role: &str) -> &str {
+    if role == "assistant" { "agent" } else { role }
+}
+
 fn generate_edge_ngrams(text: &str) -> String {
     let mut ngrams = String::with_capacity(text.len() * 2);
     // Split by non-alphanumeric characters to identify words
@@ -304,34 +572,88 @@ fn generate_edge_ngrams(text: &str) -> String {
     ngrams
 }
 
+/// Words of `text` too long for the text analyzers (see [`LONG_TOKEN_BYTES`]), split
+/// the way the analyzers split words.
+fn long_tokens(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() >= LONG_TOKEN_BYTES)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The tool a `[Tool: name] ...` snippet (see [`crate::connectors::tool_snippet`])
+/// records, lowercased.
+fn tool_name(snippet: &crate::connectors::NormalizedSnippet) -> Option<String> {
+    let rest = snippet.snippet_text.as_deref()?.strip_prefix("[Tool: ")?;
+    let name = rest.split(']').next()?.trim();
+    (!name.is_empty()).then(|| name.to_lowercase())
+}
+
+/// `path` as a `file:` atom matches it: lowercased, with `/` separators and no
+/// leading `./` or `/`.
+pub fn file_atom_term(path: &str) -> String {
+    let path = path.trim().replace('\\', "/").to_lowercase();
+    let path = path.strip_prefix("./").unwrap_or(&path);
+    path.trim_matches('/').to_string()
+}
+
+/// Every trailing run of components of `path`, as [`file_atom_term`]s, so
+/// `file:main.rs` and `file:src/main.rs` both match `/home/me/app/src/main.rs`.
+fn path_suffixes(path: &str) -> Vec<String> {
+    let path = file_atom_term(path);
+    let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+    (0..parts.len()).map(|i| parts[i..].join("/")).collect()
+}
+
+/// The schema for the configured `[index] analyzer`.
 pub fn build_schema() -> Schema {
     let mut schema_builder = Schema::builder();
+    let indexing = |tokenizer| {
+        TextFieldIndexing::default()
+            .set_tokenizer(tokenizer)
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions)
+    };
+    let words = match crate::config::analyzer() {
+        Analyzer::Simple => SIMPLE_TOKENIZER,
+        Analyzer::English => ENGLISH_TOKENIZER,
+    };
     let text = TextOptions::default()
-        .set_indexing_options(
-            TextFieldIndexing::default()
-                .set_tokenizer("hyphen_normalize")
-                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
-        )
+        .set_indexing_options(indexing(words))
         .set_stored();
-
-    let text_not_stored = TextOptions::default().set_indexing_options(
+    let text_not_stored = TextOptions::default().set_indexing_options(indexing(words));
+    // Edge n-grams are prefixes of the words as written; stemming would mangle them.
+    let prefixes = TextOptions::default().set_indexing_options(indexing(SIMPLE_TOKENIZER));
+    let long_tokens = TextOptions::default().set_indexing_options(
         TextFieldIndexing::default()
-            .set_tokenizer("hyphen_normalize")
-            .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+            .set_tokenizer(LONG_TOKENIZER)
+            .set_index_option(IndexRecordOption::Basic),
     );
 
     // Use STRING (not TEXT) so agent slug is stored as a single non-tokenized term.
     // This ensures exact match filtering works correctly with TermQuery.
     schema_builder.add_text_field("agent", STRING | STORED);
     schema_builder.add_text_field("workspace", STRING | STORED);
-    schema_builder.add_text_field("source_path", STORED);
+    // STRING so every document of a deleted session file can be removed by term.
+    schema_builder.add_text_field("source_path", STRING | STORED);
     schema_builder.add_u64_field("msg_idx", INDEXED | STORED);
     schema_builder.add_i64_field("created_at", INDEXED | STORED | FAST);
     schema_builder.add_text_field("title", text.clone());
     schema_builder.add_text_field("content", text);
-    schema_builder.add_text_field("title_prefix", text_not_stored.clone());
-    schema_builder.add_text_field("content_prefix", text_not_stored);
+    schema_builder.add_text_field("title_prefix", prefixes.clone());
+    schema_builder.add_text_field("content_prefix", prefixes);
     schema_builder.add_text_field("preview", TEXT | STORED);
+    schema_builder.add_text_field("snippets", text_not_stored);
+    schema_builder.add_text_field("snippets_json", STORED);
+    schema_builder.add_text_field("doc_key", STRING | STORED);
+    schema_builder.add_text_field("conversation_id", STRING | STORED);
+    schema_builder.add_text_field("role", STRING | STORED);
+    // Stored only: the doc store is compressed, and nothing searches the raw JSON.
+    schema_builder.add_text_field("raw_json", STORED);
+    schema_builder.add_u64_field("token_count", INDEXED | STORED | FAST);
+    schema_builder.add_text_field("long_tokens", long_tokens);
+    // Whole terms, for the `tool:` and `file:` query atoms.
+    schema_builder.add_text_field("tools", STRING);
+    schema_builder.add_text_field("files", STRING);
     schema_builder.build()
 }
 
@@ -352,9 +674,48 @@ pub fn fields_from_schema(schema: &Schema) -> Result<Fields> {
         title_prefix: get("title_prefix")?,
         content_prefix: get("content_prefix")?,
         preview: get("preview")?,
+        snippets: get("snippets")?,
+        snippets_json: get("snippets_json")?,
+        doc_key: get("doc_key")?,
+        conversation_id: get("conversation_id")?,
+        role: get("role")?,
+        raw_json: get("raw_json")?,
+        token_count: get("token_count")?,
+        long_tokens: get("long_tokens")?,
+        tools: get("tools")?,
+        files: get("files")?,
+        stemmed: tokenizer_of(schema, "content") == Some(ENGLISH_TOKENIZER),
     })
 }
 
+fn tokenizer_of<'a>(schema: &'a Schema, name: &str) -> Option<&'a str> {
+    let field = schema.get_field(name).ok()?;
+    match schema.get_field_entry(field).field_type() {
+        FieldType::Str(options) => options.get_indexing_options().map(|i| i.tokenizer()),
+        _ => None,
+    }
+}
+
+fn snippet_search_text(snippets: &[crate::connectors::NormalizedSnippet]) -> String {
+    let mut out = String::new();
+    for s in snippets {
+        for part in [
+            s.file_path
+                .as_ref()
+                .map(|p| p.to_string_lossy().into_owned()),
+            s.language.clone(),
+            s.snippet_text.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            out.push_str(&part);
+            out.push('\n');
+        }
+    }
+    out
+}
+
 fn build_preview(content: &str, max_chars: usize) -> String {
     let mut out = String::new();
     let mut chars = content.chars();
@@ -377,8 +738,21 @@ fn build_preview(content: &str, max_chars: usize) -> String {
     out
 }
 
+/// Versioned index path under `base`, without touching the filesystem.
+/// Read-only callers use this so a query never creates directories.
+pub fn index_path(base: &Path) -> std::path::PathBuf {
+    base.join("index").join(SCHEMA_VERSION)
+}
+
+/// The [`schema_hash`] the index at `path` was built with, if it has one.
+pub fn stored_schema_hash(path: &Path) -> Option<String> {
+    let meta = std::fs::read_to_string(path.join("schema_hash.json")).ok()?;
+    let json = serde_json::from_str::<serde_json::Value>(&meta).ok()?;
+    json.get("schema_hash")?.as_str().map(str::to_string)
+}
+
 pub fn index_dir(base: &Path) -> Result<std::path::PathBuf> {
-    let dir = base.join("index").join(SCHEMA_VERSION);
+    let dir = index_path(base);
     std::fs::create_dir_all(&dir)?;
     Ok(dir)
 }
@@ -387,9 +761,297 @@ pub fn ensure_tokenizer(index: &mut Index) {
     use tantivy::tokenizer::{LowerCaser, RemoveLongFilter, SimpleTokenizer, TextAnalyzer};
     let analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
         .filter(LowerCaser)
-        .filter(RemoveLongFilter::limit(40))
+        .filter(RemoveLongFilter::limit(LONG_TOKEN_BYTES))
         .build();
-    index.tokenizers().register("hyphen_normalize", analyzer);
+    index.tokenizers().register(SIMPLE_TOKENIZER, analyzer);
+    index
+        .tokenizers()
+        .register(ENGLISH_TOKENIZER, english_analyzer());
+    let long = TextAnalyzer::builder(SimpleTokenizer::default())
+        .filter(LowerCaser)
+        .build();
+    index.tokenizers().register(LONG_TOKENIZER, long);
+}
+
+fn english_analyzer() -> tantivy::tokenizer::TextAnalyzer {
+    use tantivy::tokenizer::{
+        Language, LowerCaser, RemoveLongFilter, SimpleTokenizer, Stemmer, TextAnalyzer,
+    };
+    TextAnalyzer::builder(SimpleTokenizer::default())
+        .filter(LowerCaser)
+        .filter(RemoveLongFilter::limit(LONG_TOKEN_BYTES))
+        .filter(Stemmer::new(Language::English))
+        .build()
+}
+
+/// `term` as the English analyzer indexes it ("retrying" → "retri"). Input that is
+/// not a single word comes back unchanged.
+pub fn stem_term(term: &str) -> String {
+    thread_local! {
+        static ANALYZER: std::cell::RefCell<tantivy::tokenizer::TextAnalyzer> =
+            std::cell::RefCell::new(english_analyzer());
+    }
+    ANALYZER.with(|analyzer| {
+        let mut analyzer = analyzer.borrow_mut();
+        let mut stream = analyzer.token_stream(term);
+        let mut tokens = Vec::new();
+        stream.process(&mut |token| tokens.push(token.text.clone()));
+        match tokens.as_slice() {
+            [stem] => stem.clone(),
+            _ => term.to_string(),
+        }
+    })
+}
+
+/// What the index under an `index_path` holds, for `cass stats`. Counts skip
+/// documents deleted but not yet merged away.
+#[derive(Debug, Clone, Default)]
+pub struct IndexStats {
+    pub docs: u64,
+    pub segments: usize,
+    pub size_bytes: u64,
+    /// Sum of the documents' [`approx_token_count`].
+    pub tokens: u64,
+    /// Document count per agent slug, largest first.
+    pub by_agent: Vec<(String, u64)>,
+    /// Document count per workspace, largest first; documents without one are left out.
+    pub by_workspace: Vec<(String, u64)>,
+    /// Oldest and newest message `created_at` (ms).
+    pub oldest: Option<i64>,
+    pub newest: Option<i64>,
+}
+
+/// Read [`IndexStats`] from the index at `path` without taking the writer lock.
+pub fn index_stats(path: &Path) -> Result<IndexStats> {
+    let mut index = Index::open_in_dir(path)?;
+    ensure_tokenizer(&mut index);
+    let fields = fields_from_schema(&index.schema())?;
+    let searcher = index.reader()?.searcher();
+
+    let mut stats = IndexStats {
+        docs: searcher.num_docs(),
+        segments: searcher.segment_readers().len(),
+        size_bytes: dir_size(path),
+        ..IndexStats::default()
+    };
+    let mut by_agent = std::collections::HashMap::new();
+    let mut by_workspace = std::collections::HashMap::new();
+    for segment in searcher.segment_readers() {
+        count_terms(segment, fields.agent, &mut by_agent)?;
+        count_terms(segment, fields.workspace, &mut by_workspace)?;
+        let created_at = segment.fast_fields().i64("created_at")?;
+        let token_count = segment.fast_fields().u64("token_count")?;
+        for doc in segment.doc_ids_alive() {
+            stats.tokens += token_count.first(doc).unwrap_or(0);
+            if let Some(ts) = created_at.first(doc) {
+                stats.oldest = Some(stats.oldest.map_or(ts, |o| o.min(ts)));
+                stats.newest = Some(stats.newest.map_or(ts, |n| n.max(ts)));
+            }
+        }
+    }
+    stats.by_agent = sorted_counts(by_agent);
+    stats.by_workspace = sorted_counts(by_workspace);
+    Ok(stats)
+}
+
+/// The original JSON of every message indexed from `source_path`, in conversation
+/// order, from the index at `path`. Empty unless the index was built with
+/// `[index] store_raw_json`.
+pub fn raw_messages(path: &Path, source_path: &Path) -> Result<Vec<serde_json::Value>> {
+    use tantivy::collector::DocSetCollector;
+    use tantivy::query::TermQuery;
+    use tantivy::schema::Value;
+
+    let mut index = Index::open_in_dir(path)?;
+    ensure_tokenizer(&mut index);
+    let fields = fields_from_schema(&index.schema())?;
+    let searcher = index.reader()?.searcher();
+    let query = TermQuery::new(
+        Term::from_field_text(fields.source_path, &source_path.to_string_lossy()),
+        IndexRecordOption::Basic,
+    );
+    let mut messages = Vec::new();
+    for addr in searcher.search(&query, &DocSetCollector)? {
+        let doc: tantivy::TantivyDocument = searcher.doc(addr)?;
+        let idx = doc.get_first(fields.msg_idx).and_then(|v| v.as_u64());
+        if let Some(raw) = doc.get_first(fields.raw_json).and_then(|v| v.as_str()) {
+            messages.push((idx, serde_json::from_str(raw)?));
+        }
+    }
+    messages.sort_by_key(|(idx, _)| *idx);
+    Ok(messages.into_iter().map(|(_, raw)| raw).collect())
+}
+
+/// Something `cass doctor --index` found wrong with the index at an `index_path`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IndexProblem {
+    /// No `meta.json`: the index was never built or its directory was removed.
+    Missing,
+    /// `schema_hash.json` is absent, unreadable, or names another schema.
+    SchemaMismatch { found: Option<String> },
+    /// `meta.json` (or the schema inside it) cannot be loaded.
+    MetaUnreadable { error: String },
+    /// A segment whose files are missing, truncated, or fail their checksum.
+    DamagedSegment { segment: String, error: String },
+}
+
+impl IndexProblem {
+    /// Whether the whole index has to be rebuilt, rather than single segments.
+    pub fn needs_full_rebuild(&self) -> bool {
+        !matches!(self, Self::DamagedSegment { .. })
+    }
+}
+
+/// Validate the index at `path`: `meta.json`, the schema hash, and every segment's
+/// files and checksums. Returns no problems for a healthy index.
+pub fn check_index(path: &Path) -> Vec<IndexProblem> {
+    if !path.join("meta.json").exists() {
+        return vec![IndexProblem::Missing];
+    }
+    let mut problems = Vec::new();
+    let found = std::fs::read_to_string(path.join("schema_hash.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|json| json["schema_hash"].as_str().map(String::from));
+    if found.as_deref() != Some(schema_hash().as_str()) {
+        problems.push(IndexProblem::SchemaMismatch { found });
+    }
+    let index = match Index::open_in_dir(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|index| fields_from_schema(&index.schema()).map(|_| index))
+    {
+        Ok(index) => index,
+        Err(e) => {
+            problems.push(IndexProblem::MetaUnreadable {
+                error: format!("{e:#}"),
+            });
+            return problems;
+        }
+    };
+    let segments = match index.searchable_segments() {
+        Ok(segments) => segments,
+        Err(e) => {
+            problems.push(IndexProblem::MetaUnreadable {
+                error: e.to_string(),
+            });
+            return problems;
+        }
+    };
+    for segment in segments {
+        if let Err(error) = check_segment(&index, &segment) {
+            problems.push(IndexProblem::DamagedSegment {
+                segment: segment.id().uuid_string(),
+                error,
+            });
+        }
+    }
+    problems
+}
+
+fn check_segment(index: &Index, segment: &tantivy::Segment) -> std::result::Result<(), String> {
+    tantivy::SegmentReader::open(segment).map_err(|e| e.to_string())?;
+    let mut files: Vec<_> = segment.meta().list_files().into_iter().collect();
+    files.sort();
+    for file in files {
+        if !tantivy::Directory::exists(index.directory(), &file).unwrap_or(false) {
+            continue; // optional component, e.g. no deletes yet
+        }
+        match index.directory().validate_checksum(&file) {
+            Ok(true) => {}
+            Ok(false) => return Err(format!("checksum mismatch in {}", file.display())),
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Ok(())
+}
+
+/// Remove `segment_ids` from the index's `meta.json`, so the index opens without them.
+/// Their documents are gone until re-added; their files are cleaned up by the next
+/// writer's garbage collection.
+pub fn drop_segments(path: &Path, segment_ids: &[String]) -> Result<()> {
+    let meta_path = path.join("meta.json");
+    let mut meta: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&meta_path)?)?;
+    let segments = meta["segments"]
+        .as_array_mut()
+        .ok_or_else(|| anyhow!("{} lists no segments", meta_path.display()))?;
+    segments.retain(|seg| {
+        let id = seg["segment_id"]
+            .as_str()
+            .unwrap_or_default()
+            .replace('-', "");
+        !segment_ids.contains(&id)
+    });
+    let tmp = path.join("meta.json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(&meta)?)?;
+    std::fs::rename(&tmp, &meta_path)?;
+    Ok(())
+}
+
+/// Merge status of the index at `path`, read without taking the writer lock.
+pub fn read_merge_status(path: &Path) -> Result<MergeStatus> {
+    let index = Index::open_in_dir(path)?;
+    Ok(MergeStatus::for_segments(
+        index.searchable_segment_ids()?.len(),
+    ))
+}
+
+/// Live document count per source path in the index at `path`.
+pub fn source_path_counts(path: &Path) -> Result<std::collections::HashMap<String, u64>> {
+    let mut index = Index::open_in_dir(path)?;
+    ensure_tokenizer(&mut index);
+    let fields = fields_from_schema(&index.schema())?;
+    let searcher = index.reader()?.searcher();
+    let mut counts = std::collections::HashMap::new();
+    for segment in searcher.segment_readers() {
+        count_terms(segment, fields.source_path, &mut counts)?;
+    }
+    Ok(counts)
+}
+
+/// Add the live document count of every term of `field` in `segment` to `counts`.
+fn count_terms(
+    segment: &tantivy::SegmentReader,
+    field: Field,
+    counts: &mut std::collections::HashMap<String, u64>,
+) -> Result<()> {
+    let inverted = segment.inverted_index(field)?;
+    let mut terms = inverted.terms().stream()?;
+    while terms.advance() {
+        let live = match segment.alive_bitset() {
+            Some(alive) => inverted
+                .read_postings_from_terminfo(terms.value(), IndexRecordOption::Basic)?
+                .doc_freq_given_deletes(alive),
+            None => terms.value().doc_freq,
+        };
+        if live > 0 {
+            *counts
+                .entry(String::from_utf8_lossy(terms.key()).into_owned())
+                .or_default() += u64::from(live);
+        }
+    }
+    Ok(())
+}
+
+fn sorted_counts(counts: std::collections::HashMap<String, u64>) -> Vec<(String, u64)> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// Bytes on disk used by the index at `path`.
+pub fn index_size(path: &Path) -> u64 {
+    dir_size(path)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
 }
 
 // =============================================================================
@@ -570,6 +1232,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_held_writer_lock_is_reported_and_never_wiped() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path();
+        assert!(!writer_locked(path));
+
+        let writer = TantivyIndex::open_or_create(path).unwrap();
+        assert!(writer_locked(path));
+        fs::write(path.join("schema_hash.json"), r#"{"schema_hash":"old"}"#).unwrap();
+        let err = TantivyIndex::open_or_create(path).err().unwrap();
+        assert!(err.is::<IndexLocked>(), "{err:#}");
+        assert!(
+            path.join("meta.json").exists(),
+            "the live index is left alone"
+        );
+        assert_eq!(read_merge_status(path).unwrap().segment_count, 0);
+
+        drop(writer);
+        assert!(!writer_locked(path));
+        assert!(TantivyIndex::open_or_create(path).is_ok());
+    }
+
+    #[test]
+    fn check_index_detects_the_corruption_cases() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path();
+        assert_eq!(check_index(path), vec![IndexProblem::Missing]);
+
+        {
+            let mut index = TantivyIndex::open_or_create(path).unwrap();
+            for (source, text) in [("/a", "first"), ("/b", "second")] {
+                index
+                    .writer
+                    .add_document(doc! {
+                        index.fields.source_path => source,
+                        index.fields.content => text,
+                    })
+                    .unwrap();
+                index.commit().unwrap();
+            }
+        }
+        assert!(check_index(path).is_empty());
+
+        // A truncated .store file damages one segment; dropping it leaves the other.
+        let store = fs::read_dir(path)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .find(|p| p.extension().is_some_and(|e| e == "store"))
+            .unwrap();
+        fs::OpenOptions::new()
+            .write(true)
+            .open(&store)
+            .unwrap()
+            .set_len(10)
+            .unwrap();
+        let problems = check_index(path);
+        let [IndexProblem::DamagedSegment { segment, .. }] = problems.as_slice() else {
+            panic!("{problems:?}");
+        };
+        assert!(store.to_string_lossy().contains(segment.as_str()));
+        drop_segments(path, std::slice::from_ref(segment)).unwrap();
+        assert!(check_index(path).is_empty());
+        assert_eq!(source_path_counts(path).unwrap().len(), 1);
+
+        fs::write(path.join("schema_hash.json"), r#"{"schema_hash":"old"}"#).unwrap();
+        fs::write(path.join("meta.json"), "corrupted meta content").unwrap();
+        let problems = check_index(path);
+        assert_eq!(
+            problems[0],
+            IndexProblem::SchemaMismatch {
+                found: Some("old".into())
+            }
+        );
+        assert!(matches!(problems[1], IndexProblem::MetaUnreadable { .. }));
+    }
+
     #[test]
     fn open_or_create_roundtrip_add_and_search() {
         let dir = TempDir::new().unwrap();
@@ -648,6 +1386,15 @@ mod tests {
         assert!(schema.get_field("preview").is_ok());
     }
 
+    #[test]
+    fn english_stems_fold_inflections() {
+        assert_eq!(stem_term("retrying"), "retri");
+        assert_eq!(stem_term("Retries"), stem_term("retry"));
+        assert_eq!(stem_term("foo-bar"), "foo-bar");
+        // The default analyzer leaves the word fields unstemmed.
+        assert!(!fields_from_schema(&build_schema()).unwrap().stemmed);
+    }
+
     #[test]
     fn fields_from_schema_extracts_all_fields() {
         let schema = build_schema();
@@ -664,6 +1411,152 @@ mod tests {
         let _ = fields.title_prefix;
         let _ = fields.content_prefix;
         let _ = fields.preview;
+        let _ = fields.doc_key;
+        let _ = fields.conversation_id;
+        let _ = fields.role;
+        let _ = fields.raw_json;
+        let _ = fields.token_count;
+    }
+
+    #[test]
+    fn token_counts_follow_words_and_punctuation() {
+        assert_eq!(approx_token_count(""), 0);
+        assert_eq!(approx_token_count("ok"), 1);
+        assert_eq!(approx_token_count("hello world"), 4);
+        assert_eq!(approx_token_count("fn main() {}"), 6);
+        assert_eq!(approx_token_count("naïve café"), 3);
+    }
+
+    #[test]
+    fn reindexing_a_conversation_replaces_its_documents() {
+        let dir = TempDir::new().unwrap();
+        let mut index = TantivyIndex::open_or_create(dir.path()).unwrap();
+        let conv = NormalizedConversation {
+            agent_slug: "codex".into(),
+            external_id: Some("s1".into()),
+            title: Some("title".into()),
+            workspace: None,
+            source_path: "/logs/s1.jsonl".into(),
+            started_at: Some(1),
+            ended_at: Some(2),
+            metadata: serde_json::Value::Null,
+            messages: (0..3)
+                .map(|idx| crate::connectors::NormalizedMessage {
+                    idx,
+                    role: "user".into(),
+                    author: None,
+                    created_at: Some(1),
+                    content: format!("message {idx}"),
+                    extra: serde_json::Value::Null,
+                    snippets: Vec::new(),
+                })
+                .collect(),
+        };
+
+        index.add_conversation(&conv).unwrap();
+        index.commit().unwrap();
+        index.add_conversation(&conv).unwrap();
+        index.add_messages(&conv, &conv.messages[..1]).unwrap();
+        index.commit().unwrap();
+
+        let reader = index.reader().unwrap();
+        reader.reload().unwrap();
+        assert_eq!(reader.searcher().num_docs(), 3);
+    }
+
+    #[test]
+    fn reindexing_a_shrunken_conversation_drops_its_extra_messages() {
+        let dir = TempDir::new().unwrap();
+        let mut index = TantivyIndex::open_or_create(dir.path()).unwrap();
+        let conv = |messages: i64| NormalizedConversation {
+            agent_slug: "codex".into(),
+            external_id: Some("s1".into()),
+            title: None,
+            workspace: None,
+            source_path: "/logs/s1.jsonl".into(),
+            started_at: Some(1),
+            ended_at: None,
+            metadata: serde_json::Value::Null,
+            messages: (0..messages)
+                .map(|idx| crate::connectors::NormalizedMessage {
+                    idx,
+                    role: "user".into(),
+                    author: None,
+                    created_at: Some(1),
+                    content: format!("message {idx}"),
+                    extra: serde_json::Value::Null,
+                    snippets: Vec::new(),
+                })
+                .collect(),
+        };
+        let note = crate::notes::Note {
+            id: 1,
+            source_path: "/logs/s1.jsonl".into(),
+            msg_idx: None,
+            text: "keep me".into(),
+            agent: "codex".into(),
+            workspace: String::new(),
+            title: String::new(),
+            external_id: Some("s1".into()),
+            created_at: 1,
+        };
+
+        index.add_conversation(&conv(5)).unwrap();
+        index.sync_notes(&[note]).unwrap();
+        index.commit().unwrap();
+        index.add_conversation(&conv(2)).unwrap();
+        index.commit().unwrap();
+
+        let reader = index.reader().unwrap();
+        reader.reload().unwrap();
+        // Two messages and the note.
+        assert_eq!(reader.searcher().num_docs(), 3);
+    }
+
+    #[test]
+    fn conversations_sharing_a_source_file_keep_all_their_messages() {
+        use tantivy::collector::Count;
+        use tantivy::query::TermQuery;
+        use tantivy::schema::IndexRecordOption;
+
+        let dir = TempDir::new().unwrap();
+        let mut index = TantivyIndex::open_or_create(dir.path()).unwrap();
+        let conv = |id: &str| NormalizedConversation {
+            agent_slug: "crush".into(),
+            external_id: Some(id.into()),
+            title: None,
+            workspace: None,
+            source_path: "/home/me/.crush/crush.db".into(),
+            started_at: Some(1),
+            ended_at: None,
+            metadata: serde_json::Value::Null,
+            messages: (0..2)
+                .map(|idx| crate::connectors::NormalizedMessage {
+                    idx,
+                    role: "user".into(),
+                    author: None,
+                    created_at: Some(1),
+                    content: format!("{id}word{idx}"),
+                    extra: serde_json::Value::Null,
+                    snippets: Vec::new(),
+                })
+                .collect(),
+        };
+        index.add_conversation(&conv("alpha")).unwrap();
+        index.add_conversation(&conv("beta")).unwrap();
+        index.commit().unwrap();
+
+        let reader = index.reader().unwrap();
+        reader.reload().unwrap();
+        let searcher = reader.searcher();
+        assert_eq!(searcher.num_docs(), 4);
+        for word in ["alphaword0", "alphaword1", "betaword0", "betaword1"] {
+            let query = TermQuery::new(
+                Term::from_field_text(index.fields.content, word),
+                IndexRecordOption::Basic,
+            );
+            assert_eq!(searcher.search(&query, &Count).unwrap(), 1, "{word}");
+        }
     }
 
     #[test]
@@ -762,6 +1655,17 @@ mod tests {
         assert!(result.exists());
     }
 
+    #[test]
+    fn index_path_does_not_create_directories() {
+        let dir = TempDir::new().unwrap();
+        let result = index_path(dir.path());
+
+        assert_eq!(result, index_dir(dir.path()).unwrap());
+        std::fs::remove_dir_all(dir.path().join("index")).unwrap();
+        let _ = index_path(dir.path());
+        assert!(!dir.path().join("index").exists());
+    }
+
     // =============================================================================
     // Full Index Rebuild Tests (tst.idx.rebuild)
     // Tests for complete index rebuild scenarios
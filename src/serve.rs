@@ -0,0 +1,465 @@
+//! HTTP API (`cass serve`).
+//!
+//! Every endpoint runs the matching robot-mode command as a child process of the current
+//! executable and answers with its stdout, so responses have exactly the JSON shapes of
+//! `--robot` output and a request that fails cannot take the server down. Searches still
+//! go through a running `cass daemon`, which keeps them fast.
+//!
+//! | Endpoint          | Command                      |
+//! |-------------------|------------------------------|
+//! | `GET /search?q=`  | `cass search <q> --robot`    |
+//! | `GET /list`       | `cass list --robot`          |
+//! | `GET /show?path=` | `cass show <path> --robot`   |
+//! | `GET /stats`      | `cass stats --json`          |
+//! | `POST /index`     | `cass index --json`          |
+//! | `GET /health`     | answered by the server       |
+//!
+//! Query parameters become the command's long flags (`agent=codex&agent=amp` is
+//! `--agent codex --agent amp`, `today=true` is `--today`); only flags that read data are
+//! accepted, anything else is a 400. Failed commands answer with their robot error on
+//! stderr, under a status derived from the exit code.
+//!
+//! `/show` only opens sessions the index knows: any other path is a 404, so the server
+//! cannot be used to read arbitrary files. `POST /index` needs a token, which keeps a
+//! cross-site form post from starting a reindex.
+
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Requests handled at once; each one runs a child process.
+const WORKERS: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct ServeOptions {
+    pub host: String,
+    pub port: u16,
+    /// Required as `Authorization: Bearer <token>` on every endpoint but `/health`;
+    /// without one the endpoints that write are refused.
+    pub token: Option<String>,
+    /// Origins allowed to call the API from a browser; `*` allows any.
+    pub cors_origins: Vec<String>,
+    /// Refuse `POST /index`, for deployments shared with a team.
+    pub read_only: bool,
+    pub data_dir: PathBuf,
+    pub db: Option<PathBuf>,
+}
+
+struct Endpoint {
+    path: &'static str,
+    method: Method,
+    /// Subcommand and its robot flag.
+    command: &'static [&'static str],
+    /// Query parameter passed as the positional argument.
+    positional: Option<&'static str>,
+    /// Flags taking a value; they may repeat.
+    values: &'static [&'static str],
+    /// Flags set by `name=true`.
+    switches: &'static [&'static str],
+    writes: bool,
+    /// The positional argument must be the source path of an indexed session.
+    indexed_path: bool,
+}
+
+const ENDPOINTS: &[Endpoint] = &[
+    Endpoint {
+        path: "/search",
+        method: Method::Get,
+        command: &["search", "--robot"],
+        positional: Some("q"),
+        values: &[
+            "agent",
+            "workspace",
+            "limit",
+            "offset",
+            "cursor",
+            "fields",
+            "max-content-length",
+            "max-tokens",
+            "request-id",
+            "days",
+            "since",
+            "until",
+            "aggregate",
+            "timeout",
+            "snippet-chars",
+            "group-by",
+            "group-size",
+            "recency",
+            "min-message-tokens",
+            "max-message-tokens",
+            "tag",
+            "saved",
+        ],
+        switches: &[
+            "today",
+            "yesterday",
+            "week",
+            "robot-meta",
+            "explain",
+            "highlight",
+            "fuzzy",
+            "semantic",
+            "bookmarked",
+        ],
+        writes: false,
+        indexed_path: false,
+    },
+    Endpoint {
+        path: "/list",
+        method: Method::Get,
+        command: &["list", "--robot"],
+        positional: None,
+        values: &["agent", "workspace", "since", "until", "limit"],
+        switches: &[],
+        writes: false,
+        indexed_path: false,
+    },
+    Endpoint {
+        path: "/show",
+        method: Method::Get,
+        command: &["show", "--robot"],
+        positional: Some("path"),
+        values: &["around-msg", "context"],
+        switches: &[],
+        writes: false,
+        indexed_path: true,
+    },
+    Endpoint {
+        path: "/stats",
+        method: Method::Get,
+        command: &["stats", "--json"],
+        positional: None,
+        values: &[],
+        switches: &[],
+        writes: false,
+        indexed_path: false,
+    },
+    Endpoint {
+        path: "/index",
+        method: Method::Post,
+        command: &["index", "--json"],
+        positional: None,
+        values: &[],
+        switches: &["full", "dry-run"],
+        writes: true,
+        indexed_path: false,
+    },
+];
+
+/// Serve the API until the process is killed, reporting the bound address through
+/// `on_listen` (useful with port 0).
+pub fn serve(opts: &ServeOptions, on_listen: impl FnOnce(std::net::SocketAddr)) -> Result<()> {
+    let server = Server::http((opts.host.as_str(), opts.port))
+        .map_err(|e| anyhow!("cannot listen on {}:{}: {e}", opts.host, opts.port))?;
+    let addr = server
+        .server_addr()
+        .to_ip()
+        .ok_or_else(|| anyhow!("server is not listening on an IP address"))?;
+    tracing::info!(%addr, "http api listening");
+    on_listen(addr);
+
+    let server = Arc::new(server);
+    std::thread::scope(|s| {
+        for _ in 0..WORKERS {
+            let server = server.clone();
+            s.spawn(move || {
+                while let Ok(request) = server.recv() {
+                    handle(opts, request);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+fn handle(opts: &ServeOptions, request: Request) {
+    let origin = header(&request, "Origin");
+    let cors = allowed_origin(&opts.cors_origins, origin.as_deref());
+    let (path, query) = match request.url().split_once('?') {
+        Some((path, query)) => (path.to_string(), query.to_string()),
+        None => (request.url().to_string(), String::new()),
+    };
+
+    let (status, body) = if *request.method() == Method::Options {
+        (204, String::new())
+    } else if path == "/health" {
+        (
+            200,
+            serde_json::json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }).to_string(),
+        )
+    } else if !authorized(
+        opts.token.as_deref(),
+        header(&request, "Authorization").as_deref(),
+    ) {
+        error(401, "unauthorized", "missing or wrong bearer token")
+    } else {
+        match ENDPOINTS.iter().find(|e| e.path == path) {
+            None => error(404, "not_found", &format!("no endpoint {path}")),
+            Some(endpoint) if *request.method() != endpoint.method => error(
+                405,
+                "method_not_allowed",
+                &format!("{path} takes {}", endpoint.method),
+            ),
+            Some(endpoint) if endpoint.writes && opts.read_only => {
+                error(403, "read_only", "this server was started with --read-only")
+            }
+            Some(endpoint) if endpoint.writes && opts.token.is_none() => error(
+                403,
+                "token_required",
+                &format!("{path} needs the server started with --token"),
+            ),
+            Some(endpoint) => match command_args(endpoint, &query) {
+                Ok(args) => match positional_arg(endpoint, &args) {
+                    Some(session) if endpoint.indexed_path => match is_indexed(opts, session) {
+                        Ok(true) => run(opts, &args),
+                        Ok(false) => error(
+                            404,
+                            "not_found",
+                            &format!("no indexed session at {session}"),
+                        ),
+                        Err(e) => error(503, "no_index", &format!("cannot read the index: {e:#}")),
+                    },
+                    _ => run(opts, &args),
+                },
+                Err(message) => error(400, "usage", &message),
+            },
+        }
+    };
+
+    let mut response = Response::from_string(body).with_status_code(status);
+    if status != 204 {
+        response.add_header(header_pair("Content-Type", "application/json"));
+    }
+    if let Some(allowed) = cors {
+        response.add_header(header_pair("Access-Control-Allow-Origin", &allowed));
+        response.add_header(header_pair("Vary", "Origin"));
+        if *request.method() == Method::Options {
+            response.add_header(header_pair(
+                "Access-Control-Allow-Methods",
+                "GET, POST, OPTIONS",
+            ));
+            response.add_header(header_pair(
+                "Access-Control-Allow-Headers",
+                "Authorization, Content-Type",
+            ));
+            response.add_header(header_pair("Access-Control-Max-Age", "600"));
+        }
+    }
+    if let Err(e) = request.respond(response) {
+        tracing::warn!(error = %e, "http write failed");
+    }
+}
+
+/// Run `cass <args>` against the server's data dir, mapping its exit code to a status.
+fn run(opts: &ServeOptions, args: &[String]) -> (u16, String) {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => return error(500, "internal", &format!("cannot locate cass: {e}")),
+    };
+    let mut cmd = Command::new(exe);
+    if let Some(db) = &opts.db {
+        cmd.arg("--db").arg(db);
+    }
+    cmd.args(&args[..1])
+        .arg("--data-dir")
+        .arg(&opts.data_dir)
+        .args(&args[1..])
+        .stdin(Stdio::null());
+    let output = match cmd.output() {
+        Ok(output) => output,
+        Err(e) => return error(500, "internal", &format!("cannot run cass: {e}")),
+    };
+    if output.status.success() {
+        return (200, String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+    let status = status_for_exit(output.status.code());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // Robot mode reports errors as one JSON object; anything else gets wrapped.
+    match stderr
+        .lines()
+        .rev()
+        .find_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+    {
+        Some(json) if json.get("error").is_some() => {
+            // Exit code 3 covers both a missing index and a missing session file.
+            let kind = json["error"]["kind"].as_str().unwrap_or_default();
+            let status = if kind.ends_with("not-found") || kind.ends_with("not_found") {
+                404
+            } else {
+                status
+            };
+            (status, json.to_string())
+        }
+        _ => error(status, "command_failed", stderr.trim()),
+    }
+}
+
+/// The command line for `endpoint` from the request's query string.
+fn command_args(endpoint: &Endpoint, query: &str) -> Result<Vec<String>, String> {
+    let mut args: Vec<String> = endpoint.command.iter().map(|s| s.to_string()).collect();
+    let mut positional = None;
+    for (name, value) in form_urlencoded::parse(query.as_bytes()) {
+        let flag = name.replace('_', "-");
+        if Some(name.as_ref()) == endpoint.positional {
+            positional = Some(value.into_owned());
+        } else if endpoint.values.contains(&flag.as_str()) {
+            args.push(format!("--{flag}"));
+            args.push(value.into_owned());
+        } else if endpoint.switches.contains(&flag.as_str()) {
+            match value.as_ref() {
+                "" | "1" | "true" => args.push(format!("--{flag}")),
+                "0" | "false" => {}
+                other => return Err(format!("{name} must be true or false, not '{other}'")),
+            }
+        } else {
+            return Err(format!("unknown parameter '{name}' for {}", endpoint.path));
+        }
+    }
+    if let Some(value) = positional {
+        // After `--`, a query or path starting with '-' is not read as a flag.
+        args.push("--".to_string());
+        args.push(value);
+    }
+    Ok(args)
+}
+
+/// The positional argument [`command_args`] put after `--`, if any.
+fn positional_arg<'a>(endpoint: &Endpoint, args: &'a [String]) -> Option<&'a str> {
+    endpoint.positional?;
+    match args {
+        [.., sep, value] if sep == "--" => Some(value),
+        _ => None,
+    }
+}
+
+/// Whether `source_path` is the source of a conversation in the server's database.
+fn is_indexed(opts: &ServeOptions, source_path: &str) -> Result<bool> {
+    let db = opts
+        .db
+        .clone()
+        .unwrap_or_else(|| opts.data_dir.join("agent_search.db"));
+    let storage = crate::storage::sqlite::SqliteStorage::open_readonly(&db)?;
+    let found = storage
+        .raw()
+        .prepare_cached("SELECT 1 FROM conversations WHERE source_path = ?1 LIMIT 1")?
+        .exists([source_path])?;
+    Ok(found)
+}
+
+fn status_for_exit(code: Option<i32>) -> u16 {
+    match code {
+        Some(2) => 400,
+        Some(4) => 404,
+        Some(5) => 409,
+        Some(3 | 7) => 503,
+        Some(10) => 504,
+        _ => 500,
+    }
+}
+
+/// The `Access-Control-Allow-Origin` value for a request from `origin`, if allowed.
+fn allowed_origin(allowed: &[String], origin: Option<&str>) -> Option<String> {
+    if allowed.iter().any(|a| a == "*") {
+        return Some("*".to_string());
+    }
+    let origin = origin?;
+    allowed
+        .iter()
+        .any(|a| a.trim_end_matches('/') == origin)
+        .then(|| origin.to_string())
+}
+
+fn authorized(token: Option<&str>, authorization: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    let Some(given) = authorization.and_then(|a| a.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    // Compare every byte so the time taken does not reveal the matching prefix.
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn header(request: &Request, name: &str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|h| h.field.as_str().as_str().eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str().to_string())
+}
+
+fn header_pair(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("valid header")
+}
+
+fn error(status: u16, kind: &str, message: &str) -> (u16, String) {
+    let body = serde_json::json!({
+        "schema": crate::schema_tag("error"),
+        "error": { "code": status, "kind": kind, "message": message },
+    });
+    (status, body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(path: &str) -> &'static Endpoint {
+        ENDPOINTS.iter().find(|e| e.path == path).unwrap()
+    }
+
+    #[test]
+    fn query_parameters_become_read_only_flags() {
+        let args = command_args(
+            endpoint("/search"),
+            "q=-auth+error&agent=codex&agent=amp&today=true&fuzzy=false&max_tokens=50",
+        )
+        .unwrap();
+        assert_eq!(
+            args,
+            [
+                "search",
+                "--robot",
+                "--agent",
+                "codex",
+                "--agent",
+                "amp",
+                "--today",
+                "--max-tokens",
+                "50",
+                "--",
+                "-auth error"
+            ]
+        );
+        assert!(command_args(endpoint("/search"), "q=x&save=mine").is_err());
+        assert!(command_args(endpoint("/list"), "today=maybe").is_err());
+    }
+
+    #[test]
+    fn bearer_token_and_cors_origins_are_checked() {
+        assert!(authorized(None, None));
+        assert!(authorized(Some("s3cret"), Some("Bearer s3cret")));
+        assert!(!authorized(Some("s3cret"), Some("Bearer s3cre")));
+        assert!(!authorized(Some("s3cret"), None));
+
+        let allowed = vec!["https://team.example/".to_string()];
+        assert_eq!(
+            allowed_origin(&allowed, Some("https://team.example")).as_deref(),
+            Some("https://team.example")
+        );
+        assert_eq!(allowed_origin(&allowed, Some("https://evil.example")), None);
+        assert_eq!(
+            allowed_origin(&["*".to_string()], None).as_deref(),
+            Some("*")
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Tags attached to sessions with `cass tag add <path> <tag>...`.
+//!
+//! Tags belong to the user, not to the agent's files, so they live in `tags.json` in the
+//! data dir, next to `saved_searches.json`, rather than in the database: `cass index --full`
+//! rebuilds the database from session files and would lose them. `cass search --tag`
+//! turns a tag into the set of session paths carrying it.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+const FILE_NAME: &str = "tags.json";
+
+/// Tags by session source path.
+pub type SessionTags = BTreeMap<String, BTreeSet<String>>;
+
+/// Where the tags live for `data_dir`.
+pub fn file_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(FILE_NAME)
+}
+
+/// A tag as stored and matched: trimmed and lowercased, so `Auth` and `auth` are one tag.
+pub fn normalize(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').to_lowercase()
+}
+
+/// Every session's tags under `data_dir`; a missing or unreadable file is empty.
+pub fn load(data_dir: &Path) -> SessionTags {
+    std::fs::read(file_path(data_dir))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// The tags of the session at `source_path`.
+pub fn tags_for(data_dir: &Path, source_path: &str) -> BTreeSet<String> {
+    load(data_dir).remove(source_path).unwrap_or_default()
+}
+
+/// Add `tags` to the session at `source_path`, returning its tags afterwards.
+pub fn add(data_dir: &Path, source_path: &str, tags: &[String]) -> Result<BTreeSet<String>> {
+    let mut all = load(data_dir);
+    let entry = all.entry(source_path.to_string()).or_default();
+    entry.extend(tags.iter().map(|t| normalize(t)).filter(|t| !t.is_empty()));
+    let updated = entry.clone();
+    save(data_dir, &all)?;
+    Ok(updated)
+}
+
+/// Remove `tags` from the session at `source_path` (all of them when `tags` is empty),
+/// returning its tags afterwards.
+pub fn remove(data_dir: &Path, source_path: &str, tags: &[String]) -> Result<BTreeSet<String>> {
+    let mut all = load(data_dir);
+    let mut remaining = BTreeSet::new();
+    if let Some(entry) = all.get_mut(source_path) {
+        if tags.is_empty() {
+            entry.clear();
+        } else {
+            for tag in tags {
+                entry.remove(&normalize(tag));
+            }
+        }
+        remaining = entry.clone();
+    }
+    if remaining.is_empty() {
+        all.remove(source_path);
+    }
+    save(data_dir, &all)?;
+    Ok(remaining)
+}
+
+/// Source paths of the sessions carrying any of `tags`.
+pub fn paths_with_any(data_dir: &Path, tags: &[String]) -> HashSet<String> {
+    let wanted: HashSet<String> = tags.iter().map(|t| normalize(t)).collect();
+    load(data_dir)
+        .into_iter()
+        .filter(|(_, tags)| tags.iter().any(|t| wanted.contains(t)))
+        .map(|(path, _)| path)
+        .collect()
+}
+
+fn save(data_dir: &Path, all: &SessionTags) -> Result<()> {
+    let path = file_path(data_dir);
+    std::fs::create_dir_all(data_dir)
+        .with_context(|| format!("creating {}", data_dir.display()))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(all)?)
+        .with_context(|| format!("writing {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_normalized_and_removed_per_session() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let a = "/sessions/a.jsonl";
+        let b = "/sessions/b.jsonl";
+
+        add(
+            dir.path(),
+            a,
+            &["Bugfix".into(), "#auth".into(), " ".into()],
+        )
+        .unwrap();
+        add(dir.path(), b, &["auth".into()]).unwrap();
+        assert_eq!(
+            tags_for(dir.path(), a).into_iter().collect::<Vec<_>>(),
+            ["auth", "bugfix"]
+        );
+        assert_eq!(
+            paths_with_any(dir.path(), &["AUTH".into()]),
+            HashSet::from([a.to_string(), b.to_string()])
+        );
+
+        let left = remove(dir.path(), a, &["auth".into()]).unwrap();
+        assert_eq!(left.into_iter().collect::<Vec<_>>(), ["bugfix"]);
+        remove(dir.path(), b, &[]).unwrap();
+        assert!(!load(dir.path()).contains_key(b));
+        assert!(paths_with_any(dir.path(), &["missing".into()]).is_empty());
+    }
+}
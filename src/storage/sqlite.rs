@@ -355,6 +355,40 @@ impl SqliteStorage {
         Ok(out)
     }
 
+    /// Every agent slug with the number of messages stored for it, busiest first.
+    pub fn agent_message_counts(&self) -> Result<Vec<(String, u64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.slug, COUNT(m.id) AS n FROM agents a
+             JOIN conversations c ON c.agent_id = a.id
+             JOIN messages m ON m.conversation_id = c.id
+             GROUP BY a.slug
+             ORDER BY n DESC, a.slug",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as u64)))?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    /// Every workspace path with the number of messages stored under it, busiest first.
+    pub fn workspace_message_counts(&self) -> Result<Vec<(String, u64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT w.path, COUNT(m.id) AS n FROM workspaces w
+             JOIN conversations c ON c.workspace_id = w.id
+             JOIN messages m ON m.conversation_id = c.id
+             GROUP BY w.path
+             ORDER BY n DESC, w.path",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as u64)))?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
     pub fn list_conversations(&self, limit: i64, offset: i64) -> Result<Vec<Conversation>> {
         let mut stmt = self.conn.prepare(
             r"SELECT c.id, a.slug, w.path, c.external_id, c.title, c.source_path,
@@ -366,26 +400,29 @@ impl SqliteStorage {
                 LIMIT ? OFFSET ?",
         )?;
 
-        let rows = stmt.query_map(params![limit, offset], |row| {
-            Ok(Conversation {
-                id: Some(row.get(0)?),
-                agent_slug: row.get(1)?,
-                workspace: row
-                    .get::<_, Option<String>>(2)?
-                    .map(|p| Path::new(&p).to_path_buf()),
-                external_id: row.get(3)?,
-                title: row.get(4)?,
-                source_path: Path::new(&row.get::<_, String>(5)?).to_path_buf(),
-                started_at: row.get(6)?,
-                ended_at: row.get(7)?,
-                approx_tokens: row.get(8)?,
-                metadata_json: row
-                    .get::<_, Option<String>>(9)?
-                    .and_then(|s| serde_json::from_str(&s).ok())
-                    .unwrap_or_default(),
-                messages: Vec::new(),
-            })
-        })?;
+        let rows = stmt.query_map(params![limit, offset], conversation_from_row)?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    /// Conversations read from `source_path`, without their messages.
+    pub fn conversations_for_source_path(&self, source_path: &Path) -> Result<Vec<Conversation>> {
+        let mut stmt = self.conn.prepare(
+            r"SELECT c.id, a.slug, w.path, c.external_id, c.title, c.source_path,
+                       c.started_at, c.ended_at, c.approx_tokens, c.metadata_json
+                FROM conversations c
+                JOIN agents a ON c.agent_id = a.id
+                LEFT JOIN workspaces w ON c.workspace_id = w.id
+                WHERE c.source_path = ?1
+                ORDER BY c.id",
+        )?;
+        let rows = stmt.query_map(
+            params![source_path.to_string_lossy()],
+            conversation_from_row,
+        )?;
         let mut out = Vec::new();
         for r in rows {
             out.push(r?);
@@ -393,6 +430,55 @@ impl SqliteStorage {
         Ok(out)
     }
 
+    /// Up to `limit` conversations, oldest start time first; undated ones come last.
+    pub fn oldest_conversations(&self, limit: i64) -> Result<Vec<Conversation>> {
+        let mut stmt = self.conn.prepare(
+            r"SELECT c.id, a.slug, w.path, c.external_id, c.title, c.source_path,
+                       c.started_at, c.ended_at, c.approx_tokens, c.metadata_json
+                FROM conversations c
+                JOIN agents a ON c.agent_id = a.id
+                LEFT JOIN workspaces w ON c.workspace_id = w.id
+                ORDER BY c.started_at IS NULL, c.started_at ASC, c.id ASC
+                LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit], conversation_from_row)?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    /// Number of stored messages per conversation source path.
+    pub fn message_counts_by_source_path(&self) -> Result<std::collections::HashMap<String, u64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT c.source_path, COUNT(m.id) FROM conversations c
+             JOIN messages m ON m.conversation_id = c.id
+             GROUP BY c.source_path",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as u64)))?;
+        let mut out = std::collections::HashMap::new();
+        for r in rows {
+            let (path, count) = r?;
+            out.insert(path, count);
+        }
+        Ok(out)
+    }
+
+    /// Number of stored messages per conversation id.
+    pub fn message_counts_by_conversation(&self) -> Result<std::collections::HashMap<i64, u64>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT conversation_id, COUNT(id) FROM messages GROUP BY conversation_id")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as u64)))?;
+        let mut out = std::collections::HashMap::new();
+        for r in rows {
+            let (id, count) = r?;
+            out.insert(id, count);
+        }
+        Ok(out)
+    }
+
     pub fn fetch_messages(&self, conversation_id: i64) -> Result<Vec<Message>> {
         let mut stmt = self.conn.prepare(
             "SELECT id, idx, role, author, created_at, content, extra_json FROM messages WHERE conversation_id = ? ORDER BY idx",
@@ -426,6 +512,104 @@ impl SqliteStorage {
         Ok(out)
     }
 
+    /// Snippets of every message in a conversation, keyed by message id.
+    pub fn fetch_snippets(
+        &self,
+        conversation_id: i64,
+    ) -> Result<std::collections::HashMap<i64, Vec<Snippet>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT s.id, s.message_id, s.file_path, s.start_line, s.end_line, s.language, s.snippet_text
+             FROM snippets s JOIN messages m ON s.message_id = m.id
+             WHERE m.conversation_id = ? ORDER BY s.id",
+        )?;
+        let rows = stmt.query_map(params![conversation_id], |row| {
+            Ok((
+                row.get::<_, i64>(1)?,
+                Snippet {
+                    id: Some(row.get(0)?),
+                    file_path: row.get::<_, Option<String>>(2)?.map(Into::into),
+                    start_line: row.get(3)?,
+                    end_line: row.get(4)?,
+                    language: row.get(5)?,
+                    snippet_text: row.get(6)?,
+                },
+            ))
+        })?;
+        let mut out = std::collections::HashMap::<i64, Vec<Snippet>>::new();
+        for r in rows {
+            let (message_id, snippet) = r?;
+            out.entry(message_id).or_default().push(snippet);
+        }
+        Ok(out)
+    }
+
+    /// Delete every conversation read from `source_path`, with its messages, snippets,
+    /// tags and FTS rows. Returns how many conversations were removed.
+    pub fn delete_source_path(&mut self, source_path: &Path) -> Result<usize> {
+        let path = path_to_string(source_path);
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "DELETE FROM fts_messages WHERE message_id IN (
+                 SELECT m.id FROM messages m
+                 JOIN conversations c ON m.conversation_id = c.id
+                 WHERE c.source_path = ?1)",
+            params![path],
+        )?;
+        tx.execute(
+            "DELETE FROM snippets WHERE message_id IN (
+                 SELECT m.id FROM messages m
+                 JOIN conversations c ON m.conversation_id = c.id
+                 WHERE c.source_path = ?1)",
+            params![path],
+        )?;
+        tx.execute(
+            "DELETE FROM messages WHERE conversation_id IN (
+                 SELECT id FROM conversations WHERE source_path = ?1)",
+            params![path],
+        )?;
+        tx.execute(
+            "DELETE FROM conversation_tags WHERE conversation_id IN (
+                 SELECT id FROM conversations WHERE source_path = ?1)",
+            params![path],
+        )?;
+        let deleted = tx.execute(
+            "DELETE FROM conversations WHERE source_path = ?1",
+            params![path],
+        )?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    /// Delete one conversation with its messages, snippets, tags and FTS rows, leaving
+    /// other conversations read from the same source file alone.
+    pub fn delete_conversation(&mut self, conversation_id: i64) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "DELETE FROM fts_messages WHERE message_id IN (
+                 SELECT id FROM messages WHERE conversation_id = ?1)",
+            params![conversation_id],
+        )?;
+        tx.execute(
+            "DELETE FROM snippets WHERE message_id IN (
+                 SELECT id FROM messages WHERE conversation_id = ?1)",
+            params![conversation_id],
+        )?;
+        tx.execute(
+            "DELETE FROM messages WHERE conversation_id = ?1",
+            params![conversation_id],
+        )?;
+        tx.execute(
+            "DELETE FROM conversation_tags WHERE conversation_id = ?1",
+            params![conversation_id],
+        )?;
+        tx.execute(
+            "DELETE FROM conversations WHERE id = ?1",
+            params![conversation_id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
     pub fn rebuild_fts(&mut self) -> Result<()> {
         self.conn.execute("DELETE FROM fts_messages", [])?;
         self.conn.execute_batch(
@@ -441,6 +625,14 @@ impl SqliteStorage {
 
     /// Get the timestamp of the last successful scan (milliseconds since epoch).
     /// Returns None if no scan has been recorded yet.
+    /// Number of stored messages, which is the number of search documents.
+    pub fn message_count(&self) -> Result<u64> {
+        let n: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM messages", [], |r| r.get(0))?;
+        Ok(n as u64)
+    }
+
     pub fn get_last_scan_ts(&self) -> Result<Option<i64>> {
         let ts: Option<i64> = self
             .conn
@@ -649,6 +841,27 @@ fn insert_fts_message(
     Ok(())
 }
 
+fn conversation_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Conversation> {
+    Ok(Conversation {
+        id: Some(row.get(0)?),
+        agent_slug: row.get(1)?,
+        workspace: row
+            .get::<_, Option<String>>(2)?
+            .map(|p| Path::new(&p).to_path_buf()),
+        external_id: row.get(3)?,
+        title: row.get(4)?,
+        source_path: Path::new(&row.get::<_, String>(5)?).to_path_buf(),
+        started_at: row.get(6)?,
+        ended_at: row.get(7)?,
+        approx_tokens: row.get(8)?,
+        metadata_json: row
+            .get::<_, Option<String>>(9)?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        messages: Vec::new(),
+    })
+}
+
 fn path_to_string<P: AsRef<Path>>(p: P) -> String {
     p.as_ref().to_string_lossy().into_owned()
 }
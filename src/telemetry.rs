@@ -0,0 +1,270 @@
+//! Opt-in, local-only usage metrics.
+//!
+//! Disabled by default; `cass telemetry enable` turns it on. When enabled, cass keeps
+//! aggregate counters only — which commands ran, a bucketed index size, and bucketed
+//! query latency — in `telemetry.json` next to the update state. Queries, paths and
+//! message content are never recorded, and nothing leaves the machine: the only way to
+//! share the data is to run `cass telemetry export` and send the output yourself.
+
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Bump when the exported document changes shape.
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Persistent telemetry settings and counters.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TelemetryState {
+    pub enabled: bool,
+    /// Unix timestamp when collection was (last) turned on.
+    pub enabled_at: Option<i64>,
+    /// Invocations per command name.
+    #[serde(default)]
+    pub commands: BTreeMap<String, u64>,
+    /// Completed index runs per indexed-message-count bucket.
+    #[serde(default)]
+    pub index_size: BTreeMap<String, u64>,
+    /// Searches per latency bucket.
+    #[serde(default)]
+    pub query_latency: BTreeMap<String, u64>,
+}
+
+/// A single observation; only recorded when telemetry is enabled.
+#[derive(Debug, Clone, Copy)]
+pub enum Event<'a> {
+    Command(&'a str),
+    IndexSize { messages: u64 },
+    QueryLatency { elapsed_ms: u64 },
+}
+
+impl TelemetryState {
+    pub fn load() -> Self {
+        Self::load_from(&state_path())
+    }
+
+    fn load_from(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+            Err(_) => Self::default(),
+        }
+    }
+
+    fn save_to(&self, path: &Path) -> Result<()> {
+        // Write-then-rename so a concurrent cass never reads a torn file.
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("writing {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("writing {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn apply(&mut self, event: Event<'_>) {
+        let (counters, key) = match event {
+            Event::Command(name) => (&mut self.commands, name.to_string()),
+            Event::IndexSize { messages } => (&mut self.index_size, size_bucket(messages).into()),
+            Event::QueryLatency { elapsed_ms } => {
+                (&mut self.query_latency, latency_bucket(elapsed_ms).into())
+            }
+        };
+        *counters.entry(key).or_default() += 1;
+    }
+
+    /// Turn collection on or off. Disabling also discards collected counters.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled && !self.enabled {
+            self.enabled_at = Some(now_unix());
+        }
+        if !enabled {
+            *self = Self::default();
+        }
+        self.enabled = enabled;
+    }
+
+    /// The document `cass telemetry export` prints.
+    pub fn export(&self) -> serde_json::Value {
+        serde_json::json!({
+            "schema_version": EXPORT_SCHEMA_VERSION,
+            "cass_version": env!("CARGO_PKG_VERSION"),
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+            "enabled": self.enabled,
+            "enabled_at": self.enabled_at,
+            "commands": self.commands,
+            "index_size": self.index_size,
+            "query_latency": self.query_latency,
+        })
+    }
+}
+
+/// Whether collection is turned on.
+pub fn enabled() -> bool {
+    TelemetryState::load().enabled
+}
+
+/// Record `event` if telemetry is enabled. Failures are logged and otherwise ignored.
+pub fn record(event: Event<'_>) {
+    // Unlocked check first so disabled telemetry never touches the disk.
+    if !enabled() {
+        return;
+    }
+    let result = update_at(&state_path(), |state| {
+        if state.enabled {
+            state.apply(event);
+        }
+        state.enabled
+    });
+    if let Err(e) = result {
+        tracing::debug!(error = %e, "failed to record telemetry");
+    }
+}
+
+/// Turn collection on or off and persist it, returning the new state.
+pub fn set_enabled(enabled: bool) -> Result<TelemetryState> {
+    update_at(&state_path(), |state| {
+        state.set_enabled(enabled);
+        true
+    })
+}
+
+/// Load the state at `path`, let `change` modify it and save it if `change` returns true.
+/// An advisory lock on a sibling `.lock` file is held throughout, so concurrent cass
+/// processes (say `cass index --watch` and `cass search`) never lose each other's counts.
+fn update_at(
+    path: &Path,
+    change: impl FnOnce(&mut TelemetryState) -> bool,
+) -> Result<TelemetryState> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating telemetry directory {}", parent.display()))?;
+    }
+    let lock_path = path.with_extension("json.lock");
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("opening {}", lock_path.display()))?;
+    lock.lock()
+        .with_context(|| format!("locking {}", lock_path.display()))?;
+    let mut state = TelemetryState::load_from(path);
+    if change(&mut state) {
+        state.save_to(path)?;
+    }
+    Ok(state)
+}
+
+/// Bucket an indexed message count.
+pub fn size_bucket(messages: u64) -> &'static str {
+    match messages {
+        0..1_000 => "<1k",
+        1_000..10_000 => "1k-10k",
+        10_000..100_000 => "10k-100k",
+        100_000..1_000_000 => "100k-1M",
+        _ => ">=1M",
+    }
+}
+
+/// Bucket a query latency.
+pub fn latency_bucket(elapsed_ms: u64) -> &'static str {
+    match elapsed_ms {
+        0..10 => "<10ms",
+        10..50 => "10-50ms",
+        50..200 => "50-200ms",
+        200..1_000 => "200ms-1s",
+        _ => ">=1s",
+    }
+}
+
+/// Path to the telemetry state file.
+pub fn state_path() -> PathBuf {
+    directories::ProjectDirs::from("com", "coding-agent-search", "coding-agent-search").map_or_else(
+        || PathBuf::from("telemetry.json"),
+        |dirs| dirs.data_dir().join("telemetry.json"),
+    )
+}
+
+fn now_unix() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_cover_boundaries() {
+        assert_eq!(size_bucket(0), "<1k");
+        assert_eq!(size_bucket(1_000), "1k-10k");
+        assert_eq!(size_bucket(5_000_000), ">=1M");
+        assert_eq!(latency_bucket(9), "<10ms");
+        assert_eq!(latency_bucket(10), "10-50ms");
+        assert_eq!(latency_bucket(1_000), ">=1s");
+    }
+
+    #[test]
+    fn apply_counts_only_aggregates() {
+        let mut state = TelemetryState::default();
+        state.set_enabled(true);
+        state.apply(Event::Command("search"));
+        state.apply(Event::Command("search"));
+        state.apply(Event::QueryLatency { elapsed_ms: 3 });
+        state.apply(Event::IndexSize { messages: 42_000 });
+
+        assert_eq!(state.commands["search"], 2);
+        assert_eq!(state.query_latency["<10ms"], 1);
+        assert_eq!(state.index_size["10k-100k"], 1);
+
+        let exported = state.export();
+        assert_eq!(exported["schema_version"], EXPORT_SCHEMA_VERSION);
+        assert_eq!(exported["commands"]["search"], 2);
+    }
+
+    #[test]
+    fn disabling_discards_counters() {
+        let mut state = TelemetryState::default();
+        state.set_enabled(true);
+        assert!(state.enabled_at.is_some());
+        state.apply(Event::Command("index"));
+        state.set_enabled(false);
+        assert_eq!(state, TelemetryState::default());
+    }
+
+    #[test]
+    fn concurrent_updates_keep_every_count() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("telemetry.json");
+        update_at(&path, |state| {
+            state.set_enabled(true);
+            true
+        })
+        .unwrap();
+
+        let writers: Vec<_> = (0..4)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        update_at(&path, |state| {
+                            state.apply(Event::Command("search"));
+                            true
+                        })
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        assert_eq!(TelemetryState::load_from(&path).commands["search"], 100);
+    }
+}
@@ -0,0 +1,170 @@
+//! Agent picker modal shown while the agent filter is being edited.
+//! A checkbox list of the agents present in the index with their doc counts; several
+//! agents can be ticked at once.
+
+use std::collections::BTreeSet;
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::ui::components::theme::ThemePalette;
+use crate::ui::components::workspace_picker::fuzzy_score;
+
+#[derive(Clone, Debug, Default)]
+pub struct AgentPickerState {
+    /// (slug, doc count) for every agent, busiest first.
+    pub entries: Vec<(String, u64)>,
+    /// Indices into `entries` matching the current query, best match first.
+    pub filtered: Vec<usize>,
+    pub selected: usize,
+    /// Ticked slugs; kept while the query narrows the list.
+    pub checked: BTreeSet<String>,
+}
+
+impl AgentPickerState {
+    /// Picker over `entries` with the slugs in `checked` pre-ticked.
+    pub fn new(entries: Vec<(String, u64)>, checked: impl IntoIterator<Item = String>) -> Self {
+        let filtered = (0..entries.len()).collect();
+        Self {
+            entries,
+            filtered,
+            selected: 0,
+            checked: checked.into_iter().collect(),
+        }
+    }
+
+    /// Keep the entries whose slug fuzzily matches `query`, best score first.
+    pub fn refilter(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            self.filtered = (0..self.entries.len()).collect();
+        } else {
+            let mut scored: Vec<(usize, i64)> = self
+                .entries
+                .iter()
+                .enumerate()
+                .filter_map(|(i, (slug, _))| fuzzy_score(slug, query).map(|s| (i, s)))
+                .collect();
+            scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+            self.filtered = scored.into_iter().map(|(i, _)| i).collect();
+        }
+        self.selected = 0;
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.filtered.is_empty() {
+            self.selected = 0;
+            return;
+        }
+        let len = self.filtered.len() as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+    }
+
+    /// Slug of the highlighted entry, if any entry matches.
+    pub fn selected_slug(&self) -> Option<&str> {
+        self.filtered
+            .get(self.selected)
+            .and_then(|&i| self.entries.get(i))
+            .map(|(slug, _)| slug.as_str())
+    }
+
+    /// Tick or untick the highlighted entry.
+    pub fn toggle_selected(&mut self) {
+        if let Some(slug) = self.selected_slug().map(str::to_string)
+            && !self.checked.remove(&slug)
+        {
+            self.checked.insert(slug);
+        }
+    }
+
+    /// Agents to filter on when the picker is applied: the ticked ones, or else the
+    /// highlighted one while a query is typed. Empty means all agents.
+    pub fn chosen(&self, query: &str) -> BTreeSet<String> {
+        if !self.checked.is_empty() || query.trim().is_empty() {
+            return self.checked.clone();
+        }
+        self.selected_slug()
+            .map(str::to_string)
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Render the picker overlay: the typed query on top, the checkbox list below.
+pub fn draw_agent_picker(
+    f: &mut Frame<'_>,
+    area: Rect,
+    state: &AgentPickerState,
+    query: &str,
+    palette: ThemePalette,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
+
+    let input = Paragraph::new(query)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(format!("Agents ({} selected)", state.checked.len()))
+                .style(Style::default().fg(palette.accent)),
+        )
+        .style(Style::default().fg(palette.fg))
+        .alignment(Alignment::Left);
+
+    let items: Vec<ListItem> = state
+        .filtered
+        .iter()
+        .filter_map(|&i| state.entries.get(i))
+        .map(|(slug, count)| {
+            let mark = if state.checked.contains(slug) {
+                "[x] "
+            } else {
+                "[ ] "
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(mark, Style::default().fg(palette.accent)),
+                Span::styled(slug.as_str(), Style::default().fg(palette.fg)),
+                Span::raw("  "),
+                Span::styled(
+                    format!("{count} docs"),
+                    Style::default()
+                        .fg(palette.hint)
+                        .add_modifier(Modifier::ITALIC),
+                ),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title("Space toggle · Enter apply · Esc cancel")
+                .style(Style::default().fg(palette.fg).bg(palette.surface)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(palette.accent)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("➜ ");
+
+    let mut list_state = ListState::default();
+    if !state.filtered.is_empty() {
+        list_state.select(Some(state.selected));
+    }
+
+    f.render_widget(Clear, area);
+    f.render_widget(input, chunks[0]);
+    f.render_stateful_widget(list, chunks[1], &mut list_state);
+}
@@ -1,5 +1,5 @@
 //! Breadcrumb bar component for the TUI.
-//! Displays current context (Agent › Workspace › Date) and ranking.
+//! Displays current context (Agent › Workspace › Date) and ranking or sort order.
 //! Interactive elements allow direct clearing/changing of filters.
 
 use ratatui::prelude::*;
@@ -18,14 +18,16 @@ pub enum BreadcrumbKind {
     None,
 }
 
-fn ranking_label(r: tui::RankingMode) -> &'static str {
-    match r {
-        tui::RankingMode::RecentHeavy => "Recent",
-        tui::RankingMode::Balanced => "Balanced",
-        tui::RankingMode::RelevanceHeavy => "Relevance",
-        tui::RankingMode::MatchQualityHeavy => "Quality",
-        tui::RankingMode::DateNewest => "Newest",
-        tui::RankingMode::DateOldest => "Oldest",
+fn ranking_label(r: tui::RankingMode, sort: tui::SortOrder) -> &'static str {
+    match sort {
+        tui::SortOrder::Newest => "Newest",
+        tui::SortOrder::Oldest => "Oldest",
+        tui::SortOrder::Relevance => match r {
+            tui::RankingMode::RecentHeavy => "Recent",
+            tui::RankingMode::Balanced => "Balanced",
+            tui::RankingMode::RelevanceHeavy => "Relevance",
+            tui::RankingMode::MatchQualityHeavy => "Quality",
+        },
     }
 }
 
@@ -34,6 +36,7 @@ pub fn render_breadcrumbs(
     area: Rect,
     filters: &SearchFilters,
     ranking: tui::RankingMode,
+    sort: tui::SortOrder,
     palette: ThemePalette,
 ) -> Vec<(Rect, BreadcrumbKind)> {
     let mut rects: Vec<(Rect, BreadcrumbKind)> = Vec::new();
@@ -142,7 +145,7 @@ pub fn render_breadcrumbs(
     add_sep(&mut spans);
 
     // 4. Ranking
-    let rank_text = ranking_label(ranking);
+    let rank_text = ranking_label(ranking, sort);
     // Ranking is always active, so we use a distinct color but maybe not bold unless changed?
     // Let's keep it subtle but distinct.
     spans.push(Span::styled(rank_text, Style::default().fg(palette.fg)));
@@ -0,0 +1,200 @@
+//! Month-grid date picker shown while the from/to time filters are being edited.
+//! Arrow keys walk the days, PageUp/PageDown the months; typed text still goes through
+//! the time parser, so `-7d` or `yesterday` keep working.
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+};
+
+use crate::ui::components::theme::ThemePalette;
+use crate::ui::time_parser::local_midnight;
+
+/// Width and height of the picker overlay in cells.
+const PICKER_WIDTH: u16 = 42;
+const PICKER_HEIGHT: u16 = 15;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DatePickerState {
+    /// Day under the cursor.
+    pub cursor: NaiveDate,
+    pub today: NaiveDate,
+}
+
+impl DatePickerState {
+    /// Picker on `initial`, or on `today` when there is no date to start from.
+    pub fn new(today: NaiveDate, initial: Option<NaiveDate>) -> Self {
+        Self {
+            cursor: initial.unwrap_or(today),
+            today,
+        }
+    }
+
+    pub fn move_days(&mut self, days: i64) {
+        if let Some(d) = self.cursor.checked_add_signed(Duration::days(days)) {
+            self.cursor = d;
+        }
+    }
+
+    /// Move by whole months, clamping the day to the target month's length.
+    pub fn move_months(&mut self, months: i32) {
+        let step = Months::new(months.unsigned_abs());
+        let moved = if months >= 0 {
+            self.cursor.checked_add_months(step)
+        } else {
+            self.cursor.checked_sub_months(step)
+        };
+        if let Some(d) = moved {
+            self.cursor = d;
+        }
+    }
+
+    /// Weeks of the cursor's month, Monday first; days outside the month are `None`.
+    pub fn month_grid(&self) -> Vec<[Option<NaiveDate>; 7]> {
+        let first = self.cursor.with_day(1).unwrap_or(self.cursor);
+        let lead = first.weekday().num_days_from_monday() as usize;
+        let mut weeks = Vec::new();
+        let mut week = [None; 7];
+        let mut slot = lead;
+        let mut day = Some(first);
+        while let Some(d) = day.filter(|d| d.month() == first.month()) {
+            week[slot] = Some(d);
+            slot += 1;
+            if slot == 7 {
+                weeks.push(week);
+                week = [None; 7];
+                slot = 0;
+            }
+            day = d.succ_opt();
+        }
+        if slot > 0 {
+            weeks.push(week);
+        }
+        weeks
+    }
+}
+
+/// Epoch milliseconds where `date` starts in local time.
+pub fn day_start_ms(date: NaiveDate) -> Option<i64> {
+    local_midnight(date)
+}
+
+/// Epoch milliseconds of the last instant of `date` in local time.
+pub fn day_end_ms(date: NaiveDate) -> Option<i64> {
+    local_midnight(date.succ_opt()?).map(|ms| ms - 1)
+}
+
+/// Local calendar day containing the epoch-millisecond timestamp `ms`.
+pub fn local_date(ms: i64) -> Option<NaiveDate> {
+    use chrono::TimeZone;
+    chrono::Local
+        .timestamp_millis_opt(ms)
+        .single()
+        .map(|dt| dt.date_naive())
+}
+
+/// Fixed-size overlay centred in `outer`.
+pub fn picker_area(outer: Rect) -> Rect {
+    let width = PICKER_WIDTH.min(outer.width);
+    let height = PICKER_HEIGHT.min(outer.height);
+    Rect::new(
+        outer.x + (outer.width - width) / 2,
+        outer.y + (outer.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Render the calendar: typed text on top, the month grid with `range` shaded, and the
+/// preset keys underneath.
+pub fn draw_date_picker(
+    f: &mut Frame<'_>,
+    area: Rect,
+    state: &DatePickerState,
+    title: &str,
+    range: (Option<NaiveDate>, Option<NaiveDate>),
+    query: &str,
+    palette: ThemePalette,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(4)])
+        .split(area);
+
+    let input = Paragraph::new(query)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(title.to_string())
+                .style(Style::default().fg(palette.accent)),
+        )
+        .style(Style::default().fg(palette.fg))
+        .alignment(Alignment::Left);
+
+    let in_range = |d: NaiveDate| match range {
+        (Some(from), Some(to)) => d >= from && d <= to,
+        (Some(from), None) => d == from,
+        (None, Some(to)) => d == to,
+        (None, None) => false,
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            state.cursor.format("%B %Y").to_string(),
+            Style::default()
+                .fg(palette.accent)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .alignment(Alignment::Center),
+        Line::from(Span::styled(
+            " Mo  Tu  We  Th  Fr  Sa  Su",
+            Style::default().fg(palette.hint),
+        )),
+    ];
+    for week in state.month_grid() {
+        let spans: Vec<Span> = week
+            .iter()
+            .map(|day| match day {
+                None => Span::raw("    "),
+                Some(d) => {
+                    let mut style = Style::default().fg(palette.fg);
+                    if in_range(*d) {
+                        style = style.bg(palette.surface).fg(palette.accent_alt);
+                    }
+                    if *d == state.today {
+                        style = style.add_modifier(Modifier::UNDERLINED);
+                    }
+                    if *d == state.cursor {
+                        style = style
+                            .bg(palette.accent)
+                            .fg(palette.bg)
+                            .add_modifier(Modifier::BOLD);
+                    }
+                    Span::styled(format!(" {:>2} ", d.day()), style)
+                }
+            })
+            .collect();
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(Span::styled(
+        "Alt+t today · Alt+w week · Alt+m month",
+        Style::default().fg(palette.hint),
+    )));
+
+    let grid = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain)
+            .title("←↑↓→ day · PgUp/PgDn month · Enter pick")
+            .style(Style::default().fg(palette.fg).bg(palette.bg)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(input, chunks[0]);
+    f.render_widget(grid, chunks[1]);
+}
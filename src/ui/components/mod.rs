@@ -1,8 +1,11 @@
 //! UI components registry.
+pub mod agent_picker;
 pub mod breadcrumbs;
+pub mod date_picker;
 pub mod help_strip;
 pub mod palette;
 pub mod pills;
 pub mod theme;
 pub mod toast;
 pub mod widgets;
+pub mod workspace_picker;
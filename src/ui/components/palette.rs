@@ -16,6 +16,7 @@ use crate::ui::components::theme::ThemePalette;
 pub enum PaletteAction {
     ToggleTheme,
     ToggleDensity,
+    CycleLayout,
     ToggleHelpStrip,
     OpenUpdateBanner,
     FilterAgent,
@@ -26,8 +27,16 @@ pub enum PaletteAction {
     OpenSavedViews,
     SaveViewSlot(u8),
     LoadViewSlot(u8),
+    /// Run the search saved by name with `cass search --save`.
+    RunSavedSearch(String),
     OpenBulkActions,
     ReloadIndex,
+    /// Pin the selected result's conversation above the results, or unpin it.
+    TogglePin,
+    /// Continue the selected result's session in its agent (`cass resume`).
+    ResumeSession,
+    /// Follow the selected result's conversation as its agent writes to it.
+    TailSession,
 }
 
 /// Render-ready descriptor for an action.
@@ -104,7 +113,7 @@ pub fn draw_palette(f: &mut Frame<'_>, area: Rect, state: &PaletteState, palette
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
+                .border_set(palette.glyphs.border(BorderType::Rounded))
                 .title("Command Palette")
                 .style(Style::default().fg(palette.accent)),
         )
@@ -130,7 +139,7 @@ pub fn draw_palette(f: &mut Frame<'_>, area: Rect, state: &PaletteState, palette
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_type(BorderType::Plain)
+                .border_set(palette.glyphs.border(BorderType::Plain))
                 .style(Style::default().fg(palette.fg).bg(palette.surface)),
         )
         .highlight_style(
@@ -139,7 +148,7 @@ pub fn draw_palette(f: &mut Frame<'_>, area: Rect, state: &PaletteState, palette
                 .bg(palette.accent)
                 .add_modifier(Modifier::BOLD),
         )
-        .highlight_symbol("➜ ");
+        .highlight_symbol(palette.glyphs.highlight);
 
     // Clear and draw.
     f.render_widget(Clear, area);
@@ -166,7 +175,12 @@ pub fn default_actions() -> Vec<PaletteItem> {
         item(
             PaletteAction::ToggleDensity,
             "Toggle density",
-            "Compact/Cozy/Spacious",
+            "Compact/Cozy/Spacious (Ctrl+D)",
+        ),
+        item(
+            PaletteAction::CycleLayout,
+            "Cycle layout",
+            "Split/Results/Preview",
         ),
         item(
             PaletteAction::ToggleHelpStrip,
@@ -208,6 +222,21 @@ pub fn default_actions() -> Vec<PaletteItem> {
             "Bulk actions",
             "Open bulk menu on selection",
         ),
+        item(
+            PaletteAction::TogglePin,
+            "Pin/unpin conversation",
+            "p in detail",
+        ),
+        item(
+            PaletteAction::ResumeSession,
+            "Resume session in agent",
+            "r in detail",
+        ),
+        item(
+            PaletteAction::TailSession,
+            "Tail session (follow new messages)",
+            "t in detail",
+        ),
         item(
             PaletteAction::ReloadIndex,
             "Reload index/view",
@@ -235,6 +264,20 @@ pub fn default_actions() -> Vec<PaletteItem> {
     items
 }
 
+/// One entry per saved search, listed after the default actions.
+pub fn saved_search_actions(searches: &[crate::saved_searches::SavedSearch]) -> Vec<PaletteItem> {
+    searches
+        .iter()
+        .map(|s| {
+            item(
+                PaletteAction::RunSavedSearch(s.name.clone()),
+                format!("Saved search: {}", s.name),
+                s.query.clone(),
+            )
+        })
+        .collect()
+}
+
 fn item(action: PaletteAction, label: impl Into<String>, hint: impl Into<String>) -> PaletteItem {
     PaletteItem {
         action,
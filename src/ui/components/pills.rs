@@ -55,15 +55,18 @@ pub fn draw_pills(
         } else {
             palette.hint
         };
-        let content = format!("{}: {}", pill.label, pill.value);
+        let mut content = format!("{}: {}", pill.label, pill.value);
+        if !pill.active && palette.glyphs.text_states {
+            content.push_str(" (off)");
+        }
         let para = Paragraph::new(content).block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_type(if pill.active {
+                .border_set(palette.glyphs.border(if pill.active {
                     BorderType::Rounded
                 } else {
                     BorderType::Plain
-                })
+                }))
                 .border_style(Style::default().fg(border_color))
                 .style(
                     Style::default()
@@ -8,6 +8,8 @@
 //! - Subtle agent differentiation via tinted backgrounds
 
 use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::border;
+use ratatui::widgets::BorderType;
 
 /// Premium color palette inspired by modern design systems.
 /// Uses low-saturation colors for comfort with refined accents for highlights.
@@ -339,6 +341,76 @@ pub struct ThemePalette {
     /// Alternating stripe colors for zebra-striping results (sux.6.3)
     pub stripe_even: Color,
     pub stripe_odd: Color,
+    /// Symbols drawn alongside the text; ASCII in [`ThemePalette::accessible`].
+    pub glyphs: Glyphs,
+}
+
+/// Non-text symbols the widgets draw: cursors, score dots, list markers and borders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Glyphs {
+    /// Cursor after the query while typing it.
+    pub cursor: &'static str,
+    /// Cursor while a filter is being edited.
+    pub cursor_idle: &'static str,
+    /// Prompt before a filter being edited.
+    pub prompt_idle: &'static str,
+    pub ellipsis: &'static str,
+    /// Between groups of hints.
+    pub separator: &'static str,
+    pub score_filled: &'static str,
+    pub score_empty: &'static str,
+    /// Marker before the highlighted row of a list.
+    pub highlight: &'static str,
+    /// Draw borders with `+`, `-` and `|` instead of box-drawing characters.
+    pub ascii_borders: bool,
+    /// Spell out states that color alone would otherwise carry (inactive pills, toast kinds).
+    pub text_states: bool,
+}
+
+impl Glyphs {
+    pub const UNICODE: Self = Self {
+        cursor: "▎",
+        cursor_idle: "│",
+        prompt_idle: "›",
+        ellipsis: "…",
+        separator: "·",
+        score_filled: "●",
+        score_empty: "○",
+        highlight: "➜ ",
+        ascii_borders: false,
+        text_states: false,
+    };
+
+    pub const ASCII: Self = Self {
+        cursor: "_",
+        cursor_idle: "|",
+        prompt_idle: ">",
+        ellipsis: "...",
+        separator: "|",
+        score_filled: "#",
+        score_empty: ".",
+        highlight: "> ",
+        ascii_borders: true,
+        text_states: true,
+    };
+
+    /// Border characters for a block of `kind`.
+    pub fn border(self, kind: BorderType) -> border::Set {
+        if self.ascii_borders {
+            border::Set {
+                top_left: "+",
+                top_right: "+",
+                bottom_left: "+",
+                bottom_right: "+",
+                vertical_left: "|",
+                vertical_right: "|",
+                horizontal_top: "-",
+                horizontal_bottom: "-",
+            }
+        } else {
+            kind.to_border_set()
+        }
+    }
 }
 
 impl ThemePalette {
@@ -358,6 +430,7 @@ impl ThemePalette {
             system: Color::Rgb(177, 133, 41),       // Amber
             stripe_even: Color::Rgb(250, 250, 252), // Same as bg
             stripe_odd: Color::Rgb(240, 241, 245),  // Slightly darker
+            glyphs: Glyphs::UNICODE,
         }
     }
 
@@ -377,6 +450,16 @@ impl ThemePalette {
             system: colors::ROLE_SYSTEM,
             stripe_even: colors::BG_DEEP,       // #1a1b26
             stripe_odd: Color::Rgb(30, 32, 48), // #1e2030 - slightly lighter
+            glyphs: Glyphs::UNICODE,
+        }
+    }
+
+    /// Accessible theme - the high contrast colors drawn with ASCII glyphs, for screen
+    /// readers and fonts without box-drawing or symbol characters (`[tui] accessible`).
+    pub fn accessible() -> Self {
+        Self {
+            glyphs: Glyphs::ASCII,
+            ..Self::high_contrast()
         }
     }
 
@@ -432,6 +515,14 @@ impl ThemePalette {
             "chatgpt" => (colors::AGENT_CHATGPT_BG, Color::Rgb(16, 163, 127)), // ChatGPT green
             "opencode" => (colors::AGENT_OPENCODE_BG, colors::ROLE_USER), // Neutral/sage
             "pi_agent" => (colors::AGENT_CODEX_BG, Color::Rgb(255, 140, 0)), // Orange for pi
+            "sgpt" => (colors::AGENT_CHATGPT_BG, Color::Rgb(0, 191, 255)), // Sky blue for shell-gpt
+            "copilot_cli" => (colors::AGENT_CURSOR_BG, Color::Rgb(110, 84, 148)), // Copilot purple
+            "qwen" => (colors::AGENT_GEMINI_BG, Color::Rgb(97, 92, 237)), // Indigo for qwen
+            "open_webui" => (colors::AGENT_OPENCODE_BG, Color::Rgb(230, 230, 230)), // Near white
+            "plandex" => (colors::AGENT_CLINE_BG, Color::Rgb(0, 168, 232)), // Azure for plandex
+            "crush" => (colors::AGENT_CURSOR_BG, Color::Rgb(255, 95, 175)), // Charm pink
+            "vscode_chat" => (colors::AGENT_CLAUDE_BG, Color::Rgb(0, 122, 204)), // VS Code blue
+            "trajectory" => (colors::BG_DEEP, Color::Rgb(189, 147, 249)), // Lavender for imports
             _ => (colors::BG_DEEP, colors::ACCENT_PRIMARY),
         };
 
@@ -456,6 +547,14 @@ impl ThemePalette {
             "chatgpt" => "💬",
             "opencode" => "📦",
             "pi_agent" => "🥧",
+            "sgpt" => "🐚",
+            "copilot_cli" => "🐙",
+            "qwen" => "🐉",
+            "open_webui" => "🦙",
+            "plandex" => "📐",
+            "crush" => "💘",
+            "vscode_chat" => "🟦",
+            "trajectory" => "🧪",
             _ => "✨",
         }
     }
@@ -831,6 +930,7 @@ impl ThemePalette {
             system: Color::Rgb(249, 226, 175),     // Yellow
             stripe_even: Color::Rgb(30, 30, 46),   // Base
             stripe_odd: Color::Rgb(36, 36, 54),    // Slightly lighter
+            glyphs: Glyphs::UNICODE,
         }
     }
 
@@ -852,6 +952,7 @@ impl ThemePalette {
             system: Color::Rgb(241, 250, 140),     // Yellow
             stripe_even: Color::Rgb(40, 42, 54),   // Background
             stripe_odd: Color::Rgb(48, 50, 64),    // Slightly lighter
+            glyphs: Glyphs::UNICODE,
         }
     }
 
@@ -873,6 +974,7 @@ impl ThemePalette {
             system: Color::Rgb(235, 203, 139), // Nord13 (aurora yellow)
             stripe_even: Color::Rgb(46, 52, 64), // Nord0
             stripe_odd: Color::Rgb(52, 58, 72), // Slightly lighter
+            glyphs: Glyphs::UNICODE,
         }
     }
 
@@ -896,6 +998,7 @@ impl ThemePalette {
             system: Color::Rgb(255, 255, 0), // Pure yellow
             stripe_even: Color::Rgb(0, 0, 0), // Pure black
             stripe_odd: Color::Rgb(24, 24, 24), // Very dark gray
+            glyphs: Glyphs::UNICODE,
         }
     }
 }
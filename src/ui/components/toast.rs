@@ -8,7 +8,7 @@ use ratatui::{
     layout::{Alignment, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
 };
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
@@ -39,6 +39,16 @@ impl ToastType {
         }
     }
 
+    /// Word for this toast type, shown instead of the icon when states are spelled out
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Success => "ok",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+
     /// Get the color for this toast type
     pub fn color(self, palette: &ThemePalette) -> Color {
         match self {
@@ -99,7 +109,7 @@ impl Toast {
     /// Create a new toast with default duration
     pub fn new(message: impl Into<String>, toast_type: ToastType) -> Self {
         let message = message.into();
-        let id = format!("{:?}:{}", toast_type, &message);
+        let id = format!("{:?}:{}", toast_type, message);
         Self {
             id,
             message,
@@ -305,7 +315,11 @@ pub fn render_toasts(frame: &mut Frame, manager: &ToastManager, palette: &ThemeP
 
         // Build the toast content
         let color = toast.toast_type.color(palette);
-        let icon = toast.toast_type.icon();
+        let icon = if palette.glyphs.text_states {
+            toast.toast_type.label()
+        } else {
+            toast.toast_type.icon()
+        };
 
         let count_suffix = if toast.count > 1 {
             format!(" (x{})", toast.count)
@@ -324,6 +338,7 @@ pub fn render_toasts(frame: &mut Frame, manager: &ToastManager, palette: &ThemeP
 
         let block = Block::default()
             .borders(Borders::ALL)
+            .border_set(palette.glyphs.border(BorderType::Plain))
             .border_style(Style::default().fg(color))
             .style(Style::default().bg(palette.surface));
 
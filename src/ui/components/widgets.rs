@@ -3,7 +3,7 @@
 use ratatui::layout::Alignment;
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::widgets::{Block, BorderType, Borders, Paragraph};
 
 use crate::ui::components::theme::{ThemePalette, colors, kbd_style};
 use crate::ui::data::InputMode;
@@ -90,8 +90,17 @@ pub fn search_bar(
     }
 
     // Subtle cursor indicator
-    let cursor = if in_query_mode { "▎" } else { "│" };
-    let prompt = if in_query_mode { "/" } else { "›" };
+    let glyphs = palette.glyphs;
+    let cursor = if in_query_mode {
+        glyphs.cursor
+    } else {
+        glyphs.cursor_idle
+    };
+    let prompt = if in_query_mode {
+        "/"
+    } else {
+        glyphs.prompt_idle
+    };
 
     first_line.push(Span::styled(
         format!("{prompt} "),
@@ -104,11 +113,15 @@ pub fn search_bar(
     ));
 
     // Context-aware hints line - minimal, not overwhelming
+    let separator = format!("  {}  ", glyphs.separator);
     let tips_line = if in_query_mode {
         Line::from(vec![
             Span::styled(shortcuts::HELP, kbd_style(palette)),
             Span::styled(" help", Style::default().fg(palette.hint)),
-            Span::styled("  ·  ", Style::default().fg(colors::TEXT_DISABLED)),
+            Span::styled(
+                separator.clone(),
+                Style::default().fg(colors::TEXT_DISABLED),
+            ),
             Span::styled(shortcuts::FILTER_AGENT, Style::default().fg(palette.hint)),
             Span::styled(" agent", Style::default().fg(palette.hint)),
             Span::raw("  "),
@@ -123,7 +136,10 @@ pub fn search_bar(
                 Style::default().fg(palette.hint),
             ),
             Span::styled(" time", Style::default().fg(palette.hint)),
-            Span::styled("  ·  ", Style::default().fg(colors::TEXT_DISABLED)),
+            Span::styled(
+                separator.clone(),
+                Style::default().fg(colors::TEXT_DISABLED),
+            ),
             Span::styled(shortcuts::CLEAR_FILTERS, Style::default().fg(palette.hint)),
             Span::styled(" clear", Style::default().fg(palette.hint)),
         ])
@@ -132,7 +148,10 @@ pub fn search_bar(
         Line::from(vec![
             Span::styled("Enter", kbd_style(palette)),
             Span::styled(" apply", Style::default().fg(palette.hint)),
-            Span::styled("  ·  ", Style::default().fg(colors::TEXT_DISABLED)),
+            Span::styled(
+                separator.clone(),
+                Style::default().fg(colors::TEXT_DISABLED),
+            ),
             Span::styled("Esc", Style::default().fg(palette.hint)),
             Span::styled(" cancel", Style::default().fg(palette.hint)),
         ])
@@ -145,6 +164,7 @@ pub fn search_bar(
             Block::default()
                 .title(title)
                 .borders(Borders::ALL)
+                .border_set(glyphs.border(BorderType::Plain))
                 .border_style(border_style),
         )
         .style(Style::default().bg(palette.bg))
@@ -168,6 +188,7 @@ pub fn themed_block(title: &str, palette: ThemePalette, focused: bool) -> Block<
     Block::default()
         .title(Span::styled(format!(" {title} "), title_style))
         .borders(Borders::ALL)
+        .border_set(palette.glyphs.border(BorderType::Plain))
         .border_style(border_style)
 }
 
@@ -194,7 +215,11 @@ pub fn filter_chips(
             .iter()
             .map(|w| {
                 if w.len() > 20 {
-                    format!("…{}", &w[w.len().saturating_sub(18)..])
+                    format!(
+                        "{}{}",
+                        palette.glyphs.ellipsis,
+                        &w[w.len().saturating_sub(18)..]
+                    )
                 } else {
                     w.clone()
                 }
@@ -239,11 +264,11 @@ pub fn score_indicator(score: f32, palette: ThemePalette) -> Vec<Span<'static>>
 
     vec![
         Span::styled(
-            "●".repeat(filled),
+            palette.glyphs.score_filled.repeat(filled),
             Style::default().fg(color).add_modifier(modifier),
         ),
         Span::styled(
-            "○".repeat(empty),
+            palette.glyphs.score_empty.repeat(empty),
             Style::default()
                 .fg(palette.hint)
                 .add_modifier(Modifier::DIM),
@@ -0,0 +1,170 @@
+//! Workspace picker modal shown while the workspace filter is being edited.
+//! Lists every indexed workspace with its message count, narrowed by a fuzzy query.
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::ui::components::theme::ThemePalette;
+
+#[derive(Clone, Debug, Default)]
+pub struct WorkspacePickerState {
+    /// (path, message count) for every known workspace, busiest first.
+    pub entries: Vec<(String, u64)>,
+    /// Indices into `entries` matching the current query, best match first.
+    pub filtered: Vec<usize>,
+    pub selected: usize,
+}
+
+impl WorkspacePickerState {
+    pub fn new(entries: Vec<(String, u64)>) -> Self {
+        let filtered = (0..entries.len()).collect();
+        Self {
+            entries,
+            filtered,
+            selected: 0,
+        }
+    }
+
+    /// Keep the entries whose path fuzzily matches `query`, best score first.
+    pub fn refilter(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() {
+            self.filtered = (0..self.entries.len()).collect();
+        } else {
+            let mut scored: Vec<(usize, i64)> = self
+                .entries
+                .iter()
+                .enumerate()
+                .filter_map(|(i, (path, _))| fuzzy_score(path, query).map(|s| (i, s)))
+                .collect();
+            // Stable sort keeps the busiest-first order among equal scores.
+            scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+            self.filtered = scored.into_iter().map(|(i, _)| i).collect();
+        }
+        self.selected = 0;
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.filtered.is_empty() {
+            self.selected = 0;
+            return;
+        }
+        let len = self.filtered.len() as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(len) as usize;
+    }
+
+    /// Path of the highlighted entry, if any entry matches.
+    pub fn selected_path(&self) -> Option<&str> {
+        self.filtered
+            .get(self.selected)
+            .and_then(|&i| self.entries.get(i))
+            .map(|(path, _)| path.as_str())
+    }
+}
+
+/// Score `query` as a case-insensitive subsequence of `candidate`; `None` when it is not one.
+/// Consecutive characters and matches at the start of a path segment score higher.
+pub fn fuzzy_score(candidate: &str, query: &str) -> Option<i64> {
+    let hay: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0i64;
+    let mut pos = 0usize;
+    let mut prev: Option<usize> = None;
+    for qc in query.to_lowercase().chars() {
+        let found = hay[pos..].iter().position(|&c| c == qc)? + pos;
+        score += 1;
+        if prev.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || matches!(hay[found - 1], '/' | '\\' | '-' | '_' | '.') {
+            score += 3;
+        }
+        prev = Some(found);
+        pos = found + 1;
+    }
+    // Prefer shorter paths when the match is otherwise equal.
+    Some(score * 1000 - hay.len() as i64)
+}
+
+/// Render the picker overlay: the typed query on top, matching workspaces below.
+pub fn draw_workspace_picker(
+    f: &mut Frame<'_>,
+    area: Rect,
+    state: &WorkspacePickerState,
+    query: &str,
+    palette: ThemePalette,
+) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(area);
+
+    let input = Paragraph::new(query)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(format!(
+                    "Workspace ({}/{})",
+                    state.filtered.len(),
+                    state.entries.len()
+                ))
+                .style(Style::default().fg(palette.accent)),
+        )
+        .style(Style::default().fg(palette.fg))
+        .alignment(Alignment::Left);
+
+    let items: Vec<ListItem> = if state.entries.is_empty() {
+        vec![ListItem::new(Span::styled(
+            "No workspaces indexed; Enter applies the typed path",
+            Style::default().fg(palette.hint),
+        ))]
+    } else {
+        state
+            .filtered
+            .iter()
+            .filter_map(|&i| state.entries.get(i))
+            .map(|(path, count)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(path.as_str(), Style::default().fg(palette.fg)),
+                    Span::raw("  "),
+                    Span::styled(
+                        format!("{count} hits"),
+                        Style::default()
+                            .fg(palette.hint)
+                            .add_modifier(Modifier::ITALIC),
+                    ),
+                ]))
+            })
+            .collect()
+    };
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Plain)
+                .title("Up/Down select · Enter apply · Esc cancel")
+                .style(Style::default().fg(palette.fg).bg(palette.surface)),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(palette.accent)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("➜ ");
+
+    let mut list_state = ListState::default();
+    if !state.filtered.is_empty() {
+        list_state.select(Some(state.selected));
+    }
+
+    f.render_widget(Clear, area);
+    f.render_widget(input, chunks[0]);
+    f.render_stateful_widget(list, chunks[1], &mut list_state);
+}
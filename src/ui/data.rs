@@ -20,6 +20,8 @@ pub struct ConversationView {
     pub convo: Conversation,
     pub messages: Vec<Message>,
     pub workspace: Option<Workspace>,
+    /// `cass note` annotations, shown inline under the messages they are on.
+    pub notes: Vec<crate::notes::Note>,
 }
 
 pub fn load_conversation(
@@ -65,6 +67,7 @@ pub fn load_conversation(
             convo,
             messages,
             workspace,
+            notes: Vec::new(),
         }));
     }
     Ok(None)
@@ -13,7 +13,11 @@ pub const QUIT: &str = "Esc/F10";
 pub const CLEAR_FILTERS: &str = "Ctrl+Del";
 pub const RESET_STATE: &str = "Ctrl+Shift+Del";
 pub const RANKING: &str = "F12";
+pub const SORT: &str = "Shift+F12";
+pub const LAYOUT: &str = "Ctrl+L";
+pub const DENSITY: &str = "Ctrl+D";
 pub const REFRESH: &str = "Ctrl+Shift+R";
+pub const REINDEX: &str = "Alt+r";
 pub const DETAIL_OPEN: &str = "Enter";
 pub const DETAIL_CLOSE: &str = "Esc";
 pub const FOCUS_QUERY: &str = "/";
@@ -25,15 +29,21 @@ pub const HISTORY_CYCLE: &str = "Ctrl+R";
 pub const SCOPE_AGENT: &str = "Shift+F3";
 pub const SCOPE_WORKSPACE: &str = "Shift+F4";
 pub const CYCLE_TIME_PRESETS: &str = "Shift+F5";
+pub const CYCLE_ROLE: &str = "Shift+F6";
 
 // Actions
 pub const COPY: &str = "y";
 pub const BULK_MENU: &str = "A";
 pub const TOGGLE_SELECT: &str = "Ctrl+M";
 pub const PANE_FILTER: &str = "/";
+pub const PIN: &str = "p";
+pub const RESUME: &str = "r";
+pub const TAIL: &str = "t";
 
 // Navigation
 pub const TAB_FOCUS: &str = "Tab";
+pub const FOCUS_SWITCH: &str = "Shift+Tab";
+pub const FOLD_CONVERSATION: &str = "Tab";
 pub const VIM_NAV: &str = "Alt+h/j/k/l";
 pub const JUMP_TOP: &str = "Home";
 pub const JUMP_BOTTOM: &str = "End";
@@ -1,58 +1,55 @@
-use chrono::{Duration, Local, NaiveDate, TimeZone, Utc};
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, TimeZone};
 
 /// Parses human-readable time input into a UTC timestamp (milliseconds).
 ///
 /// Supported formats:
-/// - Relative: "-7d", "-24h", "-30m", "-1w"
-/// - Keywords: "now", "today", "yesterday"
-/// - ISO dates: "2024-11-25", "2024-11-25T14:30:00Z"
+/// - Relative: "7d", "-24h", "30m", "1w", "3mo", "1y", "2 weeks ago"
+/// - Keywords: "now", "today", "yesterday", "this week", "last month", "this year"
+/// - Weekdays: "monday" (this week's, or today), "last fri" (the one before today)
+/// - ISO dates: "2024-11-25", "2024-11-25T14:30:00Z", "2024-11-25 14:30"
 /// - Date formats: "YYYY-MM-DD", "YYYY/MM/DD", "MM/DD/YYYY", "MM-DD-YYYY"
+/// - Months and years: "2024-06", "2024"
 /// - Unix timestamp: seconds (if < 10^11) or milliseconds
+///
+/// Calendar expressions name the start of their period in local time, so
+/// `--until 2024-06` stops where June begins.
 pub fn parse_time_input(input: &str) -> Option<i64> {
-    let input = input.trim().to_lowercase();
+    parse_time_input_at(input, Local::now())
+}
+
+/// [`parse_time_input`] relative to `now`.
+pub fn parse_time_input_at(input: &str, now: DateTime<Local>) -> Option<i64> {
+    let input = input
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
     if input.is_empty() {
         return None;
     }
+    let today = now.date_naive();
 
-    let now_utc = Utc::now();
-    let now_ms = now_utc.timestamp_millis();
-
-    // Relative: -7d, -24h, -1w, -30m
-    if let Some(stripped) = input.strip_prefix('-') {
-        let val_str: String = stripped.chars().take_while(|c| c.is_numeric()).collect();
-        if let Ok(val) = val_str.parse::<i64>() {
-            let unit = stripped.trim_start_matches(&val_str).trim();
-            let duration = match unit {
-                "d" | "day" | "days" => Duration::days(val),
-                "h" | "hr" | "hrs" | "hour" | "hours" => Duration::hours(val),
-                "m" | "min" | "mins" | "minute" | "minutes" => Duration::minutes(val),
-                "w" | "wk" | "wks" | "week" | "weeks" => Duration::weeks(val),
-                _ => return None,
-            };
-            return Some((now_utc - duration).timestamp_millis());
-        }
+    if let Some(ts) = parse_relative(&input, now) {
+        return Some(ts);
     }
 
     // Keywords
-    match input.as_str() {
-        "now" => return Some(now_ms),
-        "today" => {
-            let today = Local::now().date_naive();
-            let dt = today.and_hms_opt(0, 0, 0)?;
-            return Local
-                .from_local_datetime(&dt)
-                .single()
-                .map(|dt| dt.with_timezone(&Utc).timestamp_millis());
-        }
-        "yesterday" => {
-            let yesterday = Local::now().date_naive() - Duration::days(1);
-            let dt = yesterday.and_hms_opt(0, 0, 0)?;
-            return Local
-                .from_local_datetime(&dt)
-                .single()
-                .map(|dt| dt.with_timezone(&Utc).timestamp_millis());
-        }
-        _ => {}
+    let start = match input.as_str() {
+        "now" => return Some(now.timestamp_millis()),
+        "today" => Some(today),
+        "yesterday" => Some(today - Duration::days(1)),
+        "this week" => Some(week_start(today)),
+        "last week" => Some(week_start(today) - Duration::weeks(1)),
+        "this month" => today.with_day(1),
+        "last month" => today
+            .with_day(1)
+            .and_then(|d| d.checked_sub_months(Months::new(1))),
+        "this year" => NaiveDate::from_ymd_opt(today.year(), 1, 1),
+        "last year" => NaiveDate::from_ymd_opt(today.year() - 1, 1, 1),
+        _ => None,
+    };
+    if let Some(date) = start.or_else(|| parse_weekday(&input, today)) {
+        return local_midnight(date);
     }
 
     // ISO date formats (RFC3339)
@@ -60,32 +57,45 @@ pub fn parse_time_input(input: &str) -> Option<i64> {
         return Some(dt.timestamp_millis());
     }
 
-    // YYYY-MM-DD or YYYY/MM/DD (Local midnight)
-    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d")
-        .or_else(|_| NaiveDate::parse_from_str(&input, "%Y/%m/%d"))
+    // Local date and time without a zone
+    for format in ["%Y-%m-%dt%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(&input, format) {
+            return Local
+                .from_local_datetime(&dt)
+                .earliest()
+                .map(|dt| dt.timestamp_millis());
+        }
+    }
+
+    // YYYY-MM-DD, YYYY/MM/DD, and the US MM/DD/YYYY, MM-DD-YYYY (Local midnight)
+    for format in ["%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y", "%m-%d-%Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(&input, format) {
+            return local_midnight(date);
+        }
+    }
+
+    // YYYY-MM or YYYY/MM: the first of the month
+    if let Some((year, month)) = input.split_once(['-', '/'])
+        && year.len() == 4
+        && let (Ok(year), Ok(month)) = (year.parse(), month.parse())
+        && let Some(date) = NaiveDate::from_ymd_opt(year, month, 1)
     {
-        let dt = date.and_hms_opt(0, 0, 0)?;
-        return Local
-            .from_local_datetime(&dt)
-            .single()
-            .map(|dt| dt.with_timezone(&Utc).timestamp_millis());
+        return local_midnight(date);
     }
 
-    // US Formats: MM/DD/YYYY or MM-DD-YYYY
-    if let Ok(date) = NaiveDate::parse_from_str(&input, "%m/%d/%Y")
-        .or_else(|_| NaiveDate::parse_from_str(&input, "%m-%d-%Y"))
+    // YYYY: New Year's Day, rather than a timestamp in 1970
+    if input.len() == 4
+        && let Ok(year) = input.parse::<i32>()
+        && (1970..=2100).contains(&year)
     {
-        let dt = date.and_hms_opt(0, 0, 0)?;
-        return Local
-            .from_local_datetime(&dt)
-            .single()
-            .map(|dt| dt.with_timezone(&Utc).timestamp_millis());
+        return local_midnight(NaiveDate::from_ymd_opt(year, 1, 1)?);
     }
+
     // Numeric fallback (ms or seconds)
     if let Ok(n) = input.parse::<i64>() {
         // Heuristic: timestamps < 10^11 (year 5138) are likely seconds.
         if n < 100_000_000_000 {
-            return Some(n * 1000);
+            return n.checked_mul(1000);
         }
         return Some(n);
     }
@@ -93,9 +103,62 @@ pub fn parse_time_input(input: &str) -> Option<i64> {
     None
 }
 
+/// "7d", "-24h", "3 months ago": that long before `now`.
+fn parse_relative(input: &str, now: DateTime<Local>) -> Option<i64> {
+    let rest = input.strip_prefix('-').unwrap_or(input);
+    let rest = rest.strip_suffix(" ago").unwrap_or(rest);
+    let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+    let val: u32 = rest[..digits].parse().ok()?;
+    // Overflowing amounts are rejected rather than wrapped or panicked on.
+    let span = match rest[digits..].trim() {
+        "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes(val.into())?,
+        "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours(val.into())?,
+        "d" | "day" | "days" => Duration::try_days(val.into())?,
+        "w" | "wk" | "wks" | "week" | "weeks" => Duration::try_weeks(val.into())?,
+        "mo" | "mon" | "month" | "months" => {
+            return Some(now.checked_sub_months(Months::new(val))?.timestamp_millis());
+        }
+        "y" | "yr" | "yrs" | "year" | "years" => {
+            let months = Months::new(val.checked_mul(12)?);
+            return Some(now.checked_sub_months(months)?.timestamp_millis());
+        }
+        _ => return None,
+    };
+    let then = now.checked_sub_signed(span)?;
+    Some(then.timestamp_millis())
+}
+
+/// "monday" is the latest Monday up to today; "last monday" the latest before today.
+fn parse_weekday(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let (name, strictly_before) = match input.strip_prefix("last ") {
+        Some(name) => (name, true),
+        None => (input, false),
+    };
+    let weekday: chrono::Weekday = name.parse().ok()?;
+    let mut back =
+        (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    if strictly_before && back == 0 {
+        back = 7;
+    }
+    Some(today - Duration::days(back.into()))
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday().into())
+}
+
+/// Epoch milliseconds of local midnight starting `date`.
+pub(crate) fn local_midnight(date: NaiveDate) -> Option<i64> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|dt| dt.timestamp_millis())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Utc;
 
     #[test]
     fn test_relative_time() {
@@ -131,6 +194,42 @@ mod tests {
         assert!(parse_time_input("01-01-2023").is_some());
     }
 
+    #[test]
+    fn test_date_math() {
+        // Wednesday 2024-06-12, 15:00 local.
+        let now = Local.with_ymd_and_hms(2024, 6, 12, 15, 0, 0).unwrap();
+        let at = |input| parse_time_input_at(input, now).unwrap();
+        let day = |y, m, d| local_midnight(NaiveDate::from_ymd_opt(y, m, d).unwrap()).unwrap();
+
+        assert_eq!(at("7d"), at("-7d"));
+        assert_eq!(at("7 days ago"), at("-7d"));
+        assert_eq!(at("2 weeks"), (now - Duration::weeks(2)).timestamp_millis());
+        assert_eq!(
+            at("3mo"),
+            Local
+                .with_ymd_and_hms(2024, 3, 12, 15, 0, 0)
+                .unwrap()
+                .timestamp_millis()
+        );
+        assert_eq!(at("last monday"), day(2024, 6, 10));
+        assert_eq!(at("Wednesday"), day(2024, 6, 12));
+        assert_eq!(at("last wed"), day(2024, 6, 5));
+        assert_eq!(at("this week"), day(2024, 6, 10));
+        assert_eq!(at("last month"), day(2024, 5, 1));
+        assert_eq!(at("this year"), day(2024, 1, 1));
+        assert_eq!(at("2024-06"), day(2024, 6, 1));
+        assert_eq!(at("2023"), day(2023, 1, 1));
+        assert_eq!(
+            at("2024-06-01 09:30"),
+            Local
+                .with_ymd_and_hms(2024, 6, 1, 9, 30, 0)
+                .unwrap()
+                .timestamp_millis()
+        );
+        assert!(parse_time_input_at("last blursday", now).is_none());
+        assert!(parse_time_input_at("2024-13", now).is_none());
+    }
+
     #[test]
     fn test_numeric() {
         let _sec = 1700000000;
@@ -138,4 +237,18 @@ mod tests {
         assert_eq!(parse_time_input("1700000000").unwrap(), ms);
         assert_eq!(parse_time_input("1700000000000").unwrap(), ms);
     }
+
+    #[test]
+    fn overflowing_relative_amounts_are_rejected() {
+        for input in [
+            "100000000d",
+            "4000000000h",
+            "4000000000w",
+            "999999999y",
+            "4000000000mo",
+            "-9223372036854775807",
+        ] {
+            assert_eq!(parse_time_input(input), None, "{input}");
+        }
+    }
 }
@@ -10,30 +10,41 @@ use crossterm::execute;
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
 };
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 use ratatui::prelude::*;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{
     Block, BorderType, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap,
 };
+use ratatui::{TerminalOptions, Viewport};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io;
 use std::process::Command as StdCommand;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
 use std::time::{Duration, Instant};
 use syntect::easy::HighlightLines;
 use syntect::highlighting::{Theme, ThemeSet};
 use syntect::parsing::SyntaxSet;
 
 use crate::default_data_dir;
+use crate::i18n::tr;
 use crate::model::types::MessageRole;
-use crate::search::query::{CacheStats, QuerySuggestion, SearchClient, SearchFilters, SearchHit};
+use crate::search::query::{
+    CacheStats, QuerySuggestion, RecencyDecay, SearchClient, SearchFilters, SearchHit,
+    SearchResult, SuggestionKind,
+};
 use crate::search::tantivy::index_dir;
+use crate::ui::components::agent_picker::{self, AgentPickerState};
+use crate::ui::components::date_picker::{self, DatePickerState};
 use crate::ui::components::help_strip;
 use crate::ui::components::palette::{self, PaletteAction, PaletteState};
 use crate::ui::components::pills::{self, Pill};
 use crate::ui::components::theme::ThemePalette;
+use crate::ui::components::toast::{Toast, ToastManager, render_toasts};
 use crate::ui::components::widgets::search_bar;
+use crate::ui::components::workspace_picker::{self, WorkspacePickerState};
 use crate::ui::data::{ConversationView, InputMode, load_conversation, role_style};
 use crate::ui::shortcuts;
 use crate::update_check::{
@@ -82,14 +93,76 @@ pub enum MatchMode {
     Prefix,
 }
 
+/// How relevance, recency and match quality are blended when results are listed
+/// by relevance.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum RankingMode {
     RecentHeavy,
     Balanced,
     RelevanceHeavy,
     MatchQualityHeavy, // Prioritizes exact matches over wildcard/fuzzy
-    DateNewest,        // Pure newest-first (ignores relevance score)
-    DateOldest,        // Pure oldest-first (ignores relevance score)
+}
+
+impl RankingMode {
+    /// Weight of the engine's recency decay.
+    pub fn recency_weight(self) -> f32 {
+        match self {
+            RankingMode::RecentHeavy => 1.0,
+            RankingMode::Balanced => crate::config::ranking().recency_alpha.unwrap_or(0.4),
+            RankingMode::RelevanceHeavy => 0.1,
+            RankingMode::MatchQualityHeavy => 0.2, // Low recency, high quality focus
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            RankingMode::RecentHeavy => RankingMode::Balanced,
+            RankingMode::Balanced => RankingMode::RelevanceHeavy,
+            RankingMode::RelevanceHeavy => RankingMode::MatchQualityHeavy,
+            RankingMode::MatchQualityHeavy => RankingMode::RecentHeavy,
+        }
+    }
+
+    /// Short name, as persisted and shown in the search bar.
+    fn label(self) -> &'static str {
+        match self {
+            RankingMode::RecentHeavy => "recent",
+            RankingMode::Balanced => "balanced",
+            RankingMode::RelevanceHeavy => "relevance",
+            RankingMode::MatchQualityHeavy => "quality",
+        }
+    }
+}
+
+/// Order results are listed in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    /// Best match first, weighed by the [`RankingMode`] blend.
+    #[default]
+    Relevance,
+    /// Newest message first, ignoring the score.
+    Newest,
+    /// Oldest message first, ignoring the score.
+    Oldest,
+}
+
+impl SortOrder {
+    fn next(self) -> Self {
+        match self {
+            SortOrder::Relevance => SortOrder::Newest,
+            SortOrder::Newest => SortOrder::Oldest,
+            SortOrder::Oldest => SortOrder::Relevance,
+        }
+    }
+
+    /// Short name, as persisted and shown in the search bar.
+    fn label(self) -> &'static str {
+        match self {
+            SortOrder::Relevance => "relevance",
+            SortOrder::Newest => "newest",
+            SortOrder::Oldest => "oldest",
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -130,15 +203,16 @@ impl ContextWindow {
 }
 
 /// Display density presets for result lists.
-/// Controls lines-per-item and visual spacing.
+/// Controls how many rows each result takes and how much of the message it previews.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum DensityMode {
-    /// 2 lines per item: header + location. Maximum items visible.
+    /// 2 rows per item: header + 1 snippet line, no location line. Maximum items visible.
     Compact,
-    /// 4 lines per item: header + location + 2 snippet lines. Balanced.
+    /// 4 rows per item: header + location + 2 snippet lines. Balanced.
     #[default]
     Cozy,
-    /// 6 lines per item: header + location + 4 snippet lines. Maximum context.
+    /// 7 rows per item: header + location + 4 snippet lines + a blank spacer row.
+    /// Maximum context.
     Spacious,
 }
 
@@ -154,16 +228,28 @@ impl DensityMode {
     /// Max snippet lines to render per result for this density.
     fn snippet_lines(self) -> usize {
         match self {
-            DensityMode::Compact => 3,
-            DensityMode::Cozy => 5,
-            DensityMode::Spacious => 8,
+            DensityMode::Compact => 1,
+            DensityMode::Cozy => 2,
+            DensityMode::Spacious => 4,
+        }
+    }
+
+    /// Whether results get the `[file] … [ws] …` line under their header.
+    fn shows_location(self) -> bool {
+        !matches!(self, DensityMode::Compact)
+    }
+
+    /// Blank rows after each result.
+    fn padding_rows(self) -> usize {
+        match self {
+            DensityMode::Spacious => 1,
+            DensityMode::Compact | DensityMode::Cozy => 0,
         }
     }
 
-    /// Approximate lines per item for layout sizing (header + location + snippet).
+    /// Rows per item for layout sizing (header + location + snippet + padding).
     fn lines_per_item(self) -> usize {
-        // 2 lines for header/location + snippet allowance.
-        2 + self.snippet_lines()
+        1 + usize::from(self.shows_location()) + self.snippet_lines() + self.padding_rows()
     }
 
     fn label(self) -> &'static str {
@@ -173,6 +259,83 @@ impl DensityMode {
             DensityMode::Spacious => "Spacious",
         }
     }
+
+    /// The density `[tui] density` in the config file starts the TUI with.
+    fn configured() -> Self {
+        match crate::config::tui().density {
+            crate::config::Density::Compact => DensityMode::Compact,
+            crate::config::Density::Cozy => DensityMode::Cozy,
+            crate::config::Density::Spacious => DensityMode::Spacious,
+        }
+    }
+}
+
+/// How the results list and the detail pane share the screen below the search bar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PaneLayout {
+    /// Results and a live preview of the selected hit, side by side when the terminal
+    /// is wide enough and stacked otherwise.
+    #[default]
+    Split,
+    /// Results across the full width.
+    Results,
+    /// The preview across the full width; the selection still follows the arrow keys.
+    Preview,
+}
+
+impl PaneLayout {
+    fn next(self) -> Self {
+        match self {
+            PaneLayout::Split => PaneLayout::Results,
+            PaneLayout::Results => PaneLayout::Preview,
+            PaneLayout::Preview => PaneLayout::Split,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            PaneLayout::Split => "Split",
+            PaneLayout::Results => "Results",
+            PaneLayout::Preview => "Preview",
+        }
+    }
+}
+
+/// Narrowest main area that fits the results and the preview side by side.
+const SIDE_BY_SIDE_MIN_WIDTH: u16 = 100;
+
+/// Messages shown above the hit's own when the preview opens on it.
+const PREVIEW_CONTEXT_MESSAGES: usize = 2;
+
+/// The results and detail areas of `area` for `layout`; a hidden pane gets an empty
+/// rect. The pane with focus gets the larger share.
+fn split_main_area(area: Rect, layout: PaneLayout, focus: FocusRegion) -> (Rect, Rect) {
+    let hidden = Rect {
+        width: 0,
+        height: 0,
+        ..area
+    };
+    let side_by_side = area.width >= SIDE_BY_SIDE_MIN_WIDTH;
+    let (results_pct, detail_pct) = match (layout, focus, side_by_side) {
+        (PaneLayout::Results, ..) => return (area, hidden),
+        (PaneLayout::Preview, ..) => return (hidden, area),
+        (PaneLayout::Split, FocusRegion::Results, true) => (55, 45),
+        (PaneLayout::Split, FocusRegion::Detail, true) => (40, 60),
+        (PaneLayout::Split, FocusRegion::Results, false) => (70, 30),
+        (PaneLayout::Split, FocusRegion::Detail, false) => (50, 50),
+    };
+    let split = Layout::default()
+        .direction(if side_by_side {
+            Direction::Horizontal
+        } else {
+            Direction::Vertical
+        })
+        .constraints([
+            Constraint::Percentage(results_pct),
+            Constraint::Percentage(detail_pct),
+        ])
+        .split(area);
+    (split[0], split[1])
 }
 
 #[derive(Serialize, Deserialize, Default)]
@@ -194,6 +357,10 @@ struct TuiStatePersisted {
     per_pane_limit: Option<usize>,
     /// Persisted ranking mode (bead 46t.1): "recent", "balanced", "relevance", etc.
     ranking_mode: Option<String>,
+    /// Result order: "relevance", "newest", or "oldest".
+    sort_order: Option<String>,
+    /// Results/preview layout: "split", "results", or "preview".
+    pane_layout: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -204,6 +371,7 @@ struct SavedViewPersisted {
     created_from: Option<i64>,
     created_to: Option<i64>,
     ranking: Option<String>,
+    sort: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -214,6 +382,7 @@ struct SavedView {
     created_from: Option<i64>,
     created_to: Option<i64>,
     ranking: RankingMode,
+    sort: SortOrder,
 }
 
 #[derive(Clone, Debug)]
@@ -259,11 +428,11 @@ fn score_bar(score: f32, palette: ThemePalette) -> Vec<Span<'static>> {
 
     vec![
         Span::styled(
-            "●".repeat(filled),
+            palette.glyphs.score_filled.repeat(filled),
             Style::default().fg(color).add_modifier(modifier),
         ),
         Span::styled(
-            "○".repeat(empty),
+            palette.glyphs.score_empty.repeat(empty),
             Style::default()
                 .fg(palette.hint)
                 .add_modifier(Modifier::DIM),
@@ -796,6 +965,49 @@ fn format_relative_time(timestamp_ms: i64) -> String {
     }
 }
 
+/// Footer segment telling how current the index is, e.g. "12034 docs · indexed 3m ago ·
+/// profile work · Alt+r reindex".
+fn freshness_label(
+    docs: Option<u64>,
+    last_scan_ms: Option<i64>,
+    indexing: bool,
+    profile: Option<&str>,
+) -> String {
+    let mut parts = Vec::new();
+    if let Some(docs) = docs {
+        parts.push(format!("{docs} docs"));
+    }
+    parts.push(if indexing {
+        "indexing…".to_string()
+    } else {
+        match last_scan_ms {
+            Some(ts) => format!("indexed {}", format_relative_time(ts)),
+            None => "never indexed".to_string(),
+        }
+    });
+    if let Some(profile) = profile {
+        parts.push(format!("profile {profile}"));
+    }
+    parts.push(format!("{} reindex", shortcuts::REINDEX));
+    parts.join(" · ")
+}
+
+/// Ask the background indexer to rescan every source, returning the status line.
+fn request_reindex(
+    reindex_tx: Option<&crossbeam_channel::Sender<crate::indexer::IndexerEvent>>,
+) -> String {
+    let Some(tx) = reindex_tx else {
+        return "Reindex needs the background indexer; run `cass index`".to_string();
+    };
+    match tx.send(crate::indexer::IndexerEvent::Command(
+        crate::indexer::ReindexCommand::Full,
+    )) {
+        Ok(()) => "Triggered background re-index...".to_string(),
+        // The indexer thread stops when another `cass index` holds the index.
+        Err(_) => "Another cass index keeps the index current".to_string(),
+    }
+}
+
 /// Formats a timestamp as an absolute string with date and time in UTC.
 fn format_absolute_time(timestamp_ms: i64) -> String {
     DateTime::<Utc>::from_timestamp_millis(timestamp_ms).map_or_else(
@@ -819,7 +1031,7 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
 
     // Welcome / Layout section (bead 019)
     lines.push(Line::from(Span::styled(
-        "Welcome to CASS - Coding Agent Session Search",
+        tr("help-welcome"),
         palette.title(),
     )));
     lines.push(Line::from(""));
@@ -858,7 +1070,7 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
 
     // Data Directories section
     lines.extend(add_section(
-        "Data Locations",
+        &tr("help-data-locations"),
         &[
             "Index & state: ~/.local/share/coding-agent-search/".to_string(),
             "  agent_search.db - Full-text search index".to_string(),
@@ -871,7 +1083,7 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
 
     // Updates section
     lines.extend(add_section(
-        "Updates",
+        &tr("help-updates"),
         &[
             "Checks GitHub releases hourly (offline-friendly, no auto-download)".to_string(),
             "When available: banner shows at top with U/S/Esc options".to_string(),
@@ -882,7 +1094,7 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
     ));
 
     lines.extend(add_section(
-        "Search",
+        &tr("help-search"),
         &[
             format!(
                 "type to live-search; {} focuses query; {} cycles history",
@@ -896,34 +1108,43 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
         ],
     ));
     lines.extend(add_section(
-        "Filters",
+        &tr("help-filters"),
         &[
             format!("{} agent | {} workspace | {} from | {} to | {} clear all", 
                 shortcuts::FILTER_AGENT, shortcuts::FILTER_WORKSPACE, shortcuts::FILTER_DATE_FROM, shortcuts::FILTER_DATE_TO, shortcuts::CLEAR_FILTERS),
             format!("{} scope to active agent | {} clear scope | {} cycle time presets (24h/7d/30d/all)",
                 shortcuts::SCOPE_AGENT, shortcuts::SCOPE_WORKSPACE, shortcuts::CYCLE_TIME_PRESETS),
+            format!("{} cycle role: all → user → assistant → tool", shortcuts::CYCLE_ROLE),
             "Chips in search bar; Backspace removes last; Enter (query empty) edits last chip".to_string(),
         ],
     ));
     lines.extend(add_section(
-        "Modes",
+        &tr("help-modes"),
         &[
             format!(
                 "{} match mode: prefix (default) ⇄ standard",
                 shortcuts::MATCH_MODE
             ),
             format!(
-                "{} ranking: recent → balanced → relevance → match-quality",
+                "{} ranking: recent → balanced → relevance → quality",
                 shortcuts::RANKING
             ),
+            format!(
+                "{} sort: relevance → newest → oldest (ranking applies to relevance)",
+                shortcuts::SORT
+            ),
             format!(
                 "{} theme: dark/light | Ctrl+B toggle border style",
                 shortcuts::THEME
             ),
+            format!(
+                "{} layout: results | preview → results only → preview only",
+                shortcuts::LAYOUT
+            ),
         ],
     ));
     lines.extend(add_section(
-        "Context",
+        &tr("help-context"),
         &[
             format!(
                 "{} cycles S/M/L/XL context window",
@@ -933,11 +1154,17 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
         ],
     ));
     lines.extend(add_section(
-        "Density",
-        &["Shift+=/+ increase pane items; - decrease (min 4, max 50)".to_string()],
+        &tr("help-density"),
+        &[
+            format!(
+                "{} cycles Compact/Cozy/Spacious result rows ([tui] density sets the default)",
+                shortcuts::DENSITY
+            ),
+            "Shift+=/+ increase pane items; - decrease (min 4, max 50)".to_string(),
+        ],
     ));
     lines.extend(add_section(
-        "Navigation",
+        &tr("help-navigation"),
         &[
             "Arrows move; Left/Right pane; PgUp/PgDn page".to_string(),
             format!(
@@ -951,26 +1178,46 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
                 shortcuts::BULK_MENU
             ),
             "Ctrl+Enter queue item; Ctrl+O open all queued".to_string(),
-            format!("{} toggles focus (Results ⇄ Detail)", shortcuts::TAB_FOCUS),
+            "Ctrl+K bookmark/unbookmark the message (★; cass bookmark ls)".to_string(),
+            format!(
+                "{} (in Detail, or the palette) pins/unpins the conversation above the results",
+                shortcuts::PIN
+            ),
+            format!(
+                "{} (in Detail, or the palette) continues the session in its agent (claude/codex/gemini/...)",
+                shortcuts::RESUME
+            ),
+            format!(
+                "{} (in Detail, or the palette) tails the conversation: new messages appear as the agent writes them",
+                shortcuts::TAIL
+            ),
+            format!(
+                "{} folds/unfolds the selected conversation in the results",
+                shortcuts::FOLD_CONVERSATION
+            ),
+            format!(
+                "{} toggles focus (Results ⇄ Detail; Tab also returns from Detail)",
+                shortcuts::FOCUS_SWITCH
+            ),
             "[ / ] cycle detail tabs (Messages/Snippets/Raw)".to_string(),
         ],
     ));
     lines.extend(add_section(
-        "Mouse",
+        &tr("help-mouse"),
         &[
             "Click pane/item to select; click detail area to focus".to_string(),
             "Scroll wheel: navigate results or scroll detail".to_string(),
         ],
     ));
     lines.extend(add_section(
-        "Actions",
+        &tr("help-actions"),
         &[
             format!(
-                "{} opens detail modal (o=open, c=copy, p=path, s=snip, n=nano, Esc=close)",
+                "{} opens detail modal (o=open, e=edit, c=copy, y=message, p=path, s=snip, n=nano, Esc=close)",
                 shortcuts::DETAIL_OPEN
             ),
             format!(
-                "{} open hit in $EDITOR; {} copy path/content",
+                "{} (or e in detail) open hit in $EDITOR at its line; {} copy path/content",
                 shortcuts::EDITOR,
                 shortcuts::COPY
             ),
@@ -986,7 +1233,7 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
         ],
     ));
     lines.extend(add_section(
-        "States",
+        &tr("help-states"),
         &[
             "UI state persists in tui_state.json (data dir).".to_string(),
             format!(
@@ -996,7 +1243,7 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
         ],
     ));
     lines.extend(add_section(
-        "Empty state",
+        &tr("help-empty-state"),
         &[
             "Shows recent per-agent hits before typing".to_string(),
             "Recent query suggestions appear when query is empty".to_string(),
@@ -1106,6 +1353,11 @@ fn render_parsed_content(
         lines.push(Line::from(""));
     }
 
+    for note in detail.notes.iter().filter(|n| n.msg_idx.is_none()) {
+        lines.push(note_line(note, palette));
+        lines.push(Line::from(""));
+    }
+
     lines.push(Line::from(Span::styled(
         "─".repeat(60),
         Style::default().fg(palette.hint),
@@ -1142,6 +1394,10 @@ fn render_parsed_content(
         let parsed_lines = parse_message_content(content, query, palette);
         lines.extend(parsed_lines);
         lines.push(Line::from(""));
+        for note in detail.notes.iter().filter(|n| n.msg_idx == Some(msg.idx)) {
+            lines.push(note_line(note, palette));
+            lines.push(Line::from(""));
+        }
         lines.push(Line::from(Span::styled(
             "─".repeat(60),
             Style::default()
@@ -1154,6 +1410,25 @@ fn render_parsed_content(
     lines
 }
 
+/// A `cass note` annotation, set apart from the conversation's own text.
+fn note_line(note: &crate::notes::Note, palette: ThemePalette) -> Line<'static> {
+    Line::from(vec![
+        Span::styled("📌 ", Style::default()),
+        Span::styled(
+            format!("Note #{}: ", note.id),
+            Style::default()
+                .fg(palette.accent_alt)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            note.text.clone(),
+            Style::default()
+                .fg(palette.accent_alt)
+                .add_modifier(Modifier::ITALIC),
+        ),
+    ])
+}
+
 /// Parse message content and render with beautiful formatting.
 /// Handles code blocks, tool calls, JSON, and highlights search terms.
 fn parse_message_content(content: &str, query: &str, palette: ThemePalette) -> Vec<Line<'static>> {
@@ -1161,43 +1436,46 @@ fn parse_message_content(content: &str, query: &str, palette: ThemePalette) -> V
     let mut in_code_block = false;
     let mut code_lang: Option<String> = None;
     let mut code_buffer: Vec<String> = Vec::new();
+    // Unfenced diff being collected (tool output, `apply_patch` envelopes).
+    let mut diff_buffer: Vec<String> = Vec::new();
+    let all: Vec<&str> = content.lines().collect();
 
-    for line_text in content.lines() {
+    for (i, &line_text) in all.iter().enumerate() {
         let trimmed = line_text.trim_start();
 
+        if !in_code_block && !diff_buffer.is_empty() {
+            if continues_diff(&diff_buffer, line_text) {
+                diff_buffer.push(line_text.to_string());
+                if line_text.trim_end() == "*** End Patch" {
+                    lines.extend(diff_block_lines(&diff_buffer, " diff", query, palette));
+                    diff_buffer.clear();
+                }
+                continue;
+            }
+            lines.extend(diff_block_lines(&diff_buffer, " diff", query, palette));
+            diff_buffer.clear();
+        }
+
         // Handle code block start/end
         if trimmed.starts_with("```") {
             if in_code_block {
                 // End of code block - render buffered code
                 in_code_block = false;
+                let lang = code_lang.take().unwrap_or_default();
                 if !code_buffer.is_empty() {
-                    let lang_label = code_lang
-                        .take()
-                        .filter(|l| !l.is_empty())
-                        .map(|l| format!(" {l}"))
-                        .unwrap_or_default();
-                    lines.push(Line::from(vec![
-                        Span::styled("┌──", Style::default().fg(palette.hint)),
-                        Span::styled(
-                            lang_label,
-                            Style::default()
-                                .fg(palette.accent_alt)
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                    ]));
-                    for code_line in code_buffer.drain(..) {
-                        lines.push(Line::from(vec![
-                            Span::styled("│ ", Style::default().fg(palette.hint)),
-                            Span::styled(
-                                code_line,
-                                Style::default().fg(palette.fg).bg(palette.surface),
-                            ),
-                        ]));
-                    }
-                    lines.push(Line::from(Span::styled(
-                        "└──",
-                        Style::default().fg(palette.hint),
-                    )));
+                    let label = if lang.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" {lang}")
+                    };
+                    lines.extend(fenced_block_lines(
+                        &code_buffer,
+                        &lang,
+                        &label,
+                        query,
+                        palette,
+                    ));
+                    code_buffer.clear();
                 }
             } else {
                 // Start of code block - extract language (first word after ```)
@@ -1213,6 +1491,11 @@ fn parse_message_content(content: &str, query: &str, palette: ThemePalette) -> V
             continue;
         }
 
+        if starts_diff(line_text, all.get(i + 1).copied()) {
+            diff_buffer.push(line_text.to_string());
+            continue;
+        }
+
         // Handle tool call markers
         if trimmed.starts_with("[Tool:") || trimmed.starts_with("⚙️") {
             lines.push(Line::from(vec![
@@ -1287,28 +1570,250 @@ fn parse_message_content(content: &str, query: &str, palette: ThemePalette) -> V
 
     // Handle unclosed code block
     if in_code_block && !code_buffer.is_empty() {
-        lines.push(Line::from(Span::styled(
-            "┌── code",
-            Style::default().fg(palette.hint),
-        )));
-        for code_line in code_buffer {
-            lines.push(Line::from(vec![
-                Span::styled("│ ", Style::default().fg(palette.hint)),
-                Span::styled(
-                    code_line,
-                    Style::default().fg(palette.fg).bg(palette.surface),
-                ),
-            ]));
-        }
-        lines.push(Line::from(Span::styled(
-            "└──",
-            Style::default().fg(palette.hint),
-        )));
+        let lang = code_lang.unwrap_or_default();
+        lines.extend(fenced_block_lines(
+            &code_buffer,
+            &lang,
+            " code",
+            query,
+            palette,
+        ));
+    }
+    if !diff_buffer.is_empty() {
+        lines.extend(diff_block_lines(&diff_buffer, " diff", query, palette));
+    }
+
+    lines
+}
+
+/// A fenced block as a diff when it is tagged or shaped like one, as code otherwise.
+fn fenced_block_lines(
+    code: &[String],
+    lang: &str,
+    label: &str,
+    query: &str,
+    palette: ThemePalette,
+) -> Vec<Line<'static>> {
+    let tagged = matches!(lang, "diff" | "patch" | "udiff");
+    if tagged || (lang.is_empty() && looks_like_diff(code)) {
+        let label = if label.is_empty() { " diff" } else { label };
+        diff_block_lines(code, label, query, palette)
+    } else {
+        code_block_lines(code, lang, label, query, palette)
+    }
+}
+
+/// Whether an untagged block is a unified diff or an `apply_patch` envelope.
+fn looks_like_diff(code: &[String]) -> bool {
+    let first = code.first().map_or("", |l| l.trim_start());
+    if first.starts_with("diff --git ") || first.starts_with("*** Begin Patch") {
+        return true;
     }
+    code.iter().any(|l| l.starts_with("@@ "))
+        && code
+            .iter()
+            .any(|l| l.starts_with('+') || l.starts_with('-'))
+}
+
+/// Whether `line` opens an unfenced diff: a git header, an `apply_patch` envelope, or a
+/// `---` file header directly followed by its `+++` line.
+fn starts_diff(line: &str, next: Option<&str>) -> bool {
+    line.starts_with("diff --git ")
+        || line.trim_end() == "*** Begin Patch"
+        || (line.starts_with("--- ") && next.is_some_and(|n| n.starts_with("+++ ")))
+}
+
+/// Whether `line` still belongs to the unfenced diff collected in `diff`. An
+/// `apply_patch` envelope runs to its `*** End Patch`; a unified diff runs while lines
+/// carry a diff prefix or a git extended header.
+fn continues_diff(diff: &[String], line: &str) -> bool {
+    if diff
+        .first()
+        .is_some_and(|l| l.trim_end() == "*** Begin Patch")
+    {
+        return true;
+    }
+    const HEADERS: &[&str] = &[
+        "diff ",
+        "index ",
+        "new file mode",
+        "deleted file mode",
+        "old mode",
+        "new mode",
+        "similarity index",
+        "rename from",
+        "rename to",
+        "Binary files",
+    ];
+    matches!(line.chars().next(), Some(' ' | '+' | '-' | '@' | '\\'))
+        || HEADERS.iter().any(|h| line.starts_with(h))
+}
+
+/// A diff framed like a code block, with added lines green, removed lines red, hunk
+/// headers and file headers set apart so a patch reads at a glance.
+fn diff_block_lines(
+    diff: &[String],
+    label: &str,
+    query: &str,
+    palette: ThemePalette,
+) -> Vec<Line<'static>> {
+    let dark = crate::ui::components::theme::relative_luminance(palette.bg) < 0.5;
+    let removed = if dark {
+        crate::ui::components::theme::colors::STATUS_ERROR
+    } else {
+        Color::Rgb(203, 36, 49)
+    };
+    let frame = Style::default().fg(palette.hint);
+    let base = Style::default().fg(palette.fg).bg(palette.surface);
+    let mut lines = Vec::with_capacity(diff.len() + 2);
+    lines.push(Line::from(vec![
+        Span::styled("┌──", frame),
+        Span::styled(
+            label.to_string(),
+            Style::default()
+                .fg(palette.accent_alt)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]));
+    for line in diff {
+        let style = if ["+++ ", "--- ", "diff ", "index ", "*** "]
+            .iter()
+            .any(|h| line.starts_with(h))
+        {
+            base.fg(palette.accent).add_modifier(Modifier::BOLD)
+        } else if line.starts_with("@@") {
+            base.fg(palette.accent_alt)
+        } else if line.starts_with('+') {
+            base.fg(palette.user)
+        } else if line.starts_with('-') {
+            base.fg(removed)
+        } else {
+            base
+        };
+        let mut spans = vec![Span::styled("│ ", frame)];
+        spans.extend(highlight_spans_owned(line, query, palette, style));
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(Span::styled("└──", frame)));
+    lines
+}
 
+/// A fenced code block framed for the detail view, highlighted for `lang` when syntect
+/// knows it (by name, extension, or the block's first line) and plain otherwise.
+fn code_block_lines(
+    code: &[String],
+    lang: &str,
+    label: &str,
+    query: &str,
+    palette: ThemePalette,
+) -> Vec<Line<'static>> {
+    let frame = Style::default().fg(palette.hint);
+    let base = Style::default().fg(palette.fg).bg(palette.surface);
+    let highlighted = highlight_code_block(code, lang, palette);
+    let mut lines = Vec::with_capacity(code.len() + 2);
+    lines.push(Line::from(vec![
+        Span::styled("┌──", frame),
+        Span::styled(
+            label.to_string(),
+            Style::default()
+                .fg(palette.accent_alt)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]));
+    for (i, code_line) in code.iter().enumerate() {
+        let mut spans = vec![Span::styled("│ ", frame)];
+        match highlighted.as_ref().and_then(|h| h.get(i)) {
+            Some(tokens) => {
+                for (style, text) in tokens {
+                    spans.extend(highlight_spans_owned(
+                        text,
+                        query,
+                        palette,
+                        base.patch(*style),
+                    ));
+                }
+            }
+            None => spans.extend(highlight_spans_owned(code_line, query, palette, base)),
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(Span::styled("└──", frame)));
     lines
 }
 
+/// Syntax-highlighted tokens of each line of a code block.
+type HighlightedBlock = Arc<Vec<Vec<(Style, String)>>>;
+
+/// Highlighted blocks by hash of language, theme and code, since the detail view is
+/// re-rendered on every frame and syntect is far too slow to run that often.
+static CODE_HIGHLIGHTS: Lazy<Mutex<HashMap<u64, HighlightedBlock>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Most blocks kept in [`CODE_HIGHLIGHTS`]; it is emptied when full.
+const CODE_HIGHLIGHTS_MAX: usize = 512;
+
+/// Longest block highlighted; bigger ones (pasted logs, minified files) stay plain.
+const CODE_HIGHLIGHT_MAX_BYTES: usize = 256 * 1024;
+
+/// The tokens of `code` as highlighted by syntect, with the dark or light syntect theme
+/// picked by the palette's background and token backgrounds left to the palette; `None`
+/// when the language is unknown or the block is too big.
+fn highlight_code_block(
+    code: &[String],
+    lang: &str,
+    palette: ThemePalette,
+) -> Option<HighlightedBlock> {
+    use std::hash::{Hash, Hasher};
+
+    if code.iter().map(|l| l.len() + 1).sum::<usize>() > CODE_HIGHLIGHT_MAX_BYTES {
+        return None;
+    }
+    let dark = crate::ui::components::theme::relative_luminance(palette.bg) < 0.5;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    (lang, dark, code).hash(&mut hasher);
+    let key = hasher.finish();
+    if let Some(block) = CODE_HIGHLIGHTS.lock().ok()?.get(&key) {
+        return Some(Arc::clone(block));
+    }
+
+    let assets = syntax_assets()?;
+    let syntax = Some(lang.trim())
+        .filter(|l| !l.is_empty())
+        .and_then(|l| assets.ps.find_syntax_by_token(l))
+        .or_else(|| assets.ps.find_syntax_by_first_line(code.first()?))?;
+    let theme = if dark {
+        &assets.theme_dark
+    } else {
+        &assets.theme_light
+    };
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut block = Vec::with_capacity(code.len());
+    for line in code {
+        // The newline-aware syntaxes need the line ending to close line comments.
+        let line = format!("{line}\n");
+        let ranges = highlighter.highlight_line(&line, &assets.ps).ok()?;
+        block.push(
+            ranges
+                .into_iter()
+                .map(|(style, text)| {
+                    (
+                        syntect_style_to_ratatui(style),
+                        text.trim_end_matches('\n').to_string(),
+                    )
+                })
+                .filter(|(_, text)| !text.is_empty())
+                .collect(),
+        );
+    }
+    let block = Arc::new(block);
+    let mut cache = CODE_HIGHLIGHTS.lock().ok()?;
+    if cache.len() >= CODE_HIGHLIGHTS_MAX {
+        cache.clear();
+    }
+    cache.insert(key, Arc::clone(&block));
+    Some(block)
+}
+
 /// Render the full-screen detail modal for viewing parsed conversation content.
 fn render_detail_modal(
     frame: &mut Frame,
@@ -1329,7 +1834,7 @@ fn render_detail_modal(
 
     // Build title with scroll position and hints
     let title_text = format!(
-        " {} · line {}/{} · Esc · o open · c copy · p path · s snip · n nano ",
+        " {} · line {}/{} · Esc · o open · e edit · r resume · c copy · y msg · p path · s snip · l link · n nano ",
         hit.title, display_line, total_lines
     );
 
@@ -1354,36 +1859,571 @@ fn render_detail_modal(
     );
 }
 
-/// Calculate optimal items per pane based on terminal height.
-///
-/// Layout overhead (approximate):
-/// - 1 line top margin
-/// - 3 lines search bar (border + query + tips)
-/// - 1 line filter pills
-/// - 2 lines pane borders (top + bottom)
-/// - 1 line footer
-/// - 1 line bottom margin
-///
-/// Total: ~9 lines overhead.
-/// Results area is 70% of remaining height.
-/// Lines per item depends on density mode (Compact=2, Cozy=4, Spacious=6).
-fn calculate_pane_limit(terminal_height: u16, density: DensityMode) -> usize {
-    const OVERHEAD: u16 = 9;
-    const RESULTS_PERCENT: f32 = 0.70;
-    const MIN_ITEMS: usize = 3;
-    const MAX_ITEMS: usize = 30;
+/// Line offset of the message with index `msg_idx` in the rendered detail modal.
+fn message_scroll_offset(detail: &ConversationView, msg_idx: i64) -> u16 {
+    let mut preceding = detail.clone();
+    preceding.messages.retain(|m| m.idx < msg_idx);
+    let lines = render_parsed_content(&preceding, "", ThemePalette::dark());
+    u16::try_from(lines.len()).unwrap_or(u16::MAX)
+}
 
-    let lines_per_item = density.lines_per_item();
-    let available = terminal_height.saturating_sub(OVERHEAD);
-    let results_height = (f32::from(available) * RESULTS_PERCENT) as usize;
-    let items = results_height / lines_per_item;
-    items.clamp(MIN_ITEMS, MAX_ITEMS)
+/// Scroll offset that opens the detail pane a few messages above message `msg_idx`, with
+/// rendered lines counted as wrapped to `width` columns.
+fn preview_scroll_offset(detail: &ConversationView, msg_idx: i64, width: u16) -> u16 {
+    let Some(pos) = detail.messages.iter().position(|m| m.idx == msg_idx) else {
+        return 0;
+    };
+    let first = detail.messages[pos.saturating_sub(PREVIEW_CONTEXT_MESSAGES)].idx;
+    let mut preceding = detail.clone();
+    preceding.messages.retain(|m| m.idx < first);
+    let width = usize::from(width.max(1));
+    let rows: usize = render_parsed_content(&preceding, "", ThemePalette::dark())
+        .iter()
+        .map(|line| line.width().div_ceil(width).max(1))
+        .sum();
+    u16::try_from(rows).unwrap_or(u16::MAX)
 }
 
-/// Return a filtered view of results using the pane-local filter (case-insensitive).
-fn apply_pane_filter(results: &[SearchHit], pane_filter: Option<&str>) -> Vec<SearchHit> {
-    if let Some(filter) = pane_filter.map(str::trim).filter(|s| !s.is_empty()) {
-        let needle = filter.to_lowercase();
+/// The turn around a hit, read from its session file: the user prompt that led to it
+/// and the assistant reply that followed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct AdjacentTurns {
+    prompt: Option<String>,
+    reply: Option<String>,
+}
+
+/// [`AdjacentTurns`] for the message at `line` among a session file's JSON `messages`.
+/// A hit that is itself a prompt gets no separate prompt.
+fn adjacent_turns(messages: &[(usize, serde_json::Value)], line: usize) -> AdjacentTurns {
+    let Some(target) = messages.iter().position(|(l, _)| *l >= line) else {
+        return AdjacentTurns::default();
+    };
+    let text = |msg: &serde_json::Value| {
+        let text = crate::extract_text_content(msg);
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        (!text.is_empty()).then_some(text)
+    };
+    let prompt = (crate::extract_role(&messages[target].1) != "user")
+        .then(|| {
+            messages[..target]
+                .iter()
+                .rev()
+                .find(|(_, m)| crate::extract_role(m) == "user")
+                .and_then(|(_, m)| text(m))
+        })
+        .flatten();
+    let reply = messages[target + 1..]
+        .iter()
+        .find(|(_, m)| crate::extract_role(m) == "assistant")
+        .and_then(|(_, m)| text(m));
+    AdjacentTurns { prompt, reply }
+}
+
+/// [`adjacent_turns`] of the hit at `line` of `source_path`; empty when the file is gone
+/// or is not JSON lines.
+fn load_adjacent_turns(source_path: &str, line: usize) -> AdjacentTurns {
+    std::fs::File::open(source_path)
+        .map(|f| crate::jsonl_messages(std::io::BufReader::new(f)))
+        .map(|messages| adjacent_turns(&messages, line))
+        .unwrap_or_default()
+}
+
+/// The command that opens `path` in `editor`, which may carry arguments (`code --wait`),
+/// at `line` when given: `--goto path:line` for VS Code and its forks, `<line_flag><line>
+/// path` for the rest (vi, nano, emacs, helix...). `None` for an empty `editor`.
+fn editor_command(
+    editor: &str,
+    line_flag: &str,
+    path: &str,
+    line: Option<usize>,
+) -> Option<StdCommand> {
+    let mut words = editor.split_whitespace();
+    let program = words.next()?;
+    let mut cmd = StdCommand::new(program);
+    cmd.args(words);
+    let name = std::path::Path::new(program)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(program);
+    match line {
+        Some(line) if matches!(name, "code" | "code-insiders" | "codium" | "cursor") => {
+            cmd.arg("--goto").arg(format!("{path}:{line}"));
+        }
+        Some(line) => {
+            cmd.arg(format!("{line_flag}{line}")).arg(path);
+        }
+        None => {
+            cmd.arg(path);
+        }
+    }
+    Some(cmd)
+}
+
+/// Open `hit`'s session file in `editor` at its line with the TUI suspended, returning
+/// the status line to show.
+fn edit_hit<B: Backend>(
+    terminal: &mut Terminal<B>,
+    editor: &str,
+    line_flag: &str,
+    hit: &SearchHit,
+) -> String {
+    let Some(mut cmd) = editor_command(editor, line_flag, &hit.source_path, hit.line_number) else {
+        return "✗ $EDITOR is empty".to_string();
+    };
+    match run_suspended(terminal, &mut cmd) {
+        Ok(exit) if exit.success() => format!("Returned from {editor}"),
+        Ok(exit) => format!("✗ {editor} exited with {exit}"),
+        Err(e) => format!("✗ Failed to launch {editor}: {e}"),
+    }
+}
+
+/// The agent's resume command for `hit`'s session. The session id is taken back out of
+/// the conversation key, which holds the connector's external id when it had one.
+fn resume_command_for_hit(hit: &SearchHit) -> Option<crate::resume::ResumeCommand> {
+    let external_id = hit
+        .conversation_id
+        .strip_prefix(hit.agent.as_str())
+        .and_then(|rest| rest.strip_prefix(':'))
+        .filter(|id| *id != hit.source_path);
+    let workspace = (!hit.workspace.is_empty()).then(|| std::path::Path::new(&hit.workspace));
+    crate::resume::command_for(
+        &hit.agent,
+        external_id,
+        std::path::Path::new(&hit.source_path),
+        workspace,
+    )
+}
+
+/// Continue `hit`'s session in its agent with the TUI suspended, returning the status
+/// line to show.
+fn resume_hit<B: Backend>(terminal: &mut Terminal<B>, hit: &SearchHit) -> String {
+    let Some(resume) = resume_command_for_hit(hit) else {
+        return if crate::resume::SUPPORTED.contains(&hit.agent.as_str()) {
+            format!("✗ Cannot tell the {} session id of this hit", hit.agent)
+        } else {
+            format!("✗ No resume command is known for {} sessions", hit.agent)
+        };
+    };
+    let mut cmd = StdCommand::new(&resume.program);
+    cmd.args(&resume.args);
+    if let Some(dir) = &resume.cwd {
+        cmd.current_dir(dir);
+    }
+    let program = &resume.program;
+    match run_suspended(terminal, &mut cmd) {
+        Ok(exit) if exit.success() => format!("Returned from {program}"),
+        Ok(exit) => format!("✗ {program} exited with {exit}"),
+        Err(e) => format!("✗ Failed to launch {program}: {e} (is it on PATH?)"),
+    }
+}
+
+/// How often a tailed conversation is re-read from the index.
+const TAIL_POLL: Duration = Duration::from_secs(1);
+
+/// A conversation followed with `t`, like `tail -f`: messages the agent appends to its
+/// source file show up in the detail view once the background indexer has written them.
+struct TailState {
+    source_path: String,
+    /// Messages already shown; `None` until the first poll.
+    seen: Option<usize>,
+    /// Source file mtime at the last poll, to point the indexer at it when it changes.
+    modified: Option<std::time::SystemTime>,
+    polled_at: Option<Instant>,
+    /// Scroll the detail view to its last line on the next draw.
+    jump_to_end: bool,
+}
+
+impl TailState {
+    fn new(source_path: &str) -> Self {
+        Self {
+            source_path: source_path.to_string(),
+            seen: None,
+            modified: None,
+            polled_at: None,
+            jump_to_end: true,
+        }
+    }
+
+    fn due(&self) -> bool {
+        self.polled_at.is_none_or(|at| at.elapsed() >= TAIL_POLL)
+    }
+
+    /// Re-read the conversation, returning it with the number of messages new since the
+    /// last poll when its length changed. A changed source file is handed to the
+    /// indexer first, in case its watcher does not cover it.
+    fn poll(
+        &mut self,
+        storage: &crate::storage::sqlite::SqliteStorage,
+        reindex_tx: Option<&crossbeam_channel::Sender<crate::indexer::IndexerEvent>>,
+    ) -> Option<(ConversationView, usize)> {
+        self.polled_at = Some(Instant::now());
+        let modified = std::fs::metadata(&self.source_path)
+            .and_then(|m| m.modified())
+            .ok();
+        if modified != self.modified {
+            if self.modified.is_some()
+                && let Some(tx) = reindex_tx
+            {
+                let path = std::path::PathBuf::from(&self.source_path);
+                tx.send(crate::indexer::IndexerEvent::Notify(vec![path]))
+                    .ok();
+            }
+            self.modified = modified;
+        }
+        let view = load_conversation(storage, &self.source_path)
+            .ok()
+            .flatten()?;
+        let count = view.messages.len();
+        let added = match self.seen {
+            Some(seen) if seen == count => return None,
+            Some(seen) => count.saturating_sub(seen),
+            None => 0,
+        };
+        self.seen = Some(count);
+        self.jump_to_end = true;
+        Some((view, added))
+    }
+}
+
+/// Start tailing `hit`'s conversation, or stop if it is the one tailed, returning the
+/// status line to show.
+fn toggle_tail(tail: &mut Option<TailState>, hit: &SearchHit) -> String {
+    if tail
+        .as_ref()
+        .is_some_and(|t| t.source_path == hit.source_path)
+    {
+        *tail = None;
+        return "Stopped tailing".to_string();
+    }
+    *tail = Some(TailState::new(&hit.source_path));
+    format!(
+        "Tailing {}: new messages appear as they are indexed ({} stops)",
+        truncate_path(&hit.source_path, 50),
+        shortcuts::TAIL
+    )
+}
+
+/// Set while the TUI draws in an inline viewport under the prompt (`cass tui --height`)
+/// rather than on the alternate screen, for the places that hand the terminal to an
+/// editor and take it back.
+static INLINE_VIEWPORT: AtomicBool = AtomicBool::new(false);
+
+/// Fewest rows an inline viewport gets: the search bar, footer and a few results.
+const MIN_INLINE_HEIGHT: u16 = 12;
+
+/// Hand the terminal back for another program: cooked mode, no mouse capture, and off
+/// the alternate screen when the TUI is on it.
+fn leave_tui_screen() {
+    disable_raw_mode().ok();
+    if !INLINE_VIEWPORT.load(Ordering::Relaxed) {
+        execute!(io::stdout(), LeaveAlternateScreen).ok();
+    }
+    execute!(io::stdout(), DisableMouseCapture).ok();
+}
+
+/// Undo [`leave_tui_screen`].
+fn enter_tui_screen() {
+    if !INLINE_VIEWPORT.load(Ordering::Relaxed) {
+        execute!(io::stdout(), EnterAlternateScreen).ok();
+    }
+    execute!(io::stdout(), EnableMouseCapture).ok();
+    enable_raw_mode().ok();
+}
+
+/// Rewrite the cells of `buf` that spell the text of each `(text, url)` in `links` as an
+/// OSC 8 hyperlink to its url, in the same colors. Ratatui has no notion of links and
+/// counts the escapes as printed width, so they go over the drawn frame instead; the
+/// next draw overwrites changed cells with plain ones and the links are written again.
+fn write_hyperlinks(
+    out: &mut impl io::Write,
+    buf: &Buffer,
+    links: &[(String, String)],
+) -> io::Result<()> {
+    use crossterm::cursor::{MoveTo, RestorePosition, SavePosition};
+    use crossterm::queue;
+    use crossterm::style::{
+        Attribute, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+    };
+
+    queue!(out, SavePosition)?;
+    let area = buf.area;
+    for y in area.top()..area.bottom() {
+        // Byte offset in `row` where each cell's symbol starts.
+        let mut row = String::new();
+        let mut starts = Vec::with_capacity(area.width as usize);
+        for x in area.left()..area.right() {
+            starts.push((row.len(), x));
+            row.push_str(buf[(x, y)].symbol());
+        }
+        for (text, url) in links {
+            let Some(offset) = row.find(text.as_str()) else {
+                continue;
+            };
+            let Some(first) = starts.iter().position(|&(b, _)| b == offset) else {
+                continue;
+            };
+            queue!(
+                out,
+                MoveTo(starts[first].1, y),
+                Print(crate::hyperlink::open(url))
+            )?;
+            for &(_, x) in starts[first..]
+                .iter()
+                .take_while(|(b, _)| *b < offset + text.len())
+            {
+                let cell = &buf[(x, y)];
+                queue!(
+                    out,
+                    SetAttribute(Attribute::Reset),
+                    SetForegroundColor(cell.fg.into()),
+                    SetBackgroundColor(cell.bg.into())
+                )?;
+                for (modifier, attribute) in [
+                    (Modifier::BOLD, Attribute::Bold),
+                    (Modifier::DIM, Attribute::Dim),
+                    (Modifier::ITALIC, Attribute::Italic),
+                    (Modifier::UNDERLINED, Attribute::Underlined),
+                    (Modifier::REVERSED, Attribute::Reverse),
+                ] {
+                    if cell.modifier.contains(modifier) {
+                        queue!(out, SetAttribute(attribute))?;
+                    }
+                }
+                queue!(out, Print(cell.symbol()))?;
+            }
+            queue!(
+                out,
+                Print(crate::hyperlink::CLOSE),
+                SetAttribute(Attribute::Reset),
+                ResetColor
+            )?;
+        }
+    }
+    queue!(out, RestorePosition)?;
+    out.flush()
+}
+
+/// Run `command` on the terminal the TUI is drawn on: leave raw mode and the alternate
+/// screen, wait for it, then restore the terminal whatever state the program left it in.
+/// An editor that crashes skips its own cleanup, so its tty settings, colors, bracketed
+/// paste and screen contents are all reset before the TUI redraws from scratch.
+fn run_suspended<B: Backend>(
+    terminal: &mut Terminal<B>,
+    command: &mut StdCommand,
+) -> io::Result<std::process::ExitStatus> {
+    leave_tui_screen();
+    execute!(io::stdout(), crossterm::cursor::Show).ok();
+    let tty = saved_tty_settings();
+    let result = command.status();
+    if let Some(settings) = tty {
+        restore_tty_settings(&settings);
+    }
+    execute!(
+        io::stdout(),
+        crossterm::style::ResetColor,
+        crossterm::event::DisableBracketedPaste
+    )
+    .ok();
+    enter_tui_screen();
+    terminal.clear().ok();
+    result
+}
+
+/// The terminal's cooked-mode settings as `stty -g` prints them.
+#[cfg(unix)]
+fn saved_tty_settings() -> Option<String> {
+    let out = StdCommand::new("stty")
+        .arg("-g")
+        .stdin(std::process::Stdio::inherit())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&out.stdout).trim().to_string()).filter(|s| !s.is_empty())
+}
+
+#[cfg(not(unix))]
+fn saved_tty_settings() -> Option<String> {
+    None
+}
+
+/// Put back settings from [`saved_tty_settings`], before raw mode is entered again, so
+/// raw mode is not built on (and later restored to) what a crashed program left behind.
+fn restore_tty_settings(settings: &str) {
+    let _ = StdCommand::new("stty")
+        .arg(settings)
+        .stdin(std::process::Stdio::inherit())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+}
+
+/// Build the `cass://` permalink for a message in the detail view.
+fn detail_permalink(detail: &ConversationView, hit: &SearchHit) -> crate::permalink::Permalink {
+    crate::permalink::Permalink::for_conversation(
+        &detail.convo.agent_slug,
+        detail.convo.external_id.as_deref(),
+        &detail.convo.source_path,
+        hit.line_number.map(|n| n.saturating_sub(1) as i64),
+    )
+}
+
+/// Copy text to the system clipboard with the configured method (see `crate::clipboard`),
+/// confirming the outcome with a toast.
+fn copy_to_clipboard(toasts: &mut ToastManager, text: &str) -> std::result::Result<(), String> {
+    let copied = crate::clipboard::copy(text).map(|_| ());
+    toasts.push(match &copied {
+        Ok(()) => Toast::success("Copied to clipboard"),
+        Err(e) => Toast::error(format!("Copy failed: {e}")),
+    });
+    copied
+}
+
+/// A search for [`SearchWorker`] to run.
+struct SearchJob {
+    seq: u64,
+    query: String,
+    filters: SearchFilters,
+    limit: usize,
+    offset: usize,
+    /// Fetch recent conversations to show instead when the query matches nothing
+    recent_on_empty: bool,
+}
+
+/// What [`SearchWorker`] found for the job with the same `seq`.
+struct SearchReply {
+    seq: u64,
+    elapsed: Duration,
+    result: Result<SearchResult>,
+    recent: Option<Vec<SearchHit>>,
+}
+
+/// Runs searches on a thread of its own so that typing never waits on the index.
+///
+/// Only the newest job matters: queued jobs it supersedes are skipped, and a search
+/// already running when the query changes has its reply dropped.
+struct SearchWorker {
+    jobs: mpsc::Sender<SearchJob>,
+    replies: mpsc::Receiver<SearchReply>,
+    latest: Arc<AtomicU64>,
+}
+
+impl SearchWorker {
+    fn spawn(client: SearchClient) -> Self {
+        // Use search_with_fallback for implicit wildcard expansion on sparse results
+        const SPARSE_THRESHOLD: usize = 3;
+        let (jobs, job_rx) = mpsc::channel::<SearchJob>();
+        let (reply_tx, replies) = mpsc::channel();
+        let latest = Arc::new(AtomicU64::new(0));
+        let wanted = latest.clone();
+        std::thread::spawn(move || {
+            while let Ok(mut job) = job_rx.recv() {
+                while let Ok(newer) = job_rx.try_recv() {
+                    job = newer;
+                }
+                if wanted.load(Ordering::Relaxed) != job.seq {
+                    continue;
+                }
+                let started = Instant::now();
+                let result = client.search_with_fallback(
+                    &job.query,
+                    job.filters,
+                    job.limit,
+                    job.offset,
+                    SPARSE_THRESHOLD,
+                );
+                let elapsed = started.elapsed();
+                let recent = match &result {
+                    Ok(found) if job.recent_on_empty && found.hits.is_empty() => Some(
+                        client
+                            .search("", SearchFilters::default(), job.limit, 0)
+                            .unwrap_or_default(),
+                    ),
+                    _ => None,
+                };
+                if wanted.load(Ordering::Relaxed) != job.seq {
+                    continue;
+                }
+                let reply = SearchReply {
+                    seq: job.seq,
+                    elapsed,
+                    result,
+                    recent,
+                };
+                if reply_tx.send(reply).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            jobs,
+            replies,
+            latest,
+        }
+    }
+
+    /// Queue a search in place of any earlier one.
+    fn submit(
+        &self,
+        query: String,
+        filters: SearchFilters,
+        limit: usize,
+        offset: usize,
+        recent_on_empty: bool,
+    ) {
+        let seq = self.latest.fetch_add(1, Ordering::Relaxed) + 1;
+        let _ = self.jobs.send(SearchJob {
+            seq,
+            query,
+            filters,
+            limit,
+            offset,
+            recent_on_empty,
+        });
+    }
+
+    /// Drop whatever search is queued or running.
+    fn cancel(&self) {
+        self.latest.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The reply to the newest search, once it has arrived.
+    fn poll(&self) -> Option<SearchReply> {
+        let latest = self.latest.load(Ordering::Relaxed);
+        self.replies.try_iter().filter(|r| r.seq == latest).last()
+    }
+}
+
+/// Calculate optimal items per pane based on terminal height.
+///
+/// Layout overhead (approximate):
+/// - 1 line top margin
+/// - 3 lines search bar (border + query + tips)
+/// - 1 line filter pills
+/// - 2 lines pane borders (top + bottom)
+/// - 1 line footer
+/// - 1 line bottom margin
+///
+/// Total: ~9 lines overhead.
+/// Results area is 70% of remaining height.
+/// Lines per item depends on density mode (Compact=2, Cozy=4, Spacious=7).
+fn calculate_pane_limit(terminal_height: u16, density: DensityMode) -> usize {
+    const OVERHEAD: u16 = 9;
+    const RESULTS_PERCENT: f32 = 0.70;
+    const MIN_ITEMS: usize = 3;
+    const MAX_ITEMS: usize = 30;
+
+    let lines_per_item = density.lines_per_item();
+    let available = terminal_height.saturating_sub(OVERHEAD);
+    let results_height = (f32::from(available) * RESULTS_PERCENT) as usize;
+    let items = results_height / lines_per_item;
+    items.clamp(MIN_ITEMS, MAX_ITEMS)
+}
+
+/// Return a filtered view of results using the pane-local filter (case-insensitive).
+fn apply_pane_filter(results: &[SearchHit], pane_filter: Option<&str>) -> Vec<SearchHit> {
+    if let Some(filter) = pane_filter.map(str::trim).filter(|s| !s.is_empty()) {
+        let needle = filter.to_lowercase();
         results
             .iter()
             .filter(|h| {
@@ -1429,9 +2469,89 @@ fn build_agent_panes(results: &[SearchHit], per_pane_limit: usize) -> Vec<AgentP
             });
         }
     }
+    for pane in &mut panes {
+        group_by_conversation(&mut pane.hits);
+    }
     panes
 }
 
+/// Conversation a hit belongs to. Older indexes carry no conversation id; the
+/// session file stands in.
+fn conversation_of(hit: &SearchHit) -> &str {
+    if hit.conversation_id.is_empty() {
+        &hit.source_path
+    } else {
+        &hit.conversation_id
+    }
+}
+
+/// Reorder ranked `hits` so each conversation's hits sit together, conversations
+/// in order of their best hit.
+fn group_by_conversation(hits: &mut [SearchHit]) {
+    let mut order: HashMap<String, usize> = HashMap::new();
+    for hit in hits.iter() {
+        let next = order.len();
+        order
+            .entry(conversation_of(hit).to_string())
+            .or_insert(next);
+    }
+    hits.sort_by_key(|hit| order[conversation_of(hit)]);
+}
+
+/// Index ranges of the conversations in a pane's (grouped) hits.
+fn conversation_ranges(hits: &[SearchHit]) -> Vec<std::ops::Range<usize>> {
+    let mut ranges: Vec<std::ops::Range<usize>> = Vec::new();
+    for (idx, hit) in hits.iter().enumerate() {
+        match ranges.last_mut() {
+            Some(range) if conversation_of(&hits[range.start]) == conversation_of(hit) => {
+                range.end = idx + 1;
+            }
+            _ => ranges.push(idx..idx + 1),
+        }
+    }
+    ranges
+}
+
+/// Whether the hit at `idx` is folded away: its conversation is collapsed and
+/// its header row already stands for the conversation's first hit.
+fn hit_folded(hits: &[SearchHit], collapsed: &HashSet<String>, idx: usize) -> bool {
+    idx > 0
+        && idx < hits.len()
+        && conversation_of(&hits[idx]) == conversation_of(&hits[idx - 1])
+        && collapsed.contains(conversation_of(&hits[idx]))
+}
+
+impl AgentPane {
+    /// Move the selection to the next (or previous) row, skipping hits folded
+    /// into a collapsed conversation. Returns false at either end of the list.
+    fn step_selection(&mut self, forward: bool, collapsed: &HashSet<String>) -> bool {
+        let mut idx = self.selected;
+        loop {
+            idx = if forward {
+                idx + 1
+            } else if let Some(prev) = idx.checked_sub(1) {
+                prev
+            } else {
+                return false;
+            };
+            if idx >= self.hits.len() {
+                return false;
+            }
+            if !hit_folded(&self.hits, collapsed, idx) {
+                self.selected = idx;
+                return true;
+            }
+        }
+    }
+
+    /// Move a selection that sits on a folded hit up to its conversation's header.
+    fn reveal_selection(&mut self, collapsed: &HashSet<String>) {
+        while hit_folded(&self.hits, collapsed, self.selected) {
+            self.selected -= 1;
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn rebuild_panes_with_filter(
     results: &[SearchHit],
@@ -1475,13 +2595,24 @@ fn rebuild_panes_with_filter(
     panes
 }
 
+/// Title for the results showing how many hits the search has and, while more
+/// pages remain, how many of them are fetched.
+fn hit_total_label(loaded: usize, total: Option<usize>, more: bool) -> String {
+    match total {
+        Some(total) if loaded < total => format!(" {loaded}/{total} hits "),
+        Some(total) => format!(" {total} hits "),
+        None if more => format!(" {loaded}+ hits "),
+        None => format!(" {loaded} hits "),
+    }
+}
+
 fn active_hit(panes: &[AgentPane], active_idx: usize) -> Option<&SearchHit> {
     panes
         .get(active_idx)
         .and_then(|pane| pane.hits.get(pane.selected))
 }
 
-/// Known agent slugs for autocomplete suggestions
+/// Agent slugs the agent picker offers when the index cannot be read
 const KNOWN_AGENTS: &[&str] = &[
     "claude_code",
     "codex",
@@ -1492,14 +2623,13 @@ const KNOWN_AGENTS: &[&str] = &[
     "opencode",
 ];
 
-/// Returns agent suggestions matching the given prefix (case-insensitive)
-fn agent_suggestions(prefix: &str) -> Vec<&'static str> {
-    let prefix_lower = prefix.to_lowercase();
-    KNOWN_AGENTS
+/// The engine's did-you-mean fix, shown on the status line when a typo leaves only a
+/// few (or only fallback) results.
+fn spelling_hint(suggestions: &[QuerySuggestion]) -> Option<&str> {
+    suggestions
         .iter()
-        .filter(|agent| agent.to_lowercase().starts_with(&prefix_lower))
-        .copied()
-        .collect()
+        .find(|s| matches!(s.kind, SuggestionKind::SpellingFix))
+        .map(|s| s.message.as_str())
 }
 
 /// Suggests a correction for a query based on history.
@@ -1556,6 +2686,14 @@ fn agent_color(agent: &str) -> Color {
         "chatgpt" => Color::Rgb(16, 163, 127), // ChatGPT Green
         "aider" => Color::Rgb(255, 165, 0), // Orange
         "pi_agent" => Color::Rgb(255, 140, 0), // Dark Orange (Pi)
+        "sgpt" => Color::Rgb(0, 191, 255),  // Deep Sky Blue (shell-gpt)
+        "copilot_cli" => Color::Rgb(110, 84, 148), // Copilot Purple (GitHub)
+        "qwen" => Color::Rgb(97, 92, 237),  // Indigo (Qwen)
+        "open_webui" => Color::Rgb(230, 230, 230), // Near White (Open WebUI)
+        "plandex" => Color::Rgb(0, 168, 232), // Azure (Plandex)
+        "crush" => Color::Rgb(255, 95, 175), // Hot Pink (Charm)
+        "vscode_chat" => Color::Rgb(0, 122, 204), // VS Code Blue
+        "trajectory" => Color::Rgb(189, 147, 249), // Lavender (imported runs)
         _ => Color::Rgb(169, 169, 169),     // Dark Gray (fallback)
     }
 }
@@ -1572,51 +2710,22 @@ fn dim_color(color: Color, factor: f32) -> Color {
     }
 }
 
+/// Window of `text` centered on the best cluster of query matches, sized by `window`.
 fn contextual_snippet(text: &str, query: &str, window: ContextWindow) -> String {
-    let size = window.size();
     if text.is_empty() {
         return String::new();
     }
-    let chars: Vec<char> = text.chars().collect();
-    let len = chars.len();
-    if len <= size {
-        return text.to_string();
-    }
-
+    // The whole query (as a phrase) and each of its words.
     let trimmed_query = query.trim();
-    let mut char_pos: usize = 0;
-
-    if !trimmed_query.is_empty() {
-        if text.is_ascii() && trimmed_query.is_ascii() {
-            // ASCII fast path: byte offsets are safe
-            let lowercase = text.to_lowercase();
-            let q = trimmed_query.to_lowercase();
-            let byte_pos = lowercase.find(&q).or_else(|| {
-                q.split_whitespace()
-                    .next()
-                    .and_then(|first| lowercase.find(first))
-            });
-            if let Some(b) = byte_pos {
-                char_pos = b.min(text.len());
-            }
-        } else {
-            // Unicode-safe: fall back to case-sensitive search on original text
-            let first_term = trimmed_query
-                .split_whitespace()
-                .find(|s| !s.is_empty())
-                .unwrap_or(trimmed_query);
-            if let Some(b) = text.find(first_term) {
-                char_pos = text[..b].chars().count();
-            }
-        }
-    }
-
-    let start = char_pos.saturating_sub(size / 2);
-    let end = (start + size).min(len);
-    let slice: String = chars[start..end].iter().collect();
-    let prefix = if start > 0 { "…" } else { "" };
-    let suffix = if end < len { "…" } else { "" };
-    format!("{prefix}{slice}{suffix}")
+    let terms: Vec<String> = std::iter::once(trimmed_query)
+        .chain(trimmed_query.split_whitespace())
+        .map(str::to_string)
+        .collect();
+    let matches = crate::search::query::match_ranges(text, &terms);
+    let range = crate::search::query::snippet_window(text, &matches, window.size());
+    let prefix = if range.start > 0 { "…" } else { "" };
+    let suffix = if range.end < text.len() { "…" } else { "" };
+    format!("{prefix}{}{suffix}", &text[range])
 }
 
 /// Smart word wrap for display lines (sux.6.6d).
@@ -1793,6 +2902,20 @@ fn syntect_color_to_ratatui(c: syntect::highlighting::Color) -> Color {
     Color::Rgb(c.r, c.g, c.b)
 }
 
+/// Foreground and font style of a syntect token; the background is the caller's.
+fn syntect_style_to_ratatui(style: syntect::highlighting::Style) -> Style {
+    use syntect::highlighting::FontStyle;
+
+    let mut out = Style::default().fg(syntect_color_to_ratatui(style.foreground));
+    if style.font_style.contains(FontStyle::BOLD) {
+        out = out.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        out = out.add_modifier(Modifier::ITALIC);
+    }
+    out
+}
+
 fn syntax_highlight_line(
     line: &str,
     path_hint: &str,
@@ -1832,19 +2955,72 @@ fn state_path_for(data_dir: &std::path::Path) -> std::path::PathBuf {
     data_dir.join("tui_state.json")
 }
 
+/// State file for a named profile (`--profile`/`CASS_PROFILE`), so each profile
+/// remembers its own sort, ranking and layout; no profile uses `tui_state.json`.
+fn profile_state_path(data_dir: &std::path::Path, profile: Option<&str>) -> std::path::PathBuf {
+    match profile.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(name) => {
+            let name: String = name
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            data_dir.join(format!("tui_state.{name}.json"))
+        }
+        None => state_path_for(data_dir),
+    }
+}
+
 fn ranking_from_str(s: &str) -> RankingMode {
     match s {
         "recent" => RankingMode::RecentHeavy,
         "relevance" => RankingMode::RelevanceHeavy,
         "quality" => RankingMode::MatchQualityHeavy,
-        "newest" => RankingMode::DateNewest,
-        "oldest" => RankingMode::DateOldest,
         _ => RankingMode::Balanced,
     }
 }
 
+fn sort_from_str(s: &str) -> SortOrder {
+    match s {
+        "newest" => SortOrder::Newest,
+        "oldest" => SortOrder::Oldest,
+        _ => SortOrder::Relevance,
+    }
+}
+
 use crate::ui::components::breadcrumbs::{self, BreadcrumbKind};
 
+/// Roles the role quick filter steps through after "all"; the search backends take them
+/// as [`SearchFilters::roles`] values.
+const ROLE_FILTER_CYCLE: [&str; 3] = ["user", "assistant", "tool"];
+
+/// The role filter after `roles` in the cycle all → user → assistant → tool → all. A set
+/// the cycle does not produce (from a saved search, say) restarts it.
+fn next_role_filter(roles: &HashSet<String>) -> HashSet<String> {
+    let current = match roles.iter().collect::<Vec<_>>().as_slice() {
+        [only] => ROLE_FILTER_CYCLE.iter().position(|r| r == only),
+        _ => None,
+    };
+    let next = match (roles.is_empty(), current) {
+        (true, _) | (false, None) => Some(0),
+        (false, Some(i)) => Some(i + 1).filter(|&i| i < ROLE_FILTER_CYCLE.len()),
+    };
+    next.map(|i| HashSet::from([ROLE_FILTER_CYCLE[i].to_string()]))
+        .unwrap_or_default()
+}
+
+/// The active role filter for pills and chips, `None` when every role is shown.
+fn role_filter_label(roles: &HashSet<String>) -> Option<String> {
+    let mut roles: Vec<&str> = roles.iter().map(String::as_str).collect();
+    roles.sort_unstable();
+    (!roles.is_empty()).then(|| roles.join("|"))
+}
+
 fn chips_for_filters(filters: &SearchFilters, palette: ThemePalette) -> Vec<Span<'static>> {
     let mut spans: Vec<Span<'static>> = Vec::new();
     if !filters.agents.is_empty() {
@@ -1884,6 +3060,20 @@ fn chips_for_filters(filters: &SearchFilters, palette: ThemePalette) -> Vec<Span
             spans.push(Span::raw(" ".to_string()));
         }
     }
+    if let Some(roles) = role_filter_label(&filters.roles) {
+        spans.push(Span::styled(
+            format!("[role:{roles}]"),
+            Style::default().fg(palette.accent_alt),
+        ));
+        spans.push(Span::raw(" ".to_string()));
+    }
+    if filters.source_paths.is_some() {
+        spans.push(Span::styled(
+            "[tagged]".to_string(),
+            Style::default().fg(palette.accent_alt),
+        ));
+        spans.push(Span::raw(" ".to_string()));
+    }
     spans
 }
 
@@ -1895,53 +3085,57 @@ fn contextual_shortcuts(
 ) -> Vec<(String, String)> {
     if palette_open {
         return vec![
-            (shortcuts::DETAIL_CLOSE.into(), "Close".into()),
-            ("↑/↓".into(), "Select".into()),
-            (shortcuts::DETAIL_OPEN.into(), "Run".into()),
+            (shortcuts::DETAIL_CLOSE.into(), tr("strip-close")),
+            ("↑/↓".into(), tr("strip-select")),
+            (shortcuts::DETAIL_OPEN.into(), tr("strip-run")),
         ];
     }
     if show_detail_modal {
         return vec![
-            (shortcuts::DETAIL_CLOSE.into(), "Close detail".into()),
-            ("j/k".into(), "Scroll".into()),
-            ("Home/End".into(), "Top/Bottom".into()),
-            ("c".into(), "Copy".into()),
+            (shortcuts::DETAIL_CLOSE.into(), tr("strip-close-detail")),
+            ("j/k".into(), tr("strip-scroll")),
+            ("Home/End".into(), tr("strip-top-bottom")),
+            ("c".into(), tr("strip-copy")),
         ];
     }
     match input_mode {
         InputMode::Agent => vec![
-            ("type".into(), "Agent filter".into()),
-            (shortcuts::DETAIL_OPEN.into(), "Apply".into()),
-            (shortcuts::DETAIL_CLOSE.into(), "Cancel".into()),
+            ("type".into(), tr("strip-agent-filter")),
+            ("Space".into(), tr("strip-toggle")),
+            (shortcuts::DETAIL_OPEN.into(), tr("strip-apply")),
+            (shortcuts::DETAIL_CLOSE.into(), tr("strip-cancel")),
         ],
         InputMode::Workspace => vec![
-            ("type".into(), "Workspace filter".into()),
-            (shortcuts::DETAIL_OPEN.into(), "Apply".into()),
-            (shortcuts::DETAIL_CLOSE.into(), "Cancel".into()),
+            ("type".into(), tr("strip-workspace-filter")),
+            (shortcuts::DETAIL_OPEN.into(), tr("strip-apply")),
+            (shortcuts::DETAIL_CLOSE.into(), tr("strip-cancel")),
         ],
         InputMode::PaneFilter => vec![
-            ("type".into(), "Pane filter".into()),
-            (shortcuts::DETAIL_OPEN.into(), "Apply".into()),
-            (shortcuts::DETAIL_CLOSE.into(), "Clear".into()),
+            ("type".into(), tr("strip-pane-filter")),
+            (shortcuts::DETAIL_OPEN.into(), tr("strip-apply")),
+            (shortcuts::DETAIL_CLOSE.into(), tr("strip-clear")),
         ],
         InputMode::CreatedFrom | InputMode::CreatedTo => vec![
-            ("type".into(), "Date (YYYY-MM-DD)".into()),
-            (shortcuts::DETAIL_OPEN.into(), "Apply".into()),
-            (shortcuts::DETAIL_CLOSE.into(), "Cancel".into()),
+            ("←↑↓→".into(), tr("strip-nav")),
+            ("Alt+t/w/m".into(), tr("strip-date-presets")),
+            ("type".into(), tr("strip-date")),
+            (shortcuts::DETAIL_OPEN.into(), tr("strip-apply")),
+            (shortcuts::DETAIL_CLOSE.into(), tr("strip-cancel")),
         ],
         InputMode::DetailFind => vec![
-            ("type".into(), "Find term".into()),
-            (shortcuts::DETAIL_OPEN.into(), "Apply".into()),
-            (shortcuts::DETAIL_CLOSE.into(), "Cancel".into()),
+            ("type".into(), tr("strip-find-term")),
+            (shortcuts::DETAIL_OPEN.into(), tr("strip-apply")),
+            (shortcuts::DETAIL_CLOSE.into(), tr("strip-cancel")),
         ],
         InputMode::Query => match focus_region {
             FocusRegion::Results => vec![
-                ("Ctrl+P".into(), "Palette".into()),
-                (shortcuts::VIM_NAV.into(), "Nav".into()),
-                (shortcuts::DETAIL_OPEN.into(), "Open detail".into()),
-                ("m".into(), "Select".into()),
-                (shortcuts::BULK_MENU.into(), "Bulk menu".into()),
-                (shortcuts::PANE_FILTER.into(), "Pane filter".into()),
+                ("Ctrl+P".into(), tr("strip-palette")),
+                (shortcuts::VIM_NAV.into(), tr("strip-nav")),
+                (shortcuts::DETAIL_OPEN.into(), tr("strip-open-detail")),
+                (shortcuts::FOLD_CONVERSATION.into(), tr("strip-fold")),
+                ("m".into(), tr("strip-select")),
+                (shortcuts::BULK_MENU.into(), tr("strip-bulk-menu")),
+                (shortcuts::PANE_FILTER.into(), tr("strip-pane-filter")),
                 (
                     format!(
                         "{}/{}/{}",
@@ -1949,18 +3143,21 @@ fn contextual_shortcuts(
                         shortcuts::FILTER_WORKSPACE,
                         shortcuts::FILTER_DATE_FROM
                     ),
-                    "Filters".into(),
+                    tr("strip-filters"),
                 ),
-                (shortcuts::QUIT.into(), "Quit/back".into()),
+                (shortcuts::QUIT.into(), tr("strip-quit-back")),
             ],
             FocusRegion::Detail => vec![
-                (shortcuts::TAB_FOCUS.into(), "Focus results".into()),
-                ("←/→".into(), "Tabs".into()),
-                (shortcuts::PANE_FILTER.into(), "Find in detail".into()),
-                ("n/N".into(), "Next/prev match".into()),
-                ("c".into(), "Copy".into()),
-                ("o".into(), "Open file".into()),
-                (shortcuts::DETAIL_CLOSE.into(), "Close detail".into()),
+                (shortcuts::TAB_FOCUS.into(), tr("strip-focus-results")),
+                ("←/→".into(), tr("strip-tabs")),
+                (shortcuts::PANE_FILTER.into(), tr("strip-find-in-detail")),
+                ("n/N".into(), tr("strip-next-prev-match")),
+                ("c".into(), tr("strip-copy")),
+                ("o".into(), tr("strip-open-file")),
+                (shortcuts::PIN.into(), tr("strip-pin")),
+                (shortcuts::RESUME.into(), tr("strip-resume")),
+                (shortcuts::TAIL.into(), tr("strip-tail")),
+                (shortcuts::DETAIL_CLOSE.into(), tr("strip-close-detail")),
             ],
         },
     }
@@ -1970,6 +3167,7 @@ fn save_view_slot(
     slot: u8,
     filters: &SearchFilters,
     ranking: RankingMode,
+    sort: SortOrder,
     saved_views: &mut Vec<SavedView>,
 ) -> String {
     if !(1..=9).contains(&slot) {
@@ -1983,6 +3181,7 @@ fn save_view_slot(
         created_from: filters.created_from,
         created_to: filters.created_to,
         ranking,
+        sort,
     });
     saved_views.sort_by_key(|v| v.slot);
     format!("Saved view to slot {slot}")
@@ -1992,6 +3191,7 @@ fn load_view_slot(
     slot: u8,
     filters: &mut SearchFilters,
     ranking: &mut RankingMode,
+    sort: &mut SortOrder,
     saved_views: &[SavedView],
 ) -> Option<String> {
     saved_views.iter().find(|v| v.slot == slot).map(|v| {
@@ -2000,10 +3200,161 @@ fn load_view_slot(
         filters.created_from = v.created_from;
         filters.created_to = v.created_to;
         *ranking = v.ranking;
+        *sort = v.sort;
         format!("Loaded view slot {slot}")
     })
 }
 
+/// Replace the query and filters with those of a `cass search --save` entry.
+fn load_saved_search(
+    search: &crate::saved_searches::SavedSearch,
+    data_dir: &std::path::Path,
+    query: &mut String,
+    filters: &mut SearchFilters,
+) -> Result<(), String> {
+    let times = search.time_filter().map_err(|e| e.message)?;
+    *query = if search.fuzzy {
+        crate::search::query::fuzzy_query(&search.query)
+    } else {
+        search.query.clone()
+    };
+    filters.agents = search.agents.iter().cloned().collect();
+    filters.workspaces = search.workspaces.iter().cloned().collect();
+    filters.created_from = times.since;
+    filters.created_to = times.until;
+    filters.source_paths = (!search.tags.is_empty())
+        .then(|| crate::session_tags::paths_with_any(data_dir, &search.tags));
+    if search.bookmarked {
+        let marked: HashSet<String> = load_bookmarks(data_dir)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        filters.source_paths = Some(match filters.source_paths.take() {
+            Some(tagged) => tagged.intersection(&marked).cloned().collect(),
+            None => marked,
+        });
+    }
+    Ok(())
+}
+
+/// `(source_path, line_number)` of every bookmark; a `None` line stars the whole session.
+fn load_bookmarks(data_dir: &std::path::Path) -> HashSet<(String, Option<usize>)> {
+    let path = crate::bookmarks::bookmarks_path(data_dir);
+    if !path.exists() {
+        return HashSet::new();
+    }
+    crate::bookmarks::BookmarkStore::open(&path)
+        .and_then(|store| store.list(None))
+        .map(|marks| {
+            marks
+                .into_iter()
+                .map(|m| (m.source_path, m.line_number))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Bookmark `hit`'s message, or remove its bookmark, returning the status line.
+fn toggle_bookmark(
+    data_dir: &std::path::Path,
+    hit: &SearchHit,
+    bookmarked: &mut HashSet<(String, Option<usize>)>,
+) -> String {
+    let key = (hit.source_path.clone(), hit.line_number);
+    let result = crate::bookmarks::BookmarkStore::open(&crate::bookmarks::bookmarks_path(data_dir))
+        .and_then(|store| {
+            if bookmarked.contains(&key) {
+                store.remove_at(&hit.source_path, hit.line_number)?;
+                Ok(false)
+            } else {
+                let mut mark = crate::bookmarks::Bookmark::new(
+                    hit.title.clone(),
+                    hit.source_path.clone(),
+                    hit.agent.clone(),
+                    hit.workspace.clone(),
+                )
+                .with_snippet(hit.snippet.clone());
+                if let Some(line) = hit.line_number {
+                    mark = mark.with_line(line);
+                }
+                store.add(&mark)?;
+                Ok(true)
+            }
+        });
+    match result {
+        Ok(true) => {
+            bookmarked.insert(key);
+            "★ Bookmarked · Ctrl+K again to remove · cass search --bookmarked".to_string()
+        }
+        Ok(false) => {
+            bookmarked.remove(&key);
+            "Bookmark removed".to_string()
+        }
+        Err(e) => format!("Bookmark failed: {e}"),
+    }
+}
+
+/// Pin `hit`'s conversation, or unpin it, returning the status line.
+fn toggle_pin(
+    data_dir: &std::path::Path,
+    hit: &SearchHit,
+    pins: &mut Vec<crate::pins::Pin>,
+) -> String {
+    let pin = crate::pins::Pin {
+        conversation: conversation_of(hit).to_string(),
+        title: hit.title.clone(),
+        agent: hit.agent.clone(),
+        workspace: hit.workspace.clone(),
+        source_path: hit.source_path.clone(),
+        pinned_at: chrono::Utc::now().timestamp_millis(),
+    };
+    match crate::pins::toggle(data_dir, pin) {
+        Ok((all, pinned)) => {
+            *pins = all;
+            if pinned {
+                format!(
+                    "📌 Pinned ({} pinned) · {} again to unpin",
+                    pins.len(),
+                    shortcuts::PIN
+                )
+            } else {
+                "Conversation unpinned".to_string()
+            }
+        }
+        Err(e) => format!("Pin failed: {e}"),
+    }
+}
+
+/// The pinned section above the results: one row per pinned conversation.
+fn pinned_lines(pins: &[crate::pins::Pin], palette: ThemePalette) -> Vec<Line<'static>> {
+    pins.iter()
+        .map(|pin| {
+            let title = if pin.title.is_empty() {
+                "(untitled)".to_string()
+            } else {
+                pin.title.clone()
+            };
+            let mut spans = vec![
+                Span::styled(
+                    title,
+                    Style::default().fg(palette.fg).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("  @{}", pin.agent),
+                    Style::default().fg(palette.hint),
+                ),
+            ];
+            if !pin.workspace.is_empty() {
+                spans.push(Span::styled(
+                    format!("  [ws] {}", truncate_path(&pin.workspace, 30)),
+                    Style::default().fg(palette.hint),
+                ));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
 fn load_state(path: &std::path::Path) -> TuiStatePersisted {
     std::fs::read_to_string(path)
         .ok()
@@ -2227,6 +3578,16 @@ fn quick_date_range_hours(hours: i64) -> Option<(i64, i64)> {
     Some((since.timestamp_millis(), now.timestamp_millis()))
 }
 
+/// Range and label for the date picker's Alt+t/w/m presets.
+fn date_picker_preset(key: char) -> Option<((i64, i64), &'static str)> {
+    match key {
+        't' => quick_date_range_today().map(|r| (r, "today")),
+        'w' => quick_date_range_week().map(|r| (r, "last 7 days")),
+        'm' => quick_date_range_hours(30 * 24).map(|r| (r, "last 30 days")),
+        _ => None,
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum FocusRegion {
     Results,
@@ -2250,7 +3611,8 @@ fn char_width(s: &str) -> usize {
 fn footer_shortcuts(max_width: usize) -> String {
     const SHORTCUTS: &[&str] = &[
         "j/k move",
-        "Tab focus",
+        "Tab fold",
+        "Shift+Tab focus",
         "Enter open",
         "/ query",
         "[ ] tabs",
@@ -2294,22 +3656,27 @@ fn footer_shortcuts(max_width: usize) -> String {
 // Legacy helper retained for tests/compat; superseded by `footer_shortcuts` in the live footer.
 pub fn footer_legend(show_help: bool) -> &'static str {
     if show_help {
-        "Esc quit • arrows nav • Tab focus • Enter view • F8 editor • F1-F9 commands • y copy"
+        "Esc quit • arrows nav • Tab fold • Shift+Tab focus • Enter view • F8 editor • F1-F9 commands • y copy"
     } else {
         "F1 help | Enter view | Esc quit"
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn run_tui(
     data_dir_override: Option<std::path::PathBuf>,
     once: bool,
     reset_state: bool,
     progress: Option<std::sync::Arc<crate::indexer::IndexingProgress>>,
     reindex_tx: Option<crossbeam_channel::Sender<crate::indexer::IndexerEvent>>,
+    focus: Option<crate::permalink::ResolvedPermalink>,
+    profile: Option<String>,
+    inline_height: Option<u16>,
 ) -> Result<()> {
     // Resolve data dir early so we can honor reset-state in headless mode too.
     let data_dir = data_dir_override.unwrap_or_else(default_data_dir);
-    let state_path = state_path_for(&data_dir);
+    let profile = profile.or_else(|| std::env::var("CASS_PROFILE").ok());
+    let state_path = profile_state_path(&data_dir, profile.as_deref());
 
     // Optional: wipe persisted UI state before loading defaults.
     if reset_state {
@@ -2324,11 +3691,23 @@ pub fn run_tui(
         return run_tui_headless(Some(data_dir));
     }
 
+    // With a height the TUI draws in that many rows under the prompt, like
+    // `fzf --height`, and leaves the rest of the screen alone.
+    let inline_height = inline_height.map(|h| h.max(MIN_INLINE_HEIGHT));
+    INLINE_VIEWPORT.store(inline_height.is_some(), Ordering::Relaxed);
     let mut stdout = io::stdout();
     enable_raw_mode()?;
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    if inline_height.is_none() {
+        execute!(stdout, EnterAlternateScreen)?;
+    }
+    execute!(stdout, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let viewport = inline_height.map_or(Viewport::Fullscreen, Viewport::Inline);
+    let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport })?;
+    // Rows the layout gets: the inline viewport, or the whole screen.
+    let screen_rows = |terminal: &Terminal<CrosstermBackend<io::Stdout>>| {
+        inline_height.unwrap_or_else(|| terminal.size().map(|r| r.height).unwrap_or(24))
+    };
 
     let index_path = index_dir(&data_dir)?;
     let db_path = default_db_path_for(&data_dir);
@@ -2339,6 +3718,10 @@ pub fn run_tui(
     let db_reader = crate::storage::sqlite::SqliteStorage::open_readonly(&db_path).ok();
 
     let index_ready = search_client.is_some();
+    let search_worker = search_client.map(SearchWorker::spawn);
+    // A search is with the worker; `searched_query` is what it was asked, match mode applied.
+    let mut search_pending = false;
+    let mut searched_query = String::new();
     let mut status = if index_ready {
         format!(
             "Index ready at {} - type to search (Esc/F10 quit, F1 help)",
@@ -2360,7 +3743,12 @@ pub fn run_tui(
     let mut input_mode = InputMode::Query;
     let mut input_buffer = String::new();
     let page_size: usize = 120;
-    // Load density mode from persisted state (case-insensitive)
+    // Load density mode from persisted state (case-insensitive), falling back to the
+    // config file's `[tui] density`
+    // `[tui] accessible`: ASCII glyphs, high contrast and spelled-out states.
+    let accessible = crate::config::tui().accessible;
+    let link_paths = crate::hyperlink::supported();
+    let configured_density = DensityMode::configured();
     let mut density_mode = match persisted
         .density_mode
         .as_deref()
@@ -2369,17 +3757,39 @@ pub fn run_tui(
     {
         Some("compact") => DensityMode::Compact,
         Some("spacious") => DensityMode::Spacious,
-        _ => DensityMode::Cozy, // Default
+        Some("cozy") => DensityMode::Cozy,
+        _ => configured_density,
+    };
+    let mut pane_layout = match persisted.pane_layout.as_deref() {
+        Some("results") => PaneLayout::Results,
+        Some("preview") => PaneLayout::Preview,
+        _ => PaneLayout::Split,
     };
     // Calculate initial pane limit based on terminal height and density,
     // or restore from persisted state if user previously adjusted it (bead 46t.1)
-    let initial_height = terminal.size().map(|r| r.height).unwrap_or(24);
+    let initial_height = screen_rows(&terminal);
     let mut per_pane_limit: usize = persisted
         .per_pane_limit
         .unwrap_or_else(|| calculate_pane_limit(initial_height, density_mode));
     let mut last_terminal_height: u16 = initial_height;
     let mut page: usize = 0;
     let mut results: Vec<SearchHit> = Vec::new();
+    // Messages the current search matches, when the index can count them.
+    let mut total_matches: Option<usize> = None;
+    // Whether the index holds hits past those fetched; the next page loads once the
+    // selection nears the end of a pane.
+    let mut more_results = false;
+    let mut appending = false;
+    // Screenfuls (`per_pane_limit` rows each) shown per pane so far.
+    let mut pane_pages: usize = 1;
+    // Rows from the end of a pane at which the next screenful or page loads.
+    const LOAD_MORE_MARGIN: usize = 3;
+    // Selection (pane, row) that last loaded more, so more loads only as it moves.
+    let mut load_more_at: Option<(usize, usize)> = None;
+    // `cass tag` entries, re-read with each new result set.
+    let mut session_tags = crate::session_tags::load(&data_dir);
+    // Pinned conversations, listed above the results.
+    let mut pins = crate::pins::load(&data_dir);
     let mut wildcard_fallback: bool = false; // True when search used implicit wildcards
     let mut suggestions: Vec<QuerySuggestion> = Vec::new(); // Did-you-mean suggestions for zero hits
     let cache_debug = std::env::var("CASS_DEBUG_CACHE_METRICS")
@@ -2394,11 +3804,17 @@ pub fn run_tui(
     let mut pane_scroll_offset: usize = 0; // First visible pane index
     // Multi-select state: (pane_index, hit_index) tuples of selected items
     let mut selected: HashSet<(usize, usize)> = HashSet::new();
+    // Conversations folded to their header row in the results panes (Tab)
+    let mut collapsed: HashSet<String> = HashSet::new();
+    // Bookmarked (source_path, line) pairs, shared with `cass bookmark`
+    let mut bookmarked = load_bookmarks(&data_dir);
     // Require double-confirm before opening a large queue of files
     const OPEN_CONFIRM_THRESHOLD: usize = 12;
     let mut open_confirm_armed = false;
     let mut focus_region = FocusRegion::Results;
     let mut detail_scroll: u16 = 0;
+    // The hit the preview last opened on, so it re-centers only when the selection moves.
+    let mut previewed_hit: Option<(String, Option<usize>)> = None;
     let mut focus_flash_until: Option<Instant> = None;
     let mut last_tick = Instant::now();
     let tick_rate = Duration::from_millis(30);
@@ -2438,6 +3854,19 @@ pub fn run_tui(
     let mut show_bulk_modal = false;
     let mut bulk_action_idx: usize = 0;
     let mut cached_detail: Option<(String, ConversationView)> = None;
+    // The conversation followed with `t`, if any.
+    let mut tail: Option<TailState> = None;
+    // Prompt and reply around the previewed hit, read from its session file on selection.
+    let mut turn_context: Option<((String, usize), AdjacentTurns)> = None;
+    // Doc count and last scan time for the footer, re-read every FRESHNESS_REFRESH.
+    const FRESHNESS_REFRESH: Duration = Duration::from_secs(10);
+    let read_freshness = |storage: Option<&crate::storage::sqlite::SqliteStorage>| {
+        storage.map_or((None, None), |s| {
+            (s.message_count().ok(), s.get_last_scan_ts().ok().flatten())
+        })
+    };
+    let mut freshness = read_freshness(db_reader.as_ref());
+    let mut freshness_read_at = Instant::now();
     let mut detail_find: Option<DetailFindState> = None;
     let mut last_query = String::new();
     let mut needs_draw = true;
@@ -2457,6 +3886,12 @@ pub fn run_tui(
         .ranking_mode
         .as_deref()
         .map_or(RankingMode::Balanced, ranking_from_str);
+    // Newest/oldest used to be ranking modes, so older state files keep them there.
+    let mut sort_order = persisted
+        .sort_order
+        .as_deref()
+        .or(persisted.ranking_mode.as_deref())
+        .map_or(SortOrder::Relevance, sort_from_str);
     let mut saved_views: Vec<SavedView> = persisted
         .saved_views
         .as_ref()
@@ -2474,6 +3909,11 @@ pub fn run_tui(
                                 .ranking
                                 .as_deref()
                                 .map_or(RankingMode::Balanced, ranking_from_str),
+                            sort: sv
+                                .sort
+                                .as_deref()
+                                .or(sv.ranking.as_deref())
+                                .map_or(SortOrder::Relevance, sort_from_str),
                         })
                     } else {
                         None
@@ -2506,7 +3946,16 @@ pub fn run_tui(
     let mut last_breadcrumb_rects: Vec<(Rect, BreadcrumbKind)> = Vec::new();
 
     // Command palette + help strip + pills state
-    let mut palette_state = PaletteState::new(palette::default_actions());
+    let mut palette_actions = palette::default_actions();
+    palette_actions.extend(palette::saved_search_actions(&crate::saved_searches::load(
+        &data_dir,
+    )));
+    let mut palette_state = PaletteState::new(palette_actions);
+    // Loaded from the index each time the agent or workspace filter is opened.
+    let mut agent_picker: Option<AgentPickerState> = None;
+    let mut workspace_picker: Option<WorkspacePickerState> = None;
+    // Calendar for the from/to filters; kept while moving from one bound to the other.
+    let mut date_picker: Option<DatePickerState> = None;
 
     // Keep a short history of indexer percentages for sparkline rendering
     let mut progress_history: std::collections::VecDeque<u8> =
@@ -2515,10 +3964,14 @@ pub fn run_tui(
     // Track last indexing state to detect changes and trigger redraw
     // Tuple: (phase, current, total, is_rebuild, discovered_agents)
     let mut last_indexing_state: Option<(usize, usize, usize, bool, usize)> = None;
+    // Background indexing passes seen so far, and how many conversations the indexer had
+    // written by the end of the last one, to announce each finished pass once.
+    let mut seen_index_passes: usize = 0;
+    let mut indexed_at_last_pass: usize = 0;
+    let mut toasts = ToastManager::new();
 
     // Helper to get indexing phase info (returns phase, current, total, is_rebuild, pct, discovered_agents)
     let get_indexing_state = |progress: &std::sync::Arc<crate::indexer::IndexingProgress>| -> (usize, usize, usize, bool, usize, usize) {
-        use std::sync::atomic::Ordering;
         let phase = progress.phase.load(Ordering::Relaxed);
         let total = progress.total.load(Ordering::Relaxed);
         let current = progress.current.load(Ordering::Relaxed);
@@ -2562,6 +4015,13 @@ pub fn run_tui(
             2 => ("📦", "Indexing"),
             _ => ("⏳", "Processing"),
         };
+        let agent = progress
+            .current_agent
+            .lock()
+            .ok()
+            .and_then(|a| a.clone())
+            .map(|a| format!(" {a}"))
+            .unwrap_or_default();
 
         let bar_width = 8;
         // Avoid 0/0 stalls: if total is zero we still show a moving bar.
@@ -2577,7 +4037,7 @@ pub fn run_tui(
             format!(" | {icon} {phase_str} ({discovered} agents found)")
         } else {
             // During indexing, show items progress
-            format!(" | {icon} {phase_str} {current}/{total} ({pct}%) {bar}")
+            format!(" | {icon} {phase_str}{agent} {current}/{total} sessions ({pct}%) {bar}")
         };
 
         if !spark.is_empty() && phase == 2 {
@@ -2591,13 +4051,71 @@ pub fn run_tui(
         s
     };
 
+    // Permalink launch (`cass open-url`): open the linked conversation in the detail
+    // modal, scrolled to the addressed message, instead of starting on an empty query.
+    if let Some(target) = focus {
+        let loaded = db_reader.as_ref().and_then(|storage| {
+            load_conversation(storage, &target.source_path)
+                .ok()
+                .flatten()
+                .map(|mut view| {
+                    view.notes = crate::notes::for_session(&data_dir, &target.source_path);
+                    view
+                })
+        });
+        if let Some(view) = loaded {
+            let focused_msg = target
+                .msg_idx
+                .and_then(|idx| view.messages.iter().find(|m| m.idx == idx));
+            let hit = SearchHit {
+                title: view
+                    .convo
+                    .title
+                    .clone()
+                    .or(target.title.clone())
+                    .unwrap_or_else(|| target.source_path.clone()),
+                snippet: String::new(),
+                content: focused_msg.map(|m| m.content.clone()).unwrap_or_default(),
+                score: 1.0,
+                source_path: target.source_path.clone(),
+                agent: view.convo.agent_slug.clone(),
+                workspace: view
+                    .convo
+                    .workspace
+                    .as_ref()
+                    .map(|w| w.display().to_string())
+                    .unwrap_or_default(),
+                created_at: focused_msg
+                    .and_then(|m| m.created_at)
+                    .or(view.convo.started_at),
+                line_number: target.msg_idx.map(|idx| idx as usize + 1),
+                match_type: crate::search::query::MatchType::Exact,
+                snippets: Vec::new(),
+                conversation_id: String::new(),
+                role: String::new(),
+            };
+            modal_scroll = target
+                .msg_idx
+                .map_or(0, |idx| message_scroll_offset(&view, idx));
+            status = format!("Opened {}", detail_permalink(&view, &hit));
+            results = vec![hit];
+            panes = build_agent_panes(&results, per_pane_limit);
+            active_pane = 0;
+            cached_detail = Some((target.source_path.clone(), view));
+            show_detail_modal = true;
+            show_help = false;
+            dirty_since = None;
+        } else {
+            status = format!("✗ Permalink target not indexed: {}", target.source_path);
+        }
+    }
+
     loop {
         // Check for terminal resize and recalculate pane limit if needed
-        if let Ok(size) = terminal.size()
-            && size.height != last_terminal_height
-        {
-            last_terminal_height = size.height;
-            let new_limit = calculate_pane_limit(size.height, density_mode);
+        let rows = screen_rows(&terminal);
+        if rows != last_terminal_height {
+            last_terminal_height = rows;
+            let new_limit = calculate_pane_limit(rows, density_mode);
             if new_limit != per_pane_limit {
                 per_pane_limit = new_limit;
                 let prev_agent = active_hit(&panes, active_pane)
@@ -2607,7 +4125,7 @@ pub fn run_tui(
                 panes = rebuild_panes_with_filter(
                     &results,
                     pane_filter.as_deref(),
-                    per_pane_limit,
+                    per_pane_limit * pane_pages,
                     &mut active_pane,
                     &mut pane_scroll_offset,
                     prev_agent,
@@ -2618,9 +4136,52 @@ pub fn run_tui(
             }
         }
 
+        if input_mode != InputMode::Agent {
+            agent_picker = None;
+        } else if agent_picker.is_none() {
+            let mut entries = db_reader
+                .as_ref()
+                .and_then(|storage| storage.agent_message_counts().ok())
+                .unwrap_or_default();
+            if entries.is_empty() {
+                entries = KNOWN_AGENTS.iter().map(|a| (a.to_string(), 0)).collect();
+            }
+            let mut picker = AgentPickerState::new(entries, filters.agents.iter().cloned());
+            picker.refilter(&input_buffer);
+            agent_picker = Some(picker);
+        }
+        if !matches!(input_mode, InputMode::CreatedFrom | InputMode::CreatedTo) {
+            date_picker = None;
+        } else if date_picker.is_none() {
+            let bound = if input_mode == InputMode::CreatedFrom {
+                filters.created_from
+            } else {
+                filters.created_to
+            };
+            date_picker = Some(DatePickerState::new(
+                chrono::Local::now().date_naive(),
+                bound.and_then(date_picker::local_date),
+            ));
+        }
+        if input_mode != InputMode::Workspace {
+            workspace_picker = None;
+        } else if workspace_picker.is_none() {
+            let entries = db_reader
+                .as_ref()
+                .and_then(|storage| storage.workspace_message_counts().ok())
+                .unwrap_or_default();
+            let mut picker = WorkspacePickerState::new(entries);
+            picker.refilter(&input_buffer);
+            workspace_picker = Some(picker);
+        }
+
         if needs_draw {
-            terminal.draw(|f| {
-                let palette = if theme_dark {
+            // Source paths drawn this frame, linked to their files once it is on screen.
+            let mut frame_links: Vec<(String, String)> = Vec::new();
+            let frame = terminal.draw(|f| {
+                let palette = if accessible {
+                    ThemePalette::accessible()
+                } else if theme_dark {
                     ThemePalette::dark()
                 } else {
                     ThemePalette::light()
@@ -2648,10 +4209,15 @@ pub fn run_tui(
                     InputMode::PaneFilter => format!("[pane] {input_buffer}"),
                     InputMode::DetailFind => format!("[detail find] {input_buffer}"),
                 };
-                let mode_label = match match_mode {
-                    MatchMode::Standard => "standard",
-                    MatchMode::Prefix => "prefix",
-                };
+                let mode_label = format!(
+                    "{} · {} · {}",
+                    match match_mode {
+                        MatchMode::Standard => "standard",
+                        MatchMode::Prefix => "prefix",
+                    },
+                    sort_order.label(),
+                    ranking_mode.label()
+                );
                 let search_split = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints(
@@ -2665,7 +4231,7 @@ pub fn run_tui(
                     .split(chunks[0]);
 
                 let chips = chips_for_filters(&filters, palette);
-                let sb = search_bar(&bar_text, palette, input_mode, mode_label, chips);
+                let sb = search_bar(&bar_text, palette, input_mode, &mode_label, chips);
                 f.render_widget(sb, search_split[0]);
 
                 let mut pill_vec: Vec<Pill> = Vec::new();
@@ -2706,6 +4272,14 @@ pub fn run_tui(
                         editable: true,
                     });
                 }
+                if let Some(roles) = role_filter_label(&filters.roles) {
+                    pill_vec.push(Pill {
+                        label: "role".into(),
+                        value: roles,
+                        active: true,
+                        editable: true,
+                    });
+                }
                 // Render pills and record their rects for click handling
                 let pill_rects = pills::draw_pills(f, search_split[1], &pill_vec, palette);
                 last_pill_rects = pill_rects
@@ -2719,28 +4293,46 @@ pub fn run_tui(
                     search_split[2],
                     &filters,
                     ranking_mode,
+                    sort_order,
                     palette,
                 );
                 last_breadcrumb_rects = bc_rects;
 
                 // Responsive layout: detail pane expands when focused
-                let (results_pct, detail_pct) = match focus_region {
-                    FocusRegion::Results => (70, 30),
-                    FocusRegion::Detail => (50, 50),
-                };
-                let main_split = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints(
-                        [
-                            Constraint::Percentage(results_pct),
-                            Constraint::Percentage(detail_pct),
-                        ]
-                        .as_ref(),
-                    )
-                    .split(chunks[1]);
+                let (results_area, detail_area) =
+                    split_main_area(chunks[1], pane_layout, focus_region);
 
-                let results_area = main_split[0];
-                let detail_area = main_split[1];
+                // Pinned conversations take the top of the results column.
+                const MAX_PINNED_ROWS: usize = 5;
+                let results_area = if pins.is_empty() {
+                    results_area
+                } else {
+                    let rows = pins.len().min(MAX_PINNED_ROWS) as u16 + 2;
+                    let split = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Length(rows), Constraint::Min(3)])
+                        .split(results_area);
+                    let title = if pins.len() > MAX_PINNED_ROWS {
+                        format!(" 📌 Pinned ({} of {}) ", MAX_PINNED_ROWS, pins.len())
+                    } else {
+                        format!(" 📌 Pinned ({}) ", pins.len())
+                    };
+                    f.render_widget(
+                        Paragraph::new(pinned_lines(
+                            &pins[..pins.len().min(MAX_PINNED_ROWS)],
+                            palette,
+                        ))
+                        .block(
+                            Block::default()
+                                .borders(Borders::ALL)
+                                .border_type(BorderType::Rounded)
+                                .border_style(Style::default().fg(palette.accent_alt))
+                                .title(title),
+                        ),
+                        split[0],
+                    );
+                    split[1]
+                };
 
                 // Border style toggle: unicode rounded vs plain ASCII
                 let border_type = if fancy_borders {
@@ -2884,15 +4476,14 @@ pub fn run_tui(
                     }
 
                     let block = Block::default()
-                        .title("Results")
+                        .title(tr("tui-results"))
                         .borders(Borders::ALL)
                         .border_type(border_type);
                     f.render_widget(Paragraph::new(lines).block(block), results_area);
                 } else {
                     // Cap visible panes at MAX_VISIBLE_PANES
                     // Safety: clamp scroll offset to valid range to prevent slice panic
-                    let safe_scroll_offset =
-                        pane_scroll_offset.min(panes.len().saturating_sub(1).max(0));
+                    let safe_scroll_offset = pane_scroll_offset.min(panes.len().saturating_sub(1));
                     let visible_end = (safe_scroll_offset + MAX_VISIBLE_PANES).min(panes.len());
                     let visible_panes: Vec<&AgentPane> =
                         panes[safe_scroll_offset..visible_end].iter().collect();
@@ -2913,10 +4504,7 @@ pub fn run_tui(
                     for (vis_idx, pane) in visible_panes.iter().enumerate() {
                         let idx = safe_scroll_offset + vis_idx;
                         let theme = ThemePalette::agent_pane(&pane.agent);
-                        let mut state = ListState::default();
-                        state.select(Some(pane.selected));
-
-                        let items: Vec<ListItem> = pane
+                        let mut hit_items: Vec<Option<ListItem>> = pane
                             .hits
                             .iter()
                             .enumerate()
@@ -2938,6 +4526,14 @@ pub fn run_tui(
                                             .add_modifier(Modifier::BOLD),
                                     ));
                                 }
+                                if bookmarked.contains(&(hit.source_path.clone(), hit.line_number))
+                                    || bookmarked.contains(&(hit.source_path.clone(), None))
+                                {
+                                    header_spans.push(Span::styled(
+                                        "★ ",
+                                        Style::default().fg(palette.accent_alt),
+                                    ));
+                                }
                                 let icon = ThemePalette::agent_icon(&pane.agent);
                                 header_spans.push(Span::styled(
                                     format!("{icon} "),
@@ -2975,6 +4571,11 @@ pub fn run_tui(
 
                                 // Location line (separate from snippet for clarity)
                                 let truncated_source = truncate_path(&hit.source_path, 50);
+                                if link_paths
+                                    && let Some(url) = crate::hyperlink::file_url(&hit.source_path)
+                                {
+                                    frame_links.push((format!("[file] {truncated_source}"), url));
+                                }
                                 let truncated_ws = truncate_path(&hit.workspace, 30);
                                 let mut location_spans: Vec<Span> = vec![
                                     Span::styled("[file] ", Style::default().fg(palette.hint)),
@@ -3044,8 +4645,14 @@ pub fn run_tui(
                                     lerp_color(theme.bg, Color::Rgb(255, 255, 255), 0.08)
                                 };
 
-                                let mut lines = vec![header, location_line];
+                                let mut lines = vec![header];
+                                if density_mode.shows_location() {
+                                    lines.push(location_line);
+                                }
                                 lines.extend(snippet_lines);
+                                lines.extend(
+                                    (0..density_mode.padding_rows()).map(|_| Line::from("")),
+                                );
 
                                 // Staggered reveal animation (bead 013)
                                 // Calculate fade progress for this item
@@ -3099,8 +4706,73 @@ pub fn run_tui(
                                 ListItem::new(faded_lines)
                                     .style(Style::default().bg(stripe_bg).fg(faded_fg))
                             })
+                            .map(Some)
                             .collect();
 
+                        // One header row per conversation; a collapsed conversation's
+                        // header stands in for its best hit, an expanded one's sits
+                        // above its hits.
+                        let mut items: Vec<ListItem> = Vec::new();
+                        let mut selected_row = 0;
+                        for range in conversation_ranges(&pane.hits) {
+                            let first = &pane.hits[range.start];
+                            let folded = collapsed.contains(conversation_of(first));
+                            let pinned = pins
+                                .iter()
+                                .any(|p| p.conversation == conversation_of(first));
+                            let title = if first.title.is_empty() {
+                                "(untitled)"
+                            } else {
+                                first.title.as_str()
+                            };
+                            let mut spans = vec![
+                                Span::styled(
+                                    if folded { "▸ " } else { "▾ " },
+                                    Style::default().fg(theme.accent),
+                                ),
+                                Span::raw(if pinned { "📌 " } else { "" }),
+                                Span::styled(
+                                    title.to_string(),
+                                    Style::default().fg(theme.fg).add_modifier(Modifier::BOLD),
+                                ),
+                                Span::styled(
+                                    format!("  @{}", pane.agent),
+                                    Style::default().fg(palette.hint),
+                                ),
+                            ];
+                            if !first.workspace.is_empty() {
+                                spans.push(Span::styled(
+                                    format!("  [ws] {}", truncate_path(&first.workspace, 30)),
+                                    Style::default().fg(palette.hint),
+                                ));
+                            }
+                            let count = range.len();
+                            spans.push(Span::styled(
+                                format!("  ({count} {})", if count == 1 { "hit" } else { "hits" }),
+                                Style::default().fg(palette.hint),
+                            ));
+                            if range.contains(&pane.selected) && folded {
+                                selected_row = items.len();
+                            }
+                            items.push(
+                                ListItem::new(Line::from(spans))
+                                    .style(Style::default().bg(theme.bg).fg(theme.fg)),
+                            );
+                            if folded {
+                                continue;
+                            }
+                            for hit_idx in range {
+                                if hit_idx == pane.selected {
+                                    selected_row = items.len();
+                                }
+                                if let Some(item) = hit_items[hit_idx].take() {
+                                    items.push(item);
+                                }
+                            }
+                        }
+                        let mut state = ListState::default();
+                        state.select(Some(selected_row));
+
                         const FLASH_DURATION_MS: u64 = 220;
 
                         // Calculate smooth flash progress (0.0 = start/accent, 1.0 = end/normal)
@@ -3127,17 +4799,28 @@ pub fn run_tui(
                         };
                         // Use persistent agent-specific color for pane title/border
                         let pane_color = agent_color(&pane.agent);
-                        let block = Block::default()
-                            .title(Span::styled(
-                                format!("{} ({})", agent_display_name(&pane.agent), count_display),
-                                Style::default()
-                                    .fg(pane_color)
-                                    .add_modifier(if is_focused_pane {
-                                        Modifier::BOLD
-                                    } else {
-                                        Modifier::empty()
-                                    }),
-                            ))
+                        let mut block = Block::default().title(Span::styled(
+                            format!("{} ({})", agent_display_name(&pane.agent), count_display),
+                            Style::default()
+                                .fg(pane_color)
+                                .add_modifier(if is_focused_pane {
+                                    Modifier::BOLD
+                                } else {
+                                    Modifier::empty()
+                                }),
+                        ));
+                        // Hits for the whole search, with how many are fetched so far
+                        if vis_idx == 0 {
+                            let loaded = page * page_size + results.len();
+                            block = block.title(
+                                Line::from(Span::styled(
+                                    hit_total_label(loaded, total_matches, more_results),
+                                    Style::default().fg(palette.hint),
+                                ))
+                                .right_aligned(),
+                            );
+                        }
+                        let block = block
                             .borders(Borders::ALL)
                             .border_type(border_type)
                             .border_style(Style::default().fg(if is_focused_pane {
@@ -3170,7 +4853,8 @@ pub fn run_tui(
                     // Render hidden pane directional indicators (arrows)
                     if safe_scroll_offset > 0 {
                         let text = format!("◀ +{safe_scroll_offset}");
-                        let area = Rect::new(results_area.x, results_area.y, text.len() as u16, 1);
+                        let area = Rect::new(results_area.x, results_area.y, text.len() as u16, 1)
+                            .intersection(results_area);
                         f.render_widget(
                             Span::styled(
                                 text,
@@ -3190,7 +4874,8 @@ pub fn run_tui(
                             results_area.y,
                             text.len() as u16,
                             1,
-                        );
+                        )
+                        .intersection(results_area);
                         f.render_widget(
                             Span::styled(
                                 text,
@@ -3224,7 +4909,8 @@ pub fn run_tui(
                                 results_area.y,
                                 indicator.len() as u16,
                                 1,
-                            );
+                            )
+                            .intersection(results_area);
                             f.render_widget(Paragraph::new(indicator_span), indicator_area);
                         }
                     }
@@ -3239,7 +4925,14 @@ pub fn run_tui(
                         cached_detail.as_ref().map(|(_, d)| d.clone())
                     } else {
                         let loaded = if let Some(storage) = &db_reader {
-                            load_conversation(storage, &hit.source_path).ok().flatten()
+                            load_conversation(storage, &hit.source_path)
+                                .ok()
+                                .flatten()
+                                .map(|mut view| {
+                                    view.notes =
+                                        crate::notes::for_session(&data_dir, &hit.source_path);
+                                    view
+                                })
                         } else {
                             None
                         };
@@ -3249,6 +4942,19 @@ pub fn run_tui(
                         }
                         loaded
                     };
+                    // Open the preview on the selected hit's message, with some context.
+                    let anchor = (hit.source_path.clone(), hit.line_number);
+                    if previewed_hit.as_ref() != Some(&anchor) {
+                        detail_scroll = match (&detail, hit.line_number, detail_tab) {
+                            (Some(d), Some(line), DetailTab::Messages) => preview_scroll_offset(
+                                d,
+                                line as i64 - 1,
+                                detail_area.width.saturating_sub(2),
+                            ),
+                            _ => 0,
+                        };
+                        previewed_hit = Some(anchor);
+                    }
 
                     // Count messages and snippets for tab labels
                     let (msg_count, snippet_count) = if let Some(ref d) = detail {
@@ -3326,6 +5032,18 @@ pub fn run_tui(
                         Span::styled("Source: ", Style::default().fg(palette.hint)),
                         Span::raw(truncate_path(&hit.source_path, 60)),
                     ]));
+                    if let Some(tags) = session_tags.get(&hit.source_path) {
+                        let mut spans =
+                            vec![Span::styled("Tags: ", Style::default().fg(palette.hint))];
+                        for tag in tags {
+                            spans.push(Span::styled(
+                                format!("[#{tag}]"),
+                                Style::default().fg(palette.accent_alt),
+                            ));
+                            spans.push(Span::raw(" "));
+                        }
+                        meta_lines.push(Line::from(spans));
+                    }
                     meta_lines.push(Line::from(vec![
                         Span::styled("Score: ", Style::default().fg(palette.hint)),
                         Span::raw(format!("{:.2}", hit.score)),
@@ -3460,6 +5178,11 @@ pub fn run_tui(
                         }
                     }
 
+                    // Width of each content line, to find the last screenful when tailing.
+                    let tail_line_widths: Option<Vec<usize>> = tail
+                        .as_ref()
+                        .filter(|t| t.jump_to_end && t.source_path == hit.source_path)
+                        .map(|_| content_lines.iter().map(Line::width).collect());
                     let content_para = {
                         let trim = !matches!(detail_tab, DetailTab::Messages);
                         Paragraph::new(content_lines)
@@ -3483,21 +5206,32 @@ pub fn run_tui(
                             )
                         }
                     });
+                    let tail_badge = if tail
+                        .as_ref()
+                        .is_some_and(|t| t.source_path == hit.source_path)
+                    {
+                        " • tailing"
+                    } else {
+                        ""
+                    };
                     let detail_title = if detail_scroll > 0 {
                         format!(
-                            "Detail ↓{} • [←/→] tabs • Enter=expand{}",
+                            "Detail ↓{} • [←/→] tabs • Enter=expand{}{}",
                             detail_scroll,
-                            match_badge.as_deref().unwrap_or("")
+                            match_badge.as_deref().unwrap_or(""),
+                            tail_badge
                         )
                     } else if is_focused_detail {
                         format!(
-                            "Detail • [←/→] tabs • ↑/↓ or Alt+j/k scroll • Enter=expand{}",
-                            match_badge.as_deref().unwrap_or("")
+                            "Detail • [←/→] tabs • ↑/↓ or Alt+j/k scroll • Enter=expand{}{}",
+                            match_badge.as_deref().unwrap_or(""),
+                            tail_badge
                         )
                     } else {
                         format!(
-                            "Detail • [←/→] tabs • Enter=expand{}",
-                            match_badge.as_deref().unwrap_or("")
+                            "Detail • [←/→] tabs • Enter=expand{}{}",
+                            match_badge.as_deref().unwrap_or(""),
+                            tail_badge
                         )
                     };
                     let block = Block::default()
@@ -3517,12 +5251,67 @@ pub fn run_tui(
                             palette.hint
                         }));
 
+                    // Prompt → match → reply strip for the Messages tab.
+                    let mut context_lines: Vec<Line> = Vec::new();
+                    if matches!(detail_tab, DetailTab::Messages)
+                        && let Some(line) = hit.line_number
+                    {
+                        let key = (hit.source_path.clone(), line);
+                        if turn_context.as_ref().map(|(k, _)| k) != Some(&key) {
+                            let turns = load_adjacent_turns(&hit.source_path, line);
+                            turn_context = Some((key, turns));
+                        }
+                        if let Some((_, turns)) = &turn_context
+                            && (turns.prompt.is_some() || turns.reply.is_some())
+                        {
+                            let row = |marker: &str, label: &str, text: &str, style: Style| {
+                                let text: String = text.chars().take(500).collect();
+                                Line::from(vec![
+                                    Span::styled(format!("{marker} {label}: "), style),
+                                    Span::styled(text, Style::default().fg(palette.fg)),
+                                ])
+                            };
+                            if let Some(prompt) = &turns.prompt {
+                                context_lines.push(row(
+                                    "▲",
+                                    "Prompt",
+                                    prompt,
+                                    Style::default().fg(palette.user),
+                                ));
+                            }
+                            let matched =
+                                hit.content.split_whitespace().collect::<Vec<_>>().join(" ");
+                            context_lines.push(row(
+                                "●",
+                                "Match",
+                                &matched,
+                                Style::default()
+                                    .fg(palette.accent)
+                                    .add_modifier(Modifier::BOLD),
+                            ));
+                            if let Some(reply) = &turns.reply {
+                                context_lines.push(row(
+                                    "▼",
+                                    "Reply",
+                                    reply,
+                                    Style::default().fg(palette.agent),
+                                ));
+                            }
+                        }
+                    }
+                    let context_height = if context_lines.is_empty() {
+                        0
+                    } else {
+                        context_lines.len() as u16 + 2
+                    };
+
                     let layout = Layout::default()
                         .direction(Direction::Vertical)
                         .constraints(
                             [
                                 Constraint::Length(2),
                                 Constraint::Length(meta_lines.len() as u16 + 2),
+                                Constraint::Length(context_height),
                                 Constraint::Min(3),
                             ]
                             .as_ref(),
@@ -3531,12 +5320,40 @@ pub fn run_tui(
 
                     f.render_widget(tab_widget, layout[0]);
                     f.render_widget(Paragraph::new(meta_lines), layout[1]);
-                    f.render_widget(content_para.block(block), layout[2]);
+                    if !context_lines.is_empty() {
+                        f.render_widget(
+                            Paragraph::new(context_lines).block(
+                                Block::default()
+                                    .title(Span::styled(
+                                        "Context",
+                                        Style::default().fg(palette.hint),
+                                    ))
+                                    .borders(Borders::ALL)
+                                    .border_type(border_type)
+                                    .border_style(Style::default().fg(palette.hint)),
+                            ),
+                            layout[2],
+                        );
+                    }
+                    let mut content_para = content_para.block(block);
+                    if let Some(widths) = tail_line_widths {
+                        let inner = layout[3].inner(Margin::new(1, 1));
+                        let width = usize::from(inner.width.max(1));
+                        let rows: usize = widths.iter().map(|w| w.max(&1).div_ceil(width)).sum();
+                        detail_scroll =
+                            rows.saturating_sub(usize::from(inner.height))
+                                .min(usize::from(u16::MAX)) as u16;
+                        content_para = content_para.scroll((detail_scroll, 0));
+                        if let Some(t) = tail.as_mut() {
+                            t.jump_to_end = false;
+                        }
+                    }
+                    f.render_widget(content_para, layout[3]);
                 } else {
                     f.render_widget(
                         Paragraph::new("Select a result to view details").block(
                             Block::default()
-                                .title("Detail")
+                                .title(tr("tui-detail"))
                                 .borders(Borders::ALL)
                                 .border_type(border_type),
                         ),
@@ -3546,7 +5363,7 @@ pub fn run_tui(
 
                 // Footer: status + modes + dense shortcut legend
                 let mut footer_parts: Vec<String> = vec![];
-                if dirty_since.is_some() {
+                if dirty_since.is_some() || search_pending {
                     let spinner = SPINNER_CHARS[spinner_frame % SPINNER_CHARS.len()];
                     footer_parts.push(format!("{spinner} Searching..."));
                 } else if !status.is_empty() {
@@ -3592,13 +5409,11 @@ pub fn run_tui(
                 if matches!(match_mode, MatchMode::Standard) {
                     footer_parts.push("mode:standard".to_string());
                 }
-                match ranking_mode {
-                    RankingMode::RecentHeavy => footer_parts.push("rank:recent".to_string()),
-                    RankingMode::RelevanceHeavy => footer_parts.push("rank:relevance".to_string()),
-                    RankingMode::MatchQualityHeavy => footer_parts.push("rank:quality".to_string()),
-                    RankingMode::DateNewest => footer_parts.push("rank:newest".to_string()),
-                    RankingMode::DateOldest => footer_parts.push("rank:oldest".to_string()),
-                    RankingMode::Balanced => {}
+                if ranking_mode != RankingMode::Balanced {
+                    footer_parts.push(format!("rank:{}", ranking_mode.label()));
+                }
+                if sort_order != SortOrder::Relevance {
+                    footer_parts.push(format!("sort:{}", sort_order.label()));
                 }
                 if wildcard_fallback {
                     footer_parts.push("✱ fuzzy".to_string());
@@ -3684,8 +5499,31 @@ pub fn run_tui(
                 f.render_widget(query_bar, footer_split[0]);
 
                 let footer_line = footer_parts.join(" | ");
-                let footer = Paragraph::new(footer_line);
-                f.render_widget(footer, footer_split[1]);
+                let indexing = progress
+                    .as_ref()
+                    .is_some_and(|p| get_indexing_state(p).0 > 0);
+                let freshness_text =
+                    freshness_label(freshness.0, freshness.1, indexing, profile.as_deref());
+                // The status message keeps at least 40 columns on narrow terminals.
+                let freshness_width = freshness_text.chars().count() as u16 + 1;
+                let freshness_width = if footer_split[1].width >= freshness_width + 40 {
+                    freshness_width
+                } else {
+                    0
+                };
+                let status_split = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(0), Constraint::Length(freshness_width)])
+                    .split(footer_split[1]);
+                f.render_widget(Paragraph::new(footer_line), status_split[0]);
+                f.render_widget(
+                    Paragraph::new(Span::styled(
+                        freshness_text,
+                        Style::default().fg(palette.hint),
+                    ))
+                    .alignment(Alignment::Right),
+                    status_split[1],
+                );
 
                 let shortcuts = contextual_shortcuts(
                     palette_state.open,
@@ -3708,33 +5546,6 @@ pub fn run_tui(
                     f.render_widget(Paragraph::new(""), footer_split[2]);
                 }
 
-                // Render autocomplete dropdown for Agent Filter
-                if input_mode == InputMode::Agent {
-                    let suggestions = agent_suggestions(&input_buffer);
-                    if !suggestions.is_empty() {
-                        let area = Rect::new(
-                            chunks[0].x + 14, // Align with " Filter: Agent " prompt
-                            chunks[0].y + 2,  // Below title line
-                            30,
-                            (suggestions.len().min(5) as u16) + 2,
-                        );
-                        f.render_widget(ratatui::widgets::Clear, area);
-                        let items: Vec<ListItem> = suggestions
-                            .iter()
-                            .map(|s| ListItem::new(Span::raw(*s)))
-                            .collect();
-                        let list = List::new(items)
-                            .block(
-                                Block::default()
-                                    .borders(Borders::ALL)
-                                    .border_style(palette.border_focus_style())
-                                    .title("Suggestions"),
-                            )
-                            .highlight_style(Style::default().bg(palette.accent));
-                        f.render_widget(list, area);
-                    }
-                }
-
                 // Update available banner (bead 018)
                 // Shows as a single-line banner at the top when update is available
                 if let Some(ref info) = update_info
@@ -3851,11 +5662,57 @@ pub fn run_tui(
                     f.render_widget(list, area);
                 }
 
+                if let Some(picker) = &date_picker {
+                    let from = filters.created_from.and_then(date_picker::local_date);
+                    let (title, range) = if input_mode == InputMode::CreatedFrom {
+                        (
+                            "From date",
+                            (
+                                Some(picker.cursor),
+                                filters.created_to.and_then(date_picker::local_date),
+                            ),
+                        )
+                    } else {
+                        // Preview the range the cursor would close.
+                        ("To date", (from, Some(picker.cursor)))
+                    };
+                    date_picker::draw_date_picker(
+                        f,
+                        date_picker::picker_area(f.area()),
+                        picker,
+                        title,
+                        range,
+                        &input_buffer,
+                        palette,
+                    );
+                }
+
+                if let Some(picker) = &agent_picker {
+                    let area = centered_rect(50, 60, f.area());
+                    agent_picker::draw_agent_picker(f, area, picker, &input_buffer, palette);
+                }
+
+                if let Some(picker) = &workspace_picker {
+                    let area = centered_rect(70, 60, f.area());
+                    workspace_picker::draw_workspace_picker(
+                        f,
+                        area,
+                        picker,
+                        &input_buffer,
+                        palette,
+                    );
+                }
+
                 if palette_state.open {
                     let area = centered_rect(70, 60, f.area());
                     palette::draw_palette(f, area, &palette_state, palette);
                 }
+
+                render_toasts(f, &toasts, &palette);
             })?;
+            if !frame_links.is_empty() {
+                write_hyperlinks(&mut io::stdout(), frame.buffer, &frame_links)?;
+            }
             needs_draw = false;
         }
 
@@ -3894,7 +5751,8 @@ pub fn run_tui(
                                 match pill.label.as_str() {
                                     "agent" => {
                                         input_mode = InputMode::Agent;
-                                        input_buffer = pill.value.clone();
+                                        // The picker opens with the current agents ticked.
+                                        input_buffer.clear();
                                         status = "Edit agent filter".to_string();
                                         dirty_since = None;
                                     }
@@ -3918,6 +5776,15 @@ pub fn run_tui(
                                             "Edit pane filter (Enter apply, Esc clear)".to_string();
                                         dirty_since = None;
                                     }
+                                    "role" => {
+                                        filters.roles = next_role_filter(&filters.roles);
+                                        status = match role_filter_label(&filters.roles) {
+                                            Some(label) => format!("Role: {label}"),
+                                            None => "Role: all".to_string(),
+                                        };
+                                        page = 0;
+                                        dirty_since = Some(Instant::now());
+                                    }
                                     _ => {}
                                 }
                                 needs_draw = true;
@@ -3967,6 +5834,7 @@ pub fn run_tui(
                                         && item_idx < pane.hits.len()
                                     {
                                         pane.selected = item_idx;
+                                        pane.reveal_selection(&collapsed);
                                         cached_detail = None;
                                         detail_scroll = 0;
                                     }
@@ -3983,9 +5851,8 @@ pub fn run_tui(
                             }
                             FocusRegion::Results => {
                                 if let Some(pane) = panes.get_mut(active_pane)
-                                    && pane.selected > 0
+                                    && pane.step_selection(false, &collapsed)
                                 {
-                                    pane.selected = pane.selected.saturating_sub(1);
                                     cached_detail = None;
                                     detail_scroll = 0;
                                 }
@@ -4000,9 +5867,8 @@ pub fn run_tui(
                             }
                             FocusRegion::Results => {
                                 if let Some(pane) = panes.get_mut(active_pane)
-                                    && pane.selected + 1 < pane.hits.len()
+                                    && pane.step_selection(true, &collapsed)
                                 {
-                                    pane.selected += 1;
                                     cached_detail = None;
                                     detail_scroll = 0;
                                 }
@@ -4077,13 +5943,13 @@ pub fn run_tui(
                     KeyCode::PageDown => palette_state.move_selection(5),
                     KeyCode::Enter => {
                         if let Some(item) = palette_state.filtered.get(palette_state.selected) {
-                            match item.action {
+                            match item.action.clone() {
                                 PaletteAction::ToggleTheme => {
                                     theme_dark = !theme_dark;
                                 }
                                 PaletteAction::ToggleDensity => {
                                     density_mode = density_mode.next();
-                                    let height = terminal.size().map(|r| r.height).unwrap_or(24);
+                                    let height = screen_rows(&terminal);
                                     per_pane_limit = calculate_pane_limit(height, density_mode);
                                     let prev_agent = active_hit(&panes, active_pane)
                                         .map(|h| h.agent.clone())
@@ -4095,7 +5961,7 @@ pub fn run_tui(
                                     panes = rebuild_panes_with_filter(
                                         &results,
                                         pane_filter.as_deref(),
-                                        per_pane_limit,
+                                        per_pane_limit * pane_pages,
                                         &mut active_pane,
                                         &mut pane_scroll_offset,
                                         prev_agent,
@@ -4105,6 +5971,14 @@ pub fn run_tui(
                                     status = format!("Density: {}", density_mode.label());
                                     needs_draw = true;
                                 }
+                                PaletteAction::CycleLayout => {
+                                    pane_layout = pane_layout.next();
+                                    if pane_layout == PaneLayout::Results {
+                                        focus_region = FocusRegion::Results;
+                                    }
+                                    status = format!("Layout: {}", pane_layout.label());
+                                    needs_draw = true;
+                                }
                                 PaletteAction::ToggleHelpStrip => {
                                     help_pinned = !help_pinned;
                                 }
@@ -4159,15 +6033,55 @@ pub fn run_tui(
                                 PaletteAction::ReloadIndex => {
                                     dirty_since = Some(Instant::now());
                                 }
+                                PaletteAction::TogglePin => {
+                                    status = match active_hit(&panes, active_pane) {
+                                        Some(hit) => toggle_pin(&data_dir, hit, &mut pins),
+                                        None => "No result selected to pin".to_string(),
+                                    };
+                                }
+                                PaletteAction::ResumeSession => {
+                                    status = match active_hit(&panes, active_pane) {
+                                        Some(hit) => resume_hit(&mut terminal, hit),
+                                        None => "No result selected to resume".to_string(),
+                                    };
+                                }
+                                PaletteAction::TailSession => {
+                                    status = match active_hit(&panes, active_pane) {
+                                        Some(hit) => {
+                                            detail_tab = DetailTab::Messages;
+                                            toggle_tail(&mut tail, hit)
+                                        }
+                                        None => "No result selected to tail".to_string(),
+                                    };
+                                }
                                 PaletteAction::OpenSavedViews => {
-                                    status =
-                                        "Saved views: Ctrl+<n> save, Shift+<n> load".to_string();
+                                    status = "Saved views: Ctrl+<n> save, Shift+<n> load; saved searches are listed in this palette".to_string();
+                                }
+                                PaletteAction::RunSavedSearch(name) => {
+                                    status = match crate::saved_searches::get(&data_dir, &name) {
+                                        Some(search) => {
+                                            match load_saved_search(
+                                                &search,
+                                                &data_dir,
+                                                &mut query,
+                                                &mut filters,
+                                            ) {
+                                                Ok(()) => {
+                                                    dirty_since = Some(Instant::now());
+                                                    format!("Loaded saved search '{name}'")
+                                                }
+                                                Err(e) => e,
+                                            }
+                                        }
+                                        None => format!("Saved search '{name}' no longer exists"),
+                                    };
                                 }
                                 PaletteAction::SaveViewSlot(slot) => {
                                     status = save_view_slot(
                                         slot,
                                         &filters,
                                         ranking_mode,
+                                        sort_order,
                                         &mut saved_views,
                                     );
                                 }
@@ -4176,6 +6090,7 @@ pub fn run_tui(
                                         slot,
                                         &mut filters,
                                         &mut ranking_mode,
+                                        &mut sort_order,
                                         &saved_views,
                                     ) {
                                         status = msg;
@@ -4236,26 +6151,18 @@ pub fn run_tui(
                                     .or_else(|_| std::env::var("VISUAL"))
                                     .unwrap_or_else(|_| "code".to_string());
                                 // Exit raw mode
-                                disable_raw_mode().ok();
-                                execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)
-                                    .ok();
+                                leave_tui_screen();
                                 for hit in &selected_hits {
-                                    let mut cmd = StdCommand::new(&editor);
-                                    if editor == "code" {
-                                        if let Some(ln) = hit.line_number {
-                                            cmd.arg("--goto")
-                                                .arg(format!("{}:{}", hit.source_path, ln));
-                                        } else {
-                                            cmd.arg(&hit.source_path);
-                                        }
-                                    } else {
-                                        cmd.arg(&hit.source_path);
+                                    if let Some(mut cmd) = editor_command(
+                                        &editor,
+                                        &editor_line_flag,
+                                        &hit.source_path,
+                                        hit.line_number,
+                                    ) {
+                                        let _ = cmd.status();
                                     }
-                                    let _ = cmd.status();
                                 }
-                                execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)
-                                    .ok();
-                                enable_raw_mode().ok();
+                                enter_tui_screen();
                                 status =
                                     format!("Opened {} files in {}", selected_hits.len(), editor);
                                 selected.clear();
@@ -4267,48 +6174,13 @@ pub fn run_tui(
                                     .iter()
                                     .map(|h| h.source_path.clone())
                                     .collect();
-                                let text = paths.join("\n");
-                                let clipboard_cmd = if cfg!(target_os = "macos") {
-                                    Some("pbcopy")
-                                } else if StdCommand::new("which")
-                                    .arg("xclip")
-                                    .output()
-                                    .map(|o| o.status.success())
-                                    .unwrap_or(false)
-                                {
-                                    Some("xclip -selection clipboard")
-                                } else if StdCommand::new("which")
-                                    .arg("xsel")
-                                    .output()
-                                    .map(|o| o.status.success())
-                                    .unwrap_or(false)
-                                {
-                                    Some("xsel --clipboard --input")
-                                } else {
-                                    None
-                                };
-                                status = if let Some(cmd) = clipboard_cmd {
-                                    let result = StdCommand::new("sh")
-                                        .arg("-c")
-                                        .arg(cmd)
-                                        .stdin(std::process::Stdio::piped())
-                                        .spawn()
-                                        .and_then(|mut child| {
-                                            use std::io::Write;
-                                            if let Some(stdin) = child.stdin.as_mut() {
-                                                stdin.write_all(text.as_bytes())?;
-                                            }
-                                            child.wait()
-                                        });
-                                    if result.map(|s| s.success()).unwrap_or(false) {
+                                status = match copy_to_clipboard(&mut toasts, &paths.join("\n")) {
+                                    Ok(()) => {
                                         selected.clear();
                                         open_confirm_armed = false;
                                         format!("✓ Copied {} paths to clipboard", paths.len())
-                                    } else {
-                                        "✗ Clipboard copy failed".to_string()
                                     }
-                                } else {
-                                    "✗ No clipboard tool found".to_string()
+                                    Err(e) => format!("✗ {e}"),
                                 };
                             }
                             2 => {
@@ -4328,43 +6200,16 @@ pub fn run_tui(
                                     })
                                     .collect();
                                 if let Ok(json) = serde_json::to_string_pretty(&export) {
-                                    let clipboard_cmd = if cfg!(target_os = "macos") {
-                                        Some("pbcopy")
-                                    } else if StdCommand::new("which")
-                                        .arg("xclip")
-                                        .output()
-                                        .map(|o| o.status.success())
-                                        .unwrap_or(false)
-                                    {
-                                        Some("xclip -selection clipboard")
-                                    } else {
-                                        None
-                                    };
-                                    status = if let Some(cmd) = clipboard_cmd {
-                                        let result = StdCommand::new("sh")
-                                            .arg("-c")
-                                            .arg(cmd)
-                                            .stdin(std::process::Stdio::piped())
-                                            .spawn()
-                                            .and_then(|mut child| {
-                                                use std::io::Write;
-                                                if let Some(stdin) = child.stdin.as_mut() {
-                                                    stdin.write_all(json.as_bytes())?;
-                                                }
-                                                child.wait()
-                                            });
-                                        if result.map(|s| s.success()).unwrap_or(false) {
+                                    status = match copy_to_clipboard(&mut toasts, &json) {
+                                        Ok(()) => {
                                             selected.clear();
                                             open_confirm_armed = false;
                                             format!(
                                                 "✓ Exported {} items as JSON to clipboard",
                                                 export.len()
                                             )
-                                        } else {
-                                            "✗ JSON export failed".to_string()
                                         }
-                                    } else {
-                                        "✗ No clipboard tool found".to_string()
+                                        Err(e) => format!("✗ JSON export failed: {e}"),
                                     };
                                 }
                             }
@@ -4431,65 +6276,24 @@ pub fn run_tui(
                     KeyCode::Home | KeyCode::Char('g') => modal_scroll = 0,
                     KeyCode::End | KeyCode::Char('G') => modal_scroll = u16::MAX,
                     KeyCode::Char('c') => {
-                        // Copy rendered content to clipboard using xclip/xsel/pbcopy
+                        // Copy rendered content to clipboard
                         if let Some((_, ref detail)) = cached_detail {
                             let mut text = String::new();
                             for msg in &detail.messages {
                                 let role_label = match &msg.role {
                                     MessageRole::User => "YOU",
                                     MessageRole::Agent => "ASSISTANT",
-                                    MessageRole::Tool => "TOOL",
-                                    MessageRole::System => "SYSTEM",
-                                    MessageRole::Other(r) => r,
-                                };
-                                text.push_str(&format!("=== {role_label} ===\n"));
-                                text.push_str(&msg.content);
-                                text.push_str("\n\n");
-                            }
-                            // Try clipboard tools in order of preference
-                            let clipboard_cmd = if cfg!(target_os = "macos") {
-                                Some("pbcopy")
-                            } else {
-                                // Linux: prefer xclip, fallback to xsel
-                                if StdCommand::new("which")
-                                    .arg("xclip")
-                                    .output()
-                                    .map(|o| o.status.success())
-                                    .unwrap_or(false)
-                                {
-                                    Some("xclip -selection clipboard")
-                                } else if StdCommand::new("which")
-                                    .arg("xsel")
-                                    .output()
-                                    .map(|o| o.status.success())
-                                    .unwrap_or(false)
-                                {
-                                    Some("xsel --clipboard --input")
-                                } else {
-                                    None
-                                }
-                            };
-
-                            status = if let Some(cmd) = clipboard_cmd {
-                                let result = StdCommand::new("sh")
-                                    .arg("-c")
-                                    .arg(cmd)
-                                    .stdin(std::process::Stdio::piped())
-                                    .spawn()
-                                    .and_then(|mut child| {
-                                        use std::io::Write;
-                                        if let Some(stdin) = child.stdin.as_mut() {
-                                            stdin.write_all(text.as_bytes())?;
-                                        }
-                                        child.wait()
-                                    });
-                                if result.map(|s| s.success()).unwrap_or(false) {
-                                    "✓ Copied to clipboard".to_string()
-                                } else {
-                                    "✗ Clipboard copy failed".to_string()
-                                }
-                            } else {
-                                "✗ No clipboard tool found (xclip/xsel/pbcopy)".to_string()
+                                    MessageRole::Tool => "TOOL",
+                                    MessageRole::System => "SYSTEM",
+                                    MessageRole::Other(r) => r,
+                                };
+                                text.push_str(&format!("=== {role_label} ===\n"));
+                                text.push_str(&msg.content);
+                                text.push_str("\n\n");
+                            }
+                            status = match copy_to_clipboard(&mut toasts, &text) {
+                                Ok(()) => "✓ Copied to clipboard".to_string(),
+                                Err(e) => format!("✗ {e}"),
                             };
                         }
                     }
@@ -4519,16 +6323,12 @@ pub fn run_tui(
                             ));
                             if std::fs::write(&tmp_path, &text).is_ok() {
                                 // Exit raw mode, run nano, re-enter
-                                disable_raw_mode().ok();
-                                execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)
-                                    .ok();
+                                leave_tui_screen();
                                 let nano_result = StdCommand::new("nano")
                                     .arg("--view") // Read-only mode
                                     .arg(&tmp_path)
                                     .status();
-                                execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)
-                                    .ok();
-                                enable_raw_mode().ok();
+                                enter_tui_screen();
                                 // Clean up temp file
                                 std::fs::remove_file(&tmp_path).ok();
                                 status = if nano_result.is_ok() {
@@ -4543,6 +6343,16 @@ pub fn run_tui(
                             }
                         }
                     }
+                    KeyCode::Char('e') => {
+                        if let Some(hit) = active_hit(&panes, active_pane) {
+                            status = edit_hit(&mut terminal, &editor_cmd, &editor_line_flag, hit);
+                        }
+                    }
+                    KeyCode::Char('r') => {
+                        if let Some(hit) = active_hit(&panes, active_pane) {
+                            status = resume_hit(&mut terminal, hit);
+                        }
+                    }
                     KeyCode::Char('o') => {
                         // Open source file in $EDITOR or default editor
                         if let Some(pane) = panes.get(active_pane)
@@ -4568,40 +6378,21 @@ pub fn run_tui(
                                 });
 
                             // Exit raw mode for GUI editors (code) or TUI editors
-                            disable_raw_mode().ok();
-                            execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture).ok();
+                            leave_tui_screen();
 
                             // Build command with optional line number
-                            let mut cmd = StdCommand::new(&editor);
-                            if editor == "code" {
-                                // VS Code: code --goto file:line
-                                if let Some(ln) = hit.line_number {
-                                    cmd.arg("--goto").arg(format!("{path}:{ln}"));
-                                } else {
-                                    cmd.arg(path);
-                                }
-                            } else if editor == "vim" || editor == "vi" || editor == "nvim" {
-                                // Vim: vim +line file
-                                if let Some(ln) = hit.line_number {
-                                    cmd.arg(format!("+{ln}"));
-                                }
-                                cmd.arg(path);
-                            } else if editor == "nano" {
-                                // Nano: nano +line file
-                                if let Some(ln) = hit.line_number {
-                                    cmd.arg(format!("+{ln}"));
-                                }
-                                cmd.arg(path);
-                            } else {
-                                // Generic: just pass the path
-                                cmd.arg(path);
-                            }
-
-                            let result = cmd.status();
+                            let result = match editor_command(
+                                &editor,
+                                &editor_line_flag,
+                                path,
+                                hit.line_number,
+                            ) {
+                                Some(mut cmd) => cmd.status(),
+                                None => Err(std::io::Error::other("$EDITOR is empty")),
+                            };
 
                             // Re-enter raw mode
-                            execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture).ok();
-                            enable_raw_mode().ok();
+                            enter_tui_screen();
 
                             status = if result.map(|s| s.success()).unwrap_or(false) {
                                 format!("Opened {path} in {editor}")
@@ -4618,46 +6409,22 @@ pub fn run_tui(
                             && let Some(hit) = pane.hits.get(pane.selected)
                         {
                             let path = &hit.source_path;
-                            let clipboard_cmd = if cfg!(target_os = "macos") {
-                                Some("pbcopy")
-                            } else if StdCommand::new("which")
-                                .arg("xclip")
-                                .output()
-                                .map(|o| o.status.success())
-                                .unwrap_or(false)
-                            {
-                                Some("xclip -selection clipboard")
-                            } else if StdCommand::new("which")
-                                .arg("xsel")
-                                .output()
-                                .map(|o| o.status.success())
-                                .unwrap_or(false)
-                            {
-                                Some("xsel --clipboard --input")
-                            } else {
-                                None
+                            status = match copy_to_clipboard(&mut toasts, path) {
+                                Ok(()) => format!("✓ Path copied: {path}"),
+                                Err(e) => format!("✗ {e}"),
                             };
-
-                            status = if let Some(cmd) = clipboard_cmd {
-                                let result = StdCommand::new("sh")
-                                    .arg("-c")
-                                    .arg(cmd)
-                                    .stdin(std::process::Stdio::piped())
-                                    .spawn()
-                                    .and_then(|mut child| {
-                                        use std::io::Write;
-                                        if let Some(stdin) = child.stdin.as_mut() {
-                                            stdin.write_all(path.as_bytes())?;
-                                        }
-                                        child.wait()
-                                    });
-                                if result.map(|s| s.success()).unwrap_or(false) {
-                                    format!("✓ Path copied: {path}")
-                                } else {
-                                    "✗ Clipboard copy failed".to_string()
-                                }
-                            } else {
-                                "✗ No clipboard tool found".to_string()
+                        }
+                    }
+                    KeyCode::Char('l') => {
+                        // Copy cass:// permalink to the selected message
+                        if let Some((_, ref detail)) = cached_detail
+                            && let Some(pane) = panes.get(active_pane)
+                            && let Some(hit) = pane.hits.get(pane.selected)
+                        {
+                            let link = detail_permalink(detail, hit).to_string();
+                            status = match copy_to_clipboard(&mut toasts, &link) {
+                                Ok(()) => format!("✓ Permalink copied: {link}"),
+                                Err(e) => format!("✗ {e}"),
                             };
                         }
                     }
@@ -4666,47 +6433,23 @@ pub fn run_tui(
                         if let Some(pane) = panes.get(active_pane)
                             && let Some(hit) = pane.hits.get(pane.selected)
                         {
-                            let snippet = &hit.snippet;
-                            let clipboard_cmd = if cfg!(target_os = "macos") {
-                                Some("pbcopy")
-                            } else if StdCommand::new("which")
-                                .arg("xclip")
-                                .output()
-                                .map(|o| o.status.success())
-                                .unwrap_or(false)
-                            {
-                                Some("xclip -selection clipboard")
-                            } else if StdCommand::new("which")
-                                .arg("xsel")
-                                .output()
-                                .map(|o| o.status.success())
-                                .unwrap_or(false)
-                            {
-                                Some("xsel --clipboard --input")
-                            } else {
-                                None
+                            status = match copy_to_clipboard(
+                                &mut toasts,
+                                &hit.snippet.replace("**", ""),
+                            ) {
+                                Ok(()) => "✓ Snippet copied to clipboard".to_string(),
+                                Err(e) => format!("✗ {e}"),
                             };
-
-                            status = if let Some(cmd) = clipboard_cmd {
-                                let result = StdCommand::new("sh")
-                                    .arg("-c")
-                                    .arg(cmd)
-                                    .stdin(std::process::Stdio::piped())
-                                    .spawn()
-                                    .and_then(|mut child| {
-                                        use std::io::Write;
-                                        if let Some(stdin) = child.stdin.as_mut() {
-                                            stdin.write_all(snippet.as_bytes())?;
-                                        }
-                                        child.wait()
-                                    });
-                                if result.map(|s| s.success()).unwrap_or(false) {
-                                    "✓ Snippet copied to clipboard".to_string()
-                                } else {
-                                    "✗ Clipboard copy failed".to_string()
-                                }
-                            } else {
-                                "✗ No clipboard tool found".to_string()
+                        }
+                    }
+                    KeyCode::Char('y') => {
+                        // Yank the selected message
+                        if let Some(pane) = panes.get(active_pane)
+                            && let Some(hit) = pane.hits.get(pane.selected)
+                        {
+                            status = match copy_to_clipboard(&mut toasts, &hit.content) {
+                                Ok(()) => "✓ Message copied to clipboard".to_string(),
+                                Err(e) => format!("✗ {e}"),
                             };
                         }
                     }
@@ -4727,6 +6470,38 @@ pub fn run_tui(
                 continue;
             }
 
+            // Calendar navigation and presets shared by the from/to date modes.
+            if let Some(picker) = date_picker.as_mut() {
+                let mut handled = true;
+                match key.code {
+                    KeyCode::Left => picker.move_days(-1),
+                    KeyCode::Right => picker.move_days(1),
+                    KeyCode::Up => picker.move_days(-7),
+                    KeyCode::Down => picker.move_days(7),
+                    KeyCode::PageUp => picker.move_months(-1),
+                    KeyCode::PageDown => picker.move_months(1),
+                    KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::ALT) => {
+                        if let Some(((start, end), label)) = date_picker_preset(c) {
+                            filters.created_from = Some(start);
+                            filters.created_to = Some(end);
+                            page = 0;
+                            input_mode = InputMode::Query;
+                            active_pane = 0;
+                            cached_detail = None;
+                            detail_scroll = 0;
+                            status = format!("Time filter: {label}");
+                            input_buffer.clear();
+                            dirty_since = Some(Instant::now());
+                            focus_region = FocusRegion::Results;
+                        }
+                    }
+                    _ => handled = false,
+                }
+                if handled {
+                    continue;
+                }
+            }
+
             match input_mode {
                 InputMode::Query => {
                     if key.modifiers.contains(KeyModifiers::CONTROL) {
@@ -4735,21 +6510,24 @@ pub fn run_tui(
                             && c != '0'
                         {
                             let slot = c.to_digit(10).unwrap() as u8;
-                            status = save_view_slot(slot, &filters, ranking_mode, &mut saved_views);
+                            status = save_view_slot(
+                                slot,
+                                &filters,
+                                ranking_mode,
+                                sort_order,
+                                &mut saved_views,
+                            );
                             continue;
                         }
                         // Handle both 'r' and 'R' since Shift modifier may change the char
                         if matches!(key.code, KeyCode::Char('r' | 'R')) {
                             // Ctrl+Shift+R = refresh search (re-query index)
                             if key.modifiers.contains(KeyModifiers::SHIFT) {
-                                if let Some(tx) = &reindex_tx {
-                                    let _ = tx.send(crate::indexer::IndexerEvent::Command(
-                                        crate::indexer::ReindexCommand::Full,
-                                    ));
-                                    status = "Triggered background re-index...".to_string();
+                                status = if reindex_tx.is_some() {
+                                    request_reindex(reindex_tx.as_ref())
                                 } else {
-                                    status = "Refreshing search view...".to_string();
-                                }
+                                    "Refreshing search view...".to_string()
+                                };
                                 page = 0;
                                 dirty_since = Some(Instant::now());
                                 cached_detail = None;
@@ -4771,6 +6549,30 @@ pub fn run_tui(
                                 }
                             }
                         }
+                        // Ctrl+D = cycle result density
+                        if matches!(key.code, KeyCode::Char('d' | 'D')) {
+                            density_mode = density_mode.next();
+                            // Recalculate pane limit with new density
+                            let height = screen_rows(&terminal);
+                            per_pane_limit = calculate_pane_limit(height, density_mode);
+                            let prev_agent = active_hit(&panes, active_pane)
+                                .map(|h| h.agent.clone())
+                                .or_else(|| panes.get(active_pane).map(|p| p.agent.clone()));
+                            let prev_path =
+                                active_hit(&panes, active_pane).map(|h| h.source_path.clone());
+                            panes = rebuild_panes_with_filter(
+                                &results,
+                                pane_filter.as_deref(),
+                                per_pane_limit * pane_pages,
+                                &mut active_pane,
+                                &mut pane_scroll_offset,
+                                prev_agent,
+                                prev_path,
+                                MAX_VISIBLE_PANES,
+                            );
+                            status = format!("Density: {}", density_mode.label());
+                            needs_draw = true;
+                        }
                         continue;
                     }
 
@@ -4781,9 +6583,13 @@ pub fn run_tui(
                                 && c != '0' =>
                         {
                             let slot = c.to_digit(10).unwrap() as u8;
-                            if let Some(msg) =
-                                load_view_slot(slot, &mut filters, &mut ranking_mode, &saved_views)
-                            {
+                            if let Some(msg) = load_view_slot(
+                                slot,
+                                &mut filters,
+                                &mut ranking_mode,
+                                &mut sort_order,
+                                &saved_views,
+                            ) {
                                 status = msg;
                                 page = 0;
                                 dirty_since = Some(Instant::now());
@@ -4794,8 +6600,12 @@ pub fn run_tui(
                             }
                         }
                         KeyCode::Backspace if query.is_empty() => {
-                            // Clear the last applied filter (time -> workspace -> agent)
-                            if filters.created_from.is_some() || filters.created_to.is_some() {
+                            // Clear the last applied filter (role -> time -> workspace -> agent)
+                            if !filters.roles.is_empty() {
+                                filters.roles.clear();
+                                status = "Cleared role filter".to_string();
+                            } else if filters.created_from.is_some() || filters.created_to.is_some()
+                            {
                                 filters.created_from = None;
                                 filters.created_to = None;
                                 status = "Cleared time filter".to_string();
@@ -4837,9 +6647,8 @@ pub fn run_tui(
                                         suggestion_idx = Some(std::cmp::min(next, max_idx));
                                         status = "Enter to load selected recent query".to_string();
                                     } else if let Some(pane) = panes.get_mut(active_pane)
-                                        && pane.selected + 1 < pane.hits.len()
+                                        && pane.step_selection(true, &collapsed)
                                     {
-                                        pane.selected += 1;
                                         // Re-load details for new selection
                                         cached_detail = None;
                                         detail_scroll = 0;
@@ -4864,9 +6673,8 @@ pub fn run_tui(
                                         suggestion_idx = Some(next);
                                         status = "Enter to load selected recent query".to_string();
                                     } else if let Some(pane) = panes.get_mut(active_pane)
-                                        && pane.selected > 0
+                                        && pane.step_selection(false, &collapsed)
                                     {
-                                        pane.selected -= 1;
                                         // Re-load details for new selection
                                         cached_detail = None;
                                         detail_scroll = 0;
@@ -4952,53 +6760,20 @@ pub fn run_tui(
                                 // User committed to copying result - save query to history
                                 save_query_to_history(&query, &mut query_history, history_cap);
                                 let text_to_copy = if matches!(focus_region, FocusRegion::Detail) {
-                                    if let Some((_, _)) = &cached_detail {
-                                        hit.content.clone()
-                                    } else {
-                                        hit.content.clone()
-                                    }
+                                    &hit.content
                                 } else {
-                                    hit.source_path.clone()
+                                    &hit.source_path
                                 };
-
-                                #[cfg(any(target_os = "linux", target_os = "macos"))]
-                                {
-                                    use std::process::Stdio;
-                                    let child = std::process::Command::new("sh")
-                                        .arg("-c")
-                                        .arg("if command -v wl-copy >/dev/null; then wl-copy; elif command -v pbcopy >/dev/null; then pbcopy; elif command -v xclip >/dev/null; then xclip -selection clipboard; fi")
-                                        .stdin(Stdio::piped())
-                                        .spawn();
-                                    if let Ok(mut child) = child
-                                        && let Some(mut stdin) = child.stdin.take()
-                                    {
-                                        use std::io::Write;
-                                        let _ = stdin.write_all(text_to_copy.as_bytes());
-                                        drop(stdin); // Ensure EOF
-                                        let _ = child.wait();
-                                        status = "Copied to clipboard".to_string();
-                                    } else {
-                                        status =
-                                            "Clipboard copy failed (missing tool?)".to_string();
-                                    }
-                                }
-                                #[cfg(target_os = "windows")]
-                                {
-                                    let child = std::process::Command::new("powershell")
-                                        .arg("-command")
-                                        .arg("$Input | Set-Clipboard")
-                                        .stdin(std::process::Stdio::piped())
-                                        .spawn();
-                                    if let Ok(mut child) = child
-                                        && let Some(mut stdin) = child.stdin.take()
-                                    {
-                                        use std::io::Write;
-                                        let _ = stdin.write_all(text_to_copy.as_bytes());
-                                        drop(stdin);
-                                        let _ = child.wait();
-                                        status = "Copied to clipboard".to_string();
-                                    }
-                                }
+                                status = match copy_to_clipboard(&mut toasts, text_to_copy) {
+                                    Ok(()) => "Copied to clipboard".to_string(),
+                                    Err(e) => format!("✗ {e}"),
+                                };
+                            }
+                        }
+                        // Ctrl+K stars or unstars the current message
+                        KeyCode::Char('k') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            if let Some(hit) = active_hit(&panes, active_pane) {
+                                status = toggle_bookmark(&data_dir, hit, &mut bookmarked);
                             }
                         }
                         // Multi-select: Ctrl+M toggles selection on current item
@@ -5054,9 +6829,7 @@ pub fn run_tui(
                                 selected.insert(key);
                                 open_confirm_armed = false;
                                 // Move to next item in the pane
-                                if pane.selected + 1 < pane.hits.len() {
-                                    pane.selected += 1;
-                                }
+                                pane.step_selection(true, &collapsed);
                                 status = format!(
                                     "Queued ({}) · Ctrl+Enter add · Ctrl+O open all",
                                     selected.len()
@@ -5087,26 +6860,18 @@ pub fn run_tui(
                                     .or_else(|_| std::env::var("VISUAL"))
                                     .unwrap_or_else(|_| "code".to_string());
                                 // Exit raw mode
-                                disable_raw_mode().ok();
-                                execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)
-                                    .ok();
+                                leave_tui_screen();
                                 for hit in &selected_hits {
-                                    let mut cmd = StdCommand::new(&editor);
-                                    if editor == "code" {
-                                        if let Some(ln) = hit.line_number {
-                                            cmd.arg("--goto")
-                                                .arg(format!("{}:{}", hit.source_path, ln));
-                                        } else {
-                                            cmd.arg(&hit.source_path);
-                                        }
-                                    } else {
-                                        cmd.arg(&hit.source_path);
+                                    if let Some(mut cmd) = editor_command(
+                                        &editor,
+                                        &editor_line_flag,
+                                        &hit.source_path,
+                                        hit.line_number,
+                                    ) {
+                                        let _ = cmd.status();
                                     }
-                                    let _ = cmd.status();
                                 }
-                                execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)
-                                    .ok();
-                                enable_raw_mode().ok();
+                                enter_tui_screen();
                                 status =
                                     format!("Opened {} files in {}", selected_hits.len(), editor);
                                 selected.clear();
@@ -5155,10 +6920,8 @@ pub fn run_tui(
                         KeyCode::F(3) => {
                             input_mode = InputMode::Agent;
                             input_buffer.clear();
-                            status = format!(
-                                "Agents: {} (type to filter, Tab=complete, Enter=apply)",
-                                KNOWN_AGENTS.join(", ")
-                            );
+                            status = "Agent filter: type to narrow, Space=toggle, Enter=apply, Esc=cancel"
+                                .to_string();
                         }
                         KeyCode::F(4) if key.modifiers.contains(KeyModifiers::SHIFT) => {
                             filters.agents.clear();
@@ -5172,9 +6935,8 @@ pub fn run_tui(
                         KeyCode::F(4) => {
                             input_mode = InputMode::Workspace;
                             input_buffer.clear();
-                            status =
-                                "Workspace filter: type path fragment, Enter=apply, Esc=cancel"
-                                    .to_string();
+                            status = "Workspace filter: type to narrow, Up/Down select, Enter=apply, Esc=cancel"
+                                .to_string();
                         }
                         KeyCode::F(5) => {
                             if key.modifiers.contains(KeyModifiers::SHIFT) {
@@ -5200,16 +6962,26 @@ pub fn run_tui(
                                 input_mode = InputMode::CreatedFrom;
                                 input_buffer.clear();
                                 status =
-                                    "From: -7d, yesterday, 2024-11-25 | Enter=apply, Esc=cancel"
+                                    "From: pick a day or type -7d, yesterday, 2024-11-25 | Enter=apply, Del=clear, Esc=cancel"
                                         .to_string();
                             }
                         }
                         KeyCode::F(6) => {
-                            input_mode = InputMode::CreatedTo;
-                            input_buffer.clear();
-                            status =
-                                "To: -7d, yesterday, 2024-11-25, now | Enter=apply, Esc=cancel"
-                                    .to_string();
+                            if key.modifiers.contains(KeyModifiers::SHIFT) {
+                                filters.roles = next_role_filter(&filters.roles);
+                                status = match role_filter_label(&filters.roles) {
+                                    Some(label) => format!("Role: {label}"),
+                                    None => "Role: all".to_string(),
+                                };
+                                page = 0;
+                                dirty_since = Some(Instant::now());
+                            } else {
+                                input_mode = InputMode::CreatedTo;
+                                input_buffer.clear();
+                                status =
+                                    "To: pick a day or type -7d, yesterday, now | Enter=apply, Del=clear, Esc=cancel"
+                                        .to_string();
+                            }
                         }
                         KeyCode::F(7) => {
                             context_window = context_window.next();
@@ -5232,49 +7004,28 @@ pub fn run_tui(
                             );
                             needs_draw = true;
                         }
-                        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                            density_mode = density_mode.next();
-                            // Recalculate pane limit with new density
-                            let height = terminal.size().map(|r| r.height).unwrap_or(24);
-                            per_pane_limit = calculate_pane_limit(height, density_mode);
-                            let prev_agent = active_hit(&panes, active_pane)
-                                .map(|h| h.agent.clone())
-                                .or_else(|| panes.get(active_pane).map(|p| p.agent.clone()));
-                            let prev_path =
-                                active_hit(&panes, active_pane).map(|h| h.source_path.clone());
-                            panes = rebuild_panes_with_filter(
-                                &results,
-                                pane_filter.as_deref(),
-                                per_pane_limit,
-                                &mut active_pane,
-                                &mut pane_scroll_offset,
-                                prev_agent,
-                                prev_path,
-                                MAX_VISIBLE_PANES,
-                            );
-                            status = format!("Density: {}", density_mode.label());
+                        KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            pane_layout = pane_layout.next();
+                            if pane_layout == PaneLayout::Results {
+                                focus_region = FocusRegion::Results;
+                            }
+                            status = format!("Layout: {}", pane_layout.label());
                             needs_draw = true;
                         }
+                        KeyCode::F(12) if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                            sort_order = sort_order.next();
+                            status = format!("Sort: {}", sort_order.label());
+                            dirty_since = Some(Instant::now());
+                        }
                         KeyCode::F(12) => {
-                            ranking_mode = match ranking_mode {
-                                RankingMode::RecentHeavy => RankingMode::Balanced,
-                                RankingMode::Balanced => RankingMode::RelevanceHeavy,
-                                RankingMode::RelevanceHeavy => RankingMode::MatchQualityHeavy,
-                                RankingMode::MatchQualityHeavy => RankingMode::DateNewest,
-                                RankingMode::DateNewest => RankingMode::DateOldest,
-                                RankingMode::DateOldest => RankingMode::RecentHeavy,
-                            };
-                            status = format!(
-                                "Ranking: {}",
-                                match ranking_mode {
-                                    RankingMode::RecentHeavy => "recent-heavy",
-                                    RankingMode::Balanced => "balanced",
-                                    RankingMode::RelevanceHeavy => "relevance-heavy",
-                                    RankingMode::MatchQualityHeavy => "match-quality",
-                                    RankingMode::DateNewest => "date (newest first)",
-                                    RankingMode::DateOldest => "date (oldest first)",
-                                }
-                            );
+                            ranking_mode = ranking_mode.next();
+                            status = format!("Ranking: {}", ranking_mode.label());
+                            if sort_order != SortOrder::Relevance {
+                                status.push_str(&format!(
+                                    " (applies when sorted by relevance; Shift+F12 sorts, now {})",
+                                    sort_order.label()
+                                ));
+                            }
                             dirty_since = Some(Instant::now());
                         }
                         KeyCode::Delete
@@ -5286,8 +7037,8 @@ pub fn run_tui(
                             // Restore runtime defaults
                             match_mode = MatchMode::Prefix;
                             context_window = ContextWindow::Medium;
-                            density_mode = DensityMode::Cozy;
-                            let height = terminal.size().map(|r| r.height).unwrap_or(24);
+                            density_mode = configured_density;
+                            let height = screen_rows(&terminal);
                             per_pane_limit = calculate_pane_limit(height, density_mode);
                             filters = SearchFilters::default();
                             pane_filter = None;
@@ -5337,12 +7088,9 @@ pub fn run_tui(
                             if let Some(hit) = active_hit(&panes, active_pane) {
                                 // User committed to viewing result in editor - save query to history
                                 save_query_to_history(&query, &mut query_history, history_cap);
-                                let path = &hit.source_path;
-                                let mut cmd = StdCommand::new(&editor_cmd);
-                                if let Some(line) = hit.line_number {
-                                    cmd.arg(format!("{editor_line_flag}{line}"));
-                                }
-                                let _ = cmd.arg(path).status();
+                                status =
+                                    edit_hit(&mut terminal, &editor_cmd, &editor_line_flag, hit);
+                                needs_draw = true;
                             }
                         }
                         KeyCode::F(9) => {
@@ -5359,7 +7107,30 @@ pub fn run_tui(
                             );
                             dirty_since = Some(Instant::now());
                         }
-                        KeyCode::Tab => {
+                        KeyCode::Tab
+                            if matches!(focus_region, FocusRegion::Results)
+                                && !panes.is_empty() =>
+                        {
+                            // Fold or unfold the selected conversation
+                            if let Some(pane) = panes.get_mut(active_pane)
+                                && let Some(hit) = pane.hits.get(pane.selected)
+                            {
+                                let key = conversation_of(hit).to_string();
+                                if collapsed.remove(&key) {
+                                    status = "Expanded conversation".to_string();
+                                } else {
+                                    collapsed.insert(key);
+                                    let before = pane.selected;
+                                    pane.reveal_selection(&collapsed);
+                                    if pane.selected != before {
+                                        cached_detail = None;
+                                        detail_scroll = 0;
+                                    }
+                                    status = "Collapsed conversation (Tab to expand)".to_string();
+                                }
+                            }
+                        }
+                        KeyCode::Tab | KeyCode::BackTab => {
                             // Toggle focus
                             focus_region = match focus_region {
                                 FocusRegion::Results => FocusRegion::Detail,
@@ -5424,6 +7195,45 @@ pub fn run_tui(
                                     needs_draw = true;
                                     continue;
                                 }
+                                if c == 'p' {
+                                    if let Some(hit) = active_hit(&panes, active_pane) {
+                                        status = toggle_pin(&data_dir, hit, &mut pins);
+                                    }
+                                    needs_draw = true;
+                                    continue;
+                                }
+                                if c == 'r' {
+                                    if let Some(hit) = active_hit(&panes, active_pane) {
+                                        save_query_to_history(
+                                            &query,
+                                            &mut query_history,
+                                            history_cap,
+                                        );
+                                        status = resume_hit(&mut terminal, hit);
+                                    }
+                                    needs_draw = true;
+                                    continue;
+                                }
+                                if c == 't' {
+                                    if let Some(hit) = active_hit(&panes, active_pane) {
+                                        status = toggle_tail(&mut tail, hit);
+                                        detail_tab = DetailTab::Messages;
+                                    }
+                                    needs_draw = true;
+                                    continue;
+                                }
+                                if c == 'e' {
+                                    if let Some(hit) = active_hit(&panes, active_pane) {
+                                        status = edit_hit(
+                                            &mut terminal,
+                                            &editor_cmd,
+                                            &editor_line_flag,
+                                            hit,
+                                        );
+                                    }
+                                    needs_draw = true;
+                                    continue;
+                                }
                                 // Other typing returns focus to results/query
                                 focus_region = FocusRegion::Results;
                             }
@@ -5465,7 +7275,10 @@ pub fn run_tui(
                             }
 
                             if key.modifiers.contains(KeyModifiers::ALT) {
-                                if ('1'..='9').contains(&c) {
+                                if matches!(c, 'r' | 'R') {
+                                    status = request_reindex(reindex_tx.as_ref());
+                                    dirty_since = Some(Instant::now());
+                                } else if ('1'..='9').contains(&c) {
                                     let target = c.to_digit(10).unwrap_or(1) as usize - 1;
                                     if target < panes.len() {
                                         active_pane = target;
@@ -5489,7 +7302,7 @@ pub fn run_tui(
                                 panes = rebuild_panes_with_filter(
                                     &results,
                                     pane_filter.as_deref(),
-                                    per_pane_limit,
+                                    per_pane_limit * pane_pages,
                                     &mut active_pane,
                                     &mut pane_scroll_offset,
                                     prev_agent,
@@ -5512,7 +7325,7 @@ pub fn run_tui(
                                 panes = rebuild_panes_with_filter(
                                     &results,
                                     pane_filter.as_deref(),
-                                    per_pane_limit,
+                                    per_pane_limit * pane_pages,
                                     &mut active_pane,
                                     &mut pane_scroll_offset,
                                     prev_agent,
@@ -5565,6 +7378,7 @@ pub fn run_tui(
                                             && !pane.hits.is_empty()
                                         {
                                             pane.selected = pane.hits.len() - 1;
+                                            pane.reveal_selection(&collapsed);
                                             cached_detail = None;
                                             detail_scroll = 0;
                                         }
@@ -5575,9 +7389,8 @@ pub fn run_tui(
                                         match focus_region {
                                             FocusRegion::Results => {
                                                 if let Some(pane) = panes.get_mut(active_pane)
-                                                    && pane.selected + 1 < pane.hits.len()
+                                                    && pane.step_selection(true, &collapsed)
                                                 {
-                                                    pane.selected += 1;
                                                     cached_detail = None;
                                                     detail_scroll = 0;
                                                 }
@@ -5593,9 +7406,8 @@ pub fn run_tui(
                                         match focus_region {
                                             FocusRegion::Results => {
                                                 if let Some(pane) = panes.get_mut(active_pane)
-                                                    && pane.selected > 0
+                                                    && pane.step_selection(false, &collapsed)
                                                 {
-                                                    pane.selected -= 1;
                                                     cached_detail = None;
                                                     detail_scroll = 0;
                                                 }
@@ -5706,11 +7518,10 @@ pub fn run_tui(
                                 }
                                 if !filters.agents.is_empty() {
                                     input_mode = InputMode::Agent;
-                                    if let Some(last) = filters.agents.iter().next() {
-                                        input_buffer = last.clone();
-                                    }
+                                    input_buffer.clear();
                                     status =
-                                        "Edit agent filter (Enter apply, Esc cancel)".to_string();
+                                        "Edit agent filter (Space toggle, Enter apply, Esc cancel)"
+                                            .to_string();
                                     continue;
                                 }
                                 if !filters.workspaces.is_empty() {
@@ -5724,16 +7535,14 @@ pub fn run_tui(
                                 }
                                 if filters.created_from.is_some() {
                                     input_mode = InputMode::CreatedFrom;
-                                    input_buffer =
-                                        filters.created_from.unwrap_or_default().to_string();
+                                    input_buffer.clear();
                                     status =
                                         "Edit from timestamp (Enter apply, Esc cancel)".to_string();
                                     continue;
                                 }
                                 if filters.created_to.is_some() {
                                     input_mode = InputMode::CreatedTo;
-                                    input_buffer =
-                                        filters.created_to.unwrap_or_default().to_string();
+                                    input_buffer.clear();
                                     status =
                                         "Edit to timestamp (Enter apply, Esc cancel)".to_string();
                                     continue;
@@ -5762,70 +7571,81 @@ pub fn run_tui(
                         input_buffer.clear();
                         status = "Agent filter cancelled".to_string();
                     }
+                    KeyCode::Up => {
+                        if let Some(picker) = agent_picker.as_mut() {
+                            picker.move_selection(-1);
+                        }
+                    }
+                    KeyCode::Down => {
+                        if let Some(picker) = agent_picker.as_mut() {
+                            picker.move_selection(1);
+                        }
+                    }
+                    KeyCode::PageUp => {
+                        if let Some(picker) = agent_picker.as_mut() {
+                            picker.move_selection(-5);
+                        }
+                    }
+                    KeyCode::PageDown => {
+                        if let Some(picker) = agent_picker.as_mut() {
+                            picker.move_selection(5);
+                        }
+                    }
+                    KeyCode::Char(' ') => {
+                        if let Some(picker) = agent_picker.as_mut() {
+                            picker.toggle_selected();
+                            status = format!("{} agent(s) selected", picker.checked.len());
+                        }
+                    }
                     KeyCode::Tab => {
-                        // Tab completes to first matching suggestion
-                        let suggestions = agent_suggestions(&input_buffer);
-                        if let Some(first) = suggestions.first() {
-                            input_buffer = first.to_string();
-                            status = format!("Completed to '{first}'. Press Enter to apply.");
+                        // Tab completes the query to the highlighted agent
+                        if let Some(picker) = agent_picker.as_mut()
+                            && let Some(slug) = picker.selected_slug().map(str::to_string)
+                        {
+                            input_buffer = slug;
+                            picker.refilter(&input_buffer);
                         }
                     }
                     KeyCode::Enter => {
                         filters.agents.clear();
-                        if !input_buffer.trim().is_empty() {
-                            filters.agents.insert(input_buffer.trim().to_string());
+                        if let Some(picker) = &agent_picker {
+                            filters.agents.extend(picker.chosen(&input_buffer));
                         }
                         page = 0;
                         input_mode = InputMode::Query;
                         active_pane = 0;
                         cached_detail = None;
                         detail_scroll = 0;
-                        status = format!(
-                            "Agent filter set to {}",
-                            filters
-                                .agents
-                                .iter()
-                                .cloned()
-                                .collect::<Vec<_>>()
-                                .join(", ")
-                        );
+                        status = if filters.agents.is_empty() {
+                            "Scope: all agents".to_string()
+                        } else {
+                            format!(
+                                "Agent filter set to {}",
+                                filters
+                                    .agents
+                                    .iter()
+                                    .cloned()
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            )
+                        };
                         input_buffer.clear();
                         dirty_since = Some(Instant::now());
                         focus_region = FocusRegion::Results;
                     }
                     KeyCode::Backspace => {
                         input_buffer.pop();
-                        // Update suggestions in status
-                        let suggestions = agent_suggestions(&input_buffer);
-                        if !suggestions.is_empty() && !input_buffer.is_empty() {
-                            status = format!(
-                                "Suggestions: {} (Tab to complete)",
-                                suggestions.join(", ")
-                            );
-                        } else if input_buffer.is_empty() {
-                            status = format!(
-                                "Agents: {} (type to filter, Tab to complete)",
-                                KNOWN_AGENTS.join(", ")
-                            );
+                        if let Some(picker) = agent_picker.as_mut() {
+                            picker.refilter(&input_buffer);
                         }
                     }
                     KeyCode::Char(c) => {
                         input_buffer.push(c);
-                        // Update suggestions in status
-                        let suggestions = agent_suggestions(&input_buffer);
-                        if suggestions.is_empty() {
-                            status =
-                                format!("No matching agents. Known: {}", KNOWN_AGENTS.join(", "));
-                        } else if suggestions.len() == 1 {
-                            status = format!(
-                                "Match: {} (Tab to complete, Enter to apply)",
-                                suggestions[0]
-                            );
-                        } else {
-                            status = format!(
-                                "Suggestions: {} (Tab to complete)",
-                                suggestions.join(", ")
-                            );
+                        if let Some(picker) = agent_picker.as_mut() {
+                            picker.refilter(&input_buffer);
+                            if picker.filtered.is_empty() {
+                                status = format!("No indexed agent matches '{input_buffer}'");
+                            }
                         }
                     }
                     _ => {}
@@ -5836,9 +7656,36 @@ pub fn run_tui(
                         input_buffer.clear();
                         status = "Workspace filter cancelled".to_string();
                     }
+                    KeyCode::Up => {
+                        if let Some(picker) = workspace_picker.as_mut() {
+                            picker.move_selection(-1);
+                        }
+                    }
+                    KeyCode::Down => {
+                        if let Some(picker) = workspace_picker.as_mut() {
+                            picker.move_selection(1);
+                        }
+                    }
+                    KeyCode::PageUp => {
+                        if let Some(picker) = workspace_picker.as_mut() {
+                            picker.move_selection(-5);
+                        }
+                    }
+                    KeyCode::PageDown => {
+                        if let Some(picker) = workspace_picker.as_mut() {
+                            picker.move_selection(5);
+                        }
+                    }
                     KeyCode::Enter => {
                         filters.workspaces.clear();
-                        if !input_buffer.trim().is_empty() {
+                        // The highlighted workspace wins; a typed path with no match is kept as-is.
+                        let picked = workspace_picker
+                            .as_ref()
+                            .and_then(|p| p.selected_path())
+                            .map(str::to_string);
+                        if let Some(path) = picked {
+                            filters.workspaces.insert(path);
+                        } else if !input_buffer.trim().is_empty() {
                             filters.workspaces.insert(input_buffer.trim().to_string());
                         }
                         page = 0;
@@ -5861,15 +7708,46 @@ pub fn run_tui(
                     }
                     KeyCode::Backspace => {
                         input_buffer.pop();
+                        if let Some(picker) = workspace_picker.as_mut() {
+                            picker.refilter(&input_buffer);
+                        }
+                    }
+                    KeyCode::Char(c) => {
+                        input_buffer.push(c);
+                        if let Some(picker) = workspace_picker.as_mut() {
+                            picker.refilter(&input_buffer);
+                        }
                     }
-                    KeyCode::Char(c) => input_buffer.push(c),
                     _ => {}
                 },
                 InputMode::CreatedFrom => match key.code {
                     KeyCode::Esc => {
                         input_mode = InputMode::Query;
                         input_buffer.clear();
-                        status = "From timestamp cancelled".to_string();
+                        status = "From timestamp cancelled".to_string();
+                    }
+                    KeyCode::Enter
+                        if input_buffer.trim().is_empty()
+                            && let Some(picker) = date_picker =>
+                    {
+                        // Picked on the calendar: start the range there and go on to its end.
+                        filters.created_from = date_picker::day_start_ms(picker.cursor);
+                        page = 0;
+                        input_mode = InputMode::CreatedTo;
+                        cached_detail = None;
+                        detail_scroll = 0;
+                        status = format!(
+                            "From {} - pick the end date, Esc leaves it open",
+                            picker.cursor.format("%Y-%m-%d")
+                        );
+                        dirty_since = Some(Instant::now());
+                    }
+                    KeyCode::Delete => {
+                        filters.created_from = None;
+                        input_mode = InputMode::Query;
+                        input_buffer.clear();
+                        status = "From filter cleared".to_string();
+                        dirty_since = Some(Instant::now());
                     }
                     KeyCode::Enter => {
                         let parsed = crate::ui::time_parser::parse_time_input(&input_buffer);
@@ -5890,7 +7768,7 @@ pub fn run_tui(
                             focus_region = FocusRegion::Results;
                         } else {
                             status = format!(
-                                "Invalid time format '{}'. Try: -7d, yesterday, 2024-11-25",
+                                "Invalid time format '{}'. Try: 7d, yesterday, last monday, 2024-06",
                                 input_buffer.trim()
                             );
                         }
@@ -5907,6 +7785,28 @@ pub fn run_tui(
                         input_buffer.clear();
                         status = "To timestamp cancelled".to_string();
                     }
+                    KeyCode::Enter
+                        if input_buffer.trim().is_empty()
+                            && let Some(picker) = date_picker =>
+                    {
+                        // Picked on the calendar: the whole day is included.
+                        filters.created_to = date_picker::day_end_ms(picker.cursor);
+                        page = 0;
+                        input_mode = InputMode::Query;
+                        active_pane = 0;
+                        cached_detail = None;
+                        detail_scroll = 0;
+                        status = format!("To filter set: {}", picker.cursor.format("%Y-%m-%d"));
+                        dirty_since = Some(Instant::now());
+                        focus_region = FocusRegion::Results;
+                    }
+                    KeyCode::Delete => {
+                        filters.created_to = None;
+                        input_mode = InputMode::Query;
+                        input_buffer.clear();
+                        status = "To filter cleared".to_string();
+                        dirty_since = Some(Instant::now());
+                    }
                     KeyCode::Enter => {
                         let parsed = crate::ui::time_parser::parse_time_input(&input_buffer);
                         if parsed.is_some() || input_buffer.trim().is_empty() {
@@ -5926,7 +7826,7 @@ pub fn run_tui(
                             focus_region = FocusRegion::Results;
                         } else {
                             status = format!(
-                                "Invalid time format '{}'. Try: -7d, yesterday, 2024-11-25",
+                                "Invalid time format '{}'. Try: 7d, yesterday, last monday, 2024-06",
                                 input_buffer.trim()
                             );
                         }
@@ -5952,7 +7852,7 @@ pub fn run_tui(
                         panes = rebuild_panes_with_filter(
                             &results,
                             pane_filter.as_deref(),
-                            per_pane_limit,
+                            per_pane_limit * pane_pages,
                             &mut active_pane,
                             &mut pane_scroll_offset,
                             prev_agent,
@@ -5980,7 +7880,7 @@ pub fn run_tui(
                         panes = rebuild_panes_with_filter(
                             &results,
                             pane_filter.as_deref(),
-                            per_pane_limit,
+                            per_pane_limit * pane_pages,
                             &mut active_pane,
                             &mut pane_scroll_offset,
                             prev_agent,
@@ -6011,7 +7911,7 @@ pub fn run_tui(
                         panes = rebuild_panes_with_filter(
                             &results,
                             pane_filter.as_deref(),
-                            per_pane_limit,
+                            per_pane_limit * pane_pages,
                             &mut active_pane,
                             &mut pane_scroll_offset,
                             prev_agent,
@@ -6033,7 +7933,7 @@ pub fn run_tui(
                         panes = rebuild_panes_with_filter(
                             &results,
                             pane_filter.as_deref(),
-                            per_pane_limit,
+                            per_pane_limit * pane_pages,
                             &mut active_pane,
                             &mut pane_scroll_offset,
                             prev_agent,
@@ -6085,28 +7985,114 @@ pub fn run_tui(
         }
 
         if last_tick.elapsed() >= tick_rate {
-            if let Some(client) = &search_client {
-                let should_search = dirty_since.is_some_and(|t| t.elapsed() >= debounce);
-
-                if should_search {
+            if let Some(t) = tail.as_mut()
+                && t.due()
+            {
+                if active_hit(&panes, active_pane).map(|h| h.source_path.as_str())
+                    != Some(t.source_path.as_str())
+                {
+                    tail = None;
+                    status = "Stopped tailing: another result is selected".to_string();
+                    needs_draw = true;
+                } else if let Some(storage) = &db_reader
+                    && let Some((mut view, added)) = t.poll(storage, reindex_tx.as_ref())
+                {
+                    view.notes = crate::notes::for_session(&data_dir, &t.source_path);
+                    cached_detail = Some((t.source_path.clone(), view));
+                    if added > 0 {
+                        status = format!(
+                            "Tail: {added} new message{}",
+                            if added == 1 { "" } else { "s" }
+                        );
+                    }
+                    needs_draw = true;
+                }
+            }
+            if freshness_read_at.elapsed() >= FRESHNESS_REFRESH {
+                let fresh = read_freshness(db_reader.as_ref());
+                if fresh != freshness {
+                    freshness = fresh;
+                    needs_draw = true;
+                }
+                freshness_read_at = Instant::now();
+            }
+            if let Some(worker) = &search_worker {
+                // Anything that marks the view dirty outdates the search in flight.
+                if dirty_since.is_some() && search_pending {
+                    worker.cancel();
+                    search_pending = false;
+                }
+                if dirty_since.is_some_and(|t| t.elapsed() >= debounce) {
                     last_query = query.clone();
+                    searched_query = apply_match_mode(&query, match_mode);
+                    let mut search_filters = filters.clone();
+                    // Date sorts list by time, so the score needs no recency decay.
+                    search_filters.recency = (sort_order == SortOrder::Relevance)
+                        .then(|| RecencyDecay::with_weight(ranking_mode.recency_weight()));
+                    worker.submit(
+                        searched_query.clone(),
+                        search_filters,
+                        page_size,
+                        page * page_size,
+                        page == 0 && !searched_query.trim().is_empty() && pane_filter.is_none(),
+                    );
+                    search_pending = true;
+                    appending = false;
+                    dirty_since = None;
+                }
+
+                // Near the end of a pane, show the next screenful of what's fetched, or
+                // fetch the next page once the pane shows it all.
+                if dirty_since.is_none()
+                    && !search_pending
+                    && let Some(pane) = panes.get(active_pane)
+                    && pane.selected + LOAD_MORE_MARGIN >= pane.hits.len()
+                    && load_more_at != Some((active_pane, pane.selected))
+                {
+                    load_more_at = Some((active_pane, pane.selected));
+                    if pane.hits.len() < pane.total_count {
+                        pane_pages += 1;
+                        let prev_agent = Some(pane.agent.clone());
+                        let prev_path =
+                            active_hit(&panes, active_pane).map(|h| h.source_path.clone());
+                        panes = rebuild_panes_with_filter(
+                            &results,
+                            pane_filter.as_deref(),
+                            per_pane_limit * pane_pages,
+                            &mut active_pane,
+                            &mut pane_scroll_offset,
+                            prev_agent,
+                            prev_path,
+                            MAX_VISIBLE_PANES,
+                        );
+                        needs_draw = true;
+                    } else if more_results {
+                        let mut search_filters = filters.clone();
+                        search_filters.recency = (sort_order == SortOrder::Relevance)
+                            .then(|| RecencyDecay::with_weight(ranking_mode.recency_weight()));
+                        worker.submit(
+                            searched_query.clone(),
+                            search_filters,
+                            page_size,
+                            page * page_size + results.len(),
+                            false,
+                        );
+                        search_pending = true;
+                        appending = true;
+                        status = "Loading more results...".to_string();
+                    }
+                }
+
+                if search_pending && let Some(reply) = worker.poll() {
+                    search_pending = false;
                     let prev_agent = active_hit(&panes, active_pane)
                         .map(|h| h.agent.clone())
                         .or_else(|| panes.get(active_pane).map(|p| p.agent.clone()));
                     let prev_path = active_hit(&panes, active_pane).map(|h| h.source_path.clone());
-                    let q = apply_match_mode(&query, match_mode);
-                    // Use search_with_fallback for implicit wildcard expansion on sparse results
-                    const SPARSE_THRESHOLD: usize = 3;
-                    let search_started = Instant::now();
-                    match client.search_with_fallback(
-                        &q,
-                        filters.clone(),
-                        page_size,
-                        page * page_size,
-                        SPARSE_THRESHOLD,
-                    ) {
+                    let q = &searched_query;
+                    match reply.result {
                         Ok(search_result) => {
-                            last_search_ms = Some(search_started.elapsed().as_millis());
+                            last_search_ms = Some(reply.elapsed.as_millis());
                             let hits = search_result.hits;
                             cache_stats = if cache_debug {
                                 Some(search_result.cache_stats)
@@ -6115,42 +8101,34 @@ pub fn run_tui(
                             };
                             wildcard_fallback = search_result.wildcard_fallback;
                             suggestions = search_result.suggestions;
-                            dirty_since = None;
-                            // dft.2: Zero-match recent fallback
-                            // When search returns 0 results for a non-empty query, fall back to
-                            // showing recent conversations per agent
-                            let use_recent_fallback = hits.is_empty()
-                                && page == 0
-                                && !q.trim().is_empty()
-                                && pane_filter.is_none();
-
-                            if hits.is_empty() && page > 0 {
+                            let appended = std::mem::take(&mut appending);
+                            if appended && hits.is_empty() {
+                                more_results = false;
+                                status = format!("All {} results loaded", results.len());
+                                needs_draw = true;
+                            } else if hits.is_empty() && page > 0 {
                                 page = page.saturating_sub(1);
                                 active_pane = 0;
                                 dirty_since = Some(Instant::now());
                                 needs_draw = true;
-                            } else if use_recent_fallback {
-                                // Fetch recent results with no query filter (dft.2)
-                                let fallback_filters = SearchFilters::default();
-                                match client.search("", fallback_filters, page_size, 0) {
-                                    Ok(recent_hits) => {
-                                        results = recent_hits;
-                                        // Sort by recency (newest first)
-                                        results.sort_by(|a, b| {
-                                            let ts_a = a.created_at.unwrap_or(0);
-                                            let ts_b = b.created_at.unwrap_or(0);
-                                            ts_b.cmp(&ts_a)
-                                        });
-                                    }
-                                    Err(_) => {
-                                        results = Vec::new();
-                                    }
-                                }
+                            } else if let Some(recent_hits) = reply.recent {
+                                // dft.2: Zero-match recent fallback
+                                // When search returns 0 results for a non-empty query, fall back
+                                // to showing recent conversations per agent, newest first
+                                results = recent_hits;
+                                total_matches = None;
+                                more_results = false;
+                                pane_pages = 1;
+                                results.sort_by(|a, b| {
+                                    let ts_a = a.created_at.unwrap_or(0);
+                                    let ts_b = b.created_at.unwrap_or(0);
+                                    ts_b.cmp(&ts_a)
+                                });
                                 // Build panes from fallback results
                                 panes = rebuild_panes_with_filter(
                                     &results,
                                     None, // No pane filter for fallback
-                                    per_pane_limit,
+                                    per_pane_limit * pane_pages,
                                     &mut active_pane,
                                     &mut pane_scroll_offset,
                                     prev_agent.clone(),
@@ -6177,81 +8155,75 @@ pub fn run_tui(
                                         q.chars().take(30).collect::<String>()
                                     );
                                 }
+                                if let Some(hint) = spelling_hint(&suggestions) {
+                                    status = format!("{status} {hint}");
+                                }
                                 needs_draw = true;
                             } else {
-                                results = hits;
-                                let max_created = results
-                                    .iter()
-                                    .filter_map(|h| h.created_at)
-                                    .max()
-                                    .unwrap_or(0)
-                                    as f32;
+                                let fetched = hits.len();
+                                if appended {
+                                    results.extend(hits);
+                                } else {
+                                    results = hits;
+                                    pane_pages = 1;
+                                    session_tags = crate::session_tags::load(&data_dir);
+                                }
+                                total_matches = search_result.total_hits;
+                                more_results = fetched == page_size
+                                    && total_matches.is_none_or(|total| {
+                                        page * page_size + results.len() < total
+                                    });
                                 // Handle pure date sorting modes separately
-                                if matches!(
-                                    ranking_mode,
-                                    RankingMode::DateNewest | RankingMode::DateOldest
-                                ) {
+                                if sort_order != SortOrder::Relevance {
                                     results.sort_by(|a, b| {
                                         let ts_a = a.created_at.unwrap_or(0);
                                         let ts_b = b.created_at.unwrap_or(0);
-                                        if matches!(ranking_mode, RankingMode::DateNewest) {
+                                        if sort_order == SortOrder::Newest {
                                             ts_b.cmp(&ts_a) // Descending (newest first)
                                         } else {
                                             ts_a.cmp(&ts_b) // Ascending (oldest first)
                                         }
                                     });
                                 } else {
-                                    // Alpha: recency weight factor for blended ranking
-                                    let alpha = match ranking_mode {
-                                        RankingMode::RecentHeavy => 1.0,
-                                        RankingMode::Balanced => 0.4,
-                                        RankingMode::RelevanceHeavy => 0.1,
-                                        RankingMode::MatchQualityHeavy => 0.2, // Low recency, high quality focus
-                                        RankingMode::DateNewest | RankingMode::DateOldest => {
-                                            unreachable!()
-                                        }
-                                    };
-                                    // Per-hit quality factor based on match_type
+                                    // Scores already carry the engine's recency decay; weigh in
+                                    // the per-hit quality factor based on match_type
                                     //   Exact: 1.0, Prefix: 0.9, Suffix: 0.8,
-                                    //   Substring: 0.7, ImplicitWildcard: 0.6
-                                    let quality_factor =
-                                        |h: &SearchHit| -> f32 { h.match_type.quality_factor() };
+                                    //   Substring: 0.7, ImplicitWildcard: 0.6, Fuzzy: 0.5
+                                    let ranked = |h: &SearchHit| -> f32 {
+                                        h.score * h.match_type.quality_factor()
+                                    };
                                     results.sort_by(|a, b| {
-                                        let recency = |h: &SearchHit| -> f32 {
-                                            if max_created <= 0.0 {
-                                                return 0.0;
-                                            }
-                                            h.created_at.map_or(0.0, |v| v as f32 / max_created)
-                                        };
-                                        let score_a =
-                                            (a.score * quality_factor(a)) + alpha * recency(a);
-                                        let score_b =
-                                            (b.score * quality_factor(b)) + alpha * recency(b);
-                                        score_b
-                                            .partial_cmp(&score_a)
+                                        ranked(b)
+                                            .partial_cmp(&ranked(a))
                                             .unwrap_or(std::cmp::Ordering::Equal)
                                     });
                                 }
                                 panes = rebuild_panes_with_filter(
                                     &results,
                                     pane_filter.as_deref(),
-                                    per_pane_limit,
+                                    per_pane_limit * pane_pages,
                                     &mut active_pane,
                                     &mut pane_scroll_offset,
                                     prev_agent,
                                     prev_path,
                                     MAX_VISIBLE_PANES,
                                 );
+                                if !appended {
+                                    load_more_at =
+                                        panes.get(active_pane).map(|p| (active_pane, p.selected));
+                                }
                                 // Clear multi-selection when results change
                                 selected.clear();
                                 open_confirm_armed = false;
                                 // Start staggered reveal animation for new results (bead 013)
-                                if animations_enabled && !panes.is_empty() {
+                                if animations_enabled && !panes.is_empty() && !appended {
                                     reveal_anim_start = Some(Instant::now());
                                 }
                                 // Show a clean, user-friendly status
                                 let total_hits: usize = panes.iter().map(|p| p.total_count).sum();
-                                status = if total_hits == 0 {
+                                status = if appended {
+                                    format!("Loaded {fetched} more results")
+                                } else if total_hits == 0 {
                                     if pane_filter.as_ref().is_some_and(|s| !s.trim().is_empty()) {
                                         "No results match pane filter".to_string()
                                     } else {
@@ -6262,6 +8234,11 @@ pub fn run_tui(
                                 } else {
                                     format!("{} results across {} agents", total_hits, panes.len())
                                 };
+                                if total_hits > 0
+                                    && let Some(hint) = spelling_hint(&suggestions)
+                                {
+                                    status = format!("{status} · {hint}");
+                                }
                                 // Query history is now saved only on explicit commit actions
                                 // (Enter on result, F8 editor, y copy) via save_query_to_history()
                                 history_cursor = None;
@@ -6269,9 +8246,12 @@ pub fn run_tui(
                             }
                         }
                         Err(err) => {
-                            dirty_since = None;
+                            appending = false;
+                            more_results = false;
+                            total_matches = None;
                             status = "Search error (see footer).".to_string();
                             tracing::warn!("search error: {err}");
+                            toasts.push(Toast::error(format!("Search failed: {err}")));
                             results.clear();
                             panes.clear();
                             selected.clear();
@@ -6284,7 +8264,7 @@ pub fn run_tui(
                 }
             }
             // Advance spinner and redraw if search is pending
-            if dirty_since.is_some() {
+            if dirty_since.is_some() || search_pending {
                 spinner_frame = spinner_frame.wrapping_add(1);
                 needs_draw = true;
             }
@@ -6305,6 +8285,10 @@ pub fn run_tui(
                 if let Some(ref i) = info
                     && i.should_show()
                 {
+                    toasts.push(Toast::info(format!(
+                        "cass v{} is available (you have v{})",
+                        i.latest_version, i.current_version
+                    )));
                     needs_draw = true;
                 }
                 update_info = info;
@@ -6318,6 +8302,35 @@ pub fn run_tui(
                     last_indexing_state = Some(current_state);
                     needs_draw = true;
                 }
+                if let Some(err) = p.last_error.lock().ok().and_then(|mut e| e.take()) {
+                    toasts.push(Toast::error(format!("Indexing failed: {err}")));
+                    needs_draw = true;
+                }
+                // A finished pass may have changed what the results show: say so and
+                // search again, rather than leave them silently stale.
+                let passes = p.completed_passes.load(Ordering::Relaxed);
+                if passes != seen_index_passes {
+                    let first_pass = seen_index_passes == 0;
+                    seen_index_passes = passes;
+                    let added = current.saturating_sub(indexed_at_last_pass);
+                    indexed_at_last_pass = current;
+                    if added > 0 {
+                        toasts.push(Toast::success(format!(
+                            "Index updated: {added} session{}",
+                            if added == 1 { "" } else { "s" }
+                        )));
+                        dirty_since = Some(Instant::now());
+                    } else if first_pass {
+                        // Quiet watch cycles stay quiet; only the startup scan reports "nothing new".
+                        toasts.push(Toast::info("Index is up to date"));
+                    }
+                    needs_draw = true;
+                }
+            }
+            let shown_toasts = toasts.len();
+            toasts.tick();
+            if toasts.len() != shown_toasts {
+                needs_draw = true;
             }
             last_tick = Instant::now();
         }
@@ -6333,7 +8346,9 @@ pub fn run_tui(
             MatchMode::Prefix => "prefix".into(),
         }),
         context_window: Some(context_window.label().into()),
-        density_mode: Some(density_mode.label().into()),
+        // Only a density toggled away from the configured one is remembered, so a
+        // later change to `[tui] density` still takes effect.
+        density_mode: (density_mode != configured_density).then(|| density_mode.label().into()),
         // Mark that user has seen (or had opportunity to see) the help overlay
         has_seen_help: Some(true),
         // Persist query history for next session, deduplicating prefix pollution
@@ -6350,30 +8365,23 @@ pub fn run_tui(
                     workspaces: v.workspaces.iter().cloned().collect(),
                     created_from: v.created_from,
                     created_to: v.created_to,
-                    ranking: Some(match v.ranking {
-                        RankingMode::RecentHeavy => "recent".into(),
-                        RankingMode::RelevanceHeavy => "relevance".into(),
-                        RankingMode::MatchQualityHeavy => "quality".into(),
-                        RankingMode::DateNewest => "newest".into(),
-                        RankingMode::DateOldest => "oldest".into(),
-                        RankingMode::Balanced => "balanced".into(),
-                    }),
+                    ranking: Some(v.ranking.label().into()),
+                    sort: Some(v.sort.label().into()),
                 })
                 .collect(),
         ),
         // Persist pane count & ranking mode (bead 46t.1)
         per_pane_limit: Some(per_pane_limit),
-        ranking_mode: Some(match ranking_mode {
-            RankingMode::RecentHeavy => "recent".into(),
-            RankingMode::RelevanceHeavy => "relevance".into(),
-            RankingMode::MatchQualityHeavy => "quality".into(),
-            RankingMode::DateNewest => "newest".into(),
-            RankingMode::DateOldest => "oldest".into(),
-            RankingMode::Balanced => "balanced".into(),
-        }),
+        ranking_mode: Some(ranking_mode.label().into()),
+        sort_order: Some(sort_order.label().into()),
+        pane_layout: Some(pane_layout.label().to_lowercase()),
     };
     save_state(&state_path, &persisted_out);
 
+    if inline_height.is_some() {
+        // Erase the viewport so the prompt comes back where cass was started.
+        terminal.clear().ok();
+    }
     teardown_terminal()
 }
 
@@ -6394,7 +8402,10 @@ fn run_tui_headless(data_dir_override: Option<std::path::PathBuf>) -> Result<()>
 fn teardown_terminal() -> Result<()> {
     let mut stdout = io::stdout();
     disable_raw_mode()?;
-    execute!(stdout, LeaveAlternateScreen, DisableMouseCapture)?;
+    if !INLINE_VIEWPORT.load(Ordering::Relaxed) {
+        execute!(stdout, LeaveAlternateScreen)?;
+    }
+    execute!(stdout, DisableMouseCapture)?;
     Ok(())
 }
 
@@ -6426,9 +8437,12 @@ mod tests {
                 created_from: Some(1),
                 created_to: Some(2),
                 ranking: Some("recent".into()),
+                sort: Some("newest".into()),
             }]),
             per_pane_limit: Some(12),
             ranking_mode: Some("balanced".into()),
+            sort_order: Some("oldest".into()),
+            pane_layout: Some("preview".into()),
         };
         save_state(&path, &state);
 
@@ -6444,6 +8458,8 @@ mod tests {
         // Verify new fields (bead 46t.1)
         assert_eq!(loaded.per_pane_limit, Some(12));
         assert_eq!(loaded.ranking_mode.as_deref(), Some("balanced"));
+        assert_eq!(loaded.sort_order.as_deref(), Some("oldest"));
+        assert_eq!(loaded.pane_layout.as_deref(), Some("preview"));
     }
 
     #[test]
@@ -6460,6 +8476,17 @@ mod tests {
         assert!(!empty_q.is_empty());
     }
 
+    #[test]
+    fn contextual_snippet_centers_on_the_densest_match() {
+        let filler = "lorem ipsum ".repeat(40);
+        let text =
+            format!("token once. {filler}token refresh fails when the token expires. {filler}");
+        let out = contextual_snippet(&text, "token refresh", ContextWindow::Small);
+        assert!(out.starts_with('…') && out.ends_with('…'));
+        assert!(out.contains("token refresh fails when the token expires"));
+        assert!(!out.contains("token once"));
+    }
+
     /// Test `count_query_matches` for sux.6.6c
     #[test]
     fn count_query_matches_works() {
@@ -6559,6 +8586,7 @@ mod tests {
             convo,
             messages: vec![message],
             workspace: None,
+            notes: Vec::new(),
         };
 
         let lines = render_parsed_content(&detail, "", palette);
@@ -6576,6 +8604,154 @@ mod tests {
         );
     }
 
+    #[test]
+    fn role_filter_cycles_all_user_assistant_tool() {
+        let mut roles = HashSet::new();
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            roles = next_role_filter(&roles);
+            seen.push(role_filter_label(&roles));
+        }
+        assert_eq!(
+            seen,
+            [
+                Some("user".to_string()),
+                Some("assistant".to_string()),
+                Some("tool".to_string()),
+                None
+            ]
+        );
+        let mixed = HashSet::from(["user".to_string(), "system".to_string()]);
+        assert_eq!(
+            next_role_filter(&mixed),
+            HashSet::from(["user".to_string()])
+        );
+    }
+
+    #[test]
+    fn editor_commands_jump_to_the_hit_line() {
+        let args = |cmd: StdCommand| {
+            std::iter::once(cmd.get_program().to_string_lossy().to_string())
+                .chain(cmd.get_args().map(|a| a.to_string_lossy().to_string()))
+                .collect::<Vec<_>>()
+        };
+        let vim = editor_command("nvim", "+", "/s/a.jsonl", Some(12)).unwrap();
+        assert_eq!(args(vim), ["nvim", "+12", "/s/a.jsonl"]);
+        let code = editor_command("/usr/bin/code --wait", "+", "/s/a.jsonl", Some(3)).unwrap();
+        assert_eq!(
+            args(code),
+            ["/usr/bin/code", "--wait", "--goto", "/s/a.jsonl:3"]
+        );
+        let no_line = editor_command("hx", "+", "/s/a.jsonl", None).unwrap();
+        assert_eq!(args(no_line), ["hx", "/s/a.jsonl"]);
+        assert!(editor_command("  ", "+", "/s/a.jsonl", None).is_none());
+    }
+
+    #[test]
+    fn split_layout_puts_the_preview_beside_results_when_wide() {
+        let wide = Rect::new(0, 3, 160, 40);
+        let (results, detail) = split_main_area(wide, PaneLayout::Split, FocusRegion::Results);
+        assert_eq!((results.y, detail.y), (3, 3));
+        assert_eq!(detail.x, results.width);
+        assert_eq!(results.width + detail.width, 160);
+        assert!(results.width > detail.width);
+        let (results, detail) = split_main_area(wide, PaneLayout::Split, FocusRegion::Detail);
+        assert!(detail.width > results.width);
+
+        let narrow = Rect::new(0, 3, 80, 40);
+        let (results, detail) = split_main_area(narrow, PaneLayout::Split, FocusRegion::Results);
+        assert_eq!((results.width, detail.width), (80, 80));
+        assert_eq!(detail.y, 3 + results.height);
+
+        let (results, detail) = split_main_area(wide, PaneLayout::Preview, FocusRegion::Results);
+        assert!(results.is_empty());
+        assert_eq!(detail, wide);
+        let (results, detail) = split_main_area(wide, PaneLayout::Results, FocusRegion::Detail);
+        assert_eq!(results, wide);
+        assert!(detail.is_empty());
+        assert_eq!(PaneLayout::Preview.next(), PaneLayout::Split);
+    }
+
+    #[test]
+    fn detail_diffs_color_added_and_removed_lines() {
+        let palette = ThemePalette::dark();
+        let removed = crate::ui::components::theme::colors::STATUS_ERROR;
+        fn fg_of(lines: &[Line<'static>], text: &str) -> Option<Color> {
+            lines
+                .iter()
+                .find(|l| line_to_string(l) == format!("│ {text}"))
+                .and_then(|l| l.spans[1].style.fg)
+        }
+
+        let lines = parse_message_content(
+            "Applied:\ndiff --git a/x.rs b/x.rs\n--- a/x.rs\n+++ b/x.rs\n@@ -1,2 +1,2 @@\n fn f() {\n-    old()\n+    new()\nDone, tests pass.",
+            "",
+            palette,
+        );
+        assert_eq!(fg_of(&lines, "+    new()"), Some(palette.user));
+        assert_eq!(fg_of(&lines, "-    old()"), Some(removed));
+        assert_eq!(fg_of(&lines, "@@ -1,2 +1,2 @@"), Some(palette.accent_alt));
+        assert_eq!(fg_of(&lines, " fn f() {"), Some(palette.fg));
+        let texts: Vec<String> = lines.iter().map(line_to_string).collect();
+        let done = texts.iter().position(|t| t.contains("Done")).unwrap();
+        assert_eq!(
+            texts[done - 1],
+            "└──",
+            "the prose after the diff is not part of it"
+        );
+
+        let lines = parse_message_content(
+            "```diff\n-a\n+b\n```\n*** Begin Patch\n*** Update File: y.rs\n@@\n-c\n+d\n*** End Patch\n- a list item",
+            "",
+            palette,
+        );
+        assert_eq!(fg_of(&lines, "+b"), Some(palette.user));
+        assert_eq!(fg_of(&lines, "-c"), Some(removed));
+        assert!(
+            lines
+                .iter()
+                .any(|l| line_to_string(l).trim() == "• a list item")
+        );
+    }
+
+    #[test]
+    fn detail_code_blocks_are_highlighted_for_their_language() {
+        let palette = ThemePalette::dark();
+        let lines = parse_message_content(
+            "Try:\n```rust\nfn main() { let x = 1; } // done\n```\n```nonesuch\nfn main() {}\n```",
+            "main",
+            palette,
+        );
+        let code_rows: Vec<&Line> = lines
+            .iter()
+            .filter(|l| line_to_string(l).starts_with("│ "))
+            .collect();
+        assert_eq!(code_rows.len(), 2);
+        let colors = |line: &Line| {
+            line.spans[1..]
+                .iter()
+                .filter(|s| s.content != "main")
+                .filter_map(|s| s.style.fg)
+                .collect::<HashSet<_>>()
+        };
+        assert!(colors(code_rows[0]).len() > 2, "rust tokens get colors");
+        assert_eq!(colors(code_rows[1]), HashSet::from([palette.fg]));
+        for row in code_rows {
+            assert!(
+                row.spans[1..]
+                    .iter()
+                    .filter(|s| s.content != "main")
+                    .all(|s| s.style.bg == Some(palette.surface))
+            );
+            assert!(
+                row.spans
+                    .iter()
+                    .any(|s| s.content == "main" && s.style.add_modifier.contains(Modifier::BOLD)),
+                "query matches stay marked inside code"
+            );
+        }
+    }
+
     // ==========================================================================
     // Navigation State Tests (tst.ui.nav)
     // Tests for TUI navigation state machine behavior
@@ -6607,20 +8783,33 @@ mod tests {
     #[test]
     fn density_mode_cycles_through_all_options() {
         let mut mode = DensityMode::Compact;
-        assert_eq!(mode.lines_per_item(), 5); // 2 + 3 snippet lines
+        assert_eq!(mode.lines_per_item(), 2); // header + 1 snippet line
 
         mode = mode.next();
         assert_eq!(mode, DensityMode::Cozy);
-        assert_eq!(mode.lines_per_item(), 7); // 2 + 5 snippet lines
+        assert_eq!(mode.lines_per_item(), 4); // header + location + 2 snippet lines
 
         mode = mode.next();
         assert_eq!(mode, DensityMode::Spacious);
-        assert_eq!(mode.lines_per_item(), 10); // 2 + 8 snippet lines
+        assert_eq!(mode.lines_per_item(), 7); // header + location + 4 snippet lines + spacer
 
         // Wraps back to Compact
         mode = mode.next();
         assert_eq!(mode, DensityMode::Compact);
-        assert_eq!(mode.lines_per_item(), 5);
+        assert_eq!(mode.lines_per_item(), 2);
+    }
+
+    #[test]
+    fn denser_modes_fit_more_results() {
+        let limits: Vec<usize> = [
+            DensityMode::Compact,
+            DensityMode::Cozy,
+            DensityMode::Spacious,
+        ]
+        .into_iter()
+        .map(|mode| calculate_pane_limit(50, mode))
+        .collect();
+        assert!(limits[0] > limits[1] && limits[1] > limits[2], "{limits:?}");
     }
 
     #[test]
@@ -6631,10 +8820,8 @@ mod tests {
             RankingMode::Balanced,
             RankingMode::RelevanceHeavy,
             RankingMode::MatchQualityHeavy,
-            RankingMode::DateNewest,
-            RankingMode::DateOldest,
         ];
-        assert_eq!(modes.len(), 6, "should have 6 ranking modes");
+        assert_eq!(modes.len(), 4, "should have 4 ranking modes");
 
         // Test that they are all distinct
         for (i, a) in modes.iter().enumerate() {
@@ -6658,6 +8845,9 @@ mod tests {
             created_at: None,
             line_number: None,
             match_type: crate::search::query::MatchType::default(),
+            snippets: Vec::new(),
+            conversation_id: String::new(),
+            role: String::new(),
         }
     }
 
@@ -6689,6 +8879,253 @@ mod tests {
         assert_eq!(panes[0].total_count, 10, "should track total count");
     }
 
+    #[test]
+    fn build_agent_panes_keeps_each_conversation_together() {
+        let hits = vec![
+            make_hit("codex", "/a", 9.0, "snippet"),
+            make_hit("codex", "/b", 8.0, "snippet"),
+            make_hit("codex", "/a", 7.0, "snippet"),
+            make_hit("codex", "/c", 6.0, "snippet"),
+            make_hit("codex", "/b", 5.0, "snippet"),
+        ];
+
+        let panes = build_agent_panes(&hits, 10);
+        let order: Vec<(&str, f32)> = panes[0]
+            .hits
+            .iter()
+            .map(|h| (h.source_path.as_str(), h.score))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("/a", 9.0),
+                ("/a", 7.0),
+                ("/b", 8.0),
+                ("/b", 5.0),
+                ("/c", 6.0)
+            ]
+        );
+        assert_eq!(conversation_ranges(&panes[0].hits), vec![0..2, 2..4, 4..5]);
+    }
+
+    #[test]
+    fn pane_selection_skips_collapsed_conversations() {
+        let hits = vec![
+            make_hit("codex", "/a", 9.0, "snippet"),
+            make_hit("codex", "/a", 8.0, "snippet"),
+            make_hit("codex", "/a", 7.0, "snippet"),
+            make_hit("codex", "/b", 6.0, "snippet"),
+        ];
+        let mut pane = build_agent_panes(&hits, 10).remove(0);
+        let collapsed: HashSet<String> = ["/a".to_string()].into();
+
+        assert!(pane.step_selection(true, &collapsed));
+        assert_eq!(pane.selected, 3, "folded hits of /a are skipped");
+        assert!(!pane.step_selection(true, &collapsed));
+        assert!(pane.step_selection(false, &collapsed));
+        assert_eq!(pane.selected, 0, "back onto /a's header");
+
+        pane.selected = 2;
+        pane.reveal_selection(&collapsed);
+        assert_eq!(pane.selected, 0);
+    }
+
+    #[test]
+    fn hit_total_label_shows_fetched_share_of_total() {
+        assert_eq!(hit_total_label(120, Some(523), true), " 120/523 hits ");
+        assert_eq!(hit_total_label(40, Some(40), false), " 40 hits ");
+        assert_eq!(hit_total_label(120, None, true), " 120+ hits ");
+        assert_eq!(hit_total_label(7, None, false), " 7 hits ");
+    }
+
+    #[test]
+    fn freshness_label_shows_docs_age_profile_and_reindex_key() {
+        let three_minutes_ago = Utc::now().timestamp_millis() - 3 * 60 * 1000 - 500;
+        assert_eq!(
+            freshness_label(Some(1204), Some(three_minutes_ago), false, Some("work")),
+            "1204 docs · indexed 3m ago · profile work · Alt+r reindex"
+        );
+        assert_eq!(
+            freshness_label(None, None, false, None),
+            "never indexed · Alt+r reindex"
+        );
+        assert_eq!(
+            freshness_label(Some(3), Some(three_minutes_ago), true, None),
+            "3 docs · indexing… · Alt+r reindex"
+        );
+    }
+
+    #[test]
+    fn request_reindex_reports_missing_or_stopped_indexer() {
+        assert!(request_reindex(None).contains("cass index"));
+        let (tx, rx) = crossbeam_channel::unbounded();
+        assert_eq!(
+            request_reindex(Some(&tx)),
+            "Triggered background re-index..."
+        );
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(crate::indexer::IndexerEvent::Command(
+                crate::indexer::ReindexCommand::Full
+            ))
+        ));
+        drop(rx);
+        assert!(request_reindex(Some(&tx)).contains("Another cass index"));
+    }
+
+    #[test]
+    fn tail_poll_reports_messages_appended_to_the_conversation() {
+        use crate::model::types::{Agent, AgentKind, Conversation, Message};
+        use crate::storage::sqlite::SqliteStorage;
+
+        let tmp = TempDir::new().unwrap();
+        let mut storage = SqliteStorage::open(&tmp.path().join("tail.db")).unwrap();
+        let agent_id = storage
+            .ensure_agent(&Agent {
+                id: None,
+                slug: "codex".into(),
+                name: "Codex".into(),
+                version: None,
+                kind: AgentKind::Cli,
+            })
+            .unwrap();
+        let conversation = |count: i64| Conversation {
+            id: None,
+            agent_slug: "codex".into(),
+            workspace: None,
+            external_id: Some("s1".into()),
+            title: Some("Long run".into()),
+            source_path: "/logs/s1.jsonl".into(),
+            started_at: Some(1),
+            ended_at: None,
+            approx_tokens: None,
+            metadata_json: serde_json::json!({}),
+            messages: (0..count)
+                .map(|idx| Message {
+                    id: None,
+                    idx,
+                    role: MessageRole::Agent,
+                    author: None,
+                    created_at: Some(idx),
+                    content: format!("step {idx}"),
+                    extra_json: serde_json::json!({}),
+                    snippets: vec![],
+                })
+                .collect(),
+        };
+        storage
+            .insert_conversation_tree(agent_id, None, &conversation(2))
+            .unwrap();
+
+        let mut tail = TailState::new("/logs/s1.jsonl");
+        assert!(tail.due());
+        let (view, added) = tail.poll(&storage, None).expect("first poll loads");
+        assert_eq!((view.messages.len(), added), (2, 0));
+        assert!(tail.jump_to_end);
+        assert!(!tail.due());
+        tail.jump_to_end = false;
+        assert!(tail.poll(&storage, None).is_none());
+
+        storage
+            .insert_conversation_tree(agent_id, None, &conversation(5))
+            .unwrap();
+        let (view, added) = tail.poll(&storage, None).expect("appended messages");
+        assert_eq!((view.messages.len(), added), (5, 3));
+        assert!(tail.jump_to_end);
+    }
+
+    #[test]
+    fn toggle_tail_stops_on_the_tailed_conversation() {
+        let mut tail = None;
+        let hit = make_hit("codex", "/logs/a.jsonl", 1.0, "a");
+        assert!(toggle_tail(&mut tail, &hit).starts_with("Tailing"));
+        let other = make_hit("codex", "/logs/b.jsonl", 1.0, "b");
+        toggle_tail(&mut tail, &other);
+        assert_eq!(tail.as_ref().unwrap().source_path, "/logs/b.jsonl");
+        assert_eq!(toggle_tail(&mut tail, &other), "Stopped tailing");
+        assert!(tail.is_none());
+    }
+
+    #[test]
+    fn write_hyperlinks_links_the_cells_spelling_a_path() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 30, 2));
+        buf.set_string(2, 1, "[file] /s/a.jsonl", Style::default().fg(Color::Gray));
+        let links = vec![(
+            "[file] /s/a.jsonl".to_string(),
+            "file:///s/a.jsonl".to_string(),
+        )];
+        let mut out = Vec::new();
+        write_hyperlinks(&mut out, &buf, &links).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let start = out
+            .find("\x1b]8;;file:///s/a.jsonl\x1b\\")
+            .expect("link opened");
+        let end = out.find(crate::hyperlink::CLOSE).expect("link closed");
+        assert!(start < end);
+        // Row 1, column 2, 1-based.
+        assert!(out[..start].ends_with("\x1b[2;3H"));
+
+        let mut out = Vec::new();
+        let missing = vec![(
+            "[file] /s/b.jsonl".to_string(),
+            "file:///s/b.jsonl".to_string(),
+        )];
+        write_hyperlinks(&mut out, &buf, &missing).unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains("\x1b]8;;"));
+    }
+
+    #[test]
+    fn resume_command_for_hit_reads_the_session_id_from_the_conversation_key() {
+        let mut gemini = make_hit("gemini", "/g/tmp/abc/chats/session-1.json", 1.0, "");
+        gemini.conversation_id = "gemini:9f1c2d3e".into();
+        let cmd = resume_command_for_hit(&gemini).unwrap();
+        assert_eq!(cmd.args, ["--resume", "9f1c2d3e"]);
+
+        // Keyed by source path: no external id, so gemini cannot be resumed...
+        gemini.conversation_id = format!("gemini:{}", gemini.source_path);
+        assert!(resume_command_for_hit(&gemini).is_none());
+
+        // ...while claude takes the id from the file name.
+        let mut claude = make_hit("claude_code", "/c/projects/-app/0b6c.jsonl", 1.0, "");
+        claude.conversation_id = format!("claude_code:{}", claude.source_path);
+        let cmd = resume_command_for_hit(&claude).unwrap();
+        assert_eq!(cmd.shell_line(), "claude --resume 0b6c");
+    }
+
+    #[test]
+    fn adjacent_turns_find_prompt_before_and_reply_after() {
+        let messages: Vec<(usize, serde_json::Value)> = vec![
+            (
+                1,
+                serde_json::json!({"role": "user", "content": "why does  it\nfail?"}),
+            ),
+            (
+                2,
+                serde_json::json!({"role": "assistant", "content": "let me look"}),
+            ),
+            (
+                3,
+                serde_json::json!({"role": "tool", "content": "panic at main.rs:3"}),
+            ),
+            (
+                5,
+                serde_json::json!({"role": "assistant", "content": "an unwrap on None"}),
+            ),
+            (6, serde_json::json!({"role": "user", "content": "thanks"})),
+        ];
+
+        let turns = adjacent_turns(&messages, 3);
+        assert_eq!(turns.prompt.as_deref(), Some("why does it fail?"));
+        assert_eq!(turns.reply.as_deref(), Some("an unwrap on None"));
+
+        // A prompt hit has no separate prompt; the last message has no reply.
+        let turns = adjacent_turns(&messages, 1);
+        assert_eq!(turns.prompt, None);
+        assert_eq!(turns.reply.as_deref(), Some("let me look"));
+        assert_eq!(adjacent_turns(&messages, 6).reply, None);
+        assert_eq!(adjacent_turns(&messages, 9), AdjacentTurns::default());
+    }
+
     #[test]
     fn build_agent_panes_empty_input_returns_empty() {
         let panes = build_agent_panes(&[], 10);
@@ -6823,27 +9260,6 @@ mod tests {
         assert_ne!(standard, prefix);
     }
 
-    #[test]
-    fn agent_suggestions_returns_matching_agents() {
-        let suggestions = agent_suggestions("cl");
-        assert!(suggestions.contains(&"claude_code"));
-        assert!(suggestions.contains(&"cline"));
-        assert!(!suggestions.contains(&"codex"));
-    }
-
-    #[test]
-    fn agent_suggestions_case_insensitive() {
-        let suggestions = agent_suggestions("CL");
-        assert!(suggestions.contains(&"claude_code"));
-        assert!(suggestions.contains(&"cline"));
-    }
-
-    #[test]
-    fn agent_suggestions_empty_prefix_returns_all() {
-        let suggestions = agent_suggestions("");
-        assert_eq!(suggestions.len(), KNOWN_AGENTS.len());
-    }
-
     // ==========================================================================
     // UI State Persistence Tests (tst.ui.pers)
     // Tests for saving and restoring UI state across sessions
@@ -6889,6 +9305,7 @@ mod tests {
                     created_from: Some(1000),
                     created_to: Some(2000),
                     ranking: Some("recent".into()),
+                    sort: None,
                 },
                 SavedViewPersisted {
                     slot: 5,
@@ -6897,6 +9314,7 @@ mod tests {
                     created_from: None,
                     created_to: Some(5000),
                     ranking: Some("balanced".into()),
+                    sort: Some("oldest".into()),
                 },
             ]),
             ..Default::default()
@@ -6960,6 +9378,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn sort_order_cycles_and_reads_legacy_date_rankings() {
+        assert_eq!(SortOrder::Relevance.next(), SortOrder::Newest);
+        assert_eq!(SortOrder::Oldest.next(), SortOrder::Relevance);
+        for order in [SortOrder::Relevance, SortOrder::Newest, SortOrder::Oldest] {
+            assert_eq!(sort_from_str(order.label()), order);
+        }
+        // Newest/oldest were ranking modes before sort order had its own setting.
+        assert_eq!(sort_from_str("newest"), SortOrder::Newest);
+        assert_eq!(ranking_from_str("newest"), RankingMode::Balanced);
+        assert_eq!(sort_from_str("quality"), SortOrder::Relevance);
+    }
+
+    #[test]
+    fn profile_state_path_keeps_profiles_apart() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(
+            profile_state_path(dir.path(), None),
+            state_path_for(dir.path())
+        );
+        assert_eq!(
+            profile_state_path(dir.path(), Some(" ")),
+            state_path_for(dir.path())
+        );
+        assert_eq!(
+            profile_state_path(dir.path(), Some("work")),
+            dir.path().join("tui_state.work.json")
+        );
+        assert_eq!(
+            profile_state_path(dir.path(), Some("../x")),
+            dir.path().join("tui_state.___x.json")
+        );
+    }
+
     #[test]
     fn state_persistence_corrupted_state_file_uses_defaults() {
         let dir = TempDir::new().unwrap();
@@ -7805,4 +10257,51 @@ mod tests {
         let status = format!("Cleared {count} selections");
         assert_eq!(status, "Cleared 2 selections");
     }
+
+    #[test]
+    fn search_worker_replies_only_to_the_newest_search() {
+        use crate::connectors::{NormalizedConversation, NormalizedMessage};
+        use crate::search::tantivy::TantivyIndex;
+
+        let dir = TempDir::new().unwrap();
+        let mut index = TantivyIndex::open_or_create(dir.path()).unwrap();
+        let conv = NormalizedConversation {
+            agent_slug: "codex".into(),
+            external_id: None,
+            title: Some("worker convo".into()),
+            workspace: None,
+            source_path: dir.path().join("rollout-1.jsonl"),
+            started_at: Some(1_700_000_000_000),
+            ended_at: None,
+            metadata: json!({}),
+            messages: vec![NormalizedMessage {
+                idx: 0,
+                role: "user".into(),
+                author: None,
+                created_at: Some(1_700_000_000_000),
+                content: "alpha beta".into(),
+                extra: json!({}),
+                snippets: Vec::new(),
+            }],
+        };
+        index.add_conversation(&conv).unwrap();
+        index.commit().unwrap();
+        let client = SearchClient::open(dir.path(), None).unwrap().unwrap();
+        let worker = SearchWorker::spawn(client);
+
+        worker.submit("zzzz".into(), SearchFilters::default(), 10, 0, false);
+        worker.submit("alpha".into(), SearchFilters::default(), 10, 0, false);
+        let reply = loop {
+            if let Some(reply) = worker.poll() {
+                break reply;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        };
+        assert_eq!(reply.result.unwrap().hits.len(), 1);
+
+        worker.submit("alpha".into(), SearchFilters::default(), 10, 0, false);
+        worker.cancel();
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(worker.poll().is_none());
+    }
 }
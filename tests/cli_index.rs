@@ -281,3 +281,1566 @@ fn incremental_index_only_processes_new_sessions() {
         "Hit should be from codex connector"
     );
 }
+
+/// An edit that keeps an old mtime (rsync -t, cp -p) is still picked up, because
+/// incremental runs compare per-file checkpoints rather than a global timestamp.
+#[test]
+fn incremental_index_catches_edits_with_old_mtime() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+    make_codex_session(
+        &codex_home,
+        "2025/11/20",
+        "rollout-1.jsonl",
+        "first_content",
+    );
+    let file = codex_home.join("sessions/2025/11/20/rollout-1.jsonl");
+
+    let index = || {
+        let mut cmd = base_cmd(home);
+        cmd.env("CODEX_HOME", &codex_home);
+        cmd.args(["index", "--data-dir", data_dir.to_str().unwrap(), "--json"]);
+        cmd.assert().success();
+    };
+    index();
+    assert!(data_dir.join("file_checkpoints.json").exists());
+
+    let mut content = fs::read_to_string(&file).unwrap();
+    content.push_str(
+        "\n{\"type\": \"event_msg\", \"timestamp\": 1, \"payload\": {\"type\": \"user_message\", \"message\": \"backdated_followup\"}}",
+    );
+    fs::write(&file, content).unwrap();
+    let an_hour_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+    fs::File::options()
+        .write(true)
+        .open(&file)
+        .unwrap()
+        .set_modified(an_hour_ago)
+        .unwrap();
+    index();
+
+    let mut search = base_cmd(home);
+    search.env("CODEX_HOME", &codex_home);
+    search.args([
+        "search",
+        "backdated_followup",
+        "--robot",
+        "--data-dir",
+        data_dir.to_str().unwrap(),
+    ]);
+    let output = search.output().expect("search command");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    assert!(
+        !json["hits"].as_array().expect("hits array").is_empty(),
+        "backdated edit should be indexed: {json}"
+    );
+}
+
+/// Files that fail to parse are listed in quarantine.jsonl and counted in the
+/// robot output until a fixed version is indexed.
+#[test]
+fn malformed_sessions_are_quarantined_until_fixed() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+    make_codex_session(&codex_home, "2025/11/20", "rollout-1.jsonl", "good_content");
+    let broken = codex_home.join("sessions/2025/11/20/rollout-2.json");
+    fs::write(&broken, "{\"session\": {\"id\": \"x\"}, \"items\": [").unwrap();
+
+    let index = || -> serde_json::Value {
+        let mut cmd = base_cmd(home);
+        cmd.env("CODEX_HOME", &codex_home);
+        cmd.args(["index", "--data-dir", data_dir.to_str().unwrap(), "--json"]);
+        let output = cmd.output().expect("index command");
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).expect("valid json")
+    };
+    let json = index();
+    assert_eq!(json["quarantined"], 1, "{json}");
+    let listed = fs::read_to_string(data_dir.join("quarantine.jsonl")).unwrap();
+    let entry: serde_json::Value = serde_json::from_str(listed.trim()).unwrap();
+    assert_eq!(entry["agent"], "codex");
+    assert_eq!(entry["path"], broken.to_str().unwrap());
+    assert!(entry["error"].as_str().unwrap().contains("EOF"), "{entry}");
+
+    // Unchanged, the file stays quarantined without being rescanned.
+    assert_eq!(index()["quarantined"], 1);
+
+    fs::write(
+        &broken,
+        r#"{"session": {"id": "x"}, "items": [{"role": "user", "content": "repaired_content"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(index()["quarantined"], 0);
+    assert!(!data_dir.join("quarantine.jsonl").exists());
+}
+
+#[test]
+fn deleted_session_files_are_purged_from_the_index() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(&codex_home, "2025/11/20", "rollout-1.jsonl", "kept_content");
+    make_codex_session(
+        &codex_home,
+        "2025/11/20",
+        "rollout-2.jsonl",
+        "pruned_content",
+    );
+
+    let run = |args: &[&str]| -> serde_json::Value {
+        let output = base_cmd(home)
+            .args(args)
+            .args(["--data-dir", data_dir.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+    let hits = |query: &str| {
+        run(&["search", query, "--robot"])["hits"]
+            .as_array()
+            .unwrap()
+            .len()
+    };
+
+    let json = run(&["index", "--json"]);
+    assert_eq!(json["conversations"], 2, "{json}");
+    assert_eq!(json["purged"], 0, "{json}");
+    assert!(hits("pruned_content") > 0);
+
+    fs::remove_file(codex_home.join("sessions/2025/11/20/rollout-2.jsonl")).unwrap();
+    let json = run(&["index", "--json"]);
+    assert_eq!(json["purged"], 1, "{json}");
+    assert_eq!(json["conversations"], 1, "{json}");
+    assert_eq!(hits("pruned_content"), 0);
+    assert!(hits("kept_content") > 0);
+
+    assert_eq!(run(&["index", "--json"])["purged"], 0);
+}
+
+/// `cass stats --robot` reads document counts and segment info from the Tantivy index.
+#[test]
+fn stats_reports_index_documents() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(&codex_home, "2025/11/20", "rollout-1.jsonl", "first");
+    make_codex_session(&codex_home, "2025/11/21", "rollout-2.jsonl", "second");
+
+    let run = |args: &[&str]| -> serde_json::Value {
+        let output = base_cmd(home)
+            .args(args)
+            .args(["--data-dir", data_dir.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+    run(&["index", "--json"]);
+
+    let stats = run(&["stats", "--robot"]);
+    let index = &stats["index"];
+    assert_eq!(index["docs"], 4, "{stats}");
+    assert_eq!(index["by_agent"][0]["agent"], "codex", "{stats}");
+    assert_eq!(index["by_agent"][0]["docs"], 4, "{stats}");
+    assert!(index["segments"].as_u64().unwrap() >= 1, "{stats}");
+    assert!(index["size_bytes"].as_u64().unwrap() > 0, "{stats}");
+    assert!(index["tokens"].as_u64().unwrap() >= 4, "{stats}");
+    assert!(index["oldest_message"].is_string(), "{stats}");
+    assert!(index["newest_message"].as_str() >= index["oldest_message"].as_str());
+    assert!(stats["last_indexed_at"].is_string(), "{stats}");
+}
+
+/// Each incremental run commits a segment; `index optimize` merges them back into one.
+#[test]
+fn index_optimize_merges_segments() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    let run = |args: &[&str]| -> serde_json::Value {
+        let output = base_cmd(home)
+            .args(args)
+            .args(["--data-dir", data_dir.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    for (day, content) in [("20", "alpha"), ("21", "beta")] {
+        make_codex_session(
+            &codex_home,
+            &format!("2025/11/{day}"),
+            &format!("rollout-{day}.jsonl"),
+            content,
+        );
+        run(&["index", "--json"]);
+    }
+    let status = run(&["index", "--merge-status", "--json"]);
+    assert_eq!(status["segment_count"], 2, "{status}");
+    assert_eq!(status["should_merge"], false, "{status}");
+
+    let skipped = run(&["index", "optimize", "--json"]);
+    assert_eq!(skipped["merged"], false, "{skipped}");
+    assert_eq!(skipped["segments_after"], 2, "{skipped}");
+
+    let merged = run(&["index", "optimize", "--force", "--json"]);
+    assert_eq!(merged["merged"], true, "{merged}");
+    assert_eq!(merged["segments_before"], 2, "{merged}");
+    assert_eq!(merged["segments_after"], 1, "{merged}");
+    let hits = run(&["search", "beta", "--robot"]);
+    assert!(!hits["hits"].as_array().unwrap().is_empty(), "{hits}");
+}
+
+/// `cass doctor --index` spots a truncated segment and `--repair` re-adds only the
+/// documents it held, leaving the other segment in place.
+#[test]
+fn doctor_repairs_a_damaged_segment() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    let run = |args: &[&str]| {
+        base_cmd(home)
+            .args(args)
+            .args(["--data-dir", data_dir.to_str().unwrap()])
+            .output()
+            .unwrap()
+    };
+
+    for (day, content) in [("20", "alpha"), ("21", "beta")] {
+        make_codex_session(
+            &codex_home,
+            &format!("2025/11/{day}"),
+            &format!("rollout-{day}.jsonl"),
+            content,
+        );
+        assert!(run(&["index", "--json"]).status.success());
+    }
+    let healthy = run(&["doctor", "--index", "--json"]);
+    assert!(healthy.status.success(), "{healthy:?}");
+
+    let index_dir = data_dir.join("index/v4");
+    let store = fs::read_dir(&index_dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .find(|p| p.extension().is_some_and(|e| e == "store"))
+        .unwrap();
+    fs::OpenOptions::new()
+        .write(true)
+        .open(&store)
+        .unwrap()
+        .set_len(10)
+        .unwrap();
+
+    let damaged = run(&["doctor", "--index", "--json"]);
+    assert_eq!(damaged.status.code(), Some(1), "{damaged:?}");
+    let report: serde_json::Value = serde_json::from_slice(&damaged.stdout).unwrap();
+    assert_eq!(
+        report["index"]["problems"][0]["kind"], "damaged_segment",
+        "{report}"
+    );
+
+    let repaired = run(&["doctor", "--index", "--repair", "--json"]);
+    assert!(repaired.status.success(), "{repaired:?}");
+    let report: serde_json::Value = serde_json::from_slice(&repaired.stdout).unwrap();
+    assert_eq!(report["index"]["repair"]["rebuilt"], false, "{report}");
+    assert_eq!(report["index"]["repair"]["dropped_segments"], 1, "{report}");
+    assert_eq!(
+        report["index"]["repair"]["reindexed_sources"], 1,
+        "{report}"
+    );
+    assert!(report["index"]["remaining"].as_array().unwrap().is_empty());
+
+    for term in ["alpha", "beta"] {
+        let output = run(&["search", term, "--robot"]);
+        let hits: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert!(
+            !hits["hits"].as_array().unwrap().is_empty(),
+            "{term}: {hits}"
+        );
+    }
+}
+
+/// While another process writes the index, `cass index` fails with `index-locked`
+/// (or waits with `--wait`) and searches keep working read-only.
+#[test]
+fn locked_index_reports_a_structured_error_and_waits() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(
+        &codex_home,
+        "2025/11/20",
+        "rollout-1.jsonl",
+        "lockedcontent",
+    );
+    let run = |args: &[&str]| {
+        base_cmd(home)
+            .args(args)
+            .args(["--data-dir", data_dir.to_str().unwrap()])
+            .output()
+            .unwrap()
+    };
+    assert!(run(&["index", "--json"]).status.success());
+
+    let index_path = coding_agent_search::search::tantivy::index_path(&data_dir);
+    let writer =
+        coding_agent_search::search::tantivy::TantivyIndex::open_or_create(&index_path).unwrap();
+
+    let locked = run(&["index", "--json"]);
+    assert_eq!(locked.status.code(), Some(7), "{locked:?}");
+    assert!(String::from_utf8_lossy(&locked.stderr).contains("index-locked"));
+    let timed_out = run(&["index", "optimize", "--wait", "1", "--json"]);
+    assert_eq!(timed_out.status.code(), Some(7), "{timed_out:?}");
+
+    let search = run(&["search", "lockedcontent", "--robot"]);
+    assert!(search.status.success(), "{search:?}");
+    let hits: serde_json::Value = serde_json::from_slice(&search.stdout).unwrap();
+    assert!(!hits["hits"].as_array().unwrap().is_empty(), "{hits}");
+    assert!(run(&["index", "--merge-status", "--json"]).status.success());
+
+    let release = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        drop(writer);
+    });
+    let waited = run(&["index", "--wait", "30", "--json"]);
+    release.join().unwrap();
+    assert!(waited.status.success(), "{waited:?}");
+}
+
+/// An index from an older schema is rebuilt from the database, so history whose
+/// session files are gone (here, the whole agent dir) stays searchable.
+#[test]
+fn outdated_index_is_rebuilt_from_the_database() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(
+        &codex_home,
+        "2025/11/20",
+        "rollout-1.jsonl",
+        "archived_content",
+    );
+    let run = |args: &[&str]| -> serde_json::Value {
+        let output = base_cmd(home)
+            .args(args)
+            .args(["--data-dir", data_dir.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+    let hits = |query: &str| {
+        run(&["search", query, "--robot"])["hits"]
+            .as_array()
+            .unwrap()
+            .len()
+    };
+
+    assert_eq!(run(&["index", "--json"])["restored"], 0);
+    fs::remove_dir_all(&codex_home).unwrap();
+    fs::write(
+        data_dir.join("index/v4/schema_hash.json"),
+        r#"{"schema_hash":"tantivy-schema-v1"}"#,
+    )
+    .unwrap();
+
+    let json = run(&["index", "--json"]);
+    assert_eq!(json["restored"], 1, "{json}");
+    assert_eq!(json["conversations"], 1, "{json}");
+    assert!(hits("archived_content") > 0);
+    assert_eq!(run(&["index", "--json"])["restored"], 0);
+}
+
+/// With `[index] store_raw_json`, `cass export` reproduces a session's original lines
+/// from the index after the agent deleted its file.
+#[test]
+fn export_falls_back_to_raw_json_in_the_index() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(&codex_home, "2025/11/20", "rollout-1.jsonl", "raw_content");
+    let session = codex_home.join("sessions/2025/11/20/rollout-1.jsonl");
+    let original: Vec<serde_json::Value> = fs::read_to_string(&session)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    let config = home.join(".config/cass/config.toml");
+    fs::create_dir_all(config.parent().unwrap()).unwrap();
+    fs::write(&config, "[index]\nstore_raw_json = true\n").unwrap();
+
+    let data_dir_arg = data_dir.to_str().unwrap();
+    base_cmd(home)
+        .args(["index", "--json", "--data-dir", data_dir_arg])
+        .assert()
+        .success();
+    fs::remove_dir_all(&codex_home).unwrap();
+
+    let output = base_cmd(home)
+        .args(["export", session.to_str().unwrap(), "--format", "json"])
+        .args(["--data-dir", data_dir_arg])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let exported: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(exported, original);
+}
+
+/// `--progress json` turns `cass index` output into NDJSON events ending in a `done` report.
+#[test]
+fn index_progress_json_emits_ndjson_events() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(&codex_home, "2025/11/20", "rollout-1.jsonl", "evented");
+    make_codex_session(
+        &codex_home,
+        "2025/11/21",
+        "rollout-2.jsonl",
+        "evented_again",
+    );
+
+    let output = base_cmd(home)
+        .args(["index", "--robot", "--progress", "json"])
+        .args(["--data-dir", data_dir.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let events: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("every line is one JSON event"))
+        .collect();
+    let names: Vec<&str> = events
+        .iter()
+        .map(|e| e["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(names.first(), Some(&"index_start"));
+    assert!(
+        events
+            .iter()
+            .any(|e| e["event"] == "connector_start" && e["agent"] == "codex")
+    );
+    let done = events
+        .iter()
+        .find(|e| e["event"] == "connector_done" && e["agent"] == "codex")
+        .unwrap();
+    assert_eq!(done["conversations"], 2);
+    let ingested: u64 = events
+        .iter()
+        .filter(|e| e["event"] == "ingest")
+        .map(|e| e["messages"].as_u64().unwrap())
+        .sum();
+    assert_eq!(ingested, 4);
+    assert!(names.contains(&"commit"));
+    let report = events.last().unwrap();
+    assert_eq!(report["event"], "done");
+    assert_eq!(report["success"], true);
+    assert_eq!(report["conversations"], 2);
+}
+
+/// `search --robot --stream` prints one JSON line per hit, widening the search in rounds,
+/// and ends with a `_summary` record.
+#[test]
+fn search_stream_emits_ndjson_hits_and_summary() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    for i in 0..15 {
+        make_codex_session(
+            &codex_home,
+            "2025/11/20",
+            &format!("rollout-{i}.jsonl"),
+            &format!("streamed topic{i}"),
+        );
+    }
+    let data_dir_arg = data_dir.to_str().unwrap();
+    base_cmd(home)
+        .args(["index", "--json", "--data-dir", data_dir_arg])
+        .assert()
+        .success();
+
+    let output = base_cmd(home)
+        .args(["search", "streamed", "--robot", "--stream", "--limit", "12"])
+        .args(["--data-dir", data_dir_arg])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let lines: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("every line is JSON"))
+        .collect();
+    let (summary, hits) = lines.split_last().unwrap();
+    assert_eq!(hits.len(), 12);
+    let distinct: std::collections::HashSet<_> = hits
+        .iter()
+        .map(|h| (h["source_path"].to_string(), h["line_number"].to_string()))
+        .collect();
+    assert_eq!(distinct.len(), 12, "no hit is printed twice across rounds");
+    assert_eq!(summary["_summary"]["count"], 12);
+    assert_eq!(summary["_summary"]["has_more"], true);
+    assert_eq!(summary["_summary"]["rounds"], 2);
+
+    base_cmd(home)
+        .args(["search", "streamed", "--stream", "--aggregate", "agent"])
+        .assert()
+        .code(2);
+}
+
+/// `cass watch` indexes a session file written after it started, without a manual run.
+#[test]
+fn watch_indexes_new_sessions_as_they_appear() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(&codex_home, "2025/11/20", "rollout-1.jsonl", "before_watch");
+    let data_dir_arg = data_dir.to_str().unwrap();
+
+    let mut watcher = std::process::Command::new(assert_cmd::cargo::cargo_bin!("cass"))
+        .args(["watch", "--data-dir", data_dir_arg])
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .env("HOME", home)
+        .env("XDG_DATA_HOME", home.join(".local/share"))
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("CODEX_HOME", &codex_home)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let hits = |query: &str| -> u64 {
+        let output = base_cmd(home)
+            .args(["search", query, "--robot", "--data-dir", data_dir_arg])
+            .output()
+            .unwrap();
+        serde_json::from_slice::<serde_json::Value>(&output.stdout)
+            .map(|json| json["count"].as_u64().unwrap_or(0))
+            .unwrap_or(0)
+    };
+    let wait_for = |query: &str| {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+        while std::time::Instant::now() < deadline {
+            if hits(query) > 0 {
+                return true;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(250));
+        }
+        false
+    };
+
+    let caught_up = wait_for("before_watch");
+    make_codex_session(&codex_home, "2025/11/21", "rollout-2.jsonl", "during_watch");
+    let picked_up = caught_up && wait_for("during_watch");
+    watcher.kill().unwrap();
+    watcher.wait().unwrap();
+    assert!(caught_up, "watch should index existing sessions first");
+    assert!(
+        picked_up,
+        "watch should index a session written while it runs"
+    );
+}
+
+/// `cass list` shows every indexed conversation, newest activity first, with filters.
+#[test]
+fn list_shows_sessions_by_recent_activity() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(&codex_home, "2025/11/20", "rollout-1.jsonl", "older_topic");
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    make_codex_session(&codex_home, "2025/11/21", "rollout-2.jsonl", "newer_topic");
+    let data_dir_arg = data_dir.to_str().unwrap();
+    base_cmd(home)
+        .args(["index", "--json", "--data-dir", data_dir_arg])
+        .assert()
+        .success();
+
+    let list = |extra: &[&str]| -> serde_json::Value {
+        let output = base_cmd(home)
+            .args(["list", "--robot", "--data-dir", data_dir_arg])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    let all = list(&[]);
+    assert_eq!(all["count"], 2);
+    let sessions = all["sessions"].as_array().unwrap();
+    assert!(
+        sessions[0]["source_path"]
+            .as_str()
+            .unwrap()
+            .ends_with("rollout-2.jsonl")
+    );
+    assert_eq!(sessions[0]["agent"], "codex");
+    assert_eq!(sessions[0]["message_count"], 2);
+    assert!(sessions[0]["last_activity"].as_i64().is_some());
+
+    assert_eq!(list(&["--limit", "1"])["count"], 1);
+    assert_eq!(list(&["--since", "7d"])["count"], 2);
+    assert_eq!(list(&["--until", "2020-01-01"])["count"], 0);
+    assert_eq!(list(&["--agent", "claude_code"])["count"], 0);
+}
+
+/// `cass tag` labels a session, and `search --tag` keeps only sessions carrying the tag.
+#[test]
+fn tags_filter_search_to_tagged_sessions() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(&codex_home, "2025/11/20", "rollout-1.jsonl", "auth_fix");
+    make_codex_session(&codex_home, "2025/11/21", "rollout-2.jsonl", "ui_tweak");
+    let data_dir_arg = data_dir.to_str().unwrap();
+    base_cmd(home)
+        .args(["index", "--json", "--data-dir", data_dir_arg])
+        .assert()
+        .success();
+    let session = codex_home.join("sessions/2025/11/20/rollout-1.jsonl");
+    let session_arg = session.to_str().unwrap();
+
+    let tag = |args: &[&str]| -> serde_json::Value {
+        let output = base_cmd(home)
+            .arg("tag")
+            .args(args)
+            .args(["--json", "--data-dir", data_dir_arg])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+    let added = tag(&["add", session_arg, "Bugfix", "auth"]);
+    assert_eq!(
+        added["sessions"][0]["tags"],
+        serde_json::json!(["auth", "bugfix"])
+    );
+    assert_eq!(tag(&["ls"])["count"], 1);
+
+    let search = |tag: &str| -> Vec<String> {
+        let output = base_cmd(home)
+            .args(["search", "response", "--robot", "--tag", tag])
+            .args(["--data-dir", data_dir_arg])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["hits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|h| h["source_path"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let hits = search("bugfix");
+    assert!(!hits.is_empty());
+    assert!(
+        hits.iter().all(|p| p.ends_with("rollout-1.jsonl")),
+        "{hits:?}"
+    );
+    assert!(search("unused").is_empty());
+
+    let removed = tag(&["rm", session_arg, "bugfix"]);
+    assert_eq!(removed["sessions"][0]["tags"], serde_json::json!(["auth"]));
+    assert!(search("bugfix").is_empty());
+
+    base_cmd(home)
+        .args(["tag", "add", "/no/such/session.jsonl", "x"])
+        .args(["--data-dir", data_dir_arg])
+        .assert()
+        .code(4);
+}
+
+/// `cass bookmark` stars a message, and `search --bookmarked` keeps only starred sessions.
+#[test]
+fn bookmarks_star_messages_and_filter_search() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(
+        &codex_home,
+        "2025/11/20",
+        "rollout-1.jsonl",
+        "golden_session",
+    );
+    make_codex_session(
+        &codex_home,
+        "2025/11/21",
+        "rollout-2.jsonl",
+        "other_session",
+    );
+    let data_dir_arg = data_dir.to_str().unwrap();
+    base_cmd(home)
+        .args(["index", "--json", "--data-dir", data_dir_arg])
+        .assert()
+        .success();
+    let session = codex_home.join("sessions/2025/11/20/rollout-1.jsonl");
+    let session_arg = session.to_str().unwrap();
+
+    let bookmark = |args: &[&str]| -> serde_json::Value {
+        let output = base_cmd(home)
+            .arg("bookmark")
+            .args(args)
+            .args(["--json", "--data-dir", data_dir_arg])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+    let added = bookmark(&["add", session_arg, "--line", "2", "--note", "the fix"]);
+    let mark = &added["bookmarks"][0];
+    assert_eq!(mark["line_number"], 2);
+    assert_eq!(mark["agent"], "codex");
+    assert!(
+        mark["snippet"]
+            .as_str()
+            .unwrap()
+            .contains("golden_session_response")
+    );
+    let listed = bookmark(&["ls"]);
+    assert_eq!(listed["count"], 1);
+    assert_eq!(listed["bookmarks"][0]["note"], "the fix");
+
+    let search = || -> Vec<String> {
+        let output = base_cmd(home)
+            .args(["search", "session", "--robot", "--bookmarked"])
+            .args(["--data-dir", data_dir_arg])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["hits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|h| h["source_path"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let hits = search();
+    assert!(!hits.is_empty());
+    assert!(
+        hits.iter().all(|p| p.ends_with("rollout-1.jsonl")),
+        "{hits:?}"
+    );
+
+    assert_eq!(
+        bookmark(&["rm", session_arg, "--line", "2"])["removed"],
+        true
+    );
+    assert!(search().is_empty());
+    base_cmd(home)
+        .args(["bookmark", "rm", session_arg, "--line", "2"])
+        .args(["--data-dir", data_dir_arg])
+        .assert()
+        .code(4);
+}
+
+/// `cass note` annotations are searchable, survive a full re-index and can be removed.
+#[test]
+fn notes_are_searchable_and_survive_full_reindex() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(&codex_home, "2025/11/20", "rollout-1.jsonl", "flaky_test");
+    let data_dir_arg = data_dir.to_str().unwrap();
+    let index = |extra: &[&str]| {
+        base_cmd(home)
+            .args(["index", "--json", "--data-dir", data_dir_arg])
+            .args(extra)
+            .assert()
+            .success();
+    };
+    index(&[]);
+    let session = codex_home.join("sessions/2025/11/20/rollout-1.jsonl");
+    let session_arg = session.to_str().unwrap();
+
+    let output = base_cmd(home)
+        .args(["note", "add", session_arg, "1", "kumquat retry was the fix"])
+        .args(["--json", "--data-dir", data_dir_arg])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let added: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(added["notes"][0]["id"], 1);
+    assert_eq!(added["indexed"], true);
+
+    let search = || -> Vec<serde_json::Value> {
+        let output = base_cmd(home)
+            .args(["search", "kumquat", "--robot", "--data-dir", data_dir_arg])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["hits"].as_array().unwrap().clone()
+    };
+    let hits = search();
+    assert_eq!(hits.len(), 1, "{hits:?}");
+    assert_eq!(hits[0]["role"], "note");
+    assert_eq!(hits[0]["line_number"], 2);
+    assert_eq!(hits[0]["agent"], "codex");
+
+    index(&["--full"]);
+    assert_eq!(search().len(), 1);
+
+    base_cmd(home)
+        .args(["note", "add", session_arg, "99", "nowhere"])
+        .args(["--data-dir", data_dir_arg])
+        .assert()
+        .code(4);
+    base_cmd(home)
+        .args(["note", "rm", "--id", "1", "--data-dir", data_dir_arg])
+        .assert()
+        .success();
+    assert!(search().is_empty());
+}
+
+/// `cass grep` reads session files directly, so it finds sessions never indexed.
+#[test]
+fn grep_scans_sessions_without_an_index() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(
+        &codex_home,
+        "2025/11/20",
+        "rollout-1.jsonl",
+        "Panic_at_startup",
+    );
+    make_codex_session(&codex_home, "2025/11/21", "rollout-2.jsonl", "quiet_day");
+    let data_dir_arg = data_dir.to_str().unwrap();
+
+    let grep = |args: &[&str]| -> Vec<serde_json::Value> {
+        let output = base_cmd(home)
+            .arg("grep")
+            .args(args)
+            .args(["--robot", "--agent", "codex", "--data-dir", data_dir_arg])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    };
+
+    let lines = grep(&["-i", "panic_at"]);
+    assert_eq!(lines.len(), 3, "{lines:?}");
+    assert_eq!(lines[0]["agent"], "codex");
+    assert!(
+        lines[0]["source_path"]
+            .as_str()
+            .unwrap()
+            .ends_with("rollout-1.jsonl")
+    );
+    let summary = &lines[2]["_summary"];
+    assert_eq!(summary["count"], 2);
+    assert_eq!(summary["sessions_scanned"], 2);
+    assert!(!data_dir.join("agent_search.db").exists());
+
+    assert_eq!(grep(&["panic_at"])[0]["_summary"]["count"], 0);
+    assert_eq!(grep(&["-F", "_day_("])[0]["_summary"]["count"], 0);
+    let limited = grep(&["_", "--limit", "1"]);
+    assert_eq!(limited.len(), 2);
+    assert_eq!(limited[1]["_summary"]["limit_reached"], true);
+
+    base_cmd(home)
+        .args(["grep", "(", "--data-dir", data_dir_arg])
+        .assert()
+        .code(2);
+}
+
+/// `cass timeline` lists indexed sessions by day with their agent, span and message count.
+#[test]
+fn timeline_groups_sessions_by_day() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(&codex_home, "2025/11/20", "rollout-1.jsonl", "incident_a");
+    // A second session whose prompts are 90 minutes apart.
+    let start = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+        - 2 * 3_600_000;
+    fs::write(
+        codex_home.join("sessions/2025/11/20/rollout-2.jsonl"),
+        format!(
+            r#"{{"type": "event_msg", "timestamp": {start}, "payload": {{"type": "user_message", "message": "incident_b"}}}}
+{{"type": "event_msg", "timestamp": {}, "payload": {{"type": "user_message", "message": "still failing"}}}}"#,
+            start + 5_400_000
+        ),
+    )
+    .unwrap();
+    let data_dir_arg = data_dir.to_str().unwrap();
+    base_cmd(home)
+        .args(["index", "--json", "--data-dir", data_dir_arg])
+        .assert()
+        .success();
+
+    let timeline = |range: &[&str]| -> serde_json::Value {
+        let output = base_cmd(home)
+            .args(["timeline", "--robot", "--data-dir", data_dir_arg])
+            .args(range)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    let json = timeline(&["--since", "1d"]);
+    assert_eq!(json["total_sessions"], 2);
+    // Grouped by local day (one group, or two just after midnight), oldest first.
+    let groups = json["groups"].as_array().unwrap();
+    assert!(groups[0]["key"].as_str().unwrap().len() == "2025-11-20".len());
+    let sessions: Vec<&serde_json::Value> = groups
+        .iter()
+        .flat_map(|g| g["sessions"].as_array().unwrap())
+        .collect();
+    assert_eq!(sessions[0]["agent"], "codex");
+    assert_eq!(sessions[0]["title"], "incident_b");
+    assert_eq!(sessions[0]["message_count"], 2);
+    assert_eq!(sessions[0]["duration_seconds"], 5400);
+    assert_eq!(sessions[1]["title"], "incident_a");
+
+    assert_eq!(timeline(&["--until", "2d"])["total_sessions"], 0);
+    assert_eq!(
+        timeline(&["--since", "1d", "--agent", "claude_code"])["total_sessions"],
+        0
+    );
+}
+
+/// `cass export --query` renders the best-matching conversations, one file each with `-o`.
+#[test]
+fn export_by_query_writes_matching_conversations() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(
+        &codex_home,
+        "2025/11/20",
+        "rollout-1.jsonl",
+        "kiwi_migration",
+    );
+    make_codex_session(
+        &codex_home,
+        "2025/11/21",
+        "rollout-2.jsonl",
+        "mango_rollback",
+    );
+    let data_dir_arg = data_dir.to_str().unwrap();
+    base_cmd(home)
+        .args(["index", "--json", "--data-dir", data_dir_arg])
+        .assert()
+        .success();
+
+    let output = base_cmd(home)
+        .args(["export", "--query", "kiwi_migration", "--format", "md"])
+        .args(["--data-dir", data_dir_arg])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let markdown = String::from_utf8_lossy(&output.stdout);
+    assert!(markdown.contains("kiwi_migration_response"), "{markdown}");
+    assert!(!markdown.contains("mango_rollback"), "{markdown}");
+    assert!(
+        markdown.contains(" UTC*"),
+        "messages carry timestamps: {markdown}"
+    );
+
+    let out_dir = home.join("exports");
+    base_cmd(home)
+        .args(["export", "--query", "response", "--limit", "2"])
+        .args(["--format", "json", "-o", out_dir.to_str().unwrap()])
+        .args(["--data-dir", data_dir_arg])
+        .assert()
+        .success();
+    assert!(out_dir.join("rollout-1.json").exists());
+    assert!(out_dir.join("rollout-2.json").exists());
+
+    base_cmd(home)
+        .args([
+            "export",
+            "--query",
+            "nonexistentzzz",
+            "--data-dir",
+            data_dir_arg,
+        ])
+        .assert()
+        .code(4);
+}
+
+/// `--dry-run` reports per-connector counts and skipped paths without writing anything.
+#[test]
+fn index_dry_run_reports_without_writing() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(&codex_home, "2025/11/20", "rollout-1.jsonl", "dry_content");
+    let file = codex_home.join("sessions/2025/11/20/rollout-1.jsonl");
+
+    let dry_run = || -> serde_json::Value {
+        let mut cmd = base_cmd(home);
+        cmd.args([
+            "index",
+            "--dry-run",
+            "--robot",
+            "--data-dir",
+            data_dir.to_str().unwrap(),
+        ]);
+        let output = cmd.output().expect("index --dry-run");
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice(&output.stdout).expect("valid json")
+    };
+    let codex = |json: &serde_json::Value| {
+        json["connectors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["connector"] == "codex")
+            .cloned()
+            .unwrap()
+    };
+
+    let json = dry_run();
+    assert_eq!(json["dry_run"], true);
+    let report = codex(&json);
+    assert_eq!(report["detected"], true);
+    assert_eq!(report["files"], 1);
+    assert_eq!(report["conversations"], 1);
+    assert_eq!(report["messages"], 2);
+    assert!(!data_dir.join("agent_search.db").exists());
+    assert!(!data_dir.join("file_checkpoints.json").exists());
+
+    let mut cmd = base_cmd(home);
+    cmd.args(["index", "--data-dir", data_dir.to_str().unwrap(), "--json"]);
+    cmd.assert().success();
+
+    let report = codex(&dry_run());
+    assert_eq!(report["conversations"], 0);
+    assert_eq!(report["skipped"][0]["path"], file.to_str().unwrap());
+    assert_eq!(report["skipped"][0]["reason"], "unchanged since last index");
+}
+
+#[test]
+fn import_trajectory_is_searchable_and_survives_full_rebuild() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let data_dir = home.join("cass_data");
+    let traj = home.join("django__django-11099.traj");
+    fs::write(
+        &traj,
+        r#"{"history": [
+  {"role": "system", "content": "You are a software engineer.", "agent": "main"},
+  {"role": "user", "content": "UsernameValidator allows trailing newlinezqx", "agent": "main"},
+  {"role": "assistant", "content": "Use \\A and \\Z anchors.", "agent": "main", "message_type": "action"}
+], "info": {"exit_status": "submitted"}}"#,
+    )
+    .unwrap();
+
+    let search_hits = || {
+        let mut search = base_cmd(home);
+        search.args([
+            "search",
+            "newlinezqx",
+            "--robot",
+            "--agent",
+            "trajectory",
+            "--data-dir",
+            data_dir.to_str().unwrap(),
+        ]);
+        let output = search.output().expect("search command");
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["hits"].as_array().map(|h| h.len()).unwrap_or(0)
+    };
+
+    let mut import = base_cmd(home);
+    import.args([
+        "import",
+        "--format",
+        "trajectory",
+        traj.to_str().unwrap(),
+        "--data-dir",
+        data_dir.to_str().unwrap(),
+        "--json",
+    ]);
+    let output = import.output().unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["imported"][0]["conversation_id"],
+        "django__django-11099"
+    );
+    assert_eq!(json["imported"][0]["messages"], 3);
+    assert!(
+        data_dir
+            .join("imports/trajectory/django__django-11099.json")
+            .exists()
+    );
+    // The title is indexed with every message, so each message of the import matches.
+    let hits = search_hits();
+    assert!(hits > 0);
+
+    let mut full = base_cmd(home);
+    full.args([
+        "index",
+        "--full",
+        "--data-dir",
+        data_dir.to_str().unwrap(),
+        "--json",
+    ]);
+    full.assert().success();
+    assert_eq!(search_hits(), hits, "imports are re-read on full rebuild");
+}
+
+#[test]
+fn import_rejects_non_trajectory_json() {
+    let tmp = TempDir::new().unwrap();
+    let bogus = tmp.path().join("notes.json");
+    fs::write(&bogus, r#"{"hello": "world"}"#).unwrap();
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args([
+        "import",
+        "--format",
+        "trajectory",
+        bogus.to_str().unwrap(),
+        "--data-dir",
+        tmp.path().join("data").to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(contains("is not a trajectory"));
+}
+
+#[test]
+fn config_file_redirects_and_disables_connectors() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let data_dir = home.join("cass_data");
+    let backup = home.join("backup/codex-old");
+    make_codex_session(
+        &backup,
+        "2025/01/02",
+        "rollout-1.jsonl",
+        "relocated_content",
+    );
+    let config = home.join(".config/cass/config.toml");
+    fs::create_dir_all(config.parent().unwrap()).unwrap();
+
+    let run = |args: &[&str]| {
+        let mut cmd = base_cmd(home);
+        cmd.env_remove("CODEX_HOME");
+        cmd.args(args);
+        cmd.output().unwrap()
+    };
+    let hits = || {
+        let output = run(&[
+            "search",
+            "relocated_content",
+            "--robot",
+            "--data-dir",
+            data_dir.to_str().unwrap(),
+        ]);
+        assert!(output.status.success(), "{output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["hits"].as_array().map(|h| h.len()).unwrap_or(0)
+    };
+    let index_full = || {
+        let output = run(&[
+            "index",
+            "--full",
+            "--data-dir",
+            data_dir.to_str().unwrap(),
+            "--json",
+        ]);
+        assert!(output.status.success(), "{output:?}");
+    };
+
+    fs::write(
+        &config,
+        format!(
+            "[connectors]\ncodex.root = {:?}\n",
+            backup.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+    index_full();
+    assert!(
+        hits() > 0,
+        "codex sessions are read from the configured root"
+    );
+
+    fs::write(
+        &config,
+        format!(
+            "[connectors]\ncodex = {{ root = {:?}, enabled = false }}\n",
+            backup.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+    index_full();
+    assert_eq!(hits(), 0, "disabled connectors are skipped");
+}
+
+#[cfg(unix)]
+#[test]
+fn extra_roots_and_symlinked_session_dirs_are_indexed_once() {
+    use std::os::unix::fs::symlink;
+
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let data_dir = home.join("cass_data");
+    make_codex_session(
+        &home.join(".codex"),
+        "2025/01/01",
+        "rollout-1.jsonl",
+        "home_content",
+    );
+
+    // History moved to another disk: the extra root's sessions dir is a link, the
+    // tree links back to itself, and one day is reachable under two names.
+    let disk = home.join("disk2/codex-sessions");
+    make_codex_session(&disk, "2025/02/01", "rollout-2.jsonl", "moved_content");
+    let extra = home.join("codex-moved");
+    fs::create_dir_all(&extra).unwrap();
+    symlink(disk.join("sessions"), extra.join("sessions")).unwrap();
+    symlink(disk.join("sessions"), disk.join("sessions/2025/loop")).unwrap();
+    symlink(
+        disk.join("sessions/2025/02"),
+        disk.join("sessions/2025/february"),
+    )
+    .unwrap();
+
+    let config = home.join(".config/cass/config.toml");
+    fs::create_dir_all(config.parent().unwrap()).unwrap();
+    fs::write(
+        &config,
+        format!(
+            "extra_roots = [{{ agent = \"codex\", path = {:?} }}]\n",
+            extra.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let output = base_cmd(home)
+        .args(["index", "--full", "--json", "--data-dir"])
+        .arg(&data_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["conversations"], 2, "{json}");
+
+    let output = base_cmd(home)
+        .args(["search", "moved_content", "--robot", "--data-dir"])
+        .arg(&data_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(
+        json["hits"].as_array().is_some_and(|h| !h.is_empty()),
+        "{json}"
+    );
+}
+
+#[test]
+fn index_remote_mirrors_host_and_tags_conversations() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let data_dir = home.join("cass_data");
+    let remote_home = home.join("box1-home");
+    make_codex_session(
+        &remote_home.join(".codex"),
+        "2025/02/03",
+        "rollout-1.jsonl",
+        "remotebox_content",
+    );
+    // Stand-in for rsync: copy the requested `host:path` sources from a local "remote" home.
+    let fake_rsync = home.join("fake-rsync.sh");
+    fs::write(
+        &fake_rsync,
+        r#"#!/bin/sh
+for dest; do :; done
+for arg; do
+  case "$arg" in
+    -*) ;;
+    *:*) p="${arg#*:}"
+         if [ -e "$FAKE_REMOTE_HOME/$p" ]; then
+           mkdir -p "$dest/$(dirname "$p")"
+           cp -R "$FAKE_REMOTE_HOME/$p" "$dest/$(dirname "$p")/"
+         fi ;;
+  esac
+done
+"#,
+    )
+    .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&fake_rsync, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    let run = |args: &[&str]| {
+        let mut cmd = base_cmd(home);
+        cmd.env("CASS_RSYNC", &fake_rsync)
+            .env("FAKE_REMOTE_HOME", &remote_home)
+            .args(args)
+            .args(["--data-dir", data_dir.to_str().unwrap()]);
+        let output = cmd.output().unwrap();
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+    let remote_hits = || {
+        let json = run(&["search", "remotebox_content", "--robot"]);
+        let hits = json["hits"].as_array().cloned().unwrap_or_default();
+        for hit in &hits {
+            assert!(
+                hit["source_path"]
+                    .as_str()
+                    .unwrap()
+                    .contains("remotes/box1/.codex"),
+                "{hit}"
+            );
+        }
+        hits.len()
+    };
+
+    let indexed = run(&["index", "--remote", "me@box1", "--json"]);
+    assert_eq!(indexed["remotes"], serde_json::json!(["me@box1"]));
+    assert!(remote_hits() > 0);
+
+    let conn = rusqlite::Connection::open(data_dir.join("agent_search.db")).unwrap();
+    let (external_id, metadata): (String, String) = conn
+        .query_row(
+            "SELECT external_id, metadata_json FROM conversations",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap();
+    assert!(external_id.starts_with("box1:"), "{external_id}");
+    assert!(metadata.contains(r#""host":"box1""#), "{metadata}");
+    drop(conn);
+
+    // A full rebuild re-reads the mirror without contacting the remote.
+    fs::remove_file(&fake_rsync).unwrap();
+    run(&["index", "--full", "--json"]);
+    assert!(remote_hits() > 0);
+}
+
+#[test]
+fn index_remote_rejects_hosts_that_look_like_options() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let marker = home.join("rsync-ran");
+    let fake_rsync = home.join("fake-rsync.sh");
+    fs::write(
+        &fake_rsync,
+        format!("#!/bin/sh\ntouch {}\n", marker.display()),
+    )
+    .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&fake_rsync, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    let mut cmd = base_cmd(home);
+    cmd.env("CASS_RSYNC", &fake_rsync).args([
+        "index",
+        "--remote=-oProxyCommand=touch /tmp/x",
+        "--data-dir",
+        home.join("cass_data").to_str().unwrap(),
+    ]);
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(contains("starts with `-`"));
+    assert!(!marker.exists());
+}
+
+#[test]
+fn log_file_records_scan_decisions() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let data_dir = home.join("cass_data");
+    make_codex_session(
+        &home.join(".codex"),
+        "2025/03/04",
+        "rollout-1.jsonl",
+        "logged",
+    );
+    let index = |flags: &[&str], cass_log: Option<&str>| {
+        let mut cmd = base_cmd(home);
+        cmd.args(flags)
+            .args(["index", "--json", "--data-dir"])
+            .arg(&data_dir);
+        if let Some(directives) = cass_log {
+            cmd.env("CASS_LOG", directives);
+        }
+        let output = cmd.output().unwrap();
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    index(&["--log-file"], None);
+    let stderr = index(&["--log-file"], None);
+    assert!(
+        !stderr.contains("skipping unchanged file"),
+        "robot runs keep stderr quiet: {stderr}"
+    );
+    let log: String = fs::read_dir(&data_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("cass.log")
+        })
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect();
+    assert!(log.contains("rollout-1.jsonl"), "{log}");
+    assert!(log.contains("skipping unchanged file"), "{log}");
+    assert!(log.contains("parallel_scan_complete"), "{log}");
+
+    let stderr = index(&[], Some("coding_agent_search::connectors=debug"));
+    assert!(stderr.contains("skipping unchanged file"), "{stderr}");
+    let stderr = index(&["-q"], Some("debug"));
+    assert!(!stderr.contains("skipping unchanged file"), "{stderr}");
+}
+
+#[test]
+fn index_rescans_only_the_selected_agent_or_path() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    make_codex_session(&codex_home, "2025/04/01", "rollout-1.jsonl", "picked");
+    make_codex_session(&codex_home, "2025/04/02", "rollout-1.jsonl", "left_out");
+    let index = |flags: &[&str]| {
+        let output = base_cmd(home)
+            .args(["index", "--json", "--data-dir"])
+            .arg(&data_dir)
+            .args(flags)
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        (output.status.code(), stdout)
+    };
+
+    let day = codex_home.join("sessions/2025/04/01");
+    let (code, stdout) = index(&["--path", day.to_str().unwrap()]);
+    assert_eq!(code, Some(0), "{stdout}");
+    let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(report["agents"], serde_json::json!(["codex"]));
+    assert_eq!(
+        report["conversations"], 1,
+        "only the selected day: {report}"
+    );
+
+    let (code, stdout) = index(&["--agent", "codex"]);
+    assert_eq!(code, Some(0), "{stdout}");
+    let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(report["conversations"], 2, "{report}");
+
+    let (code, _) = index(&["--agent", "codex,nope"]);
+    assert_eq!(code, Some(2));
+    let elsewhere = home.join("notes");
+    fs::create_dir_all(&elsewhere).unwrap();
+    let (code, _) = index(&["--path", elsewhere.to_str().unwrap()]);
+    assert_eq!(code, Some(2), "a path no connector claims needs --agent");
+}
+
+#[test]
+fn resume_prints_or_runs_the_agent_command() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path();
+    let data_dir = home.join("cass_data");
+    let uuid = "5973b6c0-94b8-487b-a530-2aeb6098ae0e";
+    let name = format!("rollout-2025-05-01T10-00-00-{uuid}.jsonl");
+    make_codex_session(&home.join(".codex"), "2025/05/01", &name, "resumable");
+    let session = home.join(".codex/sessions/2025/05/01").join(&name);
+    base_cmd(home)
+        .args(["index", "--json", "--data-dir"])
+        .arg(&data_dir)
+        .assert()
+        .success();
+
+    let output = base_cmd(home)
+        .arg("resume")
+        .arg(&session)
+        .args(["--json", "--data-dir"])
+        .arg(&data_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["agent"], "codex");
+    assert_eq!(json["program"], "codex");
+    assert_eq!(json["args"], serde_json::json!(["resume", uuid]));
+    assert_eq!(json["command"], format!("codex resume {uuid}"));
+
+    let missing = base_cmd(home)
+        .args(["resume", "/no/such/session.jsonl", "--data-dir"])
+        .arg(&data_dir)
+        .output()
+        .unwrap();
+    assert_eq!(missing.status.code(), Some(4));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let bin = home.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        let fake = bin.join("codex");
+        fs::write(&fake, "#!/bin/sh\necho \"fake codex $*\"\n").unwrap();
+        fs::set_permissions(&fake, fs::Permissions::from_mode(0o755)).unwrap();
+        let path = format!(
+            "{}:{}",
+            bin.display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+        let output = base_cmd(home)
+            .arg("resume")
+            .arg(&session)
+            .args(["--exec", "--data-dir"])
+            .arg(&data_dir)
+            .env("PATH", path)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            format!("fake codex resume {uuid}")
+        );
+    }
+}
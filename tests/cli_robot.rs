@@ -289,6 +289,56 @@ fn search_cursor_and_token_budget() {
     }
 }
 
+#[test]
+fn search_highlight_returns_match_offsets() {
+    let mut cmd = base_cmd();
+    cmd.args([
+        "search",
+        "hello",
+        "--json",
+        "--highlight",
+        "--limit",
+        "3",
+        "--data-dir",
+        "tests/fixtures/search_demo_data",
+    ]);
+    let out = cmd.assert().success().get_output().clone();
+    let json: Value = serde_json::from_slice(&out.stdout).expect("valid search json");
+    let hits = json["hits"].as_array().expect("hits array");
+    let mut matched = 0;
+    for hit in hits {
+        let content = hit["content"].as_str().expect("content");
+        // Hits found through their title carry an empty list
+        let ranges = hit["highlights"]["content"]
+            .as_array()
+            .expect("content highlights");
+        matched += ranges.len();
+        for range in ranges {
+            let start = range["byte_start"].as_u64().unwrap() as usize;
+            let end = range["byte_end"].as_u64().unwrap() as usize;
+            assert_eq!(content[start..end].to_lowercase(), "hello");
+            let char_start = range["start"].as_u64().unwrap() as usize;
+            assert_eq!(content[..start].chars().count(), char_start);
+        }
+    }
+    assert!(matched > 0, "some content should contain a match");
+
+    // Without --highlight the payload is unchanged
+    let mut plain = base_cmd();
+    plain.args([
+        "search",
+        "hello",
+        "--json",
+        "--limit",
+        "1",
+        "--data-dir",
+        "tests/fixtures/search_demo_data",
+    ]);
+    let out = plain.assert().success().get_output().clone();
+    let json: Value = serde_json::from_slice(&out.stdout).expect("valid search json");
+    assert!(json["hits"][0].get("highlights").is_none());
+}
+
 #[test]
 fn search_cursor_jsonl_and_compact() {
     let data_dir = "tests/fixtures/search_demo_data";
@@ -379,6 +429,95 @@ fn robot_docs_env_lists_key_vars_and_no_ansi() {
     }
 }
 
+#[test]
+fn help_follows_cass_lang() {
+    let mut cmd = base_cmd();
+    cmd.env("CASS_LANG", "es_ES.UTF-8").arg("--help");
+    cmd.assert()
+        .success()
+        .stdout(contains("Ejecutar una búsqueda puntual"))
+        .stdout(contains("Unified TUI").not());
+
+    let mut cmd = base_cmd();
+    cmd.env("CASS_LANG", "xx").arg("--help");
+    cmd.assert()
+        .success()
+        .stdout(contains("Run a one-off search"));
+}
+
+#[test]
+fn error_hints_are_localized() {
+    let tmp = TempDir::new().unwrap();
+    let mut cmd = base_cmd();
+    let missing = tmp.path().join("missing.jsonl");
+    cmd.env("CASS_LANG", "es")
+        .args(["expand", missing.to_str().unwrap(), "-n", "1", "--json"]);
+    let out = cmd.assert().failure().get_output().clone();
+    let err: Value = serde_json::from_slice(&out.stderr).expect("json error on stderr");
+    assert_eq!(err["error"]["kind"], "file-not-found");
+    let hint = err["error"]["hint"].as_str().unwrap_or_default();
+    assert!(
+        hint.contains("para encontrar"),
+        "hint should be Spanish: {hint}"
+    );
+}
+
+#[test]
+fn telemetry_is_opt_in_and_exports_aggregates_only() {
+    let home = TempDir::new().unwrap();
+    let xdg = home.path().join(".local/share");
+    let cass = |args: &[&str]| {
+        let mut cmd = base_cmd();
+        cmd.env("HOME", home.path())
+            .env("XDG_DATA_HOME", &xdg)
+            .args(args);
+        cmd.assert().success().get_output().stdout.clone()
+    };
+
+    let status: Value = serde_json::from_slice(&cass(&["telemetry", "status", "--json"])).unwrap();
+    assert_eq!(status["enabled"], false);
+    cass(&[
+        "search",
+        "hello",
+        "--json",
+        "--data-dir",
+        "tests/fixtures/search_demo_data",
+    ]);
+    let exported: Value = serde_json::from_slice(&cass(&["telemetry", "export"])).unwrap();
+    assert!(
+        exported["commands"].as_object().unwrap().is_empty(),
+        "nothing is recorded before opting in"
+    );
+
+    cass(&["telemetry", "enable"]);
+    cass(&[
+        "search",
+        "hello",
+        "--json",
+        "--data-dir",
+        "tests/fixtures/search_demo_data",
+    ]);
+    let exported: Value = serde_json::from_slice(&cass(&["telemetry", "export"])).unwrap();
+    assert_eq!(exported["enabled"], true);
+    assert_eq!(exported["commands"]["search"], 1);
+    assert_eq!(
+        exported["query_latency"]
+            .as_object()
+            .unwrap()
+            .values()
+            .filter_map(Value::as_u64)
+            .sum::<u64>(),
+        1
+    );
+    let raw = serde_json::to_string(&exported).unwrap();
+    assert!(!raw.contains("hello"), "query text must never be recorded");
+
+    cass(&["telemetry", "disable"]);
+    let exported: Value = serde_json::from_slice(&cass(&["telemetry", "export"])).unwrap();
+    assert_eq!(exported["enabled"], false);
+    assert!(exported["commands"].as_object().unwrap().is_empty());
+}
+
 fn read_fixture(name: &str) -> Value {
     let path = Path::new("tests/fixtures/cli_contract").join(name);
     let body = fs::read_to_string(&path).expect("fixture readable");
@@ -683,6 +822,68 @@ fn search_missing_index_returns_json_error_contract() {
     );
 }
 
+#[test]
+fn robot_outputs_are_tagged_with_the_schema_that_describes_them() {
+    let tmp = TempDir::new().unwrap();
+    let run = |args: &[&str]| {
+        let output = base_cmd().args(args).output().unwrap();
+        let text = if output.status.success() {
+            output.stdout
+        } else {
+            output.stderr
+        };
+        serde_json::from_slice::<Value>(&text).expect("robot json")
+    };
+    let cases = [
+        (
+            "search",
+            run(&[
+                "search",
+                "hello",
+                "--robot",
+                "--data-dir",
+                "tests/fixtures/search_demo_data",
+            ]),
+        ),
+        (
+            "status",
+            run(&[
+                "status",
+                "--json",
+                "--data-dir",
+                "tests/fixtures/search_demo_data",
+            ]),
+        ),
+        (
+            "error",
+            run(&[
+                "search",
+                "foo",
+                "--json",
+                "--data-dir",
+                tmp.path().to_str().unwrap(),
+            ]),
+        ),
+    ];
+    for (shape, output) in cases {
+        let schema = run(&["schema", shape]);
+        assert_eq!(output["schema"], schema["$id"], "{shape}: {output}");
+        assert_eq!(schema["$id"], format!("cass.{shape}.v2"));
+        let properties = schema["properties"].as_object().unwrap();
+        for key in output.as_object().unwrap().keys() {
+            assert!(
+                properties.contains_key(key),
+                "{shape} output field {key} is missing from its schema"
+            );
+        }
+    }
+
+    let all = run(&["schema"]);
+    assert!(all["schemas"]["cass.error.v2"].is_object());
+    let unknown = base_cmd().args(["schema", "nope"]).output().unwrap();
+    assert_eq!(unknown.status.code(), Some(2));
+}
+
 #[test]
 fn stats_missing_index_returns_json_error_contract() {
     let tmp = TempDir::new().unwrap();
@@ -896,6 +1097,120 @@ fn status_json_reports_index_health() {
     );
 }
 
+#[test]
+fn show_robot_returns_window_around_message() {
+    let mut cmd = base_cmd();
+    cmd.args([
+        "show",
+        "tests/fixtures/claude_code_real/projects/-test-project/agent-test123.jsonl",
+        "--around-msg",
+        "1",
+        "-C",
+        "1",
+        "--robot",
+    ]);
+    let assert = cmd.assert().success();
+    let json: Value = serde_json::from_slice(&assert.get_output().stdout).expect("valid JSON");
+    let messages = json["messages"].as_array().expect("messages array");
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0]["role"], "user");
+    assert_eq!(messages[1]["msg_idx"], 1);
+    assert_eq!(messages[1]["is_target"], true);
+    assert_eq!(messages[1]["role"], "assistant");
+    assert!(
+        messages[1]["content"]
+            .as_str()
+            .unwrap()
+            .contains("matrix completion")
+    );
+    assert_eq!(json["total_messages"], 3);
+}
+
+#[test]
+fn diff_robot_aligns_two_attempts() {
+    let tmp = TempDir::new().unwrap();
+    let write = |name: &str, lines: &[(&str, &str)]| {
+        let path = tmp.path().join(name);
+        let body: Vec<String> = lines
+            .iter()
+            .map(|(role, text)| serde_json::json!({ "role": role, "content": text }).to_string())
+            .collect();
+        fs::write(&path, body.join("\n")).unwrap();
+        path
+    };
+    let a = write(
+        "a.jsonl",
+        &[
+            ("user", "fix the flaky login test"),
+            ("assistant", "I added a retry around the request."),
+            ("user", "thanks"),
+        ],
+    );
+    let b = write(
+        "b.jsonl",
+        &[
+            ("user", "fix the flaky login test"),
+            ("assistant", "I mocked the clock instead."),
+            ("user", "run the suite too"),
+            ("assistant", "All green."),
+        ],
+    );
+
+    let mut cmd = base_cmd();
+    cmd.arg("diff").arg(&a).arg(&b).arg("--robot");
+    let assert = cmd.assert().success();
+    let json: Value = serde_json::from_slice(&assert.get_output().stdout).expect("valid JSON");
+    let statuses: Vec<&str> = json["entries"]
+        .as_array()
+        .expect("entries array")
+        .iter()
+        .map(|e| e["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["same", "changed", "changed", "only_b"]);
+    assert_eq!(json["summary"]["changed_prompts"], 1);
+    assert_eq!(json["summary"]["divergent_responses"], 1);
+    assert_eq!(json["entries"][1]["b"]["msg_idx"], 1);
+    assert!(
+        json["entries"][1]["diff"]
+            .as_str()
+            .unwrap()
+            .contains("+I mocked the clock instead.")
+    );
+
+    let mut cmd = base_cmd();
+    cmd.arg("diff")
+        .arg(&a)
+        .arg(&b)
+        .args(["--mode", "side-by-side", "--width", "60"]);
+    cmd.assert()
+        .success()
+        .stdout(contains("1 changed prompt(s), 1 divergent response(s)"));
+}
+
+#[test]
+fn repl_answers_queries_and_filter_commands() {
+    let mut cmd = base_cmd();
+    cmd.args([
+        "repl",
+        "--limit",
+        "1",
+        "--data-dir",
+        "tests/fixtures/search_demo_data",
+    ])
+    .write_stdin("hello\n:more\n:show 2\n:agent nobody\nhello\n:bogus\n:quit\n");
+    let assert = cmd.assert().success();
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout);
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr);
+    assert!(stdout.contains("  1. ["), "{stdout}");
+    assert!(stdout.contains("  2. ["), "{stdout}");
+    assert!(stdout.contains("Gemini reply"), "{stdout}");
+    assert!(stdout.contains("agent=nobody"), "{stdout}");
+    assert!(stdout.contains("no hits"), "{stdout}");
+    assert!(stderr.contains("unknown command :bogus"), "{stderr}");
+    assert!(!stderr.contains("search_start"), "{stderr}");
+    assert!(!Path::new("tests/fixtures/search_demo_data/repl_history.txt").exists());
+}
+
 #[test]
 fn view_json_highlights_requested_line() {
     let mut cmd = base_cmd();
@@ -2322,3 +2637,189 @@ fn multiple_normalizations_combined() {
     // Should normalize to --robot-help
     cmd.assert().success().stdout(contains("cass --robot-help"));
 }
+
+/// Permalinks round-trip: `permalink` emits a cass:// URL that `open-url --json` resolves
+#[test]
+fn permalink_round_trips_through_open_url() {
+    let data_dir = "tests/fixtures/search_demo_data";
+    let source = "tests/fixtures/search_demo_data/gemini_logs/sessionA/session1.jsonl";
+
+    let mut cmd = base_cmd();
+    cmd.args([
+        "permalink",
+        source,
+        "-n",
+        "2",
+        "--json",
+        "--data-dir",
+        data_dir,
+    ]);
+    let output = cmd.assert().success().get_output().clone();
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid permalink json");
+    let url = json["url"].as_str().expect("url field");
+    assert_eq!(url, "cass://claude_code/session1.jsonl#1");
+
+    let mut cmd = base_cmd();
+    cmd.args(["open-url", url, "--json", "--data-dir", data_dir]);
+    let output = cmd.assert().success().get_output().clone();
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid open-url json");
+    assert_eq!(json["source_path"], source);
+    assert_eq!(json["msg_idx"], 1);
+    assert_eq!(json["line_number"], 2);
+}
+
+#[test]
+fn open_url_rejects_malformed_links() {
+    let mut cmd = base_cmd();
+    cmd.args([
+        "open-url",
+        "https://example.com",
+        "--json",
+        "--data-dir",
+        "tests/fixtures/search_demo_data",
+    ]);
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(contains("Invalid permalink"));
+}
+
+/// Robot search is a read-only path: it writes nothing under the data dir, neither
+/// index directories nor changes to the database. Its cold-start time is measured by
+/// the `search_cold_open` bench in benches/runtime_perf.rs, not asserted here.
+#[test]
+fn robot_search_fast_start_is_read_only() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    fs::copy(
+        "tests/fixtures/search_demo_data/agent_search.db",
+        data_dir.join("agent_search.db"),
+    )
+    .unwrap();
+
+    // Every file under the data dir with its length and modification time, but for the
+    // -shm/-wal files SQLite creates for any reader of a WAL-mode database.
+    let snapshot = |dir: &std::path::Path| {
+        let mut files = std::collections::BTreeMap::new();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            for entry in fs::read_dir(&dir).unwrap() {
+                let entry = entry.unwrap();
+                let meta = entry.metadata().unwrap();
+                if meta.is_dir() {
+                    pending.push(entry.path());
+                }
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if name.ends_with("-shm") || name.ends_with("-wal") {
+                    continue;
+                }
+                files.insert(entry.path(), (meta.len(), meta.modified().unwrap()));
+            }
+        }
+        files
+    };
+    let before = snapshot(&data_dir);
+    for _ in 0..2 {
+        base_cmd()
+            .args(["search", "hello", "--robot", "--data-dir"])
+            .arg(&data_dir)
+            .env("CASS_NO_DAEMON", "1")
+            .assert()
+            .success();
+    }
+
+    assert!(
+        !data_dir.join("index").exists(),
+        "robot search must not create index directories"
+    );
+    assert_eq!(
+        snapshot(&data_dir),
+        before,
+        "robot search must not write to the data dir"
+    );
+}
+
+/// `--copy` hands the first hit (or the shown message) to the configured clipboard tool,
+/// leaving the robot output on stdout as it was.
+#[cfg(unix)]
+#[test]
+fn copy_flag_pipes_the_hit_to_the_clipboard_tool() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = TempDir::new().unwrap();
+    let bin = tmp.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let tool = bin.join("pbcopy");
+    fs::write(&tool, "#!/bin/sh\ncat > \"$CLIP_OUT\"\n").unwrap();
+    fs::set_permissions(&tool, fs::Permissions::from_mode(0o755)).unwrap();
+    let config = tmp.path().join("config.toml");
+    fs::write(
+        &config,
+        "[clipboard]\nmethod = \"command\"\nssh_method = \"command\"\n",
+    )
+    .unwrap();
+    let clip = tmp.path().join("clip.txt");
+    let path = format!(
+        "{}:{}",
+        bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let run = |args: &[&str]| -> Value {
+        let output = base_cmd()
+            .args(args)
+            .args(["--data-dir", "tests/fixtures/search_demo_data"])
+            .env("CASS_CONFIG", &config)
+            .env("CASS_NO_DAEMON", "1")
+            .env("CLIP_OUT", &clip)
+            .env("PATH", &path)
+            .env_remove("WAYLAND_DISPLAY")
+            .assert()
+            .success()
+            .stderr(contains("to the clipboard"))
+            .get_output()
+            .clone();
+        serde_json::from_slice(&output.stdout).expect("robot output stays on stdout")
+    };
+
+    let search = run(&[
+        "search", "hello", "--robot", "--limit", "1", "--copy", "path",
+    ]);
+    assert_eq!(
+        fs::read_to_string(&clip).unwrap(),
+        search["hits"][0]["source_path"].as_str().unwrap()
+    );
+
+    let session = tmp.path().join("session.jsonl");
+    fs::write(
+        &session,
+        "{\"role\":\"user\",\"content\":\"why does it hang?\"}\n\
+         {\"role\":\"assistant\",\"content\":\"The lock is never released.\"}\n",
+    )
+    .unwrap();
+    let source = session.to_str().unwrap();
+    let show = run(&[
+        "show",
+        source,
+        "--around-msg",
+        "1",
+        "-C",
+        "0",
+        "--json",
+        "--copy",
+    ]);
+    assert_eq!(
+        fs::read_to_string(&clip).unwrap(),
+        "The lock is never released."
+    );
+    assert_eq!(
+        show["messages"][0]["content"],
+        "The lock is never released."
+    );
+
+    base_cmd()
+        .args(["show", source, "--copy", "snippet"])
+        .assert()
+        .failure()
+        .code(2);
+}
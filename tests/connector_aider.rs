@@ -384,6 +384,87 @@ fn aider_multiple_turns() {
     assert_eq!(msgs[5].role, "assistant");
 }
 
+// =============================================================================
+// LLM HISTORY TESTS
+// =============================================================================
+
+const LLM_HISTORY: &str = "\
+TO LLM 2024-05-01T12:00:00
+-------
+SYSTEM Act as an expert software developer.
+-------
+USER Fix the failing test in src/lib.rs
+LLM RESPONSE 2024-05-01T12:00:10
+ASSISTANT Here is the fix:
+ASSISTANT
+ASSISTANT src/lib.rs
+TO LLM 2024-05-01T12:00:20
+-------
+SYSTEM Act as an expert software developer.
+-------
+USER Fix the failing test in src/lib.rs
+-------
+ASSISTANT Here is the fix:
+-------
+USER I ran this command:
+USER cargo test
+USER Output: thread 'parses_dates' panicked at src/lib.rs:42
+-------
+SYSTEM Reminder: always use SEARCH/REPLACE blocks.
+LLM RESPONSE 2024-05-01T12:00:30
+ASSISTANT The date parser needs a timezone.
+";
+
+/// The raw LLM log becomes its own conversation with only the new turns of each prompt
+#[test]
+fn aider_parses_llm_history_as_separate_stream() {
+    let tmp = TempDir::new().unwrap();
+    create_aider_fixture(&tmp, ".aider.chat.history.md", "> Fix the test\n\nDone\n");
+    let llm = create_aider_fixture(&tmp, ".aider.llm.history", LLM_HISTORY);
+
+    let conn = AiderConnector::new();
+    let ctx = ScanContext {
+        data_root: tmp.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = conn.scan(&ctx).expect("scan");
+    assert_eq!(convs.len(), 2);
+
+    let conv = convs
+        .iter()
+        .find(|c| c.source_path == llm)
+        .expect("llm history conversation");
+    assert!(
+        conv.title
+            .as_ref()
+            .unwrap()
+            .starts_with("Aider LLM History:")
+    );
+    assert_eq!(conv.external_id.as_deref(), Some(".aider.llm.history"));
+    assert_eq!(conv.metadata["source"], "llm_history");
+
+    let roles: Vec<&str> = conv.messages.iter().map(|m| m.role.as_str()).collect();
+    assert_eq!(roles, ["user", "assistant", "user", "assistant"]);
+    assert_eq!(
+        conv.messages[0].content,
+        "Fix the failing test in src/lib.rs"
+    );
+    assert_eq!(conv.messages[1].content, "Here is the fix:\n\nsrc/lib.rs");
+    // Tool output the markdown history loses is searchable here.
+    assert!(
+        conv.messages[2]
+            .content
+            .contains("panicked at src/lib.rs:42")
+    );
+    assert!(!conv.messages[2].content.contains("Reminder"));
+
+    for (i, msg) in conv.messages.iter().enumerate() {
+        assert_eq!(msg.idx, i as i64);
+        assert!(msg.created_at.is_some());
+    }
+    assert!(conv.started_at < conv.ended_at);
+}
+
 // =============================================================================
 // EMPTY / EDGE CASE TESTS
 // =============================================================================
@@ -164,6 +164,46 @@ fn claude_connector_flattens_tool_use() {
     assert!(assistant.content.contains("/src/main.rs"));
 }
 
+/// Test tool calls and fenced code become structured snippets
+#[test]
+fn claude_connector_extracts_snippets() {
+    let dir = create_claude_temp();
+    let projects = dir.path().join("mock-claude/projects/test-proj");
+    fs::create_dir_all(&projects).unwrap();
+    let file = projects.join("session.jsonl");
+
+    let sample = r#"{"type":"user","message":{"role":"user","content":"Why does this panic?\n```rust\nlet v: Vec<u8> = vec![];\nv[0];\n```"},"timestamp":"2025-11-12T18:31:18.000Z"}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Let me run it"},{"type":"tool_use","name":"Bash","input":{"command":"cargo run","description":"Run the binary"}},{"type":"tool_use","name":"Edit","input":{"file_path":"/src/main.rs","old_string":"v[0]","new_string":"v.first()"}}]},"timestamp":"2025-11-12T18:31:20.000Z"}
+"#;
+    fs::write(&file, sample).unwrap();
+
+    let conn = ClaudeCodeConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().join("mock-claude"),
+        since_ts: None,
+    };
+    let convs = conn.scan(&ctx).unwrap();
+    let msgs = &convs[0].messages;
+
+    let code = &msgs[0].snippets;
+    assert_eq!(code.len(), 1);
+    assert_eq!(code[0].language.as_deref(), Some("rust"));
+    assert_eq!((code[0].start_line, code[0].end_line), (Some(3), Some(4)));
+    assert_eq!(
+        code[0].snippet_text.as_deref(),
+        Some("let v: Vec<u8> = vec![];\nv[0];")
+    );
+
+    let tools = &msgs[1].snippets;
+    assert_eq!(tools.len(), 2);
+    assert_eq!(
+        tools[0].snippet_text.as_deref(),
+        Some("[Tool: Bash] cargo run")
+    );
+    assert_eq!(tools[0].file_path, None);
+    assert_eq!(tools[1].file_path, Some(PathBuf::from("/src/main.rs")));
+}
+
 /// Test title extraction from first user message
 #[test]
 fn claude_connector_extracts_title_from_user() {
@@ -566,3 +606,75 @@ fn claude_connector_uses_entry_type_as_role() {
     assert_eq!(convs.len(), 1);
     assert_eq!(convs[0].messages[0].role, "user");
 }
+
+/// Test early transcripts that log bare API messages without the entry envelope
+#[test]
+fn claude_connector_reads_flat_legacy_transcripts() {
+    let dir = create_claude_temp();
+    let projects = dir.path().join("mock-claude/projects/old-proj");
+    fs::create_dir_all(&projects).unwrap();
+    let file = projects.join("legacy.jsonl");
+
+    let sample = r#"{"role":"user","content":"Why is the build slow?","timestamp":"2025-02-24T09:00:00.000Z"}
+{"role":"assistant","model":"claude-3-7-sonnet","content":[{"type":"text","text":"Incremental compilation is off."}],"timestamp":"2025-02-24T09:00:05.000Z"}
+{"role":"system","content":"ignored"}
+"#;
+    fs::write(&file, sample).unwrap();
+
+    let conn = ClaudeCodeConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().join("mock-claude"),
+        since_ts: None,
+    };
+    let convs = conn.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.messages.len(), 2);
+    assert_eq!(c.messages[1].role, "assistant");
+    assert_eq!(c.messages[1].author.as_deref(), Some("claude-3-7-sonnet"));
+    assert_eq!(c.title.as_deref(), Some("Why is the build slow?"));
+    assert!(c.started_at.is_some());
+    assert_eq!(
+        c.metadata.get("format").and_then(|v| v.as_str()),
+        Some("flat")
+    );
+}
+
+/// Sessions are parsed in parallel; each conversation must still come from its own file.
+#[test]
+fn claude_connector_keeps_each_session_with_its_file_when_parsing_in_parallel() {
+    let dir = create_claude_temp();
+    let projects = dir.path().join("mock-claude/projects/many");
+    fs::create_dir_all(&projects).unwrap();
+    for i in 0..64 {
+        fs::write(
+            projects.join(format!("session-{i}.jsonl")),
+            format!(
+                r#"{{"type":"user","sessionId":"s{i}","message":{{"role":"user","content":"question {i}"}},"timestamp":"2025-11-12T18:31:18.000Z"}}
+"#
+            ),
+        )
+        .unwrap();
+    }
+
+    let conn = ClaudeCodeConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().join("mock-claude"),
+        since_ts: None,
+    };
+    let convs = conn.scan(&ctx).expect("scan");
+    assert_eq!(convs.len(), 64);
+    for conv in &convs {
+        let i = conv
+            .external_id
+            .as_deref()
+            .and_then(|id| id.strip_prefix("session-"))
+            .and_then(|id| id.strip_suffix(".jsonl"))
+            .unwrap();
+        assert_eq!(conv.messages[0].content, format!("question {i}"));
+        assert_eq!(
+            conv.metadata.get("sessionId").and_then(|v| v.as_str()),
+            Some(format!("s{i}").as_str())
+        );
+    }
+}
@@ -238,6 +238,52 @@ fn codex_connector_reads_legacy_json_format() {
     assert_eq!(c.messages[1].role, "assistant");
 }
 
+/// Test early rollout-*.jsonl files: bare header line, un-enveloped items, state markers
+#[test]
+#[serial]
+fn codex_connector_reads_legacy_flat_jsonl() {
+    let dir = TempDir::new().unwrap();
+    let sessions = dir.path().join("sessions/2025/05/07");
+    fs::create_dir_all(&sessions).unwrap();
+    let file = sessions.join("rollout-2025-05-07T10-00-00-flat.jsonl");
+
+    let sample = r#"{"id":"flat-session","timestamp":"2025-05-07T10:00:00.000Z","instructions":null}
+{"record_type":"state"}
+{"type":"message","role":"user","content":[{"type":"input_text","text":"rename the crate"}]}
+{"type":"function_call","name":"shell","arguments":"{\"command\":[\"bash\",\"-lc\",\"cargo check\"]}","call_id":"c1"}
+{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Renamed and rebuilt."}]}
+{"record_type":"state"}
+"#;
+    fs::write(&file, sample).unwrap();
+
+    unsafe {
+        std::env::set_var("CODEX_HOME", dir.path());
+    }
+
+    let connector = CodexConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+
+    let c = &convs[0];
+    assert_eq!(c.messages.len(), 2);
+    assert_eq!(c.messages[0].content, "rename the crate");
+    assert_eq!(c.messages[0].snippets.len(), 1, "tool call joins its turn");
+    assert_eq!(c.messages[1].role, "assistant");
+    assert_eq!(c.messages[1].content, "Renamed and rebuilt.");
+    assert!(
+        c.started_at.is_some(),
+        "header timestamp starts the session"
+    );
+    assert_eq!(
+        c.metadata.get("format").and_then(|v| v.as_str()),
+        Some("flat")
+    );
+}
+
 /// Test detection with existing sessions directory
 #[test]
 #[serial]
@@ -927,3 +973,143 @@ fn codex_connector_metadata_indicates_format() {
         Some("rollout_json")
     );
 }
+
+/// Test function calls and patches attach tool snippets to the preceding turn
+#[test]
+#[serial]
+fn codex_connector_extracts_tool_call_snippets() {
+    let dir = TempDir::new().unwrap();
+    let sessions = dir.path().join("sessions/2025/12/14");
+    fs::create_dir_all(&sessions).unwrap();
+    let file = sessions.join("rollout-tools.jsonl");
+
+    let sample = r#"{"timestamp":"2025-09-30T15:42:36.190Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"fix the build"}]}}
+{"timestamp":"2025-09-30T15:42:37.000Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"text","text":"Checking first."}]}}
+{"timestamp":"2025-09-30T15:42:38.000Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"bash\",\"-lc\",\"cargo build\"]}","call_id":"c1"}}
+{"timestamp":"2025-09-30T15:42:39.000Z","type":"response_item","payload":{"type":"custom_tool_call","name":"apply_patch","input":"*** Begin Patch\n*** Update File: src/lib.rs\n@@\n-old\n+new\n*** Add File: src/new.rs\n+x\n*** End Patch"}}
+"#;
+    fs::write(&file, sample).unwrap();
+
+    unsafe {
+        std::env::set_var("CODEX_HOME", dir.path());
+    }
+
+    let connector = CodexConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    let msgs = &convs[0].messages;
+    assert_eq!(msgs.len(), 2, "tool calls do not become messages");
+
+    let snippets = &msgs[1].snippets;
+    assert_eq!(snippets.len(), 3);
+    assert_eq!(
+        snippets[0].snippet_text.as_deref(),
+        Some("[Tool: shell] cargo build")
+    );
+    assert_eq!(snippets[1].file_path, Some(PathBuf::from("src/lib.rs")));
+    assert_eq!(snippets[2].file_path, Some(PathBuf::from("src/new.rs")));
+}
+
+fn gzip(bytes: &[u8]) -> Vec<u8> {
+    use std::io::Write;
+    let mut enc = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    enc.write_all(bytes).unwrap();
+    enc.finish().unwrap()
+}
+
+/// Test rotated `.jsonl.gz`/`.jsonl.zst` rollouts are read and keep the plain file's id
+#[test]
+#[serial]
+fn codex_connector_reads_compressed_rollouts() {
+    let dir = TempDir::new().unwrap();
+    let sessions = dir.path().join("sessions/2025/12/15");
+    fs::create_dir_all(&sessions).unwrap();
+
+    let sample = |text: &str| {
+        format!(
+            r#"{{"timestamp":"2025-09-30T15:42:36.190Z","type":"response_item","payload":{{"type":"message","role":"user","content":[{{"type":"input_text","text":"{text}"}}]}}}}
+"#
+        )
+    };
+    fs::write(
+        sessions.join("rollout-gz.jsonl.gz"),
+        gzip(sample("archived with gzip").as_bytes()),
+    )
+    .unwrap();
+    fs::write(
+        sessions.join("rollout-zst.jsonl.zst"),
+        zstd::encode_all(sample("archived with zstd").as_bytes(), 0).unwrap(),
+    )
+    .unwrap();
+
+    unsafe {
+        std::env::set_var("CODEX_HOME", dir.path());
+    }
+
+    let connector = CodexConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let mut convs = connector.scan(&ctx).unwrap();
+    convs.sort_by(|a, b| a.external_id.cmp(&b.external_id));
+    assert_eq!(convs.len(), 2);
+    assert_eq!(
+        convs[0].external_id.as_deref(),
+        Some("2025/12/15/rollout-gz")
+    );
+    assert_eq!(convs[0].messages[0].content, "archived with gzip");
+    assert_eq!(convs[0].metadata["source"], "rollout");
+    assert_eq!(
+        convs[1].external_id.as_deref(),
+        Some("2025/12/15/rollout-zst")
+    );
+    assert_eq!(convs[1].messages[0].content, "archived with zstd");
+}
+
+/// Test a truncated archive still yields the lines decoded before the damage
+#[test]
+#[serial]
+fn codex_connector_recovers_truncated_archive() {
+    let dir = TempDir::new().unwrap();
+    let sessions = dir.path().join("sessions/2025/12/16");
+    fs::create_dir_all(&sessions).unwrap();
+
+    let mut body = String::new();
+    for i in 0..200 {
+        body.push_str(&format!(
+            r#"{{"timestamp":"2025-09-30T15:42:36.190Z","type":"response_item","payload":{{"type":"message","role":"user","content":[{{"type":"input_text","text":"line {i} of a long session"}}]}}}}
+"#
+        ));
+    }
+    let gz = gzip(body.as_bytes());
+    fs::write(
+        sessions.join("rollout-cut.jsonl.gz"),
+        &gz[..gz.len() * 2 / 3],
+    )
+    .unwrap();
+    fs::write(sessions.join("rollout-garbage.jsonl.gz"), b"not gzip").unwrap();
+
+    unsafe {
+        std::env::set_var("CODEX_HOME", dir.path());
+    }
+
+    let connector = CodexConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let err = connector.scan(&ctx);
+    // An archive with nothing recoverable is an error like any unreadable file;
+    // drop it and the truncated one is indexed up to the cut.
+    assert!(err.is_err());
+    fs::remove_file(sessions.join("rollout-garbage.jsonl.gz")).unwrap();
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    let msgs = &convs[0].messages;
+    assert!(!msgs.is_empty() && msgs.len() < 200, "{}", msgs.len());
+    assert_eq!(msgs[0].content, "line 0 of a long session");
+}
@@ -0,0 +1,85 @@
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use coding_agent_search::connectors::{Connector, ScanContext, copilot_cli::CopilotCliConnector};
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn copilot_cli_connector_reads_session_event_log() {
+    let dir = TempDir::new().unwrap();
+    let sessions = dir.path().join("session-state");
+    fs::create_dir_all(&sessions).unwrap();
+    let file = sessions.join("0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b.jsonl");
+
+    let sample = r#"{"type":"session.start","data":{"sessionId":"0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b","selectedModel":"claude-sonnet-4.5","context":{"cwd":"/home/test/repo"}},"id":"e1","timestamp":"2025-10-01T09:00:00.000Z"}
+{"type":"user.message","data":{"content":"find the flaky retry test"},"id":"e2","timestamp":"2025-10-01T09:00:01.000Z"}
+{"type":"assistant.message","data":{"content":"Let me search the tests.","toolRequests":[{"toolCallId":"t1","name":"grep","arguments":{"pattern":"retry"}}]},"id":"e3","timestamp":"2025-10-01T09:00:04.000Z"}
+{"type":"tool.execution_complete","data":{"toolCallId":"t1","success":true,"result":{"content":"tests/retry.rs:12: fn retry_backoff()"}},"id":"e4","timestamp":"2025-10-01T09:00:05.000Z"}
+"#;
+    fs::write(&file, sample).unwrap();
+
+    unsafe {
+        std::env::set_var("COPILOT_HOME", dir.path());
+    }
+
+    let connector = CopilotCliConnector::new();
+    assert!(connector.detect().detected);
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "copilot_cli");
+    assert_eq!(
+        c.external_id.as_deref(),
+        Some("0199a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b")
+    );
+    assert_eq!(c.workspace, Some(PathBuf::from("/home/test/repo")));
+    let roles: Vec<&str> = c.messages.iter().map(|m| m.role.as_str()).collect();
+    assert_eq!(roles, vec!["user", "assistant", "tool"]);
+    assert!(c.messages[1].content.contains("[Tool: grep]"));
+    assert!(c.messages[2].content.contains("retry_backoff"));
+    assert!(c.title.as_ref().unwrap().contains("flaky retry"));
+    assert!(c.started_at.unwrap() < c.ended_at.unwrap());
+    assert_eq!(c.metadata["model"], "claude-sonnet-4.5");
+
+    unsafe {
+        std::env::remove_var("COPILOT_HOME");
+    }
+}
+
+#[test]
+#[serial]
+fn copilot_cli_connector_reads_legacy_history_documents() {
+    let dir = TempDir::new().unwrap();
+    let history = dir.path().join("history-session-state");
+    fs::create_dir_all(&history).unwrap();
+    let sample = r#"{
+  "sessionId": "legacy-1",
+  "startTime": "2025-08-20T14:00:00.000Z",
+  "chatMessages": [
+    {"role": "user", "content": "explain this awk one-liner"},
+    {"role": "assistant", "content": "", "tool_calls": [{"id": "c1", "type": "function", "function": {"name": "bash", "arguments": "{\"command\":\"man awk\"}"}}]},
+    {"role": "tool", "tool_call_id": "c1", "content": "AWK(1) manual"},
+    {"role": "assistant", "content": "It prints the second column."}
+  ]
+}"#;
+    fs::write(history.join("session_legacy-1_1755698400000.json"), sample).unwrap();
+
+    let connector = CopilotCliConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.external_id.as_deref(), Some("legacy-1"));
+    assert_eq!(c.messages.len(), 4);
+    assert!(c.messages[1].content.contains("[Tool: bash]"));
+    assert!(c.started_at.is_some());
+}
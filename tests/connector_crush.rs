@@ -0,0 +1,100 @@
+use std::fs;
+use tempfile::TempDir;
+
+use coding_agent_search::connectors::{Connector, ScanContext, crush::CrushConnector};
+use serial_test::serial;
+
+fn write_crush_db(project: &std::path::Path) {
+    let data_dir = project.join(".crush");
+    fs::create_dir_all(&data_dir).unwrap();
+    let conn = rusqlite::Connection::open(data_dir.join("crush.db")).unwrap();
+    conn.execute_batch(
+        r#"
+        CREATE TABLE sessions (id TEXT PRIMARY KEY, parent_session_id TEXT, title TEXT,
+            message_count INTEGER, created_at INTEGER, updated_at INTEGER);
+        CREATE TABLE messages (id TEXT PRIMARY KEY, session_id TEXT, role TEXT, parts TEXT,
+            model TEXT, created_at INTEGER, updated_at INTEGER);
+        INSERT INTO sessions VALUES ('s1', NULL, 'Fix lint config', 3, 1730000000, 1730000060);
+        INSERT INTO messages VALUES ('m1', 's1', 'user',
+            '[{"type":"text","data":{"text":"why does golangci-lint ignore my config?"}}]', NULL, 1730000000, 1730000000);
+        INSERT INTO messages VALUES ('m2', 's1', 'assistant',
+            '[{"type":"reasoning","data":{"thinking":"check the config path"}},{"type":"tool_call","data":{"id":"t1","name":"view","input":"{\"file_path\":\".golangci.yml\"}"}}]',
+            'claude-sonnet-4', 1730000010, 1730000010);
+        INSERT INTO messages VALUES ('m3', 's1', 'tool',
+            '[{"type":"tool_result","data":{"tool_call_id":"t1","name":"view","content":"run:\n  timeout: 5m"}}]', NULL, 1730000011, 1730000011);
+        "#,
+    )
+    .unwrap();
+}
+
+#[test]
+#[serial]
+fn crush_reads_project_database_with_workspace() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path().join("code/linter");
+    write_crush_db(&project);
+
+    unsafe {
+        std::env::set_var("CRUSH_GLOBAL_DATA", dir.path().join("global"));
+    }
+
+    let connector = CrushConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "crush");
+    assert_eq!(c.external_id.as_deref(), Some("s1"));
+    assert_eq!(c.title.as_deref(), Some("Fix lint config"));
+    assert_eq!(c.workspace.as_deref(), Some(project.as_path()));
+    assert_eq!(c.started_at, Some(1_730_000_000_000));
+    assert_eq!(c.messages.len(), 3);
+    assert!(c.messages[1].content.contains("[Tool: view]"));
+    assert!(c.messages[1].content.contains("[Thinking]"));
+    assert_eq!(c.messages[1].author.as_deref(), Some("claude-sonnet-4"));
+    assert!(c.messages[2].content.contains("timeout: 5m"));
+
+    unsafe {
+        std::env::remove_var("CRUSH_GLOBAL_DATA");
+    }
+}
+
+#[test]
+#[serial]
+fn crush_finds_projects_from_global_registry() {
+    let dir = TempDir::new().unwrap();
+    let project = dir.path().join("elsewhere/app");
+    write_crush_db(&project);
+    let global = dir.path().join("global");
+    fs::create_dir_all(&global).unwrap();
+    fs::write(
+        global.join("projects.json"),
+        serde_json::json!({
+            "projects": [{"path": project, "data_dir": ".crush", "last_accessed": "2025-01-01T00:00:00Z"}]
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    unsafe {
+        std::env::set_var("CRUSH_GLOBAL_DATA", &global);
+    }
+
+    let connector = CrushConnector::new();
+    assert!(connector.detect().detected);
+    let empty = TempDir::new().unwrap();
+    let ctx = ScanContext {
+        data_root: empty.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    assert_eq!(convs[0].workspace.as_deref(), Some(project.as_path()));
+
+    unsafe {
+        std::env::remove_var("CRUSH_GLOBAL_DATA");
+    }
+}
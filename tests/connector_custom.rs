@@ -0,0 +1,131 @@
+use std::fs;
+use tempfile::TempDir;
+
+use coding_agent_search::connectors::custom::{CustomConnector, Mapping};
+use coding_agent_search::connectors::{Connector, ScanContext};
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn custom_mapping_indexes_json_documents() {
+    let dir = TempDir::new().unwrap();
+    let sessions = dir.path().join("sessions/2025");
+    fs::create_dir_all(&sessions).unwrap();
+    fs::write(
+        sessions.join("chat-7.json"),
+        r#"{
+  "meta": {"id": "chat-7", "subject": "Flaky CI", "cwd": "/work/ci"},
+  "history": [
+    {"who": "human", "body": {"text": "CI fails only on arm64"}, "at": 1733000000},
+    {"who": "ai", "body": {"text": "Pin the base image digest."}, "at": 1733000030},
+    {"who": "ai", "body": {}, "at": 1733000031}
+  ]
+}"#,
+    )
+    .unwrap();
+    let mapping = dir.path().join("myagent.toml");
+    fs::write(
+        &mapping,
+        r#"
+slug = "myagent"
+files = "sessions/**/*.json"
+messages = "$.history[*]"
+role = "$.who"
+content = "$.body.text | $.body.content"
+timestamp = "$.at"
+timestamp_unit = "s"
+title = "$.meta.subject"
+id = "$.meta.id"
+workspace = "$.meta.cwd"
+role_map = { human = "user", ai = "assistant" }
+"#,
+    )
+    .unwrap();
+
+    unsafe {
+        std::env::set_var("CASS_CUSTOM_CONNECTORS", &mapping);
+    }
+    let connector = CustomConnector::new();
+    unsafe {
+        std::env::remove_var("CASS_CUSTOM_CONNECTORS");
+    }
+
+    assert!(connector.detect().detected);
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "myagent");
+    assert_eq!(c.external_id.as_deref(), Some("chat-7"));
+    assert_eq!(c.title.as_deref(), Some("Flaky CI"));
+    assert_eq!(
+        c.workspace.as_deref(),
+        Some(std::path::Path::new("/work/ci"))
+    );
+    assert_eq!(c.messages.len(), 2, "record without content is dropped");
+    assert_eq!(c.messages[0].role, "user");
+    assert_eq!(c.messages[1].role, "assistant");
+    assert_eq!(c.started_at, Some(1_733_000_000_000));
+    assert_eq!(c.ended_at, Some(1_733_000_030_000));
+}
+
+#[test]
+fn custom_mapping_filters_jsonl_records() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("run.jsonl"),
+        r#"{"type":"header","session":"run-1"}
+{"type":"message","role":"user","content":"summarize the diff","ts":"2025-02-01T10:00:00Z"}
+{"type":"telemetry","role":"system","content":"tokens=512"}
+{"type":"message","role":"assistant","content":[{"type":"text","text":"Two files renamed."}],"ts":"2025-02-01T10:00:04Z"}
+"#,
+    )
+    .unwrap();
+    let mapping = dir.path().join("runs.json");
+    fs::write(
+        &mapping,
+        r#"{"slug": "runner", "files": "*.jsonl", "format": "jsonl",
+            "role": "$.role", "content": "$.content", "timestamp": "$.ts",
+            "id": "$.session", "filter": {"path": "$.type", "equals": "message"}}"#,
+    )
+    .unwrap();
+
+    let connector = CustomConnector::with_mappings(vec![Mapping::load(&mapping).unwrap()]);
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.external_id.as_deref(), Some("run-1"));
+    assert_eq!(c.messages.len(), 2);
+    assert_eq!(c.messages[1].content, "Two files renamed.");
+    assert!(c.title.as_ref().unwrap().contains("summarize"));
+}
+
+#[test]
+fn invalid_mappings_are_rejected_with_context() {
+    let dir = TempDir::new().unwrap();
+    let mapping = dir.path().join("bad.toml");
+    fs::write(
+        &mapping,
+        "slug = \"bad\"\nfiles = \"*.json\"\nmessages = \"history\"\nrole = \"$.r\"\ncontent = \"$.c\"\n",
+    )
+    .unwrap();
+    let err = Mapping::load(&mapping).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("must start with `$`"),
+        "{err:#}"
+    );
+
+    fs::write(
+        &mapping,
+        "slug = \"bad\"\nfiles = \"*\"\nrole = \"$.r\"\ncontent = \"$.c\"\nunknown_key = 1\n",
+    )
+    .unwrap();
+    assert!(Mapping::load(&mapping).is_err());
+}
@@ -568,3 +568,79 @@ fn gemini_falls_back_to_hash_directory_for_workspace() {
             .contains("myhash")
     );
 }
+
+/// Qwen Code shares the Gemini layout but is indexed under its own slug
+#[test]
+fn qwen_reuses_gemini_layout_with_own_slug() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let chats_dir = tmp.path().join("qwenhash/chats");
+    fs::create_dir_all(&chats_dir).unwrap();
+
+    let session = serde_json::json!({
+        "sessionId": "qwen-session",
+        "projectHash": "qwenhash",
+        "messages": [
+            {"type": "user", "content": "refactor the parser", "timestamp": "2025-09-01T08:00:00Z"},
+            {"type": "model", "content": "Splitting it into a lexer first.", "timestamp": "2025-09-01T08:00:05Z"}
+        ]
+    });
+    fs::write(
+        chats_dir.join("session-qwen.json"),
+        serde_json::to_string_pretty(&session).unwrap(),
+    )
+    .unwrap();
+
+    let conn = GeminiConnector::qwen();
+    let ctx = ScanContext {
+        data_root: tmp.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = conn.scan(&ctx).expect("scan");
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "qwen");
+    assert_eq!(c.external_id.as_deref(), Some("qwen-session"));
+    assert_eq!(c.metadata["source"], "qwen");
+    assert_eq!(c.messages[1].role, "assistant");
+}
+
+/// Test `/chat save` checkpoints from releases without session recording
+#[test]
+fn gemini_reads_legacy_checkpoints() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let legacy = tmp.path().join("oldhash");
+    fs::create_dir_all(&legacy).unwrap();
+    let checkpoint = serde_json::json!([
+        {"role": "user", "parts": [{"text": "Port the parser to nom"}]},
+        {"role": "model", "parts": [{"text": "Start with the lexer."}, {"functionCall": {"name": "read_file"}}]},
+        {"role": "user", "parts": [{"functionResponse": {"name": "read_file"}}]}
+    ]);
+    fs::write(legacy.join("checkpoint-nom.json"), checkpoint.to_string()).unwrap();
+
+    // Checkpoints next to recorded sessions duplicate them and are skipped.
+    let modern = tmp.path().join("newhash");
+    fs::create_dir_all(modern.join("chats")).unwrap();
+    fs::write(modern.join("checkpoint-dup.json"), checkpoint.to_string()).unwrap();
+
+    let conn = GeminiConnector::new();
+    let ctx = ScanContext {
+        data_root: tmp.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = conn.scan(&ctx).expect("scan");
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.messages.len(), 2);
+    assert_eq!(c.messages[1].role, "assistant");
+    assert_eq!(c.messages[1].content, "Start with the lexer.");
+    assert_eq!(c.workspace, Some(legacy));
+    assert!(c.started_at.is_some());
+    assert_eq!(
+        c.metadata.get("format").and_then(|v| v.as_str()),
+        Some("checkpoint")
+    );
+    assert_eq!(
+        c.metadata.get("project_hash").and_then(|v| v.as_str()),
+        Some("oldhash")
+    );
+}
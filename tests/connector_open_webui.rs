@@ -0,0 +1,91 @@
+use std::fs;
+use tempfile::TempDir;
+
+use coding_agent_search::connectors::{Connector, ScanContext, open_webui::OpenWebUiConnector};
+
+fn sample_chat() -> serde_json::Value {
+    // Two answers were generated for the first prompt; the user kept the second.
+    serde_json::json!({
+        "title": "Ollama GPU offload",
+        "models": ["llama3.1:8b"],
+        "history": {
+            "currentId": "a2",
+            "messages": {
+                "u1": {"id": "u1", "parentId": null, "role": "user", "content": "how many layers can I offload to a 12GB GPU?", "timestamp": 1726000000},
+                "a1": {"id": "a1", "parentId": "u1", "role": "assistant", "content": "discarded draft answer", "model": "llama3.1:8b", "timestamp": 1726000005},
+                "a2": {"id": "a2", "parentId": "u1", "role": "assistant", "content": "Set num_gpu to 33 for the 8B model.", "model": "llama3.1:8b", "timestamp": 1726000010}
+            }
+        }
+    })
+}
+
+#[test]
+fn open_webui_reads_chat_export_active_branch() {
+    let dir = TempDir::new().unwrap();
+    let export = serde_json::json!([{
+        "id": "chat-1",
+        "user_id": "u",
+        "title": "Ollama GPU offload",
+        "chat": sample_chat(),
+        "created_at": 1726000000,
+        "updated_at": 1726000010
+    }]);
+    fs::write(
+        dir.path().join("chat-export-1726000100.json"),
+        serde_json::to_string(&export).unwrap(),
+    )
+    .unwrap();
+
+    let connector = OpenWebUiConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "open_webui");
+    assert_eq!(c.external_id.as_deref(), Some("chat-1"));
+    assert_eq!(c.title.as_deref(), Some("Ollama GPU offload"));
+    assert_eq!(c.messages.len(), 2);
+    assert!(c.messages[1].content.contains("num_gpu"));
+    assert_eq!(c.messages[1].author.as_deref(), Some("llama3.1:8b"));
+    assert_eq!(c.messages[0].created_at, Some(1_726_000_000_000));
+    assert_eq!(c.started_at, Some(1_726_000_000_000));
+    assert_eq!(c.ended_at, Some(1_726_000_010_000));
+}
+
+#[test]
+fn open_webui_reads_chat_table_from_database() {
+    let dir = TempDir::new().unwrap();
+    let db = dir.path().join("webui.db");
+    let conn = rusqlite::Connection::open(&db).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE chat (id TEXT PRIMARY KEY, user_id TEXT, title TEXT, chat TEXT,
+                            created_at BIGINT, updated_at BIGINT, archived BOOLEAN);",
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO chat (id, user_id, title, chat, created_at, updated_at, archived)
+         VALUES ('db-chat', 'u', 'Ollama GPU offload', ?1, 1726000000, 1726000010, 0)",
+        [serde_json::to_string(&sample_chat()).unwrap()],
+    )
+    .unwrap();
+    drop(conn);
+
+    let connector = OpenWebUiConnector::new();
+    let ctx = ScanContext {
+        data_root: db,
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    assert_eq!(convs[0].external_id.as_deref(), Some("db-chat"));
+    assert_eq!(convs[0].messages.len(), 2);
+    assert!(
+        convs[0]
+            .messages
+            .iter()
+            .all(|m| !m.content.contains("discarded"))
+    );
+}
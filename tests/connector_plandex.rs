@@ -0,0 +1,48 @@
+use std::fs;
+use tempfile::TempDir;
+
+use coding_agent_search::connectors::{Connector, ScanContext, plandex::PlandexConnector};
+
+#[test]
+fn plandex_reads_plan_conversation_and_repo_workspace() {
+    let dir = TempDir::new().unwrap();
+    let convo = dir.path().join("orgs/org-1/plans/plan-abc/conversation");
+    fs::create_dir_all(&convo).unwrap();
+    // Written out of order; `num` decides the sequence.
+    fs::write(
+        convo.join("m2.json"),
+        r#"{"id":"m2","planId":"plan-abc","role":"assistant","num":2,"message":"I'll add a retry wrapper in http.go","createdAt":"2025-07-01T10:00:09Z"}"#,
+    )
+    .unwrap();
+    fs::write(
+        convo.join("m1.json"),
+        r#"{"id":"m1","planId":"plan-abc","role":"user","num":1,"message":"add retries to the http client","createdAt":"2025-07-01T10:00:00Z"}"#,
+    )
+    .unwrap();
+
+    // A repo under the scanned root that references the plan.
+    let repo = dir.path().join("projects/api");
+    fs::create_dir_all(repo.join(".plandex-v2")).unwrap();
+    fs::write(
+        repo.join(".plandex-v2/current_plans_v2.json"),
+        r#"{"main":"plan-abc"}"#,
+    )
+    .unwrap();
+
+    let connector = PlandexConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "plandex");
+    assert_eq!(c.external_id.as_deref(), Some("plan-abc"));
+    assert_eq!(c.messages.len(), 2);
+    assert_eq!(c.messages[0].role, "user");
+    assert!(c.messages[1].content.contains("retry wrapper"));
+    assert_eq!(c.workspace.as_deref(), Some(repo.as_path()));
+    assert_eq!(c.metadata["org_id"], "org-1");
+    assert!(c.started_at.unwrap() < c.ended_at.unwrap());
+}
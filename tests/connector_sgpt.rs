@@ -0,0 +1,95 @@
+use std::fs;
+use tempfile::TempDir;
+
+use coding_agent_search::connectors::{Connector, ScanContext, sgpt::SgptConnector};
+use serial_test::serial;
+
+mod util;
+use util::EnvGuard;
+
+#[test]
+#[serial]
+fn sgpt_connector_reads_chat_cache_with_mtime_timestamps() {
+    let dir = TempDir::new().unwrap();
+    let cache = dir.path().join("chat_cache");
+    fs::create_dir_all(&cache).unwrap();
+    let sample = r#"[
+  {"role": "system", "content": "You are ShellGPT"},
+  {"role": "user", "content": "how do I list open ports on linux?"},
+  {"role": "assistant", "content": "Use `ss -tulpn` to list listening sockets."}
+]"#;
+    fs::write(cache.join("ports"), sample).unwrap();
+    // Stray non-chat files are ignored
+    fs::write(cache.join("notes"), "not json").unwrap();
+
+    let _env_guard = EnvGuard::set("CHAT_CACHE_PATH", cache.to_str().unwrap());
+
+    let connector = SgptConnector::new();
+    assert!(connector.detect().detected);
+    let ctx = ScanContext {
+        data_root: cache.clone(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "sgpt");
+    assert_eq!(c.external_id.as_deref(), Some("ports"));
+    assert_eq!(c.messages.len(), 3);
+    assert_eq!(c.messages[1].role, "user");
+    assert_eq!(c.messages[2].role, "assistant");
+    assert!(c.title.as_ref().unwrap().contains("open ports"));
+
+    let mtime = fs::metadata(cache.join("ports"))
+        .unwrap()
+        .modified()
+        .unwrap()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64;
+    assert_eq!(c.started_at, Some(mtime));
+    assert_eq!(c.ended_at, Some(mtime));
+    assert!(c.messages.iter().all(|m| m.created_at == Some(mtime)));
+}
+
+#[test]
+#[serial]
+fn sgpt_connector_skips_chats_older_than_since_ts() {
+    let dir = TempDir::new().unwrap();
+    let cache = dir.path().join("chat_cache");
+    fs::create_dir_all(&cache).unwrap();
+    fs::write(
+        cache.join("old"),
+        r#"[{"role": "user", "content": "hello"}]"#,
+    )
+    .unwrap();
+
+    let connector = SgptConnector::new();
+    let ctx = ScanContext {
+        data_root: cache,
+        since_ts: Some(i64::MAX / 2),
+    };
+    assert!(connector.scan(&ctx).unwrap().is_empty());
+}
+
+#[test]
+#[serial]
+fn sgpt_connector_skips_unreadable_chats() {
+    let dir = TempDir::new().unwrap();
+    let cache = dir.path().join("chat_cache");
+    fs::create_dir_all(&cache).unwrap();
+    fs::write(cache.join("binary"), [0xff, 0xfe, 0x00, 0x80]).unwrap();
+    fs::write(
+        cache.join("good"),
+        r#"[{"role": "user", "content": "hello"}]"#,
+    )
+    .unwrap();
+
+    let ctx = ScanContext {
+        data_root: cache,
+        since_ts: None,
+    };
+    let convs = SgptConnector::new().scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    assert_eq!(convs[0].external_id.as_deref(), Some("good"));
+}
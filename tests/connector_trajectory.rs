@@ -0,0 +1,67 @@
+use std::fs;
+use tempfile::TempDir;
+
+use coding_agent_search::connectors::{Connector, ScanContext, trajectory::TrajectoryConnector};
+
+#[test]
+fn trajectory_reads_swe_agent_steps_without_history() {
+    let dir = TempDir::new().unwrap();
+    let imports = TrajectoryConnector::import_dir(dir.path());
+    fs::create_dir_all(&imports).unwrap();
+    fs::write(
+        imports.join("astropy-12907.json"),
+        r#"{"trajectory": [
+  {"thought": "Find the separability code.", "action": "find_file separable.py", "observation": "Found 1 match", "response": "", "execution_time": 0.4},
+  {"thought": "", "response": "Submitting.", "action": "submit", "observation": ""}
+], "history": [], "info": {"instance_id": "astropy__astropy-12907", "exit_status": "submitted"}}"#,
+    )
+    .unwrap();
+
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = TrajectoryConnector::new().scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "trajectory");
+    assert_eq!(c.external_id.as_deref(), Some("astropy-12907"));
+    assert_eq!(c.title.as_deref(), Some("astropy__astropy-12907"));
+    assert_eq!(c.metadata["format"], "swe-agent");
+    assert_eq!(c.metadata["exit_status"], "submitted");
+    let roles: Vec<_> = c.messages.iter().map(|m| m.role.as_str()).collect();
+    assert_eq!(roles, ["assistant", "tool", "assistant"]);
+    assert!(
+        c.messages[0]
+            .content
+            .contains("[Action] find_file separable.py")
+    );
+    assert!(c.messages[2].content.starts_with("Submitting."));
+}
+
+#[test]
+fn trajectory_reads_event_logs() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("session-42.json");
+    fs::write(
+        &path,
+        r#"[
+  {"id": 0, "timestamp": "2025-03-01T12:00:00Z", "source": "user", "action": "message", "message": "Add a /healthz endpoint", "args": {"content": "Add a /healthz endpoint"}},
+  {"id": 1, "timestamp": "2025-03-01T12:00:05Z", "source": "agent", "action": "run", "message": "Running command: grep -rn routes", "args": {"command": "grep -rn routes"}},
+  {"id": 2, "timestamp": "2025-03-01T12:00:06Z", "source": "agent", "observation": "run", "content": "app/routes.py:3:router = APIRouter()", "cause": 1},
+  {"id": 3, "timestamp": "2025-03-01T12:00:09Z", "source": "agent", "action": "edit", "message": "", "args": {"path": "app/routes.py"}},
+  {"id": 4, "source": "environment", "observation": "agent_state_changed", "content": ""}
+]"#,
+    )
+    .unwrap();
+
+    let c = TrajectoryConnector::parse_file(&path).unwrap();
+    assert_eq!(c.metadata["format"], "events");
+    assert_eq!(c.title.as_deref(), Some("Add a /healthz endpoint"));
+    let roles: Vec<_> = c.messages.iter().map(|m| m.role.as_str()).collect();
+    assert_eq!(roles, ["user", "assistant", "tool", "assistant"]);
+    assert_eq!(c.messages[1].content, "Running command: grep -rn routes");
+    assert_eq!(c.messages[3].content, "[Tool: edit] app/routes.py");
+    assert_eq!(c.started_at, Some(1_740_830_400_000));
+    assert_eq!(c.ended_at, Some(1_740_830_409_000));
+}
@@ -0,0 +1,93 @@
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+use coding_agent_search::connectors::{Connector, ScanContext, vscode_chat::VsCodeChatConnector};
+
+#[test]
+fn vscode_chat_reads_workspace_sessions_with_folder() {
+    let dir = TempDir::new().unwrap();
+    let storage = dir.path().join("workspaceStorage/4f1c2b");
+    fs::create_dir_all(storage.join("chatSessions")).unwrap();
+    fs::write(
+        storage.join("workspace.json"),
+        r#"{"folder":"file:///home/dev/my%20service"}"#,
+    )
+    .unwrap();
+    let session = serde_json::json!({
+        "version": 3,
+        "sessionId": "a1b2c3",
+        "requesterUsername": "dev",
+        "responderUsername": "GitHub Copilot",
+        "initialLocation": "panel",
+        "creationDate": 1735000000000_i64,
+        "lastMessageDate": 1735000009000_i64,
+        "requests": [{
+            "requestId": "request_1",
+            "message": {"text": "why is the healthcheck timing out?", "parts": []},
+            "timestamp": 1735000000000_i64,
+            "modelId": "copilot/gpt-4.1",
+            "response": [
+                {"value": "The probe hits `/health` before the DB pool is ready. "},
+                {"kind": "toolInvocationSerialized", "toolId": "copilot_readFile", "invocationMessage": {"value": "Reading server.ts"}},
+                {"kind": "markdownContent", "content": {"value": "Delay the probe with initialDelaySeconds."}}
+            ],
+            "result": {"timings": {"firstProgress": 800, "totalElapsed": 4200}}
+        }]
+    });
+    fs::write(
+        storage.join("chatSessions/a1b2c3.json"),
+        serde_json::to_string(&session).unwrap(),
+    )
+    .unwrap();
+
+    let connector = VsCodeChatConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "vscode_chat");
+    assert_eq!(c.external_id.as_deref(), Some("a1b2c3"));
+    assert_eq!(c.workspace, Some(PathBuf::from("/home/dev/my service")));
+    assert_eq!(c.messages.len(), 2);
+    assert_eq!(c.messages[0].role, "user");
+    let answer = &c.messages[1];
+    assert_eq!(answer.role, "assistant");
+    assert_eq!(answer.author.as_deref(), Some("copilot/gpt-4.1"));
+    assert!(answer.content.contains("DB pool"));
+    assert!(
+        answer
+            .content
+            .contains("[Tool: copilot_readFile] Reading server.ts")
+    );
+    assert!(answer.content.contains("initialDelaySeconds"));
+    assert_eq!(answer.created_at, Some(1_735_000_004_200));
+    assert_eq!(c.started_at, Some(1_735_000_000_000));
+    assert_eq!(c.ended_at, Some(1_735_000_009_000));
+}
+
+#[test]
+fn vscode_chat_reads_empty_window_sessions_without_workspace() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir_all(dir.path().join("workspaceStorage")).unwrap();
+    let global = dir.path().join("globalStorage/emptyWindowChatSessions");
+    fs::create_dir_all(&global).unwrap();
+    fs::write(
+        global.join("loose.json"),
+        r#"{"sessionId":"loose","requests":[{"message":{"text":"regex for semver"},"timestamp":1735000000000,"response":[{"value":"^\\d+\\.\\d+\\.\\d+$"}]}]}"#,
+    )
+    .unwrap();
+
+    let connector = VsCodeChatConnector::new();
+    let ctx = ScanContext {
+        data_root: dir.path().to_path_buf(),
+        since_ts: None,
+    };
+    let convs = connector.scan(&ctx).unwrap();
+    assert_eq!(convs.len(), 1);
+    assert_eq!(convs[0].workspace, None);
+    assert_eq!(convs[0].title.as_deref(), Some("regex for semver"));
+}
@@ -0,0 +1,99 @@
+#![cfg(unix)]
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+fn cass_bin() -> String {
+    std::env::var("CARGO_BIN_EXE_cass")
+        .ok()
+        .unwrap_or_else(|| env!("CARGO_BIN_EXE_cass").to_string())
+}
+
+fn copy_dir(src: &Path, dst: &Path) {
+    std::fs::create_dir_all(dst).unwrap();
+    for entry in std::fs::read_dir(src).unwrap() {
+        let entry = entry.unwrap();
+        let target = dst.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            std::fs::copy(entry.path(), &target).unwrap();
+        }
+    }
+}
+
+fn search_json(data_dir: &Path, no_daemon: bool) -> std::process::Output {
+    let mut cmd = Command::new(cass_bin());
+    cmd.args(["--verbose", "search", "hello", "--json", "--data-dir"])
+        .arg(data_dir)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1");
+    if no_daemon {
+        cmd.env("CASS_NO_DAEMON", "1");
+    }
+    cmd.output().expect("run search")
+}
+
+#[test]
+fn search_delegates_to_running_daemon() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    copy_dir(Path::new("tests/fixtures/search_demo_data"), &data_dir);
+
+    let baseline = search_json(&data_dir, true);
+    assert!(baseline.status.success(), "in-process search should work");
+    assert!(String::from_utf8_lossy(&baseline.stderr).contains("search_start"));
+    let baseline: Value = serde_json::from_slice(&baseline.stdout).unwrap();
+
+    let mut daemon = Command::new(cass_bin())
+        .args(["daemon", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("spawn daemon");
+
+    let socket = data_dir.join("cass.sock");
+    let deadline = Instant::now() + Duration::from_secs(20);
+    while !socket.exists() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    assert!(socket.exists(), "daemon should create its socket");
+
+    let delegated = search_json(&data_dir, false);
+    // A rebuild under another schema leaves the running daemon stale: skip it.
+    let index = data_dir.join("index").join("v4");
+    std::fs::create_dir_all(&index).unwrap();
+    std::fs::write(
+        index.join("schema_hash.json"),
+        r#"{"schema_hash":"rebuilt-elsewhere"}"#,
+    )
+    .unwrap();
+    let after_rebuild = search_json(&data_dir, false);
+    daemon.kill().ok();
+    daemon.wait().ok();
+
+    assert!(
+        delegated.status.success(),
+        "delegated search should succeed: {}",
+        String::from_utf8_lossy(&delegated.stderr)
+    );
+    // The in-process client logs `search_start`; a delegated query never opens one.
+    let stderr = String::from_utf8_lossy(&delegated.stderr);
+    assert!(
+        !stderr.contains("search_start"),
+        "search should have been served by the daemon: {stderr}"
+    );
+    let delegated: Value = serde_json::from_slice(&delegated.stdout).unwrap();
+    assert_eq!(delegated["count"], baseline["count"]);
+    assert_eq!(delegated["hits"], baseline["hits"]);
+
+    assert!(
+        String::from_utf8_lossy(&after_rebuild.stderr).contains("search_start"),
+        "a stale daemon should be bypassed"
+    );
+}
@@ -647,3 +647,72 @@ fn filter_by_days() {
         );
     }
 }
+
+/// Test: --since/--until accept date math, and reject what they cannot read
+#[test]
+fn filter_by_date_math_expressions() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let _guard_home = EnvGuard::set("HOME", home.to_string_lossy());
+    let _guard_codex = EnvGuard::set("CODEX_HOME", codex_home.to_string_lossy());
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+    let thirty_days_ago = now - (30 * 24 * 60 * 60 * 1000);
+    make_codex_session_at(
+        &codex_home,
+        "2024/12/01",
+        "rollout-recent.jsonl",
+        "recentsession mathtest",
+        now,
+    );
+    make_codex_session_at(
+        &codex_home,
+        "2024/11/01",
+        "rollout-old.jsonl",
+        "oldsession mathtest",
+        thirty_days_ago,
+    );
+
+    cargo_bin_cmd!("cass")
+        .args(["index", "--full", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODEX_HOME", &codex_home)
+        .env("HOME", home)
+        .assert()
+        .success();
+
+    let search = |flag: &str, value: &str| {
+        cargo_bin_cmd!("cass")
+            .args(["search", "mathtest", flag, value, "--robot", "--data-dir"])
+            .arg(&data_dir)
+            .env("HOME", home)
+            .output()
+            .expect("search command")
+    };
+    for (flag, value, expected) in [
+        ("--since", "2 weeks ago", "recentsession"),
+        ("--since", "7d", "recentsession"),
+        ("--until", "1w", "oldsession"),
+    ] {
+        let output = search(flag, value);
+        assert!(output.status.success(), "{flag} {value}: {output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid json");
+        let hits = json["hits"].as_array().expect("hits array");
+        assert!(!hits.is_empty(), "{flag} {value} should match");
+        for hit in hits {
+            let content = hit["content"].as_str().unwrap_or("");
+            assert!(content.contains(expected), "{flag} {value}: {content}");
+        }
+    }
+
+    let output = search("--since", "last blursday");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid-time"));
+}
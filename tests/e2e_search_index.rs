@@ -659,6 +659,432 @@ fn search_returns_pagination_info() {
     assert!(hits <= 3, "Returned hits should be <= limit");
 }
 
+/// Test: --group-by conversation returns one row per conversation
+#[test]
+fn search_groups_hits_by_conversation() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let _guard_home = EnvGuard::set("HOME", home.to_string_lossy());
+    let _guard_codex = EnvGuard::set("CODEX_HOME", codex_home.to_string_lossy());
+
+    for i in 1..=3 {
+        make_codex_session(
+            &codex_home,
+            "2024/11/20",
+            &format!("rollout-group{i}.jsonl"),
+            &format!("groupingterm session{i}"),
+            1732118400000 + (i as u64 * 10_000),
+        );
+    }
+
+    cargo_bin_cmd!("cass")
+        .args(["index", "--full", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODEX_HOME", &codex_home)
+        .env("HOME", home)
+        .assert()
+        .success();
+
+    let search = |extra: &[&str]| -> serde_json::Value {
+        let output = cargo_bin_cmd!("cass")
+            .args([
+                "search",
+                "groupingterm",
+                "--robot",
+                "--group-by",
+                "conversation",
+            ])
+            .args(extra)
+            .arg("--data-dir")
+            .arg(&data_dir)
+            .env("HOME", home)
+            .output()
+            .expect("search command");
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).expect("valid JSON")
+    };
+
+    let json = search(&[]);
+    let rows = json["hits"].as_array().expect("hits");
+    assert_eq!(rows.len(), 3, "one row per conversation");
+    assert_eq!(json["total_matches"], 3);
+    let mut conversations: Vec<_> = rows.iter().map(|r| r["conversation_id"].clone()).collect();
+    conversations.dedup();
+    assert_eq!(conversations.len(), 3);
+    for row in rows {
+        // Each session holds a matching user message and response.
+        assert_eq!(row["group_count"], 2);
+        let others = row["group_hits"].as_array().expect("group_hits");
+        assert_eq!(others.len(), 1);
+        assert_eq!(others[0]["conversation_id"], row["conversation_id"]);
+    }
+
+    let json = search(&["--group-size", "1", "--limit", "2"]);
+    assert_eq!(json["hits"].as_array().unwrap().len(), 2);
+    assert_eq!(json["hits"][0]["group_hits"].as_array().unwrap().len(), 0);
+    assert!(
+        json["next_cursor"].is_string(),
+        "a third conversation follows"
+    );
+}
+
+/// Test: switching `[index] analyzer` to english rebuilds the index with stemming
+#[test]
+fn english_analyzer_matches_inflections_after_reindex() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+    let config = home.join("config.toml");
+    fs::write(&config, "").unwrap();
+
+    make_codex_session(
+        &codex_home,
+        "2024/11/20",
+        "rollout-stem.jsonl",
+        "kept retrying the upload",
+        1732118400000,
+    );
+
+    let index = || {
+        cargo_bin_cmd!("cass")
+            .args(["index", "--data-dir"])
+            .arg(&data_dir)
+            .env("CODEX_HOME", &codex_home)
+            .env("HOME", home)
+            .env("CASS_CONFIG", &config)
+            .assert()
+            .success();
+    };
+    let hit_count = |query: &str| {
+        let output = cargo_bin_cmd!("cass")
+            .args(["search", query, "--robot", "--data-dir"])
+            .arg(&data_dir)
+            .env("HOME", home)
+            .env("CASS_CONFIG", &config)
+            .output()
+            .expect("search command");
+        assert!(output.status.success(), "{output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["hits"].as_array().unwrap().len()
+    };
+
+    let schema_hash =
+        || fs::read_to_string(data_dir.join("index/v4/schema_hash.json")).unwrap_or_default();
+    index();
+    assert!(!schema_hash().contains("english"));
+
+    fs::write(&config, "[index]\nanalyzer = \"english\"\n").unwrap();
+    // No --full: the changed schema hash alone makes `cass index` rebuild.
+    index();
+    assert!(schema_hash().contains("+english"), "{}", schema_hash());
+    assert!(hit_count("retries") > 0);
+    assert!(hit_count("retry") > 0);
+}
+
+/// Test: --save stores a search that --saved runs again, filters included
+#[test]
+fn saved_searches_rerun_query_and_filters() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let claude_home = home.join(".claude");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    make_codex_session(
+        &codex_home,
+        "2024/11/20",
+        "rollout-saved.jsonl",
+        "nightly build error",
+        1732118400000,
+    );
+    make_claude_session(
+        &claude_home,
+        "proj",
+        "saved.jsonl",
+        "nightly build error",
+        "2024-11-20T10:00:00Z",
+    );
+    cargo_bin_cmd!("cass")
+        .args(["index", "--full", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODEX_HOME", &codex_home)
+        .env("HOME", home)
+        .assert()
+        .success();
+
+    let search = |args: &[&str]| {
+        cargo_bin_cmd!("cass")
+            .arg("search")
+            .args(args)
+            .args(["--robot", "--data-dir"])
+            .arg(&data_dir)
+            .env("HOME", home)
+            .output()
+            .expect("search command")
+    };
+    let agents = |output: &std::process::Output| {
+        assert!(output.status.success(), "{output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["hits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|h| h["agent"].as_str().unwrap().to_string())
+            .collect::<std::collections::BTreeSet<_>>()
+    };
+
+    let saved = search(&["nightly", "--agent", "codex", "--save", "nightly-errors"]);
+    assert_eq!(agents(&saved), ["codex".to_string()].into());
+    assert!(data_dir.join("saved_searches.json").exists());
+
+    let rerun = search(&["--saved", "nightly-errors"]);
+    assert_eq!(agents(&rerun), agents(&saved));
+    // A filter on the command line replaces the saved one.
+    let widened = search(&["--saved", "nightly-errors", "--agent", "claude_code"]);
+    assert_eq!(agents(&widened), ["claude_code".to_string()].into());
+
+    let missing = search(&["--saved", "nope"]);
+    assert_eq!(missing.status.code(), Some(4), "{missing:?}");
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("nightly-errors"));
+}
+
+/// Test: the same messages captured by two connectors show up once unless --dedupe=false
+#[test]
+fn duplicate_messages_across_connectors_collapse() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let claude_home = home.join(".claude");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    // Same words, rendered differently by each connector.
+    make_codex_session(
+        &codex_home,
+        "2024/11/20",
+        "rollout-dupe.jsonl",
+        "Flaky webhook retry!",
+        1732118400000,
+    );
+    make_claude_session(
+        &claude_home,
+        "proj",
+        "dupe.jsonl",
+        "flaky webhook retry",
+        "2024-11-20T10:00:00Z",
+    );
+    cargo_bin_cmd!("cass")
+        .args(["index", "--full", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODEX_HOME", &codex_home)
+        .env("HOME", home)
+        .assert()
+        .success();
+
+    let hits = |extra: &[&str]| {
+        let output = cargo_bin_cmd!("cass")
+            .args(["search", "webhook", "--robot", "--data-dir"])
+            .arg(&data_dir)
+            .args(extra)
+            .env("HOME", home)
+            .output()
+            .expect("search command");
+        assert!(output.status.success(), "{output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["hits"].as_array().unwrap().len()
+    };
+
+    // A user message and a response per session.
+    assert_eq!(hits(&[]), 2);
+    assert_eq!(hits(&["--dedupe"]), 2);
+    assert_eq!(hits(&["--dedupe=false"]), 4);
+}
+
+/// Test: [ranking] boosts decide whether title or content matches rank first
+#[test]
+fn ranking_boosts_from_config_reorder_hits() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+    let config = home.join("config.toml");
+
+    // Titled after its first message, so every message of it matches in the title.
+    make_codex_session(
+        &codex_home,
+        "2024/11/20",
+        "rollout-titled.jsonl",
+        "kubectl deploy",
+        1732118400000,
+    );
+    make_codex_session(
+        &codex_home,
+        "2024/11/20",
+        "rollout-body.jsonl",
+        "status check",
+        1732118400000,
+    );
+    append_codex_session(
+        &codex_home.join("sessions/2024/11/20/rollout-body.jsonl"),
+        "deploy the deploy pipeline, then deploy again",
+        1732118500000,
+    );
+    cargo_bin_cmd!("cass")
+        .args(["index", "--full", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODEX_HOME", &codex_home)
+        .env("HOME", home)
+        .assert()
+        .success();
+
+    let top_hit = |ranking: &str| {
+        fs::write(&config, format!("[ranking]\n{ranking}\n")).unwrap();
+        let output = cargo_bin_cmd!("cass")
+            .args(["search", "deploy", "--robot", "--data-dir"])
+            .arg(&data_dir)
+            .env("HOME", home)
+            .env("CASS_CONFIG", &config)
+            .output()
+            .expect("search command");
+        assert!(output.status.success(), "{output:?}");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["hits"][0]["source_path"].as_str().unwrap().to_string()
+    };
+
+    assert!(top_hit("title_boost = 10.0").ends_with("rollout-titled.jsonl"));
+    assert!(
+        top_hit("title_boost = 0.1\nprefix_boost = 0.1\ncontent_boost = 5.0")
+            .ends_with("rollout-body.jsonl")
+    );
+}
+
+/// Test: Configured synonym groups widen queries without reindexing
+#[test]
+fn synonyms_from_config_expand_queries() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+    let config = home.join("config.toml");
+    fs::write(&config, "").unwrap();
+
+    make_codex_session(
+        &codex_home,
+        "2024/11/20",
+        "rollout-syn.jsonl",
+        "the kubernetes rollout stalled",
+        1732118400000,
+    );
+    cargo_bin_cmd!("cass")
+        .args(["index", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODEX_HOME", &codex_home)
+        .env("HOME", home)
+        .env("CASS_CONFIG", &config)
+        .assert()
+        .success();
+
+    let search = |query: &str| {
+        let output = cargo_bin_cmd!("cass")
+            .args(["search", query, "--robot", "--data-dir"])
+            .arg(&data_dir)
+            .env("HOME", home)
+            .env("CASS_CONFIG", &config)
+            .output()
+            .expect("search command");
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    assert_eq!(search("k8s")["hits"].as_array().unwrap().len(), 0);
+
+    fs::write(&config, "synonyms = [[\"k8s\", \"kubernetes\"]]\n").unwrap();
+    let json = search("k8s rollout");
+    let hits = json["hits"].as_array().unwrap();
+    assert!(!hits.is_empty(), "{json}");
+    assert!(
+        hits[0]["snippet"]
+            .as_str()
+            .unwrap()
+            .contains("**kubernetes**"),
+        "{json}"
+    );
+}
+
+/// Test: Following next_cursor walks every hit exactly once
+#[test]
+fn search_cursor_pages_through_all_hits() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let _guard_home = EnvGuard::set("HOME", home.to_string_lossy());
+    let _guard_codex = EnvGuard::set("CODEX_HOME", codex_home.to_string_lossy());
+
+    for i in 1..=4 {
+        make_codex_session(
+            &codex_home,
+            "2024/11/20",
+            &format!("rollout-cursor{i}.jsonl"),
+            &format!("cursorpaging session{i}"),
+            1732118400000 + (i as u64 * 10_000),
+        );
+    }
+
+    cargo_bin_cmd!("cass")
+        .args(["index", "--full", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODEX_HOME", &codex_home)
+        .env("HOME", home)
+        .assert()
+        .success();
+
+    let mut seen = Vec::new();
+    let mut cursor: Option<String> = None;
+    let mut total_hits = None;
+    for _ in 0..10 {
+        let mut cmd = cargo_bin_cmd!("cass");
+        cmd.args(["search", "cursorpaging", "--robot", "--data-dir"])
+            .arg(&data_dir)
+            .env("HOME", home);
+        match &cursor {
+            Some(c) => cmd.args(["--cursor", c]),
+            None => cmd.args(["--limit", "3"]),
+        };
+        let output = cmd.output().expect("search command");
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+
+        total_hits = json["total_hits"].as_u64();
+        for hit in json["hits"].as_array().expect("hits") {
+            seen.push(hit["content"].as_str().unwrap_or_default().to_string());
+        }
+        match json["next_cursor"].as_str() {
+            Some(next) => cursor = Some(next.to_string()),
+            None => break,
+        }
+    }
+
+    // Each session holds a user message and a response, both matching.
+    assert_eq!(seen.len(), 8, "pages: {seen:?}");
+    let mut unique = seen.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), seen.len(), "pages overlap: {seen:?}");
+    assert_eq!(total_hits, Some(8));
+}
+
 /// Test: Force rebuild recreates index
 #[test]
 fn force_rebuild_recreates_index() {
@@ -951,3 +1377,127 @@ fn empty_query_returns_recent() {
         "Empty query should not crash"
     );
 }
+
+/// Serve an OpenAI-style embeddings endpoint on localhost: each input becomes a
+/// count of its words from two topics, so texts about the same topic score alike.
+fn spawn_embeddings_endpoint() -> String {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/v1/embeddings", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = v.trim().parse().unwrap_or(0);
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            let data: Vec<_> = request["input"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .enumerate()
+                .map(|(index, text)| {
+                    let text = text.as_str().unwrap().to_lowercase();
+                    let count =
+                        |words: &[&str]| words.iter().filter(|w| text.contains(*w)).count() as f32;
+                    serde_json::json!({
+                        "index": index,
+                        "embedding": [count(&["auth", "login", "token"]), count(&["race", "mutex", "lock"])],
+                    })
+                })
+                .collect();
+            let response = serde_json::json!({ "data": data }).to_string();
+            let mut stream = reader.into_inner();
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            );
+        }
+    });
+    url
+}
+
+/// Test: `cass index` embeds messages and `search --semantic` ranks them by meaning
+#[test]
+fn semantic_search_ranks_by_embedding_similarity() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+    let config = home.join("config.toml");
+    fs::write(
+        &config,
+        format!(
+            "[embeddings]\nendpoint = \"{}\"\nmodel = \"words\"\n",
+            spawn_embeddings_endpoint()
+        ),
+    )
+    .unwrap();
+
+    make_codex_session(
+        &codex_home,
+        "2024/11/20",
+        "rollout-auth.jsonl",
+        "refreshed the login token",
+        1732118400000,
+    );
+    make_codex_session(
+        &codex_home,
+        "2024/11/21",
+        "rollout-race.jsonl",
+        "wrapped the cache in a mutex",
+        1732204800000,
+    );
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["index", "--full", "--json", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODEX_HOME", &codex_home)
+        .env("HOME", home)
+        .env("CASS_CONFIG", &config)
+        .output()
+        .expect("index command");
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["embedded"], 4, "{json}");
+
+    let output = cargo_bin_cmd!("cass")
+        .args(["search", "fixing an auth problem", "--semantic", "--json"])
+        .args(["--limit", "2", "--data-dir"])
+        .arg(&data_dir)
+        .env("HOME", home)
+        .env("CASS_CONFIG", &config)
+        .output()
+        .expect("search command");
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let hits = json["hits"].as_array().unwrap();
+    assert_eq!(hits.len(), 2);
+    for hit in hits {
+        assert!(hit["content"].as_str().unwrap().contains("login token"));
+        assert_eq!(hit["match_type"], "semantic");
+    }
+
+    // Without [embeddings] the flag fails with a structured error.
+    let output = cargo_bin_cmd!("cass")
+        .args(["search", "auth", "--semantic", "--json", "--data-dir"])
+        .arg(&data_dir)
+        .env("HOME", home)
+        .env("CASS_CONFIG", home.join("missing.toml"))
+        .output()
+        .expect("search command");
+    assert_eq!(output.status.code(), Some(9));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("semantic-search"));
+}
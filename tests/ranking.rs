@@ -1,18 +1,20 @@
-use coding_agent_search::search::query::{MatchType, SearchHit};
-
-// Utility: reproduce ranking blend used in the TUI without touching tui.rs
-fn blended_score(hit: &SearchHit, max_created: i64, alpha: f32) -> f32 {
-    let recency = if max_created > 0 {
-        hit.created_at.unwrap_or(0) as f32 / max_created as f32
-    } else {
-        0.0
+use coding_agent_search::search::query::{MatchType, RecencyDecay, SearchHit};
+
+const NOW: i64 = 1_700_000_000_000;
+const DAY_MS: i64 = 86_400_000;
+
+// Utility: the engine's recency decay (default half-life) times the TUI's quality factor
+fn blended_score(hit: &SearchHit, now: i64, alpha: f32) -> f32 {
+    let decay = RecencyDecay {
+        weight: alpha,
+        half_life_days: 30.0,
     };
-    hit.score * hit.match_type.quality_factor() + alpha * recency
+    hit.score * hit.match_type.quality_factor() * decay.factor(now, hit.created_at)
 }
 
 #[test]
 fn exact_hits_rank_above_wildcards_at_equal_recency_and_score() {
-    let max_created = 2_000_000;
+    let max_created = NOW;
     let alpha = 0.4; // Balanced mode in TUI
 
     let exact = SearchHit {
@@ -26,6 +28,9 @@ fn exact_hits_rank_above_wildcards_at_equal_recency_and_score() {
         created_at: Some(max_created),
         line_number: None,
         match_type: MatchType::Exact,
+        snippets: Vec::new(),
+        conversation_id: String::new(),
+        role: String::new(),
     };
 
     let prefix = SearchHit {
@@ -71,9 +76,12 @@ fn recency_boost_can_outweigh_quality_when_far_newer() {
         source_path: "p1".into(),
         agent: "a".into(),
         workspace: "w".into(),
-        created_at: Some(1_000_000),
+        created_at: Some(NOW - 180 * DAY_MS),
         line_number: None,
         match_type: MatchType::Exact,
+        snippets: Vec::new(),
+        conversation_id: String::new(),
+        role: String::new(),
     };
 
     let newer_suffix = SearchHit {
@@ -84,14 +92,16 @@ fn recency_boost_can_outweigh_quality_when_far_newer() {
         source_path: "p2".into(),
         agent: "a".into(),
         workspace: "w".into(),
-        created_at: Some(2_000_000),
+        created_at: Some(NOW),
         line_number: None,
         match_type: MatchType::Suffix, // quality factor 0.8 vs 1.0
+        snippets: Vec::new(),
+        conversation_id: String::new(),
+        role: String::new(),
     };
 
-    let max_created = newer_suffix.created_at.unwrap();
-    let older_score = blended_score(&older_exact, max_created, alpha);
-    let newer_score = blended_score(&newer_suffix, max_created, alpha);
+    let older_score = blended_score(&older_exact, NOW, alpha);
+    let newer_score = blended_score(&newer_suffix, NOW, alpha);
 
     assert!(
         newer_score > older_score,
@@ -103,7 +113,7 @@ fn recency_boost_can_outweigh_quality_when_far_newer() {
 fn relevance_heavy_mode_prefers_quality_over_recency() {
     // With RelevanceHeavy alpha (0.1), quality factor matters more than recency.
     let alpha = 0.1; // RelevanceHeavy mode
-    let max_created = 2_000_000;
+    let max_created = NOW;
 
     let older_exact = SearchHit {
         title: "old_exact".into(),
@@ -113,9 +123,12 @@ fn relevance_heavy_mode_prefers_quality_over_recency() {
         source_path: "p1".into(),
         agent: "a".into(),
         workspace: "w".into(),
-        created_at: Some(500_000), // Much older
+        created_at: Some(NOW - 180 * DAY_MS), // Much older
         line_number: None,
         match_type: MatchType::Exact, // quality factor 1.0
+        snippets: Vec::new(),
+        conversation_id: String::new(),
+        role: String::new(),
     };
 
     let newer_substring = SearchHit {
@@ -129,13 +142,16 @@ fn relevance_heavy_mode_prefers_quality_over_recency() {
         created_at: Some(max_created), // Most recent
         line_number: None,
         match_type: MatchType::Substring, // quality factor 0.7
+        snippets: Vec::new(),
+        conversation_id: String::new(),
+        role: String::new(),
     };
 
     let older_score = blended_score(&older_exact, max_created, alpha);
     let newer_score = blended_score(&newer_substring, max_created, alpha);
 
-    // With low alpha, exact match (1.0 * 1.0 = 1.0) + small recency should beat
-    // substring (1.0 * 0.7 = 0.7) + full recency
+    // With low alpha, exact match (1.0 * 1.0 = 1.0) with no recency left should beat
+    // substring (1.0 * 0.7 = 0.7) with full recency (x1.1)
     assert!(
         older_score > newer_score,
         "relevance-heavy: older exact ({older_score}) should beat newer substring ({newer_score})"
@@ -146,7 +162,7 @@ fn relevance_heavy_mode_prefers_quality_over_recency() {
 fn match_quality_heavy_mode_balances_quality_and_recency() {
     // MatchQualityHeavy uses alpha=0.2, moderate recency influence.
     let alpha = 0.2;
-    let max_created = 2_000_000;
+    let max_created = NOW;
 
     let exact = SearchHit {
         title: "exact".into(),
@@ -159,6 +175,9 @@ fn match_quality_heavy_mode_balances_quality_and_recency() {
         created_at: Some(max_created),
         line_number: None,
         match_type: MatchType::Exact,
+        snippets: Vec::new(),
+        conversation_id: String::new(),
+        role: String::new(),
     };
 
     let implicit = SearchHit {
@@ -176,18 +195,18 @@ fn match_quality_heavy_mode_balances_quality_and_recency() {
         "match-quality: exact ({exact_score}) should beat implicit ({implicit_score})"
     );
 
-    // The gap should be roughly 0.4 (quality difference) at same recency
+    // The gap is the quality difference scaled by the shared recency: 0.4 * 1.2 = 0.48
     let gap = exact_score - implicit_score;
     assert!(
         gap > 0.3 && gap < 0.5,
-        "quality gap should be ~0.4, got {gap}"
+        "quality gap should be ~0.48, got {gap}"
     );
 }
 
 #[test]
 fn ranking_handles_missing_created_at() {
     // Hits without created_at should still rank based on score * quality_factor
-    let max_created = 2_000_000;
+    let max_created = NOW;
     let alpha = 0.4;
 
     let hit_with_date = SearchHit {
@@ -201,6 +220,9 @@ fn ranking_handles_missing_created_at() {
         created_at: Some(max_created),
         line_number: None,
         match_type: MatchType::Prefix, // quality factor 0.9
+        snippets: Vec::new(),
+        conversation_id: String::new(),
+        role: String::new(),
     };
 
     let hit_without_date = SearchHit {
@@ -214,13 +236,16 @@ fn ranking_handles_missing_created_at() {
         created_at: None, // Missing date
         line_number: None,
         match_type: MatchType::Exact, // quality factor 1.0
+        snippets: Vec::new(),
+        conversation_id: String::new(),
+        role: String::new(),
     };
 
     let with_date_score = blended_score(&hit_with_date, max_created, alpha);
     let no_date_score = blended_score(&hit_without_date, max_created, alpha);
 
-    // No date means recency = 0, so score = 1.0 * 1.0 + 0 = 1.0
-    // With date at max: score = 1.0 * 0.9 + 0.4 * 1.0 = 1.3
+    // No date means no recency boost, so score = 1.0 * 1.0 = 1.0
+    // With date at now: score = 1.0 * 0.9 * (1 + 0.4) = 1.26
     // The hit with date + recency should win despite lower quality
     assert!(
         with_date_score > no_date_score,
@@ -229,10 +254,10 @@ fn ranking_handles_missing_created_at() {
 }
 
 #[test]
-fn ranking_handles_zero_max_created() {
-    // Edge case: when max_created is 0, recency should be 0
-    let max_created = 0;
-    let alpha = 0.4;
+fn ranking_handles_zero_weight_and_future_timestamps() {
+    // Edge cases: a zero weight leaves the score alone, and a timestamp past `now`
+    // (clock skew) counts as brand new rather than more than new
+    let alpha = 0.0;
 
     let hit = SearchHit {
         title: "t".into(),
@@ -242,16 +267,25 @@ fn ranking_handles_zero_max_created() {
         source_path: "p".into(),
         agent: "a".into(),
         workspace: "w".into(),
-        created_at: Some(1_000_000),
+        created_at: Some(NOW + DAY_MS),
         line_number: None,
         match_type: MatchType::Exact,
+        snippets: Vec::new(),
+        conversation_id: String::new(),
+        role: String::new(),
     };
 
-    let score = blended_score(&hit, max_created, alpha);
-    // recency = 0 (because max_created=0), so score = 2.0 * 1.0 + 0 = 2.0
+    let score = blended_score(&hit, NOW, alpha);
+    // No recency weight, so score = 2.0 * 1.0 * 1.0 = 2.0
     assert!(
         (score - 2.0).abs() < 0.001,
-        "score with max_created=0 should be just score*quality: {score}"
+        "score with zero weight should be just score*quality: {score}"
+    );
+
+    let score = blended_score(&hit, NOW, 0.4);
+    assert!(
+        (score - 2.8).abs() < 0.001,
+        "a future timestamp should get at most 1+weight: {score}"
     );
 }
 
@@ -259,7 +293,7 @@ fn ranking_handles_zero_max_created() {
 fn all_ranking_modes_maintain_quality_ordering_at_equal_inputs() {
     // At equal recency and Tantivy score, all modes should preserve quality ordering:
     // Exact > Prefix > Suffix > Substring > ImplicitWildcard
-    let max_created = 1_000_000;
+    let max_created = NOW;
     let alphas = [1.0, 0.4, 0.2, 0.1]; // RecentHeavy, Balanced, MatchQuality, Relevance
 
     for alpha in alphas {
@@ -274,6 +308,9 @@ fn all_ranking_modes_maintain_quality_ordering_at_equal_inputs() {
             created_at: Some(max_created),
             line_number: None,
             match_type: MatchType::Exact,
+            snippets: Vec::new(),
+            conversation_id: String::new(),
+            role: String::new(),
         };
 
         let exact_score = blended_score(&base, max_created, alpha);
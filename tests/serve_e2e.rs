@@ -0,0 +1,169 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+fn cass_bin() -> String {
+    std::env::var("CARGO_BIN_EXE_cass")
+        .ok()
+        .unwrap_or_else(|| env!("CARGO_BIN_EXE_cass").to_string())
+}
+
+fn copy_dir(src: &Path, dst: &Path) {
+    std::fs::create_dir_all(dst).unwrap();
+    for entry in std::fs::read_dir(src).unwrap() {
+        let entry = entry.unwrap();
+        let target = dst.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            std::fs::copy(entry.path(), &target).unwrap();
+        }
+    }
+}
+
+/// Send one HTTP/1.0 request and return (status, headers, body).
+fn request(addr: &str, head: &str) -> (u16, String, String) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{head}\r\nHost: {addr}\r\nConnection: close\r\n\r\n"
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (headers, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = headers[9..12].parse().unwrap();
+    (status, headers.to_string(), body.to_string())
+}
+
+/// Start `cass serve` with `args` on a free port, returning it with its address.
+fn spawn_server(data_dir: &Path, args: &[&str]) -> (Child, String) {
+    let mut server = Command::new(cass_bin())
+        .args(["serve", "--port", "0"])
+        .args(args)
+        .arg("--data-dir")
+        .arg(data_dir)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .env("CASS_NO_DAEMON", "1")
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn server");
+    // Logs may come first; the banner names the bound address.
+    let addr = BufReader::new(server.stderr.take().unwrap())
+        .lines()
+        .map_while(Result::ok)
+        .find_map(|line| Some(line.split_once("listening on http://")?.1.to_string()))
+        .expect("listening address");
+    (server, addr)
+}
+
+#[test]
+fn serve_answers_with_robot_json() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    copy_dir(Path::new("tests/fixtures/search_demo_data"), &data_dir);
+
+    let (mut server, addr) = spawn_server(
+        &data_dir,
+        &[
+            "--read-only",
+            "--token",
+            "s3cret",
+            "--cors-origin",
+            "https://team.example",
+        ],
+    );
+    let auth = "Authorization: Bearer s3cret";
+
+    let (status, _, body) = request(&addr, "GET /health HTTP/1.0");
+    let health: Value = serde_json::from_str(&body).unwrap();
+    let (unauthorized, _, _) = request(&addr, "GET /stats HTTP/1.0");
+    let (search_status, headers, search_body) = request(
+        &addr,
+        &format!("GET /search?q=hello&limit=3 HTTP/1.0\r\n{auth}\r\nOrigin: https://team.example"),
+    );
+    let (bad_param, _, _) = request(
+        &addr,
+        &format!("GET /search?q=hello&save=mine HTTP/1.0\r\n{auth}"),
+    );
+    let (missing, _, missing_body) = request(
+        &addr,
+        &format!("GET /show?path=/no/such/session.jsonl HTTP/1.0\r\n{auth}"),
+    );
+    let (indexed, _, indexed_body) = request(
+        &addr,
+        &format!(
+            "GET /show?path=tests/fixtures/search_demo_data/gemini_logs/sessionA/session1.jsonl HTTP/1.0\r\n{auth}"
+        ),
+    );
+    let (read_only, _, _) = request(&addr, &format!("POST /index HTTP/1.0\r\n{auth}"));
+    server.kill().ok();
+    server.wait().ok();
+
+    assert_eq!(status, 200);
+    assert_eq!(health["status"], "ok");
+    assert_eq!(unauthorized, 401);
+    assert_eq!(search_status, 200, "{search_body}");
+    assert!(headers.contains("Access-Control-Allow-Origin: https://team.example"));
+
+    let cli = Command::new(cass_bin())
+        .args(["search", "hello", "--robot", "--limit", "3", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .env("CASS_NO_DAEMON", "1")
+        .output()
+        .unwrap();
+    let cli: Value = serde_json::from_slice(&cli.stdout).unwrap();
+    let served: Value = serde_json::from_str(&search_body).unwrap();
+    assert!(served["count"].as_u64().unwrap() > 0);
+    assert_eq!(served["hits"], cli["hits"]);
+
+    assert_eq!(bad_param, 400);
+    assert_eq!(missing, 404);
+    let missing: Value = serde_json::from_str(&missing_body).unwrap();
+    assert_eq!(missing["error"]["kind"], "not_found");
+    // The fixture indexes this path without shipping the file: it passes the index
+    // check and reaches `cass show`, which reports the file missing.
+    assert_eq!(indexed, 404);
+    let indexed: Value = serde_json::from_str(&indexed_body).unwrap();
+    assert_eq!(indexed["error"]["kind"], "file-not-found");
+    assert_eq!(read_only, 403);
+}
+
+#[test]
+fn serve_only_shows_indexed_sessions_and_needs_a_token_to_write() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    copy_dir(Path::new("tests/fixtures/search_demo_data"), &data_dir);
+    let secret = tmp.path().join("secret.jsonl");
+    std::fs::write(&secret, "{\"role\":\"user\",\"content\":\"private\"}\n").unwrap();
+
+    let (mut server, addr) = spawn_server(&data_dir, &[]);
+    let (unindexed, _, unindexed_body) = request(
+        &addr,
+        &format!("GET /show?path={} HTTP/1.0", secret.display()),
+    );
+    let (reindex, _, reindex_body) = request(&addr, "POST /index HTTP/1.0");
+    server.kill().ok();
+    server.wait().ok();
+
+    assert_eq!(unindexed, 404);
+    assert!(!unindexed_body.contains("private"));
+    assert_eq!(reindex, 403);
+    let reindex: Value = serde_json::from_str(&reindex_body).unwrap();
+    assert_eq!(reindex["error"]["kind"], "token_required");
+
+    let public = Command::new(cass_bin())
+        .args(["serve", "--port", "0", "--host", "0.0.0.0", "--data-dir"])
+        .arg(&data_dir)
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .env_remove("CASS_SERVE_TOKEN")
+        .output()
+        .unwrap();
+    assert_eq!(public.status.code(), Some(2));
+}
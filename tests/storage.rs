@@ -249,6 +249,43 @@ fn last_scan_ts_overwrite() {
     assert_eq!(storage.get_last_scan_ts().unwrap(), Some(20));
 }
 
+#[test]
+fn workspace_message_counts_busiest_first() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("ws_counts.db");
+    let mut storage = SqliteStorage::open(&db_path).expect("open");
+    let agent_id = storage.ensure_agent(&sample_agent()).unwrap();
+
+    let quiet = storage
+        .ensure_workspace(&PathBuf::from("/ws/quiet"), None)
+        .unwrap();
+    let busy = storage
+        .ensure_workspace(&PathBuf::from("/ws/busy"), None)
+        .unwrap();
+    storage
+        .ensure_workspace(&PathBuf::from("/ws/empty"), None)
+        .unwrap();
+    storage
+        .insert_conversation_tree(
+            agent_id,
+            Some(quiet),
+            &sample_conv(Some("q"), vec![msg(0, 1)]),
+        )
+        .unwrap();
+    storage
+        .insert_conversation_tree(
+            agent_id,
+            Some(busy),
+            &sample_conv(Some("b"), vec![msg(0, 1), msg(1, 2), msg(2, 3)]),
+        )
+        .unwrap();
+
+    assert_eq!(
+        storage.workspace_message_counts().unwrap(),
+        vec![("/ws/busy".to_string(), 3), ("/ws/quiet".to_string(), 1)]
+    );
+}
+
 #[test]
 fn unsupported_schema_version_errors() {
     let tmp = tempfile::TempDir::new().unwrap();
@@ -738,3 +775,23 @@ fn pragmas_are_applied() {
         .unwrap();
     assert_eq!(fk, 1, "foreign_keys should be ON");
 }
+
+#[test]
+fn agent_message_counts_per_slug() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("agent_counts.db");
+    let mut storage = SqliteStorage::open(&db_path).expect("open");
+    let agent_id = storage.ensure_agent(&sample_agent()).unwrap();
+    storage
+        .insert_conversation_tree(
+            agent_id,
+            None,
+            &sample_conv(Some("a"), vec![msg(0, 1), msg(1, 2)]),
+        )
+        .unwrap();
+
+    assert_eq!(
+        storage.agent_message_counts().unwrap(),
+        vec![("tester".to_string(), 2)]
+    );
+}
@@ -97,3 +97,152 @@ fn detail_tabs_labels_present() {
     assert!(line.contains("Snippets"));
     assert!(line.contains("Raw"));
 }
+
+#[test]
+fn workspace_picker_fuzzy_filters_and_ranks() {
+    use coding_agent_search::ui::components::workspace_picker::{
+        WorkspacePickerState, fuzzy_score,
+    };
+
+    assert!(fuzzy_score("/home/me/projects/cass", "pcass").is_some());
+    assert!(fuzzy_score("/home/me/projects/cass", "zz").is_none());
+
+    let mut picker = WorkspacePickerState::new(vec![
+        ("/srv/backend".into(), 40),
+        ("/home/me/cass".into(), 12),
+        ("/home/me/cache-service".into(), 7),
+    ]);
+    assert_eq!(picker.selected_path(), Some("/srv/backend"));
+
+    picker.refilter("cas");
+    assert_eq!(picker.filtered.len(), 2);
+    // The contiguous segment match outranks the scattered one.
+    assert_eq!(picker.selected_path(), Some("/home/me/cass"));
+
+    picker.move_selection(1);
+    assert_eq!(picker.selected_path(), Some("/home/me/cache-service"));
+
+    picker.refilter("nothing-like-this");
+    assert_eq!(picker.selected_path(), None);
+}
+
+#[test]
+fn agent_picker_multi_selects_indexed_agents() {
+    use coding_agent_search::ui::components::agent_picker::AgentPickerState;
+
+    let mut picker = AgentPickerState::new(
+        vec![
+            ("codex".into(), 120),
+            ("claude_code".into(), 80),
+            ("cline".into(), 5),
+        ],
+        vec!["cline".to_string()],
+    );
+    picker.toggle_selected();
+    let chosen: Vec<String> = picker.chosen("").into_iter().collect();
+    assert_eq!(chosen, vec!["cline".to_string(), "codex".to_string()]);
+
+    // Ticked agents survive narrowing and win over the highlighted one.
+    picker.refilter("claude");
+    assert_eq!(picker.selected_slug(), Some("claude_code"));
+    assert_eq!(picker.chosen("claude").len(), 2);
+
+    // With nothing ticked, a typed query applies the highlighted agent, and a typo none.
+    picker.checked.clear();
+    assert_eq!(
+        picker.chosen("claude").into_iter().collect::<Vec<_>>(),
+        vec!["claude_code".to_string()]
+    );
+    picker.refilter("xyz");
+    assert!(picker.chosen("xyz").is_empty());
+}
+
+#[test]
+fn date_picker_walks_a_monday_first_month_grid() {
+    use chrono::NaiveDate;
+    use coding_agent_search::ui::components::date_picker::{
+        DatePickerState, day_end_ms, day_start_ms,
+    };
+
+    let today = NaiveDate::from_ymd_opt(2024, 2, 14).unwrap();
+    let mut picker = DatePickerState::new(today, None);
+    assert_eq!(picker.cursor, today);
+
+    // February 2024 starts on a Thursday and has 29 days.
+    let grid = picker.month_grid();
+    assert_eq!(grid.len(), 5);
+    assert_eq!(grid[0][3], NaiveDate::from_ymd_opt(2024, 2, 1));
+    assert_eq!(grid[0][2], None);
+    assert_eq!(grid[4][3], NaiveDate::from_ymd_opt(2024, 2, 29));
+
+    picker.move_days(7);
+    assert_eq!(picker.cursor, NaiveDate::from_ymd_opt(2024, 2, 21).unwrap());
+
+    let mut end_of_month = DatePickerState::new(today, NaiveDate::from_ymd_opt(2024, 1, 31));
+    end_of_month.move_months(1);
+    assert_eq!(
+        end_of_month.cursor,
+        NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+    );
+
+    let start = day_start_ms(today).unwrap();
+    let end = day_end_ms(today).unwrap();
+    assert!(end > start);
+    assert_eq!(day_start_ms(today.succ_opt().unwrap()).unwrap() - 1, end);
+}
+
+#[test]
+fn accessible_palette_draws_ascii_and_spells_out_states() {
+    use coding_agent_search::ui::components::pills::{Pill, draw_pills};
+    use coding_agent_search::ui::components::toast::{Toast, ToastManager, render_toasts};
+    use ratatui::{Terminal, backend::TestBackend};
+
+    let palette = ThemePalette::accessible();
+    let mut toasts = ToastManager::new();
+    toasts.push(Toast::error("index missing"));
+    let pills = [
+        Pill {
+            label: "agent".into(),
+            value: "codex".into(),
+            active: true,
+            editable: true,
+        },
+        Pill {
+            label: "ws".into(),
+            value: "/ws".into(),
+            active: false,
+            editable: false,
+        },
+    ];
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+    terminal
+        .draw(|f| {
+            let bar = search_bar(
+                "auth",
+                palette,
+                InputMode::Agent,
+                "standard",
+                vec![Span::raw("[agent:codex] ")],
+            );
+            f.render_widget(bar, Rect::new(0, 0, 80, 4));
+            draw_pills(f, Rect::new(0, 4, 80, 3), &pills, palette);
+            render_toasts(f, &toasts, &palette);
+        })
+        .unwrap();
+
+    let buf = terminal.backend().buffer();
+    let text: String = (0..buf.area.height)
+        .map(|y| {
+            (0..buf.area.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(text.is_ascii(), "non-ASCII glyph drawn:\n{text}");
+    assert!(text.contains("+-"), "{text}");
+    assert!(text.contains("> auth|"), "{text}");
+    assert!(text.contains("ws: /ws (off)"), "{text}");
+    assert!(text.contains("[error] index missing"), "{text}");
+}